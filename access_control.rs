@@ -0,0 +1,788 @@
+//! # Access Control
+//!
+//! Authorization rules of the messages of the contract.
+//!
+//! Every message that changes the state is listed in [`Message`] together with the [`Rule`]
+//! the caller must satisfy. The contract collects the facts about the caller in a [`Context`]
+//! and checks them against the rule of the message, so the same rule always gives the same
+//! result whatever message uses it.
+//!
+//! Each role carries a bitmask of [`Capabilities`]. The rules that depend on what an account
+//! is allowed to do, rather than on its relation with the asset, check the capabilities with
+//! [`Context::has`], so a permission can be granted to a new role without editing the messages.
+//!
+//! The owner can grant write access on the [`FieldGroups`] of a single asset to any account.
+//! The grants are checked first by [`Message::check`]: an account granted the field group of
+//! the message is allowed whatever its relation with the asset or its role.
+//!
+
+use crate::asset_erc721::Error;
+
+/// Role of an account
+pub type Role = u32;
+/// Bitmask of the capabilities of a role
+pub type Capabilities = u32;
+
+/// Create new assets
+pub const CAN_MINT: Capabilities = 1 << 0;
+/// Transfer the owned assets
+pub const CAN_TRANSFER: Capabilities = 1 << 1;
+/// Validate the assets
+pub const CAN_VALIDATE: Capabilities = 1 << 2;
+/// Update the location of the assets with no need to own them
+pub const CAN_UPDATE_LOCATION: Capabilities = 1 << 3;
+/// Assign and remove the roles of the accounts
+pub const CAN_MANAGE_ROLES: Capabilities = 1 << 4;
+/// Capabilities of the accounts with no role
+pub const DEFAULT_CAPABILITIES: Capabilities = CAN_MINT | CAN_TRANSFER;
+
+/// Bitmask of the groups of fields of an asset
+pub type FieldGroups = u32;
+
+/// Descriptions of the asset, in every locale
+pub const FIELD_DESCRIPTION: FieldGroups = 1 << 0;
+/// Photo of the asset
+pub const FIELD_PHOTO: FieldGroups = 1 << 1;
+/// Category of the asset
+pub const FIELD_CATEGORY: FieldGroups = 1 << 2;
+/// Location of the asset
+pub const FIELD_LOCATION: FieldGroups = 1 << 3;
+/// Metadata of the asset
+pub const FIELD_METADATA: FieldGroups = 1 << 4;
+/// Weight, dimensions and packaging of the asset
+pub const FIELD_PHYSICAL: FieldGroups = 1 << 5;
+
+/// Producer role
+pub const PRODUCER: Role = 0;
+/// Wholesaler role
+pub const WHOLESALER: Role = 1;
+/// Retailer role
+pub const RETAILER: Role = 2;
+/// Final Buyer role
+pub const FINAL_BUYER: Role = 3;
+/// Shipper role
+pub const SHIPPER: Role = 4;
+/// Administrator role
+pub const ADMINISTRATOR: Role = 5;
+/// Laboratory role
+pub const LABORATORY: Role = 6;
+/// Inspector role
+pub const INSPECTOR: Role = 7;
+/// Recycler role
+pub const RECYCLER: Role = 8;
+/// Auditor role, reading the privileged exports with no write capability
+pub const AUDITOR: Role = 9;
+/// Capabilities of the built-in roles, indexed by role
+pub const BUILTIN_CAPABILITIES: [Capabilities; 10] = [
+    DEFAULT_CAPABILITIES,
+    DEFAULT_CAPABILITIES,
+    DEFAULT_CAPABILITIES,
+    DEFAULT_CAPABILITIES,
+    DEFAULT_CAPABILITIES | CAN_UPDATE_LOCATION,
+    DEFAULT_CAPABILITIES | CAN_VALIDATE | CAN_UPDATE_LOCATION | CAN_MANAGE_ROLES,
+    DEFAULT_CAPABILITIES,
+    DEFAULT_CAPABILITIES | CAN_VALIDATE,
+    DEFAULT_CAPABILITIES,
+    0,
+];
+/// Names of the built-in roles, indexed by role
+pub const BUILTIN_ROLES: [&[u8]; 10] = [
+    b"Producer",
+    b"Wholesaler",
+    b"Retailer",
+    b"Final Buyer",
+    b"Shipper",
+    b"Administrator",
+    b"Laboratory",
+    b"Inspector",
+    b"Recycler",
+    b"Auditor",
+];
+/// Roles of the supply chain in the order the assets flow through them
+pub const ROLE_FLOW: [Role; 4] = [PRODUCER, WHOLESALER, RETAILER, FINAL_BUYER];
+
+/// Requirement the caller of a message must satisfy
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Rule {
+    /// Any account
+    Anyone,
+    /// Administrators only
+    Administrator,
+    /// Owner of the asset or administrators
+    Owner,
+    /// Owner of the asset, accounts delegated for all the assets of the owner or administrators
+    OwnerOrOperator,
+    /// Owner of the asset, accounts delegated for the asset or for all the assets of the owner, or administrators
+    OwnerOrDelegate,
+    /// Owner of the asset, the carrier of the asset or administrators. When the asset has no
+    /// carrier any account with [`CAN_UPDATE_LOCATION`]
+    OwnerOrShipper,
+    /// Actual carrier of the asset or administrators
+    Carrier,
+    /// Participants in the supply chain of the asset: the owner, the shippers allowed by
+    /// [`Rule::OwnerOrShipper`], the accounts with [`CAN_VALIDATE`] or administrators
+    Participant,
+    /// Any account, producers and administrators only when minting is restricted
+    Minter,
+    /// Producers or administrators
+    Producer,
+    /// Laboratories or administrators
+    Laboratory,
+    /// Recyclers or administrators
+    Recycler,
+    /// Auditors or administrators
+    Auditor,
+    /// Accounts with the capabilities or administrators
+    Capable(Capabilities),
+}
+
+/// Messages of the contract subject to access control
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Message {
+    AssetNew,
+    AssetNewBranded,
+    AssetDelete,
+    AssetDeleteForce,
+    AssetTransfer,
+    AssetDescriptionNew,
+    AssetDescriptionDelete,
+    AssetDescriptionSetLocale,
+    AssetDescriptionDeleteLocale,
+    AssetPhotoNew,
+    AssetPhotoDelete,
+    AssetCategoryNew,
+    AssetCategoryDelete,
+    AssetLocationNew,
+    AssetLocationAppend,
+    AssetLocationDelete,
+    AssetMetadataNew,
+    AssetMetadataDelete,
+    AssetValidationNew,
+    AssetValidationDelete,
+    CategoryDescriptionNew,
+    CategoryDescriptionDelete,
+    CategoryProducerSet,
+    CategoryRestrictedSet,
+    AccountRoleNew,
+    AccountRoleDelete,
+    AccountRoleUpdate,
+    AccountDelegateForAllAsset,
+    AccountDelegateSingleAsset,
+    AccountCustodialSet,
+    CustodialDelegateForAllAsset,
+    CustodialDelegateSingleAsset,
+    CustodialUndelegateSingleAsset,
+    ConfigMintRateLimitSet,
+    ConfigUpdateCooldownSet,
+    ConfigMintProducersOnlySet,
+    ConfigCategoryRegistrySet,
+    CategoryRefresh,
+    BrandRegister,
+    AssetAuthenticityCommit,
+    AssetAuthenticityReveal,
+    AssetTagBind,
+    AssetTagRebind,
+    AssetDuplicateClear,
+    AssetQuantitySet,
+    AssetQuantitySplitTransfer,
+    AssetPhysicalSet,
+    AssetPhysicalDelete,
+    AssetDepreciationSet,
+    AssetDepreciationDelete,
+    AssetServiceProviderAuthorize,
+    AssetServiceAdd,
+    AssetLienNew,
+    AssetLienConsent,
+    DepositTopUp,
+    AssetHoldPlace,
+    AssetHoldLift,
+    ConfigErasureRetentionSet,
+    AccountErasureCommit,
+    AccountErasureExecute,
+    ConfigUpdateLogSizeSet,
+    AssetImport,
+    AssetImportClose,
+    ConfigMintDepositSet,
+    CollectionMetadataSet,
+    ConfigTransferFeeSet,
+    TreasuryWithdrawPropose,
+    TreasuryWithdrawApprove,
+    TreasuryWithdrawExecute,
+    ConfigTreasuryThresholdSet,
+    InspectorAccredit,
+    InspectorRevoke,
+    AssetValidationRevoke,
+    CertificateRevoke,
+    CategoryRegulatedSet,
+    CategoryValidationRequiredSet,
+    AssetValidationRequest,
+    LicenseGrant,
+    LicenseRevoke,
+    AssetUnlock,
+    AssetNoteAdd,
+    ThreadPost,
+    CategorySubscribe,
+    CategoryUnsubscribe,
+    PriceListSet,
+    AssetMsrpSet,
+    TaxRateSet,
+    AccountJurisdictionSet,
+    JurisdictionSet,
+    CategoryComplianceSet,
+    AssetReturnReceive,
+    AssetReturnInspect,
+    AssetReturnRestock,
+    AssetReturnToDisposal,
+    AssetDispose,
+    AssetRecycle,
+    AdminAdd,
+    AdminRemove,
+    AdminTransfer,
+    AuditAdminLog,
+    AuditPrivateGrants,
+    AuditTransfers,
+    AssetRemanufacture,
+    ProductNew,
+    ProductVariantNew,
+    AssetVariantSet,
+    SkuRegister,
+    SkuAttributesSet,
+    SkuRemove,
+    ShipmentManifestCreate,
+    AssetCarrierAssign,
+    AssetCarrierSubcontract,
+    AssetCarrierRelease,
+    ShipmentCertificateIssue,
+    AssetLaboratoryAuthorize,
+    AssetAclSet,
+    AssetPrivateSet,
+    AssetPrivateDelete,
+    AssetPrivateGrant,
+    AssetPrivateRevoke,
+    AssetViewerGrant,
+    AssetViewerRevoke,
+    AssetClaimVerify,
+    ProofVerifierSet,
+    AssetCommit,
+    AssetReveal,
+    AssetBridgeLock,
+    AssetBridgeUnlock,
+    BridgeRelayerSet,
+    AssetListingNew,
+    AssetReserve,
+    AssetReservationRelease,
+    AssetTransferCancel,
+    AssetTransferFinalize,
+    AccountGracePeriodSet,
+    AssetListingCancel,
+    AssetDiscountNew,
+    AssetListingCharitySet,
+    AssetDiscountCancel,
+    AssetPurchase,
+    PriceOracleSet,
+    ConfigReferralShareSet,
+    ConfigSubscriptionSet,
+    SubscriptionRenew,
+    SubscriptionFeesWithdraw,
+    ConfigValidatorStakeSet,
+    StakeBond,
+    StakeUnbond,
+    StakeWithdraw,
+    ValidationDisputeOpen,
+    CertificateDisputeOpen,
+    ShipmentDisputeOpen,
+    ConfigShipperPenaltiesSet,
+    ConfigFraudPenaltySet,
+    RewardTokenSet,
+    RewardRateSet,
+    DisputeResolve,
+    AssetLabResultAdd,
+    CategoryLabRequirementSet,
+    RoleRegister,
+    RoleEnabledSet,
+    RoleCapabilitiesSet,
+    BrandVerify,
+    BrandUnverify,
+}
+
+impl Message {
+    /// Returns the rule the caller of the message must satisfy
+    pub fn rule(self) -> Rule {
+        match self {
+            Message::AssetNew | Message::AssetNewBranded => Rule::Minter,
+            Message::BrandRegister
+            | Message::SubscriptionRenew
+            | Message::PriceListSet
+            | Message::ProductNew
+            | Message::ProductVariantNew
+            | Message::SkuRegister
+            | Message::SkuAttributesSet
+            | Message::SkuRemove => Rule::Producer,
+            Message::AssetDelete => Rule::Owner,
+            Message::AssetTransfer => Rule::OwnerOrDelegate,
+            Message::AssetDescriptionNew
+            | Message::AssetDescriptionDelete
+            | Message::AssetDescriptionSetLocale
+            | Message::AssetDescriptionDeleteLocale
+            | Message::AssetPhotoNew
+            | Message::AssetPhotoDelete
+            | Message::AssetCategoryNew
+            | Message::AssetCategoryDelete
+            | Message::AssetMetadataNew
+            | Message::AssetMetadataDelete
+            | Message::AssetAuthenticityCommit
+            | Message::AssetTagBind
+            | Message::AssetQuantitySet
+            | Message::AssetQuantitySplitTransfer
+            | Message::AssetPhysicalSet
+            | Message::AssetPhysicalDelete
+            | Message::AssetCarrierAssign
+            | Message::AssetCarrierRelease
+            | Message::AssetLaboratoryAuthorize
+            | Message::AssetAclSet
+            | Message::AssetPrivateSet
+            | Message::AssetPrivateDelete
+            | Message::AssetPrivateGrant
+            | Message::AssetPrivateRevoke
+            | Message::AssetViewerGrant
+            | Message::AssetViewerRevoke
+            | Message::AssetCommit
+            | Message::AssetBridgeLock
+            | Message::AssetListingNew
+            | Message::AssetListingCancel
+            | Message::AssetDepreciationSet
+            | Message::AssetDepreciationDelete
+            | Message::AssetServiceProviderAuthorize
+            | Message::AssetLienConsent
+            | Message::AssetDiscountNew
+            | Message::AssetListingCharitySet
+            | Message::AssetDiscountCancel
+            | Message::AssetMsrpSet
+            | Message::AssetReturnReceive
+            | Message::AssetReturnRestock
+            | Message::AssetReturnToDisposal
+            | Message::AssetDispose
+            | Message::AssetRemanufacture
+            | Message::AssetValidationRequest
+            | Message::AssetVariantSet
+            | Message::AssetReserve
+            | Message::AssetTransferCancel
+            | Message::ValidationDisputeOpen => Rule::Owner,
+            Message::AssetLabResultAdd => Rule::Laboratory,
+            Message::AssetRecycle => Rule::Recycler,
+            Message::AuditAdminLog | Message::AuditPrivateGrants | Message::AuditTransfers => Rule::Auditor,
+            Message::AssetCarrierSubcontract => Rule::Carrier,
+            Message::AssetNoteAdd => Rule::Participant,
+            Message::AssetLocationNew | Message::AssetLocationAppend | Message::AssetLocationDelete => {
+                Rule::OwnerOrShipper
+            }
+            Message::AccountDelegateSingleAsset
+            | Message::ShipmentManifestCreate
+            | Message::AssetClaimVerify => Rule::OwnerOrOperator,
+            Message::AccountDelegateForAllAsset
+            | Message::AccountCustodialSet
+            | Message::AssetAuthenticityReveal
+            | Message::AssetReveal
+            | Message::AssetBridgeUnlock
+            | Message::AssetPurchase
+            | Message::AssetServiceAdd
+            | Message::AssetLienNew
+            | Message::DepositTopUp
+            | Message::StakeBond
+            | Message::StakeUnbond
+            | Message::StakeWithdraw
+            | Message::CertificateDisputeOpen
+            | Message::ShipmentDisputeOpen
+            | Message::ThreadPost
+            | Message::CategorySubscribe
+            | Message::AssetReservationRelease
+            | Message::AssetTransferFinalize
+            | Message::AccountGracePeriodSet
+            | Message::CategoryUnsubscribe => Rule::Anyone,
+            Message::AssetValidationNew | Message::AssetValidationDelete | Message::AssetReturnInspect => {
+                Rule::Capable(CAN_VALIDATE)
+            }
+            Message::AccountRoleNew | Message::AccountRoleDelete | Message::AccountRoleUpdate => {
+                Rule::Capable(CAN_MANAGE_ROLES)
+            }
+            Message::CategoryDescriptionNew
+            | Message::CategoryDescriptionDelete
+            | Message::CategoryProducerSet
+            | Message::CategoryRestrictedSet
+            | Message::CustodialDelegateForAllAsset
+            | Message::CustodialDelegateSingleAsset
+            | Message::CustodialUndelegateSingleAsset
+            | Message::ConfigMintRateLimitSet
+            | Message::ConfigUpdateCooldownSet
+            | Message::ConfigMintProducersOnlySet
+            | Message::ConfigCategoryRegistrySet
+            | Message::CategoryRefresh
+            | Message::BrandVerify
+            | Message::BrandUnverify
+            | Message::AssetTagRebind
+            | Message::AssetDuplicateClear
+            | Message::ShipmentCertificateIssue
+            | Message::CategoryLabRequirementSet
+            | Message::RoleRegister
+            | Message::RoleEnabledSet
+            | Message::RoleCapabilitiesSet
+            | Message::ProofVerifierSet
+            | Message::BridgeRelayerSet
+            | Message::PriceOracleSet
+            | Message::ConfigReferralShareSet
+            | Message::ConfigSubscriptionSet
+            | Message::SubscriptionFeesWithdraw
+            | Message::ConfigValidatorStakeSet
+            | Message::DisputeResolve
+            | Message::ConfigShipperPenaltiesSet
+            | Message::ConfigFraudPenaltySet
+            | Message::RewardTokenSet
+            | Message::RewardRateSet
+            | Message::AssetHoldPlace
+            | Message::AssetHoldLift
+            | Message::ConfigErasureRetentionSet
+            | Message::AccountErasureCommit
+            | Message::AccountErasureExecute
+            | Message::ConfigUpdateLogSizeSet
+            | Message::AssetImport
+            | Message::AssetImportClose
+            | Message::ConfigMintDepositSet
+            | Message::CollectionMetadataSet
+            | Message::ConfigTransferFeeSet
+            | Message::TreasuryWithdrawPropose
+            | Message::TreasuryWithdrawApprove
+            | Message::TreasuryWithdrawExecute
+            | Message::ConfigTreasuryThresholdSet
+            | Message::InspectorAccredit
+            | Message::InspectorRevoke
+            | Message::AssetValidationRevoke
+            | Message::CertificateRevoke
+            | Message::CategoryRegulatedSet
+            | Message::CategoryValidationRequiredSet
+            | Message::LicenseGrant
+            | Message::LicenseRevoke
+            | Message::AssetUnlock
+            | Message::AdminAdd
+            | Message::AdminRemove
+            | Message::AdminTransfer
+            | Message::AssetDeleteForce
+            | Message::TaxRateSet
+            | Message::AccountJurisdictionSet
+            | Message::JurisdictionSet
+            | Message::CategoryComplianceSet => Rule::Administrator,
+        }
+    }
+
+    /// Returns the group of fields of the asset written from the message
+    pub fn field_group(self) -> Option<FieldGroups> {
+        match self {
+            Message::AssetDescriptionNew
+            | Message::AssetDescriptionDelete
+            | Message::AssetDescriptionSetLocale
+            | Message::AssetDescriptionDeleteLocale => Some(FIELD_DESCRIPTION),
+            Message::AssetPhotoNew | Message::AssetPhotoDelete => Some(FIELD_PHOTO),
+            Message::AssetCategoryNew | Message::AssetCategoryDelete => Some(FIELD_CATEGORY),
+            Message::AssetLocationNew | Message::AssetLocationAppend | Message::AssetLocationDelete => {
+                Some(FIELD_LOCATION)
+            }
+            Message::AssetMetadataNew | Message::AssetMetadataDelete => Some(FIELD_METADATA),
+            Message::AssetPhysicalSet | Message::AssetPhysicalDelete => Some(FIELD_PHYSICAL),
+            _ => None,
+        }
+    }
+
+    /// Checks the caller described by `context` can call the message: the grants on the field group
+    /// of the message first, then the rule and the capabilities of the message
+    pub fn check(self, context: &Context) -> Result<(), Error> {
+        if self.field_group().is_some_and(|group| context.granted & group == group) {
+            return Ok(())
+        }
+        self.rule().check(context)?;
+        match self.capabilities() {
+            Some(capabilities) if !context.has(capabilities) => Err(Error::MissingCapability),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the facts about the caller read to check the message. The administrators satisfy every message, so
+    /// their flag is read only when the other facts are not enough.
+    pub fn needs(self) -> Needs {
+        let rule = self.rule();
+        Needs {
+            role: matches!(rule, Rule::Minter | Rule::Producer | Rule::Laboratory | Rule::Recycler | Rule::Auditor),
+            capabilities: matches!(rule, Rule::OwnerOrShipper | Rule::Participant | Rule::Capable(_))
+                || self.capabilities().is_some(),
+            delegation: matches!(rule, Rule::OwnerOrOperator | Rule::OwnerOrDelegate),
+            carrier: matches!(rule, Rule::OwnerOrShipper | Rule::Participant | Rule::Carrier),
+            grants: self.field_group().is_some(),
+        }
+    }
+
+    /// Returns the capabilities the caller must have, in addition to the rule of the message
+    pub fn capabilities(self) -> Option<Capabilities> {
+        match self {
+            Message::AssetNew | Message::AssetNewBranded => Some(CAN_MINT),
+            Message::AssetTransfer
+            | Message::AssetQuantitySplitTransfer
+            | Message::AssetListingNew
+            | Message::AssetReserve => Some(CAN_TRANSFER),
+            _ => None,
+        }
+    }
+}
+
+/// Facts about the caller read to check a message, the others are left to their default in the [`Context`]
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub struct Needs {
+    /// The role of the caller
+    pub role: bool,
+    /// The capabilities of the role of the caller
+    pub capabilities: bool,
+    /// The proxies of the asset and of its owner, when the caller is not the owner
+    pub delegation: bool,
+    /// The carrier of the asset, when the caller is not the owner
+    pub carrier: bool,
+    /// The grants on the field group of the message, when the caller is not the owner
+    pub grants: bool,
+}
+
+/// Facts about the caller of a message
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub struct Context {
+    /// The caller is an administrator
+    pub administrator: bool,
+    /// Role of the caller
+    pub role: Option<Role>,
+    /// The caller owns the asset
+    pub owner: bool,
+    /// The caller is delegated for all the assets of the owner
+    pub operator: bool,
+    /// The caller is delegated for the asset
+    pub delegate: bool,
+    /// Minting is restricted to producers
+    pub mint_restricted: bool,
+    /// The asset has a carrier
+    pub carrier_assigned: bool,
+    /// The caller is the actual carrier of the asset
+    pub carrier: bool,
+    /// Capabilities of the role of the caller
+    pub capabilities: Capabilities,
+    /// Field groups of the asset granted to the caller
+    pub granted: FieldGroups,
+}
+
+impl Context {
+    /// Returns true if the caller has all the `capabilities`, administrators have all of them
+    pub fn has(&self, capabilities: Capabilities) -> bool {
+        self.administrator || self.capabilities & capabilities == capabilities
+    }
+}
+
+impl Rule {
+    /// Checks the caller described by `context` satisfies the rule
+    pub fn check(self, context: &Context) -> Result<(), Error> {
+        if context.administrator {
+            return Ok(())
+        }
+        let allowed = match self {
+            Rule::Anyone => true,
+            Rule::Administrator => false,
+            Rule::Owner => context.owner,
+            Rule::OwnerOrOperator => context.owner || context.operator,
+            Rule::OwnerOrDelegate => context.owner || context.operator || context.delegate,
+            Rule::OwnerOrShipper => {
+                context.owner
+                    || context.carrier
+                    || (!context.carrier_assigned && context.has(CAN_UPDATE_LOCATION))
+            }
+            Rule::Carrier => context.carrier,
+            Rule::Participant => Rule::OwnerOrShipper.check(context).is_ok() || context.has(CAN_VALIDATE),
+            Rule::Minter => !context.mint_restricted || context.role == Some(PRODUCER),
+            Rule::Producer => context.role == Some(PRODUCER),
+            Rule::Laboratory => context.role == Some(LABORATORY),
+            Rule::Recycler => context.role == Some(RECYCLER),
+            Rule::Auditor => context.role == Some(AUDITOR),
+            Rule::Capable(capabilities) => context.has(capabilities),
+        };
+        if allowed {
+            return Ok(())
+        }
+        Err(match self {
+            Rule::Administrator => Error::NotAdministrator,
+            Rule::OwnerOrOperator | Rule::Carrier => Error::NotAllowed,
+            Rule::OwnerOrDelegate => Error::NotApproved,
+            Rule::Minter | Rule::Producer => Error::NotProducer,
+            Rule::Laboratory => Error::NotLaboratory,
+            Rule::Recycler => Error::NotRecycler,
+            Rule::Auditor => Error::NotAuditor,
+            Rule::Capable(_) => Error::MissingCapability,
+            _ => Error::NotOwner,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role(role: Role) -> Context {
+        Context {
+            role: Some(role),
+            capabilities: BUILTIN_CAPABILITIES[role as usize],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn needs_works() {
+        let transfer = Message::AssetTransfer.needs();
+        assert!(transfer.capabilities && transfer.delegation);
+        assert!(!transfer.role && !transfer.carrier && !transfer.grants);
+        let location = Message::AssetLocationNew.needs();
+        assert!(location.capabilities && location.carrier && location.grants);
+        assert_eq!(Message::AssetDelete.needs(), Needs::default());
+        assert!(Message::AssetNew.needs().role);
+    }
+
+    #[test]
+    fn administrator_satisfies_every_rule() {
+        let context = Context {
+            administrator: true,
+            mint_restricted: true,
+            ..Default::default()
+        };
+        for rule in [
+            Rule::Anyone,
+            Rule::Administrator,
+            Rule::Owner,
+            Rule::OwnerOrOperator,
+            Rule::OwnerOrDelegate,
+            Rule::OwnerOrShipper,
+            Rule::Carrier,
+            Rule::Participant,
+            Rule::Minter,
+            Rule::Producer,
+            Rule::Laboratory,
+            Rule::Capable(CAN_VALIDATE | CAN_MANAGE_ROLES),
+        ]
+        .iter()
+        {
+            assert_eq!(rule.check(&context), Ok(()));
+        }
+    }
+
+    #[test]
+    fn owner_rules_work() {
+        let owner = Context {
+            owner: true,
+            ..Default::default()
+        };
+        let delegate = Context {
+            delegate: true,
+            ..Default::default()
+        };
+        let operator = Context {
+            operator: true,
+            ..Default::default()
+        };
+        assert_eq!(Rule::Owner.check(&owner), Ok(()));
+        assert_eq!(Rule::Owner.check(&delegate), Err(Error::NotOwner));
+        assert_eq!(Rule::Owner.check(&role(SHIPPER)), Err(Error::NotOwner));
+        assert_eq!(Rule::OwnerOrOperator.check(&operator), Ok(()));
+        assert_eq!(Rule::OwnerOrOperator.check(&delegate), Err(Error::NotAllowed));
+        assert_eq!(Rule::OwnerOrDelegate.check(&delegate), Ok(()));
+        assert_eq!(Rule::OwnerOrDelegate.check(&operator), Ok(()));
+        assert_eq!(Rule::OwnerOrDelegate.check(&Context::default()), Err(Error::NotApproved));
+        assert_eq!(Rule::OwnerOrShipper.check(&role(SHIPPER)), Ok(()));
+        assert_eq!(Rule::OwnerOrShipper.check(&role(RETAILER)), Err(Error::NotOwner));
+    }
+
+    #[test]
+    fn carrier_rules_work() {
+        let carrier = Context {
+            role: Some(SHIPPER),
+            carrier_assigned: true,
+            carrier: true,
+            ..Default::default()
+        };
+        let other_shipper = Context {
+            role: Some(SHIPPER),
+            carrier_assigned: true,
+            ..Default::default()
+        };
+        // the location follows the actual carrier once the asset is assigned
+        assert_eq!(Rule::OwnerOrShipper.check(&carrier), Ok(()));
+        assert_eq!(Rule::OwnerOrShipper.check(&other_shipper), Err(Error::NotOwner));
+        assert_eq!(Rule::Carrier.check(&carrier), Ok(()));
+        assert_eq!(Rule::Carrier.check(&role(SHIPPER)), Err(Error::NotAllowed));
+    }
+
+    #[test]
+    fn capabilities_work() {
+        let validator = Context {
+            capabilities: CAN_VALIDATE,
+            ..Default::default()
+        };
+        assert!(role(ADMINISTRATOR).has(CAN_VALIDATE | CAN_MANAGE_ROLES));
+        assert!(role(SHIPPER).has(CAN_UPDATE_LOCATION));
+        assert!(!role(PRODUCER).has(CAN_UPDATE_LOCATION | CAN_MINT));
+        assert_eq!(Rule::Capable(CAN_VALIDATE).check(&validator), Ok(()));
+        assert_eq!(Rule::Capable(CAN_MANAGE_ROLES).check(&validator), Err(Error::MissingCapability));
+        assert_eq!(Rule::Capable(CAN_VALIDATE).check(&role(SHIPPER)), Err(Error::MissingCapability));
+        // the capability to update the locations does not depend on the role
+        assert_eq!(Rule::OwnerOrShipper.check(&Context { capabilities: CAN_UPDATE_LOCATION, ..Default::default() }), Ok(()));
+        // the participants are the owner, the shippers and the validators
+        assert_eq!(Rule::Participant.check(&validator), Ok(()));
+        assert_eq!(Rule::Participant.check(&role(INSPECTOR)), Ok(()));
+        assert_eq!(Rule::Participant.check(&role(SHIPPER)), Ok(()));
+        assert_eq!(Rule::Participant.check(&role(RETAILER)), Err(Error::NotOwner));
+    }
+
+    #[test]
+    fn grants_work() {
+        let co_packer = Context {
+            granted: FIELD_METADATA | FIELD_PHOTO,
+            ..Default::default()
+        };
+        assert_eq!(Message::AssetMetadataNew.check(&co_packer), Ok(()));
+        assert_eq!(Message::AssetPhotoDelete.check(&co_packer), Ok(()));
+        assert_eq!(Message::AssetDescriptionNew.check(&co_packer), Err(Error::NotOwner));
+        assert_eq!(Message::AssetDelete.check(&co_packer), Err(Error::NotOwner));
+        assert_eq!(Message::AssetMetadataNew.check(&Context::default()), Err(Error::NotOwner));
+        assert_eq!(Message::AssetNew.check(&Context::default()), Err(Error::MissingCapability));
+    }
+
+    #[test]
+    fn minter_rule_works() {
+        let restricted = |role: Option<Role>| {
+            Context {
+                role,
+                mint_restricted: true,
+                ..Default::default()
+            }
+        };
+        assert_eq!(Rule::Minter.check(&Context::default()), Ok(()));
+        assert_eq!(Rule::Minter.check(&restricted(Some(PRODUCER))), Ok(()));
+        assert_eq!(Rule::Minter.check(&restricted(Some(WHOLESALER))), Err(Error::NotProducer));
+        assert_eq!(Rule::Minter.check(&restricted(Some(FINAL_BUYER))), Err(Error::NotProducer));
+        assert_eq!(Rule::Minter.check(&restricted(None)), Err(Error::NotProducer));
+        assert_eq!(Rule::Producer.check(&role(PRODUCER)), Ok(()));
+        assert_eq!(Rule::Producer.check(&Context::default()), Err(Error::NotProducer));
+        assert_eq!(Rule::Laboratory.check(&role(LABORATORY)), Ok(()));
+        assert_eq!(Rule::Laboratory.check(&role(PRODUCER)), Err(Error::NotLaboratory));
+        assert_eq!(Rule::Recycler.check(&role(RECYCLER)), Ok(()));
+        assert_eq!(Rule::Recycler.check(&role(LABORATORY)), Err(Error::NotRecycler));
+        assert_eq!(Rule::Auditor.check(&role(AUDITOR)), Ok(()));
+        assert_eq!(Rule::Auditor.check(&role(INSPECTOR)), Err(Error::NotAuditor));
+    }
+
+    #[test]
+    fn messages_have_consistent_rules() {
+        assert_eq!(Message::AssetDelete.rule(), Message::AssetDescriptionDelete.rule());
+        assert_eq!(Message::AssetLocationNew.rule(), Message::AssetLocationDelete.rule());
+        assert_eq!(Message::AssetLocationNew.rule(), Message::AssetLocationAppend.rule());
+        assert_eq!(Message::AccountRoleNew.rule(), Message::AccountRoleDelete.rule());
+        assert_eq!(Message::AssetValidationNew.rule(), Rule::Capable(CAN_VALIDATE));
+        assert_eq!(Message::AssetTransfer.capabilities(), Message::AssetQuantitySplitTransfer.capabilities());
+    }
+}
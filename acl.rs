@@ -0,0 +1,91 @@
+//! # Access-Control Lists
+//!
+//! Write access on the groups of fields of a single asset, granted from its owner to specific
+//! accounts, e.g. the metadata of one asset to a co-packer.
+//!
+//! The grants belong to the owner that made them: they are dropped when the asset changes
+//! hands or is deleted.
+//!
+
+use crate::{
+    access_control::FieldGroups,
+    asset_erc721::AssetId,
+};
+use ink_env::AccountId;
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::SpreadLayout,
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+
+/// Storage of the access-control lists of the assets
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Acl {
+    /// Accounts with grants on the asset and the field groups granted to each one
+    asset_entries: StorageHashMap<AssetId, Vec<(AccountId, FieldGroups)>>,
+}
+
+impl Acl {
+    /// Returns the field groups of asset `id` granted to `account`
+    pub fn groups_of(&self, id: AssetId, account: &AccountId) -> FieldGroups {
+        self.asset_entries
+            .get(&id)
+            .and_then(|entries| entries.iter().find(|(other, _)| other == account))
+            .map_or(0, |(_, groups)| *groups)
+    }
+
+    /// Returns the accounts with grants on asset `id` and their field groups
+    pub fn entries_of(&self, id: AssetId) -> Vec<(AccountId, FieldGroups)> {
+        self.asset_entries.get(&id).cloned().unwrap_or_default()
+    }
+
+    /// Grants `groups` of asset `id` to `account`, replacing its previous grant. No groups remove the entry.
+    pub fn set(&mut self, id: AssetId, account: AccountId, groups: FieldGroups) {
+        let mut entries = self.entries_of(id);
+        entries.retain(|(other, _)| *other != account);
+        if groups != 0 {
+            entries.push((account, groups));
+        }
+        if entries.is_empty() {
+            self.asset_entries.take(&id);
+        } else {
+            self.asset_entries.insert(id, entries);
+        }
+    }
+
+    /// Removes every grant on asset `id`
+    pub fn remove(&mut self, id: AssetId) {
+        self.asset_entries.take(&id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::access_control::{
+        FIELD_LOCATION,
+        FIELD_METADATA,
+    };
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn grants_work() {
+        let bob = AccountId::from([0x2; 32]);
+        let eve = AccountId::from([0x3; 32]);
+        let mut acl = Acl::default();
+        assert_eq!(acl.groups_of(1, &bob), 0);
+        acl.set(1, bob, FIELD_METADATA);
+        acl.set(1, eve, FIELD_LOCATION);
+        acl.set(1, bob, FIELD_METADATA | FIELD_LOCATION);
+        assert_eq!(acl.groups_of(1, &bob), FIELD_METADATA | FIELD_LOCATION);
+        assert_eq!(acl.groups_of(2, &bob), 0);
+        assert_eq!(acl.entries_of(1), [(eve, FIELD_LOCATION), (bob, FIELD_METADATA | FIELD_LOCATION)]);
+        acl.set(1, eve, 0);
+        assert_eq!(acl.entries_of(1), [(bob, FIELD_METADATA | FIELD_LOCATION)]);
+        acl.remove(1);
+        assert_eq!(acl.groups_of(1, &bob), 0);
+    }
+}
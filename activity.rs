@@ -0,0 +1,164 @@
+//! # Activity
+//!
+//! Feed of the latest activities of each account on the assets.
+//!
+//! The feed of an account keeps only the last [`CAPACITY`] activities: the new activities
+//! overwrite the oldest ones, so the storage used by an account is bounded.
+//!
+
+use crate::{
+    asset_erc721::AssetId,
+    disputes::DisputeId,
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Maximum number of activities kept for each account
+pub const CAPACITY: u32 = 50;
+
+/// Kind of activity done on an asset
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub enum ActivityKind {
+    /// The account created the asset
+    Minted,
+    /// The account deleted the asset
+    Burned,
+    /// The account sent the asset to the counterpart
+    Sent,
+    /// The account received the asset from the counterpart
+    Received,
+    /// The account validated the asset
+    Validated,
+    /// The dispute on the asset has been opened between the account and the counterpart
+    DisputeOpened(DisputeId),
+    /// The dispute on the asset between the account and the counterpart has been resolved
+    DisputeResolved(DisputeId),
+}
+
+/// Activity of an account on an asset
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct ActivityEntry {
+    pub kind: ActivityKind,
+    pub id: AssetId,
+    /// Other account involved in the activity, if any
+    pub counterpart: Option<AccountId>,
+    pub block: BlockNumber,
+}
+
+/// Storage of the activity feeds of the accounts
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Activity {
+    /// Activities of the accounts, stored at the position (activities counter % CAPACITY)
+    account_activity: StorageHashMap<(AccountId, u32), ActivityEntry>,
+    /// Counter of all the activities done from the accounts
+    account_activities: StorageHashMap<AccountId, u32>,
+}
+
+impl Activity {
+    /// Appends an activity to the feed of `account`, replacing its oldest activity when the feed is full
+    pub fn record(&mut self, account: AccountId, entry: ActivityEntry) {
+        let count = *self.account_activities.get(&account).unwrap_or(&0);
+        self.account_activity.insert((account, count % CAPACITY), entry);
+        self.account_activities.insert(account, count + 1);
+    }
+
+    /// Returns the number of activities kept for `account`
+    pub fn len(&self, account: &AccountId) -> u32 {
+        core::cmp::min(*self.account_activities.get(account).unwrap_or(&0), CAPACITY)
+    }
+
+    /// Returns at most `limit` activities of `account` from the newest, skipping the newest `offset`
+    pub fn latest(&self, account: &AccountId, offset: u32, limit: u32) -> Vec<ActivityEntry> {
+        let count = *self.account_activities.get(account).unwrap_or(&0);
+        (offset..self.len(account))
+            .take(limit as usize)
+            .filter_map(|position| {
+                let index = (count - position - 1) % CAPACITY;
+                self.account_activity.get(&(*account, index)).cloned()
+            })
+            .collect()
+    }
+
+    /// Replaces the counterpart `account` with `pseudonym` in the feed of `owner`
+    pub fn pseudonymize(&mut self, owner: &AccountId, account: &AccountId, pseudonym: AccountId) {
+        for index in 0..self.len(owner) {
+            if let Some(entry) = self.account_activity.get_mut(&(*owner, index)) {
+                if entry.counterpart == Some(*account) {
+                    entry.counterpart = Some(pseudonym);
+                }
+            }
+        }
+    }
+
+    /// Removes the feed of `account`
+    pub fn clear(&mut self, account: &AccountId) {
+        for index in 0..self.len(account) {
+            self.account_activity.take(&(*account, index));
+        }
+        self.account_activities.take(account);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    fn minted(id: AssetId) -> ActivityEntry {
+        ActivityEntry {
+            kind: ActivityKind::Minted,
+            id,
+            counterpart: None,
+            block: 0,
+        }
+    }
+
+    #[ink::test]
+    fn latest_works() {
+        let alice = AccountId::from([0x1; 32]);
+        let mut activity = Activity::default();
+        assert_eq!(activity.latest(&alice, 0, 10), []);
+        for id in 0..3 {
+            activity.record(alice, minted(id));
+        }
+        assert_eq!(activity.len(&alice), 3);
+        assert_eq!(activity.latest(&alice, 0, 10), [minted(2), minted(1), minted(0)]);
+        assert_eq!(activity.latest(&alice, 1, 1), [minted(1)]);
+        assert_eq!(activity.len(&AccountId::from([0x2; 32])), 0);
+    }
+
+    #[ink::test]
+    fn feed_is_bounded() {
+        let alice = AccountId::from([0x1; 32]);
+        let mut activity = Activity::default();
+        for id in 0..CAPACITY + 2 {
+            activity.record(alice, minted(id));
+        }
+        // the oldest activities are replaced from the newest ones
+        assert_eq!(activity.len(&alice), CAPACITY);
+        assert_eq!(activity.latest(&alice, 0, 2), [minted(CAPACITY + 1), minted(CAPACITY)]);
+        assert_eq!(activity.latest(&alice, CAPACITY - 1, 10), [minted(2)]);
+    }
+}
@@ -0,0 +1,196 @@
+//! # Alerts
+//!
+//! Subscriptions of the accounts to the alerts of the categories.
+//!
+//! An account subscribes to the categories of the products it handles, as a retailer does for
+//! the product lines it stocks. The validations and the compliance certificates of the assets
+//! of a category with subscribers, and their revocations, emit an alert with the category and
+//! the digest of its subscribers as topics: a notifier filters the alerts of its categories
+//! with no need to follow every event of the contract, and checks from the digest that the
+//! subscribers did not change since it read them.
+//!
+//! The subscribers of a category are indexed from 0 to their number: an account leaving the
+//! list is replaced with the last one, so the order of the subscribers is not kept. The digest
+//! is the XOR of the Blake2x256 hashes of the SCALE encoding of the subscribers, so every
+//! subscription updates it without reading the other subscribers.
+//!
+
+use crate::{
+    asset_erc721::{
+        AssetId,
+        Error,
+    },
+    certificates::CertificateId,
+};
+use ink_env::{
+    hash::Blake2x256,
+    AccountId,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+/// Event of a category reported to its subscribers
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub enum Alert {
+    /// The asset has been validated
+    Validated(AssetId),
+    /// The validation of the asset has been revoked
+    ValidationRevoked(AssetId),
+    /// A compliance certificate has been issued for a shipment of assets of the category
+    Certified(CertificateId),
+    /// The compliance certificate has been revoked
+    CertificateRevoked(CertificateId),
+}
+
+/// Storage of the subscriptions to the alerts
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Alerts {
+    /// Counter of the subscribers of the category
+    category_subscribers_count: StorageHashMap<u32, u32>,
+    /// Subscribers of the category, indexed from 0 to the counter
+    category_subscribers: StorageHashMap<(u32, u32), AccountId>,
+    /// Position of the account in the subscribers of the category
+    subscriber_position: StorageHashMap<(u32, AccountId), u32>,
+    /// Digest of the subscribers of the category
+    category_digest: StorageHashMap<u32, Hash>,
+    /// Categories the account subscribed to
+    account_categories: StorageHashMap<AccountId, Vec<u32>>,
+}
+
+impl Alerts {
+    /// Returns the number of subscribers of `category`
+    pub fn subscribers_count(&self, category: u32) -> u32 {
+        *self.category_subscribers_count.get(&category).unwrap_or(&0)
+    }
+
+    /// Returns at most `limit` subscribers of `category` starting from position `offset`
+    pub fn subscribers_of(&self, category: u32, offset: u32, limit: u32) -> Vec<AccountId> {
+        (offset..self.subscribers_count(category))
+            .take(limit as usize)
+            .filter_map(|index| self.category_subscribers.get(&(category, index)).cloned())
+            .collect()
+    }
+
+    /// Returns the digest of the subscribers of `category`, `None` if the category has no subscriber
+    pub fn digest_of(&self, category: u32) -> Option<Hash> {
+        self.category_digest.get(&category).cloned()
+    }
+
+    /// Returns the categories `account` subscribed to
+    pub fn categories_of(&self, account: &AccountId) -> Vec<u32> {
+        self.account_categories.get(account).cloned().unwrap_or_default()
+    }
+
+    /// Subscribes `account` to `category`
+    pub fn subscribe(&mut self, account: AccountId, category: u32) -> Result<(), Error> {
+        if self.subscriber_position.contains_key(&(category, account)) {
+            return Err(Error::DuplicatedData)
+        }
+        let mut categories = self.categories_of(&account);
+        categories.push(category);
+        self.account_categories.insert(account, categories);
+        let index = self.subscribers_count(category);
+        self.category_subscribers_count.insert(category, index + 1);
+        self.category_subscribers.insert((category, index), account);
+        self.subscriber_position.insert((category, account), index);
+        self.digest_toggle(category, &account);
+        Ok(())
+    }
+
+    /// Unsubscribes `account` from `category`
+    pub fn unsubscribe(&mut self, account: AccountId, category: u32) -> Result<(), Error> {
+        let index = self.subscriber_position.take(&(category, account)).ok_or(Error::NotAllowed)?;
+        let mut categories = self.categories_of(&account);
+        categories.retain(|other| *other != category);
+        if categories.is_empty() {
+            self.account_categories.take(&account);
+        } else {
+            self.account_categories.insert(account, categories);
+        }
+        // the last subscriber takes the freed position
+        let last = self.subscribers_count(category) - 1;
+        if last == 0 {
+            self.category_subscribers_count.take(&category);
+        } else {
+            self.category_subscribers_count.insert(category, last);
+        }
+        if let Some(last_account) = self.category_subscribers.take(&(category, last)) {
+            if index != last {
+                self.category_subscribers.insert((category, index), last_account);
+                self.subscriber_position.insert((category, last_account), index);
+            }
+        }
+        self.digest_toggle(category, &account);
+        Ok(())
+    }
+
+    /// Adds `account` to the digest of the subscribers of `category` or removes it, the digest of no subscriber is removed
+    fn digest_toggle(&mut self, category: u32, account: &AccountId) {
+        if self.subscribers_count(category) == 0 {
+            self.category_digest.take(&category);
+            return
+        }
+        let mut output = [0x0; 32];
+        ink_env::hash_encoded::<Blake2x256, _>(account, &mut output);
+        if let Some(digest) = self.digest_of(category) {
+            for (byte, other) in output.iter_mut().zip(digest.as_ref()) {
+                *byte ^= other;
+            }
+        }
+        self.category_digest.insert(category, Hash::from(output));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn alerts_work() {
+        let alice = AccountId::from([0x1; 32]);
+        let bob = AccountId::from([0x2; 32]);
+        let mut alerts = Alerts::default();
+        assert_eq!(alerts.digest_of(1), None);
+        assert_eq!(alerts.subscribe(alice, 1), Ok(()));
+        assert_eq!(alerts.subscribe(alice, 1), Err(Error::DuplicatedData));
+        let digest = alerts.digest_of(1);
+        assert!(digest.is_some());
+        // the digest changes with the subscribers
+        assert_eq!(alerts.subscribe(bob, 1), Ok(()));
+        assert_eq!(alerts.subscribe(bob, 2), Ok(()));
+        let both = alerts.digest_of(1);
+        assert_ne!(both, digest);
+        assert_eq!(alerts.subscribers_of(1, 0, 10), [alice, bob]);
+        assert_eq!(alerts.categories_of(&bob), [1, 2]);
+        // the last subscriber takes the place of the one leaving the list
+        assert_eq!(alerts.unsubscribe(alice, 1), Ok(()));
+        assert_eq!(alerts.subscribers_of(1, 0, 10), [bob]);
+        assert_eq!(alerts.subscribe(alice, 1), Ok(()));
+        assert_eq!(alerts.subscribers_of(1, 0, 10), [bob, alice]);
+        // the digest depends on the subscribers, not on their order
+        assert_eq!(alerts.digest_of(1), both);
+        assert_eq!(alerts.unsubscribe(bob, 1), Ok(()));
+        assert_eq!(alerts.unsubscribe(bob, 1), Err(Error::NotAllowed));
+        assert_eq!(alerts.digest_of(1), digest);
+        assert_eq!(alerts.unsubscribe(alice, 1), Ok(()));
+        assert_eq!(alerts.digest_of(1), None);
+        assert_eq!(alerts.subscribers_count(1), 0);
+        assert_eq!(alerts.categories_of(&bob), [2]);
+    }
+}
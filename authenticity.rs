@@ -0,0 +1,126 @@
+//! # Authenticity
+//!
+//! Commit-reveal challenge proving the physical authenticity of the assets.
+//!
+//! The producer commits the hash of a secret for the asset, the secret is sealed inside the
+//! tamper-proof packaging of the product. The physical holder of the product can reveal the
+//! secret once: when its hash matches the commitment the asset is physically verified.
+//!
+
+use crate::asset_erc721::{
+    AssetId,
+    Error,
+};
+use ink_env::{
+    hash::Blake2x256,
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Physical verification of an asset
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Reveal {
+    /// Account that revealed the secret
+    pub account: AccountId,
+    /// Block of the reveal
+    pub block: BlockNumber,
+}
+
+/// Storage of the authenticity challenges of the assets
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Authenticity {
+    /// Blake2x256 hash of the secret of the asset committed from the producer
+    asset_commitment: StorageHashMap<AssetId, Hash>,
+    /// Reveal of the secret of the asset
+    asset_reveal: StorageHashMap<AssetId, Reveal>,
+}
+
+impl Authenticity {
+    /// Returns the hash of `secret` to commit for an asset
+    pub fn commitment_of(secret: &[u8]) -> Hash {
+        let mut output = [0x0; 32];
+        ink_env::hash_bytes::<Blake2x256>(secret, &mut output);
+        Hash::from(output)
+    }
+
+    /// Returns true if a commitment is stored for asset `id`
+    pub fn is_committed(&self, id: AssetId) -> bool {
+        self.asset_commitment.contains_key(&id)
+    }
+
+    /// Stores the commitment of asset `id`, it can be written once
+    pub fn commit(&mut self, id: AssetId, commitment: Hash) -> Result<(), Error> {
+        if self.asset_commitment.contains_key(&id) {
+            return Err(Error::DuplicatedData)
+        }
+        self.asset_commitment.insert(id, commitment);
+        Ok(())
+    }
+
+    /// Returns the reveal of asset `id`
+    pub fn reveal_of(&self, id: AssetId) -> Option<Reveal> {
+        self.asset_reveal.get(&id).cloned()
+    }
+
+    /// Checks `secret` matches the commitment of asset `id` and records the reveal, it can be done once
+    pub fn reveal(&mut self, id: AssetId, secret: &[u8], reveal: Reveal) -> Result<(), Error> {
+        let commitment = self.asset_commitment.get(&id).ok_or(Error::CommitmentNotFound)?;
+        if self.asset_reveal.contains_key(&id) {
+            return Err(Error::AlreadyRevealed)
+        }
+        if *commitment != Self::commitment_of(secret) {
+            return Err(Error::InvalidSecret)
+        }
+        self.asset_reveal.insert(id, reveal);
+        Ok(())
+    }
+
+    /// Removes the challenge of the deleted asset `id`
+    pub fn remove(&mut self, id: AssetId) {
+        self.asset_commitment.take(&id);
+        self.asset_reveal.take(&id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn reveal_works() {
+        let reveal = Reveal {
+            account: AccountId::from([0x1; 32]),
+            block: 1,
+        };
+        let mut authenticity = Authenticity::default();
+        assert_eq!(authenticity.reveal(1, b"secret", reveal), Err(Error::CommitmentNotFound));
+        assert_eq!(authenticity.commit(1, Authenticity::commitment_of(b"secret")), Ok(()));
+        assert_eq!(authenticity.commit(1, Authenticity::commitment_of(b"other")), Err(Error::DuplicatedData));
+        assert_eq!(authenticity.reveal(1, b"wrong", reveal), Err(Error::InvalidSecret));
+        assert_eq!(authenticity.reveal_of(1), None);
+        assert_eq!(authenticity.reveal(1, b"secret", reveal), Ok(()));
+        assert_eq!(authenticity.reveal_of(1), Some(reveal));
+        // the secret can be revealed once
+        assert_eq!(authenticity.reveal(1, b"secret", reveal), Err(Error::AlreadyRevealed));
+    }
+}
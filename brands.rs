@@ -0,0 +1,192 @@
+//! # Brands
+//!
+//! Registry of the brands of the producers and of the assets linked to each brand.
+//!
+//! The brands are numbered from 0 in the order they are registered. The assets of a brand are
+//! kept in a list indexed from 0 to the number of assets of the brand, like the assets of an
+//! owner in [`crate::ownership`].
+//!
+
+use crate::asset_erc721::{
+    AssetId,
+    Error,
+};
+use ink_env::{
+    AccountId,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+/// Brand ID
+pub type BrandId = u32;
+
+/// Brand registered from a producer
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Brand {
+    /// Account that registered the brand and can link assets to it
+    pub owner: AccountId,
+    /// Hash of the name of the brand
+    pub name: Hash,
+    /// Hash of the logo of the brand
+    pub logo: Hash,
+    /// The brand has been audited from an administrator
+    pub verified: bool,
+}
+
+/// Storage of the brands
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Brands {
+    /// Brands indexed from 0 to the brands counter
+    brands: StorageHashMap<BrandId, Brand>,
+    /// Counter of the brands registered
+    brands_count: u32,
+    /// Brand of the asset
+    asset_brand: StorageHashMap<AssetId, BrandId>,
+    /// Counter of the assets linked to each brand
+    brand_assets_count: StorageHashMap<BrandId, u32>,
+    /// List of the assets of each brand, indexed from 0 to the brand assets counter
+    brand_assets: StorageHashMap<(BrandId, u32), AssetId>,
+    /// Position of the asset in the list of the assets of its brand
+    asset_brand_index: StorageHashMap<AssetId, u32>,
+}
+
+impl Brands {
+    /// Registers a new brand and returns its id
+    pub fn register(&mut self, brand: Brand) -> BrandId {
+        let brand_id = self.brands_count;
+        self.brands.insert(brand_id, brand);
+        self.brands_count += 1;
+        brand_id
+    }
+
+    /// Returns the brand `brand_id`
+    pub fn get(&self, brand_id: BrandId) -> Option<Brand> {
+        self.brands.get(&brand_id).cloned()
+    }
+
+    /// Sets the brand `brand_id` as verified or not, fails if it already has this state
+    pub fn verified_set(&mut self, brand_id: BrandId, verified: bool) -> Result<(), Error> {
+        let brand = self.brands.get_mut(&brand_id).ok_or(Error::BrandNotFound)?;
+        if brand.verified == verified {
+            return Err(Error::DuplicatedData)
+        }
+        brand.verified = verified;
+        Ok(())
+    }
+
+    /// Returns the brand of asset `id`
+    pub fn brand_of(&self, id: AssetId) -> Option<BrandId> {
+        self.asset_brand.get(&id).cloned()
+    }
+
+    /// Returns the number of assets linked to brand `brand_id`
+    pub fn assets_count(&self, brand_id: BrandId) -> u32 {
+        *self.brand_assets_count.get(&brand_id).unwrap_or(&0)
+    }
+
+    /// Returns at most `limit` assets of brand `brand_id` starting from position `offset`
+    pub fn assets_of(&self, brand_id: BrandId, offset: u32, limit: u32) -> Vec<AssetId> {
+        (offset..self.assets_count(brand_id))
+            .take(limit as usize)
+            .filter_map(|index| self.brand_assets.get(&(brand_id, index)).cloned())
+            .collect()
+    }
+
+    /// Links asset `id` to the existing brand `brand_id`
+    pub fn link(&mut self, id: AssetId, brand_id: BrandId) -> Result<(), Error> {
+        if !self.brands.contains_key(&brand_id) {
+            return Err(Error::BrandNotFound)
+        }
+        if self.asset_brand.contains_key(&id) {
+            return Err(Error::DuplicatedData)
+        }
+        let index = self.assets_count(brand_id);
+        self.asset_brand.insert(id, brand_id);
+        self.brand_assets_count.insert(brand_id, index + 1);
+        self.brand_assets.insert((brand_id, index), id);
+        self.asset_brand_index.insert(id, index);
+        Ok(())
+    }
+
+    /// Removes asset `id` from its brand, moving the last asset of the brand in the freed position
+    pub fn unlink(&mut self, id: AssetId) -> Option<BrandId> {
+        let brand_id = self.asset_brand.take(&id)?;
+        let last = self.assets_count(brand_id).saturating_sub(1);
+        self.brand_assets_count.insert(brand_id, last);
+        let index = self.asset_brand_index.take(&id).unwrap_or(last);
+        if let Some(last_id) = self.brand_assets.take(&(brand_id, last)) {
+            if index != last {
+                self.brand_assets.insert((brand_id, index), last_id);
+                self.asset_brand_index.insert(last_id, index);
+            }
+        }
+        Some(brand_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    fn brand() -> Brand {
+        Brand {
+            owner: AccountId::from([0x1; 32]),
+            name: Hash::from([0x01; 32]),
+            logo: Hash::from([0x02; 32]),
+            verified: false,
+        }
+    }
+
+    #[ink::test]
+    fn register_works() {
+        let mut brands = Brands::default();
+        assert_eq!(brands.register(brand()), 0);
+        assert_eq!(brands.register(brand()), 1);
+        assert_eq!(brands.get(1), Some(brand()));
+        assert_eq!(brands.get(2), None);
+    }
+
+    #[ink::test]
+    fn verified_set_works() {
+        let mut brands = Brands::default();
+        let brand_id = brands.register(brand());
+        assert_eq!(brands.verified_set(brand_id, false), Err(Error::DuplicatedData));
+        assert_eq!(brands.verified_set(brand_id, true), Ok(()));
+        assert_eq!(brands.get(brand_id).map(|brand| brand.verified), Some(true));
+        assert_eq!(brands.verified_set(brand_id + 1, true), Err(Error::BrandNotFound));
+    }
+
+    #[ink::test]
+    fn link_works() {
+        let mut brands = Brands::default();
+        let brand_id = brands.register(brand());
+        assert_eq!(brands.link(1, brand_id + 1), Err(Error::BrandNotFound));
+        for id in 1..=3 {
+            assert_eq!(brands.link(id, brand_id), Ok(()));
+        }
+        assert_eq!(brands.link(1, brand_id), Err(Error::DuplicatedData));
+        assert_eq!(brands.brand_of(2), Some(brand_id));
+        assert_eq!(brands.assets_of(brand_id, 0, 10), [1, 2, 3]);
+        // the last asset takes the place of the unlinked one
+        assert_eq!(brands.unlink(1), Some(brand_id));
+        assert_eq!(brands.unlink(1), None);
+        assert_eq!(brands.assets_count(brand_id), 2);
+        assert_eq!(brands.assets_of(brand_id, 0, 10), [3, 2]);
+    }
+}
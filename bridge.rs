@@ -0,0 +1,193 @@
+//! # Bridge
+//!
+//! Wrapping of the assets mirrored as ERC-721 tokens on an EVM chain.
+//!
+//! The owner locks an asset in the custody of the contract, naming the EVM address that
+//! receives the wrapped token. The lock emits a deterministic payload, ABI-encoded as
+//! `(uint256 tokenId, address recipient, bytes32 metadata, uint256 nonce)`, whose Keccak-256
+//! hash the EVM side checks before minting. The token id is the asset id, so the mapping
+//! between the two chains is fixed by this contract.
+//!
+//! When the wrapped token is burnt, the relayer registered from the administrators submits
+//! the proof of the burn with the nonce of the lock, and the asset is released to the account
+//! named in the burn. Each burn proof unlocks once.
+//!
+
+use crate::asset_erc721::{
+    AssetId,
+    Error,
+};
+use ink_env::{
+    hash::Keccak256,
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Address of an account on the EVM chain
+pub type EvmAddress = [u8; 20];
+
+/// Lock of an asset wrapped on the EVM chain
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct BridgeLock {
+    /// Owner of the asset when it was locked
+    pub owner: AccountId,
+    /// EVM address receiving the wrapped token
+    pub recipient: EvmAddress,
+    /// Metadata pointer of the asset when it was locked
+    pub metadata: Option<Hash>,
+    /// Nonce of the lock, unique across the contract
+    pub nonce: u64,
+    /// Block of the lock
+    pub block: BlockNumber,
+}
+
+/// Storage of the assets wrapped on the EVM chain
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Bridge {
+    /// Account allowed to submit the burn proofs
+    relayer: Option<AccountId>,
+    /// Counter of the locks, the nonce of the next lock
+    nonce: u64,
+    /// Lock of the wrapped asset
+    asset_lock: StorageHashMap<AssetId, BridgeLock>,
+    /// Burn proofs already used to unlock an asset
+    burn_proofs: StorageHashMap<Hash, AssetId>,
+}
+
+impl Bridge {
+    /// Returns the ERC-721 token id of asset `id`, as a big-endian uint256
+    pub fn token_id(id: AssetId) -> [u8; 32] {
+        let mut token_id = [0x0; 32];
+        token_id[28..].copy_from_slice(&id.to_be_bytes());
+        token_id
+    }
+
+    /// Returns the ABI-encoded payload of the lock of asset `id`
+    pub fn payload(id: AssetId, lock: &BridgeLock) -> Vec<u8> {
+        let mut payload = Self::token_id(id).to_vec();
+        payload.extend_from_slice(&[0x0; 12]);
+        payload.extend_from_slice(&lock.recipient);
+        payload.extend_from_slice(lock.metadata.as_ref().map_or(&[0x0; 32][..], |metadata| metadata.as_ref()));
+        payload.extend_from_slice(&[0x0; 24]);
+        payload.extend_from_slice(&lock.nonce.to_be_bytes());
+        payload
+    }
+
+    /// Returns the Keccak-256 hash of `payload`, as checked from the EVM side
+    pub fn payload_hash(payload: &[u8]) -> Hash {
+        let mut output = [0x0; 32];
+        ink_env::hash_bytes::<Keccak256>(payload, &mut output);
+        Hash::from(output)
+    }
+
+    /// Returns the account allowed to submit the burn proofs
+    pub fn relayer(&self) -> Option<AccountId> {
+        self.relayer
+    }
+
+    /// Sets or removes the account allowed to submit the burn proofs
+    pub fn relayer_set(&mut self, relayer: Option<AccountId>) {
+        self.relayer = relayer;
+    }
+
+    /// Returns the lock of asset `id`
+    pub fn lock_of(&self, id: AssetId) -> Option<BridgeLock> {
+        self.asset_lock.get(&id).cloned()
+    }
+
+    /// Returns true if asset `id` is locked on the bridge
+    pub fn is_locked(&self, id: AssetId) -> bool {
+        self.asset_lock.contains_key(&id)
+    }
+
+    /// Locks asset `id` giving the lock the next nonce, and returns the lock
+    pub fn lock(&mut self, id: AssetId, owner: AccountId, recipient: EvmAddress, metadata: Option<Hash>, block: BlockNumber) -> Result<BridgeLock, Error> {
+        if self.asset_lock.contains_key(&id) {
+            return Err(Error::AssetBridged)
+        }
+        let lock = BridgeLock {
+            owner,
+            recipient,
+            metadata,
+            nonce: self.nonce,
+            block,
+        };
+        self.asset_lock.insert(id, lock);
+        self.nonce += 1;
+        Ok(lock)
+    }
+
+    /// Unlocks asset `id` with the proof of the burn of the lock `nonce`, and returns the lock
+    pub fn unlock(&mut self, id: AssetId, nonce: u64, burn_proof: Hash) -> Result<BridgeLock, Error> {
+        let lock = self.lock_of(id).ok_or(Error::BridgeLockNotFound)?;
+        if lock.nonce != nonce {
+            return Err(Error::BridgeLockNotFound)
+        }
+        if self.burn_proofs.contains_key(&burn_proof) {
+            return Err(Error::DuplicatedData)
+        }
+        self.burn_proofs.insert(burn_proof, id);
+        self.asset_lock.take(&id);
+        Ok(lock)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn lock_works() {
+        let owner = AccountId::from([0x1; 32]);
+        let mut bridge = Bridge::default();
+        let lock = bridge.lock(1, owner, [0xaa; 20], None, 1).expect("Cannot lock");
+        assert_eq!(lock.nonce, 0);
+        assert!(bridge.is_locked(1));
+        assert_eq!(bridge.lock(1, owner, [0xaa; 20], None, 1), Err(Error::AssetBridged));
+        assert_eq!(bridge.lock(2, owner, [0xaa; 20], None, 1).map(|lock| lock.nonce), Ok(1));
+        assert_eq!(bridge.unlock(1, 1, Hash::from([0x01; 32])), Err(Error::BridgeLockNotFound));
+        assert_eq!(bridge.unlock(1, 0, Hash::from([0x01; 32])), Ok(lock));
+        assert!(!bridge.is_locked(1));
+        // a burn proof unlocks once
+        assert_eq!(bridge.unlock(2, 1, Hash::from([0x01; 32])), Err(Error::DuplicatedData));
+        assert_eq!(bridge.unlock(1, 0, Hash::from([0x02; 32])), Err(Error::BridgeLockNotFound));
+    }
+
+    #[ink::test]
+    fn payload_works() {
+        let lock = BridgeLock {
+            owner: AccountId::from([0x1; 32]),
+            recipient: [0xaa; 20],
+            metadata: Some(Hash::from([0x03; 32])),
+            nonce: 7,
+            block: 1,
+        };
+        let payload = Bridge::payload(258, &lock);
+        assert_eq!(payload.len(), 128);
+        assert_eq!(payload[30..32], [0x01, 0x02]);
+        assert_eq!(payload[44..64], [0xaa; 20]);
+        assert_eq!(payload[64..96], [0x03; 32]);
+        assert_eq!(payload[127], 7);
+    }
+}
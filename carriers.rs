@@ -0,0 +1,198 @@
+//! # Carriers
+//!
+//! Carriers of the assets and the chain of the subcontracts of the logistics (3PL).
+//!
+//! The owner assigns a shipper to an asset, the current carrier can subcontract the leg to
+//! another shipper when the policy of the owner allows it. The chain records every carrier in
+//! order: the last one is the actual carrier, liable for the asset and allowed to write its
+//! location.
+//!
+
+use crate::asset_erc721::{
+    AssetId,
+    Error,
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Maximum number of carriers in the chain of an asset
+pub const MAX_CHAIN: usize = 8;
+
+/// Policy of an owner on the subcontracts of its assets
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub enum SubcontractPolicy {
+    /// The assigned shipper cannot subcontract
+    Forbidden,
+    /// The carrier can subcontract to any shipper
+    AnyShipper,
+    /// The carrier can subcontract to the shippers approved from the owner
+    ApprovedOnly,
+}
+
+/// Carrier in the chain of an asset
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Leg {
+    /// Shipper carrying the asset
+    pub carrier: AccountId,
+    /// Account that assigned the asset to the carrier, the owner or the previous carrier
+    pub assigned_by: AccountId,
+    /// Block of the assignment
+    pub block: BlockNumber,
+}
+
+/// Storage of the carriers of the assets
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Carriers {
+    /// Chain of the carriers of the asset, the last one is the actual carrier
+    asset_chain: StorageHashMap<AssetId, Vec<Leg>>,
+    /// Subcontract policy of the owner
+    owner_policy: StorageHashMap<AccountId, SubcontractPolicy>,
+    /// Shippers approved as subcontractors from the owner
+    owner_subcontractors: StorageHashMap<(AccountId, AccountId), ()>,
+}
+
+impl Carriers {
+    /// Returns the chain of the carriers of asset `id`
+    pub fn chain_of(&self, id: AssetId) -> Vec<Leg> {
+        self.asset_chain.get(&id).cloned().unwrap_or_default()
+    }
+
+    /// Returns the actual carrier of asset `id`
+    pub fn carrier_of(&self, id: AssetId) -> Option<AccountId> {
+        self.asset_chain.get(&id)?.last().map(|leg| leg.carrier)
+    }
+
+    /// Assigns asset `id` to a carrier, starting a new chain
+    pub fn assign(&mut self, id: AssetId, leg: Leg) {
+        self.asset_chain.insert(id, [leg].to_vec());
+    }
+
+    /// Subcontracts asset `id` from its actual carrier, `leg.assigned_by`, to `leg.carrier`.
+    /// Each shipper can appear once in the chain.
+    pub fn subcontract(&mut self, id: AssetId, leg: Leg) -> Result<(), Error> {
+        let chain = self.asset_chain.get_mut(&id).ok_or(Error::CarrierNotAssigned)?;
+        if chain.last().map(|last| last.carrier) != Some(leg.assigned_by) {
+            return Err(Error::NotAllowed)
+        }
+        if chain.iter().any(|other| other.carrier == leg.carrier) {
+            return Err(Error::DuplicatedData)
+        }
+        if chain.len() >= MAX_CHAIN {
+            return Err(Error::SubcontractNotAllowed)
+        }
+        chain.push(leg);
+        Ok(())
+    }
+
+    /// Removes the carriers of asset `id` and returns their chain
+    pub fn release(&mut self, id: AssetId) -> Option<Vec<Leg>> {
+        self.asset_chain.take(&id)
+    }
+
+    /// Returns the subcontract policy of `owner`, subcontracts are forbidden by default
+    pub fn policy_of(&self, owner: &AccountId) -> SubcontractPolicy {
+        self.owner_policy
+            .get(owner)
+            .cloned()
+            .unwrap_or(SubcontractPolicy::Forbidden)
+    }
+
+    /// Stores the subcontract policy of `owner`
+    pub fn policy_set(&mut self, owner: AccountId, policy: SubcontractPolicy) {
+        self.owner_policy.insert(owner, policy);
+    }
+
+    /// Returns true if `owner` approved `shipper` as subcontractor
+    pub fn is_approved(&self, owner: &AccountId, shipper: &AccountId) -> bool {
+        self.owner_subcontractors.contains_key(&(*owner, *shipper))
+    }
+
+    /// Approves or revokes `shipper` as subcontractor of `owner`
+    pub fn approve(&mut self, owner: AccountId, shipper: AccountId, approved: bool) {
+        if approved {
+            self.owner_subcontractors.insert((owner, shipper), ());
+        } else {
+            self.owner_subcontractors.take(&(owner, shipper));
+        }
+    }
+
+    /// Returns true if the policy of `owner` allows subcontracting to `shipper`
+    pub fn is_allowed(&self, owner: &AccountId, shipper: &AccountId) -> bool {
+        match self.policy_of(owner) {
+            SubcontractPolicy::Forbidden => false,
+            SubcontractPolicy::AnyShipper => true,
+            SubcontractPolicy::ApprovedOnly => self.is_approved(owner, shipper),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    fn leg(carrier: u8, assigned_by: u8) -> Leg {
+        Leg {
+            carrier: AccountId::from([carrier; 32]),
+            assigned_by: AccountId::from([assigned_by; 32]),
+            block: 1,
+        }
+    }
+
+    #[ink::test]
+    fn subcontract_works() {
+        let mut carriers = Carriers::default();
+        assert_eq!(carriers.subcontract(1, leg(2, 1)), Err(Error::CarrierNotAssigned));
+        carriers.assign(1, leg(1, 0));
+        // only the actual carrier can subcontract
+        assert_eq!(carriers.subcontract(1, leg(3, 2)), Err(Error::NotAllowed));
+        assert_eq!(carriers.subcontract(1, leg(2, 1)), Ok(()));
+        assert_eq!(carriers.subcontract(1, leg(1, 2)), Err(Error::DuplicatedData));
+        assert_eq!(carriers.carrier_of(1), Some(AccountId::from([0x2; 32])));
+        assert_eq!(carriers.chain_of(1), [leg(1, 0), leg(2, 1)]);
+        for carrier in 3..9 {
+            assert_eq!(carriers.subcontract(1, leg(carrier, carrier - 1)), Ok(()));
+        }
+        assert_eq!(carriers.subcontract(1, leg(9, 8)), Err(Error::SubcontractNotAllowed));
+        assert_eq!(carriers.release(1).map(|chain| chain.len()), Some(MAX_CHAIN));
+        assert_eq!(carriers.carrier_of(1), None);
+    }
+
+    #[ink::test]
+    fn policy_works() {
+        let owner = AccountId::from([0x1; 32]);
+        let shipper = AccountId::from([0x2; 32]);
+        let mut carriers = Carriers::default();
+        assert!(!carriers.is_allowed(&owner, &shipper));
+        carriers.policy_set(owner, SubcontractPolicy::AnyShipper);
+        assert!(carriers.is_allowed(&owner, &shipper));
+        carriers.policy_set(owner, SubcontractPolicy::ApprovedOnly);
+        assert!(!carriers.is_allowed(&owner, &shipper));
+        carriers.approve(owner, shipper, true);
+        assert!(carriers.is_allowed(&owner, &shipper));
+        carriers.approve(owner, shipper, false);
+        assert!(!carriers.is_allowed(&owner, &shipper));
+    }
+}
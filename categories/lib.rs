@@ -0,0 +1,153 @@
+//! # Asset Categories Registry
+//!
+//! Companion contract that keeps the taxonomy of the asset categories shared by several
+//! instances of the Asset ERC721 contract.
+//!
+//! ## Overview
+//!
+//! The owner of the registry stores the description of each category once. The asset contracts
+//! configured with the registry read the descriptions with `category_get` when an asset is
+//! assigned to a category they have not cached yet, so their administrators do not re-create the
+//! same categories on each contract.
+//!
+//! The selectors of the messages are fixed, the one of `category_get` in particular: the asset
+//! contracts call it from its selector.
+//!
+//! ## Error Handling
+//!
+//! Any function that modifies the state returns a Result type and does not changes the state
+//! if the Error occurs.
+//!
+
+#![cfg_attr(not(feature = "std"), no_std)]
+use ink_lang as ink;
+
+#[ink::contract]
+mod categories {
+    use ink_prelude::vec::Vec;
+    use ink_storage::collections::HashMap as StorageHashMap;
+    use scale::{
+        Decode,
+        Encode,
+    };
+
+    #[ink(storage)]
+    pub struct CategoryRegistry {
+        /// Account that deployed the registry, the only one that can change the categories
+        owner: AccountId,
+        /// Description of the category
+        descriptions: StorageHashMap<u32, Hash>,
+        /// Categories in the order of their creation
+        categories: Vec<u32>,
+    }
+
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        NotOwner,
+        DuplicatedData,
+        CategoryNotFound,
+    }
+
+    /// Event emitted when the description of a category is stored, updated or removed.
+    #[ink(event)]
+    pub struct CategoryUpdated {
+        #[ink(topic)]
+        category_id: u32,
+        description: Option<Hash>,
+    }
+
+    impl CategoryRegistry {
+        /// Creates a new empty registry owned by the caller.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                owner: Self::env().caller(),
+                descriptions: Default::default(),
+                categories: Default::default(),
+            }
+        }
+        /// Stores the description of a new category, only the owner of the registry can do it
+        #[ink(message, selector = "0x59ACBE6C")]
+        pub fn category_new(&mut self, category_id: u32, description: Hash) -> Result<(), Error> {
+            self.check_owner()?;
+            if self.descriptions.contains_key(&category_id) {
+                return Err(Error::DuplicatedData)
+            }
+            self.descriptions.insert(category_id, description);
+            self.categories.push(category_id);
+            self.env().emit_event(CategoryUpdated {
+                category_id,
+                description: Some(description),
+            });
+            Ok(())
+        }
+        /// Updates the description of a category, only the owner of the registry can do it. The asset contracts
+        /// keep their cached description until their administrators refresh it.
+        #[ink(message, selector = "0x1FE7BEE9")]
+        pub fn category_update(&mut self, category_id: u32, description: Hash) -> Result<(), Error> {
+            self.check_owner()?;
+            if !self.descriptions.contains_key(&category_id) {
+                return Err(Error::CategoryNotFound)
+            }
+            self.descriptions.insert(category_id, description);
+            self.env().emit_event(CategoryUpdated {
+                category_id,
+                description: Some(description),
+            });
+            Ok(())
+        }
+        /// Removes a category, only the owner of the registry can do it
+        #[ink(message, selector = "0x2DA9A758")]
+        pub fn category_delete(&mut self, category_id: u32) -> Result<(), Error> {
+            self.check_owner()?;
+            self.descriptions.take(&category_id).ok_or(Error::CategoryNotFound)?;
+            self.categories.retain(|other| *other != category_id);
+            self.env().emit_event(CategoryUpdated {
+                category_id,
+                description: None,
+            });
+            Ok(())
+        }
+        /// Returns the description of a category
+        #[ink(message, selector = "0x7F39A76A")]
+        pub fn category_get(&self, category_id: u32) -> Option<Hash> {
+            self.descriptions.get(&category_id).cloned()
+        }
+        /// Returns the categories of the registry, oldest first
+        #[ink(message, selector = "0xE5AC61F2")]
+        pub fn categories_list(&self) -> Vec<u32> {
+            self.categories.clone()
+        }
+
+        /// Checks the caller is the owner of the registry.
+        fn check_owner(&self) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner)
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink_lang as ink;
+
+        #[ink::test]
+        fn registry_works() {
+            let mut registry = CategoryRegistry::new();
+            assert_eq!(registry.category_get(7), None);
+            assert_eq!(registry.category_update(7, Hash::from([0x07; 32])), Err(Error::CategoryNotFound));
+            assert_eq!(registry.category_new(7, Hash::from([0x07; 32])), Ok(()));
+            assert_eq!(registry.category_new(7, Hash::from([0x08; 32])), Err(Error::DuplicatedData));
+            assert_eq!(registry.category_new(9, Hash::from([0x09; 32])), Ok(()));
+            assert_eq!(registry.category_update(7, Hash::from([0x0a; 32])), Ok(()));
+            assert_eq!(registry.category_get(7), Some(Hash::from([0x0a; 32])));
+            assert_eq!(registry.categories_list(), [7, 9]);
+            assert_eq!(registry.category_delete(7), Ok(()));
+            assert_eq!(registry.category_delete(7), Err(Error::CategoryNotFound));
+            assert_eq!(registry.categories_list(), [9]);
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! # Category Registry
+//!
+//! Calls to the registry contract of the categories shared between several asset contracts.
+//!
+//! The descriptions read from the registry are cached in [`crate::metadata`] like the ones
+//! stored from the administrators, so the registry is called only the first time a category is
+//! used and when an administrator refreshes it.
+//!
+
+use crate::asset_erc721::Error;
+use ink_env::{
+    call::{
+        build_call,
+        utils::ReturnType,
+        ExecutionInput,
+        Selector,
+    },
+    AccountId,
+    DefaultEnvironment,
+    Hash,
+};
+
+/// Selector of the `category_get` message of the registry
+const CATEGORY_GET: [u8; 4] = [0x7f, 0x39, 0xa7, 0x6a];
+
+/// Returns the description of category `categoryid` stored in `registry`
+pub fn description_of(registry: AccountId, categoryid: u32) -> Result<Option<Hash>, Error> {
+    build_call::<DefaultEnvironment>()
+        .callee(registry)
+        .gas_limit(0)
+        .transferred_value(0)
+        .exec_input(ExecutionInput::new(Selector::new(CATEGORY_GET)).push_arg(categoryid))
+        .returns::<ReturnType<Option<Hash>>>()
+        .fire()
+        .map_err(|_| Error::CategoryRegistryFailed)
+}
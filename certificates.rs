@@ -0,0 +1,167 @@
+//! # Certificates
+//!
+//! Cold-chain compliance certificates of the delivered shipments.
+//!
+//! The sensor history of a shipment is recorded off-chain: an administrator reviews it once
+//! the shipment is delivered and issues a certificate storing the hash of the reviewed record,
+//! the temperature range observed and the outcome. The certificate is bound to every asset
+//! of the manifest, so a buyer reads all the certificates of an asset in one query.
+//!
+
+use crate::{
+    asset_erc721::{
+        AssetId,
+        Error,
+    },
+    shipments::ManifestId,
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Certificate ID
+pub type CertificateId = u32;
+
+/// Cold-chain compliance certificate of a shipment
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Certificate {
+    /// Shipment reviewed
+    pub manifest_id: ManifestId,
+    /// Account that reviewed the sensor history
+    pub issuer: AccountId,
+    /// Hash of the sensor history reviewed
+    pub sensor_log: Hash,
+    /// Minimum temperature recorded, in tenths of degree Celsius
+    pub min_temperature: i16,
+    /// Maximum temperature recorded, in tenths of degree Celsius
+    pub max_temperature: i16,
+    /// The cold chain has been respected
+    pub compliant: bool,
+    /// Block of the issue
+    pub block: BlockNumber,
+}
+
+/// Storage of the certificates
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Certificates {
+    /// Certificates indexed from 0 to the certificates counter
+    certificates: StorageHashMap<CertificateId, Certificate>,
+    /// Counter of the certificates issued
+    certificates_count: u32,
+    /// Certificate of the shipment
+    manifest_certificate: StorageHashMap<ManifestId, CertificateId>,
+    /// Counter of the certificates bound to each asset
+    asset_certificates_count: StorageHashMap<AssetId, u32>,
+    /// List of the certificates of each asset, indexed from 0 to the asset certificates counter
+    asset_certificates: StorageHashMap<(AssetId, u32), CertificateId>,
+}
+
+impl Certificates {
+    /// Returns the certificate `certificate_id`
+    pub fn get(&self, certificate_id: CertificateId) -> Option<Certificate> {
+        self.certificates.get(&certificate_id).cloned()
+    }
+
+    /// Returns the certificate of the shipment `manifest_id`
+    pub fn certificate_of(&self, manifest_id: ManifestId) -> Option<CertificateId> {
+        self.manifest_certificate.get(&manifest_id).cloned()
+    }
+
+    /// Returns the certificates bound to asset `id` with their ids, oldest first
+    pub fn certificates_of(&self, id: AssetId) -> Vec<(CertificateId, Certificate)> {
+        let count = *self.asset_certificates_count.get(&id).unwrap_or(&0);
+        (0..count)
+            .filter_map(|index| self.asset_certificates.get(&(id, index)))
+            .filter_map(|certificate_id| self.get(*certificate_id).map(|certificate| (*certificate_id, certificate)))
+            .collect()
+    }
+
+    /// Stores the certificate of a shipment binding it to `assets`, a shipment is certified once
+    pub fn issue(&mut self, certificate: Certificate, assets: &[AssetId]) -> Result<CertificateId, Error> {
+        if self.manifest_certificate.contains_key(&certificate.manifest_id) {
+            return Err(Error::DuplicatedData)
+        }
+        let certificate_id = self.certificates_count;
+        self.certificates.insert(certificate_id, certificate);
+        self.manifest_certificate.insert(certificate.manifest_id, certificate_id);
+        self.certificates_count += 1;
+        for id in assets {
+            let index = *self.asset_certificates_count.get(id).unwrap_or(&0);
+            self.asset_certificates.insert((*id, index), certificate_id);
+            self.asset_certificates_count.insert(*id, index + 1);
+        }
+        Ok(certificate_id)
+    }
+
+    /// Unlinks the revoked certificate of the shipment `manifest_id`, so a new certificate can be issued. The revoked
+    /// certificate stays bound to the assets.
+    pub fn supersede(&mut self, manifest_id: ManifestId) {
+        self.manifest_certificate.take(&manifest_id);
+    }
+
+    /// Unbinds the certificates from the deleted asset `id`, the certificates are kept
+    pub fn remove(&mut self, id: AssetId) {
+        let count = self.asset_certificates_count.take(&id).unwrap_or(0);
+        for index in 0..count {
+            self.asset_certificates.take(&(id, index));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    fn certificate(manifest_id: ManifestId, compliant: bool) -> Certificate {
+        Certificate {
+            manifest_id,
+            issuer: AccountId::from([0x1; 32]),
+            sensor_log: Hash::from([0x01; 32]),
+            min_temperature: 20,
+            max_temperature: 45,
+            compliant,
+            block: 1,
+        }
+    }
+
+    #[ink::test]
+    fn issue_works() {
+        let mut certificates = Certificates::default();
+        assert_eq!(certificates.issue(certificate(0, true), &[1, 2]), Ok(0));
+        assert_eq!(certificates.issue(certificate(0, false), &[1]), Err(Error::DuplicatedData));
+        assert_eq!(certificates.issue(certificate(1, false), &[2]), Ok(1));
+        assert_eq!(certificates.certificate_of(1), Some(1));
+        assert_eq!(certificates.certificates_of(1), [(0, certificate(0, true))]);
+        assert_eq!(certificates.certificates_of(2), [(0, certificate(0, true)), (1, certificate(1, false))]);
+        certificates.remove(2);
+        assert!(certificates.certificates_of(2).is_empty());
+        assert_eq!(certificates.get(1), Some(certificate(1, false)));
+        // a superseded certificate is replaced from a new one
+        certificates.supersede(0);
+        assert_eq!(certificates.issue(certificate(0, false), &[1]), Ok(2));
+        assert_eq!(certificates.certificate_of(0), Some(2));
+        assert_eq!(certificates.certificates_of(1).len(), 2);
+    }
+}
@@ -0,0 +1,143 @@
+//! # Contents
+//!
+//! Reverse index from the content hashes stored for the assets to the assets referencing them.
+//!
+//! The photos, the additional metadata, the reports of the lab results and the sensor logs of
+//! the compliance certificates are indexed by their hash, so the investigators find every asset
+//! reusing a photo or a certificate with one query. An asset referencing the same content more
+//! than once is listed once, and it leaves the list when its last reference is removed. The
+//! list of a content is indexed from 0 to its length: a removed asset is replaced with the last
+//! one, so the order of the assets is not kept.
+//!
+
+use crate::asset_erc721::AssetId;
+use ink_env::Hash;
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::SpreadLayout,
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+
+/// Storage of the index of the contents
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Contents {
+    /// Counter of the assets referencing the content
+    content_assets_count: StorageHashMap<Hash, u32>,
+    /// Assets referencing the content, indexed from 0 to the counter
+    content_assets: StorageHashMap<(Hash, u32), AssetId>,
+    /// Position of the asset in the list of the content and number of its references to the content
+    asset_position: StorageHashMap<(Hash, AssetId), (u32, u32)>,
+    /// Contents referenced from the asset
+    asset_contents: StorageHashMap<AssetId, Vec<Hash>>,
+}
+
+impl Contents {
+    /// Returns the number of assets referencing `content`
+    pub fn assets_count(&self, content: &Hash) -> u32 {
+        *self.content_assets_count.get(content).unwrap_or(&0)
+    }
+
+    /// Returns at most `limit` assets referencing `content` starting from position `offset`
+    pub fn assets_of(&self, content: &Hash, offset: u32, limit: u32) -> Vec<AssetId> {
+        (offset..self.assets_count(content))
+            .take(limit as usize)
+            .filter_map(|index| self.content_assets.get(&(*content, index)).cloned())
+            .collect()
+    }
+
+    /// Adds a reference of asset `id` to `content`
+    pub fn link(&mut self, content: Hash, id: AssetId) {
+        if let Some((_, references)) = self.asset_position.get_mut(&(content, id)) {
+            *references += 1;
+            return
+        }
+        let index = self.assets_count(&content);
+        self.content_assets_count.insert(content, index + 1);
+        self.content_assets.insert((content, index), id);
+        self.asset_position.insert((content, id), (index, 1));
+        match self.asset_contents.get_mut(&id) {
+            Some(contents) => contents.push(content),
+            None => {
+                self.asset_contents.insert(id, [content].to_vec());
+            }
+        }
+    }
+
+    /// Removes a reference of asset `id` to `content`, the asset leaves the list of the content with its last reference
+    pub fn unlink(&mut self, content: Hash, id: AssetId) {
+        match self.asset_position.get_mut(&(content, id)) {
+            Some((_, references)) if *references > 1 => *references -= 1,
+            Some(_) => {
+                self.list_remove(content, id);
+                if let Some(contents) = self.asset_contents.get_mut(&id) {
+                    contents.retain(|other| *other != content);
+                }
+            }
+            None => (),
+        }
+    }
+
+    /// Removes the deleted asset `id` from the lists of all its contents
+    pub fn remove(&mut self, id: AssetId) {
+        for content in self.asset_contents.take(&id).unwrap_or_default() {
+            self.list_remove(content, id);
+        }
+    }
+
+    /// Removes asset `id` from the list of `content` moving the last asset in the freed position
+    fn list_remove(&mut self, content: Hash, id: AssetId) {
+        let (index, _) = match self.asset_position.take(&(content, id)) {
+            Some(position) => position,
+            None => return,
+        };
+        let last = self.assets_count(&content).saturating_sub(1);
+        if last == 0 {
+            self.content_assets_count.take(&content);
+        } else {
+            self.content_assets_count.insert(content, last);
+        }
+        if let Some(last_id) = self.content_assets.take(&(content, last)) {
+            if index != last {
+                self.content_assets.insert((content, index), last_id);
+                if let Some((position, _)) = self.asset_position.get_mut(&(content, last_id)) {
+                    *position = index;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn contents_work() {
+        let photo = Hash::from([0x01; 32]);
+        let report = Hash::from([0x02; 32]);
+        let mut contents = Contents::default();
+        contents.link(photo, 1);
+        contents.link(photo, 2);
+        contents.link(photo, 3);
+        // the same asset is listed once
+        contents.link(photo, 1);
+        contents.link(report, 1);
+        assert_eq!(contents.assets_of(&photo, 0, 10), [1, 2, 3]);
+        contents.unlink(photo, 1);
+        assert_eq!(contents.assets_count(&photo), 3);
+        // the last asset takes the place of the one leaving the list
+        contents.unlink(photo, 1);
+        assert_eq!(contents.assets_of(&photo, 0, 10), [3, 2]);
+        contents.unlink(photo, 1);
+        contents.remove(3);
+        assert_eq!(contents.assets_of(&photo, 0, 10), [2]);
+        contents.remove(1);
+        assert_eq!(contents.assets_count(&report), 0);
+        contents.unlink(photo, 2);
+        assert_eq!(contents.assets_count(&photo), 0);
+    }
+}
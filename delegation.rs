@@ -0,0 +1,78 @@
+//! # Delegation
+//!
+//! Proxy accounts that manage all the assets on behalf of their owners, and the owners in
+//! custodial mode whose proxies can be managed from the administrators. The proxy delegated
+//! for a single asset is stored in the record of the asset.
+//!
+
+use crate::asset_erc721::Error;
+use ink_env::AccountId;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::SpreadLayout,
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+
+/// Storage of the proxies of the owners
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Delegation {
+    /// Store the proxy accounts that can manage all the assets of the owner
+    account_proxy: StorageHashMap<(AccountId, AccountId), bool>,
+    /// Owners that allow the administrators to manage their proxies (custodial mode)
+    account_custodial: StorageHashMap<AccountId, bool>,
+}
+
+impl Delegation {
+    /// Returns true if `operator` is delegated for all the assets of `owner`
+    pub fn is_operator(&self, owner: &AccountId, operator: &AccountId) -> bool {
+        *self.account_proxy.get(&(*owner, *operator)).unwrap_or(&false)
+    }
+
+    /// Delegates or undelegates `operator` for all the assets of `owner`
+    pub fn operator_set(&mut self, owner: AccountId, operator: AccountId, approved: bool) -> Result<(), Error> {
+        if operator == owner {
+            return Err(Error::NotAllowed)
+        }
+        self.account_proxy.insert((owner, operator), approved);
+        Ok(())
+    }
+
+    /// Returns true if `owner` has enabled the custodial mode
+    pub fn is_custodial(&self, owner: &AccountId) -> bool {
+        self.account_custodial.contains_key(owner)
+    }
+
+    /// Enables or disables the custodial mode of `owner`
+    pub fn custodial_set(&mut self, owner: AccountId, enabled: bool) {
+        if enabled {
+            self.account_custodial.insert(owner, true);
+        } else {
+            self.account_custodial.take(&owner);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn operators_and_custodial_mode_work() {
+        let alice = AccountId::from([0x1; 32]);
+        let bob = AccountId::from([0x2; 32]);
+        let mut delegation = Delegation::default();
+        assert_eq!(delegation.operator_set(alice, alice, true), Err(Error::NotAllowed));
+        assert_eq!(delegation.operator_set(alice, bob, true), Ok(()));
+        assert!(delegation.is_operator(&alice, &bob));
+        assert!(!delegation.is_operator(&bob, &alice));
+        assert_eq!(delegation.operator_set(alice, bob, false), Ok(()));
+        assert!(!delegation.is_operator(&alice, &bob));
+        delegation.custodial_set(alice, true);
+        assert!(delegation.is_custodial(&alice));
+        delegation.custodial_set(alice, false);
+        assert!(!delegation.is_custodial(&alice));
+    }
+}
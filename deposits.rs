@@ -0,0 +1,137 @@
+//! # Deposits
+//!
+//! Storage deposits of the assets, for the chains charging the contract a deposit for the
+//! storage it occupies.
+//!
+//! The accounts adding data to an asset top up its deposit in native tokens, so the storage of
+//! the asset is not paid from the budget of the contract. The deposits are accounted per asset
+//! and per account; when the asset is deleted and its storage cleaned up, the deposits are
+//! released and each account reclaims its own. The asset id can be reused in the meantime: the
+//! deposits of the new asset are accounted apart from the released ones.
+//!
+//! The deposit locked from the minter to create an asset, kept in its provenance, is released
+//! to the minter with the storage deposits once the asset is deleted.
+//!
+
+use crate::asset_erc721::{
+    AssetId,
+    Error,
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::collections::HashMap as StorageHashMap;
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use ink_storage::traits::SpreadLayout;
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+
+/// Storage of the deposits of the assets
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Deposits {
+    /// Deposit of the account on the asset
+    deposits: StorageHashMap<(AssetId, AccountId), Balance>,
+    /// Accounts with a deposit on the asset
+    asset_depositors: StorageHashMap<AssetId, Vec<AccountId>>,
+    /// Deposit of the account on the deleted asset, to reclaim
+    released: StorageHashMap<(AssetId, AccountId), Balance>,
+}
+
+impl Deposits {
+    /// Returns the deposit of `account` on asset `id`
+    pub fn deposit_of(&self, id: AssetId, account: AccountId) -> Balance {
+        *self.deposits.get(&(id, account)).unwrap_or(&0)
+    }
+
+    /// Returns the total deposit on asset `id`
+    pub fn total_of(&self, id: AssetId) -> Balance {
+        self.asset_depositors
+            .get(&id)
+            .map(|depositors| depositors.iter().map(|account| self.deposit_of(id, *account)).sum())
+            .unwrap_or(0)
+    }
+
+    /// Returns the deposit of `account` on the deleted asset `id` that can be reclaimed
+    pub fn reclaimable_of(&self, id: AssetId, account: AccountId) -> Balance {
+        *self.released.get(&(id, account)).unwrap_or(&0)
+    }
+
+    /// Adds `amount` to the deposit of `account` on asset `id`, and returns the deposit
+    pub fn top_up(&mut self, id: AssetId, account: AccountId, amount: Balance) -> Balance {
+        let deposit = self.deposit_of(id, account);
+        if deposit == 0 {
+            let mut depositors = self.asset_depositors.get(&id).cloned().unwrap_or_default();
+            depositors.push(account);
+            self.asset_depositors.insert(id, depositors);
+        }
+        let deposit = deposit.saturating_add(amount);
+        self.deposits.insert((id, account), deposit);
+        deposit
+    }
+
+    /// Releases the deposits of the deleted asset `id` to their accounts
+    pub fn release(&mut self, id: AssetId) {
+        for account in self.asset_depositors.take(&id).unwrap_or_default() {
+            let deposit = self.deposits.take(&(id, account)).unwrap_or(0);
+            let released = self.reclaimable_of(id, account).saturating_add(deposit);
+            self.released.insert((id, account), released);
+        }
+    }
+
+    /// Releases the deposit `amount` locked from `account` to create the deleted asset `id`
+    pub fn unlock(&mut self, id: AssetId, account: AccountId, amount: Balance) {
+        if amount > 0 {
+            let released = self.reclaimable_of(id, account).saturating_add(amount);
+            self.released.insert((id, account), released);
+        }
+    }
+
+    /// Removes the released deposit of `account` on asset `id`, and returns it
+    pub fn reclaim(&mut self, id: AssetId, account: AccountId) -> Result<Balance, Error> {
+        self.released.take(&(id, account)).ok_or(Error::DepositNotFound)
+    }
+
+    /// Restores the released deposit of `account` on asset `id` when it cannot be paid
+    pub fn unreclaim(&mut self, id: AssetId, account: AccountId, amount: Balance) {
+        self.released.insert((id, account), amount);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn release_works() {
+        let alice = AccountId::from([0x1; 32]);
+        let bob = AccountId::from([0x2; 32]);
+        let mut deposits = Deposits::default();
+        assert_eq!(deposits.top_up(1, alice, 10), 10);
+        assert_eq!(deposits.top_up(1, alice, 5), 15);
+        assert_eq!(deposits.top_up(1, bob, 7), 7);
+        assert_eq!(deposits.total_of(1), 22);
+        assert_eq!(deposits.reclaim(1, alice), Err(Error::DepositNotFound));
+        deposits.release(1);
+        assert_eq!(deposits.total_of(1), 0);
+        // the deposits of a new asset with the same id are accounted apart
+        assert_eq!(deposits.top_up(1, alice, 3), 3);
+        assert_eq!(deposits.reclaimable_of(1, alice), 15);
+        assert_eq!(deposits.reclaim(1, alice), Ok(15));
+        assert_eq!(deposits.reclaim(1, alice), Err(Error::DepositNotFound));
+        assert_eq!(deposits.reclaim(1, bob), Ok(7));
+        assert_eq!(deposits.deposit_of(1, alice), 3);
+        // the mint deposit is released with the storage deposits
+        deposits.unlock(2, bob, 0);
+        assert_eq!(deposits.reclaim(2, bob), Err(Error::DepositNotFound));
+        assert_eq!(deposits.top_up(2, bob, 5), 5);
+        deposits.release(2);
+        deposits.unlock(2, bob, 100);
+        assert_eq!(deposits.reclaim(2, bob), Ok(105));
+    }
+}
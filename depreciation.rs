@@ -0,0 +1,157 @@
+//! # Depreciation
+//!
+//! Depreciation schedules of the assets, so the asset-finance integrations read the book value
+//! of an asset from the registry instead of computing it off-chain.
+//!
+//! A schedule stores the acquisition cost, the salvage value, the yearly rate and the start of
+//! the depreciation as a block timestamp. The book value is computed at each query from the
+//! timestamp of the current block and never falls below the salvage value.
+//!
+
+use crate::asset_erc721::AssetId;
+use ink_env::{
+    DefaultEnvironment,
+    Environment,
+};
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+type Timestamp = <DefaultEnvironment as Environment>::Timestamp;
+
+/// Milliseconds of a year of 365 days
+pub const YEAR: Timestamp = 365 * 24 * 60 * 60 * 1000;
+
+/// Basis points of a rate of 100%
+pub const RATE_MAX: u16 = 10_000;
+
+/// Depreciation method
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub enum Method {
+    /// The rate of the cost is deducted every year
+    StraightLine,
+    /// The rate of the book value at the beginning of the year is deducted every year
+    DecliningBalance,
+}
+
+/// Depreciation schedule of an asset
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Schedule {
+    /// Depreciation method
+    pub method: Method,
+    /// Acquisition cost
+    pub cost: Balance,
+    /// Residual value at the end of the depreciation
+    pub salvage: Balance,
+    /// Yearly rate in basis points
+    pub rate: u16,
+    /// Timestamp of the start of the depreciation, in milliseconds
+    pub start: Timestamp,
+}
+
+impl Schedule {
+    /// Returns true if the salvage value does not exceed the cost and the rate is at most 100%
+    pub fn is_valid(&self) -> bool {
+        self.salvage <= self.cost && self.rate <= RATE_MAX
+    }
+
+    /// Returns the book value at timestamp `now`
+    pub fn book_value(&self, now: Timestamp) -> Balance {
+        let elapsed = now.saturating_sub(self.start);
+        let rate = self.rate as Balance;
+        let scale = RATE_MAX as Balance * YEAR as Balance;
+        let value = match self.method {
+            Method::StraightLine => {
+                let depreciation = self.cost.saturating_mul(rate).saturating_mul(elapsed as Balance) / scale;
+                self.cost.saturating_sub(depreciation)
+            }
+            Method::DecliningBalance => {
+                let mut value = self.cost;
+                let mut years = elapsed / YEAR;
+                while years > 0 && value > self.salvage {
+                    value -= value.saturating_mul(rate) / RATE_MAX as Balance;
+                    years -= 1;
+                }
+                // the part of the current year is depreciated pro rata
+                let part = (elapsed % YEAR) as Balance;
+                value - value.saturating_mul(rate).saturating_mul(part) / scale
+            }
+        };
+        value.max(self.salvage)
+    }
+}
+
+/// Storage of the depreciation schedules of the assets
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Depreciation {
+    /// Depreciation schedule of the asset
+    asset_schedule: StorageHashMap<AssetId, Schedule>,
+}
+
+impl Depreciation {
+    /// Returns the depreciation schedule of asset `id`
+    pub fn schedule_of(&self, id: AssetId) -> Option<Schedule> {
+        self.asset_schedule.get(&id).cloned()
+    }
+
+    /// Stores the depreciation schedule of asset `id`, replacing the previous one
+    pub fn set(&mut self, id: AssetId, schedule: Schedule) {
+        self.asset_schedule.insert(id, schedule);
+    }
+
+    /// Removes the depreciation schedule of asset `id` and returns it
+    pub fn remove(&mut self, id: AssetId) -> Option<Schedule> {
+        self.asset_schedule.take(&id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn straight_line_works() {
+        let schedule = Schedule {
+            method: Method::StraightLine,
+            cost: 10_000,
+            salvage: 1_000,
+            rate: 2_000,
+            start: YEAR,
+        };
+        assert_eq!(schedule.book_value(0), 10_000);
+        assert_eq!(schedule.book_value(2 * YEAR), 8_000);
+        assert_eq!(schedule.book_value(2 * YEAR + YEAR / 2), 7_000);
+        assert_eq!(schedule.book_value(10 * YEAR), 1_000);
+        assert!(!Schedule { salvage: 10_001, ..schedule }.is_valid());
+        assert!(!Schedule { rate: 10_001, ..schedule }.is_valid());
+    }
+
+    #[test]
+    fn declining_balance_works() {
+        let schedule = Schedule {
+            method: Method::DecliningBalance,
+            cost: 10_000,
+            salvage: 1_000,
+            rate: 5_000,
+            start: 0,
+        };
+        assert_eq!(schedule.book_value(YEAR), 5_000);
+        assert_eq!(schedule.book_value(2 * YEAR), 2_500);
+        assert_eq!(schedule.book_value(2 * YEAR + YEAR / 2), 1_875);
+        assert_eq!(schedule.book_value(100 * YEAR), 1_000);
+    }
+}
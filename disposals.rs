@@ -0,0 +1,116 @@
+//! # Disposals
+//!
+//! End-of-life disposals of the assets.
+//!
+//! The disposal is the terminal state of an asset, distinct from its deletion: the owner records
+//! the code of the disposal method, as the recycling or the destruction of an electronic device,
+//! with the hash of the evidence, as the certificate of the treatment facility. The asset and
+//! its disposal are retained permanently with the identity of the disposer, the asset can no
+//! longer be transferred, sold or deleted.
+//!
+
+use crate::asset_erc721::{
+    AssetId,
+    Error,
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Disposal of an asset
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Disposal {
+    /// Code of the disposal method, defined from the clients
+    pub method: u32,
+    /// Hash of the evidence of the disposal
+    pub evidence: Hash,
+    /// Account that disposed of the asset
+    pub disposer: AccountId,
+    /// Block of the disposal
+    pub block: BlockNumber,
+}
+
+/// Storage of the disposals
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Disposals {
+    /// Disposal of the asset
+    asset_disposal: StorageHashMap<AssetId, Disposal>,
+    /// Assets disposed of, indexed from 0 to the disposals counter in the order of disposal
+    disposed: StorageHashMap<u32, AssetId>,
+    /// Counter of the disposals
+    disposals_count: u32,
+}
+
+impl Disposals {
+    /// Returns the disposal of asset `id`
+    pub fn disposal_of(&self, id: AssetId) -> Option<Disposal> {
+        self.asset_disposal.get(&id).cloned()
+    }
+
+    /// Returns the number of assets disposed of
+    pub fn disposals_count(&self) -> u32 {
+        self.disposals_count
+    }
+
+    /// Returns at most `limit` assets disposed of from position `cursor`, oldest first
+    pub fn list(&self, cursor: u32, limit: u32) -> Vec<AssetId> {
+        (cursor..self.disposals_count.min(cursor.saturating_add(limit)))
+            .filter_map(|index| self.disposed.get(&index).cloned())
+            .collect()
+    }
+
+    /// Records `disposal` of asset `id`, an asset is disposed of once
+    pub fn dispose(&mut self, id: AssetId, disposal: Disposal) -> Result<(), Error> {
+        if self.asset_disposal.contains_key(&id) {
+            return Err(Error::AssetDisposed)
+        }
+        self.asset_disposal.insert(id, disposal);
+        self.disposed.insert(self.disposals_count, id);
+        self.disposals_count += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn dispose_works() {
+        let disposal = Disposal {
+            method: 1,
+            evidence: Hash::from([0x01; 32]),
+            disposer: AccountId::from([0x1; 32]),
+            block: 1,
+        };
+        let mut disposals = Disposals::default();
+        assert_eq!(disposals.dispose(2, disposal), Ok(()));
+        assert_eq!(disposals.dispose(1, disposal), Ok(()));
+        assert_eq!(disposals.dispose(1, Disposal { method: 2, ..disposal }), Err(Error::AssetDisposed));
+        assert_eq!(disposals.disposal_of(1), Some(disposal));
+        assert_eq!(disposals.disposals_count(), 2);
+        assert_eq!(disposals.list(1, 10), [1]);
+    }
+}
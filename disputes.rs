@@ -0,0 +1,251 @@
+//! # Disputes
+//!
+//! Disputes opened from the parties harmed by the work of a staked account, resolved from the
+//! administrators acting as arbitrators.
+//!
+//! A dispute names its subject, the claimant and the respondent answerable for the subject.
+//! When the arbitrator upholds it the stake of the respondent is slashed to the claimant: the
+//! shares configured from the administrators for a fraud or for a breach of a shipper. Each subject has at most one dispute open at a time, and the respondent cannot
+//! withdraw its stake while a dispute against it is open.
+//!
+
+use crate::{
+    asset_erc721::{
+        AssetId,
+        Error,
+    },
+    certificates::CertificateId,
+    shipments::ManifestId,
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+    Lazy,
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Dispute ID
+pub type DisputeId = u32;
+
+/// Work contested from a dispute
+#[derive(Encode, Decode, Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub enum DisputeSubject {
+    /// Fraudulent validation of the asset
+    Validation(AssetId),
+    /// Fraudulent compliance certificate
+    Certificate(CertificateId),
+    /// Breach of the shipper of the shipment
+    Shipment(ManifestId, Breach),
+}
+
+/// Breach of a shipper
+#[derive(Encode, Decode, Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub enum Breach {
+    /// The goods have been lost
+    Loss,
+    /// The goods have been damaged
+    Damage,
+    /// Gross violation of the agreed service level, like the delivery time
+    ServiceLevel,
+}
+
+/// Shares of the stake of a shipper forfeited for each breach, in basis points
+#[derive(Encode, Decode, Debug, Default, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Penalties {
+    /// Share forfeited for the loss of the goods
+    pub loss: u16,
+    /// Share forfeited for the damage of the goods
+    pub damage: u16,
+    /// Share forfeited for a gross violation of the service level
+    pub service_level: u16,
+}
+
+impl Penalties {
+    /// Basis points of a whole stake
+    pub const MAX: u16 = 10_000;
+
+    /// Returns true if every share is at most the whole stake
+    pub fn is_valid(&self) -> bool {
+        self.loss <= Self::MAX && self.damage <= Self::MAX && self.service_level <= Self::MAX
+    }
+
+    /// Returns the amount forfeited from `stake` for `breach`
+    pub fn penalty(&self, breach: Breach, stake: Balance) -> Balance {
+        let share = match breach {
+            Breach::Loss => self.loss,
+            Breach::Damage => self.damage,
+            Breach::ServiceLevel => self.service_level,
+        };
+        stake.saturating_mul(share as Balance) / Self::MAX as Balance
+    }
+}
+
+/// Outcome of a dispute
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub enum DisputeStatus {
+    /// Waiting for the arbitrator
+    Open,
+    /// Upheld, the amount has been slashed from the respondent to the claimant
+    Upheld(Balance),
+    /// Rejected from the arbitrator
+    Rejected,
+}
+
+/// Dispute between a claimant and the account answerable for the subject
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Dispute {
+    /// Work contested
+    pub subject: DisputeSubject,
+    /// Account that opened the dispute, paid when it is upheld
+    pub claimant: AccountId,
+    /// Account answerable with its stake
+    pub respondent: AccountId,
+    /// Hash of the evidence of the claimant
+    pub evidence: Hash,
+    /// Block of the opening
+    pub opened: BlockNumber,
+    /// Outcome of the dispute
+    pub status: DisputeStatus,
+}
+
+/// Storage of the disputes
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Disputes {
+    /// Disputes indexed from 0 to the disputes counter
+    disputes: StorageHashMap<DisputeId, Dispute>,
+    /// Counter of the disputes
+    disputes_count: u32,
+    /// Dispute open on the subject
+    subject_open: StorageHashMap<DisputeSubject, DisputeId>,
+    /// Number of the disputes open against the respondent, loaded when used
+    respondent_open: Lazy<StorageHashMap<AccountId, u32>>,
+}
+
+impl Disputes {
+    /// Returns dispute `dispute_id`
+    pub fn get(&self, dispute_id: DisputeId) -> Option<Dispute> {
+        self.disputes.get(&dispute_id).cloned()
+    }
+
+    /// Returns the dispute open on `subject`
+    pub fn open_on(&self, subject: &DisputeSubject) -> Option<DisputeId> {
+        self.subject_open.get(subject).cloned()
+    }
+
+    /// Returns the number of the disputes open against `respondent`
+    pub fn open_against(&self, respondent: &AccountId) -> u32 {
+        *self.respondent_open.get(respondent).unwrap_or(&0)
+    }
+
+    /// Opens `dispute`, and returns its id
+    pub fn open(&mut self, dispute: Dispute) -> Result<DisputeId, Error> {
+        if self.subject_open.contains_key(&dispute.subject) {
+            return Err(Error::DuplicatedData)
+        }
+        let dispute_id = self.disputes_count;
+        self.subject_open.insert(dispute.subject, dispute_id);
+        let open = self.open_against(&dispute.respondent);
+        self.respondent_open.insert(dispute.respondent, open + 1);
+        self.disputes.insert(dispute_id, dispute);
+        self.disputes_count += 1;
+        Ok(dispute_id)
+    }
+
+    /// Reopens the dispute `dispute_id` closed when its outcome cannot be paid
+    pub fn reopen(&mut self, dispute_id: DisputeId) {
+        if let Some(mut dispute) = self.get(dispute_id) {
+            dispute.status = DisputeStatus::Open;
+            self.disputes.insert(dispute_id, dispute);
+            self.subject_open.insert(dispute.subject, dispute_id);
+            let open = self.open_against(&dispute.respondent);
+            self.respondent_open.insert(dispute.respondent, open + 1);
+        }
+    }
+
+    /// Closes the open dispute `dispute_id` with `status`, and returns it
+    pub fn close(&mut self, dispute_id: DisputeId, status: DisputeStatus) -> Result<Dispute, Error> {
+        let mut dispute = self.get(dispute_id).ok_or(Error::DisputeNotFound)?;
+        if dispute.status != DisputeStatus::Open {
+            return Err(Error::DisputeNotFound)
+        }
+        dispute.status = status;
+        self.disputes.insert(dispute_id, dispute);
+        self.subject_open.take(&dispute.subject);
+        match self.open_against(&dispute.respondent) {
+            0 | 1 => self.respondent_open.take(&dispute.respondent),
+            open => self.respondent_open.insert(dispute.respondent, open - 1),
+        };
+        Ok(dispute)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn disputes_work() {
+        let dispute = Dispute {
+            subject: DisputeSubject::Validation(1),
+            claimant: AccountId::from([0x1; 32]),
+            respondent: AccountId::from([0x2; 32]),
+            evidence: Hash::from([0x01; 32]),
+            opened: 1,
+            status: DisputeStatus::Open,
+        };
+        let mut disputes = Disputes::default();
+        assert_eq!(disputes.open(dispute), Ok(0));
+        assert_eq!(disputes.open(dispute), Err(Error::DuplicatedData));
+        assert_eq!(disputes.open_on(&DisputeSubject::Validation(1)), Some(0));
+        assert_eq!(disputes.open_against(&dispute.respondent), 1);
+        assert_eq!(disputes.close(0, DisputeStatus::Rejected).map(|dispute| dispute.status), Ok(DisputeStatus::Rejected));
+        assert_eq!(disputes.open_against(&dispute.respondent), 0);
+        assert_eq!(disputes.close(0, DisputeStatus::Rejected), Err(Error::DisputeNotFound));
+        assert_eq!(disputes.open_on(&DisputeSubject::Validation(1)), None);
+        // a dispute reopened is open again
+        disputes.reopen(0);
+        assert_eq!(disputes.open_on(&DisputeSubject::Validation(1)), Some(0));
+        assert_eq!(disputes.open_against(&dispute.respondent), 1);
+        assert_eq!(disputes.close(0, DisputeStatus::Rejected).map(|dispute| dispute.status), Ok(DisputeStatus::Rejected));
+        // a new dispute can be opened once the previous one is closed
+        assert_eq!(disputes.open(dispute), Ok(1));
+    }
+
+    #[ink::test]
+    fn penalties_work() {
+        let penalties = Penalties {
+            loss: 10_000,
+            damage: 2_500,
+            service_level: 0,
+        };
+        assert!(penalties.is_valid());
+        assert_eq!(penalties.penalty(Breach::Loss, 400), 400);
+        assert_eq!(penalties.penalty(Breach::Damage, 400), 100);
+        assert_eq!(penalties.penalty(Breach::ServiceLevel, 400), 0);
+        assert!(!Penalties { damage: 10_001, ..penalties }.is_valid());
+    }
+}
@@ -0,0 +1,171 @@
+//! # Erasure
+//!
+//! Erasure of the personal accounts from the historical records of the contract, for the
+//! data-protection requests of the final buyers.
+//!
+//! An account that does not own assets anymore requests its erasure. An administrator commits
+//! the Blake2x256 hash of a secret salt to the request, and once the retention period has
+//! elapsed replaces the account with its pseudonym, the Blake2x256 hash of the account and of
+//! the salt revealed. The erasure runs from the administrator, so the account never sends the
+//! salt, and the pseudonym is neither emitted nor stored with the request. The commitment fixes
+//! the pseudonym before the erasure starts, so every batch uses the same one.
+//!
+//! The journal of the transfers is processed in batches. For each transfer of the account the
+//! transfer, the sales of the asset, the messages of their threads, the route points and the
+//! notes of the asset, and the activity feed of the counterpart are rewritten, so the chain of
+//! custody of the assets stays complete. The activity feed and the tax jurisdiction of the
+//! account are cleared at the end. Some records are kept on purpose:
+//!
+//! - the creations of the assets: the minter is the producer of record and reclaims the deposit
+//!   of the asset;
+//! - the disputes and their threads: they are the records of the arbitrations, retained for the
+//!   legal claims, and their parties are staked professionals;
+//! - the tax trail: it names the seller jurisdiction and the tax collectors, not the buyers;
+//! - the stores of the standing rights and obligations of the account, like its roles, stakes,
+//!   delegations and licenses, which the account gives up before requesting the erasure.
+//!
+//! The events already emitted and the state history of the chain are outside the storage of
+//! the contract and are not erased: the erasure removes the account from the records served
+//! by the contract, not from the archives of the chain.
+//!
+
+use crate::asset_erc721::Error;
+use ink_env::{
+    hash::Blake2x256,
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Erasure request of an account
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct ErasureRequest {
+    /// Block of the request
+    pub block: BlockNumber,
+    /// Blake2x256 hash of the salt of the pseudonym, committed from an administrator
+    pub commitment: Option<Hash>,
+    /// Position of the next transfer of the journal to process
+    pub cursor: u32,
+}
+
+/// Storage of the erasure requests
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Erasure {
+    /// Pending erasure request of the account
+    account_request: StorageHashMap<AccountId, ErasureRequest>,
+    /// Counter of the accounts erased
+    erased_count: u32,
+}
+
+impl Erasure {
+    /// Returns the pseudonym of `account` with `salt`
+    pub fn pseudonym_of(account: &AccountId, salt: Hash) -> AccountId {
+        let mut output = [0x0; 32];
+        ink_env::hash_encoded::<Blake2x256, _>(&(account, salt), &mut output);
+        AccountId::from(output)
+    }
+
+    /// Returns the commitment of `salt`
+    pub fn commitment_of(salt: Hash) -> Hash {
+        let mut output = [0x0; 32];
+        ink_env::hash_encoded::<Blake2x256, _>(&salt, &mut output);
+        Hash::from(output)
+    }
+
+    /// Returns the pending erasure request of `account`
+    pub fn request_of(&self, account: &AccountId) -> Option<ErasureRequest> {
+        self.account_request.get(account).cloned()
+    }
+
+    /// Records the erasure request of `account` at `block`
+    pub fn request(&mut self, account: AccountId, block: BlockNumber) -> Result<(), Error> {
+        if self.account_request.contains_key(&account) {
+            return Err(Error::DuplicatedData)
+        }
+        self.account_request.insert(account, ErasureRequest {
+            block,
+            commitment: None,
+            cursor: 0,
+        });
+        Ok(())
+    }
+
+    /// Commits `commitment` of the salt of the pseudonym to the erasure request of `account`
+    pub fn commit(&mut self, account: &AccountId, commitment: Hash) -> Result<(), Error> {
+        let request = self.account_request.get_mut(account).ok_or(Error::ErasureNotFound)?;
+        if request.commitment.is_some() {
+            return Err(Error::DuplicatedData)
+        }
+        request.commitment = Some(commitment);
+        Ok(())
+    }
+
+    /// Cancels the erasure request of `account`, the batches already processed are not restored
+    pub fn cancel(&mut self, account: &AccountId) -> Result<ErasureRequest, Error> {
+        self.account_request.take(account).ok_or(Error::ErasureNotFound)
+    }
+
+    /// Stores the progress of the erasure request of `account`
+    pub fn update(&mut self, account: AccountId, request: ErasureRequest) {
+        self.account_request.insert(account, request);
+    }
+
+    /// Removes the completed erasure request of `account`
+    pub fn complete(&mut self, account: &AccountId) {
+        if self.account_request.take(account).is_some() {
+            self.erased_count += 1;
+        }
+    }
+
+    /// Returns the number of accounts erased
+    pub fn erased_count(&self) -> u32 {
+        self.erased_count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn request_works() {
+        let alice = AccountId::from([0x1; 32]);
+        let salt = Hash::from([0x01; 32]);
+        let pseudonym = Erasure::pseudonym_of(&alice, salt);
+        assert_ne!(pseudonym, alice);
+        assert_eq!(Erasure::pseudonym_of(&alice, salt), pseudonym);
+        assert_ne!(Erasure::pseudonym_of(&alice, Hash::from([0x02; 32])), pseudonym);
+        let mut erasure = Erasure::default();
+        assert_eq!(erasure.request(alice, 5), Ok(()));
+        assert_eq!(erasure.request(alice, 6), Err(Error::DuplicatedData));
+        assert_eq!(erasure.request_of(&alice).map(|request| request.block), Some(5));
+        // the commitment is set once
+        let commitment = Erasure::commitment_of(salt);
+        assert_eq!(erasure.commit(&alice, commitment), Ok(()));
+        assert_eq!(erasure.commit(&alice, commitment), Err(Error::DuplicatedData));
+        assert_eq!(erasure.request_of(&alice).and_then(|request| request.commitment), Some(commitment));
+        assert_eq!(erasure.commit(&pseudonym, commitment), Err(Error::ErasureNotFound));
+        erasure.complete(&alice);
+        assert_eq!(erasure.erased_count(), 1);
+        assert_eq!(erasure.cancel(&alice), Err(Error::ErasureNotFound));
+    }
+}
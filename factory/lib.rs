@@ -0,0 +1,205 @@
+//! # Asset ERC721 Factory
+//!
+//! Companion contract that instantiates the copies of the Asset ERC721 contract, one for each
+//! supply chain, and keeps their registry.
+//!
+//! ## Overview
+//!
+//! The asset contract is uploaded once and the factory stores the hash of its code. Each
+//! product line gets its own instance with isolated assets, roles and categories; the factory
+//! records the name, the creator and the block of every instance so the clients can list them
+//! from one deployment.
+//!
+//! The instances are configured after their creation with the configuration messages of the
+//! asset contract, from its administrators.
+//!
+//! The selectors of the messages are fixed in their attributes, so a refactor does not change
+//! them.
+//!
+//! ## Error Handling
+//!
+//! Any function that modifies the state returns a Result type and does not changes the state
+//! if the Error occurs.
+//!
+
+#![cfg_attr(not(feature = "std"), no_std)]
+use ink_lang as ink;
+
+#[ink::contract]
+mod factory {
+    use ink_lang::ToAccountId;
+    use ink_prelude::vec::Vec;
+    use ink_storage::{
+        collections::HashMap as StorageHashMap,
+        traits::{
+            PackedLayout,
+            SpreadLayout,
+        },
+    };
+    #[cfg(feature = "std")]
+    use ink_storage::traits::StorageLayout;
+    use scale::{
+        Decode,
+        Encode,
+    };
+    use wivsupplychain::AssetErc721;
+
+    /// Instance ID
+    pub type InstanceId = u32;
+
+    /// Instance of the asset contract for a supply chain
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+    pub struct Instance {
+        /// Account of the instance
+        pub contract: AccountId,
+        /// Blake2x256 hash of the name of the supply chain
+        pub name: Hash,
+        /// Account that requested the instance
+        pub creator: AccountId,
+        /// Block of the instantiation
+        pub block: BlockNumber,
+    }
+
+    #[ink(storage)]
+    pub struct Factory {
+        /// Account that deployed the factory, the only one that can create instances
+        owner: AccountId,
+        /// Hash of the code of the asset contract
+        code_hash: Hash,
+        /// Instances indexed from 0 to the instances counter
+        instances: StorageHashMap<InstanceId, Instance>,
+        /// Counter of the instances
+        instances_count: u32,
+        /// Instance of the supply chain, by name
+        instance_by_name: StorageHashMap<Hash, InstanceId>,
+    }
+
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        NotOwner,
+        DuplicatedName,
+        InstantiationFailed,
+    }
+
+    /// Event emitted when an instance of the asset contract is created.
+    #[ink(event)]
+    pub struct InstanceCreated {
+        #[ink(topic)]
+        instance_id: InstanceId,
+        #[ink(topic)]
+        contract: AccountId,
+        name: Hash,
+    }
+
+    impl Factory {
+        /// Creates a new factory of the asset contract with the hash of its uploaded code.
+        #[ink(constructor)]
+        pub fn new(code_hash: Hash) -> Self {
+            Self {
+                owner: Self::env().caller(),
+                code_hash,
+                instances: Default::default(),
+                instances_count: 0,
+                instance_by_name: Default::default(),
+            }
+        }
+        /// Instantiates a new copy of the asset contract for a supply chain, with the hash of its name, only
+        /// the owner of the factory can do it. The value transferred is the endowment of the instance.
+        #[ink(message, payable, selector = "0x1CB93C27")]
+        pub fn instance_new(&mut self, name: Hash) -> Result<AccountId, Error> {
+            let caller = self.env().caller();
+            if caller != self.owner {
+                return Err(Error::NotOwner)
+            }
+            if self.instance_by_name.contains_key(&name) {
+                return Err(Error::DuplicatedName)
+            }
+            let instance = AssetErc721::new()
+                .endowment(self.env().transferred_balance())
+                .code_hash(self.code_hash)
+                .instantiate()
+                .map_err(|_| Error::InstantiationFailed)?;
+            let contract = instance.to_account_id();
+            self.register(contract, name, caller);
+            Ok(contract)
+        }
+        /// Sets the hash of the code of the asset contract used for the new instances, only the owner of the factory
+        /// can do it. The instances already created are not changed.
+        #[ink(message, selector = "0x15DA89B4")]
+        pub fn code_hash_set(&mut self, code_hash: Hash) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner)
+            }
+            self.code_hash = code_hash;
+            Ok(())
+        }
+        /// Returns the hash of the code of the asset contract used for the new instances
+        #[ink(message, selector = "0x2E1CF75B")]
+        pub fn code_hash_get(&self) -> Hash {
+            self.code_hash
+        }
+        /// Returns the instances of the asset contract, oldest first
+        #[ink(message, selector = "0xA51AAEC5")]
+        pub fn instances_list(&self) -> Vec<Instance> {
+            (0..self.instances_count)
+                .filter_map(|instance_id| self.instances.get(&instance_id).cloned())
+                .collect()
+        }
+        /// Returns an instance of the asset contract
+        #[ink(message, selector = "0x418885C8")]
+        pub fn instance_get(&self, instance_id: InstanceId) -> Option<Instance> {
+            self.instances.get(&instance_id).cloned()
+        }
+        /// Returns the instance of the asset contract of a supply chain, by the hash of its name
+        #[ink(message, selector = "0x6D25DB01")]
+        pub fn instance_by_name(&self, name: Hash) -> Option<Instance> {
+            self.instance_by_name
+                .get(&name)
+                .and_then(|instance_id| self.instances.get(instance_id))
+                .cloned()
+        }
+
+        /// Records the instance `contract` in the registry.
+        fn register(&mut self, contract: AccountId, name: Hash, creator: AccountId) -> InstanceId {
+            let instance_id = self.instances_count;
+            self.instances.insert(instance_id, Instance {
+                contract,
+                name,
+                creator,
+                block: self.env().block_number(),
+            });
+            self.instance_by_name.insert(name, instance_id);
+            self.instances_count += 1;
+            self.env().emit_event(InstanceCreated {
+                instance_id,
+                contract,
+                name,
+            });
+            instance_id
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink_lang as ink;
+
+        #[ink::test]
+        fn registry_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut factory = Factory::new(Hash::from([0x01; 32]));
+            assert!(factory.instances_list().is_empty());
+            assert_eq!(factory.register(accounts.bob, Hash::from([0x0a; 32]), accounts.alice), 0);
+            assert_eq!(factory.register(accounts.eve, Hash::from([0x0b; 32]), accounts.alice), 1);
+            assert_eq!(factory.instances_list().len(), 2);
+            assert_eq!(factory.instance_by_name(Hash::from([0x0b; 32])).map(|instance| instance.contract), Some(accounts.eve));
+            assert_eq!(factory.instance_new(Hash::from([0x0a; 32])), Err(Error::DuplicatedName));
+            assert_eq!(factory.code_hash_set(Hash::from([0x02; 32])), Ok(()));
+            assert_eq!(factory.code_hash_get(), Hash::from([0x02; 32]));
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! # Legal holds
+//!
+//! Legal holds placed on the assets from the administrators, acting on a court order.
+//!
+//! A hold stores the hash of the court order and blocks the transfer, the sale, the split and
+//! the deletion of the asset until it is lifted. It is independent from the locks of the bridge
+//! and from the liens of the creditors: lifting a hold does not release them.
+//!
+
+use crate::asset_erc721::{
+    AssetId,
+    Error,
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Legal hold of an asset
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Hold {
+    /// Hash of the court order
+    pub order: Hash,
+    /// Administrator that placed the hold
+    pub placed_by: AccountId,
+    /// Block of the hold
+    pub block: BlockNumber,
+}
+
+/// Storage of the legal holds of the assets
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Holds {
+    /// Legal hold of the asset
+    asset_hold: StorageHashMap<AssetId, Hold>,
+}
+
+impl Holds {
+    /// Returns the legal hold of asset `id`
+    pub fn hold_of(&self, id: AssetId) -> Option<Hold> {
+        self.asset_hold.get(&id).cloned()
+    }
+
+    /// Returns true if asset `id` is on legal hold
+    pub fn is_held(&self, id: AssetId) -> bool {
+        self.asset_hold.contains_key(&id)
+    }
+
+    /// Places `hold` on asset `id`, an asset is held once
+    pub fn place(&mut self, id: AssetId, hold: Hold) -> Result<(), Error> {
+        if self.is_held(id) {
+            return Err(Error::AssetOnHold)
+        }
+        self.asset_hold.insert(id, hold);
+        Ok(())
+    }
+
+    /// Lifts the legal hold of asset `id` and returns it
+    pub fn lift(&mut self, id: AssetId) -> Result<Hold, Error> {
+        self.asset_hold.take(&id).ok_or(Error::HoldNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn place_works() {
+        let hold = Hold {
+            order: Hash::from([0x01; 32]),
+            placed_by: AccountId::from([0x1; 32]),
+            block: 1,
+        };
+        let mut holds = Holds::default();
+        assert_eq!(holds.lift(1), Err(Error::HoldNotFound));
+        assert_eq!(holds.place(1, hold), Ok(()));
+        assert_eq!(holds.place(1, hold), Err(Error::AssetOnHold));
+        assert!(holds.is_held(1));
+        assert_eq!(holds.lift(1), Ok(hold));
+        assert!(!holds.is_held(1));
+        assert_eq!(holds.hold_of(1), None);
+    }
+}
@@ -0,0 +1,206 @@
+//! # Inspectors
+//!
+//! Accreditations of the accounts with the Inspector role, validating the assets on behalf of
+//! the administrators.
+//!
+//! An administrator accredits an inspector on a list of categories: the inspector validates and
+//! invalidates only the assets of those categories, and the validations keep the inspector as
+//! their signer, so each one answers for its own work. The accreditation records the
+//! administrator that issued it and is replaced as a whole when the scope changes.
+//!
+//! An accreditation can expire at a block: from then on the inspector cannot validate until an
+//! administrator renews it. The inspectors of each category are indexed, so the accredited
+//! inspectors of a category are listed with no need to scan every accreditation.
+//!
+
+use crate::asset_erc721::Error;
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Accreditation of an inspector
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Accreditation {
+    /// Categories of the assets the inspector can validate
+    pub categories: Vec<u32>,
+    /// Administrator that issued the accreditation
+    pub issuer: AccountId,
+    /// Block of the accreditation
+    pub block: BlockNumber,
+    /// Block the accreditation expires at, `None` if it does not expire
+    pub expiry: Option<BlockNumber>,
+}
+
+impl Accreditation {
+    /// Returns true if the accreditation is in force at `block`
+    pub fn is_active(&self, block: BlockNumber) -> bool {
+        self.expiry.is_none_or(|expiry| block < expiry)
+    }
+}
+
+/// Storage of the accreditations of the inspectors
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Inspectors {
+    /// Accreditation of the inspector
+    accreditations: StorageHashMap<AccountId, Accreditation>,
+    /// Inspectors accredited on the category, lapsed or not
+    category_inspectors: StorageHashMap<u32, Vec<AccountId>>,
+}
+
+impl Inspectors {
+    /// Returns the accreditation of `inspector`
+    pub fn accreditation_of(&self, inspector: &AccountId) -> Option<Accreditation> {
+        self.accreditations.get(inspector).cloned()
+    }
+
+    /// Returns the inspectors with an accreditation on `category` in force at `block`
+    pub fn inspectors_of(&self, category: u32, block: BlockNumber) -> Vec<AccountId> {
+        self.category_inspectors
+            .get(&category)
+            .map(|inspectors| {
+                inspectors
+                    .iter()
+                    .filter(|inspector| {
+                        self.accreditations
+                            .get(inspector)
+                            .is_some_and(|accreditation| accreditation.is_active(block))
+                    })
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Checks that `inspector` is accredited on `category` at `block`, an asset with no category is out of any
+    /// scope
+    pub fn check(&self, inspector: &AccountId, category: Option<u32>, block: BlockNumber) -> Result<(), Error> {
+        let accreditation = self
+            .accreditations
+            .get(inspector)
+            .filter(|accreditation| category.is_some_and(|category| accreditation.categories.contains(&category)))
+            .ok_or(Error::NotAccredited)?;
+        if !accreditation.is_active(block) {
+            return Err(Error::AccreditationLapsed)
+        }
+        Ok(())
+    }
+
+    /// Accredits `inspector` with `accreditation`, replacing the previous one. Fails if the scope is empty or the
+    /// accreditation is already expired.
+    pub fn accredit(&mut self, inspector: AccountId, mut accreditation: Accreditation) -> Result<(), Error> {
+        accreditation.categories.sort_unstable();
+        accreditation.categories.dedup();
+        if accreditation.categories.is_empty() || !accreditation.is_active(accreditation.block) {
+            return Err(Error::NotAllowed)
+        }
+        self.unindex(&inspector);
+        for category in accreditation.categories.iter() {
+            let mut inspectors = self.category_inspectors.get(category).cloned().unwrap_or_default();
+            inspectors.push(inspector);
+            self.category_inspectors.insert(*category, inspectors);
+        }
+        self.accreditations.insert(inspector, accreditation);
+        Ok(())
+    }
+
+    /// Revokes the accreditation of `inspector`, and returns it
+    pub fn revoke(&mut self, inspector: &AccountId) -> Result<Accreditation, Error> {
+        self.unindex(inspector);
+        self.accreditations.take(inspector).ok_or(Error::NotAccredited)
+    }
+
+    /// Removes `inspector` from the index of the categories of its accreditation
+    fn unindex(&mut self, inspector: &AccountId) {
+        let categories = self
+            .accreditations
+            .get(inspector)
+            .map(|accreditation| accreditation.categories.clone())
+            .unwrap_or_default();
+        for category in categories {
+            let mut inspectors = self.category_inspectors.get(&category).cloned().unwrap_or_default();
+            inspectors.retain(|other| other != inspector);
+            if inspectors.is_empty() {
+                self.category_inspectors.take(&category);
+            } else {
+                self.category_inspectors.insert(category, inspectors);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn accreditations_work() {
+        let alice = AccountId::from([0x1; 32]);
+        let bob = AccountId::from([0x2; 32]);
+        let mut inspectors = Inspectors::default();
+        let accreditation = Accreditation {
+            categories: ink_prelude::vec![3, 1, 3],
+            issuer: alice,
+            block: 1,
+            expiry: None,
+        };
+        assert_eq!(inspectors.check(&bob, Some(1), 1), Err(Error::NotAccredited));
+        assert_eq!(inspectors.accredit(bob, Accreditation { categories: Vec::new(), ..accreditation.clone() }), Err(Error::NotAllowed));
+        assert_eq!(inspectors.accredit(bob, accreditation), Ok(()));
+        assert_eq!(inspectors.accreditation_of(&bob).map(|accreditation| accreditation.categories), Some(ink_prelude::vec![1, 3]));
+        assert_eq!(inspectors.check(&bob, Some(3), 1), Ok(()));
+        // the scope covers only the listed categories
+        assert_eq!(inspectors.check(&bob, Some(2), 1), Err(Error::NotAccredited));
+        assert_eq!(inspectors.check(&bob, None, 1), Err(Error::NotAccredited));
+        assert_eq!(inspectors.revoke(&bob).map(|accreditation| accreditation.issuer), Ok(alice));
+        assert_eq!(inspectors.revoke(&bob), Err(Error::NotAccredited));
+        assert_eq!(inspectors.check(&bob, Some(3), 1), Err(Error::NotAccredited));
+        assert!(inspectors.inspectors_of(3, 1).is_empty());
+    }
+
+    #[ink::test]
+    fn expiry_works() {
+        let alice = AccountId::from([0x1; 32]);
+        let bob = AccountId::from([0x2; 32]);
+        let charlie = AccountId::from([0x3; 32]);
+        let mut inspectors = Inspectors::default();
+        let accreditation = Accreditation {
+            categories: ink_prelude::vec![1, 2],
+            issuer: alice,
+            block: 5,
+            expiry: Some(10),
+        };
+        assert_eq!(inspectors.accredit(bob, Accreditation { expiry: Some(5), ..accreditation.clone() }), Err(Error::NotAllowed));
+        assert_eq!(inspectors.accredit(bob, accreditation.clone()), Ok(()));
+        assert_eq!(inspectors.accredit(charlie, Accreditation { expiry: None, ..accreditation.clone() }), Ok(()));
+        assert_eq!(inspectors.inspectors_of(2, 9), [bob, charlie]);
+        // a lapsed accreditation is not in force, nor listed
+        assert_eq!(inspectors.check(&bob, Some(1), 9), Ok(()));
+        assert_eq!(inspectors.check(&bob, Some(1), 10), Err(Error::AccreditationLapsed));
+        assert_eq!(inspectors.inspectors_of(2, 10), [charlie]);
+        // the renewal replaces the scope of the accreditation
+        assert_eq!(inspectors.accredit(bob, Accreditation { categories: ink_prelude::vec![2], block: 10, expiry: Some(20), ..accreditation }), Ok(()));
+        assert_eq!(inspectors.inspectors_of(1, 10), [charlie]);
+        assert_eq!(inspectors.inspectors_of(2, 10), [charlie, bob]);
+    }
+}
@@ -0,0 +1,168 @@
+//! # Insurance
+//!
+//! Parametric cargo insurance of the shipments.
+//!
+//! An insurer attaches a policy to a shipment before its delivery, escrowing the coverage in
+//! the contract with the temperature tolerance of the goods. The compliance certificate of the
+//! shipment triggers the policy: when the temperature range recorded exceeds the tolerance the
+//! coverage is paid to the beneficiary, otherwise it is released to the insurer. The coverage
+//! of a cancelled shipment is released to the insurer as well.
+//!
+
+use crate::{
+    asset_erc721::Error,
+    certificates::Certificate,
+    shipments::ManifestId,
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+};
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// State of an insurance policy
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub enum PolicyStatus {
+    /// The coverage is escrowed in the contract
+    Active,
+    /// The coverage has been paid to the beneficiary
+    PaidOut,
+    /// The coverage has been released to the insurer
+    Released,
+}
+
+/// Insurance policy of a shipment
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Policy {
+    /// Account that escrowed the coverage
+    pub insurer: AccountId,
+    /// Account paid on a breach of the tolerance
+    pub beneficiary: AccountId,
+    /// Amount paid on a breach of the tolerance
+    pub coverage: Balance,
+    /// PSP22 token of the coverage, `None` for the native token
+    pub token: Option<AccountId>,
+    /// Minimum temperature tolerated, in tenths of degree Celsius
+    pub min_temperature: i16,
+    /// Maximum temperature tolerated, in tenths of degree Celsius
+    pub max_temperature: i16,
+    /// Block of the attachment
+    pub block: BlockNumber,
+    /// State of the policy
+    pub status: PolicyStatus,
+}
+
+impl Policy {
+    /// Returns true if the temperature range of `certificate` exceeds the tolerance
+    pub fn is_breached(&self, certificate: &Certificate) -> bool {
+        certificate.min_temperature < self.min_temperature || certificate.max_temperature > self.max_temperature
+    }
+}
+
+/// Storage of the insurance policies of the shipments
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Insurance {
+    /// Policy of the shipment
+    manifest_policy: StorageHashMap<ManifestId, Policy>,
+}
+
+impl Insurance {
+    /// Returns the policy of the shipment `manifest_id`
+    pub fn policy_of(&self, manifest_id: ManifestId) -> Option<Policy> {
+        self.manifest_policy.get(&manifest_id).cloned()
+    }
+
+    /// Attaches `policy` to the shipment `manifest_id`, a shipment is insured once
+    pub fn attach(&mut self, manifest_id: ManifestId, policy: Policy) -> Result<(), Error> {
+        if self.manifest_policy.contains_key(&manifest_id) {
+            return Err(Error::DuplicatedData)
+        }
+        self.manifest_policy.insert(manifest_id, policy);
+        Ok(())
+    }
+
+    /// Removes the policy of the shipment `manifest_id` when its coverage cannot be escrowed
+    pub fn detach(&mut self, manifest_id: ManifestId) {
+        self.manifest_policy.take(&manifest_id);
+    }
+
+    /// Settles the active policy of the shipment `manifest_id` with `status`, and returns it
+    pub fn settle(&mut self, manifest_id: ManifestId, status: PolicyStatus) -> Result<Policy, Error> {
+        let policy = self.manifest_policy.get_mut(&manifest_id).ok_or(Error::PolicyNotFound)?;
+        if policy.status != PolicyStatus::Active {
+            return Err(Error::PolicyNotFound)
+        }
+        policy.status = status;
+        Ok(*policy)
+    }
+
+    /// Sets the policy of the shipment `manifest_id` as active again when its coverage cannot be paid
+    pub fn unsettle(&mut self, manifest_id: ManifestId) {
+        if let Some(policy) = self.manifest_policy.get_mut(&manifest_id) {
+            policy.status = PolicyStatus::Active;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_env::Hash;
+    use ink_lang as ink;
+
+    fn certificate(min_temperature: i16, max_temperature: i16) -> Certificate {
+        Certificate {
+            manifest_id: 0,
+            issuer: AccountId::from([0x1; 32]),
+            sensor_log: Hash::from([0x01; 32]),
+            min_temperature,
+            max_temperature,
+            compliant: true,
+            block: 1,
+        }
+    }
+
+    #[ink::test]
+    fn settle_works() {
+        let policy = Policy {
+            insurer: AccountId::from([0x2; 32]),
+            beneficiary: AccountId::from([0x3; 32]),
+            coverage: 500,
+            token: None,
+            min_temperature: 20,
+            max_temperature: 80,
+            block: 1,
+            status: PolicyStatus::Active,
+        };
+        assert!(!policy.is_breached(&certificate(20, 80)));
+        assert!(policy.is_breached(&certificate(10, 50)));
+        assert!(policy.is_breached(&certificate(30, 81)));
+        let mut insurance = Insurance::default();
+        assert_eq!(insurance.settle(0, PolicyStatus::PaidOut), Err(Error::PolicyNotFound));
+        assert_eq!(insurance.attach(0, policy), Ok(()));
+        assert_eq!(insurance.attach(0, policy), Err(Error::DuplicatedData));
+        assert_eq!(insurance.settle(0, PolicyStatus::PaidOut).map(|policy| policy.status), Ok(PolicyStatus::PaidOut));
+        assert_eq!(insurance.settle(0, PolicyStatus::Released), Err(Error::PolicyNotFound));
+        insurance.unsettle(0);
+        assert_eq!(insurance.policy_of(0), Some(policy));
+    }
+}
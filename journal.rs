@@ -0,0 +1,232 @@
+//! # Journal
+//!
+//! Journal of the transfers of the assets, including their creation and deletion.
+//!
+//! The transfers are appended in the order they happen, so the journal is sorted by block
+//! and the position of the first transfer since a block can be found with a binary search.
+//! Each transfer points to the previous transfer of the same asset: the custody chain of an
+//! asset is followed back from its last transfer with no need to scan the journal, so the
+//! history of an asset is read from the journal with no copy kept for each asset.
+//!
+
+use crate::asset_erc721::AssetId;
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+type Timestamp = <DefaultEnvironment as Environment>::Timestamp;
+
+/// Transfer of an asset, `from` is the zero account when the asset is created and `to` is
+/// the zero account when the asset is deleted
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct TransferRecord {
+    pub id: AssetId,
+    pub from: AccountId,
+    pub to: AccountId,
+    pub block: BlockNumber,
+    /// Timestamp of the block of the transfer, in milliseconds
+    pub timestamp: Timestamp,
+    /// Position of the previous transfer of the asset, None for its creation
+    pub previous: Option<u32>,
+}
+
+/// Storage of the journal of the transfers
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Journal {
+    /// Transfers indexed from 0 to the transfers counter, in the order they happened
+    transfers: StorageHashMap<u32, TransferRecord>,
+    /// Counter of the transfers recorded
+    transfers_count: u32,
+}
+
+impl Journal {
+    /// Appends a transfer to the journal
+    pub fn record(&mut self, record: TransferRecord) {
+        self.transfers.insert(self.transfers_count, record);
+        self.transfers_count += 1;
+    }
+
+    /// Returns the transfer at position `position`
+    pub fn get(&self, position: u32) -> Option<TransferRecord> {
+        self.transfers.get(&position).cloned()
+    }
+
+    /// Returns the number of transfers recorded
+    pub fn len(&self) -> u32 {
+        self.transfers_count
+    }
+
+    /// Returns true if no transfer has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.transfers_count == 0
+    }
+
+    /// Returns at most `limit` transfers starting from position `offset`, in the order they happened
+    pub fn entries(&self, offset: u32, limit: u32) -> Vec<TransferRecord> {
+        (offset..self.transfers_count)
+            .take(limit as usize)
+            .filter_map(|index| self.transfers.get(&index).cloned())
+            .collect()
+    }
+
+    /// Returns at most `limit` transfers of asset `id` following its chain back from position `from` included, newest
+    /// first, and the position of the next transfer of the chain, `None` at its end
+    pub fn chain(&self, id: AssetId, from: Option<u32>, limit: u32) -> (Vec<TransferRecord>, Option<u32>) {
+        let mut transfers = Vec::new();
+        if limit == 0 {
+            return (transfers, None)
+        }
+        let mut position = from;
+        while let Some(index) = position {
+            let record = match self.get(index).filter(|record| record.id == id) {
+                Some(record) => record,
+                None => break,
+            };
+            if transfers.len() as u32 == limit {
+                return (transfers, Some(index))
+            }
+            transfers.push(record);
+            position = record.previous;
+        }
+        (transfers, None)
+    }
+
+    /// Replaces `account` with `pseudonym` in at most `limit` transfers from position `offset`. Returns the
+    /// transfers rewritten, as they were before, and the position of the next transfer to process.
+    pub fn pseudonymize(&mut self, account: &AccountId, pseudonym: AccountId, offset: u32, limit: u32) -> (Vec<TransferRecord>, u32) {
+        let end = core::cmp::min(offset.saturating_add(limit), self.transfers_count);
+        let mut rewritten = Vec::new();
+        for index in offset..end {
+            if let Some(record) = self.transfers.get_mut(&index) {
+                if record.from != *account && record.to != *account {
+                    continue
+                }
+                rewritten.push(*record);
+                if record.from == *account {
+                    record.from = pseudonym;
+                }
+                if record.to == *account {
+                    record.to = pseudonym;
+                }
+            }
+        }
+        (rewritten, end)
+    }
+
+    /// Returns the position of the first transfer done from block `block` included
+    pub fn position_since(&self, block: BlockNumber) -> u32 {
+        let (mut low, mut high) = (0, self.transfers_count);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            match self.transfers.get(&middle) {
+                Some(record) if record.block < block => low = middle + 1,
+                _ => high = middle,
+            }
+        }
+        low
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    fn record(id: AssetId, block: BlockNumber) -> TransferRecord {
+        TransferRecord {
+            id,
+            from: AccountId::from([0x0; 32]),
+            to: AccountId::from([0x1; 32]),
+            block,
+            timestamp: 0,
+            previous: None,
+        }
+    }
+
+    #[ink::test]
+    fn since_works() {
+        let mut journal = Journal::default();
+        assert!(journal.is_empty());
+        assert_eq!(journal.entries(journal.position_since(0), 10), []);
+        for (id, block) in [(1, 0), (2, 3), (3, 3), (4, 7)].iter() {
+            journal.record(record(*id, *block));
+        }
+        assert_eq!(journal.len(), 4);
+        assert_eq!(journal.get(3).map(|record| record.id), Some(4));
+        assert_eq!(journal.get(4), None);
+        let ids = |block, limit| -> Vec<AssetId> {
+            journal.entries(journal.position_since(block), limit).iter().map(|r| r.id).collect()
+        };
+        assert_eq!(ids(0, 10), [1, 2, 3, 4]);
+        assert_eq!(ids(1, 10), [2, 3, 4]);
+        assert_eq!(ids(3, 1), [2]);
+        assert_eq!(ids(4, 10), [4]);
+        assert_eq!(ids(8, 10), []);
+    }
+
+    #[ink::test]
+    fn pseudonymize_works() {
+        let alice = AccountId::from([0x1; 32]);
+        let pseudonym = AccountId::from([0x9; 32]);
+        let mut journal = Journal::default();
+        for id in 1..4 {
+            journal.record(record(id, 0));
+        }
+        journal.record(TransferRecord {
+            from: alice,
+            to: AccountId::from([0x2; 32]),
+            ..record(1, 1)
+        });
+        let (rewritten, cursor) = journal.pseudonymize(&alice, pseudonym, 0, 2);
+        assert_eq!((rewritten.len(), cursor), (2, 2));
+        let (rewritten, cursor) = journal.pseudonymize(&alice, pseudonym, cursor, 10);
+        assert_eq!((rewritten.len(), cursor), (2, 4));
+        assert!(journal.entries(0, 10).iter().all(|r| r.from != alice && r.to != alice));
+        assert_eq!(journal.entries(3, 10)[0].from, pseudonym);
+    }
+
+    #[ink::test]
+    fn chain_works() {
+        let mut journal = Journal::default();
+        journal.record(record(1, 0));
+        journal.record(record(2, 0));
+        journal.record(TransferRecord {
+            previous: Some(0),
+            ..record(1, 1)
+        });
+        journal.record(TransferRecord {
+            previous: Some(2),
+            ..record(1, 2)
+        });
+        let blocks = |from, limit| {
+            let (records, next) = journal.chain(1, Some(from), limit);
+            (records.iter().map(|r| r.block).collect::<Vec<_>>(), next)
+        };
+        // the chain is followed back from the last transfer, skipping the transfers of the other assets
+        assert_eq!(blocks(3, 10), ([2, 1, 0].to_vec(), None));
+        assert_eq!(blocks(3, 2), ([2, 1].to_vec(), Some(0)));
+        assert_eq!(blocks(0, 2), ([0].to_vec(), None));
+        assert_eq!(blocks(3, 0), (Vec::new(), None));
+        assert_eq!(journal.chain(2, Some(3), 10), (Vec::new(), None));
+        assert_eq!(journal.chain(1, None, 10), (Vec::new(), None));
+    }
+}
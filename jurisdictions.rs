@@ -0,0 +1,154 @@
+//! # Jurisdictions
+//!
+//! Registry of the jurisdictions the contract applies region-specific rules for.
+//!
+//! The administrators register each jurisdiction with the hash of the document of its rules and
+//! the account of its customs collector. The registry is the single source of the jurisdictions
+//! referenced from the rest of the contract: the tax rates and the jurisdictions of the sellers,
+//! the customs duties of the cross-border shipments and the compliance flags of the categories.
+//!
+//! The compliance flags of a category in a jurisdiction are a bitmask: the contract enforces
+//! [`SALE_RESTRICTED`], the other bits are left to the rules of the jurisdiction and read from
+//! the clients.
+//!
+
+use crate::asset_erc721::Error;
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Jurisdiction, as its ISO 3166-1 alpha-2 code e.g. `*b"IT"`
+pub type Jurisdiction = [u8; 2];
+
+/// Compliance flags of a category in a jurisdiction
+pub type ComplianceFlags = u32;
+
+/// The assets of the category cannot be listed from the sellers of the jurisdiction
+pub const SALE_RESTRICTED: ComplianceFlags = 1;
+
+/// Entry of the registry of the jurisdictions
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct JurisdictionEntry {
+    /// Hash of the document of the rules of the jurisdiction
+    pub rules: Hash,
+    /// Customs collector of the jurisdiction
+    pub customs: Option<AccountId>,
+    /// Block of the last update
+    pub block: BlockNumber,
+}
+
+/// Storage of the registry of the jurisdictions
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Jurisdictions {
+    /// Entry of the jurisdiction
+    entries: StorageHashMap<Jurisdiction, JurisdictionEntry>,
+    /// Codes of the jurisdictions, indexed from 0 to the jurisdictions counter in the order of registration
+    codes: StorageHashMap<u32, Jurisdiction>,
+    /// Counter of the jurisdictions
+    codes_count: u32,
+    /// Compliance flags of the category in the jurisdiction
+    category_flags: StorageHashMap<(u32, Jurisdiction), ComplianceFlags>,
+}
+
+impl Jurisdictions {
+    /// Returns the entry of `jurisdiction`
+    pub fn get(&self, jurisdiction: Jurisdiction) -> Option<JurisdictionEntry> {
+        self.entries.get(&jurisdiction).cloned()
+    }
+
+    /// Fails if `jurisdiction` is not registered
+    pub fn check(&self, jurisdiction: Jurisdiction) -> Result<(), Error> {
+        if !self.entries.contains_key(&jurisdiction) {
+            return Err(Error::JurisdictionNotFound)
+        }
+        Ok(())
+    }
+
+    /// Returns the number of jurisdictions registered
+    pub fn jurisdictions_count(&self) -> u32 {
+        self.codes_count
+    }
+
+    /// Returns at most `limit` codes of the jurisdictions from position `cursor`, in the order of registration
+    pub fn list(&self, cursor: u32, limit: u32) -> Vec<Jurisdiction> {
+        (cursor..self.codes_count.min(cursor.saturating_add(limit)))
+            .filter_map(|index| self.codes.get(&index).cloned())
+            .collect()
+    }
+
+    /// Registers `jurisdiction` with `entry`, or updates its entry
+    pub fn set(&mut self, jurisdiction: Jurisdiction, entry: JurisdictionEntry) {
+        if self.entries.insert(jurisdiction, entry).is_none() {
+            self.codes.insert(self.codes_count, jurisdiction);
+            self.codes_count += 1;
+        }
+    }
+
+    /// Returns the compliance flags of `category` in `jurisdiction`
+    pub fn flags_of(&self, category: u32, jurisdiction: Jurisdiction) -> ComplianceFlags {
+        *self.category_flags.get(&(category, jurisdiction)).unwrap_or(&0)
+    }
+
+    /// Sets the compliance flags of `category` in the registered `jurisdiction`
+    pub fn flags_set(&mut self, category: u32, jurisdiction: Jurisdiction, flags: ComplianceFlags) -> Result<(), Error> {
+        self.check(jurisdiction)?;
+        if flags == 0 {
+            self.category_flags.take(&(category, jurisdiction));
+        } else {
+            self.category_flags.insert((category, jurisdiction), flags);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn jurisdictions_work() {
+        let entry = JurisdictionEntry {
+            rules: Hash::from([0x01; 32]),
+            customs: None,
+            block: 1,
+        };
+        let mut jurisdictions = Jurisdictions::default();
+        assert_eq!(jurisdictions.check(*b"IT"), Err(Error::JurisdictionNotFound));
+        assert_eq!(jurisdictions.flags_set(1, *b"IT", SALE_RESTRICTED), Err(Error::JurisdictionNotFound));
+        jurisdictions.set(*b"IT", entry);
+        jurisdictions.set(*b"FR", entry);
+        // an update keeps the position of the jurisdiction
+        jurisdictions.set(*b"IT", JurisdictionEntry { rules: Hash::from([0x02; 32]), ..entry });
+        assert_eq!(jurisdictions.check(*b"IT"), Ok(()));
+        assert_eq!(jurisdictions.get(*b"IT").map(|entry| entry.rules), Some(Hash::from([0x02; 32])));
+        assert_eq!(jurisdictions.jurisdictions_count(), 2);
+        assert_eq!(jurisdictions.list(0, 10), [*b"IT", *b"FR"]);
+        assert_eq!(jurisdictions.flags_set(1, *b"IT", SALE_RESTRICTED | 4), Ok(()));
+        assert_eq!(jurisdictions.flags_of(1, *b"IT"), 5);
+        assert_eq!(jurisdictions.flags_of(1, *b"FR"), 0);
+        assert_eq!(jurisdictions.flags_set(1, *b"IT", 0), Ok(()));
+        assert_eq!(jurisdictions.flags_of(1, *b"IT"), 0);
+    }
+}
@@ -0,0 +1,193 @@
+//! # Laboratories
+//!
+//! Test results attached to the assets from the accounts with the Laboratory role.
+//!
+//! The owner authorizes a laboratory on an asset, then the laboratory can attach the results
+//! of its tests. A category can require a passing result of a test type before its assets are
+//! validated.
+//!
+
+use crate::asset_erc721::{
+    AssetId,
+    Error,
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Type of test, the codes are agreed between the parties of the platform
+pub type TestType = u32;
+
+/// Result of a laboratory test
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct LabResult {
+    /// Laboratory that ran the test
+    pub laboratory: AccountId,
+    /// Type of the test
+    pub test_type: TestType,
+    /// The asset passed the test
+    pub passed: bool,
+    /// Hash of the report of the test
+    pub report: Hash,
+    /// Id of the sample tested
+    pub sample_id: Hash,
+    /// Block of the record
+    pub block: BlockNumber,
+}
+
+/// Storage of the test results of the assets
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Laboratories {
+    /// Laboratories authorized on the asset
+    asset_laboratories: StorageHashMap<AssetId, Vec<AccountId>>,
+    /// Counter of the results of each asset
+    asset_results_count: StorageHashMap<AssetId, u32>,
+    /// List of the results of each asset, indexed from 0 to the asset results counter
+    asset_results: StorageHashMap<(AssetId, u32), LabResult>,
+    /// Test type that must be passed before validating the assets of the category
+    category_requirement: StorageHashMap<u32, TestType>,
+}
+
+impl Laboratories {
+    /// Returns true if `laboratory` is authorized on asset `id`
+    pub fn is_authorized(&self, id: AssetId, laboratory: &AccountId) -> bool {
+        self.asset_laboratories
+            .get(&id)
+            .is_some_and(|laboratories| laboratories.contains(laboratory))
+    }
+
+    /// Returns the laboratories authorized on asset `id`
+    pub fn laboratories_of(&self, id: AssetId) -> Vec<AccountId> {
+        self.asset_laboratories.get(&id).cloned().unwrap_or_default()
+    }
+
+    /// Authorizes or revokes `laboratory` on asset `id`
+    pub fn authorize(&mut self, id: AssetId, laboratory: AccountId, authorized: bool) {
+        let mut laboratories = self.laboratories_of(id);
+        laboratories.retain(|other| *other != laboratory);
+        if authorized {
+            laboratories.push(laboratory);
+        }
+        if laboratories.is_empty() {
+            self.asset_laboratories.take(&id);
+        } else {
+            self.asset_laboratories.insert(id, laboratories);
+        }
+    }
+
+    /// Returns the results of asset `id`, oldest first
+    pub fn results_of(&self, id: AssetId) -> Vec<LabResult> {
+        let count = *self.asset_results_count.get(&id).unwrap_or(&0);
+        (0..count)
+            .filter_map(|index| self.asset_results.get(&(id, index)).cloned())
+            .collect()
+    }
+
+    /// Attaches `result` to asset `id`, the laboratory must be authorized on the asset
+    pub fn record(&mut self, id: AssetId, result: LabResult) -> Result<(), Error> {
+        if !self.is_authorized(id, &result.laboratory) {
+            return Err(Error::NotAllowed)
+        }
+        let index = *self.asset_results_count.get(&id).unwrap_or(&0);
+        self.asset_results.insert((id, index), result);
+        self.asset_results_count.insert(id, index + 1);
+        Ok(())
+    }
+
+    /// Returns true if asset `id` passed a test of type `test_type`
+    pub fn has_passed(&self, id: AssetId, test_type: TestType) -> bool {
+        self.results_of(id)
+            .iter()
+            .any(|result| result.test_type == test_type && result.passed)
+    }
+
+    /// Returns the test type required to validate the assets of category `category_id`
+    pub fn requirement_of(&self, category_id: u32) -> Option<TestType> {
+        self.category_requirement.get(&category_id).cloned()
+    }
+
+    /// Sets or removes the test type required to validate the assets of category `category_id`
+    pub fn requirement_set(&mut self, category_id: u32, test_type: Option<TestType>) {
+        match test_type {
+            Some(test_type) => self.category_requirement.insert(category_id, test_type),
+            None => self.category_requirement.take(&category_id),
+        };
+    }
+
+    /// Removes the authorizations and the results of the deleted asset `id`
+    pub fn remove(&mut self, id: AssetId) {
+        self.asset_laboratories.take(&id);
+        let count = self.asset_results_count.take(&id).unwrap_or(0);
+        for index in 0..count {
+            self.asset_results.take(&(id, index));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    fn result(test_type: TestType, passed: bool) -> LabResult {
+        LabResult {
+            laboratory: AccountId::from([0x1; 32]),
+            test_type,
+            passed,
+            report: Hash::from([0x01; 32]),
+            sample_id: Hash::from([0x02; 32]),
+            block: 1,
+        }
+    }
+
+    #[ink::test]
+    fn record_works() {
+        let laboratory = AccountId::from([0x1; 32]);
+        let mut laboratories = Laboratories::default();
+        assert_eq!(laboratories.record(1, result(7, true)), Err(Error::NotAllowed));
+        laboratories.authorize(1, laboratory, true);
+        laboratories.authorize(1, laboratory, true);
+        assert_eq!(laboratories.laboratories_of(1), [laboratory]);
+        assert_eq!(laboratories.record(1, result(7, false)), Ok(()));
+        assert!(!laboratories.has_passed(1, 7));
+        assert_eq!(laboratories.record(1, result(7, true)), Ok(()));
+        assert!(laboratories.has_passed(1, 7));
+        assert!(!laboratories.has_passed(1, 8));
+        assert_eq!(laboratories.results_of(1), [result(7, false), result(7, true)]);
+        laboratories.authorize(1, laboratory, false);
+        assert_eq!(laboratories.record(1, result(8, true)), Err(Error::NotAllowed));
+        laboratories.remove(1);
+        assert!(laboratories.results_of(1).is_empty());
+    }
+
+    #[ink::test]
+    fn requirement_works() {
+        let mut laboratories = Laboratories::default();
+        assert_eq!(laboratories.requirement_of(3), None);
+        laboratories.requirement_set(3, Some(7));
+        assert_eq!(laboratories.requirement_of(3), Some(7));
+        laboratories.requirement_set(3, None);
+        assert_eq!(laboratories.requirement_of(3), None);
+    }
+}
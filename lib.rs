@@ -6,6 +6,75 @@
 //!
 //! This contract manage a supply chain of assets
 //!
+//! ## Modules
+//!
+//! The storage of the contract is composed of independent modules, each one owning its data
+//! and the invariants on them:
+//!
+//! - [`ownership`]: records and owners of the assets and the list of the assets of each account
+//! - [`records`]: record of each asset, storing together its owner and the small data read with it
+//! - [`provenance`]: creation of the assets
+//! - [`metadata`]: descriptive data and categories of the assets
+//! - [`locations`]: append-only route of the locations recorded for each asset
+//! - [`contents`]: reverse index from the photos, metadata and documents to the assets referencing them
+//! - [`category_registry`]: calls to the registry contract of the categories shared between asset contracts
+//! - [`licenses`]: licenses of the producers for the categories regulated from the administrators
+//! - [`alerts`]: subscriptions of the accounts to the validations and the certificates of the categories
+//! - [`roles`]: roles of the accounts
+//! - [`subscriptions`]: participation fees paid from the producers to keep minting
+//! - [`delegation`]: proxies of the owners
+//! - [`acl`]: write access on the fields of single assets granted from their owners
+//! - [`stakes`]: native-token bonds of the validators, slashed on fraud
+//! - [`disputes`]: disputes against the work of the staked accounts, resolved from the administrators
+//! - [`revocations`]: revocation list of the certificates and the validations withdrawn from the administrators
+//! - [`brands`]: brands of the producers and their assets
+//! - [`products`]: product definitions of the producers, their variants and the assets of each variant
+//! - [`skus`]: registry of the SKU codes unique across the producers, mapped to their product definitions
+//! - [`authenticity`]: commit-reveal challenges proving the physical authenticity of the assets
+//! - [`tags`]: NFC/RFID tags bound to the assets
+//! - [`sealed`]: commit-reveal updates of the commercially sensitive values of the assets
+//! - [`quantity`]: quantity of the bulk assets and the lots split from them
+//! - [`physical`]: weight, dimensions and packaging of the assets
+//! - [`depreciation`]: depreciation schedules and book values of the assets
+//! - [`services`]: maintenance and service logs of the durable assets
+//! - [`notes`]: append-only notes of the participants on the assets, like the remarks of a handover
+//! - [`threads`]: threads of the messages between the parties of the sales and of the disputes
+//! - [`liens`]: pledges and liens on the assets, blocking their transfer until released from the creditors
+//! - [`holds`]: legal holds placed on the assets from the administrators on a court order
+//! - [`returns`]: restocking records of the returned assets, blocked until inspected and restocked
+//! - [`reservations`]: reservations of the assets for a buyer, blocking the transfers to other accounts
+//! - [`disposals`]: end-of-life disposals of the assets, retained permanently with their disposer
+//! - [`lineage`]: links of the recycled and the remanufactured assets to the assets they were made from
+//! - [`deposits`]: storage deposits of the assets topped up from the accounts adding data to them
+//! - [`privacy`]: encrypted private data of the assets, the grants of its key and its viewers
+//! - [`shipments`]: manifests of the shipments handed over between the parties and their freight
+//! - [`psp22`]: calls to the PSP22 token contracts accepted for the payments and to the reward token
+//! - [`recipients`]: calls to the receiver hook of the contracts the assets are transferred to
+//! - [`rewards`]: rewards in a PSP22 token paid for the verification work
+//! - [`sales`]: listings of the assets for sale and records of the sales settled through the contract
+//! - [`price_lists`]: price lists of the producers for the categories of their assets, with their history
+//! - [`treasury`]: fees collected on the sales to fund the operations of the registry
+//! - [`jurisdictions`]: registry of the jurisdictions with their rules, customs and compliance flags of the categories
+//! - [`taxes`]: tax rates of the jurisdictions and the tax trail of the sales
+//! - [`oracle`]: calls to the price oracles converting the fiat prices of the listings
+//! - [`carriers`]: carriers of the assets and chains of the logistics subcontracts
+//! - [`certificates`]: cold-chain compliance certificates of the delivered shipments
+//! - [`insurance`]: parametric insurance of the shipments paid on a breach of the temperature tolerance
+//! - [`inspectors`]: accreditations of the inspectors validating the assets of their categories
+//! - [`validation_queue`]: work list of the assets awaiting validation, requested from the owners or required from their categories
+//! - [`laboratories`]: test results attached to the assets from the laboratories
+//! - [`proofs`]: claims on the hidden data of the assets proven with zero-knowledge proofs
+//! - [`bridge`]: assets locked while mirrored as ERC-721 tokens on an EVM chain
+//! - [`erasure`]: erasure of the personal accounts from the historical records, replaced with pseudonyms
+//! - [`journal`]: journal of the transfers of the assets
+//! - [`pagination`]: common shape of the messages returning lists, read in pages from a cursor
+//! - [`pending`]: transfers pending for the grace period of their owner, cancellable until they finalize
+//! - [`activity`]: feed of the latest activities of each account
+//! - [`updates`]: bounded logs of the recent updates of each asset with the digest of the older ones
+//!
+//! The messages of the contract check the caller against the rules of [`access_control`]
+//! and compose the operations of the modules.
+//!
 //! ## Error Handling
 //!
 //! Any function that modifies the state returns a Result type and does not changes the state
@@ -13,17 +82,358 @@
 //! The errors are defined as an Enum type. Any other error or invariant violation
 //! triggers a panic and therefore rolls back the transaction.
 //!
+//! ## Selectors
+//!
+//! The selectors of the messages are fixed in their attributes, so renaming or moving a message
+//! does not change the call data hard-coded in the clients. A new message takes the first four
+//! bytes of the Blake2x256 hash of its name, the selector ink! would derive for it.
+//!
 
 #![cfg_attr(not(feature = "std"), no_std)]
 use ink_lang as ink;
 
+pub mod access_control;
+pub mod acl;
+pub mod activity;
+pub mod alerts;
+pub mod authenticity;
+pub mod brands;
+pub mod bridge;
+pub mod carriers;
+pub mod category_registry;
+pub mod certificates;
+pub mod contents;
+pub mod delegation;
+pub mod deposits;
+pub mod depreciation;
+pub mod disposals;
+pub mod disputes;
+pub mod erasure;
+pub mod holds;
+pub mod inspectors;
+pub mod insurance;
+pub mod journal;
+pub mod jurisdictions;
+pub mod laboratories;
+pub mod licenses;
+pub mod liens;
+pub mod lineage;
+pub mod locations;
+pub mod metadata;
+pub mod notes;
+pub mod oracle;
+pub mod ownership;
+pub mod pagination;
+pub mod pending;
+pub mod physical;
+pub mod price_lists;
+pub mod privacy;
+pub mod products;
+pub mod proofs;
+pub mod provenance;
+pub mod psp22;
+pub mod quantity;
+pub mod recipients;
+pub mod records;
+pub mod reservations;
+pub mod returns;
+pub mod revocations;
+pub mod rewards;
+pub mod roles;
+pub mod sales;
+pub mod sealed;
+pub mod services;
+pub mod shipments;
+pub mod skus;
+pub mod stakes;
+pub mod subscriptions;
+pub mod tags;
+pub mod taxes;
+pub mod threads;
+pub mod treasury;
+pub mod updates;
+pub mod validation_queue;
+
+pub use self::asset_erc721::AssetErc721;
 
 #[ink::contract]
 mod asset_erc721 {
     #[cfg(not(feature = "ink-as-dependency"))]
-    use ink_storage::collections::{
-        hashmap::Entry,
-        HashMap as StorageHashMap,
+    use ink_storage::{
+        collections::HashMap as StorageHashMap,
+        Lazy,
+        Pack,
+    };
+    use crate::{
+        access_control::{
+            Capabilities,
+            Context,
+            FieldGroups,
+            Message,
+            ADMINISTRATOR,
+            INSPECTOR,
+            LABORATORY,
+            PRODUCER,
+            ROLE_FLOW,
+            SHIPPER,
+        },
+        acl::Acl,
+        activity::{
+            Activity,
+            ActivityEntry,
+            ActivityKind,
+        },
+        alerts::{
+            Alert,
+            Alerts,
+        },
+        authenticity::{
+            Authenticity,
+            Reveal,
+        },
+        brands::{
+            Brand,
+            BrandId,
+            Brands,
+        },
+        bridge::{
+            Bridge,
+            BridgeLock,
+            EvmAddress,
+        },
+        carriers::{
+            Carriers,
+            Leg,
+            SubcontractPolicy,
+        },
+        category_registry,
+        contents::Contents,
+        certificates::{
+            Certificate,
+            CertificateId,
+            Certificates,
+        },
+        delegation::Delegation,
+        depreciation::{
+            Depreciation,
+            Schedule,
+        },
+        disposals::{
+            Disposal,
+            Disposals,
+        },
+        disputes::{
+            Breach,
+            Dispute,
+            DisputeId,
+            DisputeStatus,
+            DisputeSubject,
+            Disputes,
+            Penalties,
+        },
+        deposits::Deposits,
+        erasure::{
+            Erasure,
+            ErasureRequest,
+        },
+        holds::{
+            Hold,
+            Holds,
+        },
+        inspectors::{
+            Accreditation,
+            Inspectors,
+        },
+        jurisdictions::{
+            ComplianceFlags,
+            Jurisdiction,
+            JurisdictionEntry,
+            Jurisdictions,
+            SALE_RESTRICTED,
+        },
+        insurance::{
+            Insurance,
+            Policy,
+            PolicyStatus,
+        },
+        journal::{
+            Journal,
+            TransferRecord,
+        },
+        laboratories::{
+            LabResult,
+            Laboratories,
+            TestType,
+        },
+        licenses::{
+            License,
+            Licenses,
+        },
+        liens::{
+            Lien,
+            LienId,
+            LienStatus,
+            Liens,
+        },
+        lineage::Lineage,
+        locations::{
+            LocationPoint,
+            Locations,
+        },
+        metadata::{
+            Locale,
+            Metadata,
+        },
+        oracle::{
+            self,
+            Currency,
+        },
+        ownership::Ownership,
+        pagination::{
+            self,
+            Cursor,
+            Page,
+            PAGE_LIMIT,
+        },
+        physical::{
+            Physical,
+            PhysicalAttributes,
+        },
+        price_lists::{
+            Msrp,
+            PriceEntry,
+            PriceLists,
+        },
+        privacy::Privacy,
+        products::{
+            Product,
+            ProductId,
+            Products,
+            Variant,
+            VariantId,
+        },
+        proofs::{
+            self,
+            Claim,
+            ClaimType,
+            Proofs,
+        },
+        provenance::{
+            MintRecord,
+            Provenance,
+        },
+        quantity::{
+            Quantities,
+            Quantity,
+            Unit,
+        },
+        pending::{
+            PendingTransfer,
+            MAX_GRACE_PERIOD,
+        },
+        reservations::Reservation,
+        returns::{
+            ReturnRecord,
+            Returns,
+            StockStatus,
+        },
+        revocations::{
+            Credential,
+            Revocation,
+            Revocations,
+        },
+        rewards::{
+            Rewards,
+            Work,
+        },
+        roles::{
+            RoleInfo,
+            Roles,
+        },
+        sales::{
+            self,
+            Charity,
+            Discount,
+            Listing,
+            Sale,
+            Sales,
+        },
+        sealed::{
+            Commitment,
+            Disclosure,
+            Sealed,
+        },
+        psp22,
+        recipients::{
+            self,
+            Recipient,
+        },
+        notes::{
+            Note,
+            Notes,
+        },
+        services::{
+            ServiceEntry,
+            ServiceType,
+            Services,
+        },
+        shipments::{
+            Duty,
+            DutyStatus,
+            Freight,
+            Manifest,
+            ManifestId,
+            ManifestItem,
+            Shipments,
+            Sla,
+            MAX_ITEMS,
+        },
+        skus::{
+            SkuEntry,
+            Skus,
+        },
+        stakes::{
+            self,
+            Bond,
+            Stakes,
+        },
+        subscriptions::{
+            Subscription,
+            Subscriptions,
+        },
+        tags::Tags,
+        taxes::{
+            TaxRate,
+            TaxRecord,
+            Taxes,
+        },
+        threads::{
+            Post,
+            Thread,
+            Threads,
+        },
+        treasury::{
+            self,
+            Treasury,
+            Withdrawal,
+            WithdrawalId,
+        },
+        updates::{
+            UpdateDigest,
+            UpdateEntry,
+            Updates,
+        },
+        validation_queue::{
+            ValidationQueue,
+            ValidationRequest,
+        },
+    };
+    use ink_prelude::vec::Vec;
+    #[cfg(feature = "std")]
+    use ink_storage::traits::StorageLayout;
+    use ink_storage::traits::{
+        PackedLayout,
+        SpreadLayout,
     };
     use scale::{
         Decode,
@@ -36,32 +446,130 @@ mod asset_erc721 {
     #[ink(storage)]
     #[derive(Default)]
     pub struct AssetErc721 {
-        /// Mapping from asset to owner.
-        asset_owner: StorageHashMap<AssetId, AccountId>,
-        /// Main description of the asset
-        asset_description: StorageHashMap<AssetId,Hash>,
-        /// Main photo of the asset - Ipfs Address
-        asset_photo: StorageHashMap<AssetId, Hash>,
-        /// Category of the asset
-        asset_category: StorageHashMap<AssetId, u32>,
-        /// Stores the id and description to the allowed categories of assets
-        asset_category_description: StorageHashMap<AssetId,Hash>,
-        /// Location of the asset
-        asset_location: StorageHashMap<AssetId,Hash>,
-        // Additional Metadata of the Asset
-        asset_metadata: StorageHashMap<AssetId, Hash>,
-        // Stores the  assets validation from an administrator role
-        asset_validation: StorageHashMap<AssetId, AccountId>,
-        /// Stores the proxy accounts for the assets, a proxy can manage the asset on behalf of the owner
-        asset_proxy: StorageHashMap<AssetId, AccountId>,
-        /// Counter of the assets owned from the accounts
-        account_owned_assets: StorageHashMap<AccountId, u32>,
-        /// Store the proxy accounts that can manage all the assets of the owner
-        account_proxy: StorageHashMap<(AccountId, AccountId), bool>,
-        /// Mapping the role of an account (0 = Producer, 1= Wholesaler, 2 = Retailer, 3 = Final Buyer, 4=Shipper, 5=Administrator)
-        account_role: StorageHashMap<AccountId, u32>,
+        /// Records and owners of the assets
+        ownership: Ownership,
+        /// Creation of the assets
+        provenance: Provenance,
+        /// Descriptive data and categories of the assets
+        metadata: Metadata,
+        /// Roles of the accounts
+        roles: Roles,
+        /// Licenses of the producers, loaded when used
+        licenses: Lazy<Licenses>,
+        /// Subscriptions to the alerts of the categories, loaded when used
+        alerts: Lazy<Alerts>,
+        /// Price lists of the producers, loaded when used
+        price_lists: Lazy<PriceLists>,
+        /// Registry of the jurisdictions, loaded when used
+        jurisdictions: Lazy<Jurisdictions>,
+        /// Tax rates and tax trail of the sales, loaded when used
+        taxes: Lazy<Taxes>,
+        /// Proxies of the owners
+        delegation: Delegation,
+        /// Brands of the producers and their assets
+        brands: Brands,
+        /// Product definitions and their variants, loaded when used
+        products: Lazy<Products>,
+        /// SKU codes of the products, loaded when used
+        skus: Lazy<Skus>,
+        /// Reverse index from the content hashes to the assets, loaded when used
+        contents: Lazy<Contents>,
+        /// Authenticity challenges of the assets
+        authenticity: Authenticity,
+        /// NFC/RFID tags bound to the assets
+        tags: Tags,
+        /// Quantity of the bulk assets
+        quantities: Quantities,
+        /// Weight, dimensions and packaging of the assets
+        physical: Physical,
+        /// Depreciation schedules of the assets
+        depreciation: Depreciation,
+        /// Service logs of the assets
+        services: Services,
+        /// Routes of the assets, loaded when used
+        locations: Lazy<Locations>,
+        /// Notes of the participants on the assets, loaded when used
+        notes: Lazy<Notes>,
+        /// Threads of the parties of the sales and of the disputes, loaded when used
+        threads: Lazy<Threads>,
+        /// Liens on the assets
+        liens: Liens,
+        /// Legal holds of the assets
+        holds: Holds,
+        /// Restocking records of the returned assets, loaded when used
+        returns: Lazy<Returns>,
+        /// Disposals of the assets, loaded when used
+        disposals: Lazy<Disposals>,
+        /// Lineage of the assets made from other assets, loaded when used
+        lineage: Lazy<Lineage>,
+        /// Storage deposits of the assets
+        deposits: Deposits,
+        /// Manifests of the shipments
+        shipments: Shipments,
+        /// Carriers of the assets
+        carriers: Carriers,
+        /// Compliance certificates of the shipments
+        certificates: Certificates,
+        /// Insurance policies of the shipments
+        insurance: Insurance,
+        /// Test results of the laboratories
+        laboratories: Laboratories,
+        /// Accreditations of the inspectors, loaded when used
+        inspectors: Lazy<Inspectors>,
+        /// Assets awaiting validation, loaded when used
+        validation_queue: Lazy<ValidationQueue>,
+        /// Write access on the fields of the assets granted from their owners
+        acl: Acl,
+        /// Encrypted private data of the assets, the grants of its key and its viewers
+        privacy: Privacy,
+        /// Verifiers of the proofs and the claims proven on the assets
+        proofs: Proofs,
+        /// Commitments and disclosures of the sealed values of the assets
+        sealed: Sealed,
+        /// Assets locked while mirrored on the EVM chain
+        bridge: Bridge,
+        /// Listings and sales of the assets
+        sales: Sales,
+        /// Subscriptions of the producers
+        subscriptions: Subscriptions,
+        /// Fees collected on the sales
+        treasury: Lazy<Treasury>,
+        /// Stakes of the validators
+        stakes: Stakes,
+        /// Disputes against the staked accounts
+        disputes: Disputes,
+        /// Revocation list of the credentials, loaded when used
+        revocations: Lazy<Revocations>,
+        /// Reward token and rewards of the verification work
+        rewards: Rewards,
+        /// Journal of the transfers of the assets
+        journal: Journal,
+        /// Erasure requests of the accounts
+        erasure: Erasure,
+        /// Recent updates of each asset
+        updates: Updates,
+        /// Feed of the latest activities of each account
+        activity: Activity,
+        /// Aggregate counters of the operations done on the contract, loaded when used
+        stats: Lazy<Pack<Stats>>,
+        /// Configuration of the contract, managed by the administrators, loaded when used
+        config: Lazy<Pack<Config>>,
+        /// Name, symbol and base URI of the collection, loaded when used
+        collection: Lazy<Pack<Collection>>,
+        /// Minting window of the accounts as (first block of the window, assets created in the window)
+        account_mint_window: StorageHashMap<AccountId, (BlockNumber, u32)>,
+        /// Log of the actions done from the administrators on behalf of other accounts
+        admin_log: StorageHashMap<u32, AdminLogEntry>,
+        /// Counter of the entries of the administrators log
+        admin_log_count: Lazy<u32>,
+        /// True when the imports of the assets from a previous deployment are closed
+        imports_closed: Lazy<bool>,
+        /// Administrators of the contract, managing the set and holding every permission, loaded when used
+        administrators: Lazy<StorageHashMap<AccountId, bool>>,
     }
 
+    /// Errors of the contract, encoded as the index of the variant. The clients map them to the
+    /// stable codes of [`Error::code`].
     #[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
     pub enum Error {
@@ -75,7 +583,438 @@ mod asset_erc721 {
         CannotFetchValue,
         NotAllowed,
         DuplicatedData,
-        CategoryNotFound
+        CategoryNotFound,
+        RateLimited,
+        CooldownActive,
+        NotProducer,
+        CategoryNotAuthorized,
+        NotCustodial,
+        BrandNotFound,
+        NotBrandOwner,
+        CommitmentNotFound,
+        InvalidSecret,
+        AlreadyRevealed,
+        TagAlreadyBound,
+        QuantityNotFound,
+        InvalidQuantity,
+        InvalidManifest,
+        ManifestNotFound,
+        InvalidPayment,
+        PaymentFailed,
+        ShipmentCancelled,
+        DutyNotFound,
+        CarrierNotAssigned,
+        SubcontractNotAllowed,
+        ShipmentNotDelivered,
+        NotLaboratory,
+        LabResultRequired,
+        RoleNotFound,
+        MissingCapability,
+        PrivateDataNotFound,
+        VerifierNotFound,
+        InvalidProof,
+        AssetBridged,
+        BridgeLockNotFound,
+        CategoryRegistryFailed,
+        ListingNotFound,
+        OracleNotFound,
+        RateUnavailable,
+        PriceExceeded,
+        SubscriptionLapsed,
+        InsufficientBalance,
+        StakeRequired,
+        StakeLocked,
+        DisputeNotFound,
+        CertificateNotFound,
+        SelfReferral,
+        DiscountNotFound,
+        DiscountExpired,
+        DiscountRedeemed,
+        PolicyNotFound,
+        InvalidUsage,
+        LienNotFound,
+        AssetEncumbered,
+        HoldNotFound,
+        AssetOnHold,
+        ErasureNotFound,
+        RetentionPending,
+        DepositNotFound,
+        WithdrawalNotFound,
+        NotAccredited,
+        AccreditationLapsed,
+        LicenseRequired,
+        LicenseNotFound,
+        LockedAfterValidation,
+        /// Sale not found
+        SaleNotFound,
+        /// Price not found in the price list
+        PriceNotFound,
+        /// Jurisdiction not registered
+        JurisdictionNotFound,
+        /// The category is restricted in the jurisdiction
+        RestrictedInJurisdiction,
+        /// The asset has been returned and is not restocked
+        AssetReturned,
+        /// The asset has been disposed of
+        AssetDisposed,
+        /// The caller is not a recycler
+        NotRecycler,
+        /// The asset has not been disposed of
+        AssetNotDisposed,
+        /// The materials of the asset have already been claimed
+        MaterialsClaimed,
+        /// The asset has been retired
+        AssetRetired,
+        /// The product does not exist
+        ProductNotFound,
+        /// The variant does not exist
+        VariantNotFound,
+        /// The SKU code is empty, too long or not printable ASCII
+        InvalidSku,
+        /// The SKU code is registered for another definition
+        SkuTaken,
+        /// The SKU code does not exist
+        SkuNotFound,
+        /// The asset is delegated to a proxy
+        AssetDelegated,
+        /// The asset has a validation not revoked
+        AssetValidated,
+        /// The asset is listed in an open shipment or assigned to a carrier
+        AssetInTransit,
+        /// The asset is listed for sale
+        AssetListed,
+        /// The destination is a contract not accepting the asset from its receiver hook
+        UnsafeRecipient,
+        /// The asset is reserved for another buyer
+        AssetReserved,
+        /// The asset has no reservation
+        ReservationNotFound,
+        /// The caller is not an auditor
+        NotAuditor,
+        /// The asset has a transfer pending for the grace period of its owner
+        TransferPending,
+        /// The asset has no pending transfer
+        PendingTransferNotFound,
+        /// The grace period of the pending transfer is not over
+        GracePeriodRunning,
+        /// The asset has no validation
+        NotValidated,
+    }
+
+    impl Error {
+        /// Returns the stable code of the error for the clients not decoding the enum. The codes are
+        /// never changed nor reused: a new error takes the next free code.
+        pub fn code(self) -> u16 {
+            match self {
+                Error::NotOwner => 1,
+                Error::NotAdministrator => 2,
+                Error::NotApproved => 3,
+                Error::AssetExists => 4,
+                Error::AssetNotFound => 5,
+                Error::CannotInsert => 6,
+                Error::CannotRemove => 7,
+                Error::CannotFetchValue => 8,
+                Error::NotAllowed => 9,
+                Error::DuplicatedData => 10,
+                Error::CategoryNotFound => 11,
+                Error::RateLimited => 12,
+                Error::CooldownActive => 13,
+                Error::NotProducer => 14,
+                Error::CategoryNotAuthorized => 15,
+                Error::NotCustodial => 16,
+                Error::BrandNotFound => 17,
+                Error::NotBrandOwner => 18,
+                Error::CommitmentNotFound => 19,
+                Error::InvalidSecret => 20,
+                Error::AlreadyRevealed => 21,
+                Error::TagAlreadyBound => 22,
+                Error::QuantityNotFound => 23,
+                Error::InvalidQuantity => 24,
+                Error::InvalidManifest => 25,
+                Error::ManifestNotFound => 26,
+                Error::InvalidPayment => 27,
+                Error::PaymentFailed => 28,
+                Error::ShipmentCancelled => 29,
+                Error::DutyNotFound => 30,
+                Error::CarrierNotAssigned => 31,
+                Error::SubcontractNotAllowed => 32,
+                Error::ShipmentNotDelivered => 33,
+                Error::NotLaboratory => 34,
+                Error::LabResultRequired => 35,
+                Error::RoleNotFound => 36,
+                Error::MissingCapability => 37,
+                Error::PrivateDataNotFound => 38,
+                Error::VerifierNotFound => 39,
+                Error::InvalidProof => 40,
+                Error::AssetBridged => 41,
+                Error::BridgeLockNotFound => 42,
+                Error::CategoryRegistryFailed => 43,
+                Error::ListingNotFound => 44,
+                Error::OracleNotFound => 45,
+                Error::RateUnavailable => 46,
+                Error::PriceExceeded => 47,
+                Error::SubscriptionLapsed => 48,
+                Error::InsufficientBalance => 49,
+                Error::StakeRequired => 50,
+                Error::StakeLocked => 51,
+                Error::DisputeNotFound => 52,
+                Error::CertificateNotFound => 53,
+                Error::SelfReferral => 54,
+                Error::DiscountNotFound => 55,
+                Error::DiscountExpired => 56,
+                Error::DiscountRedeemed => 57,
+                Error::PolicyNotFound => 58,
+                Error::InvalidUsage => 59,
+                Error::LienNotFound => 60,
+                Error::AssetEncumbered => 61,
+                Error::HoldNotFound => 62,
+                Error::AssetOnHold => 63,
+                Error::ErasureNotFound => 64,
+                Error::RetentionPending => 65,
+                Error::DepositNotFound => 66,
+                Error::WithdrawalNotFound => 67,
+                Error::NotAccredited => 68,
+                Error::AccreditationLapsed => 69,
+                Error::LicenseRequired => 70,
+                Error::LicenseNotFound => 71,
+                Error::LockedAfterValidation => 72,
+                Error::SaleNotFound => 73,
+                Error::PriceNotFound => 74,
+                Error::JurisdictionNotFound => 75,
+                Error::RestrictedInJurisdiction => 76,
+                Error::AssetReturned => 77,
+                Error::AssetDisposed => 78,
+                Error::NotRecycler => 79,
+                Error::AssetNotDisposed => 80,
+                Error::MaterialsClaimed => 81,
+                Error::AssetRetired => 82,
+                Error::ProductNotFound => 83,
+                Error::VariantNotFound => 84,
+                Error::InvalidSku => 85,
+                Error::SkuTaken => 86,
+                Error::SkuNotFound => 87,
+                Error::AssetDelegated => 88,
+                Error::AssetValidated => 89,
+                Error::AssetInTransit => 90,
+                Error::AssetListed => 91,
+                Error::UnsafeRecipient => 92,
+                Error::AssetReserved => 93,
+                Error::ReservationNotFound => 94,
+                Error::NotAuditor => 95,
+                Error::TransferPending => 96,
+                Error::PendingTransferNotFound => 97,
+                Error::GracePeriodRunning => 98,
+                Error::NotValidated => 99,
+            }
+        }
+    }
+
+    /// Kind of data changed by an `AssetUpdate` event, indexed as a topic
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+    pub enum UpdateKind {
+        Description,
+        Photo,
+        Category,
+        Location,
+        Metadata,
+        Validation,
+        Authenticity,
+        Tag,
+        Quantity,
+        Physical,
+        LabResult,
+        Private,
+        Sealed,
+        Depreciation,
+        Service,
+        Note,
+    }
+
+    /// Aggregate counters maintained across the operations on the contract
+    #[derive(Encode, Decode, Debug, Default, PartialEq, Eq, Clone, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+    pub struct Stats {
+        /// Total number of assets created
+        pub mints: u64,
+        /// Total number of assets deleted
+        pub burns: u64,
+        /// Total number of assets transferred
+        pub transfers: u64,
+        /// Total number of validations done
+        pub validations: u64,
+        /// Number of accounts for each role as (role, accounts) pairs
+        pub accounts_per_role: Vec<(u32, u32)>,
+    }
+
+    impl Stats {
+        /// Moves an account from the `old` role to the `new` role in the per-role counters
+        fn role_changed(&mut self, old: Option<u32>, new: Option<u32>) {
+            if let Some(role) = old {
+                if let Some(entry) = self.accounts_per_role.iter_mut().find(|(r, _)| *r == role) {
+                    entry.1 = entry.1.saturating_sub(1);
+                }
+            }
+            if let Some(role) = new {
+                match self.accounts_per_role.iter_mut().find(|(r, _)| *r == role) {
+                    Some(entry) => entry.1 += 1,
+                    None => self.accounts_per_role.push((role, 1)),
+                }
+            }
+        }
+    }
+
+    /// Configuration of the contract, managed by the administrators
+    #[derive(Encode, Decode, Debug, Default, PartialEq, Eq, Clone, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+    pub struct Config {
+        /// Maximum number of assets an account can create every `mint_period` blocks, 0 = no limit
+        pub mint_limit: u32,
+        /// Number of blocks of the minting rate limit window
+        pub mint_period: BlockNumber,
+        /// Minimum number of blocks between two updates of the same data of an asset, 0 = no cooldown
+        pub update_cooldown: BlockNumber,
+        /// Only accounts with the Producer or Administrator role can create assets
+        pub mint_producers_only: bool,
+        /// Registry contract of the categories shared with other asset contracts, None = local categories only
+        pub category_registry: Option<AccountId>,
+        /// Fee the producers pay for each era of minting, 0 = no subscription
+        pub subscription_fee: Balance,
+        /// Number of blocks of an era of the subscriptions
+        pub subscription_era: BlockNumber,
+        /// Minimum stake bonded to validate the assets, 0 = no stake
+        pub validator_stake: Balance,
+        /// Number of blocks before the stake unbonding can be withdrawn, at least [`stakes::UNBONDING_PERIOD_MIN`]
+        pub unbonding_period: BlockNumber,
+        /// Minimum stake bonded from the shippers to agree a freight, 0 = no stake
+        pub shipper_stake: Balance,
+        /// Shares of the stake of a shipper forfeited to the consignor for each breach
+        pub shipper_penalties: Penalties,
+        /// Share of the stake of a validator or of an issuer of certificates forfeited to the claimant for a fraud,
+        /// in basis points, 0 = no slash
+        pub fraud_penalty: u16,
+        /// Share of the proceeds of a sale paid to its referrer, in basis points, 0 = no referrals
+        pub referral_share: u16,
+        /// Number of blocks between the erasure request of an account and its erasure
+        pub erasure_retention: BlockNumber,
+        /// Number of recent updates kept in the log of each asset, 0 = digest only
+        pub update_log_size: u32,
+        /// Deposit locked from the minter to create an asset, reclaimed once the asset is deleted, 0 = no deposit
+        pub mint_deposit: Balance,
+        /// Fee of the sales paid to the treasury, in basis points, 0 = no fee
+        pub transfer_fee: u16,
+        /// Number of administrators approving a withdrawal of the treasury, at least [`treasury::THRESHOLD_MIN`]
+        pub treasury_threshold: u32,
+    }
+
+    /// Metadata of the collection of the assets, as the ERC-721 metadata extension, managed by the administrators
+    #[derive(Encode, Decode, Debug, Default, PartialEq, Eq, Clone, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+    pub struct Collection {
+        /// Name of the collection
+        pub name: Vec<u8>,
+        /// Symbol of the collection
+        pub symbol: Vec<u8>,
+        /// Base URI of the token URIs of the assets
+        pub base_uri: Vec<u8>,
+    }
+
+    /// Action done from an administrator on behalf of another account
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+    pub enum AdminAction {
+        /// Proxy of `owner` installed for all the assets (`id` = None) or a single asset
+        CustodialProxyInstalled {
+            owner: AccountId,
+            operator: AccountId,
+            id: Option<AssetId>,
+        },
+        /// Proxy of `owner` revoked for all the assets (`id` = None) or a single asset
+        CustodialProxyRevoked {
+            owner: AccountId,
+            operator: AccountId,
+            id: Option<AssetId>,
+        },
+        /// Validation from `validator` cleared to unlock the core fields of asset `id` for the `reason`
+        AssetUnlocked {
+            id: AssetId,
+            validator: AccountId,
+            reason: Hash,
+        },
+        /// Asset `id` of `owner` deleted for the `reason` clearing its delegation, validation, listing and liens
+        AssetDeletedByForce {
+            id: AssetId,
+            owner: AccountId,
+            reason: Hash,
+        },
+    }
+
+    /// Entry of the administrators log
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, SpreadLayout, PackedLayout)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+    pub struct AdminLogEntry {
+        pub admin: AccountId,
+        pub block: BlockNumber,
+        pub action: AdminAction,
+    }
+
+    /// Composite view of the data stored for an asset
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct AssetInfo {
+        pub owner: AccountId,
+        pub description: Option<Hash>,
+        pub photo: Option<Hash>,
+        pub category: Option<u32>,
+        pub location: Option<Hash>,
+        pub metadata: Option<Hash>,
+        pub validation: Option<AccountId>,
+        pub delegated: Option<AccountId>,
+        pub physical: Option<PhysicalAttributes>,
+    }
+
+    /// Suggested retail price of an asset next to the price it was last sold at
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct Pricing {
+        /// Suggested retail price recorded from the producer
+        pub msrp: Option<Msrp>,
+        /// Price of the last sale settled through the contract, reduced from the discount
+        pub realized: Option<Balance>,
+        /// Fiat reference unit of the realized price
+        pub currency: Option<Currency>,
+        /// Number of the sales settled through the contract
+        pub sales: u32,
+    }
+
+    /// First inconsistency found in the custody chain of an asset
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum ProvenanceIssue {
+        /// The asset does not exist
+        AssetNotFound,
+        /// The last transfer does not end with the current owner
+        OwnerMismatch,
+        /// The transfer at this position of the journal does not start from the holder left from the previous one
+        Gap(u32),
+        /// The transfer at this position of the journal moved the asset back in the role flow with no return recorded
+        RoleFlow(u32),
+        /// The chain does not start with the creation of the asset, nor with the split of a lot
+        OriginMismatch,
+        /// The validation of the asset has been revoked
+        ValidationRevoked,
+        /// The validation has been signed from an inspector no longer accredited on the category of the asset
+        ValidationExpired,
+    }
+
+    /// Assets a batch update applies to
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum AssetSelection {
+        /// Assets listed explicitly, at most as many as the items of a manifest
+        Assets(Vec<AssetId>),
+        /// Assets of the manifest of a shipment not yet delivered nor cancelled
+        Shipment(ManifestId),
     }
 
     /// Event emitted when a asset transfer occurs.
@@ -89,6 +1028,27 @@ mod asset_erc721 {
         id: AssetId,
     }
 
+    /// Event emitted when a transfer is sent from an owner with a grace period, the asset moves once it is finalized
+    #[ink(event)]
+    pub struct TransferPending {
+        #[ink(topic)]
+        id: AssetId,
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        until: BlockNumber,
+    }
+
+    /// Event emitted when the owner cancels a pending transfer
+    #[ink(event)]
+    pub struct TransferCancelled {
+        #[ink(topic)]
+        id: AssetId,
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+    }
+
     /// Event emitted when a asset approve occurs.
     #[ink(event)]
     pub struct ProxyUpdated {
@@ -106,14 +1066,108 @@ mod asset_erc721 {
         from: AccountId,
         #[ink(topic)]
         id: AssetId,
+        #[ink(topic)]
+        kind: UpdateKind,
+    }
+    /// Event emitted when the photo or the metadata of an asset is already stored for another asset,
+    /// both the assets are flagged as possible duplicates.
+    #[ink(event)]
+    pub struct PossibleDuplicate {
+        #[ink(topic)]
+        id: AssetId,
+        #[ink(topic)]
+        other: AssetId,
+        content: Hash,
     }
-    /// Event emitted when a role is updated
+    /// Event emitted when a role is updated, with the role of the account before and after the update
     #[ink(event)]
     pub struct RoleUpdate {
         #[ink(topic)]
         from: AccountId,
         #[ink(topic)]
         id: AccountId,
+        old: Option<u32>,
+        new: Option<u32>,
+    }
+
+    /// Event emitted when an account joins or leaves the set of the administrators
+    #[ink(event)]
+    pub struct AdministratorUpdated {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        id: AccountId,
+        added: bool,
+    }
+
+    /// Event emitted when an administrator installs or revokes a proxy of a custodial owner.
+    #[ink(event)]
+    pub struct CustodialProxyUpdated {
+        #[ink(topic)]
+        admin: AccountId,
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        operator: AccountId,
+        id: Option<AssetId>,
+        approved: bool,
+    }
+
+    /// Event emitted when an administrator clears the validation of an asset to unlock its core fields.
+    #[ink(event)]
+    pub struct AssetUnlocked {
+        #[ink(topic)]
+        id: AssetId,
+        #[ink(topic)]
+        admin: AccountId,
+        #[ink(topic)]
+        validator: AccountId,
+        reason: Hash,
+    }
+
+    /// Event emitted when a producer registers a brand.
+    #[ink(event)]
+    pub struct BrandRegistered {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        brand_id: BrandId,
+    }
+
+    /// Event emitted when a producer defines a product.
+    #[ink(event)]
+    pub struct ProductDefined {
+        #[ink(topic)]
+        producer: AccountId,
+        #[ink(topic)]
+        product_id: ProductId,
+    }
+
+    /// Event emitted when a variant is added to a product.
+    #[ink(event)]
+    pub struct ProductVariantAdded {
+        #[ink(topic)]
+        product_id: ProductId,
+        variant: u32,
+    }
+
+    /// Event emitted when a SKU code is registered, its default attributes change or it is removed.
+    #[ink(event)]
+    pub struct SkuUpdated {
+        #[ink(topic)]
+        product_id: ProductId,
+        sku: Vec<u8>,
+        removed: bool,
+    }
+
+    /// Event emitted when an administrator verifies or unverifies a brand.
+    #[ink(event)]
+    pub struct BrandVerificationUpdated {
+        #[ink(topic)]
+        admin: AccountId,
+        #[ink(topic)]
+        brand_id: BrandId,
+        verified: bool,
     }
 
     /// Event emitted when an operator is enabled or disabled for an owner.
@@ -127,1108 +1181,9081 @@ mod asset_erc721 {
         approved: bool,
     }
 
-    impl AssetErc721 {
-        /// Creates a new ERC721 asset contract.
-        #[ink(constructor)]
-        pub fn new() -> Self {
-            Self {
-                asset_owner: Default::default(),
-                asset_description: Default::default(),
-                asset_photo: Default::default(),
-                asset_location: Default::default(),
-                asset_category: Default::default(),
-                asset_category_description: Default::default(),
-                asset_metadata: Default::default(),
-                asset_proxy: Default::default(),
-                asset_validation: Default::default(),
-                account_owned_assets: Default::default(),
-                account_proxy: Default::default(),
-                account_role: Default::default(),
-            }
-        }
-        /// Creates a new asset.
-        #[ink(message)]
-        pub fn asset_new(&mut self, id: AssetId) -> Result<(), Error> {
-            let caller = self.env().caller();
-            self.add_asset_to(&caller, id)?;
-            self.env().emit_event(Transfer {
-                from: Some(AccountId::from([0x0; 32])),
-                to: Some(caller),
-                id,
-            });
-            Ok(())
-        }
-        /// Verifies if an asset id is present in the storage, it returns true/false
-        #[ink(message)]
-        pub fn asset_verify(&self, id: AssetId) -> bool{
-            self.asset_owner.contains_key(&id)
-        }
-        /// Returns the owner of an asset id
-        #[ink(message)]
-        pub fn asset_get_owner(&self, id: AssetId) -> Option<AccountId> {
-            self.asset_owner.get(&id).cloned()
-        }
-        #[ink(message)]
-        /// Adds the description of an asset, only the owner can do it
-        pub fn asset_description_new(&mut self,  id: AssetId, desc: Hash) -> Result<(), Error> {
-            let caller = self.env().caller();
-            let Self {
-                asset_owner,
-                asset_description,
-                ..
-            } = self;
-            //check if asset id is present in the storage and belongs to the signer
-            let _occupied = match asset_owner.entry(id) {
-                Entry::Vacant(_) => return Err(Error::AssetNotFound),
-                Entry::Occupied(occupied) => occupied,
-            };
-            // search for description storage
-            let _assetdescription = match asset_description.entry(id) {
-                Entry::Vacant(_) => "",
-                Entry::Occupied(_assetdescription) => return Err(Error::DuplicatedData),
-            };
-            // add description if not already present
-            if self.asset_description.insert(id, desc).is_some() {
-                return Err(Error::CannotInsert)
-            };
-            self.env().emit_event(AssetUpdate {
-                from: caller,
-                id,
-            });
-            Ok(())
-        }
-        /// Returns the description of an asset id
-        #[ink(message)]
-        pub fn asset_description_get(&self, id: AssetId) ->Option<Hash> {
-            self.asset_description.get(&id).cloned() 
-        } 
-        /// Verifies if an asset description is present in the storage
-        #[ink(message)]
-        pub fn asset_description_verify(&self, id: AssetId) -> bool{
-            self.asset_description.contains_key(&id)
-        }
-        /// Removes the description of an asset, only the owner can do it
-        #[ink(message)]
-        pub fn asset_description_delete(&mut self,  id: AssetId) -> Result<(), Error> {
-            let caller = self.env().caller();
-            //check if asset id is present in the storage and belongs to the signer
-            let asset = match self.asset_owner.entry(id) {
-                Entry::Vacant(_) => return Err(Error::AssetNotFound),
-                Entry::Occupied(asset) => asset,
-            };
-            if asset.get() != &caller  && self.account_role_get(caller).unwrap()!=5 {
-                return Err(Error::NotOwner)
-            };
-            // search for description 
-            let assetdescription = match self.asset_description.entry(id) {
-                Entry::Vacant(_) => return Err(Error::AssetNotFound),
-                Entry::Occupied(assetdescription) => assetdescription,
-            };
-            // remove description
-            assetdescription.remove_entry();
-            self.env().emit_event(AssetUpdate {
-                from: caller,
-                id,
-            });
-            Ok(())
-        }
-        /// Adds the IPFS address of an asset's photo, only the owner can do it
-        #[ink(message)]
-        pub fn asset_photo_new(&mut self,  id: AssetId, photoipfs: Hash) -> Result<(), Error> {
-            let caller = self.env().caller();
-            let Self {
-                asset_owner,
-                asset_photo,
-                ..
-            } = self;
-            //check if asset id is present in the storage and belongs to the signer
-            let _occupied = match asset_owner.entry(id) {
-                Entry::Vacant(_) => return Err(Error::AssetNotFound),
-                Entry::Occupied(occupied) => occupied,
-            };
-            // search for photo storage
-            let _assetphoto = match asset_photo.entry(id) {
-                Entry::Vacant(_) => "",
-                Entry::Occupied(_assetphoto) => return Err(Error::DuplicatedData),
-            };
-            // add photo ipfs address if not already present
-            if self.asset_photo.insert(id, photoipfs).is_some() {
-                return Err(Error::CannotInsert)
-            };
-            self.env().emit_event(AssetUpdate {
-                from: caller,
-                id,
-            });
-            Ok(())
-        }
-        /// Returns the ipfs address of the asset's photo 
-        #[ink(message)]
-        pub fn asset_photo_get(&self, id: AssetId) ->  Option<Hash>{
-           self.asset_photo.get(&id).cloned()
-        }
-        /// Verifies the IPFS address of the asset photo is stored
-        #[ink(message)]
-        pub fn asset_photo_verify(&self, id: AssetId) -> bool{
-            self.asset_photo.contains_key(&id)
-        }
-        /// Removes  the ipfs address of an asset's photo, only the owner can do it
-        #[ink(message)]
-        pub fn asset_photo_delete(&mut self,  id: AssetId) -> Result<(), Error> {
-            let caller = self.env().caller();
-            //check if asset id is present in the storage and belongs to the signer
-            let asset = match self.asset_owner.entry(id) {
-                Entry::Vacant(_) => return Err(Error::AssetNotFound),
-                Entry::Occupied(asset) => asset,
-            };
-            if asset.get() != &caller  && self.account_role_get(caller).unwrap()!=5{
-                return Err(Error::NotOwner)
-            };
-            // search for photo ipfs address
-            let assetphoto = match self.asset_photo.entry(id) {
-                Entry::Vacant(_) => return Err(Error::AssetNotFound),
-                Entry::Occupied(assetphoto) => assetphoto,
-            };
-            // remove photo ipfs address
-            assetphoto.remove_entry();
-            self.env().emit_event(AssetUpdate {
-                from: caller,
-                id,
-            });
-            Ok(())
-        }
-        /// Stores the  category of an asset, only owner can do it, the category id must be already stored using "categoryDescriptionNew"
-        #[ink(message)]
-        pub fn asset_category_new(&mut self,  id: AssetId, categoryid: u32) -> Result<(), Error> {
-            let caller = self.env().caller();
-            //check if asset id is present in the storage and belongs to the signer
-            let asset = match self.asset_owner.entry(id) {
-                Entry::Vacant(_) => return Err(Error::AssetNotFound),
-                Entry::Occupied(asset) => asset,
-            };
-            if asset.get() != &caller  && self.account_role_get(caller).unwrap()!=5{
-                return Err(Error::NotOwner)
-            };
-            // search for asset_category_description in the storage
-            let _categorydescription = match self.asset_category_description.entry(categoryid) {
-                Entry::Vacant(_) => return Err(Error::CategoryNotFound),
-                Entry::Occupied(categorydescription) => categorydescription,
-            };
-            // search for asset category in the storage to avoid duplicated entries
-            let _assetcategory = match self.asset_category.entry(id) {
-                Entry::Vacant(_) => 0,
-                Entry::Occupied(_assetcategory) => return Err(Error::DuplicatedData),
-            };
-            //store the asset category
-            if self.asset_category.insert(id, categoryid).is_some() {
-                return Err(Error::CannotInsert)
-            };
-            self.env().emit_event(AssetUpdate {
-                from: caller,
-                id,
-            });
-            Ok(())
-        }
-        /// Verifies if an asset category is present in the storage, it returns true/false
-        #[ink(message)]
-        pub fn asset_category_verify(&self, id: AssetId) -> bool{
-             self.asset_category.contains_key(&id)
-         }
-        /// Removes the category of an asset, only the owner can do it
-        #[ink(message)]
-        pub fn asset_category_delete(&mut self,  id: AssetId) -> Result<(), Error> {
-            let caller = self.env().caller();
-            //check if asset id is present in the storage and belongs to the signer
-            let asset = match self.asset_owner.entry(id) {
-                Entry::Vacant(_) => return Err(Error::AssetNotFound),
-                Entry::Occupied(asset) => asset,
-            };
-            if asset.get() != &caller  && self.account_role_get(caller).unwrap()!=5{
-                return Err(Error::NotOwner)
-            };
-            // search for category
-            let assetcategory = match self.asset_category.entry(id) {
-                Entry::Vacant(_) => return Err(Error::AssetNotFound),
-                Entry::Occupied(assetcategory) => assetcategory,
-            };
-            // remove category
-            assetcategory.remove_entry();
-            self.env().emit_event(AssetUpdate {
-                from: caller,
-                id,
-            });
-            Ok(())
-        }
-        /// Adds the  location of an asset by coordinates in decimal format, comma separated: xxx.xxxxxxx,yyyy.yyyyyy only owner can do it
-        #[ink(message)]
-        pub fn asset_location_new(&mut self,  id: AssetId, location: Hash) -> Result<(), Error> {
-            let caller = self.env().caller();
-            //check if asset id is present in the storage and belongs to the signer or is a shipper
-            let asset = match self.asset_owner.entry(id) {
-                Entry::Vacant(_) => return Err(Error::AssetNotFound),
-                Entry::Occupied(asset) => asset,
-            };
-            if asset.get() != &caller  && self.account_role_get(caller).unwrap()!=4{
-                return Err(Error::NotOwner)
-            };
-            // search for location storage
-            let _assetlocation = match self.asset_location.entry(id) {
-                Entry::Vacant(_) => "",
-                Entry::Occupied(_assetlocation) => return Err(Error::DuplicatedData),
-            };
-            // add location if not already present
-            if self.asset_location.insert(id, location).is_some() {
-                return Err(Error::CannotInsert)
-            };
-            self.env().emit_event(AssetUpdate {
-                from: caller,
-                id,
-            });
-            Ok(())
-        }
-        /// Returns the location coordinates of an asset
-        #[ink(message)]
-        pub fn asset_location_get(&self, id: AssetId) ->  Option<Hash>{
-           self.asset_location.get(&id).cloned()
-        }
-        /// Verify if there is a location stored for an asset id
-        #[ink(message)]
-        pub fn asset_location_verify(&self, id: AssetId) -> bool{
-            self.asset_location.contains_key(&id)
-        }
-        /// Remove the location of an asset id, only the owner can do it
-        #[ink(message)]
-        pub fn asset_location_delete(&mut self,  id: AssetId) -> Result<(), Error> {
-            let caller = self.env().caller();
-            //check if asset id is present in the storage and belongs to the signer
-            let asset = match self.asset_owner.entry(id) {
-                Entry::Vacant(_) => return Err(Error::AssetNotFound),
-                Entry::Occupied(asset) => asset,
+    /// Event emitted when the manifest of a shipment is created.
+    #[ink(event)]
+    pub struct ManifestCreated {
+        #[ink(topic)]
+        manifest_id: ManifestId,
+        #[ink(topic)]
+        sender: AccountId,
+        #[ink(topic)]
+        receiver: AccountId,
+        hash: Hash,
+    }
+
+    /// Event emitted when the consignor agrees the freight of a shipment with a shipper.
+    #[ink(event)]
+    pub struct FreightAgreed {
+        #[ink(topic)]
+        manifest_id: ManifestId,
+        #[ink(topic)]
+        shipper: AccountId,
+        cost: Balance,
+        token: Option<AccountId>,
+    }
+
+    /// Event emitted when the receiver confirms the delivery of a shipment.
+    #[ink(event)]
+    pub struct ShipmentDelivered {
+        #[ink(topic)]
+        manifest_id: ManifestId,
+        #[ink(topic)]
+        receiver: AccountId,
+    }
+
+    /// Event emitted when a producer renews its subscription.
+    #[ink(event)]
+    pub struct SubscriptionRenewed {
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+        paid_until: BlockNumber,
+    }
+
+    /// Event emitted when the stake of an account changes.
+    #[ink(event)]
+    pub struct StakeUpdated {
+        #[ink(topic)]
+        account: AccountId,
+        bonded: Balance,
+        unbonding: Balance,
+    }
+
+    /// Event emitted when a reward is minted for a verification work.
+    #[ink(event)]
+    pub struct RewardPaid {
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+        work: Work,
+    }
+
+    /// Event emitted when a dispute is opened.
+    #[ink(event)]
+    pub struct DisputeOpened {
+        #[ink(topic)]
+        dispute_id: DisputeId,
+        #[ink(topic)]
+        respondent: AccountId,
+        subject: DisputeSubject,
+    }
+
+    /// Event emitted when a dispute is resolved from an administrator.
+    #[ink(event)]
+    pub struct DisputeResolved {
+        #[ink(topic)]
+        dispute_id: DisputeId,
+        status: DisputeStatus,
+    }
+
+    /// Event emitted when the freight of a delivered shipment is paid to the shipper.
+    #[ink(event)]
+    pub struct FreightSettled {
+        #[ink(topic)]
+        manifest_id: ManifestId,
+        #[ink(topic)]
+        shipper: AccountId,
+        cost: Balance,
+        penalty: Balance,
+    }
+
+    /// Event emitted when the customs duty of a cleared shipment is paid to the collector.
+    #[ink(event)]
+    pub struct DutyReleased {
+        #[ink(topic)]
+        manifest_id: ManifestId,
+        #[ink(topic)]
+        collector: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when the sender cancels a shipment.
+    #[ink(event)]
+    pub struct ShipmentCancelled {
+        #[ink(topic)]
+        manifest_id: ManifestId,
+        #[ink(topic)]
+        sender: AccountId,
+    }
+
+    /// Event emitted when the carrier of an asset changes, `carrier` is None when the carriers are released.
+    #[ink(event)]
+    pub struct CarrierUpdated {
+        #[ink(topic)]
+        id: AssetId,
+        #[ink(topic)]
+        carrier: Option<AccountId>,
+        from: AccountId,
+    }
+
+    /// Event emitted when the owner changes the field groups of an asset granted to an account,
+    /// `groups` is 0 when the grant is revoked.
+    #[ink(event)]
+    pub struct AclUpdated {
+        #[ink(topic)]
+        id: AssetId,
+        #[ink(topic)]
+        account: AccountId,
+        groups: FieldGroups,
+    }
+
+    /// Event emitted when the owner grants or revokes the key of the private data of an asset,
+    /// `wrapped_key` is None when the grant is revoked.
+    #[ink(event)]
+    pub struct PrivateKeyGranted {
+        #[ink(topic)]
+        id: AssetId,
+        #[ink(topic)]
+        account: AccountId,
+        wrapped_key: Option<Hash>,
+    }
+
+    /// Event emitted when the owner allows or disallows an account to view the private data of an asset.
+    #[ink(event)]
+    pub struct ViewerUpdated {
+        #[ink(topic)]
+        id: AssetId,
+        #[ink(topic)]
+        account: AccountId,
+        allowed: bool,
+    }
+
+    /// Event emitted when a verifier contract accepts the proof of a claim on an asset.
+    #[ink(event)]
+    pub struct ClaimVerified {
+        #[ink(topic)]
+        id: AssetId,
+        #[ink(topic)]
+        claim_type: ClaimType,
+        statement: Hash,
+    }
+
+    /// Event emitted when an asset is locked to be mirrored on the EVM chain, `payload_hash` is the
+    /// Keccak-256 hash of the ABI-encoded payload checked from the EVM side.
+    #[ink(event)]
+    pub struct BridgeLocked {
+        #[ink(topic)]
+        id: AssetId,
+        recipient: EvmAddress,
+        nonce: u64,
+        payload_hash: Hash,
+    }
+
+    /// Event emitted when an asset is unlocked with the proof of the burn of its wrapped token.
+    #[ink(event)]
+    pub struct BridgeUnlocked {
+        #[ink(topic)]
+        id: AssetId,
+        #[ink(topic)]
+        to: AccountId,
+        burn_proof: Hash,
+    }
+
+    /// Event emitted when an asset is listed for sale.
+    #[ink(event)]
+    pub struct AssetListed {
+        #[ink(topic)]
+        id: AssetId,
+        #[ink(topic)]
+        seller: AccountId,
+        price: Balance,
+        currency: Option<Currency>,
+    }
+
+    /// Event emitted when a producer publishes a price of its price list.
+    #[ink(event)]
+    pub struct PriceListUpdated {
+        #[ink(topic)]
+        producer: AccountId,
+        #[ink(topic)]
+        category: u32,
+        unit_price: Balance,
+        currency: Option<Currency>,
+        valid_from: BlockNumber,
+    }
+
+    /// Event emitted when the tax component of a sale is recorded.
+    #[ink(event)]
+    pub struct TaxRecorded {
+        #[ink(topic)]
+        id: AssetId,
+        #[ink(topic)]
+        jurisdiction: Jurisdiction,
+        sale: u32,
+        tax: Balance,
+        collector: Option<AccountId>,
+    }
+
+    /// Event emitted when the fee of a sale is paid to the treasury.
+    #[ink(event)]
+    pub struct FeeCollected {
+        #[ink(topic)]
+        id: AssetId,
+        token: Option<AccountId>,
+        amount: Balance,
+    }
+
+    /// Event emitted when an administrator proposes a withdrawal from the treasury.
+    #[ink(event)]
+    pub struct WithdrawalProposed {
+        #[ink(topic)]
+        withdrawal_id: WithdrawalId,
+        #[ink(topic)]
+        proposer: AccountId,
+        to: AccountId,
+        token: Option<AccountId>,
+        amount: Balance,
+    }
+
+    /// Event emitted when an administrator approves a withdrawal from the treasury.
+    #[ink(event)]
+    pub struct WithdrawalApproved {
+        #[ink(topic)]
+        withdrawal_id: WithdrawalId,
+        #[ink(topic)]
+        approver: AccountId,
+        approvals: u32,
+    }
+
+    /// Event emitted when the fees collected are withdrawn from the treasury.
+    #[ink(event)]
+    pub struct TreasuryWithdrawn {
+        #[ink(topic)]
+        withdrawal_id: WithdrawalId,
+        #[ink(topic)]
+        to: AccountId,
+        token: Option<AccountId>,
+        amount: Balance,
+    }
+
+    /// Event emitted when a listed asset is sold.
+    #[ink(event)]
+    pub struct AssetSold {
+        #[ink(topic)]
+        id: AssetId,
+        #[ink(topic)]
+        seller: AccountId,
+        #[ink(topic)]
+        buyer: AccountId,
+        amount: Balance,
+        rate: Option<Balance>,
+        referrer: Option<AccountId>,
+    }
+
+    /// Event emitted when an administrator issues the compliance certificate of a shipment.
+    #[ink(event)]
+    pub struct CertificateIssued {
+        #[ink(topic)]
+        certificate_id: CertificateId,
+        #[ink(topic)]
+        manifest_id: ManifestId,
+        compliant: bool,
+    }
+
+    /// Event emitted when an administrator revokes a certificate or a validation.
+    #[ink(event)]
+    pub struct CredentialRevoked {
+        #[ink(topic)]
+        credential: Credential,
+        #[ink(topic)]
+        issuer: AccountId,
+        reason: Hash,
+    }
+
+    /// Event emitted to the subscribers of a category when an asset of the category is validated or certified.
+    #[ink(event)]
+    pub struct CategoryAlert {
+        #[ink(topic)]
+        category: u32,
+        #[ink(topic)]
+        subscribers: Hash,
+        alert: Alert,
+    }
+
+    /// Event emitted when an insurer attaches a policy to a shipment.
+    #[ink(event)]
+    pub struct PolicyAttached {
+        #[ink(topic)]
+        manifest_id: ManifestId,
+        #[ink(topic)]
+        insurer: AccountId,
+        coverage: Balance,
+    }
+
+    /// Event emitted when the coverage of a policy is paid to the beneficiary or released to the insurer.
+    #[ink(event)]
+    pub struct PolicySettled {
+        #[ink(topic)]
+        manifest_id: ManifestId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+        status: PolicyStatus,
+    }
+
+    /// Event emitted when an account has been erased from the historical records, naming neither the account nor its pseudonym
+    #[ink(event)]
+    pub struct AccountErased {
+        /// Number of the accounts erased
+        erased: u32,
+    }
+
+    /// Event emitted when the storage deposit of an account on an asset is topped up or reclaimed
+    #[ink(event)]
+    pub struct DepositUpdated {
+        #[ink(topic)]
+        id: AssetId,
+        #[ink(topic)]
+        account: AccountId,
+        deposit: Balance,
+    }
+
+    /// Event emitted when a legal hold is placed on an asset
+    #[ink(event)]
+    pub struct AssetHoldPlaced {
+        #[ink(topic)]
+        id: AssetId,
+        #[ink(topic)]
+        order: Hash,
+    }
+
+    /// Event emitted when the legal hold of an asset is lifted
+    #[ink(event)]
+    pub struct AssetHoldLifted {
+        #[ink(topic)]
+        id: AssetId,
+        #[ink(topic)]
+        order: Hash,
+    }
+
+    /// Event emitted when an asset is reserved for a buyer
+    #[ink(event)]
+    pub struct AssetReserved {
+        #[ink(topic)]
+        id: AssetId,
+        #[ink(topic)]
+        buyer: AccountId,
+        until: BlockNumber,
+        deposit: Balance,
+    }
+
+    /// Event emitted when the reservation of an asset is released, with the account its deposit is paid to
+    #[ink(event)]
+    pub struct AssetReservationReleased {
+        #[ink(topic)]
+        id: AssetId,
+        to: AccountId,
+        deposit: Balance,
+    }
+
+    /// Event emitted when an asset is returned, inspected, restocked or routed to disposal
+    #[ink(event)]
+    pub struct AssetReturnUpdated {
+        #[ink(topic)]
+        id: AssetId,
+        status: StockStatus,
+    }
+
+    /// Event emitted when an asset is disposed of
+    #[ink(event)]
+    pub struct AssetDisposed {
+        #[ink(topic)]
+        id: AssetId,
+        #[ink(topic)]
+        disposer: AccountId,
+        method: u32,
+        evidence: Hash,
+    }
+
+    /// Event emitted when the materials of disposed assets are claimed into a recycled asset
+    #[ink(event)]
+    pub struct AssetRecycled {
+        #[ink(topic)]
+        id: AssetId,
+        sources: Vec<AssetId>,
+    }
+
+    /// Event emitted when an asset is retired and remanufactured into a new asset
+    #[ink(event)]
+    pub struct AssetRemanufactured {
+        #[ink(topic)]
+        old_id: AssetId,
+        #[ink(topic)]
+        new_id: AssetId,
+    }
+
+    /// Event emitted when a lien on an asset is filed, consented, released or rejected
+    #[ink(event)]
+    pub struct LienUpdated {
+        #[ink(topic)]
+        lien_id: LienId,
+        #[ink(topic)]
+        id: AssetId,
+        #[ink(topic)]
+        creditor: AccountId,
+        status: LienStatus,
+    }
+
+
+    impl AssetErc721 {
+        /// Creates a new ERC721 asset contract with the deployer as administrator.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self::with_administrators([Self::env().caller()].to_vec())
+        }
+        /// Creates a new ERC721 asset contract with the accounts of `administrators` as administrators. Panics if the
+        /// list is empty.
+        #[ink(constructor)]
+        pub fn new_with_administrators(administrators: Vec<AccountId>) -> Self {
+            assert!(!administrators.is_empty(), "no administrator");
+            Self::with_administrators(administrators)
+        }
+        /// Returns the contract with no data but the set of the `administrators`
+        fn with_administrators(administrators: Vec<AccountId>) -> Self {
+            Self {
+                ownership: Default::default(),
+                provenance: Default::default(),
+                metadata: Default::default(),
+                roles: Default::default(),
+                licenses: Default::default(),
+                alerts: Default::default(),
+                price_lists: Default::default(),
+                jurisdictions: Default::default(),
+                taxes: Default::default(),
+                delegation: Default::default(),
+                brands: Default::default(),
+                products: Default::default(),
+                skus: Default::default(),
+                contents: Default::default(),
+                authenticity: Default::default(),
+                tags: Default::default(),
+                quantities: Default::default(),
+                physical: Default::default(),
+                depreciation: Default::default(),
+                services: Default::default(),
+                locations: Default::default(),
+                notes: Default::default(),
+                threads: Default::default(),
+                liens: Default::default(),
+                holds: Default::default(),
+                returns: Default::default(),
+                disposals: Default::default(),
+                lineage: Default::default(),
+                deposits: Default::default(),
+                shipments: Default::default(),
+                carriers: Default::default(),
+                certificates: Default::default(),
+                insurance: Default::default(),
+                laboratories: Default::default(),
+                inspectors: Default::default(),
+                validation_queue: Default::default(),
+                acl: Default::default(),
+                privacy: Default::default(),
+                proofs: Default::default(),
+                sealed: Default::default(),
+                bridge: Default::default(),
+                sales: Default::default(),
+                subscriptions: Default::default(),
+                treasury: Default::default(),
+                stakes: Default::default(),
+                disputes: Default::default(),
+                revocations: Default::default(),
+                rewards: Default::default(),
+                journal: Default::default(),
+                erasure: Default::default(),
+                updates: Default::default(),
+                activity: Default::default(),
+                stats: Default::default(),
+                config: Default::default(),
+                collection: Default::default(),
+                account_mint_window: Default::default(),
+                admin_log: Default::default(),
+                admin_log_count: Default::default(),
+                imports_closed: Default::default(),
+                administrators: Lazy::new(administrators.into_iter().map(|account| (account, true)).collect()),
+            }
+        }
+        /// Creates a new ERC721 asset contract with `admin` as administrator, the roles of `initial_roles` and the
+        /// descriptions of `initial_categories`, so a deployment is set up in one instantiation. Panics if a role
+        /// is not enabled or an account or a category is repeated.
+        #[ink(constructor)]
+        pub fn new_with_config(admin: AccountId, initial_roles: Vec<(AccountId, u32)>, initial_categories: Vec<(u32, Hash)>) -> Self {
+            let mut contract = Self::with_administrators([admin].to_vec());
+            for (account, role) in core::iter::once((admin, ADMINISTRATOR)).chain(initial_roles) {
+                contract.roles.insert(account, role).expect("invalid initial role");
+                contract.stats.role_changed(None, Some(role));
+            }
+            for (id, description) in initial_categories {
+                contract.metadata.category_description_insert(id, description).expect("duplicated initial category");
+            }
+            contract
+        }
+        /// Creates a new asset. The native tokens transferred are locked as its deposit, at least the mint deposit
+        /// of the configuration.
+        #[ink(message, payable, selector = "0x40889615")]
+        pub fn asset_new(&mut self, id: AssetId) -> Result<(), Error> {
+            // check for producer when minting is restricted
+            let caller = self.authorize(Message::AssetNew, None)?;
+            self.mint(caller, id)
+        }
+        /// Creates a new asset linked to a brand, only the owner of the brand can do it. The native tokens
+        /// transferred are locked as its deposit, at least the mint deposit of the configuration.
+        #[ink(message, payable, selector = "0x0CB1298C")]
+        pub fn asset_new_branded(&mut self, id: AssetId, brand_id: BrandId) -> Result<(), Error> {
+            // check for producer when minting is restricted
+            let caller = self.authorize(Message::AssetNewBranded, None)?;
+            let brand = self.brands.get(brand_id).ok_or(Error::BrandNotFound)?;
+            if brand.owner != caller {
+                return Err(Error::NotBrandOwner)
+            }
+            if self.brands.brand_of(id).is_some() {
+                return Err(Error::AssetExists)
+            }
+            self.mint(caller, id)?;
+            self.brands.link(id, brand_id)
+        }
+        /// Verifies if an asset id is present in the storage, it returns true/false
+        #[ink(message, selector = "0x7833ACC7")]
+        pub fn asset_verify(&self, id: AssetId) -> bool{
+            self.ownership.exists(id)
+        }
+        /// Returns the owner of an asset id
+        #[ink(message, selector = "0x7BC9D2F7")]
+        pub fn asset_get_owner(&self, id: AssetId) -> Option<AccountId> {
+            self.ownership.owner_of(id)
+        }
+        /// Returns the account that created an asset id, the block and the timestamp of its creation
+        #[ink(message, selector = "0xDDE2669B")]
+        pub fn asset_provenance_get(&self, id: AssetId) -> Option<MintRecord> {
+            self.provenance.mint_of(id)
+        }
+        /// Returns the producer of record of an asset id, the account that created it.
+        /// It is written once when the asset is created and never changes with the transfers.
+        #[ink(message, selector = "0x1059B8AE")]
+        pub fn asset_producer_get(&self, id: AssetId) -> Option<AccountId> {
+            self.provenance.producer_of(id)
+        }
+        /// Verifies the custody chain of an asset is consistent, see `asset_provenance_check`
+        #[ink(message, selector = "0x9544B58A")]
+        pub fn asset_provenance_valid(&self, id: AssetId) -> bool {
+            self.asset_provenance_check(id).is_ok()
+        }
+        /// Checks the custody chain of an asset following its transfers back from the current owner: each transfer
+        /// starts from the holder left from the previous one, the chain starts with the creation of the asset or the
+        /// split of the lot, no transfer moved the asset back in the role flow producer, wholesaler, retailer, final
+        /// buyer unless the asset was returned, and the validation is neither revoked nor signed from an inspector no
+        /// longer accredited. Returns the number of transfers of the chain or the first inconsistency found.
+        #[ink(message, selector = "0xA56B602D")]
+        pub fn asset_provenance_check(&self, id: AssetId) -> Result<u32, ProvenanceIssue> {
+            let record = self.ownership.record_of(id).ok_or(ProvenanceIssue::AssetNotFound)?;
+            let stage = |account: &AccountId| {
+                self.roles.role_of(account).and_then(|role| ROLE_FLOW.iter().position(|flow| *flow == role))
             };
-            if asset.get() != &caller  && self.account_role_get(caller).unwrap()!=4 {
-                return Err(Error::NotOwner)
-            };
-            // search for location
-            let assetlocation = match self.asset_location.entry(id) {
-                Entry::Vacant(_) => return Err(Error::AssetNotFound),
-                Entry::Occupied(assetlocation) => assetlocation,
+            let mut holder = record.owner;
+            let mut position = record.last_transfer;
+            let mut origin = None;
+            let mut count = 0;
+            while let Some(index) = position {
+                let transfer = self.journal.get(index).filter(|transfer| transfer.id == id).ok_or(ProvenanceIssue::Gap(index))?;
+                if transfer.to != holder {
+                    return Err(if count == 0 { ProvenanceIssue::OwnerMismatch } else { ProvenanceIssue::Gap(index) })
+                }
+                // a move back in the flow is a return received from the sender since the transfer
+                if let (Some(from), Some(to)) = (stage(&transfer.from), stage(&transfer.to)) {
+                    let returned = || {
+                        self.returns
+                            .returns_of(id, 0, self.returns.returns_count(id))
+                            .iter()
+                            .any(|entry| entry.from == transfer.from && entry.received >= transfer.block)
+                    };
+                    if to < from && !returned() {
+                        return Err(ProvenanceIssue::RoleFlow(index))
+                    }
+                }
+                holder = transfer.from;
+                position = transfer.previous;
+                origin = Some(transfer);
+                count += 1;
+            }
+            let origin = origin.ok_or(ProvenanceIssue::OriginMismatch)?;
+            let created = origin.from == AccountId::from([0x0; 32])
+                && self.provenance.mint_of(id).is_none_or(|mint| mint.block == origin.block);
+            if !created && self.quantities.parent_of(id).is_none() {
+                return Err(ProvenanceIssue::OriginMismatch)
+            }
+            if let Some(signer) = record.signer.or(record.validator) {
+                if self.revocations.is_revoked(Credential::Validation(id)) {
+                    return Err(ProvenanceIssue::ValidationRevoked)
+                }
+                if self.inspector_scope_check(&signer, record.category).is_err() {
+                    return Err(ProvenanceIssue::ValidationExpired)
+                }
+            }
+            Ok(count)
+        }
+        /// Returns all the data stored for an asset id
+        #[ink(message, selector = "0x82D35CC6")]
+        pub fn asset_info_get(&self, id: AssetId) -> Option<AssetInfo> {
+            let record = self.ownership.record_of(id)?;
+            Some(AssetInfo {
+                owner: record.owner,
+                description: record.description,
+                photo: record.photo,
+                category: record.category,
+                location: record.location,
+                metadata: record.metadata,
+                validation: record.validator,
+                delegated: record.delegate,
+                physical: self.physical.attributes_of(id),
+            })
+        }
+        /// Imports an asset with the data returned from "asset_info_get" of a previous deployment of the contract,
+        /// only administrator can do it while the imports are open. The category must be registered before.
+        #[ink(message, selector = "0x2B51D911")]
+        pub fn asset_import(&mut self, id: AssetId, info: AssetInfo) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::AssetImport, None)?;
+            if *self.imports_closed {
+                return Err(Error::NotAllowed)
+            }
+            if info.category.is_some_and(|categoryid| self.metadata.category_description(categoryid).is_none()) {
+                return Err(Error::CategoryNotFound)
+            }
+            self.ownership.add(&info.owner, id)?;
+            let record = self.ownership.record_mut(id)?;
+            record.description = info.description;
+            record.photo = info.photo;
+            record.location = info.location;
+            record.metadata = info.metadata;
+            record.validator = info.validation;
+            record.delegate = info.delegated;
+            if let Some(categoryid) = info.category {
+                self.metadata.category_assign(record, categoryid)?;
+            }
+            for content in info.photo.iter().chain(info.metadata.iter()) {
+                self.content_index(id, *content);
+                self.contents.link(*content, id);
+            }
+            if let Some(attributes) = info.physical {
+                self.physical.set(id, attributes);
+            }
+            self.transferred(AccountId::from([0x0; 32]), info.owner, id);
+            Ok(())
+        }
+        /// Closes the imports of the assets for good, only administrator can do it
+        #[ink(message, selector = "0x1DFDD087")]
+        pub fn asset_import_close(&mut self) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::AssetImportClose, None)?;
+            *self.imports_closed = true;
+            Ok(())
+        }
+        #[ink(message, selector = "0xB6578DA7")]
+        /// Adds the description of an asset, only the owner can do it
+        pub fn asset_description_new(&mut self,  id: AssetId, desc: Hash) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetDescriptionNew, Some(id))?;
+            self.asset_data_insert(caller, id, UpdateKind::Description, desc)
+        }
+        /// Returns the description of an asset id
+        #[ink(message, selector = "0xB64BCBBC")]
+        pub fn asset_description_get(&self, id: AssetId) ->Option<Hash> {
+            self.ownership.record_of(id).and_then(|record| record.get(UpdateKind::Description))
+        } 
+        /// Verifies if an asset description is present in the storage
+        #[ink(message, selector = "0xFE6FF5DF")]
+        pub fn asset_description_verify(&self, id: AssetId) -> bool{
+            self.ownership.record_of(id).is_some_and(|record| record.contains(UpdateKind::Description))
+        }
+        /// Removes the description of an asset, only the owner can do it
+        #[ink(message, selector = "0x1F6246FB")]
+        pub fn asset_description_delete(&mut self,  id: AssetId) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetDescriptionDelete, Some(id))?;
+            self.asset_data_remove(caller, id, UpdateKind::Description)
+        }
+        /// Stores or replaces the translation of the description of an asset in a language
+        /// (ISO 639-1 code, for example "en"), only the owner can do it
+        #[ink(message, selector = "0xE77DCFEB")]
+        pub fn asset_description_set_locale(&mut self, id: AssetId, locale: Locale, desc: Hash) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetDescriptionSetLocale, Some(id))?;
+            // check the data is not locked from the validation
+            self.check_unlocked(id, UpdateKind::Description)?;
+            // check the cooldown since the last update
+            self.check_update_cooldown(id, UpdateKind::Description)?;
+            self.metadata.description_locale_set(id, locale, desc)?;
+            self.asset_updated(caller, id, UpdateKind::Description);
+            Ok(())
+        }
+        /// Returns the translation of the description of an asset in a language
+        #[ink(message, selector = "0x1DC4A045")]
+        pub fn asset_description_get_locale(&self, id: AssetId, locale: Locale) -> Option<Hash> {
+            self.metadata.description_locale(id, locale)
+        }
+        /// Returns a page of the languages of the available translations of the description of an asset
+        #[ink(message, selector = "0x42525A6B")]
+        pub fn asset_description_locales(&self, id: AssetId, cursor: Cursor, limit: u32) -> Page<Locale> {
+            pagination::page_of(self.metadata.description_locales(id), cursor, limit)
+        }
+        /// Removes the translation of the description of an asset in a language, only the owner can do it
+        #[ink(message, selector = "0xBBC3745C")]
+        pub fn asset_description_delete_locale(&mut self, id: AssetId, locale: Locale) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetDescriptionDeleteLocale, Some(id))?;
+            // check the data is not locked from the validation
+            self.check_unlocked(id, UpdateKind::Description)?;
+            // check the cooldown since the last update
+            self.check_update_cooldown(id, UpdateKind::Description)?;
+            self.metadata.description_locale_remove(id, locale)?;
+            self.asset_updated(caller, id, UpdateKind::Description);
+            Ok(())
+        }
+        /// Adds the IPFS address of an asset's photo, only the owner can do it
+        #[ink(message, selector = "0x6605B7F2")]
+        pub fn asset_photo_new(&mut self,  id: AssetId, photoipfs: Hash) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetPhotoNew, Some(id))?;
+            self.asset_data_insert(caller, id, UpdateKind::Photo, photoipfs)
+        }
+        /// Returns the ipfs address of the asset's photo 
+        #[ink(message, selector = "0xADE861D4")]
+        pub fn asset_photo_get(&self, id: AssetId) ->  Option<Hash>{
+           self.ownership.record_of(id).and_then(|record| record.get(UpdateKind::Photo))
+        }
+        /// Verifies the IPFS address of the asset photo is stored
+        #[ink(message, selector = "0x9216B716")]
+        pub fn asset_photo_verify(&self, id: AssetId) -> bool{
+            self.ownership.record_of(id).is_some_and(|record| record.contains(UpdateKind::Photo))
+        }
+        /// Removes  the ipfs address of an asset's photo, only the owner can do it
+        #[ink(message, selector = "0x7DECE0DF")]
+        pub fn asset_photo_delete(&mut self,  id: AssetId) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetPhotoDelete, Some(id))?;
+            self.asset_data_remove(caller, id, UpdateKind::Photo)
+        }
+        /// Stores the  category of an asset, only owner can do it, the category id must be already stored using "categoryDescriptionNew"
+        /// or in the category registry of the configuration
+        #[ink(message, selector = "0x3B5B52EF")]
+        pub fn asset_category_new(&mut self,  id: AssetId, categoryid: u32) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetCategoryNew, Some(id))?;
+            // check the data is not locked from the validation
+            self.check_unlocked(id, UpdateKind::Category)?;
+            // check the cooldown since the last update
+            self.check_update_cooldown(id, UpdateKind::Category)?;
+            // fetch the description from the shared registry when the category is not cached yet
+            self.category_resolve(categoryid)?;
+            // check the signer is authorized when the category is restricted to some producers
+            if self.metadata.category_description(categoryid).is_some() && !self.category_producer_allowed(categoryid, &caller) {
+                return Err(Error::CategoryNotAuthorized)
+            }
+            // the producer of the asset must hold a license covering a regulated category
+            let producer = self.provenance.producer_of(id).unwrap_or(caller);
+            if !self.is_administrator(&producer) && !self.licenses.is_licensed(&producer, categoryid, self.env().block_number()) {
+                return Err(Error::LicenseRequired)
+            }
+            //store the asset category
+            self.metadata.category_assign(self.ownership.record_mut(id)?, categoryid)?;
+            // queue the asset for validation when its new category requires it
+            if self.validation_queue.is_required(categoryid) && self.validation_queue.request_of(id).is_none() {
+                let block = self.env().block_number();
+                self.validation_queue.push(id, ValidationRequest {
+                    requester: caller,
+                    category: Some(categoryid),
+                    block,
+                })?;
+            }
+            self.asset_updated(caller, id, UpdateKind::Category);
+            Ok(())
+        }
+        /// Verifies if an asset category is present in the storage, it returns true/false
+        #[ink(message, selector = "0x2BE9C53A")]
+        pub fn asset_category_verify(&self, id: AssetId) -> bool{
+             self.ownership.record_of(id).and_then(|record| record.category).is_some()
+         }
+        /// Removes the category of an asset, only the owner can do it
+        #[ink(message, selector = "0x838708A4")]
+        pub fn asset_category_delete(&mut self,  id: AssetId) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetCategoryDelete, Some(id))?;
+            // check the data is not locked from the validation
+            self.check_unlocked(id, UpdateKind::Category)?;
+            // check the cooldown since the last update
+            self.check_update_cooldown(id, UpdateKind::Category)?;
+            // remove category
+            self.metadata.category_unassign(self.ownership.record_mut(id)?).ok_or(Error::AssetNotFound)?;
+            self.asset_updated(caller, id, UpdateKind::Category);
+            Ok(())
+        }
+        /// Adds the  location of an asset by coordinates in decimal format, comma separated: xxx.xxxxxxx,yyyy.yyyyyy only owner
+        /// and the carrier of the asset can do it, any shipper when the asset has no carrier
+        #[ink(message, selector = "0xB642AC0C")]
+        pub fn asset_location_new(&mut self,  id: AssetId, location: Hash) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetLocationNew, Some(id))?;
+            self.asset_data_insert(caller, id, UpdateKind::Location, location)
+        }
+        /// Appends a location to the route of an asset, replacing the latest location returned from `asset_location_get`,
+        /// so a shipper records every point of the route, the oldest points are dropped beyond the last 256. Only the owner and the carrier of the asset can do it, any
+        /// shipper when the asset has no carrier. Returns the sequence of the point in the route, from 0.
+        #[ink(message, selector = "0x407C9C34")]
+        pub fn asset_location_append(&mut self, id: AssetId, location: Hash) -> Result<u32, Error> {
+            let caller = self.authorize(Message::AssetLocationAppend, Some(id))?;
+            // check the data is not locked from the validation
+            self.check_unlocked(id, UpdateKind::Location)?;
+            // check the cooldown since the last update
+            self.check_update_cooldown(id, UpdateKind::Location)?;
+            self.ownership.record_mut(id)?.replace(UpdateKind::Location, location)?;
+            let sequence = self.location_record(caller, id, location);
+            self.asset_updated(caller, id, UpdateKind::Location);
+            Ok(sequence)
+        }
+        /// Returns a page of the route of an asset, oldest first: the points from sequence `from` included to `to`
+        /// excluded with the account that recorded them, their block and timestamp, starting from the oldest point kept.
+        /// The cursor of the next page is the sequence of its first point.
+        #[ink(message, selector = "0x5B4F0106")]
+        pub fn asset_location_history(&self, id: AssetId, from: u32, to: u32) -> Page<LocationPoint> {
+            let from = from.max(self.locations.points_first(id));
+            let to = to.min(self.locations.points_count(id));
+            let points = self.locations.points_of(id, from, to.min(from.saturating_add(PAGE_LIMIT)));
+            pagination::page(points, from, to)
+        }
+        /// Returns the number of points recorded in the route of an asset, including the points dropped from it
+        #[ink(message, selector = "0x1C630390")]
+        pub fn asset_location_count(&self, id: AssetId) -> u32 {
+            self.locations.points_count(id)
+        }
+        /// Writes one location to all the assets of a shipment in transit or of a list, replacing their previous
+        /// location, the caller must be able to write the location of every asset. One `AssetUpdate` event is emitted
+        /// for each asset. Returns the number of assets updated.
+        #[ink(message, selector = "0x4DA499A5")]
+        pub fn asset_location_update_batch(&mut self, selection: AssetSelection, location: Hash) -> Result<u32, Error> {
+            let ids = match selection {
+                AssetSelection::Assets(ids) => {
+                    if ids.is_empty() || ids.len() > MAX_ITEMS {
+                        return Err(Error::NotAllowed)
+                    }
+                    ids
+                }
+                AssetSelection::Shipment(manifest_id) => {
+                    let manifest = self.shipments.get(manifest_id).ok_or(Error::ManifestNotFound)?;
+                    if self.shipments.cancellation_of(manifest_id).is_some() {
+                        return Err(Error::ShipmentCancelled)
+                    }
+                    if self.shipments.delivery_of(manifest_id).is_some() {
+                        return Err(Error::NotAllowed)
+                    }
+                    manifest.items.iter().map(|item| item.id).collect()
+                }
             };
-            // remove description
-            assetlocation.remove_entry();
-            self.env().emit_event(AssetUpdate {
-                from: caller,
+            // check every asset before writing any location
+            let mut caller = self.env().caller();
+            for (position, id) in ids.iter().enumerate() {
+                if ids[..position].contains(id) {
+                    return Err(Error::DuplicatedData)
+                }
+                caller = self.authorize(Message::AssetLocationNew, Some(*id))?;
+                self.check_update_cooldown(*id, UpdateKind::Location)?;
+            }
+            for id in ids.iter() {
+                self.ownership.record_mut(*id)?.replace(UpdateKind::Location, location)?;
+                self.location_record(caller, *id, location);
+                self.asset_updated(caller, *id, UpdateKind::Location);
+            }
+            Ok(ids.len() as u32)
+        }
+        /// Returns the location coordinates of an asset
+        #[ink(message, selector = "0xFF0F4A10")]
+        pub fn asset_location_get(&self, id: AssetId) ->  Option<Hash>{
+           self.ownership.record_of(id).and_then(|record| record.get(UpdateKind::Location))
+        }
+        /// Verify if there is a location stored for an asset id
+        #[ink(message, selector = "0x137411D9")]
+        pub fn asset_location_verify(&self, id: AssetId) -> bool{
+            self.ownership.record_of(id).is_some_and(|record| record.contains(UpdateKind::Location))
+        }
+        /// Remove the location of an asset id, only the owner can do it
+        #[ink(message, selector = "0x11013B22")]
+        pub fn asset_location_delete(&mut self,  id: AssetId) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetLocationDelete, Some(id))?;
+            self.asset_data_remove(caller, id, UpdateKind::Location)
+        }
+        /// Add other metadata to an asset as ipfs address, only the owner can do it
+        #[ink(message, selector = "0xD3A57C10")]
+        pub fn asset_metadata_new(&mut self,  id: AssetId, metadata: Hash) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetMetadataNew, Some(id))?;
+            self.asset_data_insert(caller, id, UpdateKind::Metadata, metadata)
+        }
+        /// Returns the metada ipfs address of an asset
+        #[ink(message, selector = "0xC3B5815A")]
+        pub fn asset_metadata_get(&self, id: AssetId) ->  Option<Hash>{
+           self.ownership.record_of(id).and_then(|record| record.get(UpdateKind::Metadata))
+        }
+        /// Verifies if there is metadata stored for an asset id
+        #[ink(message, selector = "0xF548BC7E")]
+        pub fn asset_metadata_verify(&self, id: AssetId) -> bool{
+            self.ownership.record_of(id).is_some_and(|record| record.contains(UpdateKind::Metadata))
+        }
+        /// Removes metadata of an asset id, only the owner can do it
+        #[ink(message, selector = "0xC07F71EC")]
+        pub fn asset_metadata_delete(&mut self,  id: AssetId) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetMetadataDelete, Some(id))?;
+            self.asset_data_remove(caller, id, UpdateKind::Metadata)
+        }
+        /// Stores or replaces the address of the encrypted private data of an asset, only the owner can do it.
+        /// The data must be encrypted with a new key: the key grants of the previous data are removed.
+        #[ink(message, selector = "0xA58D9B97")]
+        pub fn asset_private_set(&mut self, id: AssetId, data: Hash) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetPrivateSet, Some(id))?;
+            // check the cooldown since the last update
+            self.check_update_cooldown(id, UpdateKind::Private)?;
+            self.privacy.set(id, data);
+            self.asset_updated(caller, id, UpdateKind::Private);
+            Ok(())
+        }
+        /// Returns the address of the encrypted private data of an asset
+        #[ink(message, selector = "0x8CEF03B3")]
+        pub fn asset_private_get(&self, id: AssetId) -> Option<Hash> {
+            self.privacy.data_of(id)
+        }
+        /// Removes the encrypted private data of an asset and its key grants, only the owner can do it
+        #[ink(message, selector = "0x8D9AF4B5")]
+        pub fn asset_private_delete(&mut self, id: AssetId) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetPrivateDelete, Some(id))?;
+            // check the cooldown since the last update
+            self.check_update_cooldown(id, UpdateKind::Private)?;
+            self.privacy.remove(id).ok_or(Error::CannotRemove)?;
+            self.asset_updated(caller, id, UpdateKind::Private);
+            Ok(())
+        }
+        /// Grants an account the key of the private data of an asset, storing the hash of the key wrapped
+        /// with the public key of the account, only the owner can do it
+        #[ink(message, selector = "0x4B38F8CD")]
+        pub fn asset_private_grant(&mut self, id: AssetId, account: AccountId, wrapped_key_hash: Hash) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            self.authorize(Message::AssetPrivateGrant, Some(id))?;
+            self.privacy.grant(id, account, wrapped_key_hash)?;
+            self.env().emit_event(PrivateKeyGranted {
+                id,
+                account,
+                wrapped_key: Some(wrapped_key_hash),
+            });
+            Ok(())
+        }
+        /// Revokes the key grant of an account on the private data of an asset, only the owner can do it.
+        /// The account may still hold the key: store the private data encrypted with a new key to lock it out.
+        #[ink(message, selector = "0xC0412C08")]
+        pub fn asset_private_revoke(&mut self, id: AssetId, account: AccountId) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            self.authorize(Message::AssetPrivateRevoke, Some(id))?;
+            self.privacy.revoke(id, &account)?;
+            self.env().emit_event(PrivateKeyGranted {
+                id,
+                account,
+                wrapped_key: None,
+            });
+            Ok(())
+        }
+        /// Returns the hash of the wrapped key of the private data of an asset granted to an account
+        #[ink(message, selector = "0x13C86D33")]
+        pub fn asset_private_key_get(&self, id: AssetId, account: AccountId) -> Option<Hash> {
+            self.privacy.key_of(id, &account)
+        }
+        /// Returns a page of the accounts granted the key of the private data of an asset
+        #[ink(message, selector = "0xF5270B3B")]
+        pub fn asset_private_grantees(&self, id: AssetId, cursor: Cursor, limit: u32) -> Page<AccountId> {
+            pagination::page_of(self.privacy.grantees_of(id), cursor, limit)
+        }
+        /// Allows an account to view the private data of an asset, only the owner can do it
+        #[ink(message, selector = "0x43AE4694")]
+        pub fn asset_viewer_grant(&mut self, id: AssetId, account: AccountId) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            self.authorize(Message::AssetViewerGrant, Some(id))?;
+            self.privacy.viewer_add(id, account)?;
+            self.env().emit_event(ViewerUpdated {
+                id,
+                account,
+                allowed: true,
+            });
+            Ok(())
+        }
+        /// Disallows an account to view the private data of an asset, only the owner can do it
+        #[ink(message, selector = "0xE32A8E39")]
+        pub fn asset_viewer_revoke(&mut self, id: AssetId, account: AccountId) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            self.authorize(Message::AssetViewerRevoke, Some(id))?;
+            self.privacy.viewer_remove(id, &account)?;
+            self.env().emit_event(ViewerUpdated {
+                id,
+                account,
+                allowed: false,
+            });
+            Ok(())
+        }
+        /// Returns a page of the accounts allowed to view the private data of an asset
+        #[ink(message, selector = "0x2C798524")]
+        pub fn asset_viewers(&self, id: AssetId, cursor: Cursor, limit: u32) -> Page<AccountId> {
+            pagination::page_of(self.privacy.viewers_of(id), cursor, limit)
+        }
+        /// Returns true if an account can view the private data of an asset: the owner and the viewers allowed from it
+        #[ink(message, selector = "0x1FEA4775")]
+        pub fn can_view_private(&self, id: AssetId, account: AccountId) -> bool {
+            self.ownership.owner_of(id) == Some(account) || self.privacy.is_viewer(id, &account)
+        }
+        /// Commits the Blake2x256 hash of a secret sealed in the packaging of an asset, only the producer of record
+        /// can do it while it owns the asset. The commitment can be written once.
+        #[ink(message, selector = "0x9732364F")]
+        pub fn asset_authenticity_commit(&mut self, id: AssetId, commitment: Hash) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetAuthenticityCommit, Some(id))?;
+            if self.provenance.producer_of(id) != Some(caller) || self.ownership.owner_of(id) != Some(caller) {
+                return Err(Error::NotProducer)
+            }
+            self.authenticity.commit(id, commitment)?;
+            self.asset_update_log(caller, id, UpdateKind::Authenticity);
+            Ok(())
+        }
+        /// Reveals the secret sealed in the packaging of an asset, marking the asset as physically verified
+        /// when its hash matches the commitment of the producer. It can be done once from any account.
+        #[ink(message, selector = "0xD7BEBE78")]
+        pub fn asset_authenticity_reveal(&mut self, id: AssetId, secret: Vec<u8>) -> Result<(), Error> {
+            //check if asset id is present in the storage
+            let caller = self.authorize(Message::AssetAuthenticityReveal, Some(id))?;
+            let block = self.env().block_number();
+            self.authenticity.reveal(id, &secret, Reveal {
+                account: caller,
+                block,
+            })?;
+            self.asset_update_log(caller, id, UpdateKind::Authenticity);
+            Ok(())
+        }
+        /// Returns the account and the block of the physical verification of an asset
+        #[ink(message, selector = "0x441246B0")]
+        pub fn asset_authenticity_get(&self, id: AssetId) -> Option<Reveal> {
+            self.authenticity.reveal_of(id)
+        }
+        /// Verifies if the producer has committed the secret of an asset
+        #[ink(message, selector = "0x39A86698")]
+        pub fn asset_authenticity_committed(&self, id: AssetId) -> bool {
+            self.authenticity.is_committed(id)
+        }
+        /// Commits the Blake2x256 hash of a sensitive value (e.g. a negotiated price) followed by a 32 bytes salt,
+        /// only the owner can do it. The previous commitment must have been revealed.
+        #[ink(message, selector = "0xC2AD66BB")]
+        pub fn asset_commit(&mut self, id: AssetId, commitment: Hash) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetCommit, Some(id))?;
+            self.sealed.commit(id, Commitment {
+                hash: commitment,
+                committer: caller,
+                block: self.env().block_number(),
+            })?;
+            self.asset_updated(caller, id, UpdateKind::Sealed);
+            Ok(())
+        }
+        /// Reveals the value and the salt of the commitment of an asset, disclosing the value with the block of its
+        /// commitment. Any account knowing them can do it.
+        #[ink(message, selector = "0xD38F92E0")]
+        pub fn asset_reveal(&mut self, id: AssetId, value: Vec<u8>, salt: Hash) -> Result<(), Error> {
+            //check if asset id is present in the storage
+            let caller = self.authorize(Message::AssetReveal, Some(id))?;
+            let block = self.env().block_number();
+            self.sealed.reveal(id, value, &salt, block)?;
+            self.asset_updated(caller, id, UpdateKind::Sealed);
+            Ok(())
+        }
+        /// Returns the commitment of an asset waiting for its reveal
+        #[ink(message, selector = "0x85699B7D")]
+        pub fn asset_commitment_get(&self, id: AssetId) -> Option<Commitment> {
+            self.sealed.pending_of(id)
+        }
+        /// Returns a page of the values disclosed of an asset, oldest first
+        #[ink(message, selector = "0x5C6AA2DB")]
+        pub fn asset_disclosures(&self, id: AssetId, cursor: Cursor, limit: u32) -> Page<Disclosure> {
+            pagination::page_of(self.sealed.disclosures_of(id), cursor, limit)
+        }
+        /// Binds the UID of the NFC/RFID tag applied to the product to an asset, only the owner can do it.
+        /// A tag can be bound to one asset only and an asset can have one tag only.
+        #[ink(message, selector = "0x202D0805")]
+        pub fn asset_tag_bind(&mut self, id: AssetId, uid: Hash) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetTagBind, Some(id))?;
+            self.tags.bind(uid, id)?;
+            self.asset_update_log(caller, id, UpdateKind::Tag);
+            Ok(())
+        }
+        /// Binds a tag to an asset replacing the previous bindings of the tag and of the asset,
+        /// only administrator can do it
+        #[ink(message, selector = "0x5D0894DF")]
+        pub fn asset_tag_rebind(&mut self, id: AssetId, uid: Hash) -> Result<(), Error> {
+            // check for administrator
+            let caller = self.authorize(Message::AssetTagRebind, Some(id))?;
+            if let Some(previous) = self.tags.asset_of(&uid) {
+                if previous != id {
+                    self.asset_update_log(caller, previous, UpdateKind::Tag);
+                }
+            }
+            self.tags.rebind(uid, id);
+            self.asset_update_log(caller, id, UpdateKind::Tag);
+            Ok(())
+        }
+        /// Returns the asset bound to the UID of a tag
+        #[ink(message, selector = "0xF8CADD7D")]
+        pub fn asset_by_tag(&self, uid: Hash) -> Option<AssetId> {
+            self.tags.asset_of(&uid)
+        }
+        /// Returns the UID of the tag bound to an asset
+        #[ink(message, selector = "0x3600794A")]
+        pub fn asset_tag_get(&self, id: AssetId) -> Option<Hash> {
+            self.tags.tag_of(id)
+        }
+        /// Verifies if an asset is flagged as possible duplicate, because its photo or metadata is stored for another asset
+        #[ink(message, selector = "0x22BCB40A")]
+        pub fn asset_duplicate_verify(&self, id: AssetId) -> bool {
+            self.metadata.is_duplicate(id)
+        }
+        /// Removes the possible duplicate flag of an asset after a review, only administrator can do it
+        #[ink(message, selector = "0xD6464290")]
+        pub fn asset_duplicate_clear(&mut self, id: AssetId) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::AssetDuplicateClear, Some(id))?;
+            if !self.metadata.duplicate_clear(id) {
+                return Err(Error::CannotRemove)
+            }
+            Ok(())
+        }
+        /// Returns a page of the assets referencing a content hash as photo, metadata, report of a lab result or sensor
+        /// log of a compliance certificate
+        #[ink(message, selector = "0x25187405")]
+        pub fn assets_by_content_hash(&self, content: Hash, cursor: Cursor, limit: u32) -> Page<AssetId> {
+            let assets = self.contents.assets_of(&content, cursor, pagination::limit(limit));
+            pagination::page(assets, cursor, self.contents.assets_count(&content))
+        }
+        /// Stores the quantity and the unit of measure of a bulk asset, only the owner can do it
+        #[ink(message, selector = "0x46B74EA6")]
+        pub fn asset_quantity_set(&mut self, id: AssetId, amount: u128, unit: Unit) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetQuantitySet, Some(id))?;
+            self.quantities.set(id, Quantity { amount, unit });
+            self.asset_update_log(caller, id, UpdateKind::Quantity);
+            Ok(())
+        }
+        /// Returns the quantity and the unit of measure of an asset
+        #[ink(message, selector = "0xF7AAE81D")]
+        pub fn asset_quantity_get(&self, id: AssetId) -> Option<Quantity> {
+            self.quantities.quantity_of(id)
+        }
+        /// Transfers `amount` of the quantity of an asset, only the owner can do it. The whole quantity transfers
+        /// the asset, a part of it is split in a new asset (lot) owned from `to`. Returns the id of the transferred asset.
+        #[ink(message, selector = "0x0A2D0159")]
+        pub fn asset_quantity_split_transfer(&mut self, id: AssetId, to: AccountId, amount: u128) -> Result<AssetId, Error> {
+            //check if asset id is present in the storage and the signer can transfer it
+            self.authorize(Message::AssetQuantitySplitTransfer, Some(id))?;
+            if self.bridge.is_locked(id) {
+                return Err(Error::AssetBridged)
+            }
+            if self.holds.is_held(id) {
+                return Err(Error::AssetOnHold)
+            }
+            self.check_in_stock(id)?;
+            // the lots cannot be split from the collateral of a lien
+            if self.liens.is_encumbered(id) {
+                return Err(Error::AssetEncumbered)
+            }
+            let owner = self.ownership.owner_of(id).ok_or(Error::AssetNotFound)?;
+            let quantity = self.quantities.quantity_of(id).ok_or(Error::QuantityNotFound)?;
+            self.check_reservation(id, &to)?;
+            self.check_not_pending(id)?;
+            if amount == quantity.amount {
+                self.asset_transfer_from(&owner, &to, id, Vec::new(), false)?;
+                return Ok(id)
+            }
+            if to == AccountId::from([0x0; 32]) {
+                return Err(Error::NotAllowed)
+            }
+            // search for the first free id of the lots
+            let mut skip = 0;
+            let mut lot_id = self.quantities.next_lot_id(skip);
+            while self.ownership.exists(lot_id) {
+                if lot_id == AssetId::MAX {
+                    return Err(Error::CannotInsert)
+                }
+                skip += 1;
+                lot_id = self.quantities.next_lot_id(skip);
+            }
+            self.recipient_check(&owner, &to, lot_id, Vec::new(), false)?;
+            self.quantities.split(id, lot_id, amount)?;
+            self.ownership.add(&to, lot_id)?;
+            // the lot keeps the provenance of its parent
+            if let Some(record) = self.provenance.mint_of(id) {
+                // the deposit stays locked on the asset split
+                self.provenance.record(lot_id, MintRecord {
+                    deposit: 0,
+                    ..record
+                })?;
+            }
+            self.stats.transfers += 1;
+            self.transferred(owner, to, lot_id);
+            Ok(lot_id)
+        }
+        /// Stores or replaces the weight, the dimensions and the packaging of an asset, only the owner can do it
+        #[ink(message, selector = "0x4F60F530")]
+        pub fn asset_physical_set(&mut self, id: AssetId, attributes: PhysicalAttributes) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetPhysicalSet, Some(id))?;
+            // check the cooldown since the last update
+            self.check_update_cooldown(id, UpdateKind::Physical)?;
+            self.physical.set(id, attributes);
+            self.asset_updated(caller, id, UpdateKind::Physical);
+            Ok(())
+        }
+        /// Returns the weight, the dimensions and the packaging of an asset
+        #[ink(message, selector = "0xA44A6444")]
+        pub fn asset_physical_get(&self, id: AssetId) -> Option<PhysicalAttributes> {
+            self.physical.attributes_of(id)
+        }
+        /// Deletes the weight, the dimensions and the packaging of an asset, only the owner can do it
+        #[ink(message, selector = "0xF7F6024B")]
+        pub fn asset_physical_delete(&mut self, id: AssetId) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetPhysicalDelete, Some(id))?;
+            // check the cooldown since the last update
+            self.check_update_cooldown(id, UpdateKind::Physical)?;
+            self.physical.remove(id).ok_or(Error::CannotRemove)?;
+            self.asset_updated(caller, id, UpdateKind::Physical);
+            Ok(())
+        }
+        /// Sets the depreciation schedule of an asset: method, acquisition cost, salvage value, yearly rate in basis points
+        /// and timestamp of the start in milliseconds. Only the owner can do it.
+        #[ink(message, selector = "0x75ADDD18")]
+        pub fn asset_depreciation_set(&mut self, id: AssetId, schedule: Schedule) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            let caller = self.authorize(Message::AssetDepreciationSet, Some(id))?;
+            if !schedule.is_valid() {
+                return Err(Error::NotAllowed)
+            }
+            // check the cooldown since the last update
+            self.check_update_cooldown(id, UpdateKind::Depreciation)?;
+            self.depreciation.set(id, schedule);
+            self.asset_updated(caller, id, UpdateKind::Depreciation);
+            Ok(())
+        }
+        /// Returns the depreciation schedule of an asset
+        #[ink(message, selector = "0xB81853F0")]
+        pub fn asset_depreciation_get(&self, id: AssetId) -> Option<Schedule> {
+            self.depreciation.schedule_of(id)
+        }
+        /// Deletes the depreciation schedule of an asset, only the owner can do it
+        #[ink(message, selector = "0xE2FF7EFB")]
+        pub fn asset_depreciation_delete(&mut self, id: AssetId) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            let caller = self.authorize(Message::AssetDepreciationDelete, Some(id))?;
+            // check the cooldown since the last update
+            self.check_update_cooldown(id, UpdateKind::Depreciation)?;
+            self.depreciation.remove(id).ok_or(Error::CannotRemove)?;
+            self.asset_updated(caller, id, UpdateKind::Depreciation);
+            Ok(())
+        }
+        /// Returns the book value of an asset at the timestamp of the current block, computed from its depreciation schedule
+        #[ink(message, selector = "0x2692B6D1")]
+        pub fn asset_book_value(&self, id: AssetId) -> Option<Balance> {
+            self.depreciation
+                .schedule_of(id)
+                .map(|schedule| schedule.book_value(self.env().block_timestamp()))
+        }
+        /// Authorizes or revokes a service provider on an asset, only the owner can do it. The authorizations are dropped
+        /// when the asset is transferred.
+        #[ink(message, selector = "0xF615D92A")]
+        pub fn asset_service_provider_authorize(&mut self, id: AssetId, provider: AccountId, authorized: bool) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            self.authorize(Message::AssetServiceProviderAuthorize, Some(id))?;
+            self.services.authorize(id, provider, authorized);
+            Ok(())
+        }
+        /// Returns a page of the service providers authorized on an asset
+        #[ink(message, selector = "0x92D18637")]
+        pub fn asset_service_providers(&self, id: AssetId, cursor: Cursor, limit: u32) -> Page<AccountId> {
+            pagination::page_of(self.services.providers_of(id), cursor, limit)
+        }
+        /// Appends a service to the log of an asset with the hash of its report and the reading of the odometer or of
+        /// the usage counter, only a service provider authorized on the asset can do it. The reading cannot be lower
+        /// than the one of the previous service.
+        #[ink(message, selector = "0x7C8451BA")]
+        pub fn asset_service_add(&mut self, id: AssetId, service_type: ServiceType, report: Hash, usage: u64) -> Result<(), Error> {
+            //check if asset id is present in the storage
+            let caller = self.authorize(Message::AssetServiceAdd, Some(id))?;
+            self.services.record(id, ServiceEntry {
+                provider: caller,
+                service_type,
+                report,
+                usage,
+                block: self.env().block_number(),
+            })?;
+            self.asset_update_log(caller, id, UpdateKind::Service);
+            Ok(())
+        }
+        /// Returns the number of services in the log of an asset
+        #[ink(message, selector = "0x70FA1E71")]
+        pub fn asset_service_count(&self, id: AssetId) -> u32 {
+            self.services.entries_count(id)
+        }
+        /// Returns a page of the services of the log of an asset, oldest first
+        #[ink(message, selector = "0x74FC21F0")]
+        pub fn asset_service_log(&self, id: AssetId, cursor: Cursor, limit: u32) -> Page<ServiceEntry> {
+            let entries = self.services.entries_of(id, cursor, pagination::limit(limit));
+            pagination::page(entries, cursor, self.services.entries_count(id))
+        }
+        /// Appends the hash of a note to an asset, only the owner, the shippers of the asset, the accounts with the
+        /// CAN_VALIDATE capability and the administrators can do it. The note records the role of the author.
+        #[ink(message, selector = "0xD275CC52")]
+        pub fn asset_note_add(&mut self, id: AssetId, content: Hash) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer takes part in its supply chain
+            let caller = self.authorize(Message::AssetNoteAdd, Some(id))?;
+            let block = self.env().block_number();
+            self.notes.append(id, Note {
+                author: caller,
+                role: self.roles.active_role_of(&caller),
+                content,
+                block,
+            });
+            self.asset_update_log(caller, id, UpdateKind::Note);
+            Ok(())
+        }
+        /// Returns the number of notes of an asset
+        #[ink(message, selector = "0x2D60202D")]
+        pub fn asset_notes_count(&self, id: AssetId) -> u32 {
+            self.notes.notes_count(id)
+        }
+        /// Returns a page of the notes of an asset, oldest first
+        #[ink(message, selector = "0x7268250C")]
+        pub fn asset_notes(&self, id: AssetId, cursor: Cursor, limit: u32) -> Page<Note> {
+            let notes = self.notes.notes_of(id, cursor, pagination::limit(limit));
+            pagination::page(notes, cursor, self.notes.notes_count(id))
+        }
+        /// Files a lien on an asset in favour of the signer, securing `amount` with the agreement hashed in `document`.
+        /// The lien is in force once the owner consents to it. Returns the id of the lien.
+        #[ink(message, selector = "0x86F555CA")]
+        pub fn asset_lien_new(&mut self, id: AssetId, amount: Balance, document: Hash) -> Result<LienId, Error> {
+            //check if asset id is present in the storage
+            let creditor = self.authorize(Message::AssetLienNew, Some(id))?;
+            let lien_id = self.liens.file(Lien {
+                id,
+                creditor,
+                amount,
+                document,
+                block: self.env().block_number(),
+                status: LienStatus::Pending,
+            });
+            self.env().emit_event(LienUpdated {
+                lien_id,
+                id,
+                creditor,
+                status: LienStatus::Pending,
+            });
+            Ok(lien_id)
+        }
+        /// Consents to or rejects a pending lien on an asset, only the owner can do it. The asset cannot be transferred,
+        /// sold, split or deleted while a lien consented is in force.
+        #[ink(message, selector = "0xAB639674")]
+        pub fn asset_lien_consent(&mut self, lien_id: LienId, consent: bool) -> Result<(), Error> {
+            let lien = self.liens.get(lien_id).ok_or(Error::LienNotFound)?;
+            //check if the signer is the owner of the asset
+            self.authorize(Message::AssetLienConsent, Some(lien.id))?;
+            let status = if consent { LienStatus::Active } else { LienStatus::Rejected };
+            self.liens.update(lien_id, &[LienStatus::Pending], status)?;
+            self.env().emit_event(LienUpdated {
+                lien_id,
+                id: lien.id,
+                creditor: lien.creditor,
+                status,
+            });
+            Ok(())
+        }
+        /// Releases a pending or consented lien, only its creditor can do it
+        #[ink(message, selector = "0x7E0323A0")]
+        pub fn asset_lien_release(&mut self, lien_id: LienId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let lien = self.liens.get(lien_id).ok_or(Error::LienNotFound)?;
+            if lien.creditor != caller {
+                return Err(Error::NotAllowed)
+            }
+            self.liens.update(lien_id, &[LienStatus::Pending, LienStatus::Active], LienStatus::Released)?;
+            self.env().emit_event(LienUpdated {
+                lien_id,
+                id: lien.id,
+                creditor: lien.creditor,
+                status: LienStatus::Released,
+            });
+            Ok(())
+        }
+        /// Returns a page of the pending and consented liens of an asset, the encumbrances to check before a purchase
+        #[ink(message, selector = "0xEF080FEC")]
+        pub fn asset_liens(&self, id: AssetId, cursor: Cursor, limit: u32) -> Page<(LienId, Lien)> {
+            pagination::page_of(self.liens.liens_of(id), cursor, limit)
+        }
+        /// Returns a lien
+        #[ink(message, selector = "0xC7562A60")]
+        pub fn lien_get(&self, lien_id: LienId) -> Option<Lien> {
+            self.liens.get(lien_id)
+        }
+        /// Adds the native tokens transferred to the storage deposit of the caller on an asset, anyone can do it.
+        /// Returns the deposit of the caller.
+        #[ink(message, payable, selector = "0x4FAC23F8")]
+        pub fn deposit_top_up(&mut self, id: AssetId) -> Result<Balance, Error> {
+            //check if asset id is present in the storage
+            let caller = self.authorize(Message::DepositTopUp, Some(id))?;
+            let amount = self.env().transferred_balance();
+            if amount == 0 {
+                return Err(Error::InvalidPayment)
+            }
+            let deposit = self.deposits.top_up(id, caller, amount);
+            self.env().emit_event(DepositUpdated {
+                id,
+                account: caller,
+                deposit,
+            });
+            Ok(deposit)
+        }
+        /// Pays back the storage and mint deposits of the caller on an asset deleted. Returns the amount paid.
+        #[ink(message, selector = "0x134238B8")]
+        pub fn deposit_reclaim(&mut self, id: AssetId) -> Result<Balance, Error> {
+            let caller = self.env().caller();
+            let amount = self.deposits.reclaim(id, caller)?;
+            if self.env().transfer(caller, amount).is_err() {
+                self.deposits.unreclaim(id, caller, amount);
+                return Err(Error::PaymentFailed)
+            }
+            self.env().emit_event(DepositUpdated {
+                id,
+                account: caller,
+                deposit: 0,
+            });
+            Ok(amount)
+        }
+        /// Returns the total storage deposit on an asset
+        #[ink(message, selector = "0xE5EE7B41")]
+        pub fn deposit_get(&self, id: AssetId) -> Balance {
+            self.deposits.total_of(id)
+        }
+        /// Returns the storage deposit of an account on an asset
+        #[ink(message, selector = "0xD09BF337")]
+        pub fn deposit_of(&self, id: AssetId, account: AccountId) -> Balance {
+            self.deposits.deposit_of(id, account)
+        }
+        /// Returns the storage and mint deposits of an account on an asset deleted that can be reclaimed
+        #[ink(message, selector = "0x7B2860DF")]
+        pub fn deposit_reclaimable(&self, id: AssetId, account: AccountId) -> Balance {
+            self.deposits.reclaimable_of(id, account)
+        }
+        /// Places a legal hold on an asset with the hash of the court order, only an administrator can do it.
+        /// The asset cannot be transferred, sold, split or deleted until the hold is lifted.
+        #[ink(message, selector = "0x05307679")]
+        pub fn asset_hold_place(&mut self, id: AssetId, order: Hash) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is an administrator
+            let caller = self.authorize(Message::AssetHoldPlace, Some(id))?;
+            self.holds.place(id, Hold {
+                order,
+                placed_by: caller,
+                block: self.env().block_number(),
+            })?;
+            self.env().emit_event(AssetHoldPlaced {
+                id,
+                order,
+            });
+            Ok(())
+        }
+        /// Lifts the legal hold of an asset, only an administrator can do it
+        #[ink(message, selector = "0xF3E8BEAC")]
+        pub fn asset_hold_lift(&mut self, id: AssetId) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is an administrator
+            self.authorize(Message::AssetHoldLift, Some(id))?;
+            let hold = self.holds.lift(id)?;
+            self.env().emit_event(AssetHoldLifted {
+                id,
+                order: hold.order,
+            });
+            Ok(())
+        }
+        /// Returns the legal hold of an asset
+        #[ink(message, selector = "0x83CF9E4D")]
+        pub fn asset_hold_get(&self, id: AssetId) -> Option<Hold> {
+            self.holds.hold_of(id)
+        }
+        /// Marks an asset received back from `from` as Returned with the hash of the reason, only the owner can do it.
+        /// The asset is unlisted and cannot be transferred, listed or sold until it is inspected and restocked.
+        #[ink(message, selector = "0x381E2762")]
+        pub fn asset_return_receive(&mut self, id: AssetId, from: AccountId, reason: Hash) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            self.authorize(Message::AssetReturnReceive, Some(id))?;
+            self.check_in_stock(id)?;
+            let block = self.env().block_number();
+            self.returns.receive(id, ReturnRecord {
+                from,
+                reason,
+                received: block,
+                inspector: None,
+                report: None,
+                status: StockStatus::Returned,
+                block,
+            });
+            self.sales.unlist(id);
+            self.stock_status_set(id, StockStatus::Returned)
+        }
+        /// Records the inspection of a returned asset with the hash of the report, only the accounts with the
+        /// CAN_VALIDATE capability and the administrators can do it
+        #[ink(message, selector = "0x78AE43CE")]
+        pub fn asset_return_inspect(&mut self, id: AssetId, report: Hash) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can validate it
+            let caller = self.authorize(Message::AssetReturnInspect, Some(id))?;
+            let block = self.env().block_number();
+            self.returns.inspect(id, caller, report, block)?;
+            self.stock_status_set(id, StockStatus::Inspected)
+        }
+        /// Restocks an inspected asset, back InStock, only the owner can do it
+        #[ink(message, selector = "0xBFB5E7F8")]
+        pub fn asset_return_restock(&mut self, id: AssetId) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            self.authorize(Message::AssetReturnRestock, Some(id))?;
+            let block = self.env().block_number();
+            self.returns.close(id, StockStatus::InStock, block)?;
+            self.stock_status_set(id, StockStatus::InStock)
+        }
+        /// Routes an inspected asset to disposal, only the owner can do it. The asset stays blocked.
+        #[ink(message, selector = "0xD25E3EAE")]
+        pub fn asset_return_to_disposal(&mut self, id: AssetId) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            self.authorize(Message::AssetReturnToDisposal, Some(id))?;
+            let block = self.env().block_number();
+            self.returns.close(id, StockStatus::Disposal, block)?;
+            self.stock_status_set(id, StockStatus::Disposal)
+        }
+        /// Returns the stock status of an asset
+        #[ink(message, selector = "0x5178487F")]
+        pub fn asset_stock_status(&self, id: AssetId) -> Option<StockStatus> {
+            self.ownership.record_of(id).map(|record| record.status)
+        }
+        /// Returns the number of returns of an asset
+        #[ink(message, selector = "0x194F6AC7")]
+        pub fn asset_returns_count(&self, id: AssetId) -> u32 {
+            self.returns.returns_count(id)
+        }
+        /// Returns a page of the restocking records of an asset, oldest first
+        #[ink(message, selector = "0xA61781A9")]
+        pub fn asset_returns(&self, id: AssetId, cursor: Cursor, limit: u32) -> Page<ReturnRecord> {
+            let returns = self.returns.returns_of(id, cursor, pagination::limit(limit));
+            pagination::page(returns, cursor, self.returns.returns_count(id))
+        }
+        /// Disposes of an asset at the end of its life with the code of the disposal method and the hash of the
+        /// evidence, only the owner can do it. The disposal is terminal: the asset is unlisted and retained
+        /// permanently, it can no longer be transferred, sold or deleted. A returned asset is disposed of once
+        /// routed to disposal.
+        #[ink(message, selector = "0x1081D3FB")]
+        pub fn asset_dispose(&mut self, id: AssetId, method_code: u32, evidence_hash: Hash) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            let caller = self.authorize(Message::AssetDispose, Some(id))?;
+            if self.bridge.is_locked(id) {
+                return Err(Error::AssetBridged)
+            }
+            if self.holds.is_held(id) {
+                return Err(Error::AssetOnHold)
+            }
+            if self.liens.is_encumbered(id) {
+                return Err(Error::AssetEncumbered)
+            }
+            if self.asset_stock_status(id) != Some(StockStatus::Disposal) {
+                self.check_in_stock(id)?;
+            }
+            let block = self.env().block_number();
+            self.disposals.dispose(id, Disposal {
+                method: method_code,
+                evidence: evidence_hash,
+                disposer: caller,
+                block,
+            })?;
+            self.sales.unlist(id);
+            self.ownership.record_mut(id)?.status = StockStatus::Disposed;
+            self.env().emit_event(AssetDisposed {
+                id,
+                disposer: caller,
+                method: method_code,
+                evidence: evidence_hash,
+            });
+            Ok(())
+        }
+        /// Returns the disposal of an asset
+        #[ink(message, selector = "0x85808E74")]
+        pub fn asset_disposal_get(&self, id: AssetId) -> Option<Disposal> {
+            self.disposals.disposal_of(id)
+        }
+        /// Returns a page of the assets disposed of, oldest first
+        #[ink(message, selector = "0xB5BC5009")]
+        pub fn disposals(&self, cursor: Cursor, limit: u32) -> Page<AssetId> {
+            let disposed = self.disposals.list(cursor, pagination::limit(limit));
+            pagination::page(disposed, cursor, self.disposals.disposals_count())
+        }
+        /// Creates a new recycled-material asset owned from the signer, claiming the materials of the disposed
+        /// assets `sources`, only recyclers can do it. The materials of an asset are claimed once. The native tokens
+        /// transferred are locked as its deposit, at least the mint deposit of the configuration.
+        #[ink(message, payable, selector = "0x38CE5805")]
+        pub fn asset_recycle(&mut self, id: AssetId, sources: Vec<AssetId>) -> Result<(), Error> {
+            // check for recycler
+            let caller = self.authorize(Message::AssetRecycle, None)?;
+            for source in sources.iter() {
+                if self.asset_stock_status(*source).ok_or(Error::AssetNotFound)? != StockStatus::Disposed {
+                    return Err(Error::AssetNotDisposed)
+                }
+            }
+            self.lineage.check_claimable(&sources)?;
+            self.mint(caller, id)?;
+            self.lineage.recycle(id, sources.clone())?;
+            self.env().emit_event(AssetRecycled {
                 id,
+                sources,
+            });
+            Ok(())
+        }
+        /// Returns a page of the disposed assets the materials of a recycled asset were claimed from
+        #[ink(message, selector = "0x8F64E5B0")]
+        pub fn asset_recycled_sources(&self, id: AssetId, cursor: Cursor, limit: u32) -> Page<AssetId> {
+            pagination::page_of(self.lineage.sources_of(id), cursor, limit)
+        }
+        /// Returns the recycled asset the materials of a disposed asset were claimed into
+        #[ink(message, selector = "0x86153F25")]
+        pub fn asset_recycled_into(&self, id: AssetId) -> Option<AssetId> {
+            self.lineage.recycled_into(id)
+        }
+        /// Retires asset `old_id` and creates the asset `new_id` remanufactured from it, owned from the signer, only
+        /// the owner of the retired asset can do it. The two assets are linked both ways, the retired asset is
+        /// unlisted and retained permanently. The native tokens transferred are locked as the deposit of the new
+        /// asset, at least the mint deposit of the configuration.
+        #[ink(message, payable, selector = "0x956D4063")]
+        pub fn asset_remanufacture(&mut self, old_id: AssetId, new_id: AssetId) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            let caller = self.authorize(Message::AssetRemanufacture, Some(old_id))?;
+            if self.bridge.is_locked(old_id) {
+                return Err(Error::AssetBridged)
+            }
+            if self.holds.is_held(old_id) {
+                return Err(Error::AssetOnHold)
+            }
+            if self.liens.is_encumbered(old_id) {
+                return Err(Error::AssetEncumbered)
+            }
+            self.check_in_stock(old_id)?;
+            self.mint(caller, new_id)?;
+            self.lineage.remanufacture(old_id, new_id)?;
+            self.sales.unlist(old_id);
+            self.ownership.record_mut(old_id)?.status = StockStatus::Remanufactured;
+            self.env().emit_event(AssetRemanufactured {
+                old_id,
+                new_id,
+            });
+            Ok(())
+        }
+        /// Returns the asset a retired asset was remanufactured into
+        #[ink(message, selector = "0x0FA759CF")]
+        pub fn asset_remanufactured_into(&self, id: AssetId) -> Option<AssetId> {
+            self.lineage.remanufactured_into(id)
+        }
+        /// Returns the retired asset an asset was remanufactured from
+        #[ink(message, selector = "0x4C000013")]
+        pub fn asset_remanufactured_from(&self, id: AssetId) -> Option<AssetId> {
+            self.lineage.remanufactured_from(id)
+        }
+        /// Stores the stock status of asset `id` and reports it
+        fn stock_status_set(&mut self, id: AssetId, status: StockStatus) -> Result<(), Error> {
+            self.ownership.record_mut(id)?.status = status;
+            self.env().emit_event(AssetReturnUpdated {
+                id,
+                status,
+            });
+            Ok(())
+        }
+        /// Returns the asset a lot has been split from
+        #[ink(message, selector = "0x8AF6D561")]
+        pub fn asset_parent_get(&self, id: AssetId) -> Option<AssetId> {
+            self.quantities.parent_of(id)
+        }
+        /// Validate an asset from an account with the CAN_VALIDATE capability
+        #[ink(message, selector = "0xA1F4B53D")]
+        pub fn asset_validation_new(&mut self,  id: AssetId, accountid: AccountId) -> Result<(), Error> {
+            // check for the validation capability
+            let caller = self.authorize(Message::AssetValidationNew, None)?;
+            //check if asset id is present in the storage
+            let record = self.ownership.record_of(id).ok_or(Error::AssetNotFound)?;
+            // an inspector validates only the assets of the categories of its accreditation
+            self.inspector_scope_check(&caller, record.category)?;
+            let category = record.category;
+            // check the lab result required from the category
+            let requirement = category.and_then(|category_id| self.laboratories.requirement_of(category_id));
+            if let Some(test_type) = requirement {
+                if !self.laboratories.has_passed(id, test_type) {
+                    return Err(Error::LabResultRequired)
+                }
+            }
+            // the signer must have bonded the stake answering for its validations
+            if self.stakes.bonded_of(&caller) < self.config.validator_stake {
+                return Err(Error::StakeRequired)
+            }
+            // a revoked validation is replaced, any other is kept
+            let record = self.ownership.record_mut(id)?;
+            if self.revocations.is_revoked(Credential::Validation(id)) {
+                record.invalidate()?;
+                self.revocations.clear(Credential::Validation(id));
+            }
+            // add validation if not already present
+            record.validate(accountid, caller)?;
+            self.validation_queue.remove(id);
+            self.stats.validations += 1;
+            self.activity_record(caller, ActivityKind::Validated, id, None);
+            self.reward(caller, Work::Validation);
+            // emit event to report the update
+            self.asset_update_log(caller, id, UpdateKind::Validation);
+            self.category_alert(category, Alert::Validated(id));
+            Ok(())
+        }
+        /// Requests the validation of an asset, queuing it in the work list of the validators, only the owner can do it
+        #[ink(message, selector = "0x9111CBC0")]
+        pub fn asset_validation_request(&mut self, id: AssetId) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            let caller = self.authorize(Message::AssetValidationRequest, Some(id))?;
+            if self.asset_validation_verify(id) {
+                return Err(Error::DuplicatedData)
+            }
+            let category = self.ownership.record_of(id).and_then(|record| record.category);
+            let block = self.env().block_number();
+            self.validation_queue.push(id, ValidationRequest {
+                requester: caller,
+                category,
+                block,
+            })
+        }
+        /// Returns the pending request of validation of an asset
+        #[ink(message, selector = "0xE649AE92")]
+        pub fn asset_validation_request_get(&self, id: AssetId) -> Option<ValidationRequest> {
+            self.validation_queue.request_of(id)
+        }
+        /// Returns the number of assets awaiting validation
+        #[ink(message, selector = "0x012F50CC")]
+        pub fn validation_queue_len(&self) -> u32 {
+            self.validation_queue.len()
+        }
+        /// Returns a page of the assets awaiting validation. The order of the requests is not kept when an asset
+        /// leaves the queue.
+        #[ink(message, selector = "0x0B5DD925")]
+        pub fn validation_queue(&self, cursor: Cursor, limit: u32) -> Page<AssetId> {
+            let assets = self.validation_queue.list(cursor, pagination::limit(limit));
+            pagination::page(assets, cursor, self.validation_queue.len())
+        }
+        /// Authorizes or revokes an account with the Laboratory role to attach test results to an asset,
+        /// only the owner can do it
+        #[ink(message, selector = "0x9E00B8EE")]
+        pub fn asset_laboratory_authorize(&mut self, id: AssetId, laboratory: AccountId, authorized: bool) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            self.authorize(Message::AssetLaboratoryAuthorize, Some(id))?;
+            if authorized && self.roles.active_role_of(&laboratory) != Some(LABORATORY) {
+                return Err(Error::NotLaboratory)
+            }
+            self.laboratories.authorize(id, laboratory, authorized);
+            Ok(())
+        }
+        /// Returns a page of the laboratories authorized on an asset
+        #[ink(message, selector = "0x9E6668CA")]
+        pub fn asset_laboratories(&self, id: AssetId, cursor: Cursor, limit: u32) -> Page<AccountId> {
+            pagination::page_of(self.laboratories.laboratories_of(id), cursor, limit)
+        }
+        /// Attaches the result of a test to an asset, only a laboratory authorized on the asset can do it
+        #[ink(message, selector = "0x59E7A3E7")]
+        pub fn asset_lab_result_add(&mut self, id: AssetId, test_type: TestType, passed: bool, report: Hash, sample_id: Hash) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is a laboratory
+            let caller = self.authorize(Message::AssetLabResultAdd, Some(id))?;
+            self.laboratories.record(id, LabResult {
+                laboratory: caller,
+                test_type,
+                passed,
+                report,
+                sample_id,
+                block: self.env().block_number(),
+            })?;
+            self.contents.link(report, id);
+            self.reward(caller, Work::LabTest);
+            self.asset_update_log(caller, id, UpdateKind::LabResult);
+            Ok(())
+        }
+        /// Returns a page of the test results of an asset, oldest first
+        #[ink(message, selector = "0x8F782149")]
+        pub fn asset_lab_results(&self, id: AssetId, cursor: Cursor, limit: u32) -> Page<LabResult> {
+            pagination::page_of(self.laboratories.results_of(id), cursor, limit)
+        }
+        /// Registers or removes the verifier contract of a type of claim, only administrator can do it
+        #[ink(message, selector = "0x843AB51C")]
+        pub fn proof_verifier_set(&mut self, claim_type: ClaimType, verifier: Option<AccountId>) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::ProofVerifierSet, None)?;
+            self.proofs.verifier_set(claim_type, verifier);
+            Ok(())
+        }
+        /// Returns the verifier contract of a type of claim
+        #[ink(message, selector = "0x49FE04FC")]
+        pub fn proof_verifier_get(&self, claim_type: ClaimType) -> Option<AccountId> {
+            self.proofs.verifier_of(claim_type)
+        }
+        /// Verifies a zero-knowledge proof of a claim on the hidden data of an asset with the verifier contract of
+        /// the claim type and records the claim, only the owner or its operators can do it. Only the hash of the
+        /// public inputs is stored.
+        #[ink(message, selector = "0x9BE9E676")]
+        pub fn asset_claim_verify(&mut self, id: AssetId, claim_type: ClaimType, public_inputs: Vec<u8>, proof: Vec<u8>) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetClaimVerify, Some(id))?;
+            let verifier = self.proofs.verifier_of(claim_type).ok_or(Error::VerifierNotFound)?;
+            let statement = Proofs::statement_hash(&public_inputs);
+            proofs::verify(verifier, id, public_inputs, proof)?;
+            self.proofs.record(id, Claim {
+                claim_type,
+                statement,
+                verifier,
+                prover: caller,
+                block: self.env().block_number(),
+            });
+            self.env().emit_event(ClaimVerified {
+                id,
+                claim_type,
+                statement,
+            });
+            Ok(())
+        }
+        /// Returns a page of the claims proven on an asset, oldest first
+        #[ink(message, selector = "0x0EC7C636")]
+        pub fn asset_claims(&self, id: AssetId, cursor: Cursor, limit: u32) -> Page<Claim> {
+            pagination::page_of(self.proofs.claims_of(id), cursor, limit)
+        }
+        /// Verifies if a claim of a type has been proven on an asset
+        #[ink(message, selector = "0x63D3B447")]
+        pub fn asset_claim_proven(&self, id: AssetId, claim_type: ClaimType) -> bool {
+            self.proofs.is_proven(id, claim_type)
+        }
+        /// Returns the validation account of an asset, `None` if the validation is revoked
+        #[ink(message, selector = "0xEA3038E8")]
+        pub fn asset_validation_get(&self, id: AssetId) ->  Option<AccountId>{
+           self.ownership
+               .record_of(id)
+               .filter(|_| !self.revocations.is_revoked(Credential::Validation(id)))
+               .and_then(|record| record.validator)
+        }
+        /// Returns the account that signed the validation of an asset, answering for it, `None` if the validation is revoked
+        #[ink(message, selector = "0x3AD4D37A")]
+        pub fn asset_validation_signer_get(&self, id: AssetId) -> Option<AccountId> {
+            self.ownership
+                .record_of(id)
+                .filter(|_| !self.revocations.is_revoked(Credential::Validation(id)))
+                .and_then(|record| record.signer)
+        }
+        /// Verify if there is a validation stored for an asset id and not revoked
+        #[ink(message, selector = "0xC47A5908")]
+        pub fn asset_validation_verify(&self, id: AssetId) -> bool{
+            self.asset_validation_get(id).is_some()
+        }
+        /// Remove the validation of an asset id, only an account with the CAN_VALIDATE capability can do it
+        #[ink(message, selector = "0x00B54E2A")]
+        pub fn asset_validation_delete(&mut self,  id: AssetId) -> Result<(), Error> {
+            // check for the validation capability
+            let caller = self.authorize(Message::AssetValidationDelete, None)?;
+            //check if asset id is present in the storage and remove the validation
+            let record = self.ownership.record_of(id).ok_or(Error::AssetNotFound)?;
+            self.inspector_scope_check(&caller, record.category)?;
+            self.ownership.record_mut(id)?.invalidate()?;
+            self.revocations.clear(Credential::Validation(id));
+            // emits event for asset updated
+            self.asset_update_log(caller, id, UpdateKind::Validation);
+            Ok(())
+        }
+        /// Clears the validation of an asset to unlock its core fields for a correction with the hash of the reason, only
+        /// administrator can do it. The override is recorded in the administrators log.
+        #[ink(message, selector = "0x72DFE4B5")]
+        pub fn asset_unlock(&mut self, id: AssetId, reason: Hash) -> Result<(), Error> {
+            // check for administrator
+            let caller = self.authorize(Message::AssetUnlock, None)?;
+            let record = self.ownership.record_mut(id)?;
+            let validator = record.validator.ok_or(Error::NotValidated)?;
+            record.invalidate()?;
+            self.revocations.clear(Credential::Validation(id));
+            self.asset_update_log(caller, id, UpdateKind::Validation);
+            self.admin_log_append(caller, AdminAction::AssetUnlocked { id, validator, reason });
+            self.env().emit_event(AssetUnlocked {
+                id,
+                admin: caller,
+                validator,
+                reason,
+            });
+            Ok(())
+        }
+        /// Revokes the validation of an asset with the hash of the reason, only administrator can do it. The revoked
+        /// validation is not reported from the verification queries until the asset is validated again.
+        #[ink(message, selector = "0x167752C2")]
+        pub fn asset_validation_revoke(&mut self, id: AssetId, reason: Hash) -> Result<(), Error> {
+            // check for administrator
+            let caller = self.authorize(Message::AssetValidationRevoke, None)?;
+            let record = self.ownership.record_of(id).ok_or(Error::AssetNotFound)?;
+            let issuer = record.signer.or(record.validator).ok_or(Error::AssetNotFound)?;
+            self.credential_revoke(caller, Credential::Validation(id), issuer, reason)
+        }
+        /// Revokes a compliance certificate with the hash of the reason, only administrator can do it. The revoked
+        /// certificate is not reported from the verification queries and a new certificate can be issued for the shipment.
+        #[ink(message, selector = "0x530CF26B")]
+        pub fn certificate_revoke(&mut self, certificate_id: CertificateId, reason: Hash) -> Result<(), Error> {
+            // check for administrator
+            let caller = self.authorize(Message::CertificateRevoke, None)?;
+            let certificate = self.certificates.get(certificate_id).ok_or(Error::CertificateNotFound)?;
+            self.credential_revoke(caller, Credential::Certificate(certificate_id), certificate.issuer, reason)
+        }
+        /// Verifies if a certificate or a validation is revoked
+        #[ink(message, selector = "0x15184B0F")]
+        pub fn credential_is_revoked(&self, credential: Credential) -> bool {
+            self.revocations.is_revoked(credential)
+        }
+        /// Returns the revocation applying to a certificate or a validation
+        #[ink(message, selector = "0x5E84FBC3")]
+        pub fn credential_revocation_get(&self, credential: Credential) -> Option<Revocation> {
+            self.revocations.revocation_of(credential)
+        }
+        /// Returns a page of the revocation list, oldest first
+        #[ink(message, selector = "0xDDC643FB")]
+        pub fn revocations(&self, cursor: Cursor, limit: u32) -> Page<Revocation> {
+            let revocations = self.revocations.list(cursor, pagination::limit(limit));
+            pagination::page(revocations, cursor, self.revocations.revocations_count())
+        }
+        /// Accredits an account with the Inspector role to validate the assets of a list of categories until the `expiry`
+        /// block, `None` for no expiry, replacing its previous accreditation, only administrator can do it. The list of
+        /// categories cannot be empty and the expiry must be after the current block.
+        #[ink(message, selector = "0x26727674")]
+        pub fn inspector_accredit(&mut self, inspector: AccountId, categories: Vec<u32>, expiry: Option<BlockNumber>) -> Result<(), Error> {
+            // check for administrator
+            let caller = self.authorize(Message::InspectorAccredit, None)?;
+            let block = self.env().block_number();
+            self.inspectors.accredit(inspector, Accreditation {
+                categories,
+                issuer: caller,
+                block,
+                expiry,
+            })
+        }
+        /// Revokes the accreditation of an inspector, only administrator can do it. The validations already signed
+        /// from the inspector are kept.
+        #[ink(message, selector = "0x6B0599D2")]
+        pub fn inspector_revoke(&mut self, inspector: AccountId) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::InspectorRevoke, None)?;
+            self.inspectors.revoke(&inspector).map(|_| ())
+        }
+        /// Returns the accreditation of an inspector with its categories, the administrator that issued it and its expiry
+        #[ink(message, selector = "0x576C147A")]
+        pub fn inspector_get(&self, inspector: AccountId) -> Option<Accreditation> {
+            self.inspectors.accreditation_of(&inspector)
+        }
+        /// Returns a page of the inspectors currently accredited on a category, lapsed accreditations excluded
+        #[ink(message, selector = "0x8F3ABD17")]
+        pub fn category_inspectors(&self, category_id: u32, cursor: Cursor, limit: u32) -> Page<AccountId> {
+            pagination::page_of(self.inspectors.inspectors_of(category_id, self.env().block_number()), cursor, limit)
+        }
+        /// Add a category description, you can store categories for an asset that are not yet stored here.
+        #[ink(message, selector = "0x5410C13E")]
+        pub fn category_description_new(&mut self,  id: u32, description: Hash) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::CategoryDescriptionNew, None)?;
+            // add category description if not already present
+            self.metadata.category_description_insert(id, description)
+        }
+        /// Returns the description of an asset category 
+        #[ink(message, selector = "0x3525043F")]
+        pub fn category_description_get(&self, id: AssetId) ->  Option<Hash>{
+           self.metadata.category_description(id)
+        }
+        /// Authorizes or revokes a producer to assign assets to a category, only administrator can do it. The first
+        /// authorization restricts the category: it can be assigned only from its producers and the administrators, and
+        /// stays restricted when its last producer is revoked until it is opened with `category_restricted_set`.
+        #[ink(message, selector = "0xF54763FC")]
+        pub fn category_producer_set(&mut self, category_id: u32, producer: AccountId, authorized: bool) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::CategoryProducerSet, None)?;
+            self.category_resolve(category_id)?;
+            self.metadata.category_producer_set(category_id, producer, authorized)
+        }
+        /// Verifies if a producer is authorized to assign assets to a category
+        #[ink(message, selector = "0x7026AED9")]
+        pub fn category_producer_verify(&self, category_id: u32, producer: AccountId) -> bool {
+            self.metadata.category_producer_verify(category_id, &producer)
+        }
+        /// Restricts a category to its authorized producers, none when no producer is authorized, or opens it to every
+        /// producer, only administrator can do it.
+        #[ink(message, selector = "0x1D297FA8")]
+        pub fn category_restricted_set(&mut self, category_id: u32, restricted: bool) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::CategoryRestrictedSet, None)?;
+            self.category_resolve(category_id)?;
+            self.metadata.category_restricted_set(category_id, restricted)
+        }
+        /// Verifies if a category is restricted to its authorized producers
+        #[ink(message, selector = "0xAC1E1BD0")]
+        pub fn category_restricted_verify(&self, category_id: u32) -> bool {
+            self.metadata.category_restricted(category_id)
+        }
+        /// Sets whether the assets of a category require a license of their producer, only administrator can do it
+        #[ink(message, selector = "0x0847D829")]
+        pub fn category_regulated_set(&mut self, category_id: u32, regulated: bool) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::CategoryRegulatedSet, None)?;
+            self.licenses.regulated_set(category_id, regulated);
+            Ok(())
+        }
+        /// Verifies if the assets of a category require a license of their producer
+        #[ink(message, selector = "0x5C1F50CF")]
+        pub fn category_regulated_verify(&self, category_id: u32) -> bool {
+            self.licenses.is_regulated(category_id)
+        }
+        /// Sets whether the assets of a category require validation, only administrator can do it. The assets assigned
+        /// to the category afterwards are queued for validation.
+        #[ink(message, selector = "0x9FD0F1E6")]
+        pub fn category_validation_required_set(&mut self, category_id: u32, required: bool) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::CategoryValidationRequiredSet, None)?;
+            self.validation_queue.required_set(category_id, required);
+            Ok(())
+        }
+        /// Verifies if the assets of a category require validation
+        #[ink(message, selector = "0xEFB0264B")]
+        pub fn category_validation_required_verify(&self, category_id: u32) -> bool {
+            self.validation_queue.is_required(category_id)
+        }
+        /// Registers the license of a producer with the hash of the license document, the categories it covers and the
+        /// `expiry` block, `None` for no expiry, only administrator can do it. A license with the same document is replaced.
+        #[ink(message, selector = "0x4C20E47D")]
+        pub fn license_grant(&mut self, producer: AccountId, document: Hash, categories: Vec<u32>, expiry: Option<BlockNumber>) -> Result<(), Error> {
+            // check for administrator
+            let caller = self.authorize(Message::LicenseGrant, None)?;
+            self.licenses.grant(producer, License {
+                document,
+                categories,
+                issuer: caller,
+                expiry,
+            })
+        }
+        /// Removes a license of a producer, only administrator can do it. The assets already in the categories of the
+        /// license keep them.
+        #[ink(message, selector = "0x29685AAA")]
+        pub fn license_revoke(&mut self, producer: AccountId, document: Hash) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::LicenseRevoke, None)?;
+            self.licenses.revoke(producer, document)
+        }
+        /// Returns a page of the licenses of a producer, lapsed or not
+        #[ink(message, selector = "0x44E53B65")]
+        pub fn producer_licenses(&self, producer: AccountId, cursor: Cursor, limit: u32) -> Page<License> {
+            pagination::page_of(self.licenses.licenses_of(&producer), cursor, limit)
+        }
+        /// Verifies if a producer can add assets to a category: the category is not regulated or the producer holds a
+        /// license in force covering it
+        #[ink(message, selector = "0xC7923008")]
+        pub fn producer_license_verify(&self, producer: AccountId, category_id: u32) -> bool {
+            self.licenses.is_licensed(&producer, category_id, self.env().block_number())
+        }
+        /// Subscribes the caller to the alerts of a category: the validations and the compliance certificates of its
+        /// assets, and their revocations, emit a "CategoryAlert" event with the category and the digest of its subscribers
+        #[ink(message, selector = "0x30D9A460")]
+        pub fn category_subscribe(&mut self, category_id: u32) -> Result<(), Error> {
+            let caller = self.authorize(Message::CategorySubscribe, None)?;
+            self.category_resolve(category_id)?;
+            if self.metadata.category_description(category_id).is_none() {
+                return Err(Error::CategoryNotFound)
+            }
+            self.alerts.subscribe(caller, category_id)
+        }
+        /// Unsubscribes the caller from the alerts of a category
+        #[ink(message, selector = "0xE06B7FD8")]
+        pub fn category_unsubscribe(&mut self, category_id: u32) -> Result<(), Error> {
+            let caller = self.authorize(Message::CategoryUnsubscribe, None)?;
+            self.alerts.unsubscribe(caller, category_id)
+        }
+        /// Returns a page of the subscribers of a category
+        #[ink(message, selector = "0x82765BAA")]
+        pub fn category_subscribers(&self, category_id: u32, cursor: Cursor, limit: u32) -> Page<AccountId> {
+            let subscribers = self.alerts.subscribers_of(category_id, cursor, pagination::limit(limit));
+            pagination::page(subscribers, cursor, self.alerts.subscribers_count(category_id))
+        }
+        /// Returns the digest of the subscribers of a category, the topic of its alerts
+        #[ink(message, selector = "0x606B2F6D")]
+        pub fn category_subscribers_digest(&self, category_id: u32) -> Option<Hash> {
+            self.alerts.digest_of(category_id)
+        }
+        /// Returns a page of the categories an account subscribed to
+        #[ink(message, selector = "0xC1070F14")]
+        pub fn account_category_subscriptions(&self, account: AccountId, cursor: Cursor, limit: u32) -> Page<u32> {
+            pagination::page_of(self.alerts.categories_of(&account), cursor, limit)
+        }
+        /// Sets the type of the test the assets of a category must pass before being validated, only administrator
+        /// can do it. `None` removes the requirement.
+        #[ink(message, selector = "0xDBF0AFF2")]
+        pub fn category_lab_requirement_set(&mut self, category_id: u32, test_type: Option<TestType>) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::CategoryLabRequirementSet, None)?;
+            self.laboratories.requirement_set(category_id, test_type);
+            Ok(())
+        }
+        /// Returns the type of the test the assets of a category must pass before being validated
+        #[ink(message, selector = "0xB8B0AA63")]
+        pub fn category_lab_requirement_get(&self, category_id: u32) -> Option<TestType> {
+            self.laboratories.requirement_of(category_id)
+        }
+        /// Returns the number of assets assigned to a category
+        #[ink(message, selector = "0xA74F8BC4")]
+        pub fn category_assets_count(&self, category_id: u32) -> u32 {
+            self.metadata.category_assets(category_id)
+        }
+        /// Verifies if there is a category description stored, returns true/false
+        #[ink(message, selector = "0x77152FE6")]
+        pub fn category_description_verify(&self, id: u32) -> bool{
+            self.metadata.category_description(id).is_some()
+        }
+        /// Removes the metadata of an asset id, only the owner can do it
+        #[ink(message, selector = "0xC53F5D1F")]
+        pub fn category_description_delete(&mut self,  id: u32) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::CategoryDescriptionDelete, None)?;
+            // remove category
+            self.metadata.category_description_remove(id)
+        }
+        /// Reads again the description of a category from the category registry of the configuration, only
+        /// administrator can do it. The cached description is replaced, or removed when the registry does not have it anymore.
+        #[ink(message, selector = "0x1DE30F55")]
+        pub fn category_refresh(&mut self, id: u32) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::CategoryRefresh, None)?;
+            let registry = self.config.category_registry.ok_or(Error::CategoryNotFound)?;
+            let description = category_registry::description_of(registry, id)?;
+            let cached = self.metadata.category_description_remove(id);
+            match description {
+                Some(description) => self.metadata.category_description_insert(id, description),
+                None => cached,
+            }
+        }
+        /// Deletes an existing asset. Only the owner can do it
+        #[ink(message, selector = "0x8C540605")]
+        pub fn asset_delete(&mut self, id: AssetId) -> Result<(), Error> {
+            // check if asset id is stored and the signer can delete it
+            self.authorize(Message::AssetDelete, Some(id))?;
+            if self.liens.is_encumbered(id) {
+                return Err(Error::AssetEncumbered)
+            }
+            // the asset must not be left with dangling proxies, validations, shipments or listings
+            let record = self.ownership.record_of(id).ok_or(Error::AssetNotFound)?;
+            if record.delegate.is_some() {
+                return Err(Error::AssetDelegated)
+            }
+            if record.validator.is_some() && !self.revocations.is_revoked(Credential::Validation(id)) {
+                return Err(Error::AssetValidated)
+            }
+            if record.open_shipments > 0 || self.carriers.carrier_of(id).is_some() {
+                return Err(Error::AssetInTransit)
+            }
+            if self.sales.listing_of(id).is_some() {
+                return Err(Error::AssetListed)
+            }
+            if record.reservation.is_some_and(|reservation| reservation.is_active(self.env().block_number())) {
+                return Err(Error::AssetReserved)
+            }
+            if record.pending.is_some() {
+                return Err(Error::TransferPending)
+            }
+            self.asset_burn(id)?;
+            Ok(())
+        }
+        /// Deletes an asset clearing its delegation, validation, carrier, listing and liens, only administrator can do
+        /// it with the hash of the reason. The shipments listing the asset are settled as usual on their delivery or
+        /// cancellation, so no deposit is stranded. The assets on hold, bridged, disposed or retired cannot be deleted.
+        /// The deletion is recorded in the administrators log.
+        #[ink(message, selector = "0x7D4A0DB3")]
+        pub fn asset_delete_force(&mut self, id: AssetId, reason: Hash) -> Result<(), Error> {
+            // check for administrator
+            let caller = self.authorize(Message::AssetDeleteForce, None)?;
+            let owner = self.asset_burn(id)?;
+            self.admin_log_append(caller, AdminAction::AssetDeletedByForce { id, owner, reason });
+            Ok(())
+        }
+        /// Removes asset `id` and the data of its components, returns its last owner
+        fn asset_burn(&mut self, id: AssetId) -> Result<AccountId, Error> {
+            // the assets mirrored on the EVM chain cannot be deleted
+            if self.bridge.is_locked(id) {
+                return Err(Error::AssetBridged)
+            }
+            if self.holds.is_held(id) {
+                return Err(Error::AssetOnHold)
+            }
+            // the disposed and the retired assets are retained permanently
+            match self.asset_stock_status(id) {
+                Some(StockStatus::Disposed) => return Err(Error::AssetDisposed),
+                Some(StockStatus::Remanufactured) => return Err(Error::AssetRetired),
+                _ => (),
+            }
+            let owner = self.ownership.owner_of(id).ok_or(Error::AssetNotFound)?;
+            self.reservation_refund(id, owner)?;
+            // remove asset
+            let mut record = self.ownership.remove(&owner, id)?;
+            // the burned asset does not count anymore in its category
+            self.metadata.category_unassign(&mut record);
+            let mint = self.provenance.remove(id);
+            self.brands.unlink(id);
+            self.products.unlink(id);
+            self.contents.remove(id);
+            self.authenticity.remove(id);
+            self.tags.unbind(id);
+            self.metadata.duplicate_clear(id);
+            self.quantities.remove(id);
+            self.carriers.release(id);
+            self.certificates.remove(id);
+            self.revocations.clear(Credential::Validation(id));
+            self.laboratories.remove(id);
+            self.acl.remove(id);
+            self.privacy.remove(id);
+            self.proofs.remove(id);
+            self.sealed.remove(id);
+            self.physical.remove(id);
+            self.depreciation.remove(id);
+            self.services.remove(id);
+            self.locations.remove(id);
+            self.notes.remove(id);
+            self.returns.remove(id);
+            self.lineage.remove(id);
+            self.validation_queue.remove(id);
+            self.liens.remove(id);
+            self.updates.remove(id);
+            self.deposits.release(id);
+            if let Some(mint) = mint {
+                self.deposits.unlock(id, mint.minter, mint.deposit);
+            }
+            for index in 0..self.sales.sales_count(id) {
+                self.threads.remove(Thread::Sale(id, index));
+            }
+            self.taxes.forget(id, self.sales.sales_count(id));
+            self.sales.remove(id);
+            self.price_lists.msrp_remove(id);
+            self.stats.burns += 1;
+            self.transferred(owner, AccountId::from([0x0; 32]), id);
+            Ok(owner)
+        }
+        /// Writes new role operator, only an account with the CAN_MANAGE_ROLES capability can do it
+        #[ink(message, selector = "0x428DFBE0")]
+        pub fn account_role_new(&mut self,  accountid: AccountId, role: u32) -> Result<(), Error> {
+            // check for the role management capability
+            let caller = self.authorize(Message::AccountRoleNew, None)?;
+            // only the administrators can appoint other administrators
+            if role == ADMINISTRATOR && !self.is_administrator(&caller) {
+                return Err(Error::NotAdministrator)
+            }
+            //store the role, it must be registered and enabled
+            self.roles.insert(accountid, role)?;
+            self.stats.role_changed(None, Some(role));
+            // emits event
+            self.env().emit_event(RoleUpdate {
+                from: caller,
+                id: accountid,
+                old: None,
+                new: Some(role),
+            });
+            Ok(())
+        }
+        /// Changes the role of an operator in one step, only an account with the CAN_MANAGE_ROLES capability can do it.
+        /// The account keeps its previous role until the new one is assigned.
+        #[ink(message, selector = "0xC4D1C6E6")]
+        pub fn account_role_update(&mut self, accountid: AccountId, new_role: u32) -> Result<(), Error> {
+            // check for the role management capability
+            let caller = self.authorize(Message::AccountRoleUpdate, None)?;
+            // only the administrators can appoint or remove other administrators
+            let administrator = self.is_administrator(&caller);
+            if (new_role == ADMINISTRATOR || self.roles.role_of(&accountid) == Some(ADMINISTRATOR)) && !administrator {
+                return Err(Error::NotAdministrator)
+            }
+            //store the role, it must be registered and enabled
+            let old_role = self.roles.update(accountid, new_role)?;
+            self.stats.role_changed(Some(old_role), Some(new_role));
+            self.env().emit_event(RoleUpdate {
+                from: caller,
+                id: accountid,
+                old: Some(old_role),
+                new: Some(new_role),
+            });
+            Ok(())
+        }
+        /// Registers a new role with the hash of its name, only administrator can do it. The built-in roles
+        /// (0 = Producer, 1= Wholesaler, 2 = Retailer, 3 = Final Buyer, 4=Shipper, 5=Administrator, 6=Laboratory, 7=Inspector, 8=Recycler, 9=Auditor) are always registered.
+        #[ink(message, selector = "0x3A506F37")]
+        pub fn role_register(&mut self, role: u32, name: Hash) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::RoleRegister, None)?;
+            self.roles.register(role, name)
+        }
+        /// Enables or disables a registered role, only administrator can do it. A disabled role cannot be assigned
+        /// and gives no permission to its accounts. The Administrator role cannot be disabled.
+        #[ink(message, selector = "0x290F7294")]
+        pub fn role_enabled_set(&mut self, role: u32, enabled: bool) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::RoleEnabledSet, None)?;
+            self.roles.enabled_set(role, enabled)
+        }
+        /// Sets the capabilities of a registered role, only administrator can do it. The capabilities are a
+        /// bitmask of CAN_MINT = 1, CAN_TRANSFER = 2, CAN_VALIDATE = 4, CAN_UPDATE_LOCATION = 8, CAN_MANAGE_ROLES = 16.
+        /// The capabilities of the Administrator role cannot be changed.
+        #[ink(message, selector = "0x1E06CC62")]
+        pub fn role_capabilities_set(&mut self, role: u32, capabilities: Capabilities) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::RoleCapabilitiesSet, None)?;
+            self.roles.capabilities_set(role, capabilities)
+        }
+        /// Sets or removes the PSP22 token of the rewards of the verification work, only administrator can do it.
+        /// The contract must be allowed to mint the token.
+        #[ink(message, selector = "0x7A7BBDA6")]
+        pub fn reward_token_set(&mut self, token: Option<AccountId>) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::RewardTokenSet, None)?;
+            self.rewards.token_set(token);
+            Ok(())
+        }
+        /// Returns the PSP22 token of the rewards of the verification work
+        #[ink(message, selector = "0x7EF4B58D")]
+        pub fn reward_token_get(&self) -> Option<AccountId> {
+            self.rewards.token()
+        }
+        /// Sets the reward minted to the accounts of a role for each validation, test result or certificate,
+        /// only administrator can do it. A reward of 0 disables the rewards of the role.
+        #[ink(message, selector = "0xF39A0260")]
+        pub fn reward_rate_set(&mut self, role: u32, amount: Balance) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::RewardRateSet, None)?;
+            self.roles.role_info(role).ok_or(Error::RoleNotFound)?;
+            self.rewards.rate_set(role, amount);
+            Ok(())
+        }
+        /// Returns the reward minted to the accounts of a role for each verification work
+        #[ink(message, selector = "0x14E90646")]
+        pub fn reward_rate_get(&self, role: u32) -> Balance {
+            self.rewards.rate_of(role)
+        }
+        /// Returns the total rewards minted to an account
+        #[ink(message, selector = "0xC966879F")]
+        pub fn rewards_earned(&self, account: AccountId) -> Balance {
+            self.rewards.earned_of(&account)
+        }
+        /// Returns the hash of the name of a registered role, if it is enabled and its capabilities
+        #[ink(message, selector = "0xA7F0FB5E")]
+        pub fn role_get(&self, role: u32) -> Option<RoleInfo> {
+            self.roles.role_info(role)
+        }
+        /// Returns the account role (0 = Producer, 1= Wholesaler, 2 = Retailer, 3 = Final Buyer, 4=Shipper, 5=Administrator, 6=Laboratory, 7=Inspector, 8=Recycler, 9=Auditor)
+        #[ink(message, selector = "0x6ED372C8")]
+        pub fn account_role_get(&self, accountid: AccountId) ->  Option<u32>{
+           self.roles.role_of(&accountid)
+        }
+         /// Verifies if there is a role stored for the operator
+         #[ink(message, selector = "0xCFDDCFD6")]
+         pub fn account_role_verify(&self, accountid: AccountId) -> bool{
+             self.roles.role_of(&accountid).is_some()
+         }
+        /// Removes an operator role, only an account with the CAN_MANAGE_ROLES capability can do it
+        #[ink(message, selector = "0xEA96135E")]
+        pub fn account_role_delete(&mut self,  accountid: AccountId) -> Result<(), Error> {
+            // check for the role management capability
+            let caller = self.authorize(Message::AccountRoleDelete, None)?;
+            // only the administrators can remove other administrators
+            if self.roles.role_of(&accountid) == Some(ADMINISTRATOR) && !self.is_administrator(&caller) {
+                return Err(Error::NotAdministrator)
+            }
+            // remove role
+            let role = self.roles.remove(&accountid)?;
+            self.stats.role_changed(Some(role), None);
+            self.env().emit_event(RoleUpdate {
+                from: caller,
+                id: accountid,
+                old: Some(role),
+                new: None,
+            });
+            Ok(())
+        }
+        /// Returns the number of the assets owneed from an account
+        /// This represents the amount of unique assets the owner has.
+        #[ink(message, selector = "0x323A7FAA")]
+        pub fn account_assets_number(&self, owner: AccountId) -> u32 {
+            self.ownership.balance_of(&owner)
+        }
+        /// Returns a page of the assets owned from an account with all their data
+        #[ink(message, selector = "0xB016681B")]
+        pub fn account_assets_detailed(&self, owner: AccountId, cursor: Cursor, limit: u32) -> Page<(AssetId, AssetInfo)> {
+            let assets = self.ownership.assets_of(&owner, cursor, pagination::limit(limit));
+            let (assets, next) = pagination::page(assets, cursor, self.ownership.balance_of(&owner));
+            let assets = assets
+                .into_iter()
+                .filter_map(|id| self.asset_info_get(id).map(|info| (id, info)))
+                .collect();
+            (assets, next)
+        }
+        /// Returns the number of activities kept in the feed of an account, at most the latest 50
+        #[ink(message, selector = "0xD73A62FD")]
+        pub fn account_activity_count(&self, account: AccountId) -> u32 {
+            self.activity.len(&account)
+        }
+        /// Returns a page of the activities of an account, newest first
+        #[ink(message, selector = "0x01C21D82")]
+        pub fn account_activity_get(&self, account: AccountId, cursor: Cursor, limit: u32) -> Page<ActivityEntry> {
+            let entries = self.activity.latest(&account, cursor, pagination::limit(limit));
+            pagination::page(entries, cursor, self.activity.len(&account))
+        }
+        /// Requests the erasure of the caller from the historical records, the caller cannot own assets
+        #[ink(message, selector = "0x4C64CEBB")]
+        pub fn account_erasure_request(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if self.ownership.balance_of(&caller) > 0 {
+                return Err(Error::NotAllowed)
+            }
+            self.erasure.request(caller, self.env().block_number())
+        }
+        /// Cancels the erasure request of the caller, the records already rewritten keep the pseudonym
+        #[ink(message, selector = "0x8E5723DB")]
+        pub fn account_erasure_cancel(&mut self) -> Result<(), Error> {
+            self.erasure.cancel(&self.env().caller()).map(|_| ())
+        }
+        /// Commits the Blake2x256 hash of the secret salt of the pseudonym to the erasure request of an account, only
+        /// administrator can do it. The commitment fixes the pseudonym before the erasure starts.
+        #[ink(message, selector = "0xA4134038")]
+        pub fn account_erasure_commit(&mut self, account: AccountId, commitment: Hash) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::AccountErasureCommit, None)?;
+            self.erasure.commit(&account, commitment)
+        }
+        /// Replaces an account with its pseudonym, the hash of the account and of `salt`, in at most `limit` transfers of
+        /// the journal and in the records of the assets related to them, once the retention period has elapsed since the
+        /// request, only administrator can do it. The `salt` must match the commitment of the request. Returns true when
+        /// the erasure is complete.
+        #[ink(message, selector = "0xC1DD14D8")]
+        pub fn account_erasure_execute(&mut self, account: AccountId, salt: Hash, limit: u32) -> Result<bool, Error> {
+            // check for administrator
+            self.authorize(Message::AccountErasureExecute, None)?;
+            let mut request = self.erasure.request_of(&account).ok_or(Error::ErasureNotFound)?;
+            if self.env().block_number() < request.block.saturating_add(self.config.erasure_retention) {
+                return Err(Error::RetentionPending)
+            }
+            // the owner of an asset stays identifiable
+            if self.ownership.balance_of(&account) > 0 {
+                return Err(Error::NotAllowed)
+            }
+            if request.commitment != Some(Erasure::commitment_of(salt)) {
+                return Err(Error::NotAllowed)
+            }
+            let pseudonym = Erasure::pseudonym_of(&account, salt);
+            let (rewritten, cursor) = self.journal.pseudonymize(&account, pseudonym, request.cursor, limit);
+            let zero = AccountId::from([0x0; 32]);
+            for record in rewritten {
+                self.sales.pseudonymize(record.id, &account, pseudonym);
+                for index in 0..self.sales.sales_count(record.id) {
+                    self.threads.pseudonymize(Thread::Sale(record.id, index), &account, pseudonym);
+                }
+                self.locations.pseudonymize(record.id, &account, pseudonym);
+                self.notes.pseudonymize(record.id, &account, pseudonym);
+                let counterpart = if record.from == account { record.to } else { record.from };
+                if counterpart != zero && counterpart != account {
+                    self.activity.pseudonymize(&counterpart, &account, pseudonym);
+                }
+            }
+            if cursor < self.journal.len() {
+                request.cursor = cursor;
+                self.erasure.update(account, request);
+                return Ok(false)
+            }
+            self.activity.clear(&account);
+            self.taxes.jurisdiction_set(account, None);
+            self.erasure.complete(&account);
+            self.env().emit_event(AccountErased {
+                erased: self.erasure.erased_count(),
+            });
+            Ok(true)
+        }
+        /// Returns the pending erasure request of an account
+        #[ink(message, selector = "0x10B45ABA")]
+        pub fn account_erasure_get(&self, account: AccountId) -> Option<ErasureRequest> {
+            self.erasure.request_of(&account)
+        }
+
+        /// Registers a new brand owned from the caller and returns its id, only producers can do it
+        #[ink(message, selector = "0x3EC70409")]
+        pub fn brand_register(&mut self, name: Hash, logo: Hash) -> Result<BrandId, Error> {
+            let caller = self.authorize(Message::BrandRegister, None)?;
+            let brand_id = self.brands.register(Brand {
+                owner: caller,
+                name,
+                logo,
+                verified: false,
+            });
+            self.env().emit_event(BrandRegistered {
+                owner: caller,
+                brand_id,
+            });
+            Ok(brand_id)
+        }
+        /// Marks a brand as verified from an audit, only administrator can do it
+        #[ink(message, selector = "0xF0DD8304")]
+        pub fn brand_verify(&mut self, brand_id: BrandId) -> Result<(), Error> {
+            // check for administrator
+            let caller = self.authorize(Message::BrandVerify, None)?;
+            self.brands.verified_set(brand_id, true)?;
+            self.env().emit_event(BrandVerificationUpdated {
+                admin: caller,
+                brand_id,
+                verified: true,
+            });
+            Ok(())
+        }
+        /// Removes the verification of a brand, only administrator can do it
+        #[ink(message, selector = "0xD805672F")]
+        pub fn brand_unverify(&mut self, brand_id: BrandId) -> Result<(), Error> {
+            // check for administrator
+            let caller = self.authorize(Message::BrandUnverify, None)?;
+            self.brands.verified_set(brand_id, false)?;
+            self.env().emit_event(BrandVerificationUpdated {
+                admin: caller,
+                brand_id,
+                verified: false,
+            });
+            Ok(())
+        }
+        /// Returns the owner, the name, the logo and the verification of a brand
+        #[ink(message, selector = "0x8C65B8E2")]
+        pub fn brand_get(&self, brand_id: BrandId) -> Option<Brand> {
+            self.brands.get(brand_id)
+        }
+        /// Returns the brand of an asset id
+        #[ink(message, selector = "0x11C838AE")]
+        pub fn asset_brand_get(&self, id: AssetId) -> Option<BrandId> {
+            self.brands.brand_of(id)
+        }
+        /// Returns the number of assets linked to a brand
+        #[ink(message, selector = "0x813B2CA8")]
+        pub fn brand_assets_count(&self, brand_id: BrandId) -> u32 {
+            self.brands.assets_count(brand_id)
+        }
+        /// Returns a page of the assets linked to a brand
+        #[ink(message, selector = "0xBE71D647")]
+        pub fn brand_assets(&self, brand_id: BrandId, cursor: Cursor, limit: u32) -> Page<AssetId> {
+            let assets = self.brands.assets_of(brand_id, cursor, pagination::limit(limit));
+            pagination::page(assets, cursor, self.brands.assets_count(brand_id))
+        }
+
+        /// Defines a new product of a category of the signer and returns its id, only producers can do it
+        #[ink(message, selector = "0x1A1F3BD0")]
+        pub fn product_new(&mut self, name: Hash, category_id: u32) -> Result<ProductId, Error> {
+            let caller = self.authorize(Message::ProductNew, None)?;
+            self.category_resolve(category_id)?;
+            if self.metadata.category_description(category_id).is_none() {
+                return Err(Error::CategoryNotFound)
+            }
+            if !self.category_producer_allowed(category_id, &caller) {
+                return Err(Error::NotAllowed)
+            }
+            let product_id = self.products.define(Product {
+                producer: caller,
+                name,
+                category: category_id,
+                variants_count: 0,
+            });
+            self.env().emit_event(ProductDefined {
+                producer: caller,
+                product_id,
+            });
+            Ok(product_id)
+        }
+        /// Adds a variant with its size, color and formulation to a product and returns its position in the
+        /// variants of the product, only the producer of the product can do it
+        #[ink(message, selector = "0x6D263E15")]
+        pub fn product_variant_new(&mut self, product_id: ProductId, size: Option<u32>, color: Option<[u8; 3]>, formulation: Option<Hash>) -> Result<u32, Error> {
+            let caller = self.authorize(Message::ProductVariantNew, None)?;
+            let product = self.products.get(product_id).ok_or(Error::ProductNotFound)?;
+            if product.producer != caller {
+                return Err(Error::NotProducer)
+            }
+            let variant = self.products.variant_add(product_id, Variant {
+                size,
+                color,
+                formulation,
+            })?;
+            self.env().emit_event(ProductVariantAdded {
+                product_id,
+                variant,
+            });
+            Ok(variant)
+        }
+        /// Links an asset to a variant of a product of its producer, only the owner can do it. An asset is linked
+        /// to one variant.
+        #[ink(message, selector = "0x4C7CD825")]
+        pub fn asset_variant_set(&mut self, id: AssetId, product_id: ProductId, variant: u32) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            self.authorize(Message::AssetVariantSet, Some(id))?;
+            let product = self.products.get(product_id).ok_or(Error::ProductNotFound)?;
+            if self.provenance.producer_of(id) != Some(product.producer) {
+                return Err(Error::NotProducer)
+            }
+            self.products.link(id, (product_id, variant))
+        }
+        /// Returns the producer, the name, the category and the number of variants of a product
+        #[ink(message, selector = "0xC946E2A2")]
+        pub fn product_get(&self, product_id: ProductId) -> Option<Product> {
+            self.products.get(product_id)
+        }
+        /// Returns a variant of a product
+        #[ink(message, selector = "0xD0C39F8A")]
+        pub fn product_variant_get(&self, product_id: ProductId, variant: u32) -> Option<Variant> {
+            self.products.variant_of((product_id, variant))
+        }
+        /// Returns a page of the variants of a product
+        #[ink(message, selector = "0x740B373D")]
+        pub fn product_variants(&self, product_id: ProductId, cursor: Cursor, limit: u32) -> Page<Variant> {
+            let variants = self.products.variants_of(product_id, cursor, pagination::limit(limit));
+            let count = self.products.get(product_id).map_or(0, |product| product.variants_count);
+            pagination::page(variants, cursor, count)
+        }
+        /// Returns the product and the variant of an asset id
+        #[ink(message, selector = "0xA6967158")]
+        pub fn asset_variant_get(&self, id: AssetId) -> Option<VariantId> {
+            self.products.asset_variant_of(id)
+        }
+        /// Returns the number of assets of a product, across its variants
+        #[ink(message, selector = "0xE09A6E25")]
+        pub fn product_assets_count(&self, product_id: ProductId) -> u32 {
+            self.products.product_assets_count(product_id)
+        }
+        /// Returns a page of the assets of a product, across its variants
+        #[ink(message, selector = "0xA064D611")]
+        pub fn product_assets(&self, product_id: ProductId, cursor: Cursor, limit: u32) -> Page<AssetId> {
+            let assets = self.products.product_assets_of(product_id, cursor, pagination::limit(limit));
+            pagination::page(assets, cursor, self.products.product_assets_count(product_id))
+        }
+        /// Returns the number of assets of a variant of a product
+        #[ink(message, selector = "0x25D43C05")]
+        pub fn variant_assets_count(&self, product_id: ProductId, variant: u32) -> u32 {
+            self.products.variant_assets_count((product_id, variant))
+        }
+        /// Returns a page of the assets of a variant of a product
+        #[ink(message, selector = "0x0A385E24")]
+        pub fn variant_assets(&self, product_id: ProductId, variant: u32, cursor: Cursor, limit: u32) -> Page<AssetId> {
+            let assets = self.products.variant_assets_of((product_id, variant), cursor, pagination::limit(limit));
+            pagination::page(assets, cursor, self.products.variant_assets_count((product_id, variant)))
+        }
+        /// Registers the SKU code of a product with the default attributes of its units, only the producer of the
+        /// product or administrator can do it. The codes are unique across the producers, whatever their case.
+        #[ink(message, selector = "0xE9347501")]
+        pub fn sku_register(&mut self, sku: Vec<u8>, product_id: ProductId, size: Option<u32>, color: Option<[u8; 3]>, formulation: Option<Hash>) -> Result<(), Error> {
+            let caller = self.authorize(Message::SkuRegister, None)?;
+            let product = self.product_managed(product_id, &caller)?;
+            let block = self.env().block_number();
+            self.skus.register(&sku, SkuEntry {
+                product: product_id,
+                category: product.category,
+                attributes: Variant {
+                    size,
+                    color,
+                    formulation,
+                },
+                registrar: caller,
+                block,
+            })?;
+            self.env().emit_event(SkuUpdated {
+                product_id,
+                sku,
+                removed: false,
+            });
+            Ok(())
+        }
+        /// Replaces the default attributes of a SKU, only the producer of its product or administrator can do it
+        #[ink(message, selector = "0x8F7E4600")]
+        pub fn sku_attributes_set(&mut self, sku: Vec<u8>, size: Option<u32>, color: Option<[u8; 3]>, formulation: Option<Hash>) -> Result<(), Error> {
+            let caller = self.authorize(Message::SkuAttributesSet, None)?;
+            let product_id = self.skus.get(&sku).ok_or(Error::SkuNotFound)?.product;
+            self.product_managed(product_id, &caller)?;
+            let block = self.env().block_number();
+            self.skus.attributes_set(&sku, Variant {
+                size,
+                color,
+                formulation,
+            }, block)?;
+            self.env().emit_event(SkuUpdated {
+                product_id,
+                sku,
+                removed: false,
+            });
+            Ok(())
+        }
+        /// Removes a SKU, freeing its code, only the producer of its product or administrator can do it
+        #[ink(message, selector = "0x92271B1E")]
+        pub fn sku_remove(&mut self, sku: Vec<u8>) -> Result<(), Error> {
+            let caller = self.authorize(Message::SkuRemove, None)?;
+            let product_id = self.skus.get(&sku).ok_or(Error::SkuNotFound)?.product;
+            self.product_managed(product_id, &caller)?;
+            self.skus.remove(&sku)?;
+            self.env().emit_event(SkuUpdated {
+                product_id,
+                sku,
+                removed: true,
+            });
+            Ok(())
+        }
+        /// Returns the product, the category and the default attributes a SKU code maps to
+        #[ink(message, selector = "0x8A06AD48")]
+        pub fn sku_get(&self, sku: Vec<u8>) -> Option<SkuEntry> {
+            self.skus.get(&sku)
+        }
+        /// Returns product `product_id`, fails if it does not exist or `account` is neither its producer nor
+        /// administrator
+        fn product_managed(&self, product_id: ProductId, account: &AccountId) -> Result<Product, Error> {
+            let product = self.products.get(product_id).ok_or(Error::ProductNotFound)?;
+            if product.producer != *account && !self.is_administrator(account) {
+                return Err(Error::NotProducer)
+            }
+            Ok(product)
+        }
+        /// Creates the manifest of a shipment to `receiver` listing the assets with the hashes of their seals,
+        /// only the owner or an operator of all the assets can do it. Returns the id of the manifest.
+        #[ink(message, selector = "0x736CB18B")]
+        pub fn shipment_manifest_create(&mut self, receiver: AccountId, seals: Vec<(AssetId, Hash)>) -> Result<ManifestId, Error> {
+            let manifest = self.manifest_build(receiver, seals)?;
+            self.manifest_store(manifest)
+        }
+        /// Creates the manifest of a cross-border shipment like `shipment_manifest_create`, depositing the customs
+        /// duty paid to `collector` on clearance. The duty is transferred with the call in native tokens or, when
+        /// `token` is set, in PSP22 tokens the contract is allowed to spend. Returns the id of the manifest.
+        #[ink(message, payable, selector = "0x2AA626C3")]
+        pub fn shipment_manifest_create_with_duty(&mut self, receiver: AccountId, seals: Vec<(AssetId, Hash)>, collector: AccountId, duty: Balance, token: Option<AccountId>) -> Result<ManifestId, Error> {
+            if collector == AccountId::from([0x0; 32]) {
+                return Err(Error::NotAllowed)
+            }
+            self.manifest_create_with_duty(receiver, seals, collector, None, duty, token)
+        }
+        /// Creates the manifest of a cross-border shipment like `shipment_manifest_create_with_duty`, the duty is paid
+        /// on clearance to the customs collector of the registered `jurisdiction`. Returns the id of the manifest.
+        #[ink(message, payable, selector = "0xBC7A96B0")]
+        pub fn shipment_manifest_create_with_customs(&mut self, receiver: AccountId, seals: Vec<(AssetId, Hash)>, jurisdiction: Jurisdiction, duty: Balance, token: Option<AccountId>) -> Result<ManifestId, Error> {
+            let entry = self.jurisdictions.get(jurisdiction).ok_or(Error::JurisdictionNotFound)?;
+            let collector = entry.customs.ok_or(Error::NotAllowed)?;
+            self.manifest_create_with_duty(receiver, seals, collector, Some(jurisdiction), duty, token)
+        }
+        /// Creates the manifest of a shipment depositing the duty paid to `collector` on clearance
+        fn manifest_create_with_duty(&mut self, receiver: AccountId, seals: Vec<(AssetId, Hash)>, collector: AccountId, jurisdiction: Option<Jurisdiction>, duty: Balance, token: Option<AccountId>) -> Result<ManifestId, Error> {
+            let manifest = self.manifest_build(receiver, seals)?;
+            Shipments::check(&manifest)?;
+            self.payment_collect(token, duty)?;
+            let manifest_id = self.manifest_store(manifest)?;
+            self.shipments.duty_set(manifest_id, Duty {
+                collector,
+                jurisdiction,
+                amount: duty,
+                token,
+                status: DutyStatus::Escrowed,
+            });
+            Ok(manifest_id)
+        }
+        /// Returns the manifest of a shipment
+        #[ink(message, selector = "0xC63D7B31")]
+        pub fn shipment_manifest_get(&self, manifest_id: ManifestId) -> Option<Manifest> {
+            self.shipments.get(manifest_id)
+        }
+        /// Returns the Blake2x256 hash of the encoded manifest of a shipment
+        #[ink(message, selector = "0xE6F3440C")]
+        pub fn shipment_manifest_hash(&self, manifest_id: ManifestId) -> Option<Hash> {
+            self.shipments.hash_of(manifest_id)
+        }
+        /// Agrees the freight cost of a shipment with a registered shipper, only the sender of the manifest can do it
+        /// before the delivery. The cost is deposited in the contract: in native tokens transferred with the call or,
+        /// when `token` is set, in PSP22 tokens the contract is allowed to spend. The `sla` sets the penalty of a delivery
+        /// after its deadline.
+        #[ink(message, payable, selector = "0x4F713215")]
+        pub fn shipment_freight_agree(&mut self, manifest_id: ManifestId, shipper: AccountId, cost: Balance, token: Option<AccountId>, sla: Option<Sla>) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let manifest = self.shipments.get(manifest_id).ok_or(Error::ManifestNotFound)?;
+            if manifest.sender != caller {
+                return Err(Error::NotAllowed)
+            }
+            if self.roles.active_role_of(&shipper) != Some(SHIPPER) {
+                return Err(Error::NotAllowed)
+            }
+            // the shipper must have bonded the stake answering for its breaches
+            if self.stakes.bonded_of(&shipper) < self.config.shipper_stake {
+                return Err(Error::StakeRequired)
+            }
+            if sla.is_some_and(|sla| sla.deadline < self.env().block_number()) {
+                return Err(Error::NotAllowed)
+            }
+            self.shipments.freight_set(manifest_id, Freight {
+                shipper,
+                cost,
+                token,
+                settled: false,
+                sla,
+                penalty: 0,
+            })?;
+            if let Err(error) = self.payment_collect(token, cost) {
+                self.shipments.freight_remove(manifest_id);
+                return Err(error)
+            }
+            self.env().emit_event(FreightAgreed {
+                manifest_id,
+                shipper,
+                cost,
+                token,
+            });
+            Ok(())
+        }
+        /// Returns the freight agreed for a shipment
+        #[ink(message, selector = "0x6DE7E31D")]
+        pub fn shipment_freight_get(&self, manifest_id: ManifestId) -> Option<Freight> {
+            self.shipments.freight_of(manifest_id)
+        }
+        /// Confirms the delivery of a shipment, only the receiver of the manifest can do it.
+        /// The freight deposited for the shipment is paid to the shipper. The penalty of a delivery after the deadline
+        /// of the service level is refunded to the sender from the freight and, beyond it, from the stake of the shipper.
+        #[ink(message, selector = "0x7ECC8D22")]
+        pub fn shipment_delivery_confirm(&mut self, manifest_id: ManifestId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let manifest = self.shipments.get(manifest_id).ok_or(Error::ManifestNotFound)?;
+            if manifest.receiver != caller {
+                return Err(Error::NotAllowed)
+            }
+            let freight = self.shipments.deliver(manifest_id, self.env().block_number())?;
+            if let Some(freight) = freight {
+                let deducted = freight.penalty.min(freight.cost);
+                if let Err(error) = self.payment_send(freight.token, freight.shipper, freight.cost - deducted) {
+                    self.shipments.undeliver(manifest_id);
+                    return Err(error)
+                }
+                // a penalty that cannot be paid is owed to the sender, the delivery and the payment of the shipper stand
+                if deducted > 0 && self.payment_send(freight.token, manifest.sender, deducted).is_err() {
+                    self.shipments.owe(manifest.sender, freight.token, deducted);
+                }
+                let mut penalty = deducted;
+                if freight.penalty > deducted {
+                    let slashed = self.stakes.slash(freight.shipper, freight.penalty - deducted);
+                    if slashed > 0 {
+                        if self.env().transfer(manifest.sender, slashed).is_err() {
+                            self.shipments.owe(manifest.sender, None, slashed);
+                        }
+                        self.stake_updated(freight.shipper, self.stakes.bond_of(&freight.shipper).unwrap_or_default());
+                    }
+                    penalty += slashed;
+                }
+                self.env().emit_event(FreightSettled {
+                    manifest_id,
+                    shipper: freight.shipper,
+                    cost: freight.cost - deducted,
+                    penalty,
+                });
+            }
+            self.shipment_opened(&manifest.items, false);
+            self.env().emit_event(ShipmentDelivered {
+                manifest_id,
+                receiver: caller,
+            });
+            Ok(())
+        }
+        /// Returns the block of the delivery of a shipment
+        #[ink(message, selector = "0xEBB1F50A")]
+        pub fn shipment_delivery_get(&self, manifest_id: ManifestId) -> Option<BlockNumber> {
+            self.shipments.delivery_of(manifest_id)
+        }
+        /// Pays the caller the penalties of the shipments owed in `token`, native tokens when None, that could not be
+        /// paid at the delivery. Returns the amount paid.
+        #[ink(message, selector = "0x0946B619")]
+        pub fn shipment_penalty_claim(&mut self, token: Option<AccountId>) -> Result<Balance, Error> {
+            let caller = self.env().caller();
+            let amount = self.shipments.claim(caller, token)?;
+            if let Err(error) = self.payment_send(token, caller, amount) {
+                self.shipments.owe(caller, token, amount);
+                return Err(error)
+            }
+            Ok(amount)
+        }
+        /// Returns the penalties of the shipments owed to an account in `token`, native tokens when None
+        #[ink(message, selector = "0x33885E41")]
+        pub fn shipment_penalty_owed(&self, account: AccountId, token: Option<AccountId>) -> Balance {
+            self.shipments.owed_of(&account, token)
+        }
+        /// Clears a shipment through the customs paying the escrowed duty to the collector, only the collector can do it
+        #[ink(message, selector = "0x38149A9C")]
+        pub fn shipment_customs_clear(&mut self, manifest_id: ManifestId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let duty = self.shipments.duty_of(manifest_id).ok_or(Error::DutyNotFound)?;
+            if duty.collector != caller {
+                return Err(Error::NotAllowed)
+            }
+            self.shipments.clear(manifest_id)?;
+            if let Err(error) = self.payment_send(duty.token, duty.collector, duty.amount) {
+                self.shipments.unclear(manifest_id);
+                return Err(error)
+            }
+            self.env().emit_event(DutyReleased {
+                manifest_id,
+                collector: caller,
+                amount: duty.amount,
+            });
+            Ok(())
+        }
+        /// Returns the customs duty of a shipment
+        #[ink(message, selector = "0x9AB56E8C")]
+        pub fn shipment_duty_get(&self, manifest_id: ManifestId) -> Option<Duty> {
+            self.shipments.duty_of(manifest_id)
+        }
+        /// Cancels a shipment before its delivery, only the sender of the manifest can do it.
+        /// The duty not yet cleared and the freight are refunded to the sender.
+        #[ink(message, selector = "0xF2E4A6AD")]
+        pub fn shipment_cancel(&mut self, manifest_id: ManifestId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let manifest = self.shipments.get(manifest_id).ok_or(Error::ManifestNotFound)?;
+            if manifest.sender != caller {
+                return Err(Error::NotAllowed)
+            }
+            let (duty, freight) = self.shipments.cancel(manifest_id, self.env().block_number())?;
+            if let Some(duty) = duty {
+                if let Err(error) = self.payment_send(duty.token, caller, duty.amount) {
+                    self.shipments.uncancel(manifest_id, Some(duty), freight);
+                    return Err(error)
+                }
+            }
+            if let Some(freight) = freight {
+                // the duty is refunded already, only the freight is escrowed again
+                if let Err(error) = self.payment_send(freight.token, caller, freight.cost) {
+                    self.shipments.uncancel(manifest_id, None, Some(freight));
+                    return Err(error)
+                }
+            }
+            self.shipment_opened(&manifest.items, false);
+            self.env().emit_event(ShipmentCancelled {
+                manifest_id,
+                sender: caller,
+            });
+            Ok(())
+        }
+        /// Returns the block of the cancellation of a shipment
+        #[ink(message, selector = "0x0DE88A7E")]
+        pub fn shipment_cancellation_get(&self, manifest_id: ManifestId) -> Option<BlockNumber> {
+            self.shipments.cancellation_of(manifest_id)
+        }
+        /// Issues the cold-chain compliance certificate of a delivered shipment after reviewing its sensor history,
+        /// only administrator can do it. The certificate stores the hash of the sensor history, the temperature range
+        /// recorded in tenths of degree Celsius and the outcome, and it is bound to the assets of the manifest.
+        #[ink(message, selector = "0x9A70AC37")]
+        pub fn shipment_certificate_issue(&mut self, manifest_id: ManifestId, sensor_log: Hash, min_temperature: i16, max_temperature: i16, compliant: bool) -> Result<CertificateId, Error> {
+            // check for administrator
+            let caller = self.authorize(Message::ShipmentCertificateIssue, None)?;
+            let manifest = self.shipments.get(manifest_id).ok_or(Error::ManifestNotFound)?;
+            if self.shipments.delivery_of(manifest_id).is_none() {
+                return Err(Error::ShipmentNotDelivered)
+            }
+            // the issuer must have bonded the stake answering for its certificates
+            if self.stakes.bonded_of(&caller) < self.config.validator_stake {
+                return Err(Error::StakeRequired)
+            }
+            if min_temperature > max_temperature {
+                return Err(Error::NotAllowed)
+            }
+            let assets: Vec<AssetId> = manifest
+                .items
+                .iter()
+                .map(|item| item.id)
+                .filter(|id| self.ownership.exists(*id))
+                .collect();
+            // a revoked certificate is replaced from the new one
+            if let Some(certificate_id) = self.certificates.certificate_of(manifest_id) {
+                if self.revocations.is_revoked(Credential::Certificate(certificate_id)) {
+                    self.certificates.supersede(manifest_id);
+                }
+            }
+            let certificate = Certificate {
+                manifest_id,
+                issuer: caller,
+                sensor_log,
+                min_temperature,
+                max_temperature,
+                compliant,
+                block: self.env().block_number(),
+            };
+            let certificate_id = self.certificates.issue(certificate, &assets)?;
+            for id in assets.iter() {
+                self.contents.link(sensor_log, *id);
+            }
+            self.reward(caller, Work::Certificate);
+            self.env().emit_event(CertificateIssued {
+                certificate_id,
+                manifest_id,
+                compliant,
+            });
+            self.manifest_alert(manifest_id, Alert::Certified(certificate_id));
+            // the certificate triggers the insurance, a coverage that cannot be paid is settled again with
+            // shipment_insurance_settle
+            if self.insurance.policy_of(manifest_id).is_some() {
+                self.policy_settle(manifest_id).ok();
+            }
+            Ok(certificate_id)
+        }
+        /// Attaches an insurance policy to a shipment before its delivery, escrowing the coverage paid to `beneficiary`
+        /// when the temperature range of the compliance certificate exceeds the tolerance, in tenths of degree Celsius.
+        /// The coverage is deposited in native tokens transferred with the call or, when `token` is set, in PSP22 tokens
+        /// the contract is allowed to spend. The caller is the insurer.
+        #[ink(message, payable, selector = "0x3CB0AF90")]
+        pub fn shipment_insurance_attach(&mut self, manifest_id: ManifestId, beneficiary: AccountId, coverage: Balance, token: Option<AccountId>, min_temperature: i16, max_temperature: i16) -> Result<(), Error> {
+            let insurer = self.env().caller();
+            self.shipments.get(manifest_id).ok_or(Error::ManifestNotFound)?;
+            if self.shipments.cancellation_of(manifest_id).is_some() {
+                return Err(Error::ShipmentCancelled)
+            }
+            if self.shipments.delivery_of(manifest_id).is_some() || coverage == 0 || min_temperature > max_temperature {
+                return Err(Error::NotAllowed)
+            }
+            self.insurance.attach(manifest_id, Policy {
+                insurer,
+                beneficiary,
+                coverage,
+                token,
+                min_temperature,
+                max_temperature,
+                block: self.env().block_number(),
+                status: PolicyStatus::Active,
+            })?;
+            if let Err(error) = self.payment_collect(token, coverage) {
+                self.insurance.detach(manifest_id);
+                return Err(error)
+            }
+            self.env().emit_event(PolicyAttached {
+                manifest_id,
+                insurer,
+                coverage,
+            });
+            Ok(())
+        }
+        /// Settles the insurance policy of a certified or cancelled shipment, anyone can do it. The coverage is paid to
+        /// the beneficiary when the certificate exceeds the tolerance, otherwise it is released to the insurer.
+        #[ink(message, selector = "0x93826682")]
+        pub fn shipment_insurance_settle(&mut self, manifest_id: ManifestId) -> Result<(), Error> {
+            self.policy_settle(manifest_id)
+        }
+        /// Returns the insurance policy of a shipment
+        #[ink(message, selector = "0x0DDFC020")]
+        pub fn shipment_insurance_get(&self, manifest_id: ManifestId) -> Option<Policy> {
+            self.insurance.policy_of(manifest_id)
+        }
+        /// Returns the compliance certificate of a shipment
+        #[ink(message, selector = "0x24811190")]
+        pub fn shipment_certificate_get(&self, manifest_id: ManifestId) -> Option<Certificate> {
+            self.certificates
+                .certificate_of(manifest_id)
+                .filter(|certificate_id| !self.revocations.is_revoked(Credential::Certificate(*certificate_id)))
+                .and_then(|certificate_id| self.certificates.get(certificate_id))
+        }
+        /// Returns a page of the compliance certificates of the shipments of an asset not revoked, oldest first
+        #[ink(message, selector = "0xA839B193")]
+        pub fn asset_certificates(&self, id: AssetId, cursor: Cursor, limit: u32) -> Page<Certificate> {
+            let certificates = self
+                .certificates
+                .certificates_of(id)
+                .into_iter()
+                .filter(|(certificate_id, _)| !self.revocations.is_revoked(Credential::Certificate(*certificate_id)))
+                .map(|(_, certificate)| certificate)
+                .collect();
+            pagination::page_of(certificates, cursor, limit)
+        }
+        /// Assigns an asset to a registered shipper, only the owner can do it. Once assigned, only the carrier
+        /// and the owner can change the location of the asset.
+        #[ink(message, selector = "0x5A8BC60A")]
+        pub fn asset_carrier_assign(&mut self, id: AssetId, shipper: AccountId) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetCarrierAssign, Some(id))?;
+            if self.roles.active_role_of(&shipper) != Some(SHIPPER) {
+                return Err(Error::NotAllowed)
+            }
+            self.carriers.assign(id, Leg {
+                carrier: shipper,
+                assigned_by: caller,
+                block: self.env().block_number(),
+            });
+            self.env().emit_event(CarrierUpdated {
+                id,
+                carrier: Some(shipper),
+                from: caller,
+            });
+            Ok(())
+        }
+        /// Subcontracts the carriage of an asset to another registered shipper, only the actual carrier can do it
+        /// when the subcontract policy of the owner allows it. The subcontractor becomes the actual carrier.
+        #[ink(message, selector = "0x5EA90CAD")]
+        pub fn asset_carrier_subcontract(&mut self, id: AssetId, shipper: AccountId) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer carries it
+            let caller = self.authorize(Message::AssetCarrierSubcontract, Some(id))?;
+            let owner = self.ownership.owner_of(id).ok_or(Error::AssetNotFound)?;
+            if self.roles.active_role_of(&shipper) != Some(SHIPPER) {
+                return Err(Error::NotAllowed)
+            }
+            if !self.carriers.is_allowed(&owner, &shipper) {
+                return Err(Error::SubcontractNotAllowed)
+            }
+            self.carriers.subcontract(id, Leg {
+                carrier: shipper,
+                assigned_by: caller,
+                block: self.env().block_number(),
+            })?;
+            self.env().emit_event(CarrierUpdated {
+                id,
+                carrier: Some(shipper),
+                from: caller,
+            });
+            Ok(())
+        }
+        /// Releases an asset from its carriers, only the owner can do it
+        #[ink(message, selector = "0xA0DA24FA")]
+        pub fn asset_carrier_release(&mut self, id: AssetId) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer can change it
+            let caller = self.authorize(Message::AssetCarrierRelease, Some(id))?;
+            self.carriers.release(id).ok_or(Error::CarrierNotAssigned)?;
+            self.env().emit_event(CarrierUpdated {
+                id,
+                carrier: None,
+                from: caller,
+            });
+            Ok(())
+        }
+        /// Returns the actual carrier of an asset
+        #[ink(message, selector = "0x423C78BA")]
+        pub fn asset_carrier_get(&self, id: AssetId) -> Option<AccountId> {
+            self.carriers.carrier_of(id)
+        }
+        /// Returns a page of the chain of the carriers of an asset, from the shipper assigned from the owner to the actual carrier
+        #[ink(message, selector = "0x8987A432")]
+        pub fn asset_carrier_chain(&self, id: AssetId, cursor: Cursor, limit: u32) -> Page<Leg> {
+            pagination::page_of(self.carriers.chain_of(id), cursor, limit)
+        }
+        /// Sets the policy of the caller on the subcontracts of the carriage of its assets
+        #[ink(message, selector = "0x57D051E3")]
+        pub fn account_subcontract_policy_set(&mut self, policy: SubcontractPolicy) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.carriers.policy_set(caller, policy);
+            Ok(())
+        }
+        /// Returns the subcontract policy of an owner
+        #[ink(message, selector = "0xFA7341CF")]
+        pub fn account_subcontract_policy_get(&self, owner: AccountId) -> SubcontractPolicy {
+            self.carriers.policy_of(&owner)
+        }
+        /// Approves or revokes a shipper as subcontractor of the caller, used by the ApprovedOnly policy
+        #[ink(message, selector = "0x294D03E0")]
+        pub fn account_subcontractor_approve(&mut self, shipper: AccountId, approved: bool) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.carriers.approve(caller, shipper, approved);
+            Ok(())
+        }
+        /// Verifies if an owner approved a shipper as subcontractor
+        #[ink(message, selector = "0xA1452EA6")]
+        pub fn account_subcontractor_verify(&self, owner: AccountId, shipper: AccountId) -> bool {
+            self.carriers.is_approved(&owner, &shipper)
+        }
+
+        /// Returns the configuration of the contract
+        #[ink(message, selector = "0xFD456749")]
+        pub fn config_get(&self) -> Config {
+            Config::clone(&self.config)
+        }
+        /// Sets the name, the symbol and the base URI of the collection, only administrator can do it
+        #[ink(message, selector = "0xFE4AF929")]
+        pub fn collection_metadata_set(&mut self, name: Vec<u8>, symbol: Vec<u8>, base_uri: Vec<u8>) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::CollectionMetadataSet, None)?;
+            self.collection.name = name;
+            self.collection.symbol = symbol;
+            self.collection.base_uri = base_uri;
+            Ok(())
+        }
+        /// Returns the name of the collection
+        #[ink(message, selector = "0x039F3567")]
+        pub fn collection_name(&self) -> Vec<u8> {
+            self.collection.name.clone()
+        }
+        /// Returns the symbol of the collection
+        #[ink(message, selector = "0x3827C4BE")]
+        pub fn collection_symbol(&self) -> Vec<u8> {
+            self.collection.symbol.clone()
+        }
+        /// Returns the base URI of the token URIs of the assets
+        #[ink(message, selector = "0x291266B7")]
+        pub fn collection_base_uri(&self) -> Vec<u8> {
+            self.collection.base_uri.clone()
+        }
+        /// Returns the URI of the metadata of an asset id, as the base URI of the collection followed from the
+        /// hexadecimal hash of the metadata of the asset. None if the asset has no metadata.
+        #[ink(message, selector = "0x5B64E66A")]
+        pub fn token_uri(&self, id: AssetId) -> Option<Vec<u8>> {
+            const DIGITS: &[u8; 16] = b"0123456789abcdef";
+            let metadata = self.ownership.record_of(id)?.get(UpdateKind::Metadata)?;
+            let mut uri = self.collection.base_uri.clone();
+            for byte in metadata.as_ref() {
+                uri.push(DIGITS[(byte >> 4) as usize]);
+                uri.push(DIGITS[(byte & 0x0F) as usize]);
+            }
+            Some(uri)
+        }
+        /// Sets the maximum number of assets an account can create every `period` blocks, only administrator can do it.
+        /// A `limit` of 0 disables the rate limit.
+        #[ink(message, selector = "0x00030B44")]
+        pub fn config_mint_rate_limit_set(&mut self, limit: u32, period: BlockNumber) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::ConfigMintRateLimitSet, None)?;
+            self.config.mint_limit = limit;
+            self.config.mint_period = period;
+            Ok(())
+        }
+        /// Sets the minimum number of blocks between two updates of the same data of an asset,
+        /// only administrator can do it. A cooldown of 0 disables the check.
+        #[ink(message, selector = "0x7A0D1B3F")]
+        pub fn config_update_cooldown_set(&mut self, blocks: BlockNumber) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::ConfigUpdateCooldownSet, None)?;
+            self.config.update_cooldown = blocks;
+            Ok(())
+        }
+        /// Sets the number of recent updates kept in the log of each asset, only administrator can do it. The older
+        /// updates are folded in the digest of the asset, a size of 0 keeps the digest only.
+        #[ink(message, selector = "0x7429FBB0")]
+        pub fn config_update_log_size_set(&mut self, size: u32) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::ConfigUpdateLogSizeSet, None)?;
+            self.config.update_log_size = size;
+            Ok(())
+        }
+        /// Enables or disables the restriction of minting to the Producer and Administrator roles,
+        /// only administrator can do it.
+        #[ink(message, selector = "0xC946DC4C")]
+        pub fn config_mint_producers_only_set(&mut self, enabled: bool) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::ConfigMintProducersOnlySet, None)?;
+            self.config.mint_producers_only = enabled;
+            Ok(())
+        }
+        /// Sets or removes the registry contract of the shared categories, only administrator can do it.
+        /// The descriptions already cached are kept.
+        #[ink(message, selector = "0x4BF4BD10")]
+        pub fn config_category_registry_set(&mut self, registry: Option<AccountId>) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::ConfigCategoryRegistrySet, None)?;
+            self.config.category_registry = registry;
+            Ok(())
+        }
+        /// Sets the share of the proceeds of the sales paid to their referrers, in basis points, only administrator can do it.
+        /// A `share` of 0 disables the referrals.
+        #[ink(message, selector = "0x041160CA")]
+        pub fn config_referral_share_set(&mut self, share: u16) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::ConfigReferralShareSet, None)?;
+            if share > sales::SHARE_MAX {
+                return Err(Error::NotAllowed)
+            }
+            self.config.referral_share = share;
+            Ok(())
+        }
+        /// Sets the deposit locked from the minter to create an asset, only administrator can do it. A `deposit` of 0
+        /// disables it. The deposits already locked are kept.
+        #[ink(message, selector = "0x76AF39AA")]
+        pub fn config_mint_deposit_set(&mut self, deposit: Balance) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::ConfigMintDepositSet, None)?;
+            self.config.mint_deposit = deposit;
+            Ok(())
+        }
+        /// Sets the number of blocks between the erasure request of an account and its erasure, only administrator can do it
+        #[ink(message, selector = "0x27B8CED2")]
+        pub fn config_erasure_retention_set(&mut self, blocks: BlockNumber) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::ConfigErasureRetentionSet, None)?;
+            self.config.erasure_retention = blocks;
+            Ok(())
+        }
+        /// Sets the minimum stake the shippers must have bonded to agree a freight and the shares of their stake forfeited
+        /// for each breach in basis points, only administrator can do it. A `stake` of 0 disables the requirement.
+        #[ink(message, selector = "0x24CC1D9E")]
+        pub fn config_shipper_penalties_set(&mut self, stake: Balance, penalties: Penalties) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::ConfigShipperPenaltiesSet, None)?;
+            if !penalties.is_valid() {
+                return Err(Error::NotAllowed)
+            }
+            self.config.shipper_stake = stake;
+            self.config.shipper_penalties = penalties;
+            Ok(())
+        }
+        /// Sets the share of the stake of a validator or of an issuer of certificates forfeited to the claimant of an
+        /// upheld dispute for a fraud, in basis points, only administrator can do it
+        #[ink(message, selector = "0xB281DFA4")]
+        pub fn config_fraud_penalty_set(&mut self, share: u16) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::ConfigFraudPenaltySet, None)?;
+            if share > Penalties::MAX {
+                return Err(Error::NotAllowed)
+            }
+            self.config.fraud_penalty = share;
+            Ok(())
+        }
+        /// Sets the fee the producers pay for each era of `era` blocks to keep minting, only administrator can do it.
+        /// A `fee` of 0 disables the subscriptions. The subscriptions already paid keep their end.
+        #[ink(message, selector = "0xA19C1A50")]
+        pub fn config_subscription_set(&mut self, fee: Balance, era: BlockNumber) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::ConfigSubscriptionSet, None)?;
+            if fee > 0 && era == 0 {
+                return Err(Error::NotAllowed)
+            }
+            self.config.subscription_fee = fee;
+            self.config.subscription_era = era;
+            Ok(())
+        }
+        /// Renews the subscription of the caller, only producers can do it. The native tokens transferred pay
+        /// whole eras at the configured fee, from the end of the subscription or from now when it has lapsed.
+        #[ink(message, payable, selector = "0x5888E418")]
+        pub fn subscription_renew(&mut self) -> Result<BlockNumber, Error> {
+            // check for producer
+            let caller = self.authorize(Message::SubscriptionRenew, None)?;
+            let fee = self.config.subscription_fee;
+            if fee == 0 {
+                return Err(Error::NotAllowed)
+            }
+            let amount = self.env().transferred_balance();
+            if amount == 0 || !amount.is_multiple_of(fee) {
+                return Err(Error::InvalidPayment)
+            }
+            let eras = (amount / fee) as BlockNumber;
+            let subscription = self.subscriptions.renew(caller, eras, self.config.subscription_era, amount, self.env().block_number());
+            self.env().emit_event(SubscriptionRenewed {
+                account: caller,
+                amount,
+                paid_until: subscription.paid_until,
+            });
+            Ok(subscription.paid_until)
+        }
+        /// Returns the subscription of an account
+        #[ink(message, selector = "0x784ACE8C")]
+        pub fn subscription_get(&self, account: AccountId) -> Option<Subscription> {
+            self.subscriptions.subscription_of(&account)
+        }
+        /// Verifies if an account can mint: subscriptions are disabled, the account is not a producer or its subscription
+        /// covers the current block
+        #[ink(message, selector = "0x9448F375")]
+        pub fn subscription_active(&self, account: AccountId) -> bool {
+            self.check_subscription(&account).is_ok()
+        }
+        /// Returns the subscription fees collected and not withdrawn yet
+        #[ink(message, selector = "0x4452D9FB")]
+        pub fn subscription_fees(&self) -> Balance {
+            self.subscriptions.fees()
+        }
+        /// Withdraws collected subscription fees to an account, only administrator can do it
+        #[ink(message, selector = "0x0C58A3F9")]
+        pub fn subscription_fees_withdraw(&mut self, to: AccountId, amount: Balance) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::SubscriptionFeesWithdraw, None)?;
+            self.subscriptions.withdraw(amount)?;
+            if self.env().transfer(to, amount).is_err() {
+                self.subscriptions.unwithdraw(amount);
+                return Err(Error::PaymentFailed)
+            }
+            Ok(())
+        }
+        /// Sets the fee of the sales paid to the treasury, in basis points, only administrator can do it.
+        /// A `fee` of 0 disables it, the fee cannot exceed [`treasury::FEE_MAX`].
+        #[ink(message, selector = "0x30AE5C8C")]
+        pub fn config_transfer_fee_set(&mut self, fee: u16) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::ConfigTransferFeeSet, None)?;
+            if fee > treasury::FEE_MAX {
+                return Err(Error::NotAllowed)
+            }
+            self.config.transfer_fee = fee;
+            Ok(())
+        }
+        /// Returns the fees collected in a token and not withdrawn, `None` for the native token
+        #[ink(message, selector = "0x9F6852A7")]
+        pub fn treasury_balance(&self, token: Option<AccountId>) -> Balance {
+            self.treasury.balance_of(token)
+        }
+        /// Returns a withdrawal proposed from the treasury with its approvals
+        #[ink(message, selector = "0x1CD16A95")]
+        pub fn treasury_withdrawal_get(&self, withdrawal_id: WithdrawalId) -> Option<Withdrawal> {
+            self.treasury.withdrawal_of(withdrawal_id)
+        }
+        /// Returns the number of withdrawals proposed from the treasury
+        #[ink(message, selector = "0x9B4A63C7")]
+        pub fn treasury_withdrawals_count(&self) -> u32 {
+            self.treasury.withdrawals_count()
+        }
+        /// Proposes the withdrawal of fees collected in a token to an account, only the administrators of the set can do it.
+        /// The proposal counts as the approval of the caller. Returns the id of the withdrawal.
+        #[ink(message, selector = "0xA2061559")]
+        pub fn treasury_withdraw_propose(&mut self, token: Option<AccountId>, to: AccountId, amount: Balance) -> Result<WithdrawalId, Error> {
+            // check for administrator of the set
+            let caller = self.admin_set_authorize(Message::TreasuryWithdrawPropose)?;
+            let block = self.env().block_number();
+            let withdrawal_id = self.treasury.propose(caller, token, to, amount, block)?;
+            self.env().emit_event(WithdrawalProposed {
+                withdrawal_id,
+                proposer: caller,
+                to,
+                token,
+                amount,
+            });
+            Ok(withdrawal_id)
+        }
+        /// Approves a pending withdrawal from the treasury, only the administrators of the set can do it. Returns the number
+        /// of approvals.
+        #[ink(message, selector = "0x7E4966DE")]
+        pub fn treasury_withdraw_approve(&mut self, withdrawal_id: WithdrawalId) -> Result<u32, Error> {
+            // check for administrator of the set
+            let caller = self.admin_set_authorize(Message::TreasuryWithdrawApprove)?;
+            let withdrawal = self.treasury.approve(withdrawal_id, caller)?;
+            let approvals = withdrawal.approvals.len() as u32;
+            self.env().emit_event(WithdrawalApproved {
+                withdrawal_id,
+                approver: caller,
+                approvals,
+            });
+            Ok(approvals)
+        }
+        /// Pays a pending withdrawal from the treasury approved from the configured number of administrators, only
+        /// administrator can do it. Only the approvals of the distinct accounts still in the set of the administrators
+        /// count, the holders of the Administrator role do not approve the withdrawals.
+        #[ink(message, selector = "0x3DA6A64D")]
+        pub fn treasury_withdraw_execute(&mut self, withdrawal_id: WithdrawalId) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::TreasuryWithdrawExecute, None)?;
+            let withdrawal = self.treasury.withdrawal_of(withdrawal_id).ok_or(Error::WithdrawalNotFound)?;
+            let approvals = withdrawal
+                .approvals
+                .iter()
+                .enumerate()
+                .filter(|(position, approver)| {
+                    self.administrators.contains_key(approver) && !withdrawal.approvals[..*position].contains(approver)
+                })
+                .count() as u32;
+            if approvals < self.config.treasury_threshold.max(treasury::THRESHOLD_MIN) {
+                return Err(Error::NotApproved)
+            }
+            let block = self.env().block_number();
+            let withdrawal = self.treasury.execute(withdrawal_id, block)?;
+            if let Err(error) = self.payment_send(withdrawal.token, withdrawal.to, withdrawal.amount) {
+                self.treasury.unexecute(withdrawal_id);
+                return Err(error)
+            }
+            self.env().emit_event(TreasuryWithdrawn {
+                withdrawal_id,
+                to: withdrawal.to,
+                token: withdrawal.token,
+                amount: withdrawal.amount,
+            });
+            Ok(())
+        }
+        /// Sets the number of administrators approving a withdrawal from the treasury, only administrator can do it.
+        /// The `threshold` cannot be lower than [`treasury::THRESHOLD_MIN`] nor higher than the size of the set of the
+        /// administrators.
+        #[ink(message, selector = "0x3D16E6E6")]
+        pub fn config_treasury_threshold_set(&mut self, threshold: u32) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::ConfigTreasuryThresholdSet, None)?;
+            if threshold < treasury::THRESHOLD_MIN || threshold > self.administrators.len() {
+                return Err(Error::NotAllowed)
+            }
+            self.config.treasury_threshold = threshold;
+            Ok(())
+        }
+        /// Sets the minimum stake bonded to validate the assets and the number of blocks before the stake unbonding can be
+        /// withdrawn, only administrator can do it. A `stake` of 0 disables the requirement.
+        #[ink(message, selector = "0xC37E9179")]
+        pub fn config_validator_stake_set(&mut self, stake: Balance, unbonding_period: BlockNumber) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::ConfigValidatorStakeSet, None)?;
+            if unbonding_period < stakes::UNBONDING_PERIOD_MIN {
+                return Err(Error::NotAllowed)
+            }
+            self.config.validator_stake = stake;
+            self.config.unbonding_period = unbonding_period;
+            Ok(())
+        }
+        /// Adds the native tokens transferred to the stake of the caller
+        #[ink(message, payable, selector = "0x5FE75837")]
+        pub fn stake_bond(&mut self) -> Result<Bond, Error> {
+            let caller = self.authorize(Message::StakeBond, None)?;
+            let amount = self.env().transferred_balance();
+            if amount == 0 {
+                return Err(Error::InvalidPayment)
+            }
+            let bond = self.stakes.bond(caller, amount);
+            self.stake_updated(caller, bond);
+            Ok(bond)
+        }
+        /// Starts unbonding part of the stake of the caller. The amount unbonding can be withdrawn after the configured
+        /// period and can still be slashed until then, a new request restarts the period. The stake of an account with a
+        /// dispute open against it cannot be unbonded.
+        #[ink(message, selector = "0x340BB49D")]
+        pub fn stake_unbond(&mut self, amount: Balance) -> Result<Bond, Error> {
+            let caller = self.authorize(Message::StakeUnbond, None)?;
+            self.check_no_dispute(&caller)?;
+            let period = self.config.unbonding_period.max(stakes::UNBONDING_PERIOD_MIN);
+            let release = self.env().block_number().saturating_add(period);
+            let bond = self.stakes.unbond(caller, amount, release)?;
+            self.stake_updated(caller, bond);
+            Ok(bond)
+        }
+        /// Withdraws the amount unbonding of the caller once released and with no dispute open against it
+        #[ink(message, selector = "0xFFF3E689")]
+        pub fn stake_withdraw(&mut self) -> Result<Balance, Error> {
+            let caller = self.authorize(Message::StakeWithdraw, None)?;
+            self.check_no_dispute(&caller)?;
+            let amount = self.stakes.withdraw(caller, self.env().block_number())?;
+            if self.env().transfer(caller, amount).is_err() {
+                self.stakes.unwithdraw(caller, amount);
+                return Err(Error::PaymentFailed)
+            }
+            self.stake_updated(caller, self.stakes.bond_of(&caller).unwrap_or_default());
+            Ok(amount)
+        }
+        /// Returns the stake of an account
+        #[ink(message, selector = "0xE3E2847B")]
+        pub fn stake_get(&self, account: AccountId) -> Option<Bond> {
+            self.stakes.bond_of(&account)
+        }
+        /// Opens a dispute against the fraudulent validation of an asset, only the owner can do it.
+        /// The account that signed the validation answers with its stake.
+        #[ink(message, selector = "0xA6A6D131")]
+        pub fn validation_dispute_open(&mut self, id: AssetId, evidence: Hash) -> Result<DisputeId, Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            let caller = self.authorize(Message::ValidationDisputeOpen, Some(id))?;
+            let respondent = self.ownership.record_of(id).and_then(|record| record.signer).ok_or(Error::AssetNotFound)?;
+            self.dispute_open(DisputeSubject::Validation(id), caller, respondent, evidence)
+        }
+        /// Opens a dispute against a fraudulent compliance certificate, only the receiver of the shipment can do it.
+        /// The issuer of the certificate answers with its stake.
+        #[ink(message, selector = "0xA2BF27DB")]
+        pub fn certificate_dispute_open(&mut self, certificate_id: CertificateId, evidence: Hash) -> Result<DisputeId, Error> {
+            let caller = self.authorize(Message::CertificateDisputeOpen, None)?;
+            let certificate = self.certificates.get(certificate_id).ok_or(Error::CertificateNotFound)?;
+            let manifest = self.shipments.get(certificate.manifest_id).ok_or(Error::ManifestNotFound)?;
+            if manifest.receiver != caller {
+                return Err(Error::NotAllowed)
+            }
+            self.dispute_open(DisputeSubject::Certificate(certificate_id), caller, certificate.issuer, evidence)
+        }
+        /// Opens a dispute against the shipper of a shipment for the loss or the damage of the goods or a gross violation
+        /// of the service level, only the sender of the manifest can do it. The shipper of the freight answers with the
+        /// share of its stake configured for the breach.
+        #[ink(message, selector = "0x51B0AD05")]
+        pub fn shipment_dispute_open(&mut self, manifest_id: ManifestId, breach: Breach, evidence: Hash) -> Result<DisputeId, Error> {
+            let caller = self.authorize(Message::ShipmentDisputeOpen, None)?;
+            let manifest = self.shipments.get(manifest_id).ok_or(Error::ManifestNotFound)?;
+            if manifest.sender != caller {
+                return Err(Error::NotAllowed)
+            }
+            let freight = self.shipments.freight_of(manifest_id).ok_or(Error::NotAllowed)?;
+            self.dispute_open(DisputeSubject::Shipment(manifest_id, breach), caller, freight.shipper, evidence)
+        }
+        /// Resolves an open dispute, only administrator can do it. An upheld dispute slashes the share of the stake of the
+        /// respondent configured for a fraud to the claimant and removes the contested validation, or the share configured
+        /// for the breach of a shipper. When the slash cannot be paid the dispute stays open and the stake is restored.
+        #[ink(message, selector = "0x05094C8E")]
+        pub fn dispute_resolve(&mut self, dispute_id: DisputeId, upheld: bool) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::DisputeResolve, None)?;
+            let dispute = self.disputes.get(dispute_id).ok_or(Error::DisputeNotFound)?;
+            let bond = self.stakes.bond_of(&dispute.respondent).unwrap_or_default();
+            let status = if upheld {
+                let stake = bond.bonded.saturating_add(bond.unbonding);
+                match dispute.subject {
+                    DisputeSubject::Shipment(_, breach) => DisputeStatus::Upheld(self.config.shipper_penalties.penalty(breach, stake)),
+                    _ => DisputeStatus::Upheld(stake.saturating_mul(self.config.fraud_penalty as Balance) / Penalties::MAX as Balance),
+                }
+            } else {
+                DisputeStatus::Rejected
+            };
+            self.disputes.close(dispute_id, status)?;
+            if let DisputeStatus::Upheld(amount) = status {
+                let slashed = self.stakes.slash(dispute.respondent, amount);
+                if slashed > 0 && self.env().transfer(dispute.claimant, slashed).is_err() {
+                    self.stakes.unslash(dispute.respondent, bond);
+                    self.disputes.reopen(dispute_id);
+                    return Err(Error::PaymentFailed)
+                }
+                self.stake_updated(dispute.respondent, self.stakes.bond_of(&dispute.respondent).unwrap_or_default());
+                if let DisputeSubject::Validation(id) = dispute.subject {
+                    if self.ownership.record_mut(id).and_then(|record| record.invalidate()).is_ok() {
+                        self.asset_update_log(self.env().caller(), id, UpdateKind::Validation);
+                    }
+                }
+            }
+            self.dispute_activity(ActivityKind::DisputeResolved(dispute_id), dispute.subject, dispute.claimant, dispute.respondent);
+            self.env().emit_event(DisputeResolved {
+                dispute_id,
+                status,
+            });
+            Ok(())
+        }
+        /// Returns a dispute
+        #[ink(message, selector = "0x5BACD61B")]
+        pub fn dispute_get(&self, dispute_id: DisputeId) -> Option<Dispute> {
+            self.disputes.get(dispute_id)
+        }
+        /// Appends the hash of a message to the thread of a sale or of a dispute, only the seller and the buyer of the
+        /// sale or the claimant and the respondent of the open dispute can do it. The content stays off-chain.
+        #[ink(message, selector = "0x5807553F")]
+        pub fn thread_post(&mut self, thread: Thread, content: Hash) -> Result<(), Error> {
+            let caller = self.authorize(Message::ThreadPost, None)?;
+            let (party, counterparty) = match thread {
+                Thread::Sale(id, index) => {
+                    let sale = self.sales.sale_of(id, index).ok_or(Error::SaleNotFound)?;
+                    (sale.seller, sale.buyer)
+                }
+                Thread::Dispute(dispute_id) => {
+                    let dispute = self.disputes.get(dispute_id).ok_or(Error::DisputeNotFound)?;
+                    if dispute.status != DisputeStatus::Open {
+                        return Err(Error::NotAllowed)
+                    }
+                    (dispute.claimant, dispute.respondent)
+                }
+            };
+            if caller != party && caller != counterparty {
+                return Err(Error::NotAllowed)
+            }
+            let block = self.env().block_number();
+            self.threads.append(thread, Post {
+                author: caller,
+                content,
+                block,
+            });
+            Ok(())
+        }
+        /// Returns the number of messages of the thread of a sale or of a dispute
+        #[ink(message, selector = "0xC16BD5C8")]
+        pub fn thread_posts_count(&self, thread: Thread) -> u32 {
+            self.threads.posts_count(thread)
+        }
+        /// Returns a page of the messages of the thread of a sale or of a dispute, oldest first
+        #[ink(message, selector = "0xBD138A98")]
+        pub fn thread_posts(&self, thread: Thread, cursor: Cursor, limit: u32) -> Page<Post> {
+            let posts = self.threads.posts_of(thread, cursor, pagination::limit(limit));
+            pagination::page(posts, cursor, self.threads.posts_count(thread))
+        }
+        /// Returns the cursor of the first transfer of the assets done from block `block` included
+        #[ink(message, selector = "0x679A4BEA")]
+        pub fn transfers_since(&self, block: BlockNumber) -> Cursor {
+            self.journal.position_since(block)
+        }
+        /// Returns a page of the transfers of the assets, in the order they happened. Creations are recorded from
+        /// the zero account and deletions to the zero account.
+        #[ink(message, selector = "0x578F1B4E")]
+        pub fn transfers_get(&self, cursor: Cursor, limit: u32) -> Page<TransferRecord> {
+            let transfers = self.journal.entries(cursor, pagination::limit(limit));
+            pagination::page(transfers, cursor, self.journal.len())
+        }
+        /// Returns a page of the chain of custody of an asset, newest first: its transfers from the creation or the
+        /// split of the lot with their block and timestamp. The cursor of the next page is the position in the journal of
+        /// its first transfer plus one, so the transfers done meanwhile do not shift the pages. The history of a deleted
+        /// asset is in the journal only.
+        #[ink(message, selector = "0x0009C9A0")]
+        pub fn asset_history_get(&self, id: AssetId, cursor: Cursor, limit: u32) -> Page<TransferRecord> {
+            let from = match cursor {
+                0 => self.ownership.record_of(id).and_then(|record| record.last_transfer),
+                position => Some(position - 1),
+            };
+            let (transfers, next) = self.journal.chain(id, from, pagination::limit(limit));
+            (transfers, next.map(|position| position + 1))
+        }
+        /// Returns the number of transfers recorded in the journal
+        #[ink(message, selector = "0x0A15814C")]
+        pub fn transfers_count(&self) -> u32 {
+            self.journal.len()
+        }
+        /// Returns a page of the recent updates of an asset, newest first
+        #[ink(message, selector = "0xB4C32025")]
+        pub fn asset_updates_recent(&self, id: AssetId, cursor: Cursor, limit: u32) -> Page<UpdateEntry> {
+            let updates = self.updates.latest(id, cursor, pagination::limit(limit));
+            pagination::page(updates, cursor, self.updates.len(id))
+        }
+        /// Returns the rolling digest of the updates of an asset evicted from its log and their number
+        #[ink(message, selector = "0x332C94D6")]
+        pub fn asset_updates_digest(&self, id: AssetId) -> UpdateDigest {
+            self.updates.digest_of(id)
+        }
+        /// Returns the aggregate counters of the operations done on the contract
+        #[ink(message, selector = "0x76B867E4")]
+        pub fn stats_get(&self) -> Stats {
+            Stats::clone(&self.stats)
+        }
+        /// Returns the stable code of an error, passed back as it was returned from a call or a dry-run
+        #[ink(message, selector = "0x9D2B2579")]
+        pub fn error_code(&self, error: Error) -> u16 {
+            error.code()
+        }
+
+        /// Returns the deletegated account ID for this asset if any.
+        #[ink(message, selector = "0x3572453A")]
+        pub fn asset_get_delegated_account(&self, id: AssetId) -> Option<AccountId> {
+            self.ownership.record_of(id).and_then(|record| record.delegate)
+        }
+        /// Delegate or undelegate an account to manage all the asset on behalf of the caller
+        #[ink(message, selector = "0x1FAAEDB3")]
+        pub fn account_delegate_for_all_asset(&mut self,to: AccountId,approved: bool,) -> Result<(), Error> {
+            let caller = self.authorize(Message::AccountDelegateForAllAsset, None)?;
+            self.proxy_for_all_assets(caller, to, approved)?;
+            Ok(())
+        }
+        /// Returns `true` if the operator is approved by the owner to manage any asset.
+        #[ink(message, selector = "0x33AB44D1")]
+        pub fn account_verify_delegated_for_all_asset(&self, owner: AccountId, operator: AccountId) -> bool {
+            self.delegation.is_operator(&owner, &operator)
+        }
+        /// Delegate an account to transfer the specified asset on behalf of the caller.
+        #[ink(message, selector = "0x4F3D664B")]
+        pub fn account_delegate_single_asset(&mut self, to: AccountId, id: AssetId) -> Result<(), Error> {
+            let caller = self.authorize(Message::AccountDelegateSingleAsset, Some(id))?;
+            // the asset must not be already delegated
+            let record = self.ownership.record_mut(id)?;
+            if record.delegate.is_some() {
+                return Err(Error::CannotInsert)
+            }
+            record.delegate(to)?;
+            self.env().emit_event(ProxyUpdated {
+                from: caller,
+                to,
+                id,
+            });
+            Ok(())
+        }
+        /// Grants an account write access to the field groups of an asset, replacing its previous grant, only the
+        /// owner can do it. The groups are a bitmask of FIELD_DESCRIPTION = 1, FIELD_PHOTO = 2, FIELD_CATEGORY = 4,
+        /// FIELD_LOCATION = 8, FIELD_METADATA = 16, FIELD_PHYSICAL = 32, 0 revokes the grant.
+        /// The grants are removed when the asset is transferred.
+        #[ink(message, selector = "0x6C76DFA3")]
+        pub fn asset_acl_set(&mut self, id: AssetId, account: AccountId, groups: FieldGroups) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            self.authorize(Message::AssetAclSet, Some(id))?;
+            self.acl.set(id, account, groups);
+            self.env().emit_event(AclUpdated {
+                id,
+                account,
+                groups,
+            });
+            Ok(())
+        }
+        /// Returns a page of the accounts with write access to the fields of an asset and their field groups
+        #[ink(message, selector = "0x2437F436")]
+        pub fn asset_acl_get(&self, id: AssetId, cursor: Cursor, limit: u32) -> Page<(AccountId, FieldGroups)> {
+            pagination::page_of(self.acl.entries_of(id), cursor, limit)
+        }
+        /// Enables or disables the custodial mode of the caller, allowing the administrators to install
+        /// or revoke proxies on its behalf
+        #[ink(message, selector = "0x9FBC9C2F")]
+        pub fn account_custodial_set(&mut self, enabled: bool) -> Result<(), Error> {
+            let caller = self.authorize(Message::AccountCustodialSet, None)?;
+            self.delegation.custodial_set(caller, enabled);
+            Ok(())
+        }
+        /// Verifies if an owner has enabled the custodial mode
+        #[ink(message, selector = "0x2E29DE00")]
+        pub fn account_custodial_verify(&self, owner: AccountId) -> bool {
+            self.delegation.is_custodial(&owner)
+        }
+        /// Delegate or undelegate an account to manage all the assets on behalf of a custodial owner,
+        /// only administrator can do it
+        #[ink(message, selector = "0x5F994F23")]
+        pub fn custodial_delegate_for_all_asset(&mut self, owner: AccountId, to: AccountId, approved: bool) -> Result<(), Error> {
+            let caller = self.check_custodial_administrator(Message::CustodialDelegateForAllAsset, &owner)?;
+            self.proxy_for_all_assets(owner, to, approved)?;
+            self.custodial_proxy_updated(caller, owner, to, None, approved);
+            Ok(())
+        }
+        /// Delegate an account to manage a single asset on behalf of its custodial owner, only administrator can do it
+        #[ink(message, selector = "0x699C10CF")]
+        pub fn custodial_delegate_single_asset(&mut self, to: AccountId, id: AssetId) -> Result<(), Error> {
+            let owner = self.ownership.owner_of(id).ok_or(Error::AssetNotFound)?;
+            let caller = self.check_custodial_administrator(Message::CustodialDelegateSingleAsset, &owner)?;
+            self.ownership.record_mut(id)?.delegate(to)?;
+            self.custodial_proxy_updated(caller, owner, to, Some(id), true);
+            Ok(())
+        }
+        /// Removes the delegated account of a single asset of a custodial owner, only administrator can do it
+        #[ink(message, selector = "0xE75C8079")]
+        pub fn custodial_undelegate_single_asset(&mut self, id: AssetId) -> Result<(), Error> {
+            let owner = self.ownership.owner_of(id).ok_or(Error::AssetNotFound)?;
+            let caller = self.check_custodial_administrator(Message::CustodialUndelegateSingleAsset, &owner)?;
+            let operator = self.ownership.record_mut(id)?.delegate.take().ok_or(Error::CannotRemove)?;
+            self.custodial_proxy_updated(caller, owner, operator, Some(id), false);
+            Ok(())
+        }
+        /// Adds an account to the set of the administrators, only the administrators of the set can do it
+        #[ink(message, selector = "0xE0E7E580")]
+        pub fn admin_add(&mut self, account: AccountId) -> Result<(), Error> {
+            let caller = self.admin_set_authorize(Message::AdminAdd)?;
+            if self.administrators.contains_key(&account) {
+                return Err(Error::DuplicatedData)
+            }
+            self.administrators.insert(account, true);
+            self.administrator_updated(caller, account, true);
+            Ok(())
+        }
+        /// Removes an account from the set of the administrators, only the administrators of the set can do it.
+        /// The last administrator cannot be removed.
+        #[ink(message, selector = "0x77D17704")]
+        pub fn admin_remove(&mut self, account: AccountId) -> Result<(), Error> {
+            let caller = self.admin_set_authorize(Message::AdminRemove)?;
+            if !self.administrators.contains_key(&account) {
+                return Err(Error::CannotRemove)
+            }
+            // the set cannot shrink below the approvals needed from the treasury
+            if self.administrators.len() == 1 || self.administrators.len() - 1 < self.config.treasury_threshold {
+                return Err(Error::NotAllowed)
+            }
+            self.administrators.take(&account);
+            self.administrator_updated(caller, account, false);
+            Ok(())
+        }
+        /// Hands the place of the caller in the set of the administrators over to another account
+        #[ink(message, selector = "0x2A7C7B83")]
+        pub fn admin_transfer(&mut self, to: AccountId) -> Result<(), Error> {
+            let caller = self.admin_set_authorize(Message::AdminTransfer)?;
+            if self.administrators.contains_key(&to) {
+                return Err(Error::DuplicatedData)
+            }
+            self.administrators.take(&caller);
+            self.administrators.insert(to, true);
+            self.administrator_updated(caller, caller, false);
+            self.administrator_updated(caller, to, true);
+            Ok(())
+        }
+        /// Returns true if an account is in the set of the administrators
+        #[ink(message, selector = "0x11F6A98C")]
+        pub fn admin_verify(&self, account: AccountId) -> bool {
+            self.administrators.contains_key(&account)
+        }
+        /// Returns the number of entries of the administrators log
+        #[ink(message, selector = "0x6BDCB2B1")]
+        pub fn admin_log_count(&self) -> u32 {
+            *self.admin_log_count
+        }
+        /// Returns a page of the entries of the administrators log, oldest first
+        #[ink(message, selector = "0x49478AFF")]
+        pub fn admin_log_get(&self, cursor: Cursor, limit: u32) -> Page<AdminLogEntry> {
+            let entries = (cursor..*self.admin_log_count)
+                .take(pagination::limit(limit) as usize)
+                .filter_map(|index| self.admin_log.get(&index).cloned())
+                .collect();
+            pagination::page(entries, cursor, *self.admin_log_count)
+        }
+        /// Returns the entries of the administrators log done from `admin`, or from every administrator when None,
+        /// only auditors and administrators can do it. The page reads `limit` entries of the log from the cursor and
+        /// returns the matching ones, so it can hold fewer entries and still have a next page.
+        #[ink(message, selector = "0x6A80BC80")]
+        pub fn audit_admin_log(&self, admin: Option<AccountId>, cursor: Cursor, limit: u32) -> Result<Page<AdminLogEntry>, Error> {
+            // check for auditor
+            self.authorize(Message::AuditAdminLog, None)?;
+            let end = cursor.saturating_add(pagination::limit(limit)).min(*self.admin_log_count);
+            let entries = (cursor..end)
+                .filter_map(|index| self.admin_log.get(&index).cloned())
+                .filter(|entry| admin.is_none_or(|admin| entry.admin == admin))
+                .collect();
+            Ok((entries, Some(end).filter(|end| *end < *self.admin_log_count)))
+        }
+        /// Returns the transfers of the assets sent or received from an account, in the order they happened, only
+        /// auditors and administrators can do it. The page reads `limit` transfers of the journal from the cursor and
+        /// returns the matching ones, so it can hold fewer transfers and still have a next page.
+        #[ink(message, selector = "0x211F17FB")]
+        pub fn audit_transfers(&self, account: AccountId, cursor: Cursor, limit: u32) -> Result<Page<TransferRecord>, Error> {
+            // check for auditor
+            self.authorize(Message::AuditTransfers, None)?;
+            let end = cursor.saturating_add(pagination::limit(limit)).min(self.journal.len());
+            let transfers = self
+                .journal
+                .entries(cursor, end.saturating_sub(cursor))
+                .into_iter()
+                .filter(|transfer| transfer.from == account || transfer.to == account)
+                .collect();
+            Ok((transfers, Some(end).filter(|end| *end < self.journal.len())))
+        }
+        /// Returns a page of the accounts granted the key of the private data of an asset with the hash of their
+        /// wrapped key, only auditors and administrators can do it
+        #[ink(message, selector = "0xE0CBB942")]
+        pub fn audit_private_grants(&self, id: AssetId, cursor: Cursor, limit: u32) -> Result<Page<(AccountId, Hash)>, Error> {
+            // check for auditor
+            self.authorize(Message::AuditPrivateGrants, None)?;
+            let grants = self
+                .privacy
+                .grantees_of(id)
+                .into_iter()
+                .filter_map(|account| self.privacy.key_of(id, &account).map(|key| (account, key)))
+                .collect();
+            Ok(pagination::page_of(grants, cursor, limit))
+        }
+        /// Locks an asset in the custody of the contract to mirror it as an ERC-721 token on the EVM chain,
+        /// only the owner can do it. The token id is the asset id and the token is minted to the EVM recipient.
+        #[ink(message, selector = "0xC0F6C1CB")]
+        pub fn asset_bridge_lock(&mut self, id: AssetId, recipient: EvmAddress) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            let caller = self.authorize(Message::AssetBridgeLock, Some(id))?;
+            // the contract keeps the locked asset in its own custody
+            let contract = self.env().account_id();
+            self.asset_transfer_check(&caller, &contract, id)?;
+            self.asset_move(&caller, &contract, id)?;
+            let metadata = self.ownership.record_of(id).and_then(|record| record.get(UpdateKind::Metadata));
+            let lock = self.bridge.lock(id, caller, recipient, metadata, self.env().block_number())?;
+            self.env().emit_event(BridgeLocked {
+                id,
+                recipient,
+                nonce: lock.nonce,
+                payload_hash: Bridge::payload_hash(&Bridge::payload(id, &lock)),
+            });
+            Ok(())
+        }
+        /// Unlocks an asset with the proof of the burn of its wrapped token, releasing it to the account named in the
+        /// burn, only the bridge relayer can do it. Each burn proof unlocks once.
+        #[ink(message, selector = "0x017AF612")]
+        pub fn asset_bridge_unlock(&mut self, id: AssetId, nonce: u64, burn_proof: Hash, to: AccountId) -> Result<(), Error> {
+            //check if asset id is present in the storage
+            let caller = self.authorize(Message::AssetBridgeUnlock, Some(id))?;
+            if self.bridge.relayer() != Some(caller) {
+                return Err(Error::NotAllowed)
+            }
+            if to == AccountId::from([0x0; 32]) {
+                return Err(Error::NotAllowed)
+            }
+            self.bridge.unlock(id, nonce, burn_proof)?;
+            let contract = self.env().account_id();
+            self.ownership.transfer(&contract, &to, id)?;
+            self.stats.transfers += 1;
+            self.transferred(contract, to, id);
+            self.env().emit_event(BridgeUnlocked {
+                id,
+                to,
+                burn_proof,
+            });
+            Ok(())
+        }
+        /// Returns the lock of an asset mirrored on the EVM chain
+        #[ink(message, selector = "0x5B60D22B")]
+        pub fn asset_bridge_lock_get(&self, id: AssetId) -> Option<BridgeLock> {
+            self.bridge.lock_of(id)
+        }
+        /// Returns the ABI-encoded payload of the lock of an asset: token id, EVM recipient, metadata pointer and nonce
+        #[ink(message, selector = "0x7E74E122")]
+        pub fn asset_bridge_payload(&self, id: AssetId) -> Option<Vec<u8>> {
+            self.bridge.lock_of(id).map(|lock| Bridge::payload(id, &lock))
+        }
+        /// Sets or removes the account allowed to submit the burn proofs, only administrator can do it
+        #[ink(message, selector = "0x25371032")]
+        pub fn bridge_relayer_set(&mut self, relayer: Option<AccountId>) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::BridgeRelayerSet, None)?;
+            self.bridge.relayer_set(relayer);
+            Ok(())
+        }
+        /// Returns the account allowed to submit the burn proofs
+        #[ink(message, selector = "0x49BF2FB5")]
+        pub fn bridge_relayer_get(&self) -> Option<AccountId> {
+            self.bridge.relayer()
+        }
+        /// Lists an asset for sale, only the owner can do it. The price is in the smallest unit of the fiat `currency`,
+        /// converted at the purchase from its price oracle, or in the settlement token when `currency` is None.
+        /// `token` is the PSP22 token of the payment, None for the native token.
+        #[ink(message, selector = "0xBE2AB769")]
+        pub fn asset_listing_new(&mut self, id: AssetId, price: Balance, currency: Option<Currency>, token: Option<AccountId>) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            let seller = self.authorize(Message::AssetListingNew, Some(id))?;
+            self.listing_new(seller, id, price, currency, token)
+        }
+        /// Lists an asset for sale at the price of the price list of its producer for its category in force at the
+        /// current block, only the owner can do it. The price of a bulk asset is the unit price times its quantity.
+        #[ink(message, selector = "0x3CE94EC0")]
+        pub fn asset_listing_new_at_list_price(&mut self, id: AssetId, token: Option<AccountId>) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            let seller = self.authorize(Message::AssetListingNew, Some(id))?;
+            let category = self.ownership.record_of(id).and_then(|record| record.category).ok_or(Error::PriceNotFound)?;
+            let producer = self.provenance.producer_of(id).ok_or(Error::PriceNotFound)?;
+            let entry = self
+                .price_lists
+                .price_at(&producer, category, self.env().block_number())
+                .ok_or(Error::PriceNotFound)?;
+            let price = match self.quantities.quantity_of(id) {
+                Some(quantity) => entry.unit_price.checked_mul(quantity.amount).ok_or(Error::NotAllowed)?,
+                None => entry.unit_price,
+            };
+            self.listing_new(seller, id, price, entry.currency, token)
+        }
+        /// Lists asset `id` of `seller` for sale
+        fn listing_new(&mut self, seller: AccountId, id: AssetId, price: Balance, currency: Option<Currency>, token: Option<AccountId>) -> Result<(), Error> {
+            // the assets mirrored on the EVM chain stay in the custody of the contract
+            if self.bridge.is_locked(id) {
+                return Err(Error::AssetBridged)
+            }
+            if self.holds.is_held(id) {
+                return Err(Error::AssetOnHold)
+            }
+            if self.liens.is_encumbered(id) {
+                return Err(Error::AssetEncumbered)
+            }
+            self.check_in_stock(id)?;
+            // the sellers cannot list the categories restricted in their jurisdiction
+            if let Some(jurisdiction) = self.taxes.jurisdiction_of(&seller) {
+                let category = self.ownership.record_of(id).and_then(|record| record.category);
+                if category.is_some_and(|category| self.jurisdictions.flags_of(category, jurisdiction) & SALE_RESTRICTED != 0) {
+                    return Err(Error::RestrictedInJurisdiction)
+                }
+            }
+            self.sales.list(id, Listing {
+                seller,
+                price,
+                currency,
+                token,
+                charity: None,
+                block: self.env().block_number(),
+            })?;
+            self.env().emit_event(AssetListed {
+                id,
+                seller,
+                price,
+                currency,
+            });
+            Ok(())
+        }
+        /// Removes the listing of an asset, only the owner can do it
+        #[ink(message, selector = "0xFC191026")]
+        pub fn asset_listing_cancel(&mut self, id: AssetId) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            self.authorize(Message::AssetListingCancel, Some(id))?;
+            self.sales.unlist(id).ok_or(Error::ListingNotFound)?;
+            Ok(())
+        }
+        /// Sets or removes the charity receiving a share of the proceeds of the listing of an asset, only the owner can
+        /// do it. The share, in basis points of the proceeds of the seller, is shown to the buyers on the listing.
+        #[ink(message, selector = "0xB9A948BA")]
+        pub fn asset_listing_charity_set(&mut self, id: AssetId, charity: Option<Charity>) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            self.authorize(Message::AssetListingCharitySet, Some(id))?;
+            self.sales.charity_set(id, charity)
+        }
+        /// Registers the Blake2x256 hash of a discount code on the listing of an asset, only the owner can do it.
+        /// The code reduces the price until the block `expiry` excluded, and can be redeemed once.
+        #[ink(message, selector = "0x645EA1B1")]
+        pub fn asset_discount_new(&mut self, id: AssetId, code_hash: Hash, discount: Discount) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            self.authorize(Message::AssetDiscountNew, Some(id))?;
+            if !discount.is_valid() || discount.expiry <= self.env().block_number() {
+                return Err(Error::NotAllowed)
+            }
+            self.sales.discount_add(id, code_hash, discount)
+        }
+        /// Removes a discount code from the listing of an asset, only the owner can do it
+        #[ink(message, selector = "0x30281A8A")]
+        pub fn asset_discount_cancel(&mut self, id: AssetId, code_hash: Hash) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            self.authorize(Message::AssetDiscountCancel, Some(id))?;
+            self.sales.discount_remove(id, code_hash)
+        }
+        /// Returns the discount of the hash of a code on the listing of an asset
+        #[ink(message, selector = "0x5B16A470")]
+        pub fn asset_discount_get(&self, id: AssetId, code_hash: Hash) -> Option<Discount> {
+            self.sales.discount_of(id, code_hash)
+        }
+        /// Returns the listing of an asset
+        #[ink(message, selector = "0x230F2A19")]
+        pub fn asset_listing_get(&self, id: AssetId) -> Option<Listing> {
+            self.sales.listing_of(id)
+        }
+        /// Returns the amount to pay now for a listed asset and the rate of the oracle used to compute it
+        #[ink(message, selector = "0xB58534F2")]
+        pub fn asset_listing_quote(&self, id: AssetId) -> Result<(Balance, Option<Balance>), Error> {
+            let listing = self.sales.listing_of(id).ok_or(Error::ListingNotFound)?;
+            self.settlement_of(&listing)
+        }
+        /// Reserves an asset for a buyer until the block `until_block` included, only the owner can do it. The
+        /// reserved asset can be transferred or sold only to the buyer. The native tokens transferred are the deposit
+        /// of the reservation, paid back to the owner when the buyer receives the asset or the reservation ends.
+        #[ink(message, payable, selector = "0x38C8AC84")]
+        pub fn asset_reserve(&mut self, id: AssetId, buyer: AccountId, until_block: BlockNumber) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            let owner = self.authorize(Message::AssetReserve, Some(id))?;
+            let now = self.env().block_number();
+            if buyer == owner || buyer == AccountId::from([0x0; 32]) || until_block <= now {
+                return Err(Error::NotAllowed)
+            }
+            if self.ownership.record_of(id).and_then(|record| record.reservation).is_some_and(|reservation| reservation.is_active(now)) {
+                return Err(Error::AssetReserved)
+            }
+            self.check_not_pending(id)?;
+            // the deposit of an expired reservation goes back to the owner
+            self.reservation_refund(id, owner)?;
+            let reservation = Reservation {
+                buyer,
+                until: until_block,
+                deposit: self.env().transferred_balance(),
+            };
+            self.ownership.record_mut(id)?.reservation = Some(reservation);
+            self.env().emit_event(AssetReserved {
+                id,
+                buyer,
+                until: until_block,
+                deposit: reservation.deposit,
+            });
+            Ok(())
+        }
+        /// Releases the reservation of an asset. The buyer can do it at any time and anyone once the reservation
+        /// expired, paying the deposit back to the owner. The owner can do it at any time, but the deposit of a
+        /// reservation in force is paid to the buyer.
+        #[ink(message, selector = "0x448FDF2E")]
+        pub fn asset_reservation_release(&mut self, id: AssetId) -> Result<(), Error> {
+            //check if asset id is present in the storage
+            let caller = self.authorize(Message::AssetReservationRelease, Some(id))?;
+            let owner = self.ownership.owner_of(id).ok_or(Error::AssetNotFound)?;
+            let now = self.env().block_number();
+            let reservation = self
+                .ownership
+                .record_of(id)
+                .and_then(|record| record.reservation)
+                .ok_or(Error::ReservationNotFound)?;
+            if reservation.is_active(now) && caller != owner && caller != reservation.buyer {
+                return Err(Error::NotAllowed)
+            }
+            self.reservation_refund(id, reservation.deposit_to(owner, caller, now))
+        }
+        /// Returns the reservation of an asset, also once expired until it is released or replaced
+        #[ink(message, selector = "0x748F251E")]
+        pub fn asset_reservation_get(&self, id: AssetId) -> Option<Reservation> {
+            self.ownership.record_of(id).and_then(|record| record.reservation)
+        }
+        /// Buys a listed asset paying the seller, anyone can do it. The amount is computed at the purchase and cannot
+        /// exceed `max_amount`: the native tokens transferred must be `max_amount` and the excess is refunded,
+        /// the PSP22 tokens are transferred from the buyer. The configured fee of the amount is paid to the treasury,
+        /// unless the seller or the buyer is an administrator, and the configured share of the rest is paid to
+        /// `referrer`, which cannot be the buyer or the seller. The share of the charity of the listing is donated from
+        /// the proceeds of the seller. A discount `code` of the listing reduces the price.
+        #[ink(message, payable, selector = "0xB5EA3E1F")]
+        pub fn asset_purchase(&mut self, id: AssetId, max_amount: Balance, referrer: Option<AccountId>, code: Option<Vec<u8>>) -> Result<(), Error> {
+            //check if asset id is present in the storage
+            let buyer = self.authorize(Message::AssetPurchase, Some(id))?;
+            let mut listing = self.sales.listing_of(id).ok_or(Error::ListingNotFound)?;
+            let discount = match code {
+                Some(code) => {
+                    let (code_hash, discount) = self.sales.discount_check(id, &code, self.env().block_number())?;
+                    listing.price = discount.apply(listing.price);
+                    Some(code_hash)
+                }
+                None => None,
+            };
+            if buyer == listing.seller {
+                return Err(Error::NotAllowed)
+            }
+            if self.holds.is_held(id) {
+                return Err(Error::AssetOnHold)
+            }
+            self.check_in_stock(id)?;
+            // a lien consented after the listing blocks the sale
+            if self.liens.is_encumbered(id) {
+                return Err(Error::AssetEncumbered)
+            }
+            self.check_reservation(id, &buyer)?;
+            self.check_not_pending(id)?;
+            if referrer.is_some_and(|referrer| referrer == buyer || referrer == listing.seller) {
+                return Err(Error::SelfReferral)
+            }
+            let (amount, rate) = self.settlement_of(&listing)?;
+            if amount > max_amount {
+                return Err(Error::PriceExceeded)
+            }
+            // the excess of the native tokens is refunded before any other payment
+            let refund = match listing.token {
+                None => max_amount - amount,
+                Some(_) => 0,
+            };
+            // the price includes the tax of the jurisdiction of the seller, the shares are computed without it
+            let category = self.ownership.record_of(id).and_then(|record| record.category);
+            let taxation = self
+                .taxes
+                .jurisdiction_of(&listing.seller)
+                .and_then(|jurisdiction| self.taxes.rate_of(jurisdiction, category).map(|rate| (jurisdiction, rate)));
+            let tax = taxation.map_or(0, |(_, rate)| rate.tax_of(amount));
+            let mut collector = taxation.and_then(|(_, rate)| rate.collector).filter(|_| tax > 0);
+            let net = amount - tax;
+            let fee = if self.is_administrator(&listing.seller) || self.is_administrator(&buyer) {
+                0
+            } else {
+                sales::share_of(net, self.config.transfer_fee)
+            };
+            let mut referral = referrer.map_or(0, |_| sales::share_of(net - fee, self.config.referral_share));
+            let mut donation = listing.charity.map_or(0, |charity| sales::share_of(net - fee - referral, charity.share));
+            let collected = if collector.is_some() { tax } else { 0 };
+            // the reservation converts into the sale, its deposit is paid back before the payments of the sale
+            self.reservation_refund(id, listing.seller)?;
+            self.payment_collect(listing.token, amount + refund)?;
+            if refund > 0 {
+                self.payment_send(None, buyer, refund)?;
+            }
+            if let Err(error) = self.payment_send(listing.token, listing.seller, amount - fee - referral - donation - collected) {
+                self.payment_send(listing.token, buyer, amount)?;
+                return Err(error)
+            }
+            if let Some(referrer) = referrer.filter(|_| referral > 0) {
+                // a referral that cannot be paid goes to the seller
+                if self.payment_send(listing.token, referrer, referral).is_err() {
+                    self.payment_send(listing.token, listing.seller, referral)?;
+                    referral = 0;
+                }
+            }
+            if let Some(charity) = listing.charity.filter(|_| donation > 0) {
+                // a donation that cannot be paid goes to the seller
+                if self.payment_send(listing.token, charity.account, donation).is_err() {
+                    self.payment_send(listing.token, listing.seller, donation)?;
+                    donation = 0;
+                }
+            }
+            if let Some(account) = collector {
+                // a tax that cannot be paid to the collector goes to the seller, who remits it
+                if self.payment_send(listing.token, account, tax).is_err() {
+                    self.payment_send(listing.token, listing.seller, tax)?;
+                    collector = None;
+                }
+            }
+            self.asset_move(&listing.seller, &buyer, id)?;
+            if fee > 0 {
+                self.treasury.deposit(listing.token, fee);
+                self.env().emit_event(FeeCollected {
+                    id,
+                    token: listing.token,
+                    amount: fee,
+                });
+            }
+            if let Some(code_hash) = discount {
+                self.sales.redeem(listing.seller, code_hash, self.env().block_number());
+            }
+            if let Some((jurisdiction, rate)) = taxation {
+                let sale = self.sales.sales_count(id);
+                let block = self.env().block_number();
+                self.taxes.record(TaxRecord {
+                    id,
+                    sale,
+                    jurisdiction,
+                    category,
+                    rate: rate.rate,
+                    tax,
+                    collector,
+                    block,
+                });
+                self.env().emit_event(TaxRecorded {
+                    id,
+                    jurisdiction,
+                    sale,
+                    tax,
+                    collector,
+                });
+            }
+            self.sales.record(id, Sale {
+                seller: listing.seller,
+                buyer,
+                price: listing.price,
+                currency: listing.currency,
+                rate,
+                amount,
+                token: listing.token,
+                referrer,
+                referral,
+                fee,
+                charity: listing.charity.map(|charity| charity.account),
+                donation,
+                discount,
+                block: self.env().block_number(),
+            });
+            self.env().emit_event(AssetSold {
+                id,
+                seller: listing.seller,
+                buyer,
+                amount,
+                rate,
+                referrer,
+            });
+            Ok(())
+        }
+        /// Returns a page of the sales of an asset settled through the contract, oldest first
+        #[ink(message, selector = "0xFD55DB7D")]
+        pub fn asset_sales(&self, id: AssetId, cursor: Cursor, limit: u32) -> Page<Sale> {
+            pagination::page_of(self.sales.sales_of(id), cursor, limit)
+        }
+        /// Registers a jurisdiction with the hash of the document of its rules and its customs collector, or updates
+        /// them, only administrator can do it
+        #[ink(message, selector = "0x9844140C")]
+        pub fn jurisdiction_set(&mut self, jurisdiction: Jurisdiction, rules: Hash, customs: Option<AccountId>) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::JurisdictionSet, None)?;
+            let block = self.env().block_number();
+            self.jurisdictions.set(jurisdiction, JurisdictionEntry {
+                rules,
+                customs,
+                block,
+            });
+            Ok(())
+        }
+        /// Returns the entry of a jurisdiction in the registry
+        #[ink(message, selector = "0x1CAE6270")]
+        pub fn jurisdiction_get(&self, jurisdiction: Jurisdiction) -> Option<JurisdictionEntry> {
+            self.jurisdictions.get(jurisdiction)
+        }
+        /// Returns a page of the codes of the jurisdictions registered, in the order of registration
+        #[ink(message, selector = "0x0249883A")]
+        pub fn jurisdictions(&self, cursor: Cursor, limit: u32) -> Page<Jurisdiction> {
+            let codes = self.jurisdictions.list(cursor, pagination::limit(limit));
+            pagination::page(codes, cursor, self.jurisdictions.jurisdictions_count())
+        }
+        /// Sets the compliance flags of a category in a registered jurisdiction, only administrator can do it. The
+        /// sellers of the jurisdiction cannot list the assets of a category flagged with SALE_RESTRICTED.
+        #[ink(message, selector = "0xE4F47791")]
+        pub fn category_compliance_set(&mut self, category_id: u32, jurisdiction: Jurisdiction, flags: ComplianceFlags) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::CategoryComplianceSet, None)?;
+            self.jurisdictions.flags_set(category_id, jurisdiction, flags)
+        }
+        /// Returns the compliance flags of a category in a jurisdiction
+        #[ink(message, selector = "0x778419AB")]
+        pub fn category_compliance_get(&self, category_id: u32, jurisdiction: Jurisdiction) -> ComplianceFlags {
+            self.jurisdictions.flags_of(category_id, jurisdiction)
+        }
+        /// Sets or removes the tax rate of a jurisdiction for a category, or its default rate when `category_id` is
+        /// `None`, only administrator can do it. The rate is in basis points of the price before the tax.
+        #[ink(message, selector = "0x8C4C7437")]
+        pub fn tax_rate_set(&mut self, jurisdiction: Jurisdiction, category_id: Option<u32>, rate: Option<TaxRate>) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::TaxRateSet, None)?;
+            self.jurisdictions.check(jurisdiction)?;
+            self.taxes.rate_set(jurisdiction, category_id, rate)
+        }
+        /// Returns the tax rate applying to the assets of a category sold in a jurisdiction
+        #[ink(message, selector = "0xD2F00AC3")]
+        pub fn tax_rate_get(&self, jurisdiction: Jurisdiction, category_id: Option<u32>) -> Option<TaxRate> {
+            self.taxes.rate_of(jurisdiction, category_id)
+        }
+        /// Sets or removes the jurisdiction of a seller, only administrator can do it. The jurisdiction must be registered.
+        #[ink(message, selector = "0xB8560343")]
+        pub fn account_jurisdiction_set(&mut self, account: AccountId, jurisdiction: Option<Jurisdiction>) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::AccountJurisdictionSet, None)?;
+            if let Some(jurisdiction) = jurisdiction {
+                self.jurisdictions.check(jurisdiction)?;
+            }
+            self.taxes.jurisdiction_set(account, jurisdiction);
+            Ok(())
+        }
+        /// Returns the jurisdiction of an account
+        #[ink(message, selector = "0xBEEAE8E2")]
+        pub fn account_jurisdiction_get(&self, account: AccountId) -> Option<Jurisdiction> {
+            self.taxes.jurisdiction_of(&account)
+        }
+        /// Returns the tax record of a sale of an asset, by its position in the sales of the asset
+        #[ink(message, selector = "0x0A2DB3BE")]
+        pub fn sale_tax_get(&self, id: AssetId, sale: u32) -> Option<TaxRecord> {
+            self.taxes.record_of(id, sale)
+        }
+        /// Returns a page of the tax trail of the sales, oldest first
+        #[ink(message, selector = "0x97EB95C6")]
+        pub fn tax_records(&self, cursor: Cursor, limit: u32) -> Page<TaxRecord> {
+            let records = self.taxes.list(cursor, pagination::limit(limit));
+            pagination::page(records, cursor, self.taxes.records_count())
+        }
+        /// Records the manufacturer-suggested retail price of an asset, only its producer can do it while owning the
+        /// asset. The price cannot be changed once recorded.
+        #[ink(message, selector = "0x1E713A78")]
+        pub fn asset_msrp_set(&mut self, id: AssetId, price: Balance, currency: Option<Currency>) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            let caller = self.authorize(Message::AssetMsrpSet, Some(id))?;
+            if self.provenance.producer_of(id) != Some(caller) {
+                return Err(Error::NotProducer)
+            }
+            let block = self.env().block_number();
+            self.price_lists.msrp_set(id, Msrp {
+                price,
+                currency,
+                block,
+            })
+        }
+        /// Returns the suggested retail price of an asset with the price of its last sale settled through the contract
+        #[ink(message, selector = "0x0F4618F9")]
+        pub fn asset_pricing_get(&self, id: AssetId) -> Pricing {
+            let sales = self.sales.sales_count(id);
+            let last_sale = sales.checked_sub(1).and_then(|index| self.sales.sale_of(id, index));
+            Pricing {
+                msrp: self.price_lists.msrp_of(id),
+                realized: last_sale.map(|sale| sale.price),
+                currency: last_sale.and_then(|sale| sale.currency),
+                sales,
+            }
+        }
+        /// Registers or removes the price oracle of a fiat currency, only administrator can do it.
+        /// The listings already priced in the currency cannot be bought while it has no oracle.
+        #[ink(message, selector = "0x63483A59")]
+        pub fn price_oracle_set(&mut self, currency: Currency, oracle: Option<AccountId>) -> Result<(), Error> {
+            // check for administrator
+            self.authorize(Message::PriceOracleSet, None)?;
+            self.sales.oracle_set(currency, oracle);
+            Ok(())
+        }
+        /// Returns the price oracle of a fiat currency
+        #[ink(message, selector = "0x4AE51EB8")]
+        pub fn price_oracle_get(&self, currency: Currency) -> Option<AccountId> {
+            self.sales.oracle_of(&currency)
+        }
+        /// Publishes the unit price of the assets of a category from the caller, only producers can do it. The price
+        /// applies from block `valid_from`, which cannot be in the past nor before the last price of the category.
+        #[ink(message, selector = "0x5053FFFF")]
+        pub fn price_list_set(&mut self, category_id: u32, unit_price: Balance, currency: Option<Currency>, valid_from: BlockNumber) -> Result<(), Error> {
+            let caller = self.authorize(Message::PriceListSet, None)?;
+            self.category_resolve(category_id)?;
+            if self.metadata.category_description(category_id).is_none() {
+                return Err(Error::CategoryNotFound)
+            }
+            if !self.category_producer_allowed(category_id, &caller) {
+                return Err(Error::NotAllowed)
+            }
+            let block = self.env().block_number();
+            self.price_lists.publish(caller, category_id, PriceEntry {
+                unit_price,
+                currency,
+                valid_from,
+                block,
+            })?;
+            self.env().emit_event(PriceListUpdated {
+                producer: caller,
+                category: category_id,
+                unit_price,
+                currency,
+                valid_from,
+            });
+            Ok(())
+        }
+        /// Returns the price of a producer for a category in force at the current block
+        #[ink(message, selector = "0x101CE9C2")]
+        pub fn price_list_get(&self, producer: AccountId, category_id: u32) -> Option<PriceEntry> {
+            self.price_lists.price_at(&producer, category_id, self.env().block_number())
+        }
+        /// Returns a page of the prices published from a producer for a category, oldest first, the scheduled ones
+        /// included
+        #[ink(message, selector = "0x4F59FB7E")]
+        pub fn price_list_history(&self, producer: AccountId, category_id: u32, cursor: Cursor, limit: u32) -> Page<PriceEntry> {
+            let entries = self.price_lists.history(&producer, category_id, cursor, pagination::limit(limit));
+            pagination::page(entries, cursor, self.price_lists.entries_count(&producer, category_id))
+        }
+        /// Transfers the asset from the caller to a different account. A contract destination must accept the asset
+        /// from its receiver hook.
+        #[ink(message, selector = "0x44A3D87A")]
+        pub fn asset_transfer(
+            &mut self,
+            destination: AccountId,
+            id: AssetId,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.asset_transfer_from(&caller, &destination, id, Vec::new(), false)?;
+            Ok(())
+        }
+        /// Transfers the asset from the caller to a different account passing `data` to the receiver hook of a contract
+        /// destination. A contract without the hook receives the asset only if `allow_contract_recipient` is set.
+        #[ink(message, selector = "0x442BF152")]
+        pub fn asset_safe_transfer(
+            &mut self,
+            destination: AccountId,
+            id: AssetId,
+            data: Vec<u8>,
+            allow_contract_recipient: bool,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.asset_transfer_from(&caller, &destination, id, data, allow_contract_recipient)
+        }
+
+        /// Transfer approved of owned asset.
+        #[ink(message, selector = "0x0B396F18")]
+        pub fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            id: AssetId,
+        ) -> Result<(), Error> {
+            self.asset_transfer_from(&from, &to, id, Vec::new(), false)?;
+            Ok(())
+        }
+        /// Sets the grace period of the transfers of the caller, at most 100800 blocks. Its outgoing transfers stay
+        /// pending for the recipient until the end of the period and can be cancelled meanwhile. 0, the default,
+        /// moves the assets at once. The pending transfers keep the period they were sent with.
+        #[ink(message, selector = "0x0119389C")]
+        pub fn account_grace_period_set(&mut self, grace_period: BlockNumber) -> Result<(), Error> {
+            let caller = self.authorize(Message::AccountGracePeriodSet, None)?;
+            if grace_period > MAX_GRACE_PERIOD {
+                return Err(Error::NotAllowed)
+            }
+            self.ownership.grace_period_set(caller, grace_period);
+            Ok(())
+        }
+        /// Returns the grace period of the transfers of an account, 0 for the instant transfers
+        #[ink(message, selector = "0x657B75B8")]
+        pub fn account_grace_period_get(&self, account: AccountId) -> BlockNumber {
+            self.ownership.grace_period_of(&account)
+        }
+        /// Cancels the pending transfer of an asset until the last block of its grace period, only the owner can do it
+        #[ink(message, selector = "0x7D88A23F")]
+        pub fn asset_transfer_cancel(&mut self, id: AssetId) -> Result<(), Error> {
+            //check if asset id is present in the storage and the signer is the owner
+            self.authorize(Message::AssetTransferCancel, Some(id))?;
+            let owner = self.ownership.owner_of(id).ok_or(Error::AssetNotFound)?;
+            let pending = self
+                .ownership
+                .record_of(id)
+                .and_then(|record| record.pending)
+                .ok_or(Error::PendingTransferNotFound)?;
+            if pending.is_final(self.env().block_number()) {
+                return Err(Error::NotAllowed)
+            }
+            self.ownership.record_mut(id)?.pending = None;
+            self.env().emit_event(TransferCancelled {
+                id,
+                from: owner,
+                to: pending.to,
+            });
+            Ok(())
+        }
+        /// Finalizes the pending transfer of an asset once its grace period is over, moving the asset to the
+        /// recipient, anyone can do it. The asset must not have been put on hold or encumbered meanwhile.
+        #[ink(message, selector = "0x380A3A52")]
+        pub fn asset_transfer_finalize(&mut self, id: AssetId) -> Result<(), Error> {
+            //check if asset id is present in the storage
+            self.authorize(Message::AssetTransferFinalize, Some(id))?;
+            let owner = self.ownership.owner_of(id).ok_or(Error::AssetNotFound)?;
+            let pending = self
+                .ownership
+                .record_of(id)
+                .and_then(|record| record.pending)
+                .ok_or(Error::PendingTransferNotFound)?;
+            if !pending.is_final(self.env().block_number()) {
+                return Err(Error::GracePeriodRunning)
+            }
+            if self.holds.is_held(id) {
+                return Err(Error::AssetOnHold)
+            }
+            if self.liens.is_encumbered(id) {
+                return Err(Error::AssetEncumbered)
+            }
+            self.check_in_stock(id)?;
+            self.ownership.record_mut(id)?.pending = None;
+            self.asset_move(&owner, &pending.to, id)
+        }
+        /// Returns the pending transfer of an asset, with its recipient and the last block it can be cancelled
+        #[ink(message, selector = "0xE0E7BCFD")]
+        pub fn asset_transfer_pending(&self, id: AssetId) -> Option<PendingTransfer> {
+            self.ownership.record_of(id).and_then(|record| record.pending)
+        }
+        /// Transfers asset `id` `from` the sender to the `to` AccountId, a contract destination must accept it from its
+        /// receiver hook called with `data` unless `allow_contract_recipient` is set.
+        fn asset_transfer_from(
+            &mut self,
+            from: &AccountId,
+            to: &AccountId,
+            id: AssetId,
+            data: Vec<u8>,
+            allow_contract_recipient: bool,
+        ) -> Result<(), Error> {
+            self.asset_transfer_check(from, to, id)?;
+            self.recipient_check(from, to, id, data, allow_contract_recipient)?;
+            // the transfers of an owner with a grace period wait for its end before moving the asset
+            let grace_period = self.ownership.grace_period_of(from);
+            if grace_period > 0 {
+                let pending = PendingTransfer::new(*to, self.env().block_number(), grace_period);
+                self.ownership.record_mut(id)?.pending = Some(pending);
+                self.env().emit_event(TransferPending {
+                    id,
+                    from: *from,
+                    to: *to,
+                    until: pending.until,
+                });
+                return Ok(())
+            }
+            self.asset_move(from, to, id)
+        }
+        /// Fails if the caller cannot transfer asset `id` `from` its owner to `to`
+        fn asset_transfer_check(
+            &mut self,
+            from: &AccountId,
+            to: &AccountId,
+            id: AssetId,
+        ) -> Result<(), Error> {
+            // check if asset id is stored and the signer can transfer it
+            let (_, owner) = self.authorize_asset(Message::AssetTransfer, id)?;
+            // the asset can be transferred only from its owner
+            if owner != *from {
+                return Err(Error::NotOwner)
+            };
+            if *to == AccountId::from([0x0; 32]) {
+                return Err(Error::NotAllowed)
+            };
+            // the assets mirrored on the EVM chain stay in the custody of the contract
+            if self.bridge.is_locked(id) {
+                return Err(Error::AssetBridged)
+            }
+            if self.holds.is_held(id) {
+                return Err(Error::AssetOnHold)
+            }
+            // the collateral of a lien stays with its owner until the creditor releases it
+            if self.liens.is_encumbered(id) {
+                return Err(Error::AssetEncumbered)
+            }
+            // the returned assets re-enter the supply chain once inspected and restocked
+            self.check_in_stock(id)?;
+            self.check_not_pending(id)?;
+            self.check_reservation(id, to)
+        }
+        /// Fails if a dispute is open against `account`, its stake answers for the dispute
+        fn check_no_dispute(&self, account: &AccountId) -> Result<(), Error> {
+            if self.disputes.open_against(account) > 0 {
+                return Err(Error::StakeLocked)
+            }
+            Ok(())
+        }
+        /// Fails if asset `id` has a transfer pending for the grace period of its owner
+        fn check_not_pending(&self, id: AssetId) -> Result<(), Error> {
+            match self.ownership.record_of(id).and_then(|record| record.pending) {
+                Some(_) => Err(Error::TransferPending),
+                None => Ok(()),
+            }
+        }
+        /// Fails if asset `id` is reserved for a buyer other than `to`
+        fn check_reservation(&self, id: AssetId, to: &AccountId) -> Result<(), Error> {
+            let now = self.env().block_number();
+            match self.ownership.record_of(id).and_then(|record| record.reservation) {
+                Some(reservation) if reservation.blocks(to, now) => Err(Error::AssetReserved),
+                _ => Ok(()),
+            }
+        }
+        /// Pays back the deposit of the reservation of asset `id` to `owner` and removes the reservation
+        fn reservation_refund(&mut self, id: AssetId, owner: AccountId) -> Result<(), Error> {
+            let reservation = match self.ownership.record_of(id).and_then(|record| record.reservation) {
+                Some(reservation) => reservation,
+                None => return Ok(()),
+            };
+            if reservation.deposit > 0 {
+                self.env().transfer(owner, reservation.deposit).map_err(|_| Error::PaymentFailed)?;
+            }
+            self.ownership.record_mut(id)?.reservation = None;
+            self.env().emit_event(AssetReservationReleased {
+                id,
+                to: owner,
+                deposit: reservation.deposit,
+            });
+            Ok(())
+        }
+        /// Calls the receiver hook of `to` receiving asset `id`, fails if it is a contract refusing the asset and
+        /// `allow_contract_recipient` is not set
+        fn recipient_check(&self, from: &AccountId, to: &AccountId, id: AssetId, data: Vec<u8>, allow_contract_recipient: bool) -> Result<(), Error> {
+            let recipient = recipients::notify(self.env().caller(), *from, *to, id, data);
+            if recipient == Recipient::Refusing && !allow_contract_recipient {
+                return Err(Error::UnsafeRecipient)
+            }
+            Ok(())
+        }
+        /// Fails if asset `id` has been returned and is not restocked, or has been disposed of or retired
+        fn check_in_stock(&self, id: AssetId) -> Result<(), Error> {
+            match self.ownership.record_of(id).map(|record| record.status) {
+                Some(StockStatus::Disposed) => Err(Error::AssetDisposed),
+                Some(StockStatus::Remanufactured) => Err(Error::AssetRetired),
+                Some(status) if status != StockStatus::InStock => Err(Error::AssetReturned),
+                _ => Ok(()),
+            }
+        }
+        /// Moves asset `id` from `from` to `to`, dropping the proxies, grants and listing of the previous owner.
+        fn asset_move(&mut self, from: &AccountId, to: &AccountId, id: AssetId) -> Result<(), Error> {
+            // the reservation converts into the transfer, its deposit is paid back to the owner
+            self.reservation_refund(id, *from)?;
+            self.acl.remove(id);
+            self.privacy.revoke_all(id);
+            self.privacy.viewers_clear(id);
+            self.services.revoke_all(id);
+            self.sales.unlist(id);
+            self.ownership.transfer(from, to, id)?;
+            self.ownership.record_mut(id)?.delegate = None;
+            self.stats.transfers += 1;
+            self.transferred(*from, *to, id);
+            Ok(())
+        }
+        /// Checks the caller can send `message` and is in the set of the administrators, the accounts with the
+        /// Administrator role cannot change the set
+        fn admin_set_authorize(&self, message: Message) -> Result<AccountId, Error> {
+            let caller = self.authorize(message, None)?;
+            if !self.administrators.contains_key(&caller) {
+                return Err(Error::NotAdministrator)
+            }
+            Ok(caller)
+        }
+        /// Emits the update of the set of the administrators
+        fn administrator_updated(&self, from: AccountId, id: AccountId, added: bool) {
+            self.env().emit_event(AdministratorUpdated { from, id, added });
+        }
+        /// Returns true if `account` is in the set of the administrators or has the Administrator role.
+        fn is_administrator(&self, account: &AccountId) -> bool {
+            self.administrators.contains_key(account) || self.roles.is_administrator(account)
+        }
+        /// Appends the revocation of `credential` issued from `issuer` to the revocation list and reports it
+        fn credential_revoke(&mut self, revoker: AccountId, credential: Credential, issuer: AccountId, reason: Hash) -> Result<(), Error> {
+            let block = self.env().block_number();
+            self.revocations.revoke(Revocation {
+                credential,
+                issuer,
+                revoker,
+                reason,
+                block,
+            })?;
+            self.env().emit_event(CredentialRevoked {
+                credential,
+                issuer,
+                reason,
+            });
+            match credential {
+                Credential::Validation(id) => {
+                    let category = self.ownership.record_of(id).and_then(|record| record.category);
+                    self.category_alert(category, Alert::ValidationRevoked(id));
+                }
+                Credential::Certificate(certificate_id) => {
+                    if let Some(certificate) = self.certificates.get(certificate_id) {
+                        self.manifest_alert(certificate.manifest_id, Alert::CertificateRevoked(certificate_id));
+                    }
+                }
+            }
+            Ok(())
+        }
+        /// Emits `alert` to the subscribers of `category`, if any
+        fn category_alert(&self, category: Option<u32>, alert: Alert) {
+            let digest = category.and_then(|category| self.alerts.digest_of(category).map(|digest| (category, digest)));
+            if let Some((category, subscribers)) = digest {
+                self.env().emit_event(CategoryAlert {
+                    category,
+                    subscribers,
+                    alert,
+                });
+            }
+        }
+        /// Emits `alert` to the subscribers of each category of the assets of shipment `manifest_id`, once per category
+        fn manifest_alert(&self, manifest_id: ManifestId, alert: Alert) {
+            let mut categories: Vec<u32> = self
+                .shipments
+                .get(manifest_id)
+                .map(|manifest| manifest.items)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|item| self.ownership.record_of(item.id).and_then(|record| record.category))
+                .collect();
+            categories.sort_unstable();
+            categories.dedup();
+            for category in categories {
+                self.category_alert(Some(category), alert);
+            }
+        }
+        /// Fails if `account` has the Inspector role and is not accredited on `category` at the current block, the
+        /// other accounts validating the assets are not restricted to a scope.
+        fn inspector_scope_check(&self, account: &AccountId, category: Option<u32>) -> Result<(), Error> {
+            if self.roles.active_role_of(account) == Some(INSPECTOR) {
+                self.inspectors.check(account, category, self.env().block_number())?;
+            }
+            Ok(())
+        }
+
+        /// Builds the manifest of a shipment from the caller to `receiver`, the caller must be the owner or an
+        /// operator of all the assets.
+        fn manifest_build(&self, receiver: AccountId, seals: Vec<(AssetId, Hash)>) -> Result<Manifest, Error> {
+            if receiver == AccountId::from([0x0; 32]) {
+                return Err(Error::NotAllowed)
+            }
+            let mut items = Vec::with_capacity(seals.len());
+            let mut sender = self.env().caller();
+            for (id, seal) in seals {
+                //check if asset id is present in the storage and the signer can ship it
+                sender = self.authorize(Message::ShipmentManifestCreate, Some(id))?;
+                items.push(ManifestItem {
+                    id,
+                    quantity: self.quantities.quantity_of(id),
+                    seal,
+                });
+            }
+            Ok(Manifest {
+                sender,
+                receiver,
+                block: self.env().block_number(),
+                items,
+            })
+        }
+
+        /// Stores the manifest of a shipment and emits the ManifestCreated event.
+        fn manifest_store(&mut self, manifest: Manifest) -> Result<ManifestId, Error> {
+            let (sender, receiver) = (manifest.sender, manifest.receiver);
+            let items = manifest.items.clone();
+            let (manifest_id, hash) = self.shipments.create(manifest)?;
+            // the assets in transit cannot be deleted until the shipment is delivered or cancelled
+            self.shipment_opened(&items, true);
+            self.env().emit_event(ManifestCreated {
+                manifest_id,
+                sender,
+                receiver,
+                hash,
+            });
+            Ok(manifest_id)
+        }
+
+        /// Counts a shipment of the `items` opened or closed in the records of the assets, the assets deleted by force
+        /// are skipped
+        fn shipment_opened(&mut self, items: &[ManifestItem], opened: bool) {
+            for item in items {
+                if let Ok(record) = self.ownership.record_mut(item.id) {
+                    record.open_shipments = if opened {
+                        record.open_shipments.saturating_add(1)
+                    } else {
+                        record.open_shipments.saturating_sub(1)
+                    };
+                }
+            }
+        }
+
+        /// Collects `amount` from the caller: the native tokens must be transferred with the call,
+        /// the PSP22 `token` is transferred from the caller to the contract.
+        fn payment_collect(&self, token: Option<AccountId>, amount: Balance) -> Result<(), Error> {
+            let transferred = self.env().transferred_balance();
+            match token {
+                None if transferred == amount => Ok(()),
+                Some(token) if transferred == 0 => {
+                    psp22::transfer_from(token, self.env().caller(), self.env().account_id(), amount)
+                }
+                _ => Err(Error::InvalidPayment),
+            }
+        }
+
+        /// Pays `amount` of the native tokens, or of the PSP22 `token`, from the contract to `to`.
+        fn payment_send(&self, token: Option<AccountId>, to: AccountId, amount: Balance) -> Result<(), Error> {
+            match token {
+                None => self.env().transfer(to, amount).map_err(|_| Error::PaymentFailed),
+                Some(token) => psp22::transfer(token, to, amount),
+            }
+        }
+
+        /// Pays the coverage of the policy of the shipment `manifest_id` to the beneficiary on a breach of the tolerance
+        /// recorded from the certificate, or releases it to the insurer when the shipment is compliant or cancelled.
+        fn policy_settle(&mut self, manifest_id: ManifestId) -> Result<(), Error> {
+            let policy = self.insurance.policy_of(manifest_id).ok_or(Error::PolicyNotFound)?;
+            let status = if self.shipments.cancellation_of(manifest_id).is_some() {
+                PolicyStatus::Released
+            } else {
+                let certificate = self.shipment_certificate_get(manifest_id).ok_or(Error::CertificateNotFound)?;
+                if policy.is_breached(&certificate) {
+                    PolicyStatus::PaidOut
+                } else {
+                    PolicyStatus::Released
+                }
+            };
+            self.insurance.settle(manifest_id, status)?;
+            let to = match status {
+                PolicyStatus::PaidOut => policy.beneficiary,
+                _ => policy.insurer,
+            };
+            if let Err(error) = self.payment_send(policy.token, to, policy.coverage) {
+                self.insurance.unsettle(manifest_id);
+                return Err(error)
+            }
+            self.env().emit_event(PolicySettled {
+                manifest_id,
+                to,
+                amount: policy.coverage,
+                status,
+            });
+            Ok(())
+        }
+
+        /// Returns the amount to pay for `listing` and the rate of the oracle used, when priced in a fiat currency.
+        fn settlement_of(&self, listing: &Listing) -> Result<(Balance, Option<Balance>), Error> {
+            match listing.currency {
+                None => Ok((listing.price, None)),
+                Some(currency) => {
+                    let oracle = self.sales.oracle_of(&currency).ok_or(Error::OracleNotFound)?;
+                    let rate = oracle::rate_of(oracle, currency, listing.token)?;
+                    let amount = oracle::convert(listing.price, rate).ok_or(Error::RateUnavailable)?;
+                    Ok((amount, Some(rate)))
+                }
+            }
+        }
+
+        /// Checks the caller satisfies the access control rule of `message`, and returns the caller.
+        /// When the message acts on asset `id` the asset must exist.
+        fn authorize(&self, message: Message, id: Option<AssetId>) -> Result<AccountId, Error> {
+            match id {
+                Some(id) => self.authorize_asset(message, id).map(|(caller, _)| caller),
+                None => {
+                    let caller = self.env().caller();
+                    self.check(message, caller, self.context_of(message, &caller))?;
+                    Ok(caller)
+                }
+            }
+        }
+
+        /// Checks the caller can call `message` on asset `id`, and returns the caller and the owner of the asset.
+        /// Only the facts needed from the message are read, the owner once.
+        fn authorize_asset(&self, message: Message, id: AssetId) -> Result<(AccountId, AccountId), Error> {
+            let caller = self.env().caller();
+            let record = self.ownership.record_of(id).ok_or(Error::AssetNotFound)?;
+            let owner = record.owner;
+            let mut context = self.context_of(message, &caller);
+            context.owner = owner == caller;
+            let needs = message.needs();
+            if !context.owner {
+                if needs.delegation {
+                    context.operator = self.delegation.is_operator(&owner, &caller);
+                    context.delegate = record.delegate == Some(caller);
+                }
+                if needs.carrier {
+                    let carrier = self.carriers.carrier_of(id);
+                    context.carrier_assigned = carrier.is_some();
+                    context.carrier = carrier == Some(caller);
+                }
+                if needs.grants {
+                    context.granted = self.acl.groups_of(id, &caller);
+                }
+            }
+            self.check(message, caller, context)?;
+            Ok((caller, owner))
+        }
+
+        /// Returns the facts about `caller` needed from `message` that do not depend on an asset.
+        fn context_of(&self, message: Message, caller: &AccountId) -> Context {
+            let needs = message.needs();
+            Context {
+                role: if needs.role { self.roles.active_role_of(caller) } else { None },
+                mint_restricted: needs.role && self.config.mint_producers_only,
+                capabilities: if needs.capabilities { self.roles.capabilities_of(caller) } else { 0 },
+                ..Default::default()
+            }
+        }
+
+        /// Checks `context` satisfies `message`, reading the administrator flag of `caller` only when it does not.
+        fn check(&self, message: Message, caller: AccountId, context: Context) -> Result<(), Error> {
+            match message.check(&context) {
+                Err(_) if self.is_administrator(&caller) => Ok(()),
+                result => result,
+            }
+        }
+
+        /// Stores the data `kind` of asset `id` if not already present.
+        fn asset_data_insert(&mut self, caller: AccountId, id: AssetId, kind: UpdateKind, value: Hash) -> Result<(), Error> {
+            if self.ownership.record_of(id).is_some_and(|record| record.contains(kind)) {
+                return Err(Error::DuplicatedData)
+            }
+            // check the data is not locked from the validation
+            self.check_unlocked(id, kind)?;
+            // check the cooldown since the last update
+            self.check_update_cooldown(id, kind)?;
+            self.ownership.record_mut(id)?.insert(kind, value)?;
+            let duplicate = if kind == UpdateKind::Photo || kind == UpdateKind::Metadata {
+                self.contents.link(value, id);
+                self.content_index(id, value)
+            } else {
+                None
+            };
+            if kind == UpdateKind::Location {
+                self.location_record(caller, id, value);
+            }
+            self.asset_updated(caller, id, kind);
+            if let Some(other) = duplicate {
+                self.env().emit_event(PossibleDuplicate {
+                    id,
+                    other,
+                    content: value,
+                });
+            }
+            Ok(())
+        }
+
+        /// Appends `location` recorded from `caller` to the route of asset `id` and returns its sequence.
+        fn location_record(&mut self, caller: AccountId, id: AssetId, location: Hash) -> u32 {
+            let point = LocationPoint {
+                location,
+                recorder: caller,
+                block: self.env().block_number(),
+                timestamp: self.env().block_timestamp(),
+            };
+            self.locations.append(id, point)
+        }
+
+        /// Indexes `content` stored as photo or metadata of asset `id`, and returns the other asset still storing the
+        /// same content. Both the assets are flagged as possible duplicates.
+        fn content_index(&mut self, id: AssetId, content: Hash) -> Option<AssetId> {
+            let other = self
+                .metadata
+                .content_asset(&content)
+                .filter(|other| self.ownership.record_of(*other).is_some_and(|record| record.has_content(&content)));
+            self.metadata.content_index(id, content, other);
+            other.filter(|other| *other != id)
+        }
+
+        /// Removes the data `kind` of asset `id`.
+        fn asset_data_remove(&mut self, caller: AccountId, id: AssetId, kind: UpdateKind) -> Result<(), Error> {
+            // check the data is not locked from the validation
+            self.check_unlocked(id, kind)?;
+            // check the cooldown since the last update
+            self.check_update_cooldown(id, kind)?;
+            let value = self.ownership.record_mut(id)?.remove(kind)?;
+            if kind == UpdateKind::Photo || kind == UpdateKind::Metadata {
+                self.contents.unlink(value, id);
+            }
+            self.asset_updated(caller, id, kind);
+            Ok(())
+        }
+
+        /// Records the update of the data `kind` of asset `id` and emits its event.
+        fn asset_updated(&mut self, caller: AccountId, id: AssetId, kind: UpdateKind) {
+            if self.config.update_cooldown > 0 {
+                let now = self.env().block_number();
+                self.metadata.record_update(id, kind, now);
+            }
+            self.asset_update_log(caller, id, kind);
+        }
+
+        /// Appends the update of the data `kind` of asset `id` to its log and emits the `AssetUpdate` event.
+        fn asset_update_log(&mut self, caller: AccountId, id: AssetId, kind: UpdateKind) {
+            self.updates.record(id, UpdateEntry {
+                from: caller,
+                kind,
+                block: self.env().block_number(),
+            }, self.config.update_log_size);
+            self.env().emit_event(AssetUpdate {
+                from: caller,
+                id,
+                kind,
+            });
+        }
+
+        /// Creates asset `id` owned from `caller`, locking the native tokens transferred as its mint deposit.
+        fn mint(&mut self, caller: AccountId, id: AssetId) -> Result<(), Error> {
+            let deposit = self.env().transferred_balance();
+            if deposit < self.config.mint_deposit {
+                return Err(Error::InvalidPayment)
+            }
+            self.check_subscription(&caller)?;
+            let mint_window = self.check_mint_rate_limit(&caller)?;
+            self.ownership.add(&caller, id)?;
+            if let Some(window) = mint_window {
+                self.account_mint_window.insert(caller, window);
+            }
+            self.provenance.record(id, MintRecord {
+                minter: caller,
+                block: self.env().block_number(),
+                timestamp: self.env().block_timestamp(),
+                deposit,
+            })?;
+            self.stats.mints += 1;
+            self.transferred(AccountId::from([0x0; 32]), caller, id);
+            Ok(())
+        }
+
+        /// Records the transfer of asset `id` in the journal, linked to the previous transfer of the asset, and emits
+        /// its event.
+        fn transferred(&mut self, from: AccountId, to: AccountId, id: AssetId) {
+            let block = self.env().block_number();
+            let position = self.journal.len();
+            // the record is already loaded from the transfer, the deleted assets have none
+            let previous = self.ownership.record_mut(id).ok().and_then(|record| record.last_transfer.replace(position));
+            self.journal.record(TransferRecord {
+                id,
+                from,
+                to,
+                block,
+                timestamp: self.env().block_timestamp(),
+                previous,
+            });
+            let zero = AccountId::from([0x0; 32]);
+            if from == zero {
+                self.activity_record(to, ActivityKind::Minted, id, None);
+            } else if to == zero {
+                self.activity_record(from, ActivityKind::Burned, id, None);
+            } else {
+                self.activity_record(from, ActivityKind::Sent, id, Some(to));
+                self.activity_record(to, ActivityKind::Received, id, Some(from));
+            }
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: Some(to),
+                id,
+            });
+        }
+
+        /// Appends the activity `kind` of the dispute on `subject` to the feeds of its parties, on the asset contested or
+        /// the first asset of the contested shipment.
+        fn dispute_activity(&mut self, kind: ActivityKind, subject: DisputeSubject, claimant: AccountId, respondent: AccountId) {
+            let manifest_id = match subject {
+                DisputeSubject::Validation(_) => None,
+                DisputeSubject::Certificate(certificate_id) => self.certificates.get(certificate_id).map(|certificate| certificate.manifest_id),
+                DisputeSubject::Shipment(manifest_id, _) => Some(manifest_id),
+            };
+            let id = match subject {
+                DisputeSubject::Validation(id) => Some(id),
+                _ => manifest_id
+                    .and_then(|manifest_id| self.shipments.get(manifest_id))
+                    .and_then(|manifest| manifest.items.first().map(|item| item.id)),
+            };
+            if let Some(id) = id {
+                self.activity_record(claimant, kind, id, Some(respondent));
+                self.activity_record(respondent, kind, id, Some(claimant));
+            }
+        }
+
+        /// Appends an activity on asset `id` to the feed of `account`.
+        fn activity_record(&mut self, account: AccountId, kind: ActivityKind, id: AssetId, counterpart: Option<AccountId>) {
+            let block = self.env().block_number();
+            self.activity.record(account, ActivityEntry {
+                kind,
+                id,
+                counterpart,
+                block,
+            });
+        }
+
+        /// Approves or disapproves the operator to transfer all assets of the owner.
+        fn proxy_for_all_assets(
+            &mut self,
+            owner: AccountId,
+            to: AccountId,
+            approved: bool,
+        ) -> Result<(), Error> {
+            self.delegation.operator_set(owner, to, approved)?;
+            self.env().emit_event(ApprovalForAll {
+                owner,
+                operator: to,
+                approved,
+            });
+            Ok(())
+        }
+
+        /// Checks the minting rate limit of `account` and returns its minting window including the new asset,
+        /// fails if the configured rate limit is exceeded.
+        fn check_mint_rate_limit(&self, account: &AccountId) -> Result<Option<(BlockNumber, u32)>, Error> {
+            if self.config.mint_limit == 0 {
+                return Ok(None)
+            }
+            let now = self.env().block_number();
+            let (start, minted) = match self.account_mint_window.get(account) {
+                Some(&(start, minted)) if now < start.saturating_add(self.config.mint_period) => (start, minted),
+                _ => (now, 0),
+            };
+            if minted >= self.config.mint_limit {
+                return Err(Error::RateLimited)
+            }
+            Ok(Some((start, minted + 1)))
+        }
+
+        /// Opens a dispute of `claimant` on `subject` against `respondent`, and returns its id.
+        fn dispute_open(&mut self, subject: DisputeSubject, claimant: AccountId, respondent: AccountId, evidence: Hash) -> Result<DisputeId, Error> {
+            let dispute_id = self.disputes.open(Dispute {
+                subject,
+                claimant,
+                respondent,
+                evidence,
+                opened: self.env().block_number(),
+                status: DisputeStatus::Open,
+            })?;
+            self.dispute_activity(ActivityKind::DisputeOpened(dispute_id), subject, claimant, respondent);
+            self.env().emit_event(DisputeOpened {
+                dispute_id,
+                respondent,
+                subject,
+            });
+            Ok(dispute_id)
+        }
+
+        /// Mints the reward of the role of `account` for `work`, when the rewards are enabled. A reward that cannot
+        /// be minted is skipped.
+        fn reward(&mut self, account: AccountId, work: Work) {
+            let token = match self.rewards.token() {
+                Some(token) => token,
+                None => return,
+            };
+            let amount = self
+                .roles
+                .active_role_of(&account)
+                .map_or(0, |role| self.rewards.rate_of(role));
+            if amount > 0 && psp22::mint(token, account, amount).is_ok() {
+                self.rewards.record(account, amount);
+                self.env().emit_event(RewardPaid {
+                    account,
+                    amount,
+                    work,
+                });
+            }
+        }
+
+        /// Emits the event of the new stake of `account`.
+        fn stake_updated(&self, account: AccountId, bond: Bond) {
+            self.env().emit_event(StakeUpdated {
+                account,
+                bonded: bond.bonded,
+                unbonding: bond.unbonding,
+            });
+        }
+
+        /// Fails if `account` is a producer whose subscription does not cover the current block, when the
+        /// subscriptions are enabled.
+        fn check_subscription(&self, account: &AccountId) -> Result<(), Error> {
+            if self.config.subscription_fee == 0
+                || self.roles.active_role_of(account) != Some(PRODUCER)
+                || self.is_administrator(account)
+                || self.subscriptions.is_active(account, self.env().block_number())
+            {
+                return Ok(())
+            }
+            Err(Error::SubscriptionLapsed)
+        }
+
+        /// Fails if the data `kind` of asset `id` is a core field, the description, the photo or the category, and the
+        /// asset has a validation in force, so the validated data cannot change.
+        fn check_unlocked(&self, id: AssetId, kind: UpdateKind) -> Result<(), Error> {
+            let core = matches!(kind, UpdateKind::Description | UpdateKind::Photo | UpdateKind::Category);
+            if core && self.asset_validation_verify(id) {
+                return Err(Error::LockedAfterValidation)
+            }
+            Ok(())
+        }
+
+        /// Fails if the data `kind` of asset `id` has been updated less than the configured cooldown ago.
+        fn check_update_cooldown(&self, id: AssetId, kind: UpdateKind) -> Result<(), Error> {
+            if self.config.update_cooldown == 0 {
+                return Ok(())
+            }
+            match self.metadata.last_update(id, kind) {
+                Some(last) if self.env().block_number() < last.saturating_add(self.config.update_cooldown) => {
+                    Err(Error::CooldownActive)
+                }
+                _ => Ok(()),
+            }
+        }
+
+        /// Returns the caller if it can call `message` and `owner` has enabled the custodial mode.
+        fn check_custodial_administrator(&self, message: Message, owner: &AccountId) -> Result<AccountId, Error> {
+            // check for administrator
+            let caller = self.authorize(message, None)?;
+            if !self.delegation.is_custodial(owner) {
+                return Err(Error::NotCustodial)
+            }
+            Ok(caller)
+        }
+
+        /// Records a custodial proxy change in the administrators log and emits its event.
+        fn custodial_proxy_updated(&mut self, admin: AccountId, owner: AccountId, operator: AccountId, id: Option<AssetId>, approved: bool) {
+            let action = if approved {
+                AdminAction::CustodialProxyInstalled { owner, operator, id }
+            } else {
+                AdminAction::CustodialProxyRevoked { owner, operator, id }
+            };
+            self.admin_log_append(admin, action);
+            self.env().emit_event(CustodialProxyUpdated {
+                admin,
+                owner,
+                operator,
+                id,
+                approved,
+            });
+        }
+
+        /// Appends an action to the administrators log.
+        fn admin_log_append(&mut self, admin: AccountId, action: AdminAction) {
+            let block = self.env().block_number();
+            self.admin_log.insert(*self.admin_log_count, AdminLogEntry {
+                admin,
+                block,
+                action,
+            });
+            *self.admin_log_count += 1;
+        }
+
+        /// Caches the description of category `categoryid` from the category registry of the configuration,
+        /// when the category is not stored locally.
+        fn category_resolve(&mut self, categoryid: u32) -> Result<(), Error> {
+            if self.metadata.category_description(categoryid).is_some() {
+                return Ok(())
+            }
+            if let Some(registry) = self.config.category_registry {
+                if let Some(description) = category_registry::description_of(registry, categoryid)? {
+                    self.metadata.category_description_insert(categoryid, description)?;
+                }
+            }
+            Ok(())
+        }
+
+        /// Returns true if `account` can assign assets to category `categoryid`: the category is not restricted,
+        /// the account is an authorized producer or an administrator.
+        fn category_producer_allowed(&self, categoryid: u32, account: &AccountId) -> bool {
+            !self.metadata.category_restricted(categoryid)
+                || self.is_administrator(account)
+                || self.metadata.category_producer_verify(categoryid, account)
+        }
+    }
+
+    /// Unit tests
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+        use ink_env::{
+            call,
+            test,
+        };
+        use ink_lang as ink;
+        use crate::physical::Packaging;
+        use crate::depreciation::{
+            Method,
+            YEAR,
+        };
+        use crate::sales::Reduction;
+        use crate::access_control::{
+            CAN_MANAGE_ROLES,
+            CAN_MINT,
+            CAN_VALIDATE,
+            FIELD_METADATA,
+        };
+
+        type Event = <AssetErc721 as ::ink_lang::BaseEvent>::Type;
+
+        #[ink::test]
+        fn mint_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Asset 1 does not exists.
+            assert_eq!(asseterc721.asset_get_owner(1), None);
+            // Alice does not owns assets.
+            assert_eq!(asseterc721.account_assets_number(accounts.alice), 0);
+            // Create asset Id 1.
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            // Alice owns 1 asset.
+            assert_eq!(asseterc721.account_assets_number(accounts.alice), 1);
+        }
+
+        #[ink::test]
+        fn mint_existing_should_fail() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Create asset Id 1.
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            // The first Transfer event takes place
+            assert_eq!(1, ink_env::test::recorded_events().count());
+            // Alice owns 1 asset.
+            assert_eq!(asseterc721.account_assets_number(accounts.alice), 1);
+            // Alice owns asset Id 1.
+            assert_eq!(asseterc721.asset_get_owner(1), Some(accounts.alice));
+            // Cannot create  asset Id if it exists.
+            // Bob cannot own asset Id 1.
+            assert_eq!(asseterc721.asset_new(1), Err(Error::AssetExists));
+        }
+
+        #[ink::test]
+        fn transfer_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Create asset Id 1 for Alice
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            // Alice owns asset 1
+            assert_eq!(asseterc721.account_assets_number(accounts.alice), 1);
+            // Bob does not owns any asset
+            assert_eq!(asseterc721.account_assets_number(accounts.bob), 0);
+            // The first Transfer event takes place
+            assert_eq!(1, ink_env::test::recorded_events().count());
+            // Alice transfers asset 1 to Bob
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 1), Ok(()));
+            // The second Transfer event takes place
+            assert_eq!(2, ink_env::test::recorded_events().count());
+            // Bob owns asset 1
+            assert_eq!(asseterc721.account_assets_number(accounts.bob), 1);
+        }
+
+        #[ink::test]
+        fn invalid_transfer_should_fail() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Transfer asset fails if it does not exists.
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 2), Err(Error::AssetNotFound));
+            // Asset Id 2 does not exists.
+            assert_eq!(asseterc721.asset_get_owner(2), None);
+            // Create asset Id 2.
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            // Alice owns 1 asset.
+            assert_eq!(asseterc721.account_assets_number(accounts.alice), 1);
+            // Asset Id 2 is owned by Alice.
+            assert_eq!(asseterc721.asset_get_owner(2), Some(accounts.alice));
+            // Get contract address
+            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .unwrap_or([0x0; 32].into());
+            // Create call
+            let mut data =
+                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])); // account_assets_number
+            data.push_arg(&accounts.bob);
+            // Push the new execution context to set Bob as caller
+            ink_env::test::push_execution_context::<ink_env::DefaultEnvironment>(
+                accounts.bob,
+                callee,
+                1000000,
+                1000000,
+                data,
+            );
+            // Bob cannot transfer not owned assets.
+            assert_eq!(asseterc721.asset_transfer(accounts.eve, 2), Err(Error::NotApproved));
+        }
+
+        #[ink::test]
+        fn approved_transfer_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Create asset Id 1.
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            // Asset Id 1 is owned by Alice.
+            assert_eq!(asseterc721.asset_get_owner(1), Some(accounts.alice));
+            // Approve asset Id 1 transfer for Bob on behalf of Alice.
+            assert_eq!(asseterc721.account_delegate_single_asset(accounts.bob, 1), Ok(()));
+            // Get contract address.
+            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .unwrap_or([0x0; 32].into());
+            // Create call
+            let mut data =
+                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])); // account_assets_number
+            data.push_arg(&accounts.bob);
+            // Push the new execution context to set Bob as caller
+            ink_env::test::push_execution_context::<ink_env::DefaultEnvironment>(
+                accounts.bob,
+                callee,
+                1000000,
+                1000000,
+                data,
+            );
+            // Bob transfers asset Id 1 from Alice to Eve.
+            assert_eq!(
+                asseterc721.transfer_from(accounts.alice, accounts.eve, 1),
+                Ok(())
+            );
+            // AssetId 3 is owned by Eve.
+            assert_eq!(asseterc721.asset_get_owner(1), Some(accounts.eve));
+            // Alice does not owns assets.
+            assert_eq!(asseterc721.account_assets_number(accounts.alice), 0);
+            // Bob does not owns assets.
+            assert_eq!(asseterc721.account_assets_number(accounts.bob), 0);
+            // Eve owns 1 asset.
+            assert_eq!(asseterc721.account_assets_number(accounts.eve), 1);
+        }
+
+        #[ink::test]
+        fn approved_for_all_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Create asset Id 1.
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            // Create asset Id 2.
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            // Alice owns 2 assets.
+            assert_eq!(asseterc721.account_assets_number(accounts.alice), 2);
+            // Approve asset Id 1 transfer for Bob on behalf of Alice.
+            assert_eq!(asseterc721.account_delegate_for_all_asset(accounts.bob, true), Ok(()));
+            // Bob is an approved operator for Alice
+            assert_eq!(
+                asseterc721.account_verify_delegated_for_all_asset(accounts.alice, accounts.bob),
+                true
+            );
+            // Get contract address.
+            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .unwrap_or([0x0; 32].into());
+            // Create call
+            let mut data =
+                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])); // account_assets_number
+            data.push_arg(&accounts.bob);
+            // Push the new execution context to set Bob as caller
+            ink_env::test::push_execution_context::<ink_env::DefaultEnvironment>(
+                accounts.bob,
+                callee,
+                1000000,
+                1000000,
+                data,
+            );
+            // Bob transfers asset Id 1 from Alice to Eve.
+            assert_eq!(
+                asseterc721.transfer_from(accounts.alice, accounts.eve, 1),
+                Ok(())
+            );
+            // AssetId 1 is owned by Eve.
+            assert_eq!(asseterc721.asset_get_owner(1), Some(accounts.eve));
+            // Alice owns 1 asset.
+            assert_eq!(asseterc721.account_assets_number(accounts.alice), 1);
+            // Bob transfers asset Id 2 from Alice to Eve.
+            assert_eq!(
+                asseterc721.transfer_from(accounts.alice, accounts.eve, 2),
+                Ok(())
+            );
+            // Bob does not owns assets.
+            assert_eq!(asseterc721.account_assets_number(accounts.bob), 0);
+            // Eve owns 2 assets.
+            assert_eq!(asseterc721.account_assets_number(accounts.eve), 2);
+            // Get back to the parent execution context.
+            ink_env::test::pop_execution_context();
+            // Remove operator approval for Bob on behalf of Alice.
+            assert_eq!(asseterc721.account_delegate_for_all_asset(accounts.bob, false), Ok(()));
+            // Bob is not an approved operator for Alice.
+            assert_eq!(
+                asseterc721.account_verify_delegated_for_all_asset(accounts.alice, accounts.bob),
+                false
+            );
+        }
+
+        #[ink::test]
+        fn not_approved_transfer_should_fail() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Create asset Id 1.
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            // Alice owns 1 asset.
+            assert_eq!(asseterc721.account_assets_number(accounts.alice), 1);
+            // Bob does not owns assets.
+            assert_eq!(asseterc721.account_assets_number(accounts.bob), 0);
+            // Eve does not owns assets.
+            assert_eq!(asseterc721.account_assets_number(accounts.eve), 0);
+            // Get contract address.
+            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .unwrap_or([0x0; 32].into());
+            // Create call
+            let mut data =
+                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])); // account_assets_number
+            data.push_arg(&accounts.bob);
+            // Push the new execution context to set Eve as caller
+            ink_env::test::push_execution_context::<ink_env::DefaultEnvironment>(
+                accounts.eve,
+                callee,
+                1000000,
+                1000000,
+                data,
+            );
+            // Eve is not an approved operator by Alice.
+            assert_eq!(
+                asseterc721.transfer_from(accounts.alice, accounts.frank, 1),
+                Err(Error::NotApproved)
+            );
+            // Alice owns 1 asset.
+            assert_eq!(asseterc721.account_assets_number(accounts.alice), 1);
+            // Bob does not owns assets.
+            assert_eq!(asseterc721.account_assets_number(accounts.bob), 0);
+            // Eve does not owns assets.
+            assert_eq!(asseterc721.account_assets_number(accounts.eve), 0);
+        }
+
+        #[ink::test]
+        fn asset_delete_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Create asset Id 1 for Alice
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            // Alice owns 1 asset.
+            assert_eq!(asseterc721.account_assets_number(accounts.alice), 1);
+            // Alice owns asset Id 1.
+            assert_eq!(asseterc721.asset_get_owner(1), Some(accounts.alice));
+            // Destroy asset Id 1.
+            assert_eq!(asseterc721.asset_delete(1), Ok(()));
+            // Alice does not owns assets.
+            assert_eq!(asseterc721.account_assets_number(accounts.alice), 0);
+            // Asset Id 1 does not exists
+            assert_eq!(asseterc721.asset_get_owner(1), None);
+        }
+
+        #[ink::test]
+        fn assets_by_content_hash_works() {
+            let photo = Hash::from([0x01; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            for id in 1..5 {
+                assert_eq!(asseterc721.asset_new(id), Ok(()));
+            }
+            // the same content stored as photo and as metadata
+            assert_eq!(asseterc721.asset_photo_new(1, photo), Ok(()));
+            assert_eq!(asseterc721.asset_photo_new(2, photo), Ok(()));
+            assert_eq!(asseterc721.asset_metadata_new(3, photo), Ok(()));
+            assert_eq!(asseterc721.asset_metadata_new(1, photo), Ok(()));
+            assert_eq!(asseterc721.assets_by_content_hash(photo, 0, 2), ([1, 2].to_vec(), Some(2)));
+            assert_eq!(asseterc721.assets_by_content_hash(photo, 2, 2), ([3].to_vec(), None));
+            // the asset is listed while it references the content
+            assert_eq!(asseterc721.asset_photo_delete(1), Ok(()));
+            assert_eq!(asseterc721.assets_by_content_hash(photo, 0, 10).0, [1, 2, 3]);
+            assert_eq!(asseterc721.asset_metadata_delete(1), Ok(()));
+            assert_eq!(asseterc721.assets_by_content_hash(photo, 0, 10).0, [3, 2]);
+            assert_eq!(asseterc721.asset_delete(2), Ok(()));
+            assert_eq!(asseterc721.assets_by_content_hash(photo, 0, 10).0, [3]);
+            assert_eq!(asseterc721.assets_by_content_hash(Hash::from([0x02; 32]), 0, 10), (Vec::new(), None));
+        }
+
+        #[ink::test]
+        fn asset_location_update_batch_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let location = Hash::from([0x01; 32]);
+            let seal = Hash::from([0x02; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Bob is a shipper
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.account_role_new(accounts.bob, 4), Ok(()));
+            for id in 1..4 {
+                assert_eq!(asseterc721.asset_new(id), Ok(()));
+            }
+            assert_eq!(asseterc721.shipment_manifest_create(accounts.charlie, [(1, seal), (2, seal)].to_vec()), Ok(0));
+            // one location for all the assets of the shipment, one event each
+            set_sender(accounts.bob);
+            let events = ink_env::test::recorded_events().count();
+            assert_eq!(asseterc721.asset_location_update_batch(AssetSelection::Shipment(0), location), Ok(2));
+            assert_eq!(ink_env::test::recorded_events().count(), events + 2);
+            assert_eq!(asseterc721.asset_location_get(2), Some(location));
+            assert_eq!(asseterc721.asset_location_get(3), None);
+            // the next fix replaces the previous one
+            let location = Hash::from([0x03; 32]);
+            assert_eq!(asseterc721.asset_location_update_batch(AssetSelection::Assets([1, 3].to_vec()), location), Ok(2));
+            assert_eq!(asseterc721.asset_location_get(1), Some(location));
+            // no location is written unless every asset can be updated
+            let other = Hash::from([0x04; 32]);
+            assert_eq!(asseterc721.asset_location_update_batch(AssetSelection::Assets(Vec::new()), other), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.asset_location_update_batch(AssetSelection::Assets([1, 1].to_vec()), other), Err(Error::DuplicatedData));
+            assert_eq!(asseterc721.asset_location_update_batch(AssetSelection::Assets([1, 9].to_vec()), other), Err(Error::AssetNotFound));
+            assert_eq!(asseterc721.asset_location_get(1), Some(location));
+            set_sender(accounts.django);
+            assert_eq!(asseterc721.asset_location_update_batch(AssetSelection::Shipment(0), other), Err(Error::NotOwner));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.shipment_cancel(0), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_location_update_batch(AssetSelection::Shipment(0), other), Err(Error::ShipmentCancelled));
+            assert_eq!(asseterc721.asset_location_update_batch(AssetSelection::Shipment(1), other), Err(Error::ManifestNotFound));
+        }
+
+        #[ink::test]
+        fn asset_location_history_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let start = Hash::from([0x01; 32]);
+            let stop = Hash::from([0x02; 32]);
+            let end = Hash::from([0x03; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Bob is a shipper
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.account_role_new(accounts.bob, 4), Ok(()));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.asset_location_new(1, start), Ok(()));
+            assert_eq!(asseterc721.asset_location_new(1, stop), Err(Error::DuplicatedData));
+            // the shipper records the route, the latest point is the location of the asset
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_location_append(1, stop), Ok(1));
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>().expect("Cannot advance block");
+            assert_eq!(asseterc721.asset_location_append(1, end), Ok(2));
+            assert_eq!(asseterc721.asset_location_append(2, end), Ok(0));
+            assert_eq!(asseterc721.asset_location_get(1), Some(end));
+            assert_eq!(asseterc721.asset_location_count(1), 3);
+            let (points, next) = asseterc721.asset_location_history(1, 0, 2);
+            assert_eq!(points.iter().map(|point| point.location).collect::<Vec<_>>(), [start, stop]);
+            assert_eq!((points[0].recorder, points[1].recorder), (accounts.alice, accounts.bob));
+            assert_eq!(next, None);
+            let (points, next) = asseterc721.asset_location_history(1, 1, u32::MAX);
+            assert_eq!(points.len(), 2);
+            assert!(points[1].block > points[0].block);
+            assert_eq!(next, None);
+            assert_eq!(asseterc721.asset_location_history(1, 3, 10), (Vec::new(), None));
+            // the delete clears the latest location only
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_location_delete(1), Ok(()));
+            assert_eq!(asseterc721.asset_location_get(1), None);
+            assert_eq!(asseterc721.asset_location_count(1), 3);
+            set_sender(accounts.django);
+            assert_eq!(asseterc721.asset_location_append(1, start), Err(Error::NotOwner));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_delete(2), Ok(()));
+            assert_eq!(asseterc721.asset_location_count(2), 0);
+        }
+
+        #[ink::test]
+        fn asset_safe_transfer_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::test::get_current_contract_account_id::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get contract account");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            // the contract has no receiver hook, the asset would be parked in it
+            assert_eq!(asseterc721.asset_transfer(contract, 1), Err(Error::UnsafeRecipient));
+            assert_eq!(asseterc721.asset_safe_transfer(contract, 1, Vec::new(), false), Err(Error::UnsafeRecipient));
+            // a plain account receives the asset as before
+            assert_eq!(asseterc721.asset_safe_transfer(accounts.bob, 1, b"order 42".to_vec(), false), Ok(()));
+            // the contract recipient is allowed explicitly
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_safe_transfer(contract, 1, Vec::new(), true), Ok(()));
+            assert_eq!(asseterc721.asset_get_owner(1), Some(contract));
+        }
+
+        #[ink::test]
+        fn transfer_grace_period_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.account_grace_period_set(MAX_GRACE_PERIOD + 1), Err(Error::NotAllowed));
+            // Alice opts in to a grace period of 2 blocks
+            assert_eq!(asseterc721.account_grace_period_set(2), Ok(()));
+            assert_eq!(asseterc721.account_grace_period_get(accounts.alice), 2);
+            assert_eq!(asseterc721.asset_transfer_cancel(1), Err(Error::PendingTransferNotFound));
+            // the transfer sent by mistake to Charlie is pending and can be cancelled
+            assert_eq!(asseterc721.asset_transfer(accounts.charlie, 1), Ok(()));
+            assert_eq!(asseterc721.asset_get_owner(1), Some(accounts.alice));
+            assert_eq!(asseterc721.asset_transfer_pending(1), Some(PendingTransfer { to: accounts.charlie, until: 2 }));
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 1), Err(Error::TransferPending));
+            assert_eq!(asseterc721.asset_delete(1), Err(Error::TransferPending));
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.asset_transfer_cancel(1), Err(Error::NotOwner));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_transfer_cancel(1), Ok(()));
+            assert_eq!(asseterc721.asset_transfer_pending(1), None);
+            // the transfer to Bob finalizes once the grace period is over
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 1), Ok(()));
+            set_sender(accounts.django);
+            assert_eq!(asseterc721.asset_transfer_finalize(1), Err(Error::GracePeriodRunning));
+            for _ in 0..3 {
+                ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot advance block");
+            }
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_transfer_cancel(1), Err(Error::NotAllowed));
+            set_sender(accounts.django);
+            assert_eq!(asseterc721.asset_transfer_finalize(1), Ok(()));
+            assert_eq!(asseterc721.asset_get_owner(1), Some(accounts.bob));
+            assert_eq!(asseterc721.asset_transfer_pending(1), None);
+            // Bob has no grace period, his transfers are instant
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_transfer(accounts.eve, 1), Ok(()));
+            assert_eq!(asseterc721.asset_get_owner(1), Some(accounts.eve));
+        }
+
+        #[ink::test]
+        fn asset_reserve_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>().expect("Cannot get contract");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_reservation_release(1), Err(Error::ReservationNotFound));
+            assert_eq!(asseterc721.asset_reserve(1, accounts.alice, 5), Err(Error::NotAllowed));
+            // Alice reserves asset Id 1 for Bob until block 2 with a deposit of 100
+            set_sender_value(accounts.alice, 100);
+            assert_eq!(test::set_account_balance::<Environment>(contract, 100), Ok(()));
+            assert_eq!(asseterc721.asset_reserve(1, accounts.bob, 2), Ok(()));
+            assert_eq!(asseterc721.asset_reservation_get(1), Some(Reservation { buyer: accounts.bob, until: 2, deposit: 100 }));
+            assert_eq!(asseterc721.asset_reserve(1, accounts.charlie, 2), Err(Error::AssetReserved));
+            // the asset is transferred or sold only to Bob
+            assert_eq!(asseterc721.asset_transfer(accounts.charlie, 1), Err(Error::AssetReserved));
+            assert_eq!(asseterc721.asset_listing_new(1, 100, None, None), Ok(()));
+            set_sender_value(accounts.charlie, 100);
+            assert_eq!(asseterc721.asset_purchase(1, 100, None, None), Err(Error::AssetReserved));
+            assert_eq!(asseterc721.asset_reservation_release(1), Err(Error::NotAllowed));
+            // the sale to Bob fails before its payments when the deposit cannot be paid back
+            set_sender_value(accounts.bob, 100);
+            assert_eq!(test::set_account_balance::<Environment>(contract, 0), Ok(()));
+            let balance = test::get_account_balance::<Environment>(accounts.alice).expect("Cannot get balance");
+            assert_eq!(asseterc721.asset_purchase(1, 100, None, None), Err(Error::PaymentFailed));
+            assert_eq!(asseterc721.asset_get_owner(1), Some(accounts.alice));
+            assert_eq!(asseterc721.asset_reservation_get(1).map(|reservation| reservation.deposit), Some(100));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.alice), Ok(balance));
+            assert!(asseterc721.asset_listing_get(1).is_some());
+            assert_eq!(test::set_account_balance::<Environment>(contract, 100), Ok(()));
+            // the transfer to Bob converts the reservation and pays the deposit back to Alice
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_listing_cancel(1), Ok(()));
+            let balance = test::get_account_balance::<Environment>(accounts.alice).expect("Cannot get balance");
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 1), Ok(()));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.alice), Ok(balance + 100));
+            assert_eq!(asseterc721.asset_reservation_get(1), None);
+            // the owner withdrawing the reservation in force pays the deposit to the buyer
+            set_sender_value(accounts.bob, 50);
+            assert_eq!(test::set_account_balance::<Environment>(contract, 50), Ok(()));
+            assert_eq!(asseterc721.asset_reserve(1, accounts.charlie, 2), Ok(()));
+            let balance = test::get_account_balance::<Environment>(accounts.charlie).expect("Cannot get balance");
+            assert_eq!(asseterc721.asset_reservation_release(1), Ok(()));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.charlie), Ok(balance + 50));
+            // once expired the reservation no longer blocks the transfers and anyone releases it
+            set_sender_value(accounts.bob, 0);
+            assert_eq!(asseterc721.asset_reserve(1, accounts.charlie, 1), Ok(()));
+            assert_eq!(asseterc721.asset_delete(1), Err(Error::AssetReserved));
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            set_sender(accounts.django);
+            assert_eq!(asseterc721.asset_reservation_release(1), Ok(()));
+            assert_eq!(asseterc721.asset_reservation_get(1), None);
+        }
+
+        #[ink::test]
+        fn asset_delete_dependencies_work() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let hash = Hash::from([0x01; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            // Bob lists asset Id 1 for sale
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_listing_new(1, 100, None, None), Ok(()));
+            assert_eq!(asseterc721.asset_delete(1), Err(Error::AssetListed));
+            assert_eq!(asseterc721.asset_listing_cancel(1), Ok(()));
+            // the asset cannot be deleted while it is shipped
+            assert_eq!(asseterc721.shipment_manifest_create(accounts.charlie, [(1, hash)].to_vec()), Ok(0));
+            assert_eq!(asseterc721.asset_delete(1), Err(Error::AssetInTransit));
+            assert_eq!(asseterc721.shipment_cancel(0), Ok(()));
+            // nor while it is validated
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_validation_new(1, accounts.alice), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_delete(1), Err(Error::AssetValidated));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_validation_delete(1), Ok(()));
+            // nor while it is delegated
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.account_delegate_single_asset(accounts.django, 1), Ok(()));
+            assert_eq!(asseterc721.asset_delete(1), Err(Error::AssetDelegated));
+            // the administrator deletes it by force
+            assert_eq!(asseterc721.asset_delete_force(1, hash), Err(Error::NotAdministrator));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_delete_force(1, hash), Ok(()));
+            assert_eq!(asseterc721.asset_get_owner(1), None);
+            assert_eq!(asseterc721.admin_log_count(), 1);
+            // the shipment of an asset deleted by force is cancelled as usual
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.shipment_manifest_create(accounts.charlie, [(2, hash)].to_vec()), Ok(1));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_delete_force(2, hash), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.shipment_cancel(1), Ok(()));
+        }
+
+        #[ink::test]
+        fn asset_delete_fails_asset_not_found() {
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Try asset_deleteing a non existent asset
+            assert_eq!(asseterc721.asset_delete(1), Err(Error::AssetNotFound));
+        }
+
+        #[ink::test]
+        fn asset_delete_fails_not_owner() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Create asset Id 1 for Alice
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            // Try asset_deleteing this asset with a different account
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_delete(1), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn asset_update_event_carries_kind() {
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Create asset Id 1 and add its photo
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_photo_new(1, Hash::from([0x01; 32])), Ok(()));
+            // The last event is an AssetUpdate of kind Photo
+            let emitted = ink_env::test::recorded_events().last().expect("no event emitted");
+            let decoded = <Event as scale::Decode>::decode(&mut &emitted.data[..])
+                .expect("invalid event data");
+            match decoded {
+                Event::AssetUpdate(event) => {
+                    assert_eq!(event.id, 1);
+                    assert_eq!(event.kind, UpdateKind::Photo);
+                }
+                _ => panic!("expected an AssetUpdate event"),
+            }
+        }
+
+        #[ink::test]
+        fn account_assets_detailed_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Create asset Id 1, 2 and 3 for Alice
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.asset_new(3), Ok(()));
+            assert_eq!(asseterc721.asset_photo_new(2, Hash::from([0x02; 32])), Ok(()));
+            // The first page of size 2 points to the second page, that contains only asset Id 3
+            assert_eq!(asseterc721.account_assets_detailed(accounts.alice, 0, 2).1, Some(2));
+            let (page, next) = asseterc721.account_assets_detailed(accounts.alice, 2, 2);
+            assert_eq!(next, None);
+            assert_eq!(page.len(), 1);
+            assert_eq!(page[0].0, 3);
+            assert_eq!(page[0].1.owner, accounts.alice);
+            // Alice transfers asset Id 1 to Bob, asset Id 3 takes its place
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 1), Ok(()));
+            let page = asseterc721.account_assets_detailed(accounts.alice, 0, 10).0;
+            let ids: Vec<AssetId> = page.iter().map(|(id, _)| *id).collect();
+            assert_eq!(ids, [3, 2]);
+            // The embedded data contains the photo of asset Id 2
+            assert_eq!(page[1].1.photo, Some(Hash::from([0x02; 32])));
+            // Bob owns asset Id 1
+            let page = asseterc721.account_assets_detailed(accounts.bob, 0, 10).0;
+            assert_eq!(page.len(), 1);
+            assert_eq!(page[0].0, 1);
+        }
+
+        #[ink::test]
+        fn category_assets_count_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Store the description of category 7
+            assert_eq!(asseterc721.metadata.category_description_insert(7, Hash::from([0x07; 32])), Ok(()));
+            assert_eq!(asseterc721.category_assets_count(7), 0);
+            // Create asset Id 1 and 2 in category 7
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.asset_category_new(1, 7), Ok(()));
+            assert_eq!(asseterc721.asset_category_new(2, 7), Ok(()));
+            assert_eq!(asseterc721.category_assets_count(7), 2);
+            // Removing the category of asset Id 1 decreases the counter
+            assert_eq!(asseterc721.asset_category_delete(1), Ok(()));
+            assert_eq!(asseterc721.category_assets_count(7), 1);
+            // Deleting asset Id 2 decreases the counter
+            assert_eq!(asseterc721.asset_delete(2), Ok(()));
+            assert_eq!(asseterc721.category_assets_count(7), 0);
+            assert!(!asseterc721.asset_category_verify(2));
+            assert_eq!(asseterc721.account_assets_number(accounts.alice), 1);
+        }
+
+        #[ink::test]
+        fn stats_get_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Create asset Id 1 and 2, transfer asset Id 1 and delete asset Id 2
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 1), Ok(()));
+            assert_eq!(asseterc721.asset_delete(2), Ok(()));
+            let stats = asseterc721.stats_get();
+            assert_eq!(stats.mints, 2);
+            assert_eq!(stats.burns, 1);
+            assert_eq!(stats.transfers, 1);
+            assert_eq!(stats.validations, 0);
+            // Moving an account between roles updates the per-role counters
+            let mut stats = Stats::default();
+            stats.role_changed(None, Some(0));
+            stats.role_changed(None, Some(0));
+            stats.role_changed(Some(0), Some(4));
+            assert_eq!(stats.accounts_per_role, [(0, 1), (4, 1)]);
+        }
+
+        #[ink::test]
+        fn mint_rate_limit_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            // Allow 2 assets every 3 blocks
+            assert_eq!(asseterc721.config_mint_rate_limit_set(2, 3), Ok(()));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            // A failed creation does not count in the window
+            assert_eq!(asseterc721.asset_new(1), Err(Error::AssetExists));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            // The third asset in the same window is refused
+            assert_eq!(asseterc721.asset_new(3), Err(Error::RateLimited));
+            assert_eq!(asseterc721.asset_get_owner(3), None);
+            // A new window starts after 3 blocks
+            for _ in 0..3 {
+                ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot advance block");
+            }
+            assert_eq!(asseterc721.asset_new(3), Ok(()));
+            // Disabling the limit allows any number of assets
+            assert_eq!(asseterc721.config_mint_rate_limit_set(0, 0), Ok(()));
+            assert_eq!(asseterc721.asset_new(4), Ok(()));
+            assert_eq!(asseterc721.asset_new(5), Ok(()));
+            // Bob is not an administrator
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.config_mint_rate_limit_set(1, 1), Err(Error::NotAdministrator));
+        }
+
+        #[ink::test]
+        fn update_cooldown_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            // Require 2 blocks between updates of the same data
+            assert_eq!(asseterc721.config_update_cooldown_set(2), Ok(()));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_photo_new(1, Hash::from([0x01; 32])), Ok(()));
+            // Updating the photo again in the same block is refused
+            assert_eq!(asseterc721.asset_photo_delete(1), Err(Error::CooldownActive));
+            // A different kind of data can be updated
+            assert_eq!(asseterc721.asset_metadata_new(1, Hash::from([0x02; 32])), Ok(()));
+            // The photo can be updated after 2 blocks
+            for _ in 0..2 {
+                ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot advance block");
+            }
+            assert_eq!(asseterc721.asset_photo_delete(1), Ok(()));
+        }
+
+        #[ink::test]
+        fn collection_metadata_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator and labels the collection
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.collection_metadata_set(b"Supply Chain".to_vec(), b"SCA".to_vec(), b"ipfs://base/".to_vec()), Ok(()));
+            assert_eq!(asseterc721.collection_name(), b"Supply Chain");
+            assert_eq!(asseterc721.collection_symbol(), b"SCA");
+            assert_eq!(asseterc721.collection_base_uri(), b"ipfs://base/");
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.collection_metadata_set(Vec::new(), Vec::new(), Vec::new()), Err(Error::NotAdministrator));
+            // The token URI composes the base URI with the metadata of the asset
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.token_uri(1), None);
+            assert_eq!(asseterc721.asset_metadata_new(1, Hash::from([0xA5; 32])), Ok(()));
+            let mut uri = b"ipfs://base/".to_vec();
+            uri.extend_from_slice(&[b'a', b'5'].repeat(32));
+            assert_eq!(asseterc721.token_uri(1), Some(uri));
+            assert_eq!(asseterc721.token_uri(2), None);
+        }
+
+        #[ink::test]
+        fn category_registry_config_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            // Refreshing a category needs a registry
+            assert_eq!(asseterc721.category_refresh(7), Err(Error::CategoryNotFound));
+            assert_eq!(asseterc721.config_category_registry_set(Some(accounts.django)), Ok(()));
+            assert_eq!(asseterc721.config_get().category_registry, Some(accounts.django));
+            // Categories cached locally are assigned without calling the registry
+            assert_eq!(asseterc721.category_description_new(7, Hash::from([0x07; 32])), Ok(()));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_category_new(1, 7), Ok(()));
+            assert_eq!(asseterc721.category_assets_count(7), 1);
+            // Only administrators can change the registry
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.config_category_registry_set(None), Err(Error::NotAdministrator));
+            assert_eq!(asseterc721.category_refresh(7), Err(Error::NotAdministrator));
+        }
+
+        #[ink::test]
+        fn subscription_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator, Bob is a producer
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.roles.insert(accounts.bob, 0), Ok(()));
+            // The producers pay 10 for each era of 2 blocks
+            assert_eq!(asseterc721.config_subscription_set(10, 0), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.config_subscription_set(10, 2), Ok(()));
+            set_sender_value(accounts.bob, 0);
+            assert!(!asseterc721.subscription_active(accounts.bob));
+            assert_eq!(asseterc721.asset_new(1), Err(Error::SubscriptionLapsed));
+            // Other accounts mint as usual
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.subscription_renew(), Err(Error::NotProducer));
+            // Bob pays whole eras only
+            set_sender_value(accounts.bob, 15);
+            assert_eq!(asseterc721.subscription_renew(), Err(Error::InvalidPayment));
+            set_sender_value(accounts.bob, 10);
+            assert_eq!(asseterc721.subscription_renew(), Ok(2));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.subscription_fees(), 10);
+            // The lapsed subscription suspends the minting, not the transfers
+            for _ in 0..2 {
+                ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot advance block");
+            }
+            assert_eq!(asseterc721.asset_new(3), Err(Error::SubscriptionLapsed));
+            assert_eq!(asseterc721.asset_transfer(accounts.eve, 1), Ok(()));
+            // Only administrators withdraw the fees
+            assert_eq!(asseterc721.subscription_fees_withdraw(accounts.bob, 10), Err(Error::NotAdministrator));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.subscription_fees_withdraw(accounts.alice, 20), Err(Error::InsufficientBalance));
+            // the fees stay collected when they cannot be paid
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>().expect("Cannot get contract");
+            assert_eq!(test::set_account_balance::<Environment>(contract, 0), Ok(()));
+            assert_eq!(asseterc721.subscription_fees_withdraw(accounts.alice, 10), Err(Error::PaymentFailed));
+            assert_eq!(asseterc721.subscription_fees(), 10);
+            assert_eq!(test::set_account_balance::<Environment>(contract, 10), Ok(()));
+            assert_eq!(asseterc721.subscription_fees_withdraw(accounts.alice, 10), Ok(()));
+            assert_eq!(asseterc721.subscription_fees(), 0);
+        }
+
+        #[ink::test]
+        fn validator_stake_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>().expect("Cannot get contract");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            // Bob creates asset Id 1
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            // The validators must bond 100
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.config_validator_stake_set(100, 2), Ok(()));
+            assert_eq!(asseterc721.asset_validation_new(1, accounts.alice), Err(Error::StakeRequired));
+            set_sender_value(accounts.alice, 100);
+            assert_eq!(asseterc721.stake_bond().map(|bond| bond.bonded), Ok(100));
+            assert_eq!(asseterc721.asset_validation_new(1, accounts.alice), Ok(()));
+            assert_eq!(asseterc721.config_validator_stake_set(100, 0), Err(Error::NotAllowed));
+            // Alice cannot escape a dispute unbonding her stake
+            assert_eq!(asseterc721.stake_unbond(40).map(|bond| bond.unbonding), Ok(40));
+            assert_eq!(asseterc721.stake_withdraw(), Err(Error::StakeLocked));
+            // Bob disputes the validation
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.validation_dispute_open(1, Hash::from([0x01; 32])), Ok(0));
+            // the stake stays locked past its release while the dispute is open
+            for _ in 0..3 {
+                ink_env::test::advance_block::<ink_env::DefaultEnvironment>().expect("Cannot advance block");
+            }
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.stake_withdraw(), Err(Error::StakeLocked));
+            assert_eq!(asseterc721.stake_unbond(10), Err(Error::StakeLocked));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.validation_dispute_open(1, Hash::from([0x01; 32])), Err(Error::DuplicatedData));
+            assert_eq!(asseterc721.dispute_get(0).map(|dispute| dispute.respondent), Some(accounts.alice));
+            assert_eq!(asseterc721.dispute_resolve(0, true), Err(Error::NotAdministrator));
+            assert_eq!(asseterc721.config_fraud_penalty_set(5_000), Err(Error::NotAdministrator));
+            // A fraud forfeits half of the stake
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.config_fraud_penalty_set(10_001), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.config_fraud_penalty_set(5_000), Ok(()));
+            // the dispute stays open and the stake is restored when the slash cannot be paid
+            assert_eq!(test::set_account_balance::<Environment>(contract, 0), Ok(()));
+            let stake = asseterc721.stake_get(accounts.alice);
+            assert_eq!(asseterc721.dispute_resolve(0, true), Err(Error::PaymentFailed));
+            assert_eq!(asseterc721.dispute_get(0).map(|dispute| dispute.status), Some(DisputeStatus::Open));
+            assert_eq!(asseterc721.stake_get(accounts.alice), stake);
+            assert_eq!(asseterc721.stake_withdraw(), Err(Error::StakeLocked));
+            // The upheld dispute slashes half of the stake to Bob and removes the validation
+            assert_eq!(test::set_account_balance::<Environment>(contract, 100), Ok(()));
+            let balance = test::get_account_balance::<Environment>(accounts.bob).expect("Cannot get balance");
+            assert_eq!(asseterc721.dispute_resolve(0, true), Ok(()));
+            assert_eq!(asseterc721.dispute_resolve(0, true), Err(Error::DisputeNotFound));
+            assert_eq!(asseterc721.dispute_get(0).map(|dispute| dispute.status), Some(DisputeStatus::Upheld(50)));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.bob), Ok(balance + 50));
+            assert_eq!(asseterc721.stake_get(accounts.alice).map(|bond| (bond.bonded, bond.unbonding)), Some((10, 40)));
+            assert!(!asseterc721.asset_validation_verify(1));
+            // The amount unbonding is restored when it cannot be paid
+            assert_eq!(test::set_account_balance::<Environment>(contract, 0), Ok(()));
+            assert_eq!(asseterc721.stake_withdraw(), Err(Error::PaymentFailed));
+            assert_eq!(asseterc721.stake_get(accounts.alice).map(|bond| bond.unbonding), Some(40));
+            assert_eq!(test::set_account_balance::<Environment>(contract, 40), Ok(()));
+            assert_eq!(asseterc721.stake_withdraw(), Ok(40));
+            assert_eq!(asseterc721.stake_get(accounts.alice).map(|bond| (bond.bonded, bond.unbonding)), Some((10, 0)));
+        }
+
+        #[ink::test]
+        fn rewards_config_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            // Only administrators configure the rewards
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.reward_token_set(Some(accounts.django)), Err(Error::NotAdministrator));
+            assert_eq!(asseterc721.reward_rate_set(5, 10), Err(Error::NotAdministrator));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.reward_rate_set(99, 10), Err(Error::RoleNotFound));
+            assert_eq!(asseterc721.reward_rate_set(5, 10), Ok(()));
+            assert_eq!(asseterc721.reward_rate_get(5), 10);
+            assert_eq!(asseterc721.reward_token_set(Some(accounts.django)), Ok(()));
+            assert_eq!(asseterc721.reward_token_get(), Some(accounts.django));
+            // Without a reward token the validation is not rewarded
+            assert_eq!(asseterc721.reward_token_set(None), Ok(()));
+            assert_eq!(asseterc721.asset_validation_new(1, accounts.alice), Ok(()));
+            assert_eq!(asseterc721.rewards_earned(accounts.alice), 0);
+        }
+
+        #[ink::test]
+        fn mint_producers_only_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator, Bob is a producer, Eve is a retailer
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.roles.insert(accounts.bob, 0), Ok(()));
+            assert_eq!(asseterc721.roles.insert(accounts.eve, 2), Ok(()));
+            // Anyone can create assets by default
+            set_sender(accounts.frank);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            // Restrict minting to producers
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.config_mint_producers_only_set(true), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            // Role-less accounts cannot create assets
+            set_sender(accounts.frank);
+            assert_eq!(asseterc721.asset_new(3), Err(Error::NotProducer));
+            // Retailers cannot create assets
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_new(3), Err(Error::NotProducer));
+            // Producers can create assets
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(3), Ok(()));
+            assert_eq!(asseterc721.asset_get_owner(3), Some(accounts.bob));
+            // Only administrators can change the restriction
+            assert_eq!(asseterc721.config_mint_producers_only_set(false), Err(Error::NotAdministrator));
+        }
+
+        #[ink::test]
+        fn category_producer_authorization_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator, Bob and Eve are producers
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.roles.insert(accounts.bob, 0), Ok(()));
+            assert_eq!(asseterc721.roles.insert(accounts.eve, 0), Ok(()));
+            assert_eq!(asseterc721.category_description_new(1, Hash::from([0x01; 32])), Ok(()));
+            // Authorizing on a missing category fails
+            assert_eq!(asseterc721.category_producer_set(2, accounts.bob, true), Err(Error::CategoryNotFound));
+            // Only Bob is authorized on category 1
+            assert_eq!(asseterc721.category_producer_set(1, accounts.bob, true), Ok(()));
+            assert!(asseterc721.category_producer_verify(1, accounts.bob));
+            assert!(!asseterc721.category_producer_verify(1, accounts.eve));
+            // Eve cannot assign category 1 to her asset
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_category_new(1, 1), Err(Error::CategoryNotAuthorized));
+            // Bob can assign category 1 to his asset
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.asset_category_new(2, 1), Ok(()));
+            // Only administrators can authorize producers
+            assert_eq!(asseterc721.category_producer_set(1, accounts.eve, true), Err(Error::NotAdministrator));
+            // Once Bob is revoked the category stays restricted, no producer can assign it
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.category_producer_set(1, accounts.bob, false), Ok(()));
+            assert!(asseterc721.category_restricted_verify(1));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(3), Ok(()));
+            assert_eq!(asseterc721.asset_category_new(3, 1), Err(Error::CategoryNotAuthorized));
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_category_new(1, 1), Err(Error::CategoryNotAuthorized));
+            assert_eq!(asseterc721.category_restricted_set(1, false), Err(Error::NotAdministrator));
+            // until an administrator opens it explicitly
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.category_restricted_set(1, false), Ok(()));
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_category_new(1, 1), Ok(()));
+        }
+
+        #[ink::test]
+        fn producer_license_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let document = Hash::from([0x02; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator, category 1 is regulated
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.category_description_new(1, Hash::from([0x01; 32])), Ok(()));
+            assert_eq!(asseterc721.category_regulated_set(1, true), Ok(()));
+            assert!(asseterc721.category_regulated_verify(1));
+            // Bob cannot add his asset to category 1 without a license
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_category_new(1, 1), Err(Error::LicenseRequired));
+            assert_eq!(asseterc721.license_grant(accounts.bob, document, vec![1], None), Err(Error::NotAdministrator));
+            // Bob holds a license for 2 blocks
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.license_grant(accounts.bob, document, vec![1], Some(2)), Ok(()));
+            assert!(asseterc721.producer_license_verify(accounts.bob, 1));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_category_new(1, 1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            for _ in 0..2 {
+                ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot advance block");
+            }
+            // the lapsed license does not cover the category, even for an asset transferred to another owner
+            assert_eq!(asseterc721.asset_transfer(accounts.eve, 2), Ok(()));
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_category_new(2, 1), Err(Error::LicenseRequired));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.producer_licenses(accounts.bob, 0, 10).0.len(), 1);
+            assert_eq!(asseterc721.license_revoke(accounts.bob, document), Ok(()));
+            assert_eq!(asseterc721.license_revoke(accounts.bob, document), Err(Error::LicenseNotFound));
+            // the administrators need no license
+            assert_eq!(asseterc721.asset_new(3), Ok(()));
+            assert_eq!(asseterc721.asset_category_new(3, 1), Ok(()));
+        }
+
+        #[ink::test]
+        fn custodial_proxy_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator, Bob owns asset Id 1
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            // Bob is not in custodial mode
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.custodial_delegate_single_asset(accounts.eve, 1), Err(Error::NotCustodial));
+            // Bob enables the custodial mode
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.account_custodial_set(true), Ok(()));
+            assert!(asseterc721.account_custodial_verify(accounts.bob));
+            // Only administrators can install custodial proxies
+            assert_eq!(asseterc721.custodial_delegate_single_asset(accounts.eve, 1), Err(Error::NotAdministrator));
+            // Alice installs Eve as proxy of asset Id 1 and Frank as proxy of all Bob's assets
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.custodial_delegate_single_asset(accounts.eve, 1), Ok(()));
+            assert_eq!(asseterc721.asset_get_delegated_account(1), Some(accounts.eve));
+            assert_eq!(asseterc721.custodial_delegate_for_all_asset(accounts.bob, accounts.frank, true), Ok(()));
+            assert!(asseterc721.account_verify_delegated_for_all_asset(accounts.bob, accounts.frank));
+            // Alice revokes Eve
+            assert_eq!(asseterc721.custodial_undelegate_single_asset(1), Ok(()));
+            assert_eq!(asseterc721.asset_get_delegated_account(1), None);
+            // Every action is in the administrators log
+            assert_eq!(asseterc721.admin_log_count(), 3);
+            let log = asseterc721.admin_log_get(2, 10).0;
+            assert_eq!(log.len(), 1);
+            assert_eq!(log[0].admin, accounts.alice);
+            assert_eq!(log[0].action, AdminAction::CustodialProxyRevoked {
+                owner: accounts.bob,
+                operator: accounts.eve,
+                id: Some(1),
             });
-            Ok(())
         }
-        /// Add other metadata to an asset as ipfs address, only the owner can do it
-        #[ink(message)]
-        pub fn asset_metadata_new(&mut self,  id: AssetId, metadata: Hash) -> Result<(), Error> {
-            let caller = self.env().caller();
-            //check if asset id is present in the storage and belongs to the signer
-            let asset = match self.asset_owner.entry(id) {
-                Entry::Vacant(_) => return Err(Error::AssetNotFound),
-                Entry::Occupied(asset) => asset,
-            };
-            if asset.get() != &caller  && self.account_role_get(caller).unwrap()!=5{
-                return Err(Error::NotOwner)
-            };
-            // search for metadata storage
-            let _assetmetadata = match self.asset_metadata.entry(id) {
-                Entry::Vacant(_) => "",
-                Entry::Occupied(_assetmetadata) => return Err(Error::DuplicatedData),
+
+        #[ink::test]
+        fn access_control_is_consistent() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator, Eve is a shipper, Bob owns asset Id 1 and 2
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.roles.insert(accounts.eve, 4), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            // Frank has no role and cannot change Bob's asset
+            set_sender(accounts.frank);
+            assert_eq!(asseterc721.asset_description_new(1, Hash::from([0x01; 32])), Err(Error::NotOwner));
+            assert_eq!(asseterc721.asset_location_new(1, Hash::from([0x01; 32])), Err(Error::NotOwner));
+            assert_eq!(asseterc721.category_description_new(1, Hash::from([0x01; 32])), Err(Error::NotAdministrator));
+            // The shipper can change the location but not the photo
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_location_new(1, Hash::from([0x01; 32])), Ok(()));
+            assert_eq!(asseterc721.asset_photo_new(1, Hash::from([0x01; 32])), Err(Error::NotOwner));
+            // The administrator can change any data and delete the asset
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_location_delete(1), Ok(()));
+            assert_eq!(asseterc721.asset_photo_new(1, Hash::from([0x01; 32])), Ok(()));
+            assert_eq!(asseterc721.asset_delete(1), Ok(()));
+            assert_eq!(asseterc721.account_assets_number(accounts.bob), 1);
+            // The administrator cannot transfer an asset from an account that does not own it
+            assert_eq!(asseterc721.transfer_from(accounts.frank, accounts.eve, 2), Err(Error::NotOwner));
+            assert_eq!(asseterc721.transfer_from(accounts.bob, accounts.eve, 2), Ok(()));
+            // Unknown assets are reported before the authorization
+            set_sender(accounts.frank);
+            assert_eq!(asseterc721.asset_delete(3), Err(Error::AssetNotFound));
+        }
+
+        #[ink::test]
+        fn transfers_since_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice creates asset Id 1 and transfers it to Bob in the next block
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 1), Ok(()));
+            // Bob deletes asset Id 1
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_delete(1), Ok(()));
+            assert_eq!(asseterc721.transfers_count(), 3);
+            // The transfers since the first block include the creation
+            assert_eq!(asseterc721.transfers_since(0), 0);
+            let (journal, next) = asseterc721.transfers_get(0, 10);
+            assert_eq!(journal.len(), 3);
+            assert_eq!(next, None);
+            assert_eq!(journal[0].from, AccountId::from([0x0; 32]));
+            assert_eq!(journal[2].to, AccountId::from([0x0; 32]));
+            // The transfers since the second block skip the creation, read one per page
+            let cursor = asseterc721.transfers_since(1);
+            assert_eq!(cursor, 1);
+            assert_eq!(asseterc721.transfers_get(cursor, 1), (ink_prelude::vec![TransferRecord {
+                id: 1,
+                from: accounts.alice,
+                to: accounts.bob,
+                block: 1,
+                timestamp: journal[1].timestamp,
+                previous: Some(0),
+            }], Some(2)));
+        }
+
+        #[ink::test]
+        fn asset_history_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 1), Ok(()));
+            let timestamp = ink_env::block_timestamp::<ink_env::DefaultEnvironment>().expect("Cannot get timestamp");
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_transfer(accounts.charlie, 1), Ok(()));
+            // the chain of custody of asset Id 1, newest first, skips the creation of asset Id 2
+            let (history, next) = asseterc721.asset_history_get(1, 0, 10);
+            assert_eq!(next, None);
+            assert_eq!(history.iter().map(|transfer| (transfer.from, transfer.to)).collect::<Vec<_>>(), [
+                (accounts.bob, accounts.charlie),
+                (accounts.alice, accounts.bob),
+                (AccountId::from([0x0; 32]), accounts.alice),
+            ]);
+            assert_eq!(history[1].timestamp, timestamp);
+            assert!(history[2].timestamp < timestamp);
+            // read one transfer per page, the cursor follows the journal
+            assert_eq!(asseterc721.asset_history_get(1, 0, 1), (history[..1].to_vec(), Some(3)));
+            // a transfer done between the pages does not shift them
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.asset_transfer(accounts.django, 1), Ok(()));
+            assert_eq!(asseterc721.asset_history_get(1, 3, 1), (history[1..2].to_vec(), Some(1)));
+            assert_eq!(asseterc721.asset_history_get(1, 1, 1), (history[2..].to_vec(), None));
+            assert_eq!(asseterc721.asset_history_get(1, 0, 1).0[0].to, accounts.django);
+            // a cursor on the transfer of another asset ends the history
+            assert_eq!(asseterc721.asset_history_get(1, 2, 10), (Vec::new(), None));
+            assert_eq!(asseterc721.asset_history_get(3, 0, 10), (Vec::new(), None));
+        }
+
+        #[ink::test]
+        fn account_activity_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator, she creates, validates and transfers asset Id 1 to Bob
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_validation_new(1, accounts.alice), Ok(()));
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 1), Ok(()));
+            // Alice's feed starts from the newest activity
+            assert_eq!(asseterc721.account_activity_count(accounts.alice), 3);
+            let kinds: Vec<ActivityKind> = asseterc721
+                .account_activity_get(accounts.alice, 0, 10).0
+                .iter()
+                .map(|entry| entry.kind)
+                .collect();
+            assert_eq!(kinds, [ActivityKind::Sent, ActivityKind::Validated, ActivityKind::Minted]);
+            // Bob received the asset from Alice
+            let feed = asseterc721.account_activity_get(accounts.bob, 0, 10).0;
+            assert_eq!(feed, [ActivityEntry {
+                kind: ActivityKind::Received,
+                id: 1,
+                counterpart: Some(accounts.alice),
+                block: 0,
+            }]);
+            assert_eq!(asseterc721.account_activity_get(accounts.alice, 1, 1).0[0].kind, ActivityKind::Validated);
+            // Bob disputes the validation of Alice, both parties record the opening and the resolution
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.validation_dispute_open(1, Hash::from([0x01; 32])), Ok(0));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.dispute_resolve(0, false), Ok(()));
+            let feed = asseterc721.account_activity_get(accounts.bob, 0, 2).0;
+            assert_eq!(
+                feed.iter().map(|entry| (entry.kind, entry.id, entry.counterpart)).collect::<Vec<_>>(),
+                [(ActivityKind::DisputeResolved(0), 1, Some(accounts.alice)), (ActivityKind::DisputeOpened(0), 1, Some(accounts.alice))]
+            );
+            let feed = asseterc721.account_activity_get(accounts.alice, 0, 2).0;
+            assert_eq!(
+                feed.iter().map(|entry| (entry.kind, entry.counterpart)).collect::<Vec<_>>(),
+                [(ActivityKind::DisputeResolved(0), Some(accounts.bob)), (ActivityKind::DisputeOpened(0), Some(accounts.bob))]
+            );
+        }
+
+        #[ink::test]
+        fn asset_provenance_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice creates asset Id 1 in the second block
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            let provenance = asseterc721.asset_provenance_get(1).expect("Missing provenance");
+            assert_eq!(provenance.minter, accounts.alice);
+            assert_eq!(provenance.block, 1);
+            assert_eq!(provenance.timestamp, ink_env::block_timestamp::<ink_env::DefaultEnvironment>().expect("Cannot get timestamp"));
+            // The provenance does not change with the transfers
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 1), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_transfer(accounts.eve, 1), Ok(()));
+            assert_eq!(asseterc721.asset_provenance_get(1), Some(provenance));
+            assert_eq!(asseterc721.asset_producer_get(1), Some(accounts.alice));
+            // The provenance is removed with the asset
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_delete(1), Ok(()));
+            assert_eq!(asseterc721.asset_provenance_get(1), None);
+        }
+
+        #[ink::test]
+        fn asset_provenance_check_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let hash = Hash::from([0x01; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator, Bob a producer, Charlie a wholesaler, Django a retailer, Eve a final buyer
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            for (account, role) in [(accounts.bob, 0), (accounts.charlie, 1), (accounts.django, 2), (accounts.eve, 3)].iter() {
+                assert_eq!(asseterc721.account_role_new(*account, *role), Ok(()));
+            }
+            assert_eq!(asseterc721.asset_provenance_check(1), Err(ProvenanceIssue::AssetNotFound));
+            // Asset 1 flows down the supply chain
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_transfer(accounts.charlie, 1), Ok(()));
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.asset_transfer(accounts.django, 1), Ok(()));
+            set_sender(accounts.django);
+            assert_eq!(asseterc721.asset_transfer(accounts.eve, 1), Ok(()));
+            assert_eq!(asseterc721.asset_provenance_check(1), Ok(4));
+            assert!(asseterc721.asset_provenance_valid(1));
+            // Asset 2 goes back from the wholesaler to the producer, at position 6 of the journal
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.asset_transfer(accounts.charlie, 2), Ok(()));
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 2), Ok(()));
+            assert_eq!(asseterc721.asset_provenance_check(2), Err(ProvenanceIssue::RoleFlow(6)));
+            assert!(!asseterc721.asset_provenance_valid(2));
+            // Eve sends asset 1 back to the retailer, consistent once the return is received
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_transfer(accounts.django, 1), Ok(()));
+            assert_eq!(asseterc721.asset_provenance_check(1), Err(ProvenanceIssue::RoleFlow(7)));
+            set_sender(accounts.django);
+            assert_eq!(asseterc721.asset_return_receive(1, accounts.eve, hash), Ok(()));
+            assert_eq!(asseterc721.asset_provenance_check(1), Ok(5));
+            // A revoked validation breaks the chain
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(3), Ok(()));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_validation_new(3, accounts.alice), Ok(()));
+            assert_eq!(asseterc721.asset_provenance_check(3), Ok(1));
+            assert_eq!(asseterc721.asset_validation_revoke(3, hash), Ok(()));
+            assert_eq!(asseterc721.asset_provenance_check(3), Err(ProvenanceIssue::ValidationRevoked));
+        }
+
+        #[ink::test]
+        fn brand_registry_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Bob is a producer, Eve is a retailer
+            assert_eq!(asseterc721.roles.insert(accounts.bob, 0), Ok(()));
+            assert_eq!(asseterc721.roles.insert(accounts.eve, 2), Ok(()));
+            // Only producers can register a brand
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.brand_register(Hash::from([0x01; 32]), Hash::from([0x02; 32])), Err(Error::NotProducer));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.brand_register(Hash::from([0x01; 32]), Hash::from([0x02; 32])), Ok(0));
+            assert_eq!(asseterc721.brand_get(0).map(|brand| brand.owner), Some(accounts.bob));
+            // Bob creates asset Id 1 and 2 linked to his brand
+            assert_eq!(asseterc721.asset_new_branded(1, 0), Ok(()));
+            assert_eq!(asseterc721.asset_new_branded(2, 0), Ok(()));
+            assert_eq!(asseterc721.asset_new_branded(3, 1), Err(Error::BrandNotFound));
+            assert_eq!(asseterc721.asset_brand_get(1), Some(0));
+            assert_eq!(asseterc721.brand_assets(0, 0, 10).0, [1, 2]);
+            // Eve cannot link her assets to Bob's brand
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_new_branded(3, 0), Err(Error::NotBrandOwner));
+            assert!(!asseterc721.asset_verify(3));
+            // A deleted asset is removed from its brand
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_delete(1), Ok(()));
+            assert_eq!(asseterc721.brand_assets_count(0), 1);
+            assert_eq!(asseterc721.brand_assets(0, 0, 10).0, [2]);
+        }
+
+        #[ink::test]
+        fn brand_verification_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator, Bob is a producer and registers a brand
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.roles.insert(accounts.bob, 0), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.brand_register(Hash::from([0x01; 32]), Hash::from([0x02; 32])), Ok(0));
+            // A registered brand is not verified and only an administrator can verify it
+            assert_eq!(asseterc721.brand_get(0).map(|brand| brand.verified), Some(false));
+            assert_eq!(asseterc721.brand_verify(0), Err(Error::NotAdministrator));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.brand_verify(0), Ok(()));
+            assert_eq!(asseterc721.brand_verify(0), Err(Error::DuplicatedData));
+            assert_eq!(asseterc721.brand_get(0).map(|brand| brand.verified), Some(true));
+            assert_eq!(asseterc721.brand_unverify(0), Ok(()));
+            assert_eq!(asseterc721.brand_get(0).map(|brand| brand.verified), Some(false));
+            assert_eq!(asseterc721.brand_verify(1), Err(Error::BrandNotFound));
+        }
+
+        #[ink::test]
+        fn product_variants_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator, Bob and Eve are producers
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.roles.insert(accounts.bob, 0), Ok(()));
+            assert_eq!(asseterc721.roles.insert(accounts.eve, 0), Ok(()));
+            assert_eq!(asseterc721.category_description_new(10, Hash::from([0x01; 32])), Ok(()));
+            // Bob defines a product of category 10 sold in two sizes
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.product_new(Hash::from([0x02; 32]), 20), Err(Error::CategoryNotFound));
+            assert_eq!(asseterc721.product_new(Hash::from([0x02; 32]), 10), Ok(0));
+            assert_eq!(asseterc721.product_variant_new(1, Some(38), None, None), Err(Error::ProductNotFound));
+            assert_eq!(asseterc721.product_variant_new(0, Some(38), Some([0xFF, 0x00, 0x00]), None), Ok(0));
+            assert_eq!(asseterc721.product_variant_new(0, Some(40), Some([0xFF, 0x00, 0x00]), None), Ok(1));
+            assert_eq!(asseterc721.product_get(0).map(|product| product.variants_count), Some(2));
+            assert_eq!(asseterc721.product_variant_get(0, 1).and_then(|variant| variant.size), Some(40));
+            assert_eq!(asseterc721.product_variants(0, 0, 10).0.len(), 2);
+            // Bob's assets reference the variants of his product
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.asset_new(3), Ok(()));
+            assert_eq!(asseterc721.asset_variant_set(1, 0, 2), Err(Error::VariantNotFound));
+            assert_eq!(asseterc721.asset_variant_set(1, 0, 0), Ok(()));
+            assert_eq!(asseterc721.asset_variant_set(1, 0, 1), Err(Error::DuplicatedData));
+            assert_eq!(asseterc721.asset_variant_set(2, 0, 1), Ok(()));
+            assert_eq!(asseterc721.asset_variant_set(3, 0, 0), Ok(()));
+            assert_eq!(asseterc721.asset_variant_get(2), Some((0, 1)));
+            assert_eq!(asseterc721.product_assets(0, 0, 10).0, [1, 2, 3]);
+            assert_eq!(asseterc721.variant_assets(0, 0, 0, 10).0, [1, 3]);
+            // Eve can neither add variants to Bob's product nor link her assets to it
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.product_variant_new(0, Some(42), None, None), Err(Error::NotProducer));
+            assert_eq!(asseterc721.asset_new(4), Ok(()));
+            assert_eq!(asseterc721.asset_variant_set(4, 0, 0), Err(Error::NotProducer));
+            // A deleted asset is removed from its product and its variant
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_delete(1), Ok(()));
+            assert_eq!(asseterc721.product_assets_count(0), 2);
+            assert_eq!(asseterc721.variant_assets(0, 0, 0, 10).0, [3]);
+            assert_eq!(asseterc721.variant_assets_count(0, 1), 1);
+        }
+
+        #[ink::test]
+        fn sku_registry_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator, Bob and Eve are producers of category 10
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.roles.insert(accounts.bob, 0), Ok(()));
+            assert_eq!(asseterc721.roles.insert(accounts.eve, 0), Ok(()));
+            assert_eq!(asseterc721.category_description_new(10, Hash::from([0x01; 32])), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.product_new(Hash::from([0x02; 32]), 10), Ok(0));
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.product_new(Hash::from([0x03; 32]), 10), Ok(1));
+            // Bob registers the SKU of his product, Eve cannot register it for hers nor change it
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.sku_register(b"TS-38".to_vec(), 2, None, None, None), Err(Error::ProductNotFound));
+            assert_eq!(asseterc721.sku_register(b"TS 38".to_vec(), 0, None, None, None), Err(Error::InvalidSku));
+            assert_eq!(asseterc721.sku_register(b"TS-38".to_vec(), 1, None, None, None), Err(Error::NotProducer));
+            assert_eq!(asseterc721.sku_register(b"TS-38".to_vec(), 0, Some(38), None, None), Ok(()));
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.sku_register(b"ts-38".to_vec(), 1, None, None, None), Err(Error::SkuTaken));
+            assert_eq!(asseterc721.sku_attributes_set(b"TS-38".to_vec(), Some(40), None, None), Err(Error::NotProducer));
+            assert_eq!(asseterc721.sku_remove(b"TS-38".to_vec()), Err(Error::NotProducer));
+            let entry = asseterc721.sku_get(b"ts-38".to_vec()).expect("SKU not found");
+            assert_eq!((entry.product, entry.category, entry.attributes.size, entry.registrar), (0, 10, Some(38), accounts.bob));
+            // The administrator frees the code of a conflicting SKU
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.sku_remove(b"TS-38".to_vec()), Ok(()));
+            assert_eq!(asseterc721.sku_remove(b"TS-38".to_vec()), Err(Error::SkuNotFound));
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.sku_register(b"ts-38".to_vec(), 1, None, None, None), Ok(()));
+            assert_eq!(asseterc721.sku_attributes_set(b"TS-38".to_vec(), Some(40), None, None), Ok(()));
+            assert_eq!(asseterc721.sku_get(b"TS-38".to_vec()).map(|entry| (entry.product, entry.attributes.size)), Some((1, Some(40))));
+        }
+
+        #[ink::test]
+        fn asset_authenticity_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice creates asset Id 1 and commits the secret of its packaging
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_authenticity_reveal(1, b"secret".to_vec()), Err(Error::CommitmentNotFound));
+            let commitment = crate::authenticity::Authenticity::commitment_of(b"secret");
+            assert_eq!(asseterc721.asset_authenticity_commit(1, commitment), Ok(()));
+            assert_eq!(asseterc721.asset_authenticity_commit(1, commitment), Err(Error::DuplicatedData));
+            assert!(asseterc721.asset_authenticity_committed(1));
+            // Bob receives the asset and cannot change the commitment
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 1), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_authenticity_commit(1, commitment), Err(Error::NotProducer));
+            // Eve holds the product and reveals the secret once
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_authenticity_reveal(1, b"wrong".to_vec()), Err(Error::InvalidSecret));
+            assert_eq!(asseterc721.asset_authenticity_get(1), None);
+            assert_eq!(asseterc721.asset_authenticity_reveal(1, b"secret".to_vec()), Ok(()));
+            assert_eq!(asseterc721.asset_authenticity_get(1), Some(Reveal {
+                account: accounts.eve,
+                block: 0,
+            }));
+            assert_eq!(asseterc721.asset_authenticity_reveal(1, b"secret".to_vec()), Err(Error::AlreadyRevealed));
+        }
+
+        #[ink::test]
+        fn asset_tag_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            let tag = Hash::from([0x01; 32]);
+            // Alice is an administrator, Bob owns asset Id 1 and 2
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            // The tag resolves to one asset only
+            assert_eq!(asseterc721.asset_tag_bind(1, tag), Ok(()));
+            assert_eq!(asseterc721.asset_tag_bind(2, tag), Err(Error::TagAlreadyBound));
+            assert_eq!(asseterc721.asset_by_tag(tag), Some(1));
+            // Only an administrator can move the tag
+            assert_eq!(asseterc721.asset_tag_rebind(2, tag), Err(Error::NotAdministrator));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_tag_rebind(2, tag), Ok(()));
+            assert_eq!(asseterc721.asset_by_tag(tag), Some(2));
+            assert_eq!(asseterc721.asset_tag_get(1), None);
+            // The tag is released when the asset is deleted
+            assert_eq!(asseterc721.asset_delete(2), Ok(()));
+            assert_eq!(asseterc721.asset_by_tag(tag), None);
+        }
+
+        #[ink::test]
+        fn possible_duplicate_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            let photo = Hash::from([0x01; 32]);
+            // Alice is an administrator and creates asset Id 1 with a photo
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_photo_new(1, photo), Ok(()));
+            // Bob stores the same photo for asset Id 2
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.asset_photo_new(2, photo), Ok(()));
+            // Both the assets are flagged and the event is emitted
+            assert!(asseterc721.asset_duplicate_verify(1));
+            assert!(asseterc721.asset_duplicate_verify(2));
+            let event = ink_env::test::recorded_events().last().expect("No event");
+            match <Event as scale::Decode>::decode(&mut &event.data[..]).expect("Invalid event") {
+                Event::PossibleDuplicate(duplicate) => {
+                    assert_eq!((duplicate.id, duplicate.other, duplicate.content), (2, 1, photo));
+                }
+                _ => panic!("Expected PossibleDuplicate event"),
+            }
+            // Only an administrator can clear the flag
+            assert_eq!(asseterc721.asset_duplicate_clear(2), Err(Error::NotAdministrator));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_duplicate_clear(2), Ok(()));
+            assert_eq!(asseterc721.asset_duplicate_clear(2), Err(Error::CannotRemove));
+            assert!(!asseterc721.asset_duplicate_verify(2));
+        }
+
+        #[ink::test]
+        fn localized_descriptions_work() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice creates asset Id 1 with the description in english and italian
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_description_set_locale(1, *b"en", Hash::from([0x01; 32])), Ok(()));
+            assert_eq!(asseterc721.asset_description_set_locale(1, *b"it", Hash::from([0x02; 32])), Ok(()));
+            assert_eq!(asseterc721.asset_description_locales(1, 0, 10).0, [*b"en", *b"it"]);
+            assert_eq!(asseterc721.asset_description_get_locale(1, *b"it"), Some(Hash::from([0x02; 32])));
+            // Bob cannot change the translations
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_description_set_locale(1, *b"en", Hash::from([0x03; 32])), Err(Error::NotOwner));
+            assert_eq!(asseterc721.asset_description_delete_locale(1, *b"en"), Err(Error::NotOwner));
+            // Alice removes the english translation
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_description_delete_locale(1, *b"en"), Ok(()));
+            assert_eq!(asseterc721.asset_description_locales(1, 0, 10).0, [*b"it"]);
+            assert_eq!(asseterc721.asset_description_get_locale(1, *b"en"), None);
+        }
+
+        #[ink::test]
+        fn quantity_split_transfer_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice creates asset Id 1 with 1000 liters of milk
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_quantity_split_transfer(1, accounts.bob, 10), Err(Error::QuantityNotFound));
+            assert_eq!(asseterc721.asset_quantity_set(1, 1000, Unit::Liter), Ok(()));
+            // Alice transfers 300 liters to Bob in a new lot
+            let lot_id = crate::quantity::FIRST_LOT_ID;
+            assert_eq!(asseterc721.asset_quantity_split_transfer(1, accounts.bob, 300), Ok(lot_id));
+            assert_eq!(asseterc721.asset_get_owner(lot_id), Some(accounts.bob));
+            assert_eq!(asseterc721.asset_quantity_get(1), Some(Quantity { amount: 700, unit: Unit::Liter }));
+            assert_eq!(asseterc721.asset_quantity_get(lot_id), Some(Quantity { amount: 300, unit: Unit::Liter }));
+            assert_eq!(asseterc721.asset_parent_get(lot_id), Some(1));
+            assert_eq!(asseterc721.asset_producer_get(lot_id), Some(accounts.alice));
+            // More than the available quantity cannot be transferred
+            assert_eq!(asseterc721.asset_quantity_split_transfer(1, accounts.bob, 701), Err(Error::InvalidQuantity));
+            // Bob cannot split Alice's asset
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_quantity_split_transfer(1, accounts.bob, 100), Err(Error::NotOwner));
+            // The whole quantity transfers the asset
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_quantity_split_transfer(1, accounts.eve, 700), Ok(1));
+            assert_eq!(asseterc721.asset_get_owner(1), Some(accounts.eve));
+        }
+
+        #[ink::test]
+        fn physical_attributes_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let attributes = PhysicalAttributes {
+                weight: 12_500,
+                length: 400,
+                width: 300,
+                height: 250,
+                packaging: Packaging::Box,
             };
-            // add metadata if not already present
-            if self.asset_metadata.insert(id, metadata).is_some() {
-                return Err(Error::CannotInsert)
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_physical_delete(1), Err(Error::CannotRemove));
+            assert_eq!(asseterc721.asset_physical_set(1, attributes), Ok(()));
+            assert_eq!(asseterc721.asset_physical_get(1), Some(attributes));
+            // The attributes are part of the composite view of the asset
+            assert_eq!(asseterc721.asset_info_get(1).and_then(|info| info.physical), Some(attributes));
+            // Bob cannot change Alice's asset
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_physical_set(1, attributes), Err(Error::NotOwner));
+            assert_eq!(asseterc721.asset_physical_delete(1), Err(Error::NotOwner));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_physical_delete(1), Ok(()));
+            assert_eq!(asseterc721.asset_physical_get(1), None);
+        }
+
+        #[ink::test]
+        fn asset_depreciation_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let schedule = Schedule {
+                method: Method::StraightLine,
+                cost: 10_000,
+                salvage: 1_000,
+                rate: 2_000,
+                start: YEAR,
             };
-            self.env().emit_event(AssetUpdate {
-                from: caller,
-                id,
-            });
-            Ok(())
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_book_value(1), None);
+            assert_eq!(asseterc721.asset_depreciation_delete(1), Err(Error::CannotRemove));
+            assert_eq!(asseterc721.asset_depreciation_set(1, Schedule { salvage: 20_000, ..schedule }), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.asset_depreciation_set(1, schedule), Ok(()));
+            assert_eq!(asseterc721.asset_depreciation_get(1), Some(schedule));
+            // The depreciation has not started yet
+            assert_eq!(asseterc721.asset_book_value(1), Some(10_000));
+            // Bob cannot change Alice's asset
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_depreciation_set(1, schedule), Err(Error::NotOwner));
+            assert_eq!(asseterc721.asset_depreciation_delete(1), Err(Error::NotOwner));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_depreciation_delete(1), Ok(()));
+            assert_eq!(asseterc721.asset_book_value(1), None);
+        }
+
+        #[ink::test]
+        fn asset_service_log_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let report = Hash::from([0x01; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            // Alice authorizes the workshop of Charlie on asset Id 1
+            assert_eq!(asseterc721.asset_service_provider_authorize(1, accounts.charlie, true), Ok(()));
+            assert_eq!(asseterc721.asset_service_providers(1, 0, 10).0, [accounts.charlie]);
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_service_provider_authorize(1, accounts.bob, true), Err(Error::NotOwner));
+            assert_eq!(asseterc721.asset_service_add(1, 3, report, 1_000), Err(Error::NotAllowed));
+            // Charlie records two services, the odometer cannot go backwards
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.asset_service_add(1, 3, report, 1_000), Ok(()));
+            assert_eq!(asseterc721.asset_service_add(1, 4, report, 800), Err(Error::InvalidUsage));
+            assert_eq!(asseterc721.asset_service_add(1, 4, report, 5_000), Ok(()));
+            assert_eq!(asseterc721.asset_service_count(1), 2);
+            assert_eq!(asseterc721.asset_service_log(1, 1, 10).0.iter().map(|entry| (entry.service_type, entry.usage)).collect::<Vec<_>>(), [(4, 5_000)]);
+            // The log travels with the asset, the authorizations do not
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 1), Ok(()));
+            assert_eq!(asseterc721.asset_service_count(1), 2);
+            assert!(asseterc721.asset_service_providers(1, 0, 10).0.is_empty());
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.asset_service_add(1, 3, report, 6_000), Err(Error::NotAllowed));
+        }
+
+        #[ink::test]
+        fn asset_notes_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let remark = Hash::from([0x01; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.account_role_new(accounts.bob, SHIPPER), Ok(()));
+            assert_eq!(asseterc721.account_role_new(accounts.charlie, INSPECTOR), Ok(()));
+            assert_eq!(asseterc721.account_role_new(accounts.django, 2), Ok(()));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_transfer(accounts.eve, 1), Ok(()));
+            // The owner, the shippers and the inspectors append notes, the other accounts do not
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_note_add(1, remark), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_note_add(1, remark), Ok(()));
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.asset_note_add(1, remark), Ok(()));
+            set_sender(accounts.django);
+            assert_eq!(asseterc721.asset_note_add(1, remark), Err(Error::NotOwner));
+            assert_eq!(asseterc721.asset_note_add(2, remark), Err(Error::AssetNotFound));
+            // Each note keeps the role of its author
+            assert_eq!(asseterc721.asset_notes_count(1), 3);
+            assert_eq!(
+                asseterc721.asset_notes(1, 0, 10).0.iter().map(|note| (note.author, note.role)).collect::<Vec<_>>(),
+                [(accounts.eve, None), (accounts.bob, Some(SHIPPER)), (accounts.charlie, Some(INSPECTOR))]
+            );
+            assert_eq!(asseterc721.asset_notes(1, 2, 10).0.len(), 1);
+        }
+
+        #[ink::test]
+        fn asset_lien_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let document = Hash::from([0x01; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            // Charlie files a lien on asset Id 1, pending until Alice consents
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.asset_lien_new(1, 1_000, document), Ok(0));
+            assert_eq!(asseterc721.asset_lien_new(2, 1_000, document), Err(Error::AssetNotFound));
+            assert_eq!(asseterc721.asset_lien_consent(0, true), Err(Error::NotOwner));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_liens(1, 0, 10).0.len(), 1);
+            assert_eq!(asseterc721.asset_lien_consent(0, true), Ok(()));
+            assert_eq!(asseterc721.asset_lien_consent(0, true), Err(Error::LienNotFound));
+            // The encumbered asset cannot change owner
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 1), Err(Error::AssetEncumbered));
+            assert_eq!(asseterc721.asset_listing_new(1, 100, None, None), Err(Error::AssetEncumbered));
+            assert_eq!(asseterc721.asset_delete(1), Err(Error::AssetEncumbered));
+            assert_eq!(asseterc721.asset_lien_release(0), Err(Error::NotAllowed));
+            // Only the creditor releases the lien
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.asset_lien_release(0), Ok(()));
+            assert_eq!(asseterc721.lien_get(0).map(|lien| lien.status), Some(LienStatus::Released));
+            assert!(asseterc721.asset_liens(1, 0, 10).0.is_empty());
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 1), Ok(()));
+            // A rejected lien does not encumber the asset
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.asset_lien_new(1, 500, document), Ok(1));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_lien_consent(1, false), Ok(()));
+            assert!(asseterc721.asset_liens(1, 0, 10).0.is_empty());
+            assert_eq!(asseterc721.asset_transfer(accounts.alice, 1), Ok(()));
+        }
+
+        #[ink::test]
+        fn asset_hold_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let order = Hash::from([0x01; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_hold_place(1, order), Err(Error::NotAdministrator));
+            // Alice places a legal hold on asset Id 1 of Bob
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.asset_hold_place(2, order), Err(Error::AssetNotFound));
+            assert_eq!(asseterc721.asset_hold_place(1, order), Ok(()));
+            assert_eq!(asseterc721.asset_hold_place(1, order), Err(Error::AssetOnHold));
+            assert_eq!(asseterc721.asset_hold_get(1).map(|hold| hold.order), Some(order));
+            // The held asset cannot change owner or be deleted
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_transfer(accounts.charlie, 1), Err(Error::AssetOnHold));
+            assert_eq!(asseterc721.asset_listing_new(1, 100, None, None), Err(Error::AssetOnHold));
+            assert_eq!(asseterc721.asset_delete(1), Err(Error::AssetOnHold));
+            assert_eq!(asseterc721.asset_hold_lift(1), Err(Error::NotAdministrator));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_hold_lift(1), Ok(()));
+            assert_eq!(asseterc721.asset_hold_lift(1), Err(Error::HoldNotFound));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_transfer(accounts.charlie, 1), Ok(()));
+        }
+
+        #[ink::test]
+        fn asset_return_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let reason = Hash::from([0x01; 32]);
+            let report = Hash::from([0x02; 32]);
+            // Create a new contract instance administered from Django.
+            let mut asseterc721 = AssetErc721::new_with_administrators([accounts.django].to_vec());
+            assert_eq!(asseterc721.roles.insert(accounts.eve, 7), Ok(()));
+            // Bob returns asset Id 1 to Alice, that marks it Returned
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.asset_listing_new(1, 100, None, None), Ok(()));
+            assert_eq!(asseterc721.asset_stock_status(1), Some(StockStatus::InStock));
+            assert_eq!(asseterc721.asset_return_receive(1, accounts.bob, reason), Ok(()));
+            assert_eq!(asseterc721.asset_return_receive(1, accounts.bob, reason), Err(Error::AssetReturned));
+            assert_eq!(asseterc721.asset_stock_status(1), Some(StockStatus::Returned));
+            assert_eq!(asseterc721.asset_listing_get(1), None);
+            // The returned asset cannot re-enter the supply chain before it is inspected and restocked
+            assert_eq!(asseterc721.asset_transfer(accounts.charlie, 1), Err(Error::AssetReturned));
+            assert_eq!(asseterc721.asset_listing_new(1, 100, None, None), Err(Error::AssetReturned));
+            assert_eq!(asseterc721.asset_return_restock(1), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.asset_return_inspect(1, report), Err(Error::MissingCapability));
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_return_inspect(1, report), Ok(()));
+            assert_eq!(asseterc721.asset_return_restock(1), Err(Error::NotOwner));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_return_restock(1), Ok(()));
+            assert_eq!(asseterc721.asset_stock_status(1), Some(StockStatus::InStock));
+            assert_eq!(asseterc721.asset_transfer(accounts.charlie, 1), Ok(()));
+            let record = asseterc721.asset_returns(1, 0, 10).0[0];
+            assert_eq!((record.from, record.inspector, record.report, record.status), (accounts.bob, Some(accounts.eve), Some(report), StockStatus::InStock));
+            // Asset Id 2 is routed to disposal and stays blocked
+            assert_eq!(asseterc721.asset_return_receive(2, accounts.bob, reason), Ok(()));
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_return_inspect(2, report), Ok(()));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_return_to_disposal(2), Ok(()));
+            assert_eq!(asseterc721.asset_return_restock(2), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.asset_transfer(accounts.charlie, 2), Err(Error::AssetReturned));
+            assert_eq!(asseterc721.asset_returns_count(2), 1);
+        }
+
+        #[ink::test]
+        fn asset_dispose_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let evidence = Hash::from([0x01; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.roles.insert(accounts.eve, 7), Ok(()));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.asset_listing_new(1, 100, None, None), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_dispose(1, 3, evidence), Err(Error::NotOwner));
+            // Alice disposes of asset Id 1, that is retained permanently
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_dispose(1, 3, evidence), Ok(()));
+            assert_eq!(asseterc721.asset_dispose(1, 3, evidence), Err(Error::AssetDisposed));
+            assert_eq!(asseterc721.asset_stock_status(1), Some(StockStatus::Disposed));
+            assert_eq!(asseterc721.asset_listing_get(1), None);
+            assert_eq!(
+                asseterc721.asset_disposal_get(1).map(|disposal| (disposal.method, disposal.evidence, disposal.disposer)),
+                Some((3, evidence, accounts.alice))
+            );
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 1), Err(Error::AssetDisposed));
+            assert_eq!(asseterc721.asset_return_receive(1, accounts.bob, evidence), Err(Error::AssetDisposed));
+            assert_eq!(asseterc721.asset_delete(1), Err(Error::AssetDisposed));
+            assert_eq!(asseterc721.asset_get_owner(1), Some(accounts.alice));
+            // A returned asset is disposed of once routed to disposal
+            assert_eq!(asseterc721.asset_return_receive(2, accounts.bob, evidence), Ok(()));
+            assert_eq!(asseterc721.asset_dispose(2, 3, evidence), Err(Error::AssetReturned));
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_return_inspect(2, evidence), Ok(()));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_return_to_disposal(2), Ok(()));
+            assert_eq!(asseterc721.asset_dispose(2, 1, evidence), Ok(()));
+            assert_eq!(asseterc721.disposals(0, 10).0, [1, 2]);
         }
-        /// Returns the metada ipfs address of an asset
-        #[ink(message)]
-        pub fn asset_metadata_get(&self, id: AssetId) ->  Option<Hash>{
-           self.asset_metadata.get(&id).cloned()
+
+        #[ink::test]
+        fn asset_recycle_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let evidence = Hash::from([0x01; 32]);
+            // Create a new contract instance administered from Django.
+            let mut asseterc721 = AssetErc721::new_with_administrators([accounts.django].to_vec());
+            assert_eq!(asseterc721.roles.insert(accounts.bob, 8), Ok(()));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.asset_new(3), Ok(()));
+            assert_eq!(asseterc721.asset_dispose(1, 1, evidence), Ok(()));
+            assert_eq!(asseterc721.asset_dispose(2, 1, evidence), Ok(()));
+            assert_eq!(asseterc721.asset_recycle(10, [1, 2].to_vec()), Err(Error::NotRecycler));
+            // Bob claims the materials of the disposed assets Id 1 and 2 into asset Id 10
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_recycle(10, [1, 3].to_vec()), Err(Error::AssetNotDisposed));
+            assert_eq!(asseterc721.asset_recycle(10, [1, 4].to_vec()), Err(Error::AssetNotFound));
+            assert_eq!(asseterc721.asset_recycle(10, [1, 2].to_vec()), Ok(()));
+            assert_eq!(asseterc721.asset_get_owner(10), Some(accounts.bob));
+            assert_eq!(asseterc721.asset_recycled_sources(10, 0, 10), ([1, 2].to_vec(), None));
+            assert_eq!(asseterc721.asset_recycled_sources(10, 0, 1), ([1].to_vec(), Some(1)));
+            assert_eq!(asseterc721.asset_recycled_into(2), Some(10));
+            // The materials of an asset are claimed once
+            assert_eq!(asseterc721.asset_recycle(11, [2].to_vec()), Err(Error::MaterialsClaimed));
+            assert!(!asseterc721.asset_verify(11));
+            let recycled = ink_env::test::recorded_events()
+                .filter_map(|event| <Event as scale::Decode>::decode(&mut &event.data[..]).ok())
+                .filter_map(|event| match event {
+                    Event::AssetRecycled(recycled) => Some((recycled.id, recycled.sources)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(recycled, [(10, [1, 2].to_vec())]);
         }
-        /// Verifies if there is metadata stored for an asset id
-        #[ink(message)]
-        pub fn asset_metadata_verify(&self, id: AssetId) -> bool{
-            self.asset_metadata.contains_key(&id)
+
+        #[ink::test]
+        fn asset_remanufacture_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.asset_listing_new(1, 100, None, None), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_remanufacture(1, 10), Err(Error::NotOwner));
+            // Alice retires asset Id 1 and creates the refurbished asset Id 10
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_remanufacture(1, 2), Err(Error::AssetExists));
+            assert_eq!(asseterc721.asset_remanufacture(1, 10), Ok(()));
+            assert_eq!(asseterc721.asset_remanufacture(1, 11), Err(Error::AssetRetired));
+            assert_eq!(asseterc721.asset_stock_status(1), Some(StockStatus::Remanufactured));
+            assert_eq!(asseterc721.asset_get_owner(10), Some(accounts.alice));
+            assert_eq!(asseterc721.asset_remanufactured_into(1), Some(10));
+            assert_eq!(asseterc721.asset_remanufactured_from(10), Some(1));
+            assert_eq!(asseterc721.asset_listing_get(1), None);
+            // The retired asset is retained permanently
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 1), Err(Error::AssetRetired));
+            assert_eq!(asseterc721.asset_delete(1), Err(Error::AssetRetired));
+            // The refurbished asset is in the supply chain
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 10), Ok(()));
         }
-        /// Removes metadata of an asset id, only the owner can do it
-        #[ink(message)]
-        pub fn asset_metadata_delete(&mut self,  id: AssetId) -> Result<(), Error> {
-            let caller = self.env().caller();
-            //check if asset id is present in the storage and belongs to the signer
-            let asset = match self.asset_owner.entry(id) {
-                Entry::Vacant(_) => return Err(Error::AssetNotFound),
-                Entry::Occupied(asset) => asset,
-            };
-            if asset.get() != &caller  && self.account_role_get(caller).unwrap()!=5{
-                return Err(Error::NotOwner)
-            };
-            // search for metadata
-            let assetmetadata = match self.asset_metadata.entry(id) {
-                Entry::Vacant(_) => return Err(Error::AssetNotFound),
-                Entry::Occupied(assetmetadata) => assetmetadata
-            };
-            // remove description
-            assetmetadata.remove_entry();
-            self.env().emit_event(AssetUpdate {
-                from: caller,
-                id,
-            });
-            Ok(())
+
+        #[ink::test]
+        fn account_erasure_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let salt = Hash::from([0x01; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.config_erasure_retention_set(2), Ok(()));
+            // Bob buys asset Id 1 from Alice and later gives it to Charlie
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 1), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.account_erasure_request(), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.asset_note_add(1, Hash::from([0x03; 32])), Ok(()));
+            assert_eq!(asseterc721.asset_location_append(1, Hash::from([0x04; 32])), Ok(0));
+            assert_eq!(asseterc721.asset_transfer(accounts.charlie, 1), Ok(()));
+            assert_eq!(asseterc721.account_erasure_request(), Ok(()));
+            assert_eq!(asseterc721.account_erasure_request(), Err(Error::DuplicatedData));
+            // Only the administrators commit the salt and run the erasure
+            let commitment = Erasure::commitment_of(salt);
+            assert_eq!(asseterc721.account_erasure_commit(accounts.bob, commitment), Err(Error::NotAdministrator));
+            assert_eq!(asseterc721.account_erasure_execute(accounts.bob, salt, 10), Err(Error::NotAdministrator));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.account_erasure_commit(accounts.charlie, commitment), Err(Error::ErasureNotFound));
+            assert_eq!(asseterc721.account_erasure_execute(accounts.bob, salt, 10), Err(Error::RetentionPending));
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>().expect("Cannot advance block");
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>().expect("Cannot advance block");
+            assert_eq!(asseterc721.account_erasure_execute(accounts.bob, salt, 10), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.account_erasure_commit(accounts.bob, commitment), Ok(()));
+            assert_eq!(asseterc721.account_erasure_commit(accounts.bob, commitment), Err(Error::DuplicatedData));
+            // The request does not reveal the pseudonym and the salt must match the commitment
+            assert_eq!(asseterc721.account_erasure_get(accounts.bob).and_then(|request| request.commitment), Some(commitment));
+            assert_eq!(asseterc721.account_erasure_execute(accounts.bob, Hash::from([0x02; 32]), 2), Err(Error::NotAllowed));
+            // Bob is replaced in batches of two transfers
+            assert_eq!(asseterc721.account_erasure_execute(accounts.bob, salt, 2), Ok(false));
+            assert_eq!(asseterc721.account_erasure_execute(accounts.bob, salt, 2), Ok(true));
+            assert_eq!(asseterc721.account_erasure_get(accounts.bob), None);
+            let pseudonym = Erasure::pseudonym_of(&accounts.bob, salt);
+            let journal = asseterc721.transfers_get(0, 10).0;
+            assert_eq!(journal[1].to, pseudonym);
+            assert_eq!(journal[2].from, pseudonym);
+            assert_eq!(journal[2].to, accounts.charlie);
+            assert_eq!(asseterc721.account_activity_count(accounts.bob), 0);
+            assert_eq!(asseterc721.account_activity_get(accounts.charlie, 0, 1).0[0].counterpart, Some(pseudonym));
+            assert_eq!(asseterc721.account_activity_get(accounts.alice, 0, 1).0[0].counterpart, Some(pseudonym));
+            // The notes and the route of the asset name the pseudonym
+            assert_eq!(asseterc721.asset_notes(1, 0, 10).0[0].author, pseudonym);
+            assert_eq!(asseterc721.asset_location_history(1, 0, 10).0[0].recorder, pseudonym);
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.account_erasure_cancel(), Err(Error::ErasureNotFound));
         }
-        /// Validate an asset from an administrator account
-        #[ink(message)]
-        pub fn asset_validation_new(&mut self,  id: AssetId, accountid: AccountId) -> Result<(), Error> {
-            let caller = self.env().caller();
-            // check for administrator 
-            let administrator=AssetErc721::administrator_accountid().unwrap();
-            if administrator != caller && self.account_role_get(caller).unwrap()!=5{
-                return Err(Error::NotAdministrator)
-            }
-            //check if asset id is present in the storage
-            let _asset = match self.asset_owner.entry(id) {
-                Entry::Vacant(_) => return Err(Error::AssetNotFound),
-                Entry::Occupied(asset) => asset,
-            };
-            // search for validation storage to avoid duplicated entries
-            let _assetvalidation= match self.asset_validation.entry(id) {
-                Entry::Vacant(_) => "",
-                Entry::Occupied(_assetvalidation) => return Err(Error::DuplicatedData),
-            };
-            // add validation if not already present
-            if self.asset_validation.insert(id, accountid).is_some() {
-                return Err(Error::CannotInsert)
+
+        #[ink::test]
+        fn asset_updates_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.config_update_log_size_set(2), Ok(()));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_quantity_set(1, 10, Unit::Kilogram), Ok(()));
+            assert_eq!(asseterc721.asset_tag_bind(1, Hash::from([0x01; 32])), Ok(()));
+            assert_eq!(asseterc721.asset_quantity_set(1, 8, Unit::Kilogram), Ok(()));
+            // The log keeps the last two updates, the first one is folded in the digest
+            let kinds = asseterc721.asset_updates_recent(1, 0, 10).0.iter().map(|entry| entry.kind).collect::<Vec<_>>();
+            assert_eq!(kinds, [UpdateKind::Quantity, UpdateKind::Tag]);
+            let oldest = UpdateEntry {
+                from: accounts.alice,
+                kind: UpdateKind::Quantity,
+                block: 0,
             };
-            // emit event to report the update
-            self.env().emit_event(AssetUpdate {
-                from: caller,
-                id,
-            });
-            Ok(())
+            assert_eq!(asseterc721.asset_updates_digest(1), UpdateDigest::default().fold(&oldest));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.config_update_log_size_set(10), Err(Error::NotAdministrator));
         }
-        /// Returns the validation account of an asset
-        #[ink(message)]
-        pub fn asset_validation_get(&self, id: AssetId) ->  Option<AccountId>{
-           self.asset_validation.get(&id).cloned()
+
+        #[ink::test]
+        fn deposit_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>().expect("Cannot get contract");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            // Charlie tops up the storage deposit of asset Id 1 of Alice
+            set_sender_value(accounts.charlie, 0);
+            assert_eq!(asseterc721.deposit_top_up(1), Err(Error::InvalidPayment));
+            set_sender_value(accounts.charlie, 30);
+            assert_eq!(asseterc721.deposit_top_up(2), Err(Error::AssetNotFound));
+            assert_eq!(asseterc721.deposit_top_up(1), Ok(30));
+            assert_eq!(asseterc721.deposit_top_up(1), Ok(60));
+            assert_eq!(asseterc721.deposit_get(1), 60);
+            // The deposit is reclaimed once the asset is deleted
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.deposit_reclaim(1), Err(Error::DepositNotFound));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_delete(1), Ok(()));
+            assert_eq!(asseterc721.deposit_get(1), 0);
+            assert_eq!(asseterc721.deposit_reclaimable(1, accounts.charlie), 60);
+            set_sender(accounts.charlie);
+            assert_eq!(test::set_account_balance::<Environment>(contract, 60), Ok(()));
+            let balance = test::get_account_balance::<Environment>(accounts.charlie).expect("Cannot get balance");
+            assert_eq!(asseterc721.deposit_reclaim(1), Ok(60));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.charlie), Ok(balance + 60));
+            assert_eq!(asseterc721.deposit_reclaim(1), Err(Error::DepositNotFound));
         }
-        /// Verify if there is a validation stored for an asset id
-        #[ink(message)]
-        pub fn asset_validation_verify(&self, id: AssetId) -> bool{
-            self.asset_validation.contains_key(&id)
+
+        #[ink::test]
+        fn mint_deposit_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>().expect("Cannot get contract");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.config_mint_deposit_set(100), Ok(()));
+            // Bob creates asset Id 1 paying the deposit
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.config_mint_deposit_set(0), Err(Error::NotAdministrator));
+            set_sender_value(accounts.bob, 50);
+            assert_eq!(asseterc721.asset_new(1), Err(Error::InvalidPayment));
+            assert!(!asseterc721.asset_verify(1));
+            set_sender_value(accounts.bob, 100);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_provenance_get(1).map(|mint| mint.deposit), Some(100));
+            // The deposit stays with Bob when the asset is transferred, he reclaims it once the asset is deleted
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_transfer(accounts.charlie, 1), Ok(()));
+            assert_eq!(asseterc721.deposit_reclaim(1), Err(Error::DepositNotFound));
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.asset_delete(1), Ok(()));
+            assert_eq!(asseterc721.deposit_reclaimable(1, accounts.bob), 100);
+            set_sender(accounts.bob);
+            assert_eq!(test::set_account_balance::<Environment>(contract, 100), Ok(()));
+            assert_eq!(asseterc721.deposit_reclaim(1), Ok(100));
         }
-        /// Remove the validation of an asset id, only an administrator can do it
-        #[ink(message)]
-        pub fn asset_validation_delete(&mut self,  id: AssetId) -> Result<(), Error> {
-            let caller = self.env().caller();
-            // check for administrator 
-            let administrator=AssetErc721::administrator_accountid().unwrap();
-            if administrator != caller && self.account_role_get(caller).unwrap()!=5{
-                return Err(Error::NotAdministrator)
-            }
-            //check if asset id is present in the storage
-            let _asset = match self.asset_owner.entry(id) {
-                Entry::Vacant(_) => return Err(Error::AssetNotFound),
-                Entry::Occupied(asset) => asset,
-            };
-            // search for validation
-            let assetvalidation = match self.asset_validation.entry(id) {
-                Entry::Vacant(_) => return Err(Error::AssetNotFound),
-                Entry::Occupied(assetvalidation) => assetvalidation,
-            };
-            // remove validation
-            assetvalidation.remove_entry();
-            // emits event for asset updated
-            self.env().emit_event(AssetUpdate {
-                from: caller,
-                id,
-            });
-            Ok(())
+
+        #[ink::test]
+        fn transfer_fee_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>().expect("Cannot get contract");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            // The treasury takes 2% of the sales, the referrers 5% of the rest
+            assert_eq!(asseterc721.config_transfer_fee_set(treasury::FEE_MAX + 1), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.config_transfer_fee_set(200), Ok(()));
+            assert_eq!(asseterc721.config_referral_share_set(500), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.asset_listing_new(2, 1_000, None, None), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_listing_new(1, 1_000, None, None), Ok(()));
+            // Charlie buys asset Id 1 from Bob referred from Eve
+            assert_eq!(test::set_account_balance::<Environment>(contract, 1_000), Ok(()));
+            let balance = test::get_account_balance::<Environment>(accounts.bob).expect("Cannot get balance");
+            set_sender_value(accounts.charlie, 1_000);
+            assert_eq!(asseterc721.asset_purchase(1, 1_000, Some(accounts.eve), None), Ok(()));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.bob), Ok(balance + 931));
+            assert_eq!(asseterc721.asset_sales(1, 0, 10).0[0].fee, 20);
+            assert_eq!(asseterc721.asset_sales(1, 0, 10).0[0].referral, 49);
+            assert_eq!(asseterc721.treasury_balance(None), 20);
+            // The sales of the administrators are exempt
+            assert_eq!(test::set_account_balance::<Environment>(contract, 1_020), Ok(()));
+            assert_eq!(asseterc721.asset_purchase(2, 1_000, None, None), Ok(()));
+            assert_eq!(asseterc721.asset_sales(2, 0, 10).0[0].fee, 0);
+            assert_eq!(asseterc721.treasury_balance(None), 20);
+            // Only administrators withdraw the fees
+            assert_eq!(asseterc721.treasury_withdraw_propose(None, accounts.charlie, 20), Err(Error::NotAdministrator));
         }
-        /// Add a category description, you can store categories for an asset that are not yet stored here.
-        #[ink(message)]
-        pub fn category_description_new(&mut self,  id: u32, description: Hash) -> Result<(), Error> {
-            let caller = self.env().caller();
-            // check for administrator 
-            let administrator=AssetErc721::administrator_accountid().unwrap();
-            if administrator != caller && self.account_role_get(caller).unwrap()!=5{
-                return Err(Error::NotAdministrator)
-            }
-            // search for category description storage
-            let _category_description = match self.asset_category_description.entry(id) {
-                Entry::Vacant(_) => "",
-                Entry::Occupied(_category_description) => return Err(Error::DuplicatedData),
-            };
-            // add category description if not already present
-            if self.asset_category_description.insert(id, description).is_some() {
-                return Err(Error::CannotInsert)
+
+        #[ink::test]
+        fn charity_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>().expect("Cannot get contract");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            let charity = Charity {
+                account: accounts.eve,
+                share: 1_000,
             };
-            Ok(())
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_listing_charity_set(1, Some(charity)), Err(Error::ListingNotFound));
+            assert_eq!(asseterc721.asset_listing_new(1, 1_000, None, None), Ok(()));
+            // Alice donates 10% of her proceeds to Eve, the buyers read it on the listing
+            assert_eq!(asseterc721.asset_listing_charity_set(1, Some(charity)), Ok(()));
+            assert_eq!(asseterc721.asset_listing_get(1).and_then(|listing| listing.charity), Some(charity));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_listing_charity_set(1, None), Err(Error::NotOwner));
+            // Bob buys the asset
+            assert_eq!(test::set_account_balance::<Environment>(contract, 1_000), Ok(()));
+            let balance = test::get_account_balance::<Environment>(accounts.alice).expect("Cannot get balance");
+            let charity_balance = test::get_account_balance::<Environment>(accounts.eve).expect("Cannot get balance");
+            set_sender_value(accounts.bob, 1_000);
+            assert_eq!(asseterc721.asset_purchase(1, 1_000, None, None), Ok(()));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.alice), Ok(balance + 900));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.eve), Ok(charity_balance + 100));
+            let sale = asseterc721.asset_sales(1, 0, 10).0[0];
+            assert_eq!((sale.charity, sale.donation), (Some(accounts.eve), 100));
         }
-        /// Returns the description of an asset category 
-        #[ink(message)]
-        pub fn category_description_get(&self, id: AssetId) ->  Option<Hash>{
-           self.asset_category_description.get(&id).cloned()
+
+        #[ink::test]
+        fn treasury_withdrawal_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>().expect("Cannot get contract");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new_with_administrators([accounts.alice, accounts.bob, accounts.eve].to_vec());
+            asseterc721.treasury.deposit(None, 50);
+            assert_eq!(asseterc721.config_treasury_threshold_set(1), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.config_treasury_threshold_set(4), Err(Error::NotAllowed));
+            // Alice proposes to withdraw 20 to Django, her approval alone is not enough
+            assert_eq!(asseterc721.treasury_withdraw_propose(None, accounts.django, 51), Err(Error::InsufficientBalance));
+            assert_eq!(asseterc721.treasury_withdraw_propose(None, accounts.django, 20), Ok(0));
+            assert_eq!(asseterc721.treasury_withdraw_execute(0), Err(Error::NotApproved));
+            assert_eq!(asseterc721.treasury_withdraw_approve(0), Err(Error::DuplicatedData));
+            // Bob approves, but he is not an administrator anymore at the execution
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.treasury_withdraw_approve(0), Ok(2));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.admin_remove(accounts.bob), Ok(()));
+            assert_eq!(asseterc721.treasury_withdraw_execute(0), Err(Error::NotApproved));
+            // Eve approves and executes the withdrawal
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.treasury_withdraw_approve(0), Ok(3));
+            assert_eq!(test::set_account_balance::<Environment>(contract, 50), Ok(()));
+            let balance = test::get_account_balance::<Environment>(accounts.django).expect("Cannot get balance");
+            assert_eq!(asseterc721.treasury_withdraw_execute(0), Ok(()));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.django), Ok(balance + 20));
+            assert_eq!(asseterc721.treasury_balance(None), 30);
+            assert_eq!(asseterc721.treasury_withdraw_execute(0), Err(Error::WithdrawalNotFound));
+            // The trail keeps the approvals of the withdrawal
+            let withdrawal = asseterc721.treasury_withdrawal_get(0).expect("Cannot get withdrawal");
+            assert_eq!(withdrawal.approvals, [accounts.alice, accounts.bob, accounts.eve]);
+            assert_eq!(withdrawal.executed, Some(0));
+            assert_eq!(asseterc721.treasury_withdrawals_count(), 1);
         }
-        /// Verifies if there is a category description stored, returns true/false
-        #[ink(message)]
-        pub fn category_description_verify(&self, id: u32) -> bool{
-            self.asset_category_description.contains_key(&id)
+
+        #[ink::test]
+        fn treasury_threshold_needs_administrators() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new_with_administrators([accounts.alice, accounts.bob].to_vec());
+            asseterc721.treasury.deposit(None, 50);
+            assert_eq!(asseterc721.config_treasury_threshold_set(2), Ok(()));
+            // the set cannot shrink below the threshold
+            assert_eq!(asseterc721.admin_remove(accounts.bob), Err(Error::NotAllowed));
+            // Alice gives the Administrator role to accounts of her own, they cannot approve the withdrawal
+            assert_eq!(asseterc721.account_role_new(accounts.charlie, 5), Ok(()));
+            assert_eq!(asseterc721.account_role_new(accounts.django, 5), Ok(()));
+            assert_eq!(asseterc721.treasury_withdraw_propose(None, accounts.eve, 20), Ok(0));
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.treasury_withdraw_approve(0), Err(Error::NotAdministrator));
+            assert_eq!(asseterc721.treasury_withdraw_propose(None, accounts.eve, 20), Err(Error::NotAdministrator));
+            set_sender(accounts.django);
+            assert_eq!(asseterc721.treasury_withdraw_approve(0), Err(Error::NotAdministrator));
+            assert_eq!(asseterc721.treasury_withdraw_execute(0), Err(Error::NotApproved));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.treasury_withdraw_execute(0), Err(Error::NotApproved));
+            assert_eq!(asseterc721.treasury_balance(None), 50);
         }
-        /// Removes the metadata of an asset id, only the owner can do it
-        #[ink(message)]
-        pub fn category_description_delete(&mut self,  id: u32) -> Result<(), Error> {
-            let caller = self.env().caller();
-            // check for administrator 
-            let administrator=AssetErc721::administrator_accountid().unwrap();
-            if administrator != caller && self.account_role_get(caller).unwrap()!=5{
-                return Err(Error::NotAdministrator)
+
+        #[ink::test]
+        fn inspector_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.account_role_new(accounts.bob, INSPECTOR), Ok(()));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.asset_new(3), Ok(()));
+            assert_eq!(asseterc721.category_description_new(10, Hash::from([0x01; 32])), Ok(()));
+            assert_eq!(asseterc721.category_description_new(20, Hash::from([0x02; 32])), Ok(()));
+            assert_eq!(asseterc721.asset_category_new(1, 10), Ok(()));
+            assert_eq!(asseterc721.asset_category_new(2, 20), Ok(()));
+            // Bob validates only the categories of his accreditation
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.inspector_accredit(accounts.bob, ink_prelude::vec![10], None), Err(Error::NotAdministrator));
+            assert_eq!(asseterc721.asset_validation_new(1, accounts.bob), Err(Error::NotAccredited));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.inspector_accredit(accounts.bob, Vec::new(), None), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.inspector_accredit(accounts.bob, ink_prelude::vec![10], None), Ok(()));
+            assert_eq!(asseterc721.inspector_get(accounts.bob).map(|accreditation| accreditation.issuer), Some(accounts.alice));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_validation_new(1, accounts.bob), Ok(()));
+            assert_eq!(asseterc721.asset_validation_new(2, accounts.bob), Err(Error::NotAccredited));
+            assert_eq!(asseterc721.asset_validation_new(3, accounts.bob), Err(Error::NotAccredited));
+            // the validation keeps the inspector that signed it
+            assert_eq!(asseterc721.asset_validation_signer_get(1), Some(accounts.bob));
+            // the administrators are not restricted to a scope
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_validation_new(2, accounts.alice), Ok(()));
+            assert_eq!(asseterc721.asset_validation_signer_get(2), Some(accounts.alice));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_validation_delete(2), Err(Error::NotAccredited));
+            // a revoked inspector cannot validate anymore
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.inspector_revoke(accounts.bob), Ok(()));
+            assert_eq!(asseterc721.inspector_revoke(accounts.bob), Err(Error::NotAccredited));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_validation_delete(1), Err(Error::NotAccredited));
+        }
+
+        #[ink::test]
+        fn inspector_expiry_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.account_role_new(accounts.bob, INSPECTOR), Ok(()));
+            assert_eq!(asseterc721.account_role_new(accounts.charlie, INSPECTOR), Ok(()));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.category_description_new(10, Hash::from([0x01; 32])), Ok(()));
+            assert_eq!(asseterc721.asset_category_new(1, 10), Ok(()));
+            // Bob is accredited for 2 blocks, Charlie with no expiry
+            assert_eq!(asseterc721.inspector_accredit(accounts.bob, ink_prelude::vec![10], Some(0)), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.inspector_accredit(accounts.bob, ink_prelude::vec![10], Some(2)), Ok(()));
+            assert_eq!(asseterc721.inspector_accredit(accounts.charlie, ink_prelude::vec![10, 20], None), Ok(()));
+            assert_eq!(asseterc721.category_inspectors(10, 0, 10), (ink_prelude::vec![accounts.bob, accounts.charlie], None));
+            for _ in 0..2 {
+                ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot advance block");
             }
-            //check if the category is present
-            let category = match self.asset_category_description.entry(id) {
-                Entry::Vacant(_) => return Err(Error::CategoryNotFound),
-                Entry::Occupied(category) => category,
-            };
-            // remove category
-            category.remove_entry();
-            Ok(())
+            // the lapsed accreditation does not validate and is not listed
+            assert_eq!(asseterc721.category_inspectors(10, 0, 10), (ink_prelude::vec![accounts.charlie], None));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_validation_new(1, accounts.bob), Err(Error::AccreditationLapsed));
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.asset_validation_new(1, accounts.charlie), Ok(()));
+            assert_eq!(asseterc721.inspector_get(accounts.bob).and_then(|accreditation| accreditation.expiry), Some(2));
         }
-        /// Deletes an existing asset. Only the owner can do it
-        #[ink(message)]
-        pub fn asset_delete(&mut self, id: AssetId) -> Result<(), Error> {
-            let caller = self.env().caller();
-            let Self {
-                asset_owner,
-                account_owned_assets,
-                ..
-            } = self;
-            // check if asset id is store
-            let occupied = match asset_owner.entry(id) {
-                Entry::Vacant(_) => return Err(Error::AssetNotFound),
-                Entry::Occupied(occupied) => occupied,
-            };
-            // check if the assets belongs to signer
-            if occupied.get() != &caller {
-                return Err(Error::NotOwner)
+
+        /// Returns the storage reads and writes of `call` on the contract loaded from the storage at `root`, and
+        /// stores the contract back like at the end of a contract call
+        fn storage_access<F: FnOnce(&mut AssetErc721)>(root: &ink_primitives::Key, call: F) -> (usize, usize) {
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>().expect("Cannot get contract");
+            let before = test::get_contract_storage_rw::<Environment>(&contract).expect("Cannot get storage");
+            // the contract is not dropped, dropping the storage collections clears their cells
+            let mut asseterc721: core::mem::ManuallyDrop<AssetErc721> = core::mem::ManuallyDrop::new(ink_storage::traits::pull_spread_root(root));
+            call(&mut asseterc721);
+            ink_storage::traits::push_spread_root(&*asseterc721, root);
+            let after = test::get_contract_storage_rw::<Environment>(&contract).expect("Cannot get storage");
+            (after.0 - before.0, after.1 - before.1)
+        }
+
+        #[ink::test]
+        fn asset_import_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let photo = Hash::from([0x01; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Bob owns asset Id 1 with the same photo in the new deployment
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_photo_new(1, photo), Ok(()));
+            let info = AssetInfo {
+                owner: accounts.charlie,
+                description: Some(Hash::from([0x02; 32])),
+                photo: Some(photo),
+                category: Some(7),
+                location: None,
+                metadata: None,
+                validation: Some(accounts.eve),
+                delegated: Some(accounts.django),
+                physical: None,
             };
-            //decreate counter assets owned
-            decrease_counter_of(account_owned_assets, &caller)?;
-            // remove asset
-            occupied.remove_entry();
-            self.env().emit_event(Transfer {
-                from: Some(caller),
-                to: Some(AccountId::from([0x0; 32])),
-                id,
+            assert_eq!(asseterc721.asset_import(2, info.clone()), Err(Error::NotAdministrator));
+            // Alice imports asset Id 2 of Charlie from the previous deployment
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.asset_import(2, info.clone()), Err(Error::CategoryNotFound));
+            assert_eq!(asseterc721.category_description_new(7, Hash::from([0x07; 32])), Ok(()));
+            assert_eq!(asseterc721.asset_import(1, info.clone()), Err(Error::AssetExists));
+            assert_eq!(asseterc721.asset_import(2, info.clone()), Ok(()));
+            assert_eq!(asseterc721.asset_info_get(2), Some(info.clone()));
+            assert_eq!(asseterc721.category_assets_count(7), 1);
+            assert!(asseterc721.asset_duplicate_verify(2));
+            // the imports cannot be opened again
+            assert_eq!(asseterc721.asset_import_close(), Ok(()));
+            assert_eq!(asseterc721.asset_import(3, info), Err(Error::NotAllowed));
+            // the imported asset is managed like the others
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 2), Ok(()));
+            assert_eq!(asseterc721.asset_get_delegated_account(2), None);
+        }
+
+        #[ink::test]
+        fn transfer_storage_access() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let root = ink_primitives::Key::from([0x00; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.account_delegate_for_all_asset(accounts.charlie, true), Ok(()));
+            ink_storage::traits::push_spread_root(&asseterc721, &root);
+            // Loading and storing the contract is the same for every call
+            let (load_reads, load_writes) = storage_access(&root, |_| {});
+            assert!(load_reads <= 103);
+            let (reads, writes) = storage_access(&root, |asseterc721| {
+                assert_eq!(asseterc721.asset_transfer(accounts.bob, 1), Ok(()));
             });
-            Ok(())
-        } 
-        /// Writes new role operator, only administrator can do it
-        #[ink(message)]
-        pub fn account_role_new(&mut self,  accountid: AccountId, role: u32) -> Result<(), Error> {
-            let caller = self.env().caller();
-            let administrator=AssetErc721::administrator_accountid().unwrap();
-            // check for administrator 
-            if administrator != caller && self.account_role_get(caller).unwrap()!=5{
-                return Err(Error::NotAdministrator)
-            }
-            // check fo valid role (0-9)
-            if role>5{
-                return Err(Error::CannotInsert)
-            }
-            // search for role in storage
-            let _operatorrole = match self.account_role.entry(accountid) {
-                Entry::Vacant(_) => 0,
-                Entry::Occupied(_operatorrole) => return Err(Error::DuplicatedData),
-            };
-            //store the role
-            if self.account_role.insert(accountid, role).is_some() {
-                return Err(Error::CannotInsert)
-            };
-            // emits event
-            self.env().emit_event(RoleUpdate {
-                from: caller,
-                id: accountid,
+            assert!(reads - load_reads <= 23 && writes - load_writes <= 18);
+            let (reads, writes) = storage_access(&root, |asseterc721| {
+                set_sender(accounts.charlie);
+                assert_eq!(asseterc721.transfer_from(accounts.alice, accounts.bob, 2), Ok(()));
             });
-            Ok(())
-        }
-        /// Returns the account role (0 = Producer, 1= Wholesaler, 2 = Retailer, 3 = Final Buyer, 4=Shipper, 5=Administrator)
-        #[ink(message)]
-        pub fn account_role_get(&self, accountid: AccountId) ->  Option<u32>{
-           self.account_role.get(&accountid).cloned()
+            assert!(reads - load_reads <= 24 && writes - load_writes <= 15);
         }
-         /// Verifies if there is a role stored for the operator
-         #[ink(message)]
-         pub fn account_role_verify(&self, accountid: AccountId) -> bool{
-             self.account_role.contains_key(&accountid)
-         }
-        /// Removes an operator role, only the Administrator can do it
-        #[ink(message)]
-        pub fn account_role_delete(&mut self,  accountid: AccountId) -> Result<(), Error> {
-            let caller = self.env().caller();
-            let administrator=AssetErc721::administrator_accountid().unwrap();
-            // check for administrator 
-            if administrator != caller && self.account_role_get(caller).unwrap()!=5{
-                return Err(Error::NotAdministrator)
+
+        #[ink::test]
+        fn owner_index_storage_access() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let root = ink_primitives::Key::from([0x00; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice owns 64 assets, 4 pages of her list
+            for id in 0..64 {
+                assert_eq!(asseterc721.asset_new(id), Ok(()));
             }
-            // search for role in storage
-            let operatorrole = match self.account_role.entry(accountid) {
-                Entry::Vacant(_) => return Err(Error::CannotRemove),
-                Entry::Occupied(operatorrole) => operatorrole,
-            };
-            // remove role
-            operatorrole.remove_entry();
-            self.env().emit_event(RoleUpdate {
-                from: caller,
-                id: accountid,
+            ink_storage::traits::push_spread_root(&asseterc721, &root);
+            let (load_reads, load_writes) = storage_access(&root, |_| {});
+            let (reads, _) = storage_access(&root, |asseterc721| {
+                assert_eq!(asseterc721.ownership.assets_of(&accounts.alice, 0, 64).len(), 64);
             });
-            Ok(())
+            assert!(reads - load_reads <= 5);
+            let (reads, writes) = storage_access(&root, |asseterc721| {
+                assert_eq!(asseterc721.asset_transfer(accounts.bob, 0), Ok(()));
+            });
+            // the position of the asset and the last asset are in different pages
+            assert!(reads - load_reads <= 24 && writes - load_writes <= 18);
         }
-        /// Returns the number of the assets owneed from an account
-        /// This represents the amount of unique assets the owner has.
-        #[ink(message)]
-        pub fn account_assets_number(&self, owner: AccountId) -> u32 {
-            self.account_assets_number_or_zero(&owner)
+
+        #[ink::test]
+        fn error_code_works() {
+            let asseterc721 = AssetErc721::new();
+            // The codes are pinned, whatever the position of the variants in the enum
+            assert_eq!(asseterc721.error_code(Error::NotOwner), 1);
+            assert_eq!(asseterc721.error_code(Error::AssetNotFound), 5);
+            assert_eq!(asseterc721.error_code(Error::DepositNotFound), 66);
+            assert_eq!(asseterc721.error_code(Error::NotAccredited), 68);
+            assert_eq!(asseterc721.error_code(Error::AccreditationLapsed), 69);
+            assert_eq!(asseterc721.error_code(Error::LicenseNotFound), 71);
+            assert_eq!(asseterc721.error_code(Error::LockedAfterValidation), 72);
+            assert_eq!(asseterc721.error_code(Error::SaleNotFound), 73);
+            assert_eq!(asseterc721.error_code(Error::PriceNotFound), 74);
+            assert_eq!(asseterc721.error_code(Error::JurisdictionNotFound), 75);
+            assert_eq!(asseterc721.error_code(Error::RestrictedInJurisdiction), 76);
+            assert_eq!(asseterc721.error_code(Error::AssetReturned), 77);
+            assert_eq!(asseterc721.error_code(Error::AssetDisposed), 78);
+            assert_eq!(asseterc721.error_code(Error::NotRecycler), 79);
+            assert_eq!(asseterc721.error_code(Error::AssetNotDisposed), 80);
+            assert_eq!(asseterc721.error_code(Error::MaterialsClaimed), 81);
+            assert_eq!(asseterc721.error_code(Error::AssetRetired), 82);
+            assert_eq!(asseterc721.error_code(Error::ProductNotFound), 83);
+            assert_eq!(asseterc721.error_code(Error::VariantNotFound), 84);
+            assert_eq!(asseterc721.error_code(Error::InvalidSku), 85);
+            assert_eq!(asseterc721.error_code(Error::SkuTaken), 86);
+            assert_eq!(asseterc721.error_code(Error::SkuNotFound), 87);
+            assert_eq!(asseterc721.error_code(Error::AssetListed), 91);
+            assert_eq!(asseterc721.error_code(Error::UnsafeRecipient), 92);
+            assert_eq!(asseterc721.error_code(Error::AssetReserved), 93);
+            assert_eq!(asseterc721.error_code(Error::ReservationNotFound), 94);
+            assert_eq!(asseterc721.error_code(Error::NotAuditor), 95);
+            assert_eq!(asseterc721.error_code(Error::TransferPending), 96);
+            assert_eq!(asseterc721.error_code(Error::PendingTransferNotFound), 97);
+            assert_eq!(asseterc721.error_code(Error::GracePeriodRunning), 98);
+            assert_eq!(asseterc721.error_code(Error::NotValidated), 99);
+            // The code is read from the error as encoded in the result of a call
+            let result: Result<(), Error> = Err(Error::LienNotFound);
+            let encoded = result.encode();
+            let decoded = <Result<(), Error>>::decode(&mut &encoded[..]).expect("Cannot decode");
+            assert_eq!(decoded.map_err(|error| asseterc721.error_code(error)), Err(60));
+        }
+
+        #[ink::test]
+        fn shipment_manifest_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let seal = Hash::from([0x01; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice creates asset Id 1 and 2, asset Id 2 has 40 kilograms of coffee
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.asset_quantity_set(2, 40, Unit::Kilogram), Ok(()));
+            assert_eq!(asseterc721.shipment_manifest_create(accounts.bob, Vec::new()), Err(Error::InvalidManifest));
+            assert_eq!(asseterc721.shipment_manifest_create(accounts.bob, vec![(1, seal), (3, seal)]), Err(Error::AssetNotFound));
+            assert_eq!(asseterc721.shipment_manifest_create(accounts.bob, vec![(1, seal), (2, seal)]), Ok(0));
+            let manifest = asseterc721.shipment_manifest_get(0).expect("Manifest not found");
+            assert_eq!(manifest.sender, accounts.alice);
+            assert_eq!(manifest.receiver, accounts.bob);
+            assert_eq!(manifest.items[1].quantity, Some(Quantity { amount: 40, unit: Unit::Kilogram }));
+            assert_eq!(asseterc721.shipment_manifest_hash(0), Some(manifest.hash()));
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            let decoded = <Event as scale::Decode>::decode(&mut &emitted_events.last().unwrap().data[..]);
+            assert!(matches!(decoded, Ok(Event::ManifestCreated(ManifestCreated { manifest_id: 0, .. }))));
+            // Bob cannot list Alice's assets
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.shipment_manifest_create(accounts.eve, vec![(1, seal)]), Err(Error::NotAllowed));
+        }
+
+        #[ink::test]
+        fn shipment_freight_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let seal = Hash::from([0x01; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator, Eve is a shipper, Bob receives the shipment
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.account_role_new(accounts.eve, 4), Ok(()));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.shipment_manifest_create(accounts.bob, vec![(1, seal)]), Ok(0));
+            // Alice agrees 100 with Eve, the cost must be transferred with the call
+            set_sender_value(accounts.alice, 90);
+            assert_eq!(asseterc721.shipment_freight_agree(0, accounts.eve, 100, None, None), Err(Error::InvalidPayment));
+            assert_eq!(asseterc721.shipment_freight_get(0), None);
+            set_sender_value(accounts.alice, 100);
+            assert_eq!(asseterc721.shipment_freight_agree(0, accounts.bob, 100, None, None), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.shipment_freight_agree(0, accounts.eve, 100, None, None), Ok(()));
+            assert_eq!(asseterc721.shipment_freight_agree(0, accounts.eve, 100, None, None), Err(Error::DuplicatedData));
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>().expect("Cannot get contract");
+            assert_eq!(test::set_account_balance::<Environment>(contract, 100), Ok(()));
+            // Only the receiver confirms the delivery
+            assert_eq!(asseterc721.shipment_delivery_confirm(0), Err(Error::NotAllowed));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.shipment_delivery_confirm(0), Ok(()));
+            assert_eq!(asseterc721.shipment_delivery_confirm(0), Err(Error::DuplicatedData));
+            assert_eq!(asseterc721.shipment_delivery_get(0), Some(0));
+            assert_eq!(asseterc721.shipment_freight_get(0).map(|freight| freight.settled), Some(true));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.eve), Ok(100));
         }
 
-        /// Returns the deletegated account ID for this asset if any.
-        #[ink(message)]
-        pub fn asset_get_delegated_account(&self, id: AssetId) -> Option<AccountId> {
-            self.asset_proxy.get(&id).cloned()    
-        }
-        /// Delegate or undelegate an account to manage all the asset on behalf of the caller
-        #[ink(message)]
-        pub fn account_delegate_for_all_asset(&mut self,to: AccountId,approved: bool,) -> Result<(), Error> {
-            self.proxy_for_all_assets(to, approved)?;
-            Ok(())
+        #[ink::test]
+        fn asset_purchase_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>().expect("Cannot get contract");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            // A price in euro needs the oracle of the euro
+            assert_eq!(asseterc721.asset_listing_new(1, 100, Some(*b"EUR"), None), Err(Error::OracleNotFound));
+            assert_eq!(asseterc721.price_oracle_set(*b"EUR", Some(accounts.django)), Ok(()));
+            assert_eq!(asseterc721.price_oracle_get(*b"EUR"), Some(accounts.django));
+            assert_eq!(asseterc721.asset_listing_new(2, 100, Some(*b"EUR"), None), Ok(()));
+            // Alice lists asset Id 1 for 100 native tokens
+            assert_eq!(asseterc721.asset_listing_new(1, 100, None, None), Ok(()));
+            assert_eq!(asseterc721.asset_listing_new(1, 100, None, None), Err(Error::DuplicatedData));
+            assert_eq!(asseterc721.asset_listing_quote(1), Ok((100, None)));
+            assert_eq!(asseterc721.asset_purchase(1, 100, None, None), Err(Error::NotAllowed));
+            // Bob cannot buy it for less than the price
+            set_sender_value(accounts.bob, 50);
+            assert_eq!(asseterc721.asset_purchase(1, 50, None, None), Err(Error::PriceExceeded));
+            assert_eq!(asseterc721.asset_listing_cancel(1), Err(Error::NotOwner));
+            // Bob buys it sending 120, the excess is refunded
+            set_sender_value(accounts.bob, 120);
+            let balance = test::get_account_balance::<Environment>(accounts.alice).expect("Cannot get balance");
+            // the sale fails before paying the seller when the excess cannot be refunded
+            assert_eq!(test::set_account_balance::<Environment>(contract, 10), Ok(()));
+            assert_eq!(asseterc721.asset_purchase(1, 120, None, None), Err(Error::PaymentFailed));
+            assert_eq!(asseterc721.asset_get_owner(1), Some(accounts.alice));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.alice), Ok(balance));
+            assert!(asseterc721.asset_listing_get(1).is_some());
+            assert_eq!(test::set_account_balance::<Environment>(contract, 120), Ok(()));
+            assert_eq!(asseterc721.asset_purchase(1, 120, None, None), Ok(()));
+            assert_eq!(asseterc721.asset_get_owner(1), Some(accounts.bob));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.alice), Ok(balance + 100));
+            assert_eq!(test::get_account_balance::<Environment>(contract), Ok(0));
+            assert_eq!(asseterc721.asset_listing_get(1), None);
+            assert_eq!(asseterc721.asset_sales(1, 0, 10).0.iter().map(|sale| (sale.buyer, sale.amount, sale.rate)).collect::<Vec<_>>(), [(accounts.bob, 100, None)]);
+            assert_eq!(asseterc721.asset_purchase(1, 120, None, None), Err(Error::ListingNotFound));
+            // A transfer drops the listing
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_transfer(accounts.eve, 2), Ok(()));
+            assert_eq!(asseterc721.asset_listing_get(2), None);
+            // Only administrators can register the oracles
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.price_oracle_set(*b"EUR", None), Err(Error::NotAdministrator));
         }
-        /// Returns `true` if the operator is approved by the owner to manage any asset.
-        #[ink(message)]
-        pub fn account_verify_delegated_for_all_asset(&self, owner: AccountId, operator: AccountId) -> bool {
-            self.check_proxy_for_all(owner, operator)
+
+        #[ink::test]
+        fn thread_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>().expect("Cannot get contract");
+            let message = Hash::from([0x01; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            // Bob sells asset Id 1 validated from Alice to Charlie
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_listing_new(1, 100, None, None), Ok(()));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_validation_new(1, accounts.alice), Ok(()));
+            set_sender_value(accounts.charlie, 100);
+            assert_eq!(test::set_account_balance::<Environment>(contract, 100), Ok(()));
+            assert_eq!(asseterc721.asset_purchase(1, 100, None, None), Ok(()));
+            // The seller and the buyer write in the thread of the sale, the others do not
+            assert_eq!(asseterc721.thread_post(Thread::Sale(1, 0), message), Ok(()));
+            assert_eq!(asseterc721.thread_post(Thread::Sale(1, 1), message), Err(Error::SaleNotFound));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.thread_post(Thread::Sale(1, 0), message), Ok(()));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.thread_post(Thread::Sale(1, 0), message), Err(Error::NotAllowed));
+            assert_eq!(
+                asseterc721.thread_posts(Thread::Sale(1, 0), 0, 10).0.iter().map(|post| post.author).collect::<Vec<_>>(),
+                [accounts.charlie, accounts.bob]
+            );
+            // The parties of a dispute write in its thread until it is resolved
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.thread_post(Thread::Dispute(0), message), Err(Error::DisputeNotFound));
+            assert_eq!(asseterc721.validation_dispute_open(1, message), Ok(0));
+            assert_eq!(asseterc721.thread_post(Thread::Dispute(0), message), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.thread_post(Thread::Dispute(0), message), Err(Error::NotAllowed));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.thread_post(Thread::Dispute(0), message), Ok(()));
+            assert_eq!(asseterc721.thread_posts_count(Thread::Dispute(0)), 2);
+            assert_eq!(asseterc721.dispute_resolve(0, false), Ok(()));
+            assert_eq!(asseterc721.thread_post(Thread::Dispute(0), message), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.thread_posts(Thread::Dispute(0), 1, 10).0.len(), 1);
         }
-        /// Delegate an account to transfer the specified asset on behalf of the caller.
-        #[ink(message)]
-        pub fn account_delegate_single_asset(&mut self, to: AccountId, id: AssetId) -> Result<(), Error> {
-            self.delegate_for_single_asset(&to, id)?;
-            Ok(())
+
+        #[ink::test]
+        fn price_list_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.account_role_new(accounts.bob, PRODUCER), Ok(()));
+            assert_eq!(asseterc721.category_description_new(10, Hash::from([0x01; 32])), Ok(()));
+            // Bob publishes the price of category 10 and schedules a raise at block 5
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.price_list_set(20, 100, None, 0), Err(Error::CategoryNotFound));
+            assert_eq!(asseterc721.price_list_set(10, 100, None, 0), Ok(()));
+            assert_eq!(asseterc721.price_list_set(10, 120, None, 5), Ok(()));
+            assert_eq!(asseterc721.price_list_set(10, 110, None, 3), Err(Error::NotAllowed));
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.price_list_set(10, 90, None, 0), Err(Error::NotProducer));
+            assert_eq!(asseterc721.price_list_get(accounts.bob, 10).map(|entry| entry.unit_price), Some(100));
+            assert_eq!(asseterc721.price_list_history(accounts.bob, 10, 0, 10).0.len(), 2);
+            // The listing refers to the price in force
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.asset_listing_new_at_list_price(1, None), Err(Error::PriceNotFound));
+            assert_eq!(asseterc721.asset_category_new(1, 10), Ok(()));
+            assert_eq!(asseterc721.asset_category_new(2, 10), Ok(()));
+            assert_eq!(asseterc721.asset_listing_new_at_list_price(1, None), Ok(()));
+            assert_eq!(asseterc721.asset_listing_get(1).map(|listing| listing.price), Some(100));
+            for _ in 0..5 {
+                ink_env::test::advance_block::<ink_env::DefaultEnvironment>().expect("Cannot advance block");
+            }
+            assert_eq!(asseterc721.asset_listing_new_at_list_price(2, None), Ok(()));
+            assert_eq!(asseterc721.asset_listing_get(2).map(|listing| listing.price), Some(120));
         }
-        /// Transfers the asset from the caller to a different account.
-        #[ink(message)]
-        pub fn asset_transfer(
-            &mut self,
-            destination: AccountId,
-            id: AssetId,
-        ) -> Result<(), Error> {
-            let caller = self.env().caller();
-            self.asset_transfer_from(&caller, &destination, id)?;
-            Ok(())
+
+        #[ink::test]
+        fn asset_pricing_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>().expect("Cannot get contract");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Bob suggests the retail price of asset Id 1 he created
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.asset_msrp_set(1, 150, None), Ok(()));
+            assert_eq!(asseterc721.asset_msrp_set(1, 100, None), Err(Error::DuplicatedData));
+            // Only the producer records the price, while owning the asset
+            assert_eq!(asseterc721.asset_transfer(accounts.charlie, 2), Ok(()));
+            assert_eq!(asseterc721.asset_msrp_set(2, 150, None), Err(Error::NotOwner));
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.asset_msrp_set(2, 150, None), Err(Error::NotProducer));
+            // The realized price is recorded at the sale
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_listing_new(1, 100, None, None), Ok(()));
+            set_sender_value(accounts.django, 100);
+            assert_eq!(test::set_account_balance::<Environment>(contract, 100), Ok(()));
+            assert_eq!(asseterc721.asset_purchase(1, 100, None, None), Ok(()));
+            let pricing = asseterc721.asset_pricing_get(1);
+            assert_eq!(pricing.msrp.map(|msrp| msrp.price), Some(150));
+            assert_eq!((pricing.realized, pricing.currency, pricing.sales), (Some(100), None, 1));
+            assert_eq!(asseterc721.asset_pricing_get(2), Pricing { msrp: None, realized: None, currency: None, sales: 0 });
         }
 
-        /// Transfer approved of owned asset.
-        #[ink(message)]
-        pub fn transfer_from(
-            &mut self,
-            from: AccountId,
-            to: AccountId,
-            id: AssetId,
-        ) -> Result<(), Error> {
-            self.asset_transfer_from(&from, &to, id)?;
-            Ok(())
+        #[ink::test]
+        fn sale_tax_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>().expect("Cannot get contract");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.category_description_new(10, Hash::from([0x01; 32])), Ok(()));
+            assert_eq!(asseterc721.jurisdiction_set(*b"IT", Hash::from([0x02; 32]), None), Ok(()));
+            // Italy taxes the sales at 10%, and the assets of category 10 at 25% collected from Eve
+            let collected = TaxRate { rate: 2_500, collector: Some(accounts.eve) };
+            assert_eq!(asseterc721.tax_rate_set(*b"IT", None, Some(TaxRate { rate: 10_001, collector: None })), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.tax_rate_set(*b"IT", None, Some(TaxRate { rate: 1_000, collector: None })), Ok(()));
+            assert_eq!(asseterc721.tax_rate_set(*b"IT", Some(10), Some(collected)), Ok(()));
+            assert_eq!(asseterc721.tax_rate_get(*b"IT", Some(10)), Some(collected));
+            assert_eq!(asseterc721.account_jurisdiction_set(accounts.bob, Some(*b"IT")), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.account_jurisdiction_set(accounts.bob, None), Err(Error::NotAdministrator));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.asset_category_new(1, 10), Ok(()));
+            assert_eq!(asseterc721.asset_listing_new(1, 125, None, None), Ok(()));
+            assert_eq!(asseterc721.asset_listing_new(2, 110, None, None), Ok(()));
+            // The tax included in the price of asset Id 1 is paid to Eve
+            let bob = test::get_account_balance::<Environment>(accounts.bob).expect("Cannot get balance");
+            let eve = test::get_account_balance::<Environment>(accounts.eve).expect("Cannot get balance");
+            set_sender_value(accounts.charlie, 125);
+            assert_eq!(test::set_account_balance::<Environment>(contract, 125), Ok(()));
+            assert_eq!(asseterc721.asset_purchase(1, 125, None, None), Ok(()));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.bob), Ok(bob + 100));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.eve), Ok(eve + 25));
+            let record = asseterc721.sale_tax_get(1, 0).expect("Tax record not found");
+            assert_eq!((record.jurisdiction, record.category, record.tax, record.collector), (*b"IT", Some(10), 25, Some(accounts.eve)));
+            // The tax of asset Id 2 is left to the seller
+            set_sender_value(accounts.charlie, 110);
+            assert_eq!(test::set_account_balance::<Environment>(contract, 110), Ok(()));
+            assert_eq!(asseterc721.asset_purchase(2, 110, None, None), Ok(()));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.bob), Ok(bob + 210));
+            assert_eq!(
+                asseterc721.tax_records(0, 10).0.iter().map(|record| (record.id, record.tax, record.collector)).collect::<Vec<_>>(),
+                [(1, 25, Some(accounts.eve)), (2, 10, None)]
+            );
         }
-        /// Transfers asset `id` `from` the sender to the `to` AccountId.
-        fn asset_transfer_from(
-            &mut self,
-            from: &AccountId,
-            to: &AccountId,
-            id: AssetId,
-        ) -> Result<(), Error> {
-            let caller = self.env().caller();
-            if !self.exists(id) {
-                return Err(Error::AssetNotFound)
-            };
-            if !self.approved_or_owner(Some(caller), id)  && self.account_role_get(caller).unwrap()!=5 {
-                return Err(Error::NotApproved)
-            };
-            self.clear_proxy_asset(id)?;
-            self.asset_remove_from(from, id)?;
-            self.add_asset_to(to, id)?;
-            self.env().emit_event(Transfer {
-                from: Some(*from),
-                to: Some(*to),
-                id,
-            });
-            Ok(())
+
+        #[ink::test]
+        fn jurisdiction_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let rules = Hash::from([0x02; 32]);
+            let seal = Hash::from([0x01; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.category_description_new(10, Hash::from([0x01; 32])), Ok(()));
+            // The jurisdictions are referenced once registered
+            let rate = TaxRate { rate: 1_000, collector: None };
+            assert_eq!(asseterc721.tax_rate_set(*b"IT", None, Some(rate)), Err(Error::JurisdictionNotFound));
+            assert_eq!(asseterc721.account_jurisdiction_set(accounts.alice, Some(*b"IT")), Err(Error::JurisdictionNotFound));
+            assert_eq!(asseterc721.category_compliance_set(10, *b"IT", SALE_RESTRICTED), Err(Error::JurisdictionNotFound));
+            assert_eq!(asseterc721.jurisdiction_set(*b"IT", rules, Some(accounts.eve)), Ok(()));
+            assert_eq!(asseterc721.jurisdiction_set(*b"FR", rules, None), Ok(()));
+            assert_eq!(asseterc721.jurisdiction_get(*b"IT").map(|entry| (entry.rules, entry.customs)), Some((rules, Some(accounts.eve))));
+            assert_eq!(asseterc721.jurisdictions(0, 10).0, [*b"IT", *b"FR"]);
+            assert_eq!(asseterc721.tax_rate_set(*b"IT", None, Some(rate)), Ok(()));
+            // The sellers of Italy cannot list the assets of category 10
+            assert_eq!(asseterc721.category_compliance_set(10, *b"IT", SALE_RESTRICTED), Ok(()));
+            assert_eq!(asseterc721.category_compliance_get(10, *b"IT"), SALE_RESTRICTED);
+            assert_eq!(asseterc721.account_jurisdiction_set(accounts.alice, Some(*b"IT")), Ok(()));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.asset_category_new(1, 10), Ok(()));
+            assert_eq!(asseterc721.asset_listing_new(1, 100, None, None), Err(Error::RestrictedInJurisdiction));
+            assert_eq!(asseterc721.asset_listing_new(2, 100, None, None), Ok(()));
+            assert_eq!(asseterc721.account_jurisdiction_set(accounts.alice, Some(*b"FR")), Ok(()));
+            assert_eq!(asseterc721.asset_listing_new(1, 100, None, None), Ok(()));
+            // The duty of a shipment to Italy is paid to its customs collector
+            set_sender_value(accounts.alice, 50);
+            assert_eq!(asseterc721.shipment_manifest_create_with_customs(accounts.bob, vec![(1, seal)], *b"DE", 50, None), Err(Error::JurisdictionNotFound));
+            assert_eq!(asseterc721.shipment_manifest_create_with_customs(accounts.bob, vec![(1, seal)], *b"FR", 50, None), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.shipment_manifest_create_with_customs(accounts.bob, vec![(1, seal)], *b"IT", 50, None), Ok(0));
+            assert_eq!(asseterc721.shipment_duty_get(0).map(|duty| (duty.collector, duty.jurisdiction)), Some((accounts.eve, Some(*b"IT"))));
+            // Only administrators manage the registry
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.jurisdiction_set(*b"DE", rules, None), Err(Error::NotAdministrator));
+            assert_eq!(asseterc721.category_compliance_set(10, *b"IT", 0), Err(Error::NotAdministrator));
         }
-       /// Get hard coded super administrator AccountId ###### CUSTOMIZE ADMINISTRATOR #######
-        fn  administrator_accountid() -> Option<AccountId> {   
-            //Administrator hexadecimal Account 
-            //Alice account decoding 5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY in hex: 0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d
-            let accountid32: [u8;32] = hex_literal::hex!["d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"].into();
-            Some(ink_env::AccountId::from(accountid32))
+
+        #[ink::test]
+        fn referral_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>().expect("Cannot get contract");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            // The referrers earn 5% of the sales
+            assert_eq!(asseterc721.config_referral_share_set(10_001), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.config_referral_share_set(500), Ok(()));
+            assert_eq!(asseterc721.config_get().referral_share, 500);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_listing_new(1, 200, None, None), Ok(()));
+            // Neither the buyer nor the seller can refer the sale
+            set_sender_value(accounts.bob, 200);
+            assert_eq!(asseterc721.asset_purchase(1, 200, Some(accounts.bob), None), Err(Error::SelfReferral));
+            assert_eq!(asseterc721.asset_purchase(1, 200, Some(accounts.alice), None), Err(Error::SelfReferral));
+            // Bob buys the asset referred from Eve
+            assert_eq!(test::set_account_balance::<Environment>(contract, 200), Ok(()));
+            let balance = test::get_account_balance::<Environment>(accounts.alice).expect("Cannot get balance");
+            let referrer_balance = test::get_account_balance::<Environment>(accounts.eve).expect("Cannot get balance");
+            assert_eq!(asseterc721.asset_purchase(1, 200, Some(accounts.eve), None), Ok(()));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.alice), Ok(balance + 190));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.eve), Ok(referrer_balance + 10));
+            assert_eq!(asseterc721.asset_sales(1, 0, 10).0.iter().map(|sale| (sale.referrer, sale.referral)).collect::<Vec<_>>(), [(Some(accounts.eve), 10)]);
+            // Only administrators set the share
+            assert_eq!(asseterc721.config_referral_share_set(0), Err(Error::NotAdministrator));
         }
-        /// Removes asset `id` from the owner.
-        fn asset_remove_from(
-            &mut self,
-            from: &AccountId,
-            id: AssetId,
-        ) -> Result<(), Error> {
-            let Self {
-                asset_owner,
-                account_owned_assets,
-                ..
-            } = self;
-            let occupied = match asset_owner.entry(id) {
-                Entry::Vacant(_) => return Err(Error::AssetNotFound),
-                Entry::Occupied(occupied) => occupied,
+
+        #[ink::test]
+        fn discount_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>().expect("Cannot get contract");
+            let code_hash = Sales::code_hash_of(b"SPRING");
+            let discount = Discount {
+                reduction: Reduction::Percent(25),
+                expiry: 10,
             };
-            decrease_counter_of(account_owned_assets, from)?;
-            occupied.remove_entry();
-            Ok(())
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            // Alice lists the assets for 200 with the code "SPRING"
+            assert_eq!(asseterc721.asset_discount_new(1, code_hash, discount), Err(Error::ListingNotFound));
+            assert_eq!(asseterc721.asset_listing_new(1, 200, None, None), Ok(()));
+            assert_eq!(asseterc721.asset_listing_new(2, 200, None, None), Ok(()));
+            assert_eq!(asseterc721.asset_discount_new(1, code_hash, Discount { reduction: Reduction::Percent(0), ..discount }), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.asset_discount_new(1, code_hash, discount), Ok(()));
+            assert_eq!(asseterc721.asset_discount_new(2, code_hash, discount), Ok(()));
+            assert_eq!(asseterc721.asset_discount_get(1, code_hash), Some(discount));
+            // Bob buys asset Id 1 for 150 with the code
+            set_sender_value(accounts.bob, 150);
+            assert_eq!(asseterc721.asset_discount_cancel(1, code_hash), Err(Error::NotOwner));
+            assert_eq!(asseterc721.asset_purchase(1, 150, None, Some(b"SUMMER".to_vec())), Err(Error::DiscountNotFound));
+            assert_eq!(test::set_account_balance::<Environment>(contract, 150), Ok(()));
+            let balance = test::get_account_balance::<Environment>(accounts.alice).expect("Cannot get balance");
+            assert_eq!(asseterc721.asset_purchase(1, 150, None, Some(b"SPRING".to_vec())), Ok(()));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.alice), Ok(balance + 150));
+            assert_eq!(asseterc721.asset_sales(1, 0, 10).0.iter().map(|sale| (sale.price, sale.discount)).collect::<Vec<_>>(), [(150, Some(code_hash))]);
+            assert_eq!(asseterc721.asset_discount_get(1, code_hash), None);
+            // The code is used and cannot be redeemed on asset Id 2
+            assert_eq!(asseterc721.asset_purchase(2, 150, None, Some(b"SPRING".to_vec())), Err(Error::DiscountRedeemed));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_discount_cancel(2, code_hash), Ok(()));
+            assert_eq!(asseterc721.asset_discount_cancel(2, code_hash), Err(Error::DiscountNotFound));
         }
 
-        /// Adds the asset `id` to the `to` AccountID.
-        fn add_asset_to(&mut self, to: &AccountId, id: AssetId) -> Result<(), Error> {
-            let Self {
-                asset_owner,
-                account_owned_assets,
-                ..
-            } = self;
-            let vacant_asset_owner = match asset_owner.entry(id) {
-                Entry::Vacant(vacant) => vacant,
-                Entry::Occupied(_) => return Err(Error::AssetExists),
-            };
-            if *to == AccountId::from([0x0; 32]) {
-                return Err(Error::NotAllowed)
+        #[ink::test]
+        fn shipment_sla_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let seal = Hash::from([0x01; 32]);
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>().expect("Cannot get contract");
+            let sla = Sla {
+                deadline: 2,
+                penalty_per_block: 30,
+                cap: 150,
             };
-            let entry = account_owned_assets.entry(*to);
-            increase_counter_of(entry);
-            vacant_asset_owner.insert(*to);
-            Ok(())
-        }
-        /// Approves or disapproves the operator to transfer all assets of the caller.
-        fn proxy_for_all_assets(
-            &mut self,
-            to: AccountId,
-            approved: bool,
-        ) -> Result<(), Error> {
-            let caller = self.env().caller();
-            if to == caller {
-                return Err(Error::NotAllowed)
-            }
-            self.env().emit_event(ApprovalForAll {
-                owner: caller,
-                operator: to,
-                approved,
-            });
-            if self.check_proxy_for_all(caller, to) {
-                let status = self
-                    .account_proxy
-                    .get_mut(&(caller, to))
-                    .ok_or(Error::CannotFetchValue)?;
-                *status = approved;
-                Ok(())
-            } else {
-                match self.account_proxy.insert((caller, to), approved) {
-                    Some(_) => Err(Error::CannotInsert),
-                    None => Ok(()),
-                }
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator, Eve is a shipper with a stake of 100, Bob receives the shipment
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.account_role_new(accounts.eve, 4), Ok(()));
+            set_sender_value(accounts.eve, 100);
+            assert_eq!(asseterc721.stake_bond().map(|bond| bond.bonded), Ok(100));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.shipment_manifest_create(accounts.bob, vec![(1, seal)]), Ok(0));
+            // Alice agrees 100 with Eve for a delivery by block 2
+            set_sender_value(accounts.alice, 100);
+            assert_eq!(asseterc721.shipment_freight_agree(0, accounts.eve, 100, None, Some(sla)), Ok(()));
+            assert_eq!(asseterc721.shipment_freight_get(0).and_then(|freight| freight.sla), Some(sla));
+            // Bob confirms the delivery at block 7, the penalty of 150 takes the freight and 50 of the stake
+            for _ in 0..7 {
+                ink_env::test::advance_block::<ink_env::DefaultEnvironment>().expect("Cannot advance block");
             }
+            // the contract holds the freight only, the slashed stake cannot be paid and is owed to Alice
+            assert_eq!(test::set_account_balance::<Environment>(contract, 100), Ok(()));
+            let balance = test::get_account_balance::<Environment>(accounts.alice).expect("Cannot get balance");
+            let shipper_balance = test::get_account_balance::<Environment>(accounts.eve).expect("Cannot get balance");
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.shipment_delivery_confirm(0), Ok(()));
+            assert_eq!(asseterc721.shipment_delivery_confirm(0), Err(Error::DuplicatedData));
+            assert_eq!(asseterc721.shipment_freight_get(0).map(|freight| freight.penalty), Some(150));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.alice), Ok(balance + 100));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.eve), Ok(shipper_balance));
+            assert_eq!(asseterc721.stake_get(accounts.eve).map(|bond| bond.bonded), Some(50));
+            assert_eq!(asseterc721.shipment_penalty_owed(accounts.alice, None), 50);
+            // Alice claims the penalty owed once it can be paid
+            assert_eq!(asseterc721.shipment_penalty_claim(None), Err(Error::InsufficientBalance));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.shipment_penalty_claim(None), Err(Error::PaymentFailed));
+            assert_eq!(asseterc721.shipment_penalty_owed(accounts.alice, None), 50);
+            assert_eq!(test::set_account_balance::<Environment>(contract, 50), Ok(()));
+            assert_eq!(asseterc721.shipment_penalty_claim(None), Ok(50));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.alice), Ok(balance + 150));
+            assert_eq!(asseterc721.shipment_penalty_owed(accounts.alice, None), 0);
         }
 
-        /// Approves the passed AccountId to transfer the specified asset on behalf of the message's sender.
-        fn delegate_for_single_asset(&mut self, to: &AccountId, id: AssetId) -> Result<(), Error> {
-            let caller = self.env().caller();
-            let owner = self.asset_get_owner(id);
-            if !(owner == Some(caller)
-                || self.check_proxy_for_all(owner.expect("Error with AccountId"), caller))
-            {
-                return Err(Error::NotAllowed)
-            };
-            if *to == AccountId::from([0x0; 32]) {
-                return Err(Error::NotAllowed)
-            };
-
-            if self.asset_proxy.insert(id, *to).is_some() {
-                return Err(Error::CannotInsert)
-            };
-            self.env().emit_event(ProxyUpdated {
-                from: caller,
-                to: *to,
-                id,
-            });
-            Ok(())
+        #[ink::test]
+        fn shipment_insurance_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let seal = Hash::from([0x01; 32]);
+            let sensor_log = Hash::from([0x02; 32]);
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>().expect("Cannot get contract");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator and ships asset Id 1 and 2 to Bob
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.shipment_manifest_create(accounts.bob, vec![(1, seal)]), Ok(0));
+            assert_eq!(asseterc721.shipment_manifest_create(accounts.bob, vec![(2, seal)]), Ok(1));
+            // Django insures both shipments for Alice with a coverage of 500 between 2 and 8 degrees
+            set_sender_value(accounts.django, 500);
+            assert_eq!(asseterc721.shipment_insurance_attach(0, accounts.alice, 500, None, 80, 20), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.shipment_insurance_attach(0, accounts.alice, 500, None, 20, 80), Ok(()));
+            assert_eq!(asseterc721.shipment_insurance_attach(0, accounts.alice, 500, None, 20, 80), Err(Error::DuplicatedData));
+            assert_eq!(asseterc721.shipment_insurance_attach(1, accounts.alice, 500, None, 20, 80), Ok(()));
+            assert_eq!(asseterc721.shipment_insurance_settle(0), Err(Error::CertificateNotFound));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.shipment_delivery_confirm(0), Ok(()));
+            assert_eq!(asseterc721.shipment_delivery_confirm(1), Ok(()));
+            // The certificate of shipment 0 records an excursion to 11 degrees, the coverage is paid to Alice
+            set_sender(accounts.alice);
+            assert_eq!(test::set_account_balance::<Environment>(contract, 1000), Ok(()));
+            let balance = test::get_account_balance::<Environment>(accounts.alice).expect("Cannot get balance");
+            assert_eq!(asseterc721.shipment_certificate_issue(0, sensor_log, 30, 110, false), Ok(0));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.alice), Ok(balance + 500));
+            assert_eq!(asseterc721.shipment_insurance_get(0).map(|policy| policy.status), Some(PolicyStatus::PaidOut));
+            assert_eq!(asseterc721.shipment_insurance_settle(0), Err(Error::PolicyNotFound));
+            // Shipment 1 is compliant, the coverage is released to Django
+            let insurer_balance = test::get_account_balance::<Environment>(accounts.django).expect("Cannot get balance");
+            assert_eq!(asseterc721.shipment_certificate_issue(1, sensor_log, 30, 70, true), Ok(1));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.django), Ok(insurer_balance + 500));
+            assert_eq!(asseterc721.shipment_insurance_get(1).map(|policy| policy.status), Some(PolicyStatus::Released));
         }
 
-        /// Removes existing approval from asset `id`.
-        fn clear_proxy_asset(&mut self, id: AssetId) -> Result<(), Error> {
-            if !self.asset_proxy.contains_key(&id) {
-                return Ok(())
+        #[ink::test]
+        fn shipper_penalties_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let seal = Hash::from([0x01; 32]);
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>().expect("Cannot get contract");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator, Eve is a shipper
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.account_role_new(accounts.eve, 4), Ok(()));
+            // The shippers bond 200 and forfeit half of their stake for damaged goods
+            let penalties = Penalties {
+                loss: 10_000,
+                damage: 5_000,
+                service_level: 1_000,
             };
-            match self.asset_proxy.take(&id) {
-                Some(_res) => Ok(()),
-                None => Err(Error::CannotRemove),
-            }
+            assert_eq!(asseterc721.config_shipper_penalties_set(200, Penalties { loss: 10_001, ..penalties }), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.config_shipper_penalties_set(200, penalties), Ok(()));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.shipment_manifest_create(accounts.bob, vec![(1, seal)]), Ok(0));
+            // Without a freight there is no shipper to dispute
+            assert_eq!(asseterc721.shipment_dispute_open(0, Breach::Damage, seal), Err(Error::NotAllowed));
+            set_sender_value(accounts.alice, 100);
+            assert_eq!(asseterc721.shipment_freight_agree(0, accounts.eve, 100, None, None), Err(Error::StakeRequired));
+            set_sender_value(accounts.eve, 200);
+            assert_eq!(asseterc721.stake_bond().map(|bond| bond.bonded), Ok(200));
+            set_sender_value(accounts.alice, 100);
+            assert_eq!(asseterc721.shipment_freight_agree(0, accounts.eve, 100, None, None), Ok(()));
+            // Only the consignor disputes the shipment
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.shipment_dispute_open(0, Breach::Damage, seal), Err(Error::NotAllowed));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.shipment_dispute_open(0, Breach::Damage, seal), Ok(0));
+            // Half of the stake of Eve is forfeited to Alice
+            assert_eq!(test::set_account_balance::<Environment>(contract, 300), Ok(()));
+            assert_eq!(asseterc721.dispute_resolve(0, true), Ok(()));
+            assert_eq!(asseterc721.dispute_get(0).map(|dispute| dispute.status), Some(DisputeStatus::Upheld(100)));
+            assert_eq!(asseterc721.stake_get(accounts.eve).map(|bond| bond.bonded), Some(100));
+            // A rejected dispute keeps the stake
+            assert_eq!(asseterc721.shipment_dispute_open(0, Breach::ServiceLevel, seal), Ok(1));
+            assert_eq!(asseterc721.dispute_resolve(1, false), Ok(()));
+            assert_eq!(asseterc721.stake_get(accounts.eve).map(|bond| bond.bonded), Some(100));
+            // The disputes of the shipment are recorded in the feed of the shipper on the asset shipped
+            let feed = asseterc721.account_activity_get(accounts.eve, 0, 1).0;
+            assert_eq!(feed.iter().map(|entry| (entry.kind, entry.id, entry.counterpart)).collect::<Vec<_>>(), [(ActivityKind::DisputeResolved(1), 1, Some(accounts.alice))]);
         }
 
-        // Returns the total number of assets from an account.
-        fn account_assets_number_or_zero(&self, of: &AccountId) -> u32 {
-            *self.account_owned_assets.get(of).unwrap_or(&0)
+        #[ink::test]
+        fn shipment_duty_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let seal = Hash::from([0x01; 32]);
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>().expect("Cannot get contract");
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            // Alice ships asset Id 1 and 2 to Bob depositing a duty of 50 for Eve, the customs collector
+            set_sender_value(accounts.alice, 40);
+            assert_eq!(asseterc721.shipment_manifest_create_with_duty(accounts.bob, vec![(1, seal)], accounts.eve, 50, None), Err(Error::InvalidPayment));
+            assert_eq!(asseterc721.shipment_manifest_get(0), None);
+            set_sender_value(accounts.alice, 50);
+            assert_eq!(asseterc721.shipment_manifest_create_with_duty(accounts.bob, vec![(1, seal)], accounts.eve, 50, None), Ok(0));
+            assert_eq!(asseterc721.shipment_manifest_create_with_duty(accounts.bob, vec![(2, seal)], accounts.eve, 50, None), Ok(1));
+            assert_eq!(test::set_account_balance::<Environment>(contract, 100), Ok(()));
+            // Only the collector clears the shipment
+            assert_eq!(asseterc721.shipment_customs_clear(0), Err(Error::NotAllowed));
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.shipment_customs_clear(0), Ok(()));
+            assert_eq!(asseterc721.shipment_customs_clear(0), Err(Error::DuplicatedData));
+            assert_eq!(asseterc721.shipment_duty_get(0).map(|duty| duty.status), Some(DutyStatus::Released));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.eve), Ok(50));
+            // Alice cancels the second shipment and gets the duty back
+            assert_eq!(asseterc721.shipment_cancel(1), Err(Error::NotAllowed));
+            set_sender(accounts.alice);
+            let balance = test::get_account_balance::<Environment>(accounts.alice).expect("Cannot get balance");
+            assert_eq!(asseterc721.shipment_cancel(1), Ok(()));
+            assert_eq!(asseterc721.shipment_cancel(1), Err(Error::ShipmentCancelled));
+            assert_eq!(asseterc721.shipment_cancellation_get(1), Some(0));
+            assert_eq!(asseterc721.shipment_duty_get(1).map(|duty| duty.status), Some(DutyStatus::Refunded));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.alice), Ok(balance + 50));
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.shipment_customs_clear(1), Err(Error::ShipmentCancelled));
+            // The freight refund fails after the duty refund, only the freight is escrowed again
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.account_role_new(accounts.django, 4), Ok(()));
+            set_sender_value(accounts.alice, 50);
+            assert_eq!(asseterc721.shipment_manifest_create_with_duty(accounts.bob, vec![(2, seal)], accounts.eve, 50, None), Ok(2));
+            set_sender_value(accounts.alice, 100);
+            assert_eq!(asseterc721.shipment_freight_agree(2, accounts.django, 100, None, None), Ok(()));
+            assert_eq!(test::set_account_balance::<Environment>(contract, 50), Ok(()));
+            let balance = test::get_account_balance::<Environment>(accounts.alice).expect("Cannot get balance");
+            assert_eq!(asseterc721.shipment_cancel(2), Err(Error::PaymentFailed));
+            assert_eq!(asseterc721.shipment_cancellation_get(2), None);
+            assert_eq!(asseterc721.shipment_duty_get(2).map(|duty| duty.status), Some(DutyStatus::Refunded));
+            assert_eq!(asseterc721.shipment_freight_get(2).map(|freight| freight.cost), Some(100));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.alice), Ok(balance + 50));
+            // the duty refunded is neither refunded again nor paid to the collector
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.shipment_customs_clear(2), Err(Error::DuplicatedData));
+            set_sender(accounts.alice);
+            assert_eq!(test::set_account_balance::<Environment>(contract, 100), Ok(()));
+            assert_eq!(asseterc721.shipment_cancel(2), Ok(()));
+            assert_eq!(test::get_account_balance::<Environment>(accounts.alice), Ok(balance + 150));
+            assert_eq!(test::get_account_balance::<Environment>(contract), Ok(0));
         }
 
-        /// Gets an operator on other Account's behalf.
-        fn check_proxy_for_all(&self, owner: AccountId, operator: AccountId) -> bool {
-            *self
-                .account_proxy
-                .get(&(owner, operator))
-                .unwrap_or(&false)
+        #[ink::test]
+        fn carrier_subcontract_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let location = Hash::from([0x01; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator, Charlie, Django and Eve are shippers, Bob owns asset Id 1
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            for shipper in [accounts.charlie, accounts.django, accounts.eve].iter() {
+                assert_eq!(asseterc721.account_role_new(*shipper, 4), Ok(()));
+            }
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_carrier_assign(1, accounts.frank), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.asset_carrier_assign(1, accounts.charlie), Ok(()));
+            // Charlie cannot subcontract until Bob allows it
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.asset_carrier_subcontract(1, accounts.django), Err(Error::SubcontractNotAllowed));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.account_subcontract_policy_set(SubcontractPolicy::ApprovedOnly), Ok(()));
+            assert_eq!(asseterc721.account_subcontractor_approve(accounts.django, true), Ok(()));
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.asset_carrier_subcontract(1, accounts.eve), Err(Error::SubcontractNotAllowed));
+            assert_eq!(asseterc721.asset_carrier_subcontract(1, accounts.django), Ok(()));
+            // The location follows the actual carrier
+            assert_eq!(asseterc721.asset_carrier_subcontract(1, accounts.eve), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.asset_location_new(1, location), Err(Error::NotOwner));
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_location_new(1, location), Err(Error::NotOwner));
+            set_sender(accounts.django);
+            assert_eq!(asseterc721.asset_location_new(1, location), Ok(()));
+            assert_eq!(asseterc721.asset_carrier_get(1), Some(accounts.django));
+            let carriers: Vec<AccountId> = asseterc721.asset_carrier_chain(1, 0, 10).0.iter().map(|leg| leg.carrier).collect();
+            assert_eq!(carriers, [accounts.charlie, accounts.django]);
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_carrier_release(1), Ok(()));
+            assert_eq!(asseterc721.asset_carrier_release(1), Err(Error::CarrierNotAssigned));
+            assert!(asseterc721.asset_carrier_chain(1, 0, 10).0.is_empty());
         }
 
-        /// Returns true if the AccountId `from` is the owner of asset `id`
-        /// or it has been approved on behalf of the asset `id` owner.
-        fn approved_or_owner(&self, from: Option<AccountId>, id: AssetId) -> bool {
-            let owner = self.asset_get_owner(id);
-            from != Some(AccountId::from([0x0; 32]))
-                && (from == owner
-                    || from == self.asset_proxy.get(&id).cloned()
-                    || self.check_proxy_for_all(
-                        owner.expect("Error with AccountId"),
-                        from.expect("Error with AccountId"),
-                    ))
+        #[ink::test]
+        fn shipment_certificate_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let seal = Hash::from([0x01; 32]);
+            let sensor_log = Hash::from([0x02; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator and ships asset Id 1 and 2 to Bob
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.shipment_manifest_create(accounts.bob, vec![(1, seal), (2, seal)]), Ok(0));
+            assert_eq!(asseterc721.shipment_certificate_issue(0, sensor_log, 20, 45, true), Err(Error::ShipmentNotDelivered));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.shipment_delivery_confirm(0), Ok(()));
+            assert_eq!(asseterc721.shipment_certificate_issue(0, sensor_log, 20, 45, true), Err(Error::NotAdministrator));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.shipment_certificate_issue(0, sensor_log, 45, 20, true), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.shipment_certificate_issue(0, sensor_log, 20, 45, true), Ok(0));
+            assert_eq!(asseterc721.shipment_certificate_issue(0, sensor_log, 20, 45, true), Err(Error::DuplicatedData));
+            // The buyer reads the certificates of the asset in one query
+            let certificates = asseterc721.asset_certificates(2, 0, 10).0;
+            assert_eq!(certificates.len(), 1);
+            assert_eq!(certificates[0].sensor_log, sensor_log);
+            assert!(certificates[0].compliant);
+            assert_eq!(asseterc721.shipment_certificate_get(0), Some(certificates[0]));
         }
 
-        /// Returns true if asset `id` exists or false if it does not.
-        fn exists(&self, id: AssetId) -> bool {
-            self.asset_owner.get(&id).is_some() && self.asset_owner.contains_key(&id)
+        #[ink::test]
+        fn category_alert_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let seal = Hash::from([0x01; 32]);
+            let alerts = || {
+                ink_env::test::recorded_events()
+                    .filter_map(|event| match <Event as scale::Decode>::decode(&mut &event.data[..]) {
+                        Ok(Event::CategoryAlert(alert)) => Some((alert.category, alert.subscribers, alert.alert)),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+            };
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.category_description_new(10, Hash::from([0x01; 32])), Ok(()));
+            assert_eq!(asseterc721.category_description_new(20, Hash::from([0x02; 32])), Ok(()));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.asset_new(3), Ok(()));
+            assert_eq!(asseterc721.asset_category_new(1, 10), Ok(()));
+            assert_eq!(asseterc721.asset_category_new(2, 20), Ok(()));
+            assert_eq!(asseterc721.asset_category_new(3, 10), Ok(()));
+            // Bob stocks the products of category 10
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.category_subscribe(30), Err(Error::CategoryNotFound));
+            assert_eq!(asseterc721.category_subscribe(10), Ok(()));
+            assert_eq!(asseterc721.category_subscribe(10), Err(Error::DuplicatedData));
+            assert_eq!(asseterc721.account_category_subscriptions(accounts.bob, 0, 10), ([10].to_vec(), None));
+            assert_eq!(asseterc721.category_subscribers(10, 0, 10).0, [accounts.bob]);
+            let digest = asseterc721.category_subscribers_digest(10).expect("Digest not found");
+            // Only the assets of the subscribed categories raise alerts
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_validation_new(1, accounts.alice), Ok(()));
+            assert_eq!(asseterc721.asset_validation_new(2, accounts.alice), Ok(()));
+            assert_eq!(alerts(), [(10, digest, Alert::Validated(1))]);
+            // A certificate raises one alert for each category of its shipment
+            assert_eq!(asseterc721.shipment_manifest_create(accounts.bob, vec![(1, seal), (2, seal), (3, seal)]), Ok(0));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.shipment_delivery_confirm(0), Ok(()));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.shipment_certificate_issue(0, seal, 20, 45, true), Ok(0));
+            assert_eq!(asseterc721.asset_validation_revoke(1, seal), Ok(()));
+            assert_eq!(alerts()[1..], [(10, digest, Alert::Certified(0)), (10, digest, Alert::ValidationRevoked(1))]);
+            // The unsubscribed categories raise no alert
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.category_unsubscribe(10), Ok(()));
+            assert_eq!(asseterc721.category_unsubscribe(10), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.category_subscribers_digest(10), None);
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_validation_new(3, accounts.alice), Ok(()));
+            assert_eq!(alerts().len(), 3);
         }
-    }
 
-    fn decrease_counter_of(
-        hmap: &mut StorageHashMap<AccountId, u32>,
-        of: &AccountId,
-    ) -> Result<(), Error> {
-        let count = (*hmap).get_mut(of).ok_or(Error::CannotFetchValue)?;
-        *count -= 1;
-        Ok(())
-    }
+        #[ink::test]
+        fn validation_lock_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let hash = Hash::from([0x01; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator, Bob describes asset Id 1 before its validation
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.category_description_new(1, hash), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_description_new(1, hash), Ok(()));
+            assert_eq!(asseterc721.asset_category_new(1, 1), Ok(()));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_validation_new(1, accounts.alice), Ok(()));
+            // the core fields cannot change once validated, the other data can
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_description_delete(1), Err(Error::LockedAfterValidation));
+            assert_eq!(asseterc721.asset_description_set_locale(1, *b"en", hash), Err(Error::LockedAfterValidation));
+            assert_eq!(asseterc721.asset_photo_new(1, hash), Err(Error::LockedAfterValidation));
+            assert_eq!(asseterc721.asset_category_delete(1), Err(Error::LockedAfterValidation));
+            assert_eq!(asseterc721.asset_location_new(1, hash), Ok(()));
+            // a revoked validation does not lock the data
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_validation_revoke(1, hash), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_photo_new(1, hash), Ok(()));
+        }
 
-    /// Increase asset counter from the `of` AccountId.
-    fn increase_counter_of(entry: Entry<AccountId, u32>) {
-        entry.and_modify(|v| *v += 1).or_insert(1);
-    }
+        #[ink::test]
+        fn validation_queue_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let hash = Hash::from([0x01; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator, the assets of category 1 require validation
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.category_description_new(1, hash), Ok(()));
+            assert_eq!(asseterc721.category_description_new(2, hash), Ok(()));
+            assert_eq!(asseterc721.category_validation_required_set(1, true), Ok(()));
+            assert!(asseterc721.category_validation_required_verify(1));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.category_validation_required_set(2, true), Err(Error::NotAdministrator));
+            // Bob requests the validation of asset 1, asset 2 is queued entering category 1, asset 3 is not
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            assert_eq!(asseterc721.asset_new(3), Ok(()));
+            assert_eq!(asseterc721.asset_validation_request(1), Ok(()));
+            assert_eq!(asseterc721.asset_validation_request(1), Err(Error::DuplicatedData));
+            assert_eq!(asseterc721.asset_category_new(2, 1), Ok(()));
+            assert_eq!(asseterc721.asset_category_new(3, 2), Ok(()));
+            assert_eq!(asseterc721.validation_queue(0, 10).0, [1, 2]);
+            assert_eq!(asseterc721.asset_validation_request_get(2).and_then(|request| request.category), Some(1));
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_validation_request(3), Err(Error::NotOwner));
+            // The validated and the deleted assets leave the queue
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_validation_new(2, accounts.alice), Ok(()));
+            assert_eq!(asseterc721.validation_queue(0, 10).0, [1]);
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_validation_request(2), Err(Error::DuplicatedData));
+            assert_eq!(asseterc721.asset_delete(1), Ok(()));
+            assert_eq!(asseterc721.validation_queue_len(), 0);
+            assert_eq!(asseterc721.asset_validation_request_get(1), None);
+        }
 
-    /// Unit tests
-    #[cfg(test)]
-    mod tests {
-        /// Imports all the definitions from the outer scope so we can use them here.
-        use super::*;
-        use ink_env::{
-            call,
-            test,
-        };
-        use ink_lang as ink;
+        #[ink::test]
+        fn asset_unlock_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let hash = Hash::from([0x01; 32]);
+            let reason = Hash::from([0x02; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            // Alice is an administrator and validates asset Id 1 of Bob
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_description_new(1, hash), Ok(()));
+            assert_eq!(asseterc721.asset_unlock(1, reason), Err(Error::NotAdministrator));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_unlock(1, reason), Err(Error::NotValidated));
+            assert_eq!(asseterc721.asset_unlock(2, reason), Err(Error::AssetNotFound));
+            assert_eq!(asseterc721.asset_validation_new(1, accounts.alice), Ok(()));
+            // the override clears the validation and leaves a trace in the administrators log
+            assert_eq!(asseterc721.asset_unlock(1, reason), Ok(()));
+            assert!(!asseterc721.asset_validation_verify(1));
+            let log = asseterc721.admin_log_get(0, 10).0;
+            assert_eq!(log[0].admin, accounts.alice);
+            assert_eq!(log[0].action, AdminAction::AssetUnlocked {
+                id: 1,
+                validator: accounts.alice,
+                reason,
+            });
+            // the owner corrects the data
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_description_delete(1), Ok(()));
+        }
 
         #[ink::test]
-        fn mint_works() {
+        fn revocation_works() {
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
+            let seal = Hash::from([0x01; 32]);
+            let sensor_log = Hash::from([0x02; 32]);
+            let reason = Hash::from([0x03; 32]);
             // Create a new contract instance.
             let mut asseterc721 = AssetErc721::new();
-            // Asset 1 does not exists.
-            assert_eq!(asseterc721.asset_get_owner(1), None);
-            // Alice does not owns assets.
-            assert_eq!(asseterc721.account_assets_number(accounts.alice), 0);
-            // Create asset Id 1.
+            // Alice is an administrator, validates asset Id 1 and certifies its shipment to Bob
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
             assert_eq!(asseterc721.asset_new(1), Ok(()));
-            // Alice owns 1 asset.
-            assert_eq!(asseterc721.account_assets_number(accounts.alice), 1);
+            assert_eq!(asseterc721.asset_validation_revoke(1, reason), Err(Error::AssetNotFound));
+            assert_eq!(asseterc721.asset_validation_new(1, accounts.alice), Ok(()));
+            assert_eq!(asseterc721.shipment_manifest_create(accounts.bob, vec![(1, seal)]), Ok(0));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.shipment_delivery_confirm(0), Ok(()));
+            assert_eq!(asseterc721.asset_validation_revoke(1, reason), Err(Error::NotAdministrator));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.shipment_certificate_issue(0, sensor_log, 20, 45, true), Ok(0));
+            // the revoked credentials are not reported from the verification queries
+            assert_eq!(asseterc721.asset_validation_revoke(1, reason), Ok(()));
+            assert_eq!(asseterc721.asset_validation_revoke(1, reason), Err(Error::DuplicatedData));
+            assert!(asseterc721.credential_is_revoked(Credential::Validation(1)));
+            assert!(!asseterc721.asset_validation_verify(1));
+            assert_eq!(asseterc721.asset_validation_get(1), None);
+            assert_eq!(asseterc721.certificate_revoke(1, reason), Err(Error::CertificateNotFound));
+            assert_eq!(asseterc721.certificate_revoke(0, reason), Ok(()));
+            assert_eq!(asseterc721.shipment_certificate_get(0), None);
+            assert!(asseterc721.asset_certificates(1, 0, 10).0.is_empty());
+            let revocation = asseterc721.credential_revocation_get(Credential::Certificate(0)).expect("Cannot get revocation");
+            assert_eq!((revocation.issuer, revocation.revoker, revocation.reason), (accounts.alice, accounts.alice, reason));
+            // a new validation and a new certificate replace the revoked ones, the revocations stay in the list
+            assert_eq!(asseterc721.asset_validation_new(1, accounts.alice), Ok(()));
+            assert!(asseterc721.asset_validation_verify(1));
+            assert_eq!(asseterc721.shipment_certificate_issue(0, sensor_log, 20, 45, false), Ok(1));
+            assert_eq!(asseterc721.shipment_certificate_get(0).map(|certificate| certificate.compliant), Some(false));
+            assert_eq!(asseterc721.asset_certificates(1, 0, 10).0.len(), 1);
+            let (revocations, next) = asseterc721.revocations(0, 10);
+            assert_eq!(revocations.iter().map(|revocation| revocation.credential).collect::<Vec<_>>(), [Credential::Validation(1), Credential::Certificate(0)]);
+            assert_eq!(next, None);
         }
 
         #[ink::test]
-        fn mint_existing_should_fail() {
+        fn lab_results_works() {
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
+            let report = Hash::from([0x01; 32]);
+            let sample_id = Hash::from([0x02; 32]);
             // Create a new contract instance.
             let mut asseterc721 = AssetErc721::new();
-            // Create asset Id 1.
+            // Alice is an administrator, Eve is a laboratory, Bob owns asset Id 1 in category 3
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.account_role_new(accounts.eve, 6), Ok(()));
+            assert_eq!(asseterc721.metadata.category_description_insert(3, Hash::from([0x03; 32])), Ok(()));
+            assert_eq!(asseterc721.category_lab_requirement_set(3, Some(7)), Ok(()));
+            set_sender(accounts.bob);
             assert_eq!(asseterc721.asset_new(1), Ok(()));
-            // The first Transfer event takes place
-            assert_eq!(1, ink_env::test::recorded_events().count());
-            // Alice owns 1 asset.
-            assert_eq!(asseterc721.account_assets_number(accounts.alice), 1);
-            // Alice owns asset Id 1.
-            assert_eq!(asseterc721.asset_get_owner(1), Some(accounts.alice));
-            // Cannot create  asset Id if it exists.
-            // Bob cannot own asset Id 1.
-            assert_eq!(asseterc721.asset_new(1), Err(Error::AssetExists));
+            assert_eq!(asseterc721.asset_category_new(1, 3), Ok(()));
+            assert_eq!(asseterc721.asset_laboratory_authorize(1, accounts.charlie, true), Err(Error::NotLaboratory));
+            // Eve cannot attach results before Bob authorizes her
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_lab_result_add(1, 7, true, report, sample_id), Err(Error::NotAllowed));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_lab_result_add(1, 7, true, report, sample_id), Err(Error::NotLaboratory));
+            assert_eq!(asseterc721.asset_laboratory_authorize(1, accounts.eve, true), Ok(()));
+            assert_eq!(asseterc721.asset_laboratories(1, 0, 10).0, [accounts.eve]);
+            // The category requires a passing test of type 7 before the validation
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_lab_result_add(1, 7, false, report, sample_id), Ok(()));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_validation_new(1, accounts.alice), Err(Error::LabResultRequired));
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_lab_result_add(1, 7, true, report, sample_id), Ok(()));
+            assert_eq!(asseterc721.asset_lab_results(1, 0, 10).0.len(), 2);
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_validation_new(1, accounts.alice), Ok(()));
         }
 
         #[ink::test]
-        fn transfer_works() {
+        fn role_registry_works() {
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
+            let customs = 10;
             // Create a new contract instance.
             let mut asseterc721 = AssetErc721::new();
-            // Create asset Id 1 for Alice
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.account_role_new(accounts.bob, customs), Err(Error::CannotInsert));
+            assert_eq!(asseterc721.role_register(customs, Hash::from([0x01; 32])), Ok(()));
+            assert_eq!(asseterc721.role_register(customs, Hash::from([0x01; 32])), Err(Error::DuplicatedData));
+            assert_eq!(asseterc721.account_role_new(accounts.bob, customs), Ok(()));
+            // A disabled shipper cannot write the locations of the assets
+            assert_eq!(asseterc721.account_role_new(accounts.eve, 4), Ok(()));
             assert_eq!(asseterc721.asset_new(1), Ok(()));
-            // Alice owns asset 1
-            assert_eq!(asseterc721.account_assets_number(accounts.alice), 1);
-            // Bob does not owns any asset
-            assert_eq!(asseterc721.account_assets_number(accounts.bob), 0);
-            // The first Transfer event takes place
-            assert_eq!(1, ink_env::test::recorded_events().count());
-            // Alice transfers asset 1 to Bob
-            assert_eq!(asseterc721.asset_transfer(accounts.bob, 1), Ok(()));
-            // The second Transfer event takes place
-            assert_eq!(2, ink_env::test::recorded_events().count());
-            // Bob owns asset 1
-            assert_eq!(asseterc721.account_assets_number(accounts.bob), 1);
+            assert_eq!(asseterc721.role_enabled_set(4, false), Ok(()));
+            assert_eq!(asseterc721.role_get(4).map(|info| info.enabled), Some(false));
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_location_new(1, Hash::from([0x02; 32])), Err(Error::NotOwner));
+            assert_eq!(asseterc721.role_enabled_set(4, true), Err(Error::NotAdministrator));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.role_enabled_set(4, true), Ok(()));
+            assert_eq!(asseterc721.role_enabled_set(5, false), Err(Error::NotAllowed));
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_location_new(1, Hash::from([0x02; 32])), Ok(()));
         }
 
         #[ink::test]
-        fn invalid_transfer_should_fail() {
+        fn new_with_config_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            // Bob is the administrator, Eve a producer and category 10 is described from the deployment
+            let mut asseterc721 = AssetErc721::new_with_config(
+                accounts.bob,
+                [(accounts.eve, 0)].to_vec(),
+                [(10, Hash::from([0x01; 32]))].to_vec(),
+            );
+            assert_eq!(asseterc721.account_role_get(accounts.bob), Some(5));
+            assert_eq!(asseterc721.account_role_get(accounts.eve), Some(0));
+            assert_eq!(asseterc721.category_description_get(10), Some(Hash::from([0x01; 32])));
+            assert_eq!(asseterc721.stats_get().accounts_per_role, [(5, 1), (0, 1)]);
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.category_description_new(20, Hash::from([0x02; 32])), Ok(()));
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.product_new(Hash::from([0x03; 32]), 10), Ok(0));
+        }
+
+        #[ink::test]
+        fn account_role_update_works() {
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
             // Create a new contract instance.
             let mut asseterc721 = AssetErc721::new();
-            // Transfer asset fails if it does not exists.
-            assert_eq!(asseterc721.asset_transfer(accounts.bob, 2), Err(Error::AssetNotFound));
-            // Asset Id 2 does not exists.
-            assert_eq!(asseterc721.asset_get_owner(2), None);
-            // Create asset Id 2.
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.account_role_update(accounts.bob, 1), Err(Error::RoleNotFound));
+            assert_eq!(asseterc721.account_role_new(accounts.bob, 0), Ok(()));
+            assert_eq!(asseterc721.account_role_update(accounts.bob, 0), Err(Error::DuplicatedData));
+            assert_eq!(asseterc721.account_role_update(accounts.bob, 10), Err(Error::CannotInsert));
+            // Bob moves from producer to wholesaler in one step
+            assert_eq!(asseterc721.account_role_update(accounts.bob, 1), Ok(()));
+            assert_eq!(asseterc721.account_role_get(accounts.bob), Some(1));
+            assert_eq!(asseterc721.stats_get().accounts_per_role, [(0, 0), (1, 1)]);
+            let emitted = ink_env::test::recorded_events().last().expect("no event emitted");
+            let decoded = <Event as scale::Decode>::decode(&mut &emitted.data[..])
+                .expect("invalid event data");
+            match decoded {
+                Event::RoleUpdate(event) => {
+                    assert_eq!(event.id, accounts.bob);
+                    assert_eq!((event.old, event.new), (Some(0), Some(1)));
+                }
+                _ => panic!("expected a RoleUpdate event"),
+            }
+            // Only the administrators can appoint or demote administrators
+            assert_eq!(asseterc721.role_register(10, Hash::from([0x01; 32])), Ok(()));
+            assert_eq!(asseterc721.role_capabilities_set(10, CAN_MANAGE_ROLES), Ok(()));
+            assert_eq!(asseterc721.account_role_new(accounts.eve, 10), Ok(()));
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.account_role_update(accounts.bob, 5), Err(Error::NotAdministrator));
+            assert_eq!(asseterc721.account_role_update(accounts.alice, 0), Err(Error::NotAdministrator));
+            assert_eq!(asseterc721.account_role_update(accounts.bob, 2), Ok(()));
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.account_role_update(accounts.bob, 0), Err(Error::MissingCapability));
+        }
+
+        #[ink::test]
+        fn role_capabilities_work() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let validator = 10;
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.role_register(validator, Hash::from([0x01; 32])), Ok(()));
+            assert_eq!(asseterc721.role_capabilities_set(validator, CAN_VALIDATE | CAN_MANAGE_ROLES), Ok(()));
+            assert_eq!(asseterc721.role_capabilities_set(5, 0), Err(Error::NotAllowed));
+            assert_eq!(asseterc721.account_role_new(accounts.bob, validator), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.role_capabilities_set(validator, CAN_MINT), Err(Error::NotAdministrator));
+            // the role validates the assets with no need to be administrator
+            assert_eq!(asseterc721.asset_validation_new(1, accounts.bob), Ok(()));
+            // but cannot mint or appoint administrators
+            assert_eq!(asseterc721.asset_new(2), Err(Error::MissingCapability));
+            assert_eq!(asseterc721.account_role_new(accounts.eve, 5), Err(Error::NotAdministrator));
+            assert_eq!(asseterc721.account_role_delete(accounts.alice), Err(Error::NotAdministrator));
+            assert_eq!(asseterc721.account_role_new(accounts.eve, 0), Ok(()));
+            // the accounts with no role keep the default capabilities
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.asset_validation_new(1, accounts.charlie), Err(Error::MissingCapability));
             assert_eq!(asseterc721.asset_new(2), Ok(()));
-            // Alice owns 1 asset.
-            assert_eq!(asseterc721.account_assets_number(accounts.alice), 1);
-            // Asset Id 2 is owned by Alice.
-            assert_eq!(asseterc721.asset_get_owner(2), Some(accounts.alice));
-            // Get contract address
-            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>()
-                .unwrap_or([0x0; 32].into());
-            // Create call
-            let mut data =
-                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])); // account_assets_number
-            data.push_arg(&accounts.bob);
-            // Push the new execution context to set Bob as caller
-            ink_env::test::push_execution_context::<ink_env::DefaultEnvironment>(
-                accounts.bob,
-                callee,
-                1000000,
-                1000000,
-                data,
-            );
-            // Bob cannot transfer not owned assets.
-            assert_eq!(asseterc721.asset_transfer(accounts.eve, 2), Err(Error::NotApproved));
+            // a role without the transfer capability cannot transfer its assets
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.role_capabilities_set(0, CAN_MINT), Ok(()));
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.asset_new(3), Ok(()));
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 3), Err(Error::MissingCapability));
         }
 
         #[ink::test]
-        fn approved_transfer_works() {
+        fn asset_acl_works() {
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
             // Create a new contract instance.
             let mut asseterc721 = AssetErc721::new();
-            // Create asset Id 1.
             assert_eq!(asseterc721.asset_new(1), Ok(()));
-            // Asset Id 1 is owned by Alice.
-            assert_eq!(asseterc721.asset_get_owner(1), Some(accounts.alice));
-            // Approve asset Id 1 transfer for Bob on behalf of Alice.
-            assert_eq!(asseterc721.account_delegate_single_asset(accounts.bob, 1), Ok(()));
-            // Get contract address.
-            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>()
-                .unwrap_or([0x0; 32].into());
-            // Create call
-            let mut data =
-                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])); // account_assets_number
-            data.push_arg(&accounts.bob);
-            // Push the new execution context to set Bob as caller
-            ink_env::test::push_execution_context::<ink_env::DefaultEnvironment>(
-                accounts.bob,
-                callee,
-                1000000,
-                1000000,
-                data,
-            );
-            // Bob transfers asset Id 1 from Alice to Eve.
-            assert_eq!(
-                asseterc721.transfer_from(accounts.alice, accounts.eve, 1),
-                Ok(())
-            );
-            // AssetId 3 is owned by Eve.
-            assert_eq!(asseterc721.asset_get_owner(1), Some(accounts.eve));
-            // Alice does not owns assets.
-            assert_eq!(asseterc721.account_assets_number(accounts.alice), 0);
-            // Bob does not owns assets.
-            assert_eq!(asseterc721.account_assets_number(accounts.bob), 0);
-            // Eve owns 1 asset.
-            assert_eq!(asseterc721.account_assets_number(accounts.eve), 1);
+            assert_eq!(asseterc721.asset_new(2), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_acl_set(1, accounts.bob, FIELD_METADATA), Err(Error::NotOwner));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_acl_set(1, accounts.bob, FIELD_METADATA), Ok(()));
+            assert_eq!(asseterc721.asset_acl_get(1, 0, 10).0, [(accounts.bob, FIELD_METADATA)]);
+            // the co-packer writes the metadata of the granted asset only
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_metadata_new(1, Hash::from([0x01; 32])), Ok(()));
+            assert_eq!(asseterc721.asset_metadata_new(2, Hash::from([0x02; 32])), Err(Error::NotOwner));
+            assert_eq!(asseterc721.asset_photo_new(1, Hash::from([0x03; 32])), Err(Error::NotOwner));
+            assert_eq!(asseterc721.asset_delete(1), Err(Error::NotOwner));
+            // the grants do not survive the transfer of the asset
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_transfer(accounts.eve, 1), Ok(()));
+            assert!(asseterc721.asset_acl_get(1, 0, 10).0.is_empty());
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_metadata_delete(1), Err(Error::NotOwner));
         }
 
         #[ink::test]
-        fn approved_for_all_works() {
+        fn admin_set_works() {
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
+            // Bob and Charlie administer the contract deployed from Alice
+            let mut asseterc721 = AssetErc721::new_with_administrators([accounts.bob, accounts.charlie].to_vec());
+            assert!(!asseterc721.admin_verify(accounts.alice));
+            assert_eq!(asseterc721.account_role_new(accounts.eve, 5), Err(Error::MissingCapability));
+            assert_eq!(asseterc721.admin_add(accounts.alice), Err(Error::NotAdministrator));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.account_role_new(accounts.eve, 5), Ok(()));
+            assert_eq!(asseterc721.admin_add(accounts.charlie), Err(Error::DuplicatedData));
+            assert_eq!(asseterc721.admin_add(accounts.django), Ok(()));
+            assert_eq!(asseterc721.admin_remove(accounts.charlie), Ok(()));
+            assert_eq!(asseterc721.admin_remove(accounts.charlie), Err(Error::CannotRemove));
+            // the administrators of the role cannot change the set
+            set_sender(accounts.eve);
+            assert_eq!(asseterc721.admin_add(accounts.eve), Err(Error::NotAdministrator));
+            // Django hands his place over to Alice
+            set_sender(accounts.django);
+            assert_eq!(asseterc721.admin_transfer(accounts.bob), Err(Error::DuplicatedData));
+            assert_eq!(asseterc721.admin_transfer(accounts.alice), Ok(()));
+            assert!(!asseterc721.admin_verify(accounts.django));
+            assert!(asseterc721.admin_verify(accounts.alice));
+            assert_eq!(asseterc721.admin_remove(accounts.bob), Err(Error::NotAdministrator));
+            // the last administrator cannot be removed
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.admin_remove(accounts.bob), Ok(()));
+            assert_eq!(asseterc721.admin_remove(accounts.alice), Err(Error::NotAllowed));
+            // the deployer administers the contract created with no list
+            let asseterc721 = AssetErc721::new();
+            assert!(asseterc721.admin_verify(accounts.alice));
+        }
+
+        #[ink::test]
+        fn audit_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let wrapped_key = Hash::from([0x02; 32]);
             // Create a new contract instance.
             let mut asseterc721 = AssetErc721::new();
-            // Create asset Id 1.
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
             assert_eq!(asseterc721.asset_new(1), Ok(()));
-            // Create asset Id 2.
             assert_eq!(asseterc721.asset_new(2), Ok(()));
-            // Alice owns 2 assets.
-            assert_eq!(asseterc721.account_assets_number(accounts.alice), 2);
-            // Approve asset Id 1 transfer for Bob on behalf of Alice.
-            assert_eq!(asseterc721.account_delegate_for_all_asset(accounts.bob, true), Ok(()));
-            // Bob is an approved operator for Alice
-            assert_eq!(
-                asseterc721.check_proxy_for_all(accounts.alice, accounts.bob),
-                true
-            );
-            // Get contract address.
-            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>()
-                .unwrap_or([0x0; 32].into());
-            // Create call
-            let mut data =
-                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])); // account_assets_number
-            data.push_arg(&accounts.bob);
-            // Push the new execution context to set Bob as caller
-            ink_env::test::push_execution_context::<ink_env::DefaultEnvironment>(
-                accounts.bob,
-                callee,
-                1000000,
-                1000000,
-                data,
-            );
-            // Bob transfers asset Id 1 from Alice to Eve.
-            assert_eq!(
-                asseterc721.transfer_from(accounts.alice, accounts.eve, 1),
-                Ok(())
-            );
-            // AssetId 1 is owned by Eve.
-            assert_eq!(asseterc721.asset_get_owner(1), Some(accounts.eve));
-            // Alice owns 1 asset.
-            assert_eq!(asseterc721.account_assets_number(accounts.alice), 1);
-            // Bob transfers asset Id 2 from Alice to Eve.
-            assert_eq!(
-                asseterc721.transfer_from(accounts.alice, accounts.eve, 2),
-                Ok(())
-            );
-            // Bob does not owns assets.
-            assert_eq!(asseterc721.account_assets_number(accounts.bob), 0);
-            // Eve owns 2 assets.
-            assert_eq!(asseterc721.account_assets_number(accounts.eve), 2);
-            // Get back to the parent execution context.
-            ink_env::test::pop_execution_context();
-            // Remove operator approval for Bob on behalf of Alice.
-            assert_eq!(asseterc721.account_delegate_for_all_asset(accounts.bob, false), Ok(()));
-            // Bob is not an approved operator for Alice.
-            assert_eq!(
-                asseterc721.check_proxy_for_all(accounts.alice, accounts.bob),
-                false
-            );
+            assert_eq!(asseterc721.asset_private_set(1, Hash::from([0x01; 32])), Ok(()));
+            assert_eq!(asseterc721.asset_private_grant(1, accounts.bob, wrapped_key), Ok(()));
+            assert_eq!(asseterc721.asset_transfer(accounts.bob, 2), Ok(()));
+            assert_eq!(asseterc721.asset_delete_force(2, Hash::from([0x03; 32])), Ok(()));
+            // Alice appoints Charlie auditor
+            assert_eq!(asseterc721.account_role_new(accounts.charlie, 9), Ok(()));
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.audit_private_grants(1, 0, 10), Ok(([(accounts.bob, wrapped_key)].to_vec(), None)));
+            let transfers = asseterc721.audit_transfers(accounts.bob, 0, 10).expect("Cannot read the transfers").0;
+            assert_eq!(transfers.iter().map(|transfer| (transfer.id, transfer.from, transfer.to)).collect::<Vec<_>>(), [
+                (2, accounts.alice, accounts.bob),
+                (2, accounts.bob, AccountId::from([0x0; 32])),
+            ]);
+            // the page reads the journal from the cursor, the creation of asset Id 1 does not match
+            assert_eq!(asseterc721.audit_transfers(accounts.bob, 0, 1), Ok((Vec::new(), Some(1))));
+            assert_eq!(asseterc721.audit_admin_log(Some(accounts.alice), 0, 10).map(|page| page.0.len()), Ok(1));
+            assert_eq!(asseterc721.audit_admin_log(Some(accounts.bob), 0, 10), Ok((Vec::new(), None)));
+            // the auditor has no write capability
+            assert_eq!(asseterc721.asset_new(3), Err(Error::MissingCapability));
+            // the other accounts cannot read the exports
+            set_sender(accounts.django);
+            assert_eq!(asseterc721.audit_admin_log(None, 0, 10), Err(Error::NotAuditor));
+            assert_eq!(asseterc721.audit_private_grants(1, 0, 10), Err(Error::NotAuditor));
         }
 
         #[ink::test]
-        fn not_approved_transfer_should_fail() {
+        fn asset_private_works() {
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
+            let document = Hash::from([0x0a; 32]);
+            let wrapped_key = Hash::from([0x01; 32]);
             // Create a new contract instance.
             let mut asseterc721 = AssetErc721::new();
-            // Create asset Id 1.
             assert_eq!(asseterc721.asset_new(1), Ok(()));
-            // Alice owns 1 asset.
-            assert_eq!(asseterc721.account_assets_number(accounts.alice), 1);
-            // Bob does not owns assets.
-            assert_eq!(asseterc721.account_assets_number(accounts.bob), 0);
-            // Eve does not owns assets.
-            assert_eq!(asseterc721.account_assets_number(accounts.eve), 0);
-            // Get contract address.
-            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>()
-                .unwrap_or([0x0; 32].into());
-            // Create call
-            let mut data =
-                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])); // account_assets_number
-            data.push_arg(&accounts.bob);
-            // Push the new execution context to set Eve as caller
-            ink_env::test::push_execution_context::<ink_env::DefaultEnvironment>(
-                accounts.eve,
-                callee,
-                1000000,
-                1000000,
-                data,
-            );
-            // Eve is not an approved operator by Alice.
-            assert_eq!(
-                asseterc721.transfer_from(accounts.alice, accounts.frank, 1),
-                Err(Error::NotApproved)
-            );
-            // Alice owns 1 asset.
-            assert_eq!(asseterc721.account_assets_number(accounts.alice), 1);
-            // Bob does not owns assets.
-            assert_eq!(asseterc721.account_assets_number(accounts.bob), 0);
-            // Eve does not owns assets.
-            assert_eq!(asseterc721.account_assets_number(accounts.eve), 0);
+            assert_eq!(asseterc721.asset_private_grant(1, accounts.bob, wrapped_key), Err(Error::PrivateDataNotFound));
+            assert_eq!(asseterc721.asset_private_set(1, document), Ok(()));
+            assert_eq!(asseterc721.asset_private_get(1), Some(document));
+            assert_eq!(asseterc721.asset_private_grant(1, accounts.bob, wrapped_key), Ok(()));
+            assert_eq!(asseterc721.asset_private_grant(1, accounts.eve, wrapped_key), Ok(()));
+            assert_eq!(asseterc721.asset_private_key_get(1, accounts.bob), Some(wrapped_key));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_private_revoke(1, accounts.eve), Err(Error::NotOwner));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_private_revoke(1, accounts.eve), Ok(()));
+            assert_eq!(asseterc721.asset_private_grantees(1, 0, 10).0, [accounts.bob]);
+            // the grants of the seller do not pass to the buyer
+            assert_eq!(asseterc721.asset_transfer(accounts.charlie, 1), Ok(()));
+            assert_eq!(asseterc721.asset_private_key_get(1, accounts.bob), None);
+            assert_eq!(asseterc721.asset_private_get(1), Some(document));
+            set_sender(accounts.charlie);
+            assert_eq!(asseterc721.asset_private_delete(1), Ok(()));
+            assert_eq!(asseterc721.asset_private_delete(1), Err(Error::CannotRemove));
         }
 
         #[ink::test]
-        fn asset_delete_works() {
+        fn asset_viewers_works() {
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
             // Create a new contract instance.
             let mut asseterc721 = AssetErc721::new();
-            // Create asset Id 1 for Alice
             assert_eq!(asseterc721.asset_new(1), Ok(()));
-            // Alice owns 1 asset.
-            assert_eq!(asseterc721.account_assets_number(accounts.alice), 1);
-            // Alice owns asset Id 1.
-            assert_eq!(asseterc721.asset_get_owner(1), Some(accounts.alice));
-            // Destroy asset Id 1.
-            assert_eq!(asseterc721.asset_delete(1), Ok(()));
-            // Alice does not owns assets.
-            assert_eq!(asseterc721.account_assets_number(accounts.alice), 0);
-            // Asset Id 1 does not exists
-            assert_eq!(asseterc721.asset_get_owner(1), None);
+            assert!(asseterc721.can_view_private(1, accounts.alice));
+            assert!(!asseterc721.can_view_private(1, accounts.bob));
+            assert!(!asseterc721.can_view_private(2, accounts.alice));
+            assert_eq!(asseterc721.asset_viewer_grant(1, accounts.bob), Ok(()));
+            assert_eq!(asseterc721.asset_viewer_grant(1, accounts.bob), Err(Error::DuplicatedData));
+            assert!(asseterc721.can_view_private(1, accounts.bob));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_viewer_grant(1, accounts.eve), Err(Error::NotOwner));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_viewer_grant(1, accounts.eve), Ok(()));
+            assert_eq!(asseterc721.asset_viewer_revoke(1, accounts.eve), Ok(()));
+            assert_eq!(asseterc721.asset_viewers(1, 0, 10).0, [accounts.bob]);
+            // the new owner starts with no viewers
+            assert_eq!(asseterc721.asset_transfer(accounts.charlie, 1), Ok(()));
+            assert!(!asseterc721.can_view_private(1, accounts.bob));
+            assert!(!asseterc721.can_view_private(1, accounts.alice));
+            assert!(asseterc721.can_view_private(1, accounts.charlie));
         }
 
         #[ink::test]
-        fn asset_delete_fails_asset_not_found() {
+        fn asset_claim_verify_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let verifier = AccountId::from([0x9; 32]);
             // Create a new contract instance.
             let mut asseterc721 = AssetErc721::new();
-            // Try asset_deleteing a non existent asset
-            assert_eq!(asseterc721.asset_delete(1), Err(Error::AssetNotFound));
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_claim_verify(1, 3, [8].to_vec(), [0x01].to_vec()), Err(Error::VerifierNotFound));
+            assert_eq!(asseterc721.proof_verifier_set(3, Some(verifier)), Ok(()));
+            assert_eq!(asseterc721.proof_verifier_get(3), Some(verifier));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.proof_verifier_set(3, None), Err(Error::NotAdministrator));
+            assert_eq!(asseterc721.asset_claim_verify(1, 3, [8].to_vec(), [0x01].to_vec()), Err(Error::NotAllowed));
+            assert!(!asseterc721.asset_claim_proven(1, 3));
         }
 
         #[ink::test]
-        fn asset_delete_fails_not_owner() {
+        fn asset_commit_reveal_works() {
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
+            let salt = Hash::from([0x05; 32]);
+            let commitment = Sealed::commitment_of(b"1500", &salt);
             // Create a new contract instance.
             let mut asseterc721 = AssetErc721::new();
-            // Create asset Id 1 for Alice
             assert_eq!(asseterc721.asset_new(1), Ok(()));
-            // Try asset_deleteing this asset with a different account
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_commit(1, commitment), Err(Error::NotOwner));
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.asset_commit(1, commitment), Ok(()));
+            assert_eq!(asseterc721.asset_commitment_get(1).map(|pending| pending.hash), Some(commitment));
+            // the counterparty reveals the value when both are ready
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_reveal(1, b"1400".to_vec(), salt), Err(Error::InvalidSecret));
+            assert_eq!(asseterc721.asset_reveal(1, b"1500".to_vec(), salt), Ok(()));
+            assert_eq!(asseterc721.asset_reveal(1, b"1500".to_vec(), salt), Err(Error::CommitmentNotFound));
+            let disclosures = asseterc721.asset_disclosures(1, 0, 10).0;
+            assert_eq!(disclosures.len(), 1);
+            assert_eq!(disclosures[0].value, b"1500".to_vec());
+            assert_eq!(disclosures[0].committer, accounts.alice);
+        }
+
+        #[ink::test]
+        fn asset_bridge_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let contract = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get contract id");
+            let burn_proof = Hash::from([0x01; 32]);
+            // Create a new contract instance.
+            let mut asseterc721 = AssetErc721::new();
+            assert_eq!(asseterc721.roles.insert(accounts.alice, 5), Ok(()));
+            assert_eq!(asseterc721.bridge_relayer_set(Some(accounts.django)), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(asseterc721.asset_new(1), Ok(()));
+            assert_eq!(asseterc721.asset_metadata_new(1, Hash::from([0x03; 32])), Ok(()));
+            assert_eq!(asseterc721.asset_bridge_lock(1, [0xaa; 20]), Ok(()));
+            assert_eq!(asseterc721.asset_get_owner(1), Some(contract));
+            let payload = asseterc721.asset_bridge_payload(1).expect("Missing payload");
+            assert_eq!(payload[64..96], [0x03; 32]);
+            // the locked asset cannot move, not even from an administrator
+            set_sender(accounts.alice);
+            assert_eq!(asseterc721.transfer_from(contract, accounts.alice, 1), Err(Error::AssetBridged));
+            assert_eq!(asseterc721.asset_delete(1), Err(Error::AssetBridged));
+            assert_eq!(asseterc721.asset_bridge_unlock(1, 0, burn_proof, accounts.eve), Err(Error::NotAllowed));
+            set_sender(accounts.django);
+            assert_eq!(asseterc721.asset_bridge_unlock(1, 1, burn_proof, accounts.eve), Err(Error::BridgeLockNotFound));
+            assert_eq!(asseterc721.asset_bridge_unlock(1, 0, burn_proof, accounts.eve), Ok(()));
+            assert_eq!(asseterc721.asset_get_owner(1), Some(accounts.eve));
+            assert_eq!(asseterc721.asset_bridge_lock_get(1), None);
+            // the burn proof cannot be replayed
             set_sender(accounts.eve);
-            assert_eq!(asseterc721.asset_delete(1), Err(Error::NotOwner));
+            assert_eq!(asseterc721.asset_bridge_lock(1, [0xaa; 20]), Ok(()));
+            set_sender(accounts.django);
+            assert_eq!(asseterc721.asset_bridge_unlock(1, 1, burn_proof, accounts.eve), Err(Error::DuplicatedData));
         }
 
         fn set_sender(sender: AccountId) {
+            set_sender_value(sender, 1000000);
+        }
+
+        fn set_sender_value(sender: AccountId, value: Balance) {
             let callee = ink_env::account_id::<ink_env::DefaultEnvironment>()
                 .unwrap_or([0x0; 32].into());
             test::push_execution_context::<Environment>(
                 sender,
                 callee,
                 1000000,
-                1000000,
+                value,
                 test::CallData::new(call::Selector::new([0x00; 4])), // dummy
             );
         }
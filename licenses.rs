@@ -0,0 +1,160 @@
+//! # Licenses
+//!
+//! Licenses of the producers for the regulated categories.
+//!
+//! The administrators mark the categories whose assets need a license, as food or medicines,
+//! and register the licenses of the producers with the hash of the license document, the
+//! categories it covers and its expiry. An asset enters a regulated category only when its
+//! producer holds a license in force covering the category.
+//!
+
+use crate::asset_erc721::Error;
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// License of a producer
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct License {
+    /// Hash of the license document
+    pub document: Hash,
+    /// Categories covered from the license
+    pub categories: Vec<u32>,
+    /// Administrator that registered the license
+    pub issuer: AccountId,
+    /// Block the license expires at, `None` if it does not expire
+    pub expiry: Option<BlockNumber>,
+}
+
+impl License {
+    /// Returns true if the license covers `category` at `block`
+    pub fn covers(&self, category: u32, block: BlockNumber) -> bool {
+        self.categories.contains(&category) && self.expiry.is_none_or(|expiry| block < expiry)
+    }
+}
+
+/// Storage of the licenses
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Licenses {
+    /// Licenses of the producer
+    producer_licenses: StorageHashMap<AccountId, Vec<License>>,
+    /// Categories requiring a license
+    regulated: StorageHashMap<u32, bool>,
+}
+
+impl Licenses {
+    /// Returns the licenses of `producer`, lapsed or not
+    pub fn licenses_of(&self, producer: &AccountId) -> Vec<License> {
+        self.producer_licenses.get(producer).cloned().unwrap_or_default()
+    }
+
+    /// Returns true if the assets of `category` require a license
+    pub fn is_regulated(&self, category: u32) -> bool {
+        self.regulated.contains_key(&category)
+    }
+
+    /// Sets whether the assets of `category` require a license
+    pub fn regulated_set(&mut self, category: u32, regulated: bool) {
+        if regulated {
+            self.regulated.insert(category, true);
+        } else {
+            self.regulated.take(&category);
+        }
+    }
+
+    /// Returns true if `producer` can add assets to `category` at `block`: the category is not regulated or the
+    /// producer holds a license in force covering it
+    pub fn is_licensed(&self, producer: &AccountId, category: u32, block: BlockNumber) -> bool {
+        !self.is_regulated(category)
+            || self
+                .producer_licenses
+                .get(producer)
+                .is_some_and(|licenses| licenses.iter().any(|license| license.covers(category, block)))
+    }
+
+    /// Registers `license` of `producer`, replacing the license with the same document. Fails if the license
+    /// covers no category.
+    pub fn grant(&mut self, producer: AccountId, license: License) -> Result<(), Error> {
+        if license.categories.is_empty() {
+            return Err(Error::NotAllowed)
+        }
+        let mut licenses = self.licenses_of(&producer);
+        licenses.retain(|other| other.document != license.document);
+        licenses.push(license);
+        self.producer_licenses.insert(producer, licenses);
+        Ok(())
+    }
+
+    /// Removes the license `document` of `producer`
+    pub fn revoke(&mut self, producer: AccountId, document: Hash) -> Result<(), Error> {
+        let mut licenses = self.licenses_of(&producer);
+        let len = licenses.len();
+        licenses.retain(|license| license.document != document);
+        if licenses.len() == len {
+            return Err(Error::LicenseNotFound)
+        }
+        if licenses.is_empty() {
+            self.producer_licenses.take(&producer);
+        } else {
+            self.producer_licenses.insert(producer, licenses);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn licenses_work() {
+        let alice = AccountId::from([0x1; 32]);
+        let bob = AccountId::from([0x2; 32]);
+        let license = License {
+            document: Hash::from([0x01; 32]),
+            categories: ink_prelude::vec![1, 2],
+            issuer: alice,
+            expiry: Some(10),
+        };
+        let mut licenses = Licenses::default();
+        // the categories not regulated need no license
+        assert!(licenses.is_licensed(&bob, 1, 0));
+        licenses.regulated_set(1, true);
+        assert!(!licenses.is_licensed(&bob, 1, 0));
+        assert_eq!(licenses.grant(bob, License { categories: Vec::new(), ..license.clone() }), Err(Error::NotAllowed));
+        assert_eq!(licenses.grant(bob, license.clone()), Ok(()));
+        assert!(licenses.is_licensed(&bob, 1, 9));
+        assert!(!licenses.is_licensed(&bob, 1, 10));
+        // a license with the same document is renewed
+        assert_eq!(licenses.grant(bob, License { expiry: None, ..license.clone() }), Ok(()));
+        assert_eq!(licenses.licenses_of(&bob).len(), 1);
+        assert!(licenses.is_licensed(&bob, 1, 10));
+        assert_eq!(licenses.revoke(bob, license.document), Ok(()));
+        assert_eq!(licenses.revoke(bob, license.document), Err(Error::LicenseNotFound));
+        assert!(!licenses.is_licensed(&bob, 1, 0));
+        licenses.regulated_set(1, false);
+        assert!(licenses.is_licensed(&bob, 1, 0));
+    }
+}
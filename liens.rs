@@ -0,0 +1,190 @@
+//! # Liens
+//!
+//! Pledges and liens registered on the assets in favour of their creditors.
+//!
+//! A creditor files a lien on an asset with the amount secured and the hash of the agreement,
+//! and the lien is in force once the owner consents to it. An asset with a lien in force cannot
+//! be transferred, sold, split or deleted until the creditor releases the lien, so a buyer reads
+//! the encumbrances of an asset before the purchase.
+//!
+
+use crate::asset_erc721::{
+    AssetId,
+    Error,
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Lien ID
+pub type LienId = u32;
+
+/// State of a lien
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub enum LienStatus {
+    /// Filed from the creditor, waiting for the consent of the owner
+    Pending,
+    /// In force, the asset cannot change owner
+    Active,
+    /// Released from the creditor
+    Released,
+    /// Rejected from the owner
+    Rejected,
+}
+
+/// Lien on an asset
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Lien {
+    /// Asset encumbered
+    pub id: AssetId,
+    /// Creditor secured from the asset
+    pub creditor: AccountId,
+    /// Amount secured
+    pub amount: Balance,
+    /// Hash of the agreement
+    pub document: Hash,
+    /// Block of the filing
+    pub block: BlockNumber,
+    /// State of the lien
+    pub status: LienStatus,
+}
+
+/// Storage of the liens
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Liens {
+    /// Liens indexed from 0 to the liens counter
+    liens: StorageHashMap<LienId, Lien>,
+    /// Counter of the liens filed
+    liens_count: u32,
+    /// Pending and active liens of the asset
+    asset_liens: StorageHashMap<AssetId, Vec<LienId>>,
+}
+
+impl Liens {
+    /// Returns lien `lien_id`
+    pub fn get(&self, lien_id: LienId) -> Option<Lien> {
+        self.liens.get(&lien_id).cloned()
+    }
+
+    /// Returns the pending and active liens of asset `id`
+    pub fn liens_of(&self, id: AssetId) -> Vec<(LienId, Lien)> {
+        self.asset_liens
+            .get(&id)
+            .map(|lien_ids| {
+                lien_ids
+                    .iter()
+                    .filter_map(|lien_id| self.get(*lien_id).map(|lien| (*lien_id, lien)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns true if asset `id` has a lien in force
+    pub fn is_encumbered(&self, id: AssetId) -> bool {
+        self.asset_liens.get(&id).is_some_and(|lien_ids| {
+            lien_ids
+                .iter()
+                .any(|lien_id| self.liens.get(lien_id).is_some_and(|lien| lien.status == LienStatus::Active))
+        })
+    }
+
+    /// Files the pending `lien`, and returns its id
+    pub fn file(&mut self, lien: Lien) -> LienId {
+        let lien_id = self.liens_count;
+        self.liens.insert(lien_id, Lien {
+            status: LienStatus::Pending,
+            ..lien
+        });
+        self.liens_count += 1;
+        let mut lien_ids = self.asset_liens.get(&lien.id).cloned().unwrap_or_default();
+        lien_ids.push(lien_id);
+        self.asset_liens.insert(lien.id, lien_ids);
+        lien_id
+    }
+
+    /// Moves lien `lien_id` from state `from` to state `to`, and returns it. A lien released or
+    /// rejected does not encumber the asset anymore.
+    pub fn update(&mut self, lien_id: LienId, from: &[LienStatus], to: LienStatus) -> Result<Lien, Error> {
+        let mut lien = self.get(lien_id).ok_or(Error::LienNotFound)?;
+        if !from.contains(&lien.status) {
+            return Err(Error::LienNotFound)
+        }
+        lien.status = to;
+        self.liens.insert(lien_id, lien);
+        if to == LienStatus::Released || to == LienStatus::Rejected {
+            let mut lien_ids = self.asset_liens.get(&lien.id).cloned().unwrap_or_default();
+            lien_ids.retain(|other| *other != lien_id);
+            if lien_ids.is_empty() {
+                self.asset_liens.take(&lien.id);
+            } else {
+                self.asset_liens.insert(lien.id, lien_ids);
+            }
+        }
+        Ok(lien)
+    }
+
+    /// Rejects the pending liens of the deleted asset `id`
+    pub fn remove(&mut self, id: AssetId) {
+        for lien_id in self.asset_liens.take(&id).unwrap_or_default() {
+            if let Some(lien) = self.liens.get_mut(&lien_id) {
+                lien.status = LienStatus::Rejected;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn liens_work() {
+        let lien = Lien {
+            id: 1,
+            creditor: AccountId::from([0x2; 32]),
+            amount: 1_000,
+            document: Hash::from([0x01; 32]),
+            block: 1,
+            status: LienStatus::Pending,
+        };
+        let mut liens = Liens::default();
+        assert_eq!(liens.file(lien), 0);
+        assert_eq!(liens.file(lien), 1);
+        // a pending lien does not encumber the asset
+        assert!(!liens.is_encumbered(1));
+        assert_eq!(liens.update(0, &[LienStatus::Pending], LienStatus::Active).map(|lien| lien.status), Ok(LienStatus::Active));
+        assert_eq!(liens.update(0, &[LienStatus::Pending], LienStatus::Active), Err(Error::LienNotFound));
+        assert!(liens.is_encumbered(1));
+        assert_eq!(liens.update(1, &[LienStatus::Pending], LienStatus::Rejected).map(|lien| lien.status), Ok(LienStatus::Rejected));
+        assert_eq!(liens.liens_of(1).iter().map(|(lien_id, _)| *lien_id).collect::<Vec<_>>(), [0]);
+        assert_eq!(liens.update(0, &[LienStatus::Pending, LienStatus::Active], LienStatus::Released).map(|lien| lien.status), Ok(LienStatus::Released));
+        assert!(!liens.is_encumbered(1));
+        assert!(liens.liens_of(1).is_empty());
+        assert_eq!(liens.get(0).map(|lien| lien.status), Some(LienStatus::Released));
+        assert_eq!(liens.update(7, &[LienStatus::Pending], LienStatus::Active), Err(Error::LienNotFound));
+    }
+}
@@ -0,0 +1,142 @@
+//! # Lineage
+//!
+//! Links between the assets made from other assets and their sources.
+//!
+//! The recyclers claim the materials of the disposed assets into new recycled-material assets:
+//! each recycled asset keeps the list of its source assets and each source points to the asset
+//! its materials were claimed into, so a recycled-content claim is verified by following the
+//! links back to the disposals of the sources. The materials of a source are claimed once.
+//!
+//! The owner of an asset refurbished as a new unit retires it and creates the remanufactured
+//! asset: the two assets point to each other, so the full history of the unit before the
+//! refurbishment is one hop away from the new asset.
+//!
+
+use crate::asset_erc721::{
+    AssetId,
+    Error,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::collections::HashMap as StorageHashMap;
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use ink_storage::traits::SpreadLayout;
+
+/// Storage of the lineage of the assets
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Lineage {
+    /// Source assets of the recycled asset
+    recycled_sources: StorageHashMap<AssetId, Vec<AssetId>>,
+    /// Recycled asset the materials of the source asset were claimed into
+    source_recycled: StorageHashMap<AssetId, AssetId>,
+    /// Asset the retired asset was remanufactured into
+    remanufactured_into: StorageHashMap<AssetId, AssetId>,
+    /// Retired asset the asset was remanufactured from
+    remanufactured_from: StorageHashMap<AssetId, AssetId>,
+}
+
+impl Lineage {
+    /// Returns the source assets of the recycled asset `id`
+    pub fn sources_of(&self, id: AssetId) -> Vec<AssetId> {
+        self.recycled_sources.get(&id).cloned().unwrap_or_default()
+    }
+
+    /// Returns the recycled asset the materials of asset `id` were claimed into
+    pub fn recycled_into(&self, id: AssetId) -> Option<AssetId> {
+        self.source_recycled.get(&id).cloned()
+    }
+
+    /// Fails if `sources` is empty, repeats an asset or contains an asset whose materials are already claimed
+    pub fn check_claimable(&self, sources: &[AssetId]) -> Result<(), Error> {
+        if sources.is_empty() {
+            return Err(Error::NotAllowed)
+        }
+        for (index, source) in sources.iter().enumerate() {
+            if sources[..index].contains(source) || self.source_recycled.contains_key(source) {
+                return Err(Error::MaterialsClaimed)
+            }
+        }
+        Ok(())
+    }
+
+    /// Links the recycled asset `id` to the `sources` its materials were claimed from
+    pub fn recycle(&mut self, id: AssetId, sources: Vec<AssetId>) -> Result<(), Error> {
+        self.check_claimable(&sources)?;
+        for source in sources.iter() {
+            self.source_recycled.insert(*source, id);
+        }
+        self.recycled_sources.insert(id, sources);
+        Ok(())
+    }
+
+    /// Returns the asset the retired asset `id` was remanufactured into
+    pub fn remanufactured_into(&self, id: AssetId) -> Option<AssetId> {
+        self.remanufactured_into.get(&id).cloned()
+    }
+
+    /// Returns the retired asset the asset `id` was remanufactured from
+    pub fn remanufactured_from(&self, id: AssetId) -> Option<AssetId> {
+        self.remanufactured_from.get(&id).cloned()
+    }
+
+    /// Links the retired asset `old_id` and the asset `new_id` remanufactured from it, an asset is remanufactured
+    /// once
+    pub fn remanufacture(&mut self, old_id: AssetId, new_id: AssetId) -> Result<(), Error> {
+        if self.remanufactured_into.contains_key(&old_id) || self.remanufactured_from.contains_key(&new_id) {
+            return Err(Error::DuplicatedData)
+        }
+        self.remanufactured_into.insert(old_id, new_id);
+        self.remanufactured_from.insert(new_id, old_id);
+        Ok(())
+    }
+
+    /// Removes the links of the deleted asset `id`: the materials of its sources can be claimed again and the
+    /// asset it was remanufactured from no longer points to it
+    pub fn remove(&mut self, id: AssetId) {
+        for source in self.recycled_sources.take(&id).unwrap_or_default() {
+            self.source_recycled.take(&source);
+        }
+        if let Some(old_id) = self.remanufactured_from.take(&id) {
+            self.remanufactured_into.take(&old_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn recycle_works() {
+        let mut lineage = Lineage::default();
+        assert_eq!(lineage.recycle(10, Vec::new()), Err(Error::NotAllowed));
+        assert_eq!(lineage.recycle(10, [1, 2, 1].to_vec()), Err(Error::MaterialsClaimed));
+        assert_eq!(lineage.recycle(10, [1, 2].to_vec()), Ok(()));
+        // the materials of a source are claimed once
+        assert_eq!(lineage.recycle(11, [2, 3].to_vec()), Err(Error::MaterialsClaimed));
+        assert_eq!(lineage.recycled_into(3), None);
+        assert_eq!(lineage.sources_of(10), [1, 2]);
+        assert_eq!(lineage.recycled_into(2), Some(10));
+        lineage.remove(10);
+        assert_eq!(lineage.recycled_into(2), None);
+        assert_eq!(lineage.recycle(11, [2, 3].to_vec()), Ok(()));
+    }
+
+    #[ink::test]
+    fn remanufacture_works() {
+        let mut lineage = Lineage::default();
+        assert_eq!(lineage.remanufacture(1, 2), Ok(()));
+        assert_eq!(lineage.remanufacture(1, 3), Err(Error::DuplicatedData));
+        assert_eq!(lineage.remanufacture(4, 2), Err(Error::DuplicatedData));
+        // the refurbished unit can be remanufactured again
+        assert_eq!(lineage.remanufacture(2, 3), Ok(()));
+        assert_eq!(lineage.remanufactured_into(1), Some(2));
+        assert_eq!(lineage.remanufactured_from(2), Some(1));
+        assert_eq!(lineage.remanufactured_into(2), Some(3));
+        lineage.remove(3);
+        assert_eq!(lineage.remanufactured_into(2), None);
+        assert_eq!(lineage.remanufactured_from(2), Some(1));
+    }
+}
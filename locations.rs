@@ -0,0 +1,151 @@
+//! # Locations
+//!
+//! Route of the assets, the append-only log of the locations recorded for each asset.
+//!
+//! Every location written for an asset is appended to its log with the account that recorded
+//! it, the block and the timestamp, so a shipper records the whole route of a shipment while
+//! the record of the asset keeps the latest point only. The log is indexed by asset and
+//! sequence from 0 and it is never rewritten. It keeps the last [`MAX_POINTS`] points of the
+//! route: the older ones are dropped as new points arrive, so the log removed with the asset
+//! stays bounded however long the route.
+//!
+
+use crate::asset_erc721::AssetId;
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+type Timestamp = <DefaultEnvironment as Environment>::Timestamp;
+
+/// Maximum number of points kept in the route of an asset
+pub const MAX_POINTS: u32 = 256;
+
+/// Point of the route of an asset
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct LocationPoint {
+    /// Coordinates of the location
+    pub location: Hash,
+    /// Account that recorded the location
+    pub recorder: AccountId,
+    /// Block of the record
+    pub block: BlockNumber,
+    /// Timestamp of the block of the record, in milliseconds
+    pub timestamp: Timestamp,
+}
+
+/// Storage of the routes of the assets
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Locations {
+    /// Counter of the points of each asset
+    asset_points_count: StorageHashMap<AssetId, u32>,
+    /// Last points of each asset, indexed by sequence up to the asset points counter
+    asset_points: StorageHashMap<(AssetId, u32), LocationPoint>,
+}
+
+impl Locations {
+    /// Returns the number of points recorded for asset `id`, including the points dropped from its route
+    pub fn points_count(&self, id: AssetId) -> u32 {
+        *self.asset_points_count.get(&id).unwrap_or(&0)
+    }
+
+    /// Returns the sequence of the oldest point kept in the route of asset `id`
+    pub fn points_first(&self, id: AssetId) -> u32 {
+        self.points_count(id).saturating_sub(MAX_POINTS)
+    }
+
+    /// Returns the points kept of asset `id` with sequence from `from` included to `to` excluded, oldest first
+    pub fn points_of(&self, id: AssetId, from: u32, to: u32) -> Vec<LocationPoint> {
+        (from.max(self.points_first(id))..to.min(self.points_count(id)))
+            .filter_map(|sequence| self.asset_points.get(&(id, sequence)).cloned())
+            .collect()
+    }
+
+    /// Appends `point` to the route of asset `id`, dropping the oldest point beyond [`MAX_POINTS`], and returns its sequence
+    pub fn append(&mut self, id: AssetId, point: LocationPoint) -> u32 {
+        let sequence = self.points_count(id);
+        self.asset_points.insert((id, sequence), point);
+        self.asset_points_count.insert(id, sequence + 1);
+        if sequence >= MAX_POINTS {
+            self.asset_points.take(&(id, sequence - MAX_POINTS));
+        }
+        sequence
+    }
+
+    /// Replaces `account` with `pseudonym` as the recorder of the points kept of asset `id`
+    pub fn pseudonymize(&mut self, id: AssetId, account: &AccountId, pseudonym: AccountId) {
+        for sequence in self.points_first(id)..self.points_count(id) {
+            if let Some(point) = self.asset_points.get_mut(&(id, sequence)) {
+                if point.recorder == *account {
+                    point.recorder = pseudonym;
+                }
+            }
+        }
+    }
+
+    /// Removes the route of the deleted asset `id`
+    pub fn remove(&mut self, id: AssetId) {
+        let first = self.points_first(id);
+        let count = self.asset_points_count.take(&id).unwrap_or(0);
+        for sequence in first..count {
+            self.asset_points.take(&(id, sequence));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    fn point(location: u8, block: BlockNumber) -> LocationPoint {
+        LocationPoint {
+            location: Hash::from([location; 32]),
+            recorder: AccountId::from([0x1; 32]),
+            block,
+            timestamp: 0,
+        }
+    }
+
+    #[ink::test]
+    fn route_works() {
+        let mut locations = Locations::default();
+        assert_eq!(locations.append(1, point(1, 1)), 0);
+        assert_eq!(locations.append(1, point(2, 2)), 1);
+        assert_eq!(locations.append(2, point(3, 2)), 0);
+        // the same location recorded again is a new point of the route
+        assert_eq!(locations.append(1, point(1, 3)), 2);
+        assert_eq!(locations.points_count(1), 3);
+        assert_eq!(locations.points_of(1, 1, 10), [point(2, 2), point(1, 3)]);
+        assert_eq!(locations.points_of(1, 0, 1), [point(1, 1)]);
+        locations.remove(1);
+        assert!(locations.points_of(1, 0, 10).is_empty());
+        assert_eq!(locations.points_count(2), 1);
+        // the route keeps the last points only
+        for block in 0..BlockNumber::from(MAX_POINTS) + 2 {
+            locations.append(3, point(1, block));
+        }
+        assert_eq!(locations.points_first(3), 2);
+        assert_eq!(locations.points_of(3, 0, 3), [point(1, 2)]);
+        assert_eq!(locations.points_of(3, 0, u32::MAX).len() as u32, MAX_POINTS);
+    }
+}
@@ -0,0 +1,308 @@
+//! # Metadata
+//!
+//! Descriptive data of the assets and the registry of the categories with their descriptions,
+//! counters and authorized producers. The hashes of the description, photo, location and
+//! additional metadata and the category of an asset are stored in its [`AssetRecord`].
+//!
+//! The description can be translated in more languages, the translations are keyed by the
+//! [`Locale`] code.
+//!
+//! The photos and the metadata are indexed by content: when the same content is stored for a
+//! second asset both the assets are flagged as possible duplicates.
+//!
+
+use crate::{
+    asset_erc721::{
+        AssetId,
+        Error,
+        UpdateKind,
+    },
+    records::AssetRecord,
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::SpreadLayout,
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Language code ISO 639-1 of a translation, for example `*b"en"`
+pub type Locale = [u8; 2];
+
+/// Maximum number of translations of the description of an asset
+pub const MAX_LOCALES: usize = 32;
+
+/// Storage of the descriptive data of the assets and of the categories
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Metadata {
+    /// Translations of the description of the asset
+    asset_description_locale: StorageHashMap<(AssetId, Locale), Hash>,
+    /// Languages of the translations of the description of the asset
+    asset_locales: StorageHashMap<AssetId, Vec<Locale>>,
+    /// Stores the id and description to the allowed categories of assets
+    category_description: StorageHashMap<u32, Hash>,
+    /// Counter of the assets assigned to each category
+    category_assets: StorageHashMap<u32, u32>,
+    /// Producers authorized to assign assets to a category
+    category_producer: StorageHashMap<(u32, AccountId), bool>,
+    /// Categories restricted to their authorized producers, even when no producer is authorized
+    category_restricted: StorageHashMap<u32, bool>,
+    /// Block of the last update of each kind of data of the assets
+    asset_last_update: StorageHashMap<(AssetId, UpdateKind), BlockNumber>,
+    /// Last asset that stored the content as photo or metadata
+    content_asset: StorageHashMap<Hash, AssetId>,
+    /// Assets flagged as possible duplicates of other assets
+    asset_duplicate: StorageHashMap<AssetId, bool>,
+}
+
+impl Metadata {
+    /// Returns the last asset that stored `content` as photo or metadata
+    pub fn content_asset(&self, content: &Hash) -> Option<AssetId> {
+        self.content_asset.get(content).cloned()
+    }
+
+    /// Indexes `content` stored as photo or metadata of asset `id`. When `other` asset still stores the same
+    /// content both the assets are flagged as possible duplicates, otherwise the content is indexed to `id`.
+    pub fn content_index(&mut self, id: AssetId, content: Hash, other: Option<AssetId>) {
+        match other {
+            Some(other) if other != id => {
+                self.asset_duplicate.insert(other, true);
+                self.asset_duplicate.insert(id, true);
+            }
+            _ => {
+                self.content_asset.insert(content, id);
+            }
+        }
+    }
+
+    /// Returns true if asset `id` is flagged as possible duplicate
+    pub fn is_duplicate(&self, id: AssetId) -> bool {
+        self.asset_duplicate.contains_key(&id)
+    }
+
+    /// Removes the duplicate flag of asset `id`, returns false if it was not flagged
+    pub fn duplicate_clear(&mut self, id: AssetId) -> bool {
+        self.asset_duplicate.take(&id).is_some()
+    }
+
+    /// Returns the translation of the description of asset `id` in language `locale`
+    pub fn description_locale(&self, id: AssetId, locale: Locale) -> Option<Hash> {
+        self.asset_description_locale.get(&(id, locale)).cloned()
+    }
+
+    /// Returns the languages of the translations of the description of asset `id`
+    pub fn description_locales(&self, id: AssetId) -> Vec<Locale> {
+        self.asset_locales.get(&id).cloned().unwrap_or_default()
+    }
+
+    /// Stores or replaces the translation of the description of asset `id` in language `locale`
+    pub fn description_locale_set(&mut self, id: AssetId, locale: Locale, value: Hash) -> Result<(), Error> {
+        let mut locales = self.description_locales(id);
+        if !locales.contains(&locale) {
+            if locales.len() >= MAX_LOCALES {
+                return Err(Error::CannotInsert)
+            }
+            locales.push(locale);
+            self.asset_locales.insert(id, locales);
+        }
+        self.asset_description_locale.insert((id, locale), value);
+        Ok(())
+    }
+
+    /// Removes the translation of the description of asset `id` in language `locale`
+    pub fn description_locale_remove(&mut self, id: AssetId, locale: Locale) -> Result<(), Error> {
+        self.asset_description_locale
+            .take(&(id, locale))
+            .ok_or(Error::AssetNotFound)?;
+        let mut locales = self.description_locales(id);
+        locales.retain(|l| *l != locale);
+        if locales.is_empty() {
+            self.asset_locales.take(&id);
+        } else {
+            self.asset_locales.insert(id, locales);
+        }
+        Ok(())
+    }
+
+    /// Assigns the asset of `record` to the existing category `categoryid`
+    pub fn category_assign(&mut self, record: &mut AssetRecord, categoryid: u32) -> Result<(), Error> {
+        if !self.category_description.contains_key(&categoryid) {
+            return Err(Error::CategoryNotFound)
+        }
+        if record.category.is_some() {
+            return Err(Error::DuplicatedData)
+        }
+        record.category = Some(categoryid);
+        self.category_assets.entry(categoryid).and_modify(|v| *v += 1).or_insert(1);
+        Ok(())
+    }
+
+    /// Removes the asset of `record` from its category and returns the category
+    pub fn category_unassign(&mut self, record: &mut AssetRecord) -> Option<u32> {
+        let categoryid = record.category.take()?;
+        if let Some(count) = self.category_assets.get_mut(&categoryid) {
+            *count = count.saturating_sub(1);
+        }
+        Some(categoryid)
+    }
+
+    /// Returns the number of assets assigned to category `categoryid`
+    pub fn category_assets(&self, categoryid: u32) -> u32 {
+        *self.category_assets.get(&categoryid).unwrap_or(&0)
+    }
+
+    /// Returns the description of category `categoryid`
+    pub fn category_description(&self, categoryid: u32) -> Option<Hash> {
+        self.category_description.get(&categoryid).cloned()
+    }
+
+    /// Stores the description of the new category `categoryid`
+    pub fn category_description_insert(&mut self, categoryid: u32, description: Hash) -> Result<(), Error> {
+        if self.category_description.contains_key(&categoryid) {
+            return Err(Error::DuplicatedData)
+        }
+        self.category_description.insert(categoryid, description);
+        Ok(())
+    }
+
+    /// Removes the description of category `categoryid`
+    pub fn category_description_remove(&mut self, categoryid: u32) -> Result<(), Error> {
+        self.category_description
+            .take(&categoryid)
+            .map(|_| ())
+            .ok_or(Error::CategoryNotFound)
+    }
+
+    /// Authorizes or revokes `producer` to assign assets to category `categoryid`
+    pub fn category_producer_set(&mut self, categoryid: u32, producer: AccountId, authorized: bool) -> Result<(), Error> {
+        if !self.category_description.contains_key(&categoryid) {
+            return Err(Error::CategoryNotFound)
+        }
+        let key = (categoryid, producer);
+        match (authorized, self.category_producer.contains_key(&key)) {
+            (true, false) => {
+                self.category_producer.insert(key, true);
+                self.category_restricted.insert(categoryid, true);
+            }
+            (false, true) => {
+                self.category_producer.take(&key);
+            }
+            _ => return Err(Error::DuplicatedData),
+        }
+        Ok(())
+    }
+
+    /// Returns true if `producer` is authorized to assign assets to category `categoryid`
+    pub fn category_producer_verify(&self, categoryid: u32, producer: &AccountId) -> bool {
+        self.category_producer.contains_key(&(categoryid, *producer))
+    }
+
+    /// Returns true if only the authorized producers can assign assets to category `categoryid`
+    pub fn category_restricted(&self, categoryid: u32) -> bool {
+        self.category_restricted.contains_key(&categoryid)
+    }
+
+    /// Restricts category `categoryid` to its authorized producers or opens it to every producer
+    pub fn category_restricted_set(&mut self, categoryid: u32, restricted: bool) -> Result<(), Error> {
+        if !self.category_description.contains_key(&categoryid) {
+            return Err(Error::CategoryNotFound)
+        }
+        if restricted {
+            self.category_restricted.insert(categoryid, true);
+        } else {
+            self.category_restricted.take(&categoryid);
+        }
+        Ok(())
+    }
+
+    /// Returns the block of the last update of data `kind` of asset `id`
+    pub fn last_update(&self, id: AssetId, kind: UpdateKind) -> Option<BlockNumber> {
+        self.asset_last_update.get(&(id, kind)).cloned()
+    }
+
+    /// Records `block` as the last update of data `kind` of asset `id`
+    pub fn record_update(&mut self, id: AssetId, kind: UpdateKind, block: BlockNumber) {
+        self.asset_last_update.insert((id, kind), block);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn description_locales_work() {
+        let mut metadata = Metadata::default();
+        let english = Hash::from([0x01; 32]);
+        let italian = Hash::from([0x02; 32]);
+        assert_eq!(metadata.description_locale_set(1, *b"en", italian), Ok(()));
+        assert_eq!(metadata.description_locale_set(1, *b"en", english), Ok(()));
+        assert_eq!(metadata.description_locale_set(1, *b"it", italian), Ok(()));
+        assert_eq!(metadata.description_locale(1, *b"en"), Some(english));
+        assert_eq!(metadata.description_locales(1), [*b"en", *b"it"]);
+        assert_eq!(metadata.description_locale_remove(1, *b"en"), Ok(()));
+        assert_eq!(metadata.description_locale_remove(1, *b"en"), Err(Error::AssetNotFound));
+        assert_eq!(metadata.description_locales(1), [*b"it"]);
+        assert!(metadata.description_locales(2).is_empty());
+    }
+
+    #[ink::test]
+    fn duplicates_are_flagged() {
+        let mut metadata = Metadata::default();
+        let hash = Hash::from([0x01; 32]);
+        metadata.content_index(1, hash, None);
+        assert_eq!(metadata.content_asset(&hash), Some(1));
+        // the same asset is not a duplicate of itself
+        metadata.content_index(1, hash, Some(1));
+        assert!(!metadata.is_duplicate(1));
+        // the same content stored by another asset is a duplicate
+        metadata.content_index(2, hash, Some(1));
+        assert!(metadata.is_duplicate(1));
+        assert!(metadata.is_duplicate(2));
+        assert!(metadata.duplicate_clear(1));
+        assert!(!metadata.duplicate_clear(1));
+        // a content not stored anymore from its asset is indexed to the new asset
+        metadata.content_index(3, hash, None);
+        assert_eq!(metadata.content_asset(&hash), Some(3));
+        assert!(!metadata.is_duplicate(3));
+    }
+
+    #[ink::test]
+    fn categories_work() {
+        let producer = AccountId::from([0x1; 32]);
+        let mut metadata = Metadata::default();
+        let mut first = AssetRecord::new(producer, 0);
+        let mut second = AssetRecord::new(producer, 1);
+        assert_eq!(metadata.category_assign(&mut first, 7), Err(Error::CategoryNotFound));
+        assert_eq!(metadata.category_description_insert(7, Hash::from([0x07; 32])), Ok(()));
+        assert_eq!(metadata.category_assign(&mut first, 7), Ok(()));
+        assert_eq!(metadata.category_assign(&mut first, 7), Err(Error::DuplicatedData));
+        assert_eq!(metadata.category_assign(&mut second, 7), Ok(()));
+        assert_eq!(metadata.category_assets(7), 2);
+        assert_eq!(metadata.category_unassign(&mut first), Some(7));
+        assert_eq!(metadata.category_unassign(&mut first), None);
+        assert_eq!(metadata.category_assets(7), 1);
+        // the first authorized producer restricts the category, until it is opened explicitly
+        assert!(!metadata.category_restricted(7));
+        assert_eq!(metadata.category_producer_set(7, producer, true), Ok(()));
+        assert!(metadata.category_restricted(7));
+        assert!(metadata.category_producer_verify(7, &producer));
+        assert_eq!(metadata.category_producer_set(7, producer, true), Err(Error::DuplicatedData));
+        assert_eq!(metadata.category_producer_set(7, producer, false), Ok(()));
+        assert!(metadata.category_restricted(7));
+        assert_eq!(metadata.category_restricted_set(7, false), Ok(()));
+        assert!(!metadata.category_restricted(7));
+        assert_eq!(metadata.category_restricted_set(8, true), Err(Error::CategoryNotFound));
+    }
+}
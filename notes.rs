@@ -0,0 +1,131 @@
+//! # Notes
+//!
+//! Free notes appended to the assets from the participants of the supply chain.
+//!
+//! The owner, the shippers and the inspectors of an asset append the hash of a short note, like
+//! the remarks of a handover, that does not fit any structured field. Each note keeps its author
+//! with the role the author had when writing it. The log is append-only: the notes are never
+//! changed and stay with the asset when it is transferred.
+//!
+
+use crate::{
+    access_control::Role,
+    asset_erc721::AssetId,
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Note on an asset
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Note {
+    /// Author of the note
+    pub author: AccountId,
+    /// Role of the author when writing the note, `None` for the accounts with no role
+    pub role: Option<Role>,
+    /// Hash of the content of the note
+    pub content: Hash,
+    /// Block of the note
+    pub block: BlockNumber,
+}
+
+/// Storage of the notes of the assets
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Notes {
+    /// Counter of the notes of each asset
+    asset_notes_count: StorageHashMap<AssetId, u32>,
+    /// List of the notes of each asset, indexed from 0 to the asset notes counter
+    asset_notes: StorageHashMap<(AssetId, u32), Note>,
+}
+
+impl Notes {
+    /// Returns the number of notes of asset `id`
+    pub fn notes_count(&self, id: AssetId) -> u32 {
+        *self.asset_notes_count.get(&id).unwrap_or(&0)
+    }
+
+    /// Returns at most `limit` notes of asset `id` starting from position `offset`, oldest first
+    pub fn notes_of(&self, id: AssetId, offset: u32, limit: u32) -> Vec<Note> {
+        (offset..self.notes_count(id))
+            .take(limit as usize)
+            .filter_map(|index| self.asset_notes.get(&(id, index)).cloned())
+            .collect()
+    }
+
+    /// Appends `note` to the log of asset `id`
+    pub fn append(&mut self, id: AssetId, note: Note) {
+        let index = self.notes_count(id);
+        self.asset_notes.insert((id, index), note);
+        self.asset_notes_count.insert(id, index + 1);
+    }
+
+    /// Replaces `account` with `pseudonym` as the author of the notes of asset `id`
+    pub fn pseudonymize(&mut self, id: AssetId, account: &AccountId, pseudonym: AccountId) {
+        for index in 0..self.notes_count(id) {
+            if let Some(note) = self.asset_notes.get_mut(&(id, index)) {
+                if note.author == *account {
+                    note.author = pseudonym;
+                }
+            }
+        }
+    }
+
+    /// Removes the log of the deleted asset `id`
+    pub fn remove(&mut self, id: AssetId) {
+        let count = self.asset_notes_count.take(&id).unwrap_or(0);
+        for index in 0..count {
+            self.asset_notes.take(&(id, index));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    fn note(content: u8) -> Note {
+        Note {
+            author: AccountId::from([0x1; 32]),
+            role: None,
+            content: Hash::from([content; 32]),
+            block: 1,
+        }
+    }
+
+    #[ink::test]
+    fn notes_work() {
+        let mut notes = Notes::default();
+        notes.append(1, note(1));
+        notes.append(1, note(2));
+        notes.append(2, note(3));
+        assert_eq!(notes.notes_count(1), 2);
+        assert_eq!(notes.notes_of(1, 0, 10), [note(1), note(2)]);
+        assert_eq!(notes.notes_of(1, 1, 10), [note(2)]);
+        notes.remove(1);
+        assert_eq!(notes.notes_count(1), 0);
+        assert!(notes.notes_of(1, 0, 10).is_empty());
+        assert_eq!(notes.notes_of(2, 0, 1), [note(3)]);
+    }
+}
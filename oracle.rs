@@ -0,0 +1,73 @@
+//! # Oracle
+//!
+//! Calls to the price-oracle contracts converting the fiat reference units of the listings
+//! into the settlement token.
+//!
+//! The oracle contracts expose `rate_get(currency: Currency, token: Option<AccountId>) -> Option<Balance>`:
+//! the amount of the smallest unit of the settlement token (`None` for the native token) worth
+//! one smallest unit of `currency` (e.g. one cent), as a fixed-point number scaled by [`RATE_SCALE`].
+//!
+
+use crate::asset_erc721::Error;
+use ink_env::{
+    call::{
+        build_call,
+        utils::ReturnType,
+        ExecutionInput,
+        Selector,
+    },
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+};
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+
+/// Fiat reference unit, as its ISO 4217 alphabetic code e.g. `*b"EUR"`
+pub type Currency = [u8; 3];
+
+/// Scale of the rates returned from the oracles
+pub const RATE_SCALE: Balance = 1_000_000_000;
+
+/// Selector of the `rate_get` message of the oracle contracts
+const RATE_GET: [u8; 4] = [0x68, 0xbf, 0x69, 0xc4];
+
+/// Returns the rate of `currency` in the settlement `token` published from `oracle`
+pub fn rate_of(oracle: AccountId, currency: Currency, token: Option<AccountId>) -> Result<Balance, Error> {
+    let rate = build_call::<DefaultEnvironment>()
+        .callee(oracle)
+        .gas_limit(0)
+        .transferred_value(0)
+        .exec_input(
+            ExecutionInput::new(Selector::new(RATE_GET))
+                .push_arg(currency)
+                .push_arg(token),
+        )
+        .returns::<ReturnType<Option<Balance>>>()
+        .fire();
+    match rate {
+        Ok(Some(rate)) if rate > 0 => Ok(rate),
+        _ => Err(Error::RateUnavailable),
+    }
+}
+
+/// Returns the settlement amount of `price` reference units at `rate`, rounded up
+pub fn convert(price: Balance, rate: Balance) -> Option<Balance> {
+    let scaled = price.checked_mul(rate)?;
+    Some(scaled / RATE_SCALE + if scaled % RATE_SCALE > 0 { 1 } else { 0 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_works() {
+        // 1 cent is worth 2.5 units of the token
+        assert_eq!(convert(1000, 2_500_000_000), Some(2500));
+        // the seller never gets less than the price
+        assert_eq!(convert(3, 333_333_333), Some(1));
+        assert_eq!(convert(0, 333_333_333), Some(0));
+        assert_eq!(convert(Balance::MAX, 2), None);
+    }
+}
@@ -0,0 +1,335 @@
+//! # Ownership
+//!
+//! Records of the assets and the list of the assets owned from each account.
+//!
+//! The assets of an account are kept in a list indexed from 0 to the number of owned assets,
+//! and the [`AssetRecord`] of each asset stores its owner with its position in the list, so an
+//! asset can be removed by moving the last asset of the list in its place.
+//!
+//! The list is stored in pages of [`PAGE_SIZE`] assets, one storage cell each. Adding an asset
+//! writes the last page and removing it writes at most the page of the asset and the last page,
+//! whatever the number of assets owned, while the enumeration reads one cell for each page
+//! instead of one for each asset.
+//!
+//! The counter of the assets of an account is stored in its [`Holding`] with the grace period
+//! of its transfers, read by every transfer with no further cell.
+//!
+
+use crate::{
+    asset_erc721::{
+        AssetId,
+        Error,
+    },
+    records::AssetRecord,
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::{
+        hashmap::Entry,
+        HashMap as StorageHashMap,
+    },
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Number of assets stored in a page of the list of the assets of an account
+pub const PAGE_SIZE: u32 = 16;
+
+/// Assets owned from an account and the grace period of its transfers
+#[derive(Encode, Decode, Debug, Default, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Holding {
+    /// Number of assets owned
+    pub count: u32,
+    /// Blocks the transfers of the account stay pending before they finalize, 0 for the instant transfers
+    pub grace_period: BlockNumber,
+}
+
+/// Storage of the records and of the owners of the assets
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Ownership {
+    /// Record of the asset, with its owner
+    assets: StorageHashMap<AssetId, AssetRecord>,
+    /// Counter of the assets owned from the accounts and grace period of their transfers
+    account_owned_assets: StorageHashMap<AccountId, Holding>,
+    /// Pages of the list of the assets owned from the accounts, the asset at position `index` is in page
+    /// `index / PAGE_SIZE`
+    account_assets: StorageHashMap<(AccountId, u32), Vec<AssetId>>,
+}
+
+impl Ownership {
+    /// Returns the owner of asset `id`
+    pub fn owner_of(&self, id: AssetId) -> Option<AccountId> {
+        self.assets.get(&id).map(|record| record.owner)
+    }
+
+    /// Returns true if asset `id` exists
+    pub fn exists(&self, id: AssetId) -> bool {
+        self.assets.contains_key(&id)
+    }
+
+    /// Returns the record of asset `id`
+    pub fn record_of(&self, id: AssetId) -> Option<&AssetRecord> {
+        self.assets.get(&id)
+    }
+
+    /// Returns the record of asset `id` to update it in place
+    pub fn record_mut(&mut self, id: AssetId) -> Result<&mut AssetRecord, Error> {
+        self.assets.get_mut(&id).ok_or(Error::AssetNotFound)
+    }
+
+    /// Returns the number of assets owned from `owner`
+    pub fn balance_of(&self, owner: &AccountId) -> u32 {
+        self.account_owned_assets.get(owner).map_or(0, |holding| holding.count)
+    }
+
+    /// Returns the grace period of the transfers of `owner`
+    pub fn grace_period_of(&self, owner: &AccountId) -> BlockNumber {
+        self.account_owned_assets.get(owner).map_or(0, |holding| holding.grace_period)
+    }
+
+    /// Sets the grace period of the transfers of `owner`, 0 for the instant transfers
+    pub fn grace_period_set(&mut self, owner: AccountId, grace_period: BlockNumber) {
+        match self.account_owned_assets.get_mut(&owner) {
+            Some(holding) => holding.grace_period = grace_period,
+            None => {
+                self.account_owned_assets.insert(owner, Holding { count: 0, grace_period });
+            }
+        }
+    }
+
+    /// Returns at most `limit` assets owned from `owner` starting from position `offset`
+    pub fn assets_of(&self, owner: &AccountId, offset: u32, limit: u32) -> Vec<AssetId> {
+        let end = self.balance_of(owner).min(offset.saturating_add(limit));
+        let mut assets = Vec::new();
+        let mut index = offset;
+        while index < end {
+            let page = match self.account_assets.get(&(*owner, index / PAGE_SIZE)) {
+                Some(page) => page,
+                None => break,
+            };
+            let start = (index % PAGE_SIZE) as usize;
+            let take = (end - index).min(PAGE_SIZE - index % PAGE_SIZE) as usize;
+            assets.extend(page.iter().skip(start).take(take));
+            index += take as u32;
+        }
+        assets
+    }
+
+    /// Assigns the new asset `id` to `to`, appending it at the end of the list of its assets.
+    pub fn add(&mut self, to: &AccountId, id: AssetId) -> Result<(), Error> {
+        let vacant_asset = match self.assets.entry(id) {
+            Entry::Vacant(vacant) => vacant,
+            Entry::Occupied(_) => return Err(Error::AssetExists),
+        };
+        if *to == AccountId::from([0x0; 32]) {
+            return Err(Error::NotAllowed)
+        };
+        let index = Self::holding_push(&mut self.account_owned_assets, to);
+        vacant_asset.insert(AssetRecord::new(*to, index));
+        self.list_push(to, index, id);
+        Ok(())
+    }
+
+    /// Removes asset `id` from `from`, moving the last asset of its list in the freed position.
+    /// Returns the record of the asset.
+    pub fn remove(&mut self, from: &AccountId, id: AssetId) -> Result<AssetRecord, Error> {
+        if self.owner_of(id).as_ref() != Some(from) {
+            return Err(Error::AssetNotFound)
+        }
+        let record = self.assets.take(&id).ok_or(Error::AssetNotFound)?;
+        self.list_remove(from, Some(record.index))?;
+        Ok(record)
+    }
+
+    /// Moves asset `id` from `from` to `to`. The owner and the position of the asset are updated in place, so
+    /// the rest of the record and the entries of the maps keyed by the asset are kept.
+    pub fn transfer(&mut self, from: &AccountId, to: &AccountId, id: AssetId) -> Result<(), Error> {
+        if *to == AccountId::from([0x0; 32]) {
+            return Err(Error::NotAllowed)
+        }
+        let index = match self.assets.get(&id) {
+            Some(record) if record.owner == *from => record.index,
+            _ => return Err(Error::AssetNotFound),
+        };
+        self.list_remove(from, Some(index))?;
+        let to_index = Self::holding_push(&mut self.account_owned_assets, to);
+        self.list_push(to, to_index, id);
+        if let Some(record) = self.assets.get_mut(&id) {
+            record.owner = *to;
+            record.index = to_index;
+        }
+        Ok(())
+    }
+
+    /// Removes the asset at position `index` from the list of `from`, moving the last asset of the list in its place.
+    fn list_remove(&mut self, from: &AccountId, index: Option<u32>) -> Result<(), Error> {
+        let holding = self
+            .account_owned_assets
+            .get_mut(from)
+            .ok_or(Error::CannotFetchValue)?;
+        holding.count -= 1;
+        let last = holding.count;
+        let index = index.unwrap_or(last);
+        let last_key = (*from, last / PAGE_SIZE);
+        let last_id = self.account_assets.get_mut(&last_key).and_then(|page| page.pop());
+        if last % PAGE_SIZE == 0 {
+            self.account_assets.take(&last_key);
+        }
+        if let Some(last_id) = last_id {
+            if index != last {
+                if let Some(slot) = self
+                    .account_assets
+                    .get_mut(&(*from, index / PAGE_SIZE))
+                    .and_then(|page| page.get_mut((index % PAGE_SIZE) as usize))
+                {
+                    *slot = last_id;
+                }
+                if let Some(record) = self.assets.get_mut(&last_id) {
+                    record.index = index;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Counts one more asset owned from `to` in `holdings` and returns the position of the asset at the end of its list.
+    fn holding_push(holdings: &mut StorageHashMap<AccountId, Holding>, to: &AccountId) -> u32 {
+        match holdings.get_mut(to) {
+            Some(holding) => {
+                holding.count += 1;
+                holding.count - 1
+            }
+            None => {
+                holdings.insert(*to, Holding { count: 1, grace_period: 0 });
+                0
+            }
+        }
+    }
+
+    /// Appends asset `id` at position `index`, the end of the list of `to`.
+    fn list_push(&mut self, to: &AccountId, index: u32, id: AssetId) {
+        let key = (*to, index / PAGE_SIZE);
+        match self.account_assets.get_mut(&key) {
+            Some(page) => page.push(id),
+            None => {
+                self.account_assets.insert(key, ink_prelude::vec![id]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn add_works() {
+        let alice = AccountId::from([0x1; 32]);
+        let mut ownership = Ownership::default();
+        assert_eq!(ownership.add(&alice, 1), Ok(()));
+        assert_eq!(ownership.add(&alice, 2), Ok(()));
+        assert_eq!(ownership.owner_of(1), Some(alice));
+        assert_eq!(ownership.balance_of(&alice), 2);
+        assert_eq!(ownership.assets_of(&alice, 0, 10), [1, 2]);
+        // an asset cannot be added twice or to the zero account
+        assert_eq!(ownership.add(&alice, 1), Err(Error::AssetExists));
+        assert_eq!(ownership.add(&AccountId::from([0x0; 32]), 3), Err(Error::NotAllowed));
+        // the grace period of the transfers is kept next to the counter of the assets
+        ownership.grace_period_set(alice, 5);
+        assert_eq!(ownership.grace_period_of(&alice), 5);
+        assert_eq!(ownership.balance_of(&alice), 2);
+        ownership.grace_period_set(AccountId::from([0x2; 32]), 5);
+        assert_eq!(ownership.balance_of(&AccountId::from([0x2; 32])), 0);
+    }
+
+    #[ink::test]
+    fn remove_keeps_the_list_compact() {
+        let alice = AccountId::from([0x1; 32]);
+        let bob = AccountId::from([0x2; 32]);
+        let mut ownership = Ownership::default();
+        for id in 1..=3 {
+            assert_eq!(ownership.add(&alice, id), Ok(()));
+        }
+        // only the owner can lose the asset
+        assert_eq!(ownership.remove(&bob, 1), Err(Error::AssetNotFound));
+        assert_eq!(ownership.remove(&alice, 1).map(|record| record.owner), Ok(alice));
+        assert!(!ownership.exists(1));
+        assert_eq!(ownership.balance_of(&alice), 2);
+        // the last asset takes the place of the removed one
+        assert_eq!(ownership.assets_of(&alice, 0, 10), [3, 2]);
+        assert_eq!(ownership.assets_of(&alice, 1, 1), [2]);
+        assert_eq!(ownership.remove(&alice, 3).map(|record| record.index), Ok(0));
+        assert_eq!(ownership.assets_of(&alice, 0, 10), [2]);
+    }
+
+    #[ink::test]
+    fn pages_stay_compact() {
+        let alice = AccountId::from([0x1; 32]);
+        let bob = AccountId::from([0x2; 32]);
+        let count = PAGE_SIZE * 2 + 3;
+        let mut ownership = Ownership::default();
+        for id in 0..count {
+            assert_eq!(ownership.add(&alice, id), Ok(()));
+        }
+        assert_eq!(ownership.assets_of(&alice, 0, count), (0..count).collect::<Vec<_>>());
+        // the enumeration crosses the pages
+        assert_eq!(ownership.assets_of(&alice, PAGE_SIZE - 1, 3), [PAGE_SIZE - 1, PAGE_SIZE, PAGE_SIZE + 1]);
+        // the last asset of the last page takes the place of the one leaving the first page
+        assert_eq!(ownership.transfer(&alice, &bob, 1), Ok(()));
+        assert_eq!(ownership.assets_of(&alice, 1, 1), [count - 1]);
+        assert_eq!(ownership.record_of(count - 1).map(|record| record.index), Some(1));
+        // the emptied pages are removed and filled again
+        for id in (PAGE_SIZE * 2)..(count - 1) {
+            assert_eq!(ownership.remove(&alice, id).map(|_| ()), Ok(()));
+        }
+        assert_eq!(ownership.balance_of(&alice), PAGE_SIZE * 2);
+        assert_eq!(ownership.transfer(&bob, &alice, 1), Ok(()));
+        assert_eq!(ownership.assets_of(&alice, PAGE_SIZE * 2, 10), [1]);
+        let mut assets = ownership.assets_of(&alice, 0, count);
+        assets.sort_unstable();
+        assert_eq!(assets, (0..PAGE_SIZE * 2).chain(Some(count - 1)).collect::<Vec<_>>());
+    }
+
+    #[ink::test]
+    fn transfer_works() {
+        let alice = AccountId::from([0x1; 32]);
+        let bob = AccountId::from([0x2; 32]);
+        let mut ownership = Ownership::default();
+        for id in 1..=3 {
+            assert_eq!(ownership.add(&alice, id), Ok(()));
+        }
+        assert_eq!(ownership.transfer(&bob, &alice, 1), Err(Error::AssetNotFound));
+        assert_eq!(ownership.transfer(&alice, &AccountId::from([0x0; 32]), 1), Err(Error::NotAllowed));
+        assert_eq!(ownership.record_mut(1).and_then(|record| record.validate(alice, alice)), Ok(()));
+        assert_eq!(ownership.transfer(&alice, &bob, 1), Ok(()));
+        assert_eq!(ownership.transfer(&alice, &bob, 3), Ok(()));
+        assert_eq!(ownership.owner_of(1), Some(bob));
+        // the rest of the record moves with the asset
+        assert_eq!(ownership.record_of(1).and_then(|record| record.validator), Some(alice));
+        assert_eq!(ownership.assets_of(&alice, 0, 10), [2]);
+        assert_eq!(ownership.assets_of(&bob, 0, 10), [1, 3]);
+        // the positions stay consistent for the next removals
+        assert_eq!(ownership.remove(&bob, 1).map(|record| record.index), Ok(0));
+        assert_eq!(ownership.assets_of(&bob, 0, 10), [3]);
+        assert_eq!(ownership.transfer(&bob, &bob, 3), Ok(()));
+        assert_eq!(ownership.assets_of(&bob, 0, 10), [3]);
+    }
+}
@@ -0,0 +1,61 @@
+//! # Pagination
+//!
+//! Common shape of the messages returning lists.
+//!
+//! Every list is read in pages: the message takes the cursor where the page starts, 0 for the
+//! first page, and the maximum number of items, capped to [`PAGE_LIMIT`] so no call can exceed
+//! the size limit of the return value. It returns the items with the cursor of the next page,
+//! `None` when the list is over, so the clients read any list with the same loop.
+//!
+
+use ink_prelude::vec::Vec;
+
+/// Position in a list where a page starts
+pub type Cursor = u32;
+
+/// Page of a list: the items and the cursor of the next page, `None` at the end of the list
+pub type Page<T> = (Vec<T>, Option<Cursor>);
+
+/// Maximum number of items returned in a page
+pub const PAGE_LIMIT: u32 = 100;
+
+/// Returns `limit` capped to [`PAGE_LIMIT`]
+pub fn limit(limit: u32) -> u32 {
+    limit.min(PAGE_LIMIT)
+}
+
+/// Returns the page of `items` read from position `cursor` of a list of `len` items
+pub fn page<T>(items: Vec<T>, cursor: Cursor, len: u32) -> Page<T> {
+    let next = cursor.saturating_add(items.len() as u32);
+    let next = if !items.is_empty() && next < len { Some(next) } else { None };
+    (items, next)
+}
+
+/// Returns the page of at most `limit` items from position `cursor` of the list `items`, read whole
+pub fn page_of<T>(items: Vec<T>, cursor: Cursor, limit: u32) -> Page<T> {
+    let len = items.len() as u32;
+    let items = items
+        .into_iter()
+        .skip(cursor as usize)
+        .take(self::limit(limit) as usize)
+        .collect();
+    page(items, cursor, len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_work() {
+        let items: Vec<u32> = (0..5).collect();
+        assert_eq!(page_of(items.clone(), 0, 2), (ink_prelude::vec![0, 1], Some(2)));
+        assert_eq!(page_of(items.clone(), 2, 2), (ink_prelude::vec![2, 3], Some(4)));
+        assert_eq!(page_of(items.clone(), 4, 2), (ink_prelude::vec![4], None));
+        assert_eq!(page_of(items.clone(), 7, 2), (Vec::new(), None));
+        // a limit of 0 returns no item and no next page
+        assert_eq!(page_of(items, 0, 0), (Vec::new(), None));
+        let long: Vec<u32> = (0..PAGE_LIMIT * 2).collect();
+        assert_eq!(page_of(long, 0, u32::MAX).1, Some(PAGE_LIMIT));
+    }
+}
@@ -0,0 +1,74 @@
+//! # Pending transfers
+//!
+//! Transfers held back for a grace period before they finalize.
+//!
+//! An owner can opt in to a grace period of a number of blocks: its outgoing transfers do not
+//! move the asset at once but leave it pending for the recipient until the end of the period.
+//! Meanwhile the owner can cancel the transfer sent by mistake, and once the period is over
+//! anyone finalizes it moving the asset. The owners with no grace period transfer instantly.
+//! The pending transfer is kept in the record of the asset, and the grace period of the owner
+//! next to the counter of its assets, so the instant transfers read nothing more.
+//!
+
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+};
+use ink_storage::traits::{
+    PackedLayout,
+    SpreadLayout,
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Maximum grace period of the transfers, one week of 6 seconds blocks
+pub const MAX_GRACE_PERIOD: BlockNumber = 100_800;
+
+/// Transfer of an asset waiting for the end of the grace period of its owner
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct PendingTransfer {
+    /// Recipient of the asset
+    pub to: AccountId,
+    /// Last block the owner can cancel the transfer
+    pub until: BlockNumber,
+}
+
+impl PendingTransfer {
+    /// Returns the transfer to `to` sent at block `now` with a grace period of `grace_period` blocks
+    pub fn new(to: AccountId, now: BlockNumber, grace_period: BlockNumber) -> Self {
+        Self {
+            to,
+            until: now.saturating_add(grace_period),
+        }
+    }
+
+    /// Returns true if the transfer can be finalized at block `now`, once the grace period is over
+    pub fn is_final(&self, now: BlockNumber) -> bool {
+        now > self.until
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn pending_transfer_works() {
+        let bob = AccountId::from([0x2; 32]);
+        let transfer = PendingTransfer::new(bob, 10, 5);
+        assert_eq!(transfer.until, 15);
+        // the owner can cancel until the last block of the grace period
+        assert!(!transfer.is_final(15));
+        assert!(transfer.is_final(16));
+        assert_eq!(PendingTransfer::new(bob, BlockNumber::MAX, 5).until, BlockNumber::MAX);
+    }
+}
@@ -0,0 +1,98 @@
+//! # Physical
+//!
+//! Physical attributes of the assets: weight, dimensions and packaging, stored as structured
+//! values so the shippers and the customs can read them without resolving the metadata hash.
+//!
+
+use crate::asset_erc721::AssetId;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+/// Packaging of a product
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub enum Packaging {
+    Unpacked,
+    Box,
+    Bag,
+    Crate,
+    Drum,
+    Pallet,
+    Container,
+}
+
+/// Physical attributes of an asset
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct PhysicalAttributes {
+    /// Gross weight in grams
+    pub weight: u64,
+    /// Length in millimeters
+    pub length: u32,
+    /// Width in millimeters
+    pub width: u32,
+    /// Height in millimeters
+    pub height: u32,
+    /// Packaging of the product
+    pub packaging: Packaging,
+}
+
+/// Storage of the physical attributes of the assets
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Physical {
+    /// Physical attributes of the asset
+    asset_attributes: StorageHashMap<AssetId, PhysicalAttributes>,
+}
+
+impl Physical {
+    /// Returns the physical attributes of asset `id`
+    pub fn attributes_of(&self, id: AssetId) -> Option<PhysicalAttributes> {
+        self.asset_attributes.get(&id).cloned()
+    }
+
+    /// Stores the physical attributes of asset `id`, replacing the previous ones
+    pub fn set(&mut self, id: AssetId, attributes: PhysicalAttributes) {
+        self.asset_attributes.insert(id, attributes);
+    }
+
+    /// Removes the physical attributes of asset `id` and returns them
+    pub fn remove(&mut self, id: AssetId) -> Option<PhysicalAttributes> {
+        self.asset_attributes.take(&id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn set_works() {
+        let attributes = PhysicalAttributes {
+            weight: 12_500,
+            length: 400,
+            width: 300,
+            height: 250,
+            packaging: Packaging::Box,
+        };
+        let mut physical = Physical::default();
+        assert_eq!(physical.attributes_of(1), None);
+        physical.set(1, attributes);
+        physical.set(1, PhysicalAttributes { weight: 13_000, ..attributes });
+        assert_eq!(physical.attributes_of(1).map(|attributes| attributes.weight), Some(13_000));
+        assert!(physical.remove(1).is_some());
+        assert_eq!(physical.attributes_of(1), None);
+    }
+}
@@ -0,0 +1,189 @@
+//! # Price lists
+//!
+//! Price lists of the producers for the categories of their assets.
+//!
+//! A producer publishes the unit price of a category with its currency and the block it is
+//! valid from, so the buyers and the listings refer to an authoritative on-chain price instead
+//! of repeating it. The entries are never changed: a new price is appended to the history of
+//! the category and replaces the previous one from its first valid block. A price can be
+//! scheduled for a later block but not back-dated.
+//!
+//! The producer also records the suggested retail price of each asset it mints, before handing
+//! it over. The price is fixed once recorded: the brands compare it with the prices of the sales
+//! settled through the contract to detect the assets dumped on the grey market.
+//!
+
+use crate::{
+    asset_erc721::{
+        AssetId,
+        Error,
+    },
+    oracle::Currency,
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Entry of the price list of a producer
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct PriceEntry {
+    /// Price of a unit in the smallest unit of `currency`, or of the settlement token when `currency` is None
+    pub unit_price: Balance,
+    /// Fiat reference unit of the price
+    pub currency: Option<Currency>,
+    /// First block the price applies
+    pub valid_from: BlockNumber,
+    /// Block of the publication
+    pub block: BlockNumber,
+}
+
+/// Manufacturer-suggested retail price of an asset
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Msrp {
+    /// Price in the smallest unit of `currency`, or of the settlement token when `currency` is None
+    pub price: Balance,
+    /// Fiat reference unit of the price
+    pub currency: Option<Currency>,
+    /// Block the price was recorded at
+    pub block: BlockNumber,
+}
+
+/// Storage of the price lists
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct PriceLists {
+    /// Counter of the entries of the producer for the category
+    entries_count: StorageHashMap<(AccountId, u32), u32>,
+    /// Entries of the producer for the category, indexed from 0 to the entries counter
+    entries: StorageHashMap<(AccountId, u32, u32), PriceEntry>,
+    /// Suggested retail price of the asset
+    asset_msrp: StorageHashMap<AssetId, Msrp>,
+}
+
+impl PriceLists {
+    /// Returns the number of entries of `producer` for `category`
+    pub fn entries_count(&self, producer: &AccountId, category: u32) -> u32 {
+        *self.entries_count.get(&(*producer, category)).unwrap_or(&0)
+    }
+
+    /// Returns at most `limit` entries of `producer` for `category` starting from position `offset`, oldest first
+    pub fn history(&self, producer: &AccountId, category: u32, offset: u32, limit: u32) -> Vec<PriceEntry> {
+        (offset..self.entries_count(producer, category))
+            .take(limit as usize)
+            .filter_map(|index| self.entries.get(&(*producer, category, index)).cloned())
+            .collect()
+    }
+
+    /// Returns the price of `producer` for `category` in force at `block`
+    pub fn price_at(&self, producer: &AccountId, category: u32, block: BlockNumber) -> Option<PriceEntry> {
+        (0..self.entries_count(producer, category))
+            .rev()
+            .filter_map(|index| self.entries.get(&(*producer, category, index)))
+            .find(|entry| entry.valid_from <= block)
+            .cloned()
+    }
+
+    /// Appends `entry` to the price list of `producer` for `category`. Fails if the price is back-dated or
+    /// valid before the last entry.
+    pub fn publish(&mut self, producer: AccountId, category: u32, entry: PriceEntry) -> Result<(), Error> {
+        let index = self.entries_count(&producer, category);
+        let last = index
+            .checked_sub(1)
+            .and_then(|last| self.entries.get(&(producer, category, last)));
+        if entry.valid_from < entry.block || last.is_some_and(|last| entry.valid_from < last.valid_from) {
+            return Err(Error::NotAllowed)
+        }
+        self.entries.insert((producer, category, index), entry);
+        self.entries_count.insert((producer, category), index + 1);
+        Ok(())
+    }
+
+    /// Returns the suggested retail price of asset `id`
+    pub fn msrp_of(&self, id: AssetId) -> Option<Msrp> {
+        self.asset_msrp.get(&id).cloned()
+    }
+
+    /// Records the suggested retail price of asset `id`, fails if it is already recorded
+    pub fn msrp_set(&mut self, id: AssetId, msrp: Msrp) -> Result<(), Error> {
+        if self.asset_msrp.contains_key(&id) {
+            return Err(Error::DuplicatedData)
+        }
+        self.asset_msrp.insert(id, msrp);
+        Ok(())
+    }
+
+    /// Removes the suggested retail price of the deleted asset `id`
+    pub fn msrp_remove(&mut self, id: AssetId) {
+        self.asset_msrp.take(&id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    fn entry(unit_price: Balance, valid_from: BlockNumber) -> PriceEntry {
+        PriceEntry {
+            unit_price,
+            currency: Some(*b"EUR"),
+            valid_from,
+            block: 1,
+        }
+    }
+
+    #[ink::test]
+    fn price_lists_work() {
+        let alice = AccountId::from([0x1; 32]);
+        let mut price_lists = PriceLists::default();
+        assert_eq!(price_lists.publish(alice, 1, entry(100, 0)), Err(Error::NotAllowed));
+        assert_eq!(price_lists.publish(alice, 1, entry(100, 1)), Ok(()));
+        assert_eq!(price_lists.publish(alice, 1, entry(120, 10)), Ok(()));
+        // a price cannot apply before the last one
+        assert_eq!(price_lists.publish(alice, 1, entry(90, 5)), Err(Error::NotAllowed));
+        assert_eq!(price_lists.price_at(&alice, 1, 0), None);
+        assert_eq!(price_lists.price_at(&alice, 1, 9), Some(entry(100, 1)));
+        assert_eq!(price_lists.price_at(&alice, 1, 10), Some(entry(120, 10)));
+        // the price lists of each category are apart
+        assert_eq!(price_lists.price_at(&alice, 2, 10), None);
+        assert_eq!(price_lists.entries_count(&alice, 1), 2);
+        assert_eq!(price_lists.history(&alice, 1, 1, 10), [entry(120, 10)]);
+    }
+
+    #[ink::test]
+    fn msrp_works() {
+        let msrp = Msrp {
+            price: 100,
+            currency: None,
+            block: 1,
+        };
+        let mut price_lists = PriceLists::default();
+        assert_eq!(price_lists.msrp_set(1, msrp), Ok(()));
+        // the price is fixed once recorded
+        assert_eq!(price_lists.msrp_set(1, Msrp { price: 50, ..msrp }), Err(Error::DuplicatedData));
+        assert_eq!(price_lists.msrp_of(1), Some(msrp));
+        price_lists.msrp_remove(1);
+        assert_eq!(price_lists.msrp_of(1), None);
+    }
+}
@@ -0,0 +1,199 @@
+//! # Privacy
+//!
+//! Encrypted private data of the assets (purchase price, customer data) and the grants of
+//! the key to decrypt it.
+//!
+//! The owner stores the address of the encrypted document and, for each account allowed to
+//! read it, the hash of the document key wrapped with the public key of the account. The keys
+//! are exchanged off-chain: the grants record who can decrypt. A revoked account may still
+//! hold the old key, so storing new private data, encrypted with a new key, drops every grant.
+//!
+//! The owner also keeps the list of the viewers of each asset: the off-chain gateways serving
+//! the decrypted documents authorize their requests against it.
+//!
+
+use crate::asset_erc721::{
+    AssetId,
+    Error,
+};
+use ink_env::{
+    AccountId,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::SpreadLayout,
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+
+/// Storage of the private data of the assets
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Privacy {
+    /// Address of the encrypted private data of the asset
+    asset_data: StorageHashMap<AssetId, Hash>,
+    /// Hash of the wrapped key granted to the account for the private data of the asset
+    asset_grants: StorageHashMap<(AssetId, AccountId), Hash>,
+    /// Accounts with a key grant on the asset
+    asset_grantees: StorageHashMap<AssetId, Vec<AccountId>>,
+    /// Accounts allowed to view the private data of the asset
+    asset_viewers: StorageHashMap<AssetId, Vec<AccountId>>,
+}
+
+impl Privacy {
+    /// Returns the address of the encrypted private data of asset `id`
+    pub fn data_of(&self, id: AssetId) -> Option<Hash> {
+        self.asset_data.get(&id).cloned()
+    }
+
+    /// Stores the private data of asset `id` encrypted with a new key, the grants of the previous key are removed
+    pub fn set(&mut self, id: AssetId, data: Hash) {
+        self.revoke_all(id);
+        self.asset_data.insert(id, data);
+    }
+
+    /// Returns the hash of the wrapped key of asset `id` granted to `account`
+    pub fn key_of(&self, id: AssetId, account: &AccountId) -> Option<Hash> {
+        self.asset_grants.get(&(id, *account)).cloned()
+    }
+
+    /// Returns the accounts with a key grant on asset `id`
+    pub fn grantees_of(&self, id: AssetId) -> Vec<AccountId> {
+        self.asset_grantees.get(&id).cloned().unwrap_or_default()
+    }
+
+    /// Grants `account` the key of the private data of asset `id`, replacing its previous grant
+    pub fn grant(&mut self, id: AssetId, account: AccountId, wrapped_key: Hash) -> Result<(), Error> {
+        if !self.asset_data.contains_key(&id) {
+            return Err(Error::PrivateDataNotFound)
+        }
+        if self.asset_grants.insert((id, account), wrapped_key).is_none() {
+            let mut grantees = self.grantees_of(id);
+            grantees.push(account);
+            self.asset_grantees.insert(id, grantees);
+        }
+        Ok(())
+    }
+
+    /// Revokes the key grant of `account` on asset `id`
+    pub fn revoke(&mut self, id: AssetId, account: &AccountId) -> Result<(), Error> {
+        self.asset_grants.take(&(id, *account)).ok_or(Error::CannotRemove)?;
+        let mut grantees = self.grantees_of(id);
+        grantees.retain(|other| other != account);
+        if grantees.is_empty() {
+            self.asset_grantees.take(&id);
+        } else {
+            self.asset_grantees.insert(id, grantees);
+        }
+        Ok(())
+    }
+
+    /// Revokes every key grant on asset `id`, the private data is kept
+    pub fn revoke_all(&mut self, id: AssetId) {
+        for account in self.asset_grantees.take(&id).unwrap_or_default() {
+            self.asset_grants.take(&(id, account));
+        }
+    }
+
+    /// Returns the accounts allowed to view the private data of asset `id`
+    pub fn viewers_of(&self, id: AssetId) -> Vec<AccountId> {
+        self.asset_viewers.get(&id).cloned().unwrap_or_default()
+    }
+
+    /// Returns true if `account` is allowed to view the private data of asset `id`
+    pub fn is_viewer(&self, id: AssetId, account: &AccountId) -> bool {
+        self.asset_viewers
+            .get(&id)
+            .is_some_and(|viewers| viewers.contains(account))
+    }
+
+    /// Allows `account` to view the private data of asset `id`
+    pub fn viewer_add(&mut self, id: AssetId, account: AccountId) -> Result<(), Error> {
+        let mut viewers = self.viewers_of(id);
+        if viewers.contains(&account) {
+            return Err(Error::DuplicatedData)
+        }
+        viewers.push(account);
+        self.asset_viewers.insert(id, viewers);
+        Ok(())
+    }
+
+    /// Removes `account` from the viewers of the private data of asset `id`
+    pub fn viewer_remove(&mut self, id: AssetId, account: &AccountId) -> Result<(), Error> {
+        let mut viewers = self.viewers_of(id);
+        if !viewers.contains(account) {
+            return Err(Error::CannotRemove)
+        }
+        viewers.retain(|other| other != account);
+        if viewers.is_empty() {
+            self.asset_viewers.take(&id);
+        } else {
+            self.asset_viewers.insert(id, viewers);
+        }
+        Ok(())
+    }
+
+    /// Removes every viewer of asset `id`
+    pub fn viewers_clear(&mut self, id: AssetId) {
+        self.asset_viewers.take(&id);
+    }
+
+    /// Removes the private data, the key grants and the viewers of asset `id`
+    pub fn remove(&mut self, id: AssetId) -> Option<Hash> {
+        self.revoke_all(id);
+        self.viewers_clear(id);
+        self.asset_data.take(&id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn grants_work() {
+        let bob = AccountId::from([0x2; 32]);
+        let eve = AccountId::from([0x3; 32]);
+        let mut privacy = Privacy::default();
+        assert_eq!(privacy.grant(1, bob, Hash::from([0x01; 32])), Err(Error::PrivateDataNotFound));
+        privacy.set(1, Hash::from([0x0a; 32]));
+        assert_eq!(privacy.grant(1, bob, Hash::from([0x01; 32])), Ok(()));
+        assert_eq!(privacy.grant(1, eve, Hash::from([0x02; 32])), Ok(()));
+        assert_eq!(privacy.grant(1, bob, Hash::from([0x03; 32])), Ok(()));
+        assert_eq!(privacy.grantees_of(1), [bob, eve]);
+        assert_eq!(privacy.key_of(1, &bob), Some(Hash::from([0x03; 32])));
+        assert_eq!(privacy.revoke(1, &eve), Ok(()));
+        assert_eq!(privacy.revoke(1, &eve), Err(Error::CannotRemove));
+        assert_eq!(privacy.key_of(1, &eve), None);
+        // new private data drops the grants of the previous key
+        privacy.set(1, Hash::from([0x0b; 32]));
+        assert_eq!(privacy.key_of(1, &bob), None);
+        assert!(privacy.grantees_of(1).is_empty());
+        assert_eq!(privacy.remove(1), Some(Hash::from([0x0b; 32])));
+        assert_eq!(privacy.data_of(1), None);
+    }
+
+    #[ink::test]
+    fn viewers_work() {
+        let bob = AccountId::from([0x2; 32]);
+        let eve = AccountId::from([0x3; 32]);
+        let mut privacy = Privacy::default();
+        assert!(!privacy.is_viewer(1, &bob));
+        assert_eq!(privacy.viewer_add(1, bob), Ok(()));
+        assert_eq!(privacy.viewer_add(1, bob), Err(Error::DuplicatedData));
+        assert_eq!(privacy.viewer_add(1, eve), Ok(()));
+        assert!(privacy.is_viewer(1, &bob));
+        assert!(!privacy.is_viewer(2, &bob));
+        // new private data keeps the viewers
+        privacy.set(1, Hash::from([0x0a; 32]));
+        assert_eq!(privacy.viewers_of(1), [bob, eve]);
+        assert_eq!(privacy.viewer_remove(1, &bob), Ok(()));
+        assert_eq!(privacy.viewer_remove(1, &bob), Err(Error::CannotRemove));
+        assert_eq!(privacy.viewers_of(1), [eve]);
+        privacy.remove(1);
+        assert!(!privacy.is_viewer(1, &eve));
+    }
+}
@@ -0,0 +1,252 @@
+//! # Products
+//!
+//! Product definitions of the producers and their variants, as the catalogs of the ERPs.
+//!
+//! A producer defines a product of one of its categories and adds the variants it is sold in,
+//! each with its own size, color and formulation. The assets reference a variant of a product:
+//! the contract keeps the list of the assets of each product and of each variant, so the units
+//! of a product line are enumerated with no need to scan every asset.
+//!
+
+use crate::asset_erc721::{
+    AssetId,
+    Error,
+};
+use ink_env::{
+    AccountId,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+/// Product ID
+pub type ProductId = u32;
+
+/// Variant of a product, as the product and the position of the variant in the variants of the product
+pub type VariantId = (ProductId, u32);
+
+/// Product definition of a producer
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Product {
+    /// Producer that defined the product and can add its variants
+    pub producer: AccountId,
+    /// Hash of the name and the description of the product
+    pub name: Hash,
+    /// Category of the product
+    pub category: u32,
+    /// Counter of the variants of the product
+    pub variants_count: u32,
+}
+
+/// Attributes of a variant of a product
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Variant {
+    /// Size, in the unit of the product
+    pub size: Option<u32>,
+    /// Color as its RGB code
+    pub color: Option<[u8; 3]>,
+    /// Hash of the formulation
+    pub formulation: Option<Hash>,
+}
+
+/// Storage of the products
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Products {
+    /// Products indexed from 0 to the products counter
+    products: StorageHashMap<ProductId, Product>,
+    /// Counter of the products defined
+    products_count: u32,
+    /// Variants of the products
+    variants: StorageHashMap<VariantId, Variant>,
+    /// Variant of the asset
+    asset_variant: StorageHashMap<AssetId, VariantId>,
+    /// Counter of the assets of each product
+    product_assets_count: StorageHashMap<ProductId, u32>,
+    /// List of the assets of each product, indexed from 0 to the product assets counter
+    product_assets: StorageHashMap<(ProductId, u32), AssetId>,
+    /// Position of the asset in the list of the assets of its product
+    asset_product_index: StorageHashMap<AssetId, u32>,
+    /// Counter of the assets of each variant
+    variant_assets_count: StorageHashMap<VariantId, u32>,
+    /// List of the assets of each variant, indexed from 0 to the variant assets counter
+    variant_assets: StorageHashMap<(VariantId, u32), AssetId>,
+    /// Position of the asset in the list of the assets of its variant
+    asset_variant_index: StorageHashMap<AssetId, u32>,
+}
+
+impl Products {
+    /// Defines a new product and returns its id
+    pub fn define(&mut self, product: Product) -> ProductId {
+        let product_id = self.products_count;
+        self.products.insert(product_id, Product { variants_count: 0, ..product });
+        self.products_count += 1;
+        product_id
+    }
+
+    /// Returns the product `product_id`
+    pub fn get(&self, product_id: ProductId) -> Option<Product> {
+        self.products.get(&product_id).cloned()
+    }
+
+    /// Adds `variant` to the product `product_id` and returns its position in the variants of the product
+    pub fn variant_add(&mut self, product_id: ProductId, variant: Variant) -> Result<u32, Error> {
+        let product = self.products.get_mut(&product_id).ok_or(Error::ProductNotFound)?;
+        let index = product.variants_count;
+        product.variants_count += 1;
+        self.variants.insert((product_id, index), variant);
+        Ok(index)
+    }
+
+    /// Returns the variant `variant_id`
+    pub fn variant_of(&self, variant_id: VariantId) -> Option<Variant> {
+        self.variants.get(&variant_id).cloned()
+    }
+
+    /// Returns at most `limit` variants of the product `product_id` starting from position `offset`
+    pub fn variants_of(&self, product_id: ProductId, offset: u32, limit: u32) -> Vec<Variant> {
+        let count = self.get(product_id).map_or(0, |product| product.variants_count);
+        (offset..count)
+            .take(limit as usize)
+            .filter_map(|index| self.variant_of((product_id, index)))
+            .collect()
+    }
+
+    /// Returns the variant of asset `id`
+    pub fn asset_variant_of(&self, id: AssetId) -> Option<VariantId> {
+        self.asset_variant.get(&id).cloned()
+    }
+
+    /// Returns the number of assets of the product `product_id`
+    pub fn product_assets_count(&self, product_id: ProductId) -> u32 {
+        *self.product_assets_count.get(&product_id).unwrap_or(&0)
+    }
+
+    /// Returns at most `limit` assets of the product `product_id` starting from position `offset`
+    pub fn product_assets_of(&self, product_id: ProductId, offset: u32, limit: u32) -> Vec<AssetId> {
+        (offset..self.product_assets_count(product_id))
+            .take(limit as usize)
+            .filter_map(|index| self.product_assets.get(&(product_id, index)).cloned())
+            .collect()
+    }
+
+    /// Returns the number of assets of the variant `variant_id`
+    pub fn variant_assets_count(&self, variant_id: VariantId) -> u32 {
+        *self.variant_assets_count.get(&variant_id).unwrap_or(&0)
+    }
+
+    /// Returns at most `limit` assets of the variant `variant_id` starting from position `offset`
+    pub fn variant_assets_of(&self, variant_id: VariantId, offset: u32, limit: u32) -> Vec<AssetId> {
+        (offset..self.variant_assets_count(variant_id))
+            .take(limit as usize)
+            .filter_map(|index| self.variant_assets.get(&(variant_id, index)).cloned())
+            .collect()
+    }
+
+    /// Links asset `id` to the existing variant `variant_id`
+    pub fn link(&mut self, id: AssetId, variant_id: VariantId) -> Result<(), Error> {
+        if !self.variants.contains_key(&variant_id) {
+            return Err(Error::VariantNotFound)
+        }
+        if self.asset_variant.contains_key(&id) {
+            return Err(Error::DuplicatedData)
+        }
+        let (product_id, _) = variant_id;
+        self.asset_variant.insert(id, variant_id);
+        let index = self.product_assets_count(product_id);
+        self.product_assets_count.insert(product_id, index + 1);
+        self.product_assets.insert((product_id, index), id);
+        self.asset_product_index.insert(id, index);
+        let index = self.variant_assets_count(variant_id);
+        self.variant_assets_count.insert(variant_id, index + 1);
+        self.variant_assets.insert((variant_id, index), id);
+        self.asset_variant_index.insert(id, index);
+        Ok(())
+    }
+
+    /// Removes asset `id` from its variant and product, moving the last asset of each list in the freed position
+    pub fn unlink(&mut self, id: AssetId) -> Option<VariantId> {
+        let variant_id = self.asset_variant.take(&id)?;
+        let (product_id, _) = variant_id;
+        let last = self.product_assets_count(product_id).saturating_sub(1);
+        self.product_assets_count.insert(product_id, last);
+        let index = self.asset_product_index.take(&id).unwrap_or(last);
+        if let Some(last_id) = self.product_assets.take(&(product_id, last)) {
+            if index != last {
+                self.product_assets.insert((product_id, index), last_id);
+                self.asset_product_index.insert(last_id, index);
+            }
+        }
+        let last = self.variant_assets_count(variant_id).saturating_sub(1);
+        self.variant_assets_count.insert(variant_id, last);
+        let index = self.asset_variant_index.take(&id).unwrap_or(last);
+        if let Some(last_id) = self.variant_assets.take(&(variant_id, last)) {
+            if index != last {
+                self.variant_assets.insert((variant_id, index), last_id);
+                self.asset_variant_index.insert(last_id, index);
+            }
+        }
+        Some(variant_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    fn product() -> Product {
+        Product {
+            producer: AccountId::from([0x1; 32]),
+            name: Hash::from([0x01; 32]),
+            category: 10,
+            variants_count: 0,
+        }
+    }
+
+    fn variant(size: u32) -> Variant {
+        Variant {
+            size: Some(size),
+            color: Some([0xFF, 0x00, 0x00]),
+            formulation: None,
+        }
+    }
+
+    #[ink::test]
+    fn products_work() {
+        let mut products = Products::default();
+        let product_id = products.define(product());
+        assert_eq!(products.variant_add(product_id + 1, variant(38)), Err(Error::ProductNotFound));
+        assert_eq!(products.variant_add(product_id, variant(38)), Ok(0));
+        assert_eq!(products.variant_add(product_id, variant(40)), Ok(1));
+        assert_eq!(products.get(product_id).map(|product| product.variants_count), Some(2));
+        assert_eq!(products.variants_of(product_id, 1, 10), [variant(40)]);
+        assert_eq!(products.link(1, (product_id, 2)), Err(Error::VariantNotFound));
+        assert_eq!(products.link(1, (product_id, 0)), Ok(()));
+        assert_eq!(products.link(2, (product_id, 1)), Ok(()));
+        assert_eq!(products.link(3, (product_id, 0)), Ok(()));
+        assert_eq!(products.link(1, (product_id, 1)), Err(Error::DuplicatedData));
+        assert_eq!(products.asset_variant_of(2), Some((product_id, 1)));
+        assert_eq!(products.product_assets_of(product_id, 0, 10), [1, 2, 3]);
+        assert_eq!(products.variant_assets_of((product_id, 0), 0, 10), [1, 3]);
+        // the last asset takes the place of the unlinked one in both lists
+        assert_eq!(products.unlink(1), Some((product_id, 0)));
+        assert_eq!(products.unlink(1), None);
+        assert_eq!(products.product_assets_of(product_id, 0, 10), [3, 2]);
+        assert_eq!(products.variant_assets_of((product_id, 0), 0, 10), [3]);
+    }
+}
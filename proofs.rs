@@ -0,0 +1,187 @@
+//! # Proofs
+//!
+//! Claims about the hidden data of the assets proven with succinct zero-knowledge proofs,
+//! e.g. "the temperature never exceeded 8°C" without publishing the sensor readings.
+//!
+//! Each type of claim is checked from a verifier contract registered from the administrators.
+//! The contract forwards the proof and its public inputs to the verifier and records the claim
+//! against the asset only when the verifier accepts it: the raw data never reaches the chain.
+//!
+//! The verifier contracts expose `verify(asset: AssetId, public_inputs: Vec<u8>, proof: Vec<u8>) -> bool`,
+//! the asset is part of the statement so a proof cannot be replayed on another asset.
+//!
+
+use crate::asset_erc721::{
+    AssetId,
+    Error,
+};
+use ink_env::{
+    call::{
+        build_call,
+        utils::ReturnType,
+        ExecutionInput,
+        Selector,
+    },
+    hash::Blake2x256,
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Type of claim, the codes are agreed between the parties of the platform
+pub type ClaimType = u32;
+
+/// Selector of the `verify` message of the verifier contracts
+const VERIFY: [u8; 4] = [0x18, 0x60, 0xff, 0x3b];
+
+/// Claim proven on an asset
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Claim {
+    /// Type of the claim
+    pub claim_type: ClaimType,
+    /// Blake2x256 hash of the public inputs of the proof
+    pub statement: Hash,
+    /// Verifier contract that accepted the proof
+    pub verifier: AccountId,
+    /// Account that submitted the proof
+    pub prover: AccountId,
+    /// Block of the verification
+    pub block: BlockNumber,
+}
+
+/// Storage of the verifiers and of the claims proven on the assets
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Proofs {
+    /// Verifier contract of the claim type
+    claim_verifier: StorageHashMap<ClaimType, AccountId>,
+    /// Counter of the claims of each asset
+    asset_claims_count: StorageHashMap<AssetId, u32>,
+    /// List of the claims of each asset, indexed from 0 to the asset claims counter
+    asset_claims: StorageHashMap<(AssetId, u32), Claim>,
+}
+
+impl Proofs {
+    /// Returns the Blake2x256 hash of the public inputs of a proof
+    pub fn statement_hash(public_inputs: &[u8]) -> Hash {
+        let mut output = [0x0; 32];
+        ink_env::hash_bytes::<Blake2x256>(public_inputs, &mut output);
+        Hash::from(output)
+    }
+
+    /// Returns the verifier contract of `claim_type`
+    pub fn verifier_of(&self, claim_type: ClaimType) -> Option<AccountId> {
+        self.claim_verifier.get(&claim_type).cloned()
+    }
+
+    /// Registers or removes the verifier contract of `claim_type`
+    pub fn verifier_set(&mut self, claim_type: ClaimType, verifier: Option<AccountId>) {
+        match verifier {
+            Some(verifier) => self.claim_verifier.insert(claim_type, verifier),
+            None => self.claim_verifier.take(&claim_type),
+        };
+    }
+
+    /// Returns the claims proven on asset `id`, oldest first
+    pub fn claims_of(&self, id: AssetId) -> Vec<Claim> {
+        let count = *self.asset_claims_count.get(&id).unwrap_or(&0);
+        (0..count)
+            .filter_map(|index| self.asset_claims.get(&(id, index)).cloned())
+            .collect()
+    }
+
+    /// Returns true if a claim of type `claim_type` has been proven on asset `id`
+    pub fn is_proven(&self, id: AssetId, claim_type: ClaimType) -> bool {
+        self.claims_of(id)
+            .iter()
+            .any(|claim| claim.claim_type == claim_type)
+    }
+
+    /// Records `claim` on asset `id`
+    pub fn record(&mut self, id: AssetId, claim: Claim) {
+        let index = *self.asset_claims_count.get(&id).unwrap_or(&0);
+        self.asset_claims.insert((id, index), claim);
+        self.asset_claims_count.insert(id, index + 1);
+    }
+
+    /// Removes the claims of the deleted asset `id`
+    pub fn remove(&mut self, id: AssetId) {
+        let count = self.asset_claims_count.take(&id).unwrap_or(0);
+        for index in 0..count {
+            self.asset_claims.take(&(id, index));
+        }
+    }
+}
+
+/// Asks `verifier` to check `proof` of the statement `public_inputs` on asset `id`
+pub fn verify(verifier: AccountId, id: AssetId, public_inputs: Vec<u8>, proof: Vec<u8>) -> Result<(), Error> {
+    let accepted = build_call::<DefaultEnvironment>()
+        .callee(verifier)
+        .gas_limit(0)
+        .transferred_value(0)
+        .exec_input(
+            ExecutionInput::new(Selector::new(VERIFY))
+                .push_arg(id)
+                .push_arg(public_inputs)
+                .push_arg(proof),
+        )
+        .returns::<ReturnType<bool>>()
+        .fire();
+    match accepted {
+        Ok(true) => Ok(()),
+        _ => Err(Error::InvalidProof),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    fn claim(claim_type: ClaimType) -> Claim {
+        Claim {
+            claim_type,
+            statement: Hash::from([0x01; 32]),
+            verifier: AccountId::from([0x9; 32]),
+            prover: AccountId::from([0x1; 32]),
+            block: 1,
+        }
+    }
+
+    #[ink::test]
+    fn claims_work() {
+        let verifier = AccountId::from([0x9; 32]);
+        let mut proofs = Proofs::default();
+        assert_eq!(proofs.verifier_of(3), None);
+        proofs.verifier_set(3, Some(verifier));
+        assert_eq!(proofs.verifier_of(3), Some(verifier));
+        assert!(!proofs.is_proven(1, 3));
+        proofs.record(1, claim(3));
+        proofs.record(1, claim(4));
+        assert!(proofs.is_proven(1, 3));
+        assert!(!proofs.is_proven(2, 3));
+        assert_eq!(proofs.claims_of(1), [claim(3), claim(4)]);
+        proofs.remove(1);
+        assert!(proofs.claims_of(1).is_empty());
+        proofs.verifier_set(3, None);
+        assert_eq!(proofs.verifier_of(3), None);
+    }
+}
@@ -0,0 +1,114 @@
+//! # Provenance
+//!
+//! Origin of the assets: the account that created each asset and when, kept separately from
+//! the current owner so it is not changed by the transfers.
+//!
+//! The creation is written once when the asset is created: the account that created it is the
+//! producer of record of the asset and cannot be replaced from its later owners. The record
+//! keeps the deposit locked from the producer to create the asset, returned once it is deleted.
+//!
+
+use crate::asset_erc721::{
+    AssetId,
+    Error,
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+};
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+type Timestamp = <DefaultEnvironment as Environment>::Timestamp;
+
+/// Creation of an asset
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct MintRecord {
+    /// Account that created the asset
+    pub minter: AccountId,
+    /// Block of the creation
+    pub block: BlockNumber,
+    /// Timestamp of the block of the creation
+    pub timestamp: Timestamp,
+    /// Deposit locked from the minter until the asset is deleted
+    pub deposit: Balance,
+}
+
+/// Storage of the origin of the assets
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Provenance {
+    /// Creation of the assets
+    asset_mint: StorageHashMap<AssetId, MintRecord>,
+}
+
+impl Provenance {
+    /// Returns the creation of asset `id`
+    pub fn mint_of(&self, id: AssetId) -> Option<MintRecord> {
+        self.asset_mint.get(&id).cloned()
+    }
+
+    /// Returns the producer of record of asset `id`, the account that created it
+    pub fn producer_of(&self, id: AssetId) -> Option<AccountId> {
+        self.asset_mint.get(&id).map(|record| record.minter)
+    }
+
+    /// Records the creation of asset `id`, fails if it is already recorded
+    pub fn record(&mut self, id: AssetId, record: MintRecord) -> Result<(), Error> {
+        if self.asset_mint.contains_key(&id) {
+            return Err(Error::DuplicatedData)
+        }
+        self.asset_mint.insert(id, record);
+        Ok(())
+    }
+
+    /// Removes the creation of the deleted asset `id`, and returns it
+    pub fn remove(&mut self, id: AssetId) -> Option<MintRecord> {
+        self.asset_mint.take(&id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn record_works() {
+        let record = MintRecord {
+            minter: AccountId::from([0x1; 32]),
+            block: 3,
+            timestamp: 42,
+            deposit: 0,
+        };
+        let mut provenance = Provenance::default();
+        assert_eq!(provenance.mint_of(1), None);
+        assert_eq!(provenance.record(1, record), Ok(()));
+        assert_eq!(provenance.mint_of(1), Some(record));
+        assert_eq!(provenance.producer_of(1), Some(record.minter));
+        // the creation is written once
+        let spoofed = MintRecord {
+            minter: AccountId::from([0x2; 32]),
+            ..record
+        };
+        assert_eq!(provenance.record(1, spoofed), Err(Error::DuplicatedData));
+        assert_eq!(provenance.producer_of(1), Some(record.minter));
+        assert_eq!(provenance.remove(1), Some(record));
+        assert_eq!(provenance.mint_of(1), None);
+    }
+}
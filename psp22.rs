@@ -0,0 +1,103 @@
+//! # PSP22
+//!
+//! Calls to the PSP22 token contracts accepted for the payments and to the reward token.
+//!
+//! Only the outcome of the calls is decoded: the error returned from the token contract is
+//! reported as [`Error::PaymentFailed`].
+//!
+
+use crate::asset_erc721::Error;
+use ink_env::{
+    call::{
+        build_call,
+        utils::ReturnType,
+        ExecutionInput,
+        Selector,
+    },
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+};
+use ink_prelude::vec::Vec;
+use scale::{
+    Decode,
+    Input,
+};
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+
+/// Selector of `PSP22::transfer`
+const TRANSFER: [u8; 4] = [0xdb, 0x20, 0xf9, 0xf5];
+/// Selector of `PSP22::transfer_from`
+const TRANSFER_FROM: [u8; 4] = [0x54, 0xb3, 0xc7, 0x6e];
+/// Selector of `PSP22Mintable::mint`
+const MINT: [u8; 4] = [0xfc, 0x3c, 0x75, 0xd4];
+
+/// Outcome of a call returning `Result<(), PSP22Error>`
+struct Outcome(bool);
+
+impl Decode for Outcome {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, scale::Error> {
+        Ok(Outcome(input.read_byte()? == 0))
+    }
+}
+
+/// Transfers `value` tokens of `token` from the contract to `to`
+pub fn transfer(token: AccountId, to: AccountId, value: Balance) -> Result<(), Error> {
+    let outcome = build_call::<DefaultEnvironment>()
+        .callee(token)
+        .gas_limit(0)
+        .transferred_value(0)
+        .exec_input(
+            ExecutionInput::new(Selector::new(TRANSFER))
+                .push_arg(to)
+                .push_arg(value)
+                .push_arg(Vec::<u8>::new()),
+        )
+        .returns::<ReturnType<Outcome>>()
+        .fire();
+    match outcome {
+        Ok(Outcome(true)) => Ok(()),
+        _ => Err(Error::PaymentFailed),
+    }
+}
+
+/// Transfers `value` tokens of `token` from `from` to `to`, the contract must be allowed to spend them
+pub fn transfer_from(token: AccountId, from: AccountId, to: AccountId, value: Balance) -> Result<(), Error> {
+    let outcome = build_call::<DefaultEnvironment>()
+        .callee(token)
+        .gas_limit(0)
+        .transferred_value(0)
+        .exec_input(
+            ExecutionInput::new(Selector::new(TRANSFER_FROM))
+                .push_arg(from)
+                .push_arg(to)
+                .push_arg(value)
+                .push_arg(Vec::<u8>::new()),
+        )
+        .returns::<ReturnType<Outcome>>()
+        .fire();
+    match outcome {
+        Ok(Outcome(true)) => Ok(()),
+        _ => Err(Error::PaymentFailed),
+    }
+}
+
+/// Mints `value` tokens of `token` to `to`, the contract must be a minter of the token
+pub fn mint(token: AccountId, to: AccountId, value: Balance) -> Result<(), Error> {
+    let outcome = build_call::<DefaultEnvironment>()
+        .callee(token)
+        .gas_limit(0)
+        .transferred_value(0)
+        .exec_input(
+            ExecutionInput::new(Selector::new(MINT))
+                .push_arg(to)
+                .push_arg(value),
+        )
+        .returns::<ReturnType<Outcome>>()
+        .fire();
+    match outcome {
+        Ok(Outcome(true)) => Ok(()),
+        _ => Err(Error::PaymentFailed),
+    }
+}
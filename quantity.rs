@@ -0,0 +1,128 @@
+//! # Quantity
+//!
+//! Quantity and unit of measure of the assets representing bulk commodities (grain, milk,
+//! fuel...), and the lots created splitting them.
+//!
+//! A part of the quantity of an asset can be split in a new asset, the lot, that keeps the
+//! asset it was split from as its parent. The lots take the ids from [`FIRST_LOT_ID`] on.
+//!
+
+use crate::asset_erc721::{
+    AssetId,
+    Error,
+};
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+/// First id assigned to the lots split from the assets
+pub const FIRST_LOT_ID: AssetId = 0x8000_0000;
+
+/// Unit of measure of a quantity
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub enum Unit {
+    Piece,
+    Gram,
+    Kilogram,
+    Tonne,
+    Milliliter,
+    Liter,
+    CubicMeter,
+}
+
+/// Quantity of an asset
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Quantity {
+    pub amount: u128,
+    pub unit: Unit,
+}
+
+/// Storage of the quantities of the assets
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Quantities {
+    /// Quantity of the asset
+    asset_quantity: StorageHashMap<AssetId, Quantity>,
+    /// Asset the lot has been split from
+    asset_parent: StorageHashMap<AssetId, AssetId>,
+    /// Counter of the lots split from the assets
+    lots_count: u32,
+}
+
+impl Quantities {
+    /// Returns the quantity of asset `id`
+    pub fn quantity_of(&self, id: AssetId) -> Option<Quantity> {
+        self.asset_quantity.get(&id).cloned()
+    }
+
+    /// Stores the quantity of asset `id`
+    pub fn set(&mut self, id: AssetId, quantity: Quantity) {
+        self.asset_quantity.insert(id, quantity);
+    }
+
+    /// Returns the asset the lot `id` has been split from
+    pub fn parent_of(&self, id: AssetId) -> Option<AssetId> {
+        self.asset_parent.get(&id).cloned()
+    }
+
+    /// Returns the id of the next lot, it must be checked to be free before splitting
+    pub fn next_lot_id(&self, skip: u32) -> AssetId {
+        FIRST_LOT_ID.saturating_add(self.lots_count).saturating_add(skip)
+    }
+
+    /// Moves `amount` of the quantity of asset `id` to the new lot `lot_id`.
+    /// The amount must be greater than 0 and less than the quantity of the asset.
+    pub fn split(&mut self, id: AssetId, lot_id: AssetId, amount: u128) -> Result<(), Error> {
+        let quantity = self.asset_quantity.get_mut(&id).ok_or(Error::QuantityNotFound)?;
+        if amount == 0 || amount >= quantity.amount {
+            return Err(Error::InvalidQuantity)
+        }
+        quantity.amount -= amount;
+        let unit = quantity.unit;
+        self.asset_quantity.insert(lot_id, Quantity { amount, unit });
+        self.asset_parent.insert(lot_id, id);
+        self.lots_count = lot_id - FIRST_LOT_ID + 1;
+        Ok(())
+    }
+
+    /// Removes the quantity and the parent of the deleted asset `id`
+    pub fn remove(&mut self, id: AssetId) {
+        self.asset_quantity.take(&id);
+        self.asset_parent.take(&id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn split_works() {
+        let mut quantities = Quantities::default();
+        let lot_id = quantities.next_lot_id(0);
+        assert_eq!(lot_id, FIRST_LOT_ID);
+        assert_eq!(quantities.split(1, lot_id, 10), Err(Error::QuantityNotFound));
+        quantities.set(1, Quantity { amount: 100, unit: Unit::Liter });
+        assert_eq!(quantities.split(1, lot_id, 0), Err(Error::InvalidQuantity));
+        assert_eq!(quantities.split(1, lot_id, 100), Err(Error::InvalidQuantity));
+        assert_eq!(quantities.split(1, lot_id, 30), Ok(()));
+        assert_eq!(quantities.quantity_of(1), Some(Quantity { amount: 70, unit: Unit::Liter }));
+        assert_eq!(quantities.quantity_of(lot_id), Some(Quantity { amount: 30, unit: Unit::Liter }));
+        assert_eq!(quantities.parent_of(lot_id), Some(1));
+        assert_eq!(quantities.next_lot_id(0), FIRST_LOT_ID + 1);
+        assert_eq!(quantities.next_lot_id(2), FIRST_LOT_ID + 3);
+    }
+}
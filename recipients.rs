@@ -0,0 +1,74 @@
+//! # Recipients
+//!
+//! Calls to the receiver hook of the contracts the assets are transferred to.
+//!
+//! Before an asset is moved its destination is called with `on_asset_received(operator: AccountId,
+//! from: AccountId, id: AssetId, data: Vec<u8>) -> bool`: a plain account cannot be called and
+//! receives the asset as before, a contract must implement the hook and return `true`, otherwise
+//! the asset could be parked for good in a contract unable to send it back. The caller of a safe
+//! transfer can still allow a contract without the hook explicitly.
+//!
+//! The off-chain environment of the tests cannot call other contracts: there every contract
+//! account is a contract without the hook.
+//!
+
+use crate::asset_erc721::AssetId;
+#[cfg(not(test))]
+use ink_env::call::{
+    build_call,
+    utils::ReturnType,
+    ExecutionInput,
+    Selector,
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+};
+use ink_prelude::vec::Vec;
+
+/// Selector of the `on_asset_received` message of the receiving contracts
+#[cfg(not(test))]
+const ON_ASSET_RECEIVED: [u8; 4] = [0x91, 0xd2, 0x14, 0x7e];
+
+/// Destination of a transfer
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Recipient {
+    /// Plain account, not a contract
+    Account,
+    /// Contract that accepted the asset from its hook
+    Accepting,
+    /// Contract without the hook or that refused the asset
+    Refusing,
+}
+
+/// Calls the hook of `to` receiving asset `id` from `from` on behalf of `operator`
+#[cfg(not(test))]
+pub fn notify(operator: AccountId, from: AccountId, to: AccountId, id: AssetId, data: Vec<u8>) -> Recipient {
+    let accepted = build_call::<DefaultEnvironment>()
+        .callee(to)
+        .gas_limit(0)
+        .transferred_value(0)
+        .exec_input(
+            ExecutionInput::new(Selector::new(ON_ASSET_RECEIVED))
+                .push_arg(operator)
+                .push_arg(from)
+                .push_arg(id)
+                .push_arg(data),
+        )
+        .returns::<ReturnType<bool>>()
+        .fire();
+    match accepted {
+        Err(ink_env::Error::NotCallable) => Recipient::Account,
+        Ok(true) => Recipient::Accepting,
+        _ => Recipient::Refusing,
+    }
+}
+
+/// Returns the contract accounts of the off-chain environment as contracts without the hook
+#[cfg(test)]
+pub fn notify(_operator: AccountId, _from: AccountId, to: AccountId, _id: AssetId, _data: Vec<u8>) -> Recipient {
+    match ink_env::test::get_contract_rent_allowance::<DefaultEnvironment>(to) {
+        Ok(_) => Recipient::Refusing,
+        Err(_) => Recipient::Account,
+    }
+}
@@ -0,0 +1,232 @@
+//! # Records
+//!
+//! Record of an asset: the owner and the small data read together with it, stored in a single
+//! cell of the contract so most messages load the asset once.
+//!
+//! The record keeps the owner with the position of the asset in the list of its assets, the
+//! hashes of the description, photo, location and metadata, the category, the validation, the
+//! delegated account and the stock status. The larger data, as the translations, the physical attributes, the
+//! sales or the logs, stay in the maps of their components and are loaded only when read.
+//!
+
+use crate::{
+    asset_erc721::{
+        Error,
+        UpdateKind,
+    },
+    pending::PendingTransfer,
+    reservations::Reservation,
+    returns::StockStatus,
+};
+use ink_env::{
+    AccountId,
+    Hash,
+};
+use ink_storage::traits::{
+    PackedLayout,
+    SpreadLayout,
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+/// Record of an asset
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct AssetRecord {
+    /// Owner of the asset
+    pub owner: AccountId,
+    /// Position of the asset in the list of the assets owned from its owner
+    pub index: u32,
+    /// Main description of the asset
+    pub description: Option<Hash>,
+    /// Main photo of the asset - Ipfs Address
+    pub photo: Option<Hash>,
+    /// Location of the asset
+    pub location: Option<Hash>,
+    /// Additional Metadata of the Asset
+    pub metadata: Option<Hash>,
+    /// Category of the asset
+    pub category: Option<u32>,
+    /// Validation of the asset from an administrator role
+    pub validator: Option<AccountId>,
+    /// Account that signed the validation of the asset, answerable with its stake
+    pub signer: Option<AccountId>,
+    /// Proxy account that can manage the asset on behalf of the owner
+    pub delegate: Option<AccountId>,
+    /// Stock status of the asset, blocking its transfer while it is returned
+    pub status: StockStatus,
+    /// Position of the last transfer of the asset in the journal
+    pub last_transfer: Option<u32>,
+    /// Number of the shipments listing the asset not yet delivered nor cancelled
+    pub open_shipments: u32,
+    /// Reservation of the asset for a buyer
+    pub reservation: Option<Reservation>,
+    /// Transfer of the asset waiting for the end of the grace period of its owner
+    pub pending: Option<PendingTransfer>,
+}
+
+impl AssetRecord {
+    /// Returns the record of a new asset of `owner` at position `index` of its list
+    pub fn new(owner: AccountId, index: u32) -> Self {
+        Self {
+            owner,
+            index,
+            description: None,
+            photo: None,
+            location: None,
+            metadata: None,
+            category: None,
+            validator: None,
+            signer: None,
+            delegate: None,
+            status: StockStatus::InStock,
+            last_transfer: None,
+            open_shipments: 0,
+            reservation: None,
+            pending: None,
+        }
+    }
+
+    /// Returns the hash of data `kind`
+    pub fn get(&self, kind: UpdateKind) -> Option<Hash> {
+        match kind {
+            UpdateKind::Description => self.description,
+            UpdateKind::Photo => self.photo,
+            UpdateKind::Location => self.location,
+            UpdateKind::Metadata => self.metadata,
+            UpdateKind::Category
+            | UpdateKind::Validation
+            | UpdateKind::Authenticity
+            | UpdateKind::Tag
+            | UpdateKind::Quantity
+            | UpdateKind::Physical
+            | UpdateKind::LabResult
+            | UpdateKind::Private
+            | UpdateKind::Sealed
+            | UpdateKind::Depreciation
+            | UpdateKind::Service
+            | UpdateKind::Note => None,
+        }
+    }
+
+    /// Returns true if data `kind` is stored
+    pub fn contains(&self, kind: UpdateKind) -> bool {
+        self.get(kind).is_some()
+    }
+
+    /// Stores the hash of data `kind`, fails if it is already present
+    pub fn insert(&mut self, kind: UpdateKind, value: Hash) -> Result<(), Error> {
+        let hash = self.hash_mut(kind).ok_or(Error::NotAllowed)?;
+        if hash.is_some() {
+            return Err(Error::DuplicatedData)
+        }
+        *hash = Some(value);
+        Ok(())
+    }
+
+    /// Stores the hash of data `kind` replacing the previous one, and returns it
+    pub fn replace(&mut self, kind: UpdateKind, value: Hash) -> Result<Option<Hash>, Error> {
+        let hash = self.hash_mut(kind).ok_or(Error::NotAllowed)?;
+        Ok(hash.replace(value))
+    }
+
+    /// Removes the hash of data `kind` and returns it
+    pub fn remove(&mut self, kind: UpdateKind) -> Result<Hash, Error> {
+        let hash = self.hash_mut(kind).ok_or(Error::NotAllowed)?;
+        hash.take().ok_or(Error::AssetNotFound)
+    }
+
+    /// Returns true if `content` is the photo or the metadata of the asset
+    pub fn has_content(&self, content: &Hash) -> bool {
+        self.photo.as_ref() == Some(content) || self.metadata.as_ref() == Some(content)
+    }
+
+    /// Stores `validator` as the validation signed from `signer`, fails if the asset is already validated
+    pub fn validate(&mut self, validator: AccountId, signer: AccountId) -> Result<(), Error> {
+        if self.validator.is_some() {
+            return Err(Error::DuplicatedData)
+        }
+        self.validator = Some(validator);
+        self.signer = Some(signer);
+        Ok(())
+    }
+
+    /// Removes the validation
+    pub fn invalidate(&mut self) -> Result<(), Error> {
+        self.signer = None;
+        self.validator.take().map(|_| ()).ok_or(Error::AssetNotFound)
+    }
+
+    /// Delegates `to` and returns the account delegated before
+    pub fn delegate(&mut self, to: AccountId) -> Result<Option<AccountId>, Error> {
+        if to == AccountId::from([0x0; 32]) {
+            return Err(Error::NotAllowed)
+        }
+        Ok(self.delegate.replace(to))
+    }
+
+    fn hash_mut(&mut self, kind: UpdateKind) -> Option<&mut Option<Hash>> {
+        match kind {
+            UpdateKind::Description => Some(&mut self.description),
+            UpdateKind::Photo => Some(&mut self.photo),
+            UpdateKind::Location => Some(&mut self.location),
+            UpdateKind::Metadata => Some(&mut self.metadata),
+            UpdateKind::Category
+            | UpdateKind::Validation
+            | UpdateKind::Authenticity
+            | UpdateKind::Tag
+            | UpdateKind::Quantity
+            | UpdateKind::Physical
+            | UpdateKind::LabResult
+            | UpdateKind::Private
+            | UpdateKind::Sealed
+            | UpdateKind::Depreciation
+            | UpdateKind::Service
+            | UpdateKind::Note => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn hashes_work() {
+        let mut record = AssetRecord::new(AccountId::from([0x1; 32]), 0);
+        let hash = Hash::from([0x01; 32]);
+        assert_eq!(record.insert(UpdateKind::Photo, hash), Ok(()));
+        assert_eq!(record.insert(UpdateKind::Photo, hash), Err(Error::DuplicatedData));
+        assert_eq!(record.get(UpdateKind::Photo), Some(hash));
+        assert!(record.has_content(&hash));
+        // every kind of data is stored separately
+        assert!(!record.contains(UpdateKind::Description));
+        assert_eq!(record.insert(UpdateKind::Category, hash), Err(Error::NotAllowed));
+        assert_eq!(record.remove(UpdateKind::Photo), Ok(hash));
+        assert_eq!(record.remove(UpdateKind::Photo), Err(Error::AssetNotFound));
+        assert_eq!(record.replace(UpdateKind::Location, hash), Ok(None));
+        assert_eq!(record.replace(UpdateKind::Location, Hash::from([0x02; 32])), Ok(Some(hash)));
+    }
+
+    #[ink::test]
+    fn validation_and_delegation_work() {
+        let alice = AccountId::from([0x1; 32]);
+        let bob = AccountId::from([0x2; 32]);
+        let mut record = AssetRecord::new(alice, 0);
+        assert_eq!(record.invalidate(), Err(Error::AssetNotFound));
+        assert_eq!(record.validate(alice, bob), Ok(()));
+        assert_eq!(record.validate(alice, bob), Err(Error::DuplicatedData));
+        assert_eq!(record.signer, Some(bob));
+        assert_eq!(record.invalidate(), Ok(()));
+        assert_eq!(record.validator, None);
+        assert_eq!(record.signer, None);
+        assert_eq!(record.delegate(AccountId::from([0x0; 32])), Err(Error::NotAllowed));
+        assert_eq!(record.delegate(bob), Ok(None));
+        assert_eq!(record.delegate(alice), Ok(Some(bob)));
+    }
+}
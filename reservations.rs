@@ -0,0 +1,91 @@
+//! # Reservations
+//!
+//! Reservations of the assets placed from their owners for a buyer.
+//!
+//! The owner reserves an asset for a buyer until a block: while the reservation is in force the
+//! asset can be transferred or sold only to the buyer, and the reservation ends once the buyer
+//! receives it. The owner can lock a deposit with the reservation as a guarantee to the buyer:
+//! the deposit is returned to the owner when the reservation converts into a purchase, expires
+//! or is released from the buyer, and it is paid to the buyer when the owner withdraws the
+//! reservation before its expiry. The reservation is kept in the record of the asset, so the
+//! transfers check it with no further reads.
+//!
+
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+};
+use ink_storage::traits::{
+    PackedLayout,
+    SpreadLayout,
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Reservation of an asset for a buyer
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Reservation {
+    /// Buyer the asset is reserved for
+    pub buyer: AccountId,
+    /// Last block of the reservation
+    pub until: BlockNumber,
+    /// Deposit locked from the owner as a guarantee to the buyer
+    pub deposit: Balance,
+}
+
+impl Reservation {
+    /// Returns true if the reservation is in force at block `now`
+    pub fn is_active(&self, now: BlockNumber) -> bool {
+        now <= self.until
+    }
+
+    /// Returns true if the reservation in force at block `now` blocks the transfer of the asset to `to`
+    pub fn blocks(&self, to: &AccountId, now: BlockNumber) -> bool {
+        self.is_active(now) && self.buyer != *to
+    }
+
+    /// Returns the account the deposit is paid to when the reservation is released from `caller` at block `now`:
+    /// the buyer when the owner withdraws the reservation in force, the owner otherwise
+    pub fn deposit_to(&self, owner: AccountId, caller: AccountId, now: BlockNumber) -> AccountId {
+        if caller == owner && self.is_active(now) {
+            self.buyer
+        } else {
+            owner
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn reservation_works() {
+        let alice = AccountId::from([0x1; 32]);
+        let bob = AccountId::from([0x2; 32]);
+        let charlie = AccountId::from([0x3; 32]);
+        let reservation = Reservation {
+            buyer: bob,
+            until: 10,
+            deposit: 100,
+        };
+        // only the buyer receives the asset until the last block of the reservation
+        assert!(reservation.blocks(&charlie, 10));
+        assert!(!reservation.blocks(&bob, 10));
+        assert!(!reservation.blocks(&charlie, 11));
+        // the owner withdrawing the reservation in force pays the deposit to the buyer
+        assert_eq!(reservation.deposit_to(alice, alice, 5), bob);
+        assert_eq!(reservation.deposit_to(alice, bob, 5), alice);
+        assert_eq!(reservation.deposit_to(alice, alice, 11), alice);
+    }
+}
@@ -0,0 +1,183 @@
+//! # Returns
+//!
+//! Restocking records of the assets returned from the buyers.
+//!
+//! The owner receiving a returned asset marks it Returned: the asset cannot be transferred,
+//! listed or sold until an account able to validate the assets inspects it and records the hash
+//! of its report. Once inspected the owner either restocks the asset, which is back InStock, or
+//! routes it to disposal, where it stays blocked. The stock status is kept in the record of the
+//! asset, so the transfers check it with no further reads, while each return is appended to the
+//! restocking records of the asset and never changed once closed.
+//!
+
+use crate::asset_erc721::{
+    AssetId,
+    Error,
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Stock status of an asset
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub enum StockStatus {
+    /// The asset is in the supply chain
+    InStock,
+    /// The asset has been returned and waits for the inspection
+    Returned,
+    /// The asset has been inspected and waits to be restocked or routed to disposal
+    Inspected,
+    /// The asset has been routed to disposal
+    Disposal,
+    /// The asset has been disposed of at the end of its life
+    Disposed,
+    /// The asset has been retired and remanufactured into a new asset
+    Remanufactured,
+}
+
+/// Restocking record of a return
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct ReturnRecord {
+    /// Account that returned the asset
+    pub from: AccountId,
+    /// Hash of the reason of the return
+    pub reason: Hash,
+    /// Block the asset was received at
+    pub received: BlockNumber,
+    /// Account that inspected the asset
+    pub inspector: Option<AccountId>,
+    /// Hash of the report of the inspection
+    pub report: Option<Hash>,
+    /// Status of the asset after the return
+    pub status: StockStatus,
+    /// Block of the last change of the status
+    pub block: BlockNumber,
+}
+
+/// Storage of the restocking records
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Returns {
+    /// Counter of the returns of the asset
+    asset_returns_count: StorageHashMap<AssetId, u32>,
+    /// Returns of the asset, indexed from 0 to the returns counter
+    asset_returns: StorageHashMap<(AssetId, u32), ReturnRecord>,
+}
+
+impl Returns {
+    /// Returns the number of returns of asset `id`
+    pub fn returns_count(&self, id: AssetId) -> u32 {
+        *self.asset_returns_count.get(&id).unwrap_or(&0)
+    }
+
+    /// Returns at most `limit` returns of asset `id` starting from position `offset`, oldest first
+    pub fn returns_of(&self, id: AssetId, offset: u32, limit: u32) -> Vec<ReturnRecord> {
+        (offset..self.returns_count(id))
+            .take(limit as usize)
+            .filter_map(|index| self.asset_returns.get(&(id, index)).cloned())
+            .collect()
+    }
+
+    /// Appends the return `record` of asset `id`
+    pub fn receive(&mut self, id: AssetId, record: ReturnRecord) {
+        let index = self.returns_count(id);
+        self.asset_returns.insert((id, index), record);
+        self.asset_returns_count.insert(id, index + 1);
+    }
+
+    /// Records the inspection of the last return of asset `id`, fails if the asset is not waiting for it
+    pub fn inspect(&mut self, id: AssetId, inspector: AccountId, report: Hash, block: BlockNumber) -> Result<(), Error> {
+        let record = self.last_mut(id, StockStatus::Returned)?;
+        record.inspector = Some(inspector);
+        record.report = Some(report);
+        record.status = StockStatus::Inspected;
+        record.block = block;
+        Ok(())
+    }
+
+    /// Closes the last return of asset `id` with `status`, fails if the asset is not inspected
+    pub fn close(&mut self, id: AssetId, status: StockStatus, block: BlockNumber) -> Result<(), Error> {
+        if !matches!(status, StockStatus::InStock | StockStatus::Disposal) {
+            return Err(Error::NotAllowed)
+        }
+        let record = self.last_mut(id, StockStatus::Inspected)?;
+        record.status = status;
+        record.block = block;
+        Ok(())
+    }
+
+    /// Removes the returns of the deleted asset `id`
+    pub fn remove(&mut self, id: AssetId) {
+        let count = self.asset_returns_count.take(&id).unwrap_or(0);
+        for index in 0..count {
+            self.asset_returns.take(&(id, index));
+        }
+    }
+
+    /// Returns the last return of asset `id`, fails if it is not in `status`
+    fn last_mut(&mut self, id: AssetId, status: StockStatus) -> Result<&mut ReturnRecord, Error> {
+        let index = self.returns_count(id).checked_sub(1).ok_or(Error::NotAllowed)?;
+        self.asset_returns
+            .get_mut(&(id, index))
+            .filter(|record| record.status == status)
+            .ok_or(Error::NotAllowed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn returns_work() {
+        let alice = AccountId::from([0x1; 32]);
+        let record = ReturnRecord {
+            from: alice,
+            reason: Hash::from([0x01; 32]),
+            received: 1,
+            inspector: None,
+            report: None,
+            status: StockStatus::Returned,
+            block: 1,
+        };
+        let mut returns = Returns::default();
+        assert_eq!(returns.inspect(1, alice, Hash::from([0x02; 32]), 2), Err(Error::NotAllowed));
+        returns.receive(1, record);
+        // the asset is inspected before it is restocked
+        assert_eq!(returns.close(1, StockStatus::InStock, 2), Err(Error::NotAllowed));
+        assert_eq!(returns.inspect(1, alice, Hash::from([0x02; 32]), 2), Ok(()));
+        assert_eq!(returns.inspect(1, alice, Hash::from([0x02; 32]), 2), Err(Error::NotAllowed));
+        assert_eq!(returns.close(1, StockStatus::Returned, 3), Err(Error::NotAllowed));
+        assert_eq!(returns.close(1, StockStatus::InStock, 3), Ok(()));
+        returns.receive(1, record);
+        assert_eq!(returns.returns_count(1), 2);
+        assert_eq!(
+            returns.returns_of(1, 0, 10).iter().map(|record| (record.status, record.report)).collect::<Vec<_>>(),
+            [(StockStatus::InStock, Some(Hash::from([0x02; 32]))), (StockStatus::Returned, None)]
+        );
+        returns.remove(1);
+        assert_eq!(returns.returns_count(1), 0);
+    }
+}
@@ -0,0 +1,163 @@
+//! # Revocations
+//!
+//! Revocation list of the certificates and the validations issued on the contract.
+//!
+//! An administrator withdraws a compliance certificate or the validation of an asset with the
+//! hash of the reason, and the revocation is appended to the list with the account that issued
+//! the credential and the block. The verification queries of the contract consult the list, so
+//! a revoked credential is never reported as in force, and the clients follow the list from
+//! their last cursor to react to the withdrawn credentials.
+//!
+//! A revoked validation is replaced from a new validation of the asset: the revocation stays in
+//! the list but does not apply to the new validation.
+//!
+
+use crate::{
+    asset_erc721::{
+        AssetId,
+        Error,
+    },
+    certificates::CertificateId,
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Revocation ID
+pub type RevocationId = u32;
+
+/// Credential issued on the contract that can be revoked
+#[derive(Encode, Decode, Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub enum Credential {
+    /// Compliance certificate of a shipment
+    Certificate(CertificateId),
+    /// Validation of an asset
+    Validation(AssetId),
+}
+
+/// Revocation of a credential
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Revocation {
+    /// Credential revoked
+    pub credential: Credential,
+    /// Account that issued the credential
+    pub issuer: AccountId,
+    /// Administrator that revoked the credential
+    pub revoker: AccountId,
+    /// Hash of the reason of the revocation
+    pub reason: Hash,
+    /// Block of the revocation
+    pub block: BlockNumber,
+}
+
+/// Storage of the revocation list
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Revocations {
+    /// Revocations indexed from 0 to the revocations counter
+    revocations: StorageHashMap<RevocationId, Revocation>,
+    /// Counter of the revocations
+    revocations_count: u32,
+    /// Revocation of the credential while it applies
+    revoked: StorageHashMap<Credential, RevocationId>,
+}
+
+impl Revocations {
+    /// Returns revocation `revocation_id`
+    pub fn get(&self, revocation_id: RevocationId) -> Option<Revocation> {
+        self.revocations.get(&revocation_id).cloned()
+    }
+
+    /// Returns the number of revocations in the list
+    pub fn revocations_count(&self) -> u32 {
+        self.revocations_count
+    }
+
+    /// Returns at most `limit` revocations from position `cursor` of the list, oldest first
+    pub fn list(&self, cursor: u32, limit: u32) -> Vec<Revocation> {
+        (cursor..self.revocations_count.min(cursor.saturating_add(limit)))
+            .filter_map(|revocation_id| self.get(revocation_id))
+            .collect()
+    }
+
+    /// Returns the revocation applying to `credential`
+    pub fn revocation_of(&self, credential: Credential) -> Option<Revocation> {
+        self.revoked.get(&credential).and_then(|revocation_id| self.get(*revocation_id))
+    }
+
+    /// Returns true if `credential` is revoked
+    pub fn is_revoked(&self, credential: Credential) -> bool {
+        self.revoked.contains_key(&credential)
+    }
+
+    /// Appends `revocation` to the list, and returns its id. Fails if the credential is already revoked.
+    pub fn revoke(&mut self, revocation: Revocation) -> Result<RevocationId, Error> {
+        if self.is_revoked(revocation.credential) {
+            return Err(Error::DuplicatedData)
+        }
+        let revocation_id = self.revocations_count;
+        self.revocations.insert(revocation_id, revocation);
+        self.revoked.insert(revocation.credential, revocation_id);
+        self.revocations_count += 1;
+        Ok(revocation_id)
+    }
+
+    /// Stops applying the revocation of `credential` once the credential is replaced or removed, the
+    /// revocation stays in the list
+    pub fn clear(&mut self, credential: Credential) {
+        self.revoked.take(&credential);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn revocations_work() {
+        let revocation = Revocation {
+            credential: Credential::Validation(1),
+            issuer: AccountId::from([0x1; 32]),
+            revoker: AccountId::from([0x2; 32]),
+            reason: Hash::from([0x01; 32]),
+            block: 1,
+        };
+        let mut revocations = Revocations::default();
+        assert!(!revocations.is_revoked(Credential::Validation(1)));
+        assert_eq!(revocations.revoke(revocation), Ok(0));
+        assert_eq!(revocations.revoke(revocation), Err(Error::DuplicatedData));
+        // the credentials of each kind are revoked apart
+        assert!(!revocations.is_revoked(Credential::Certificate(1)));
+        assert_eq!(revocations.revoke(Revocation { credential: Credential::Certificate(1), ..revocation }), Ok(1));
+        assert_eq!(revocations.revocation_of(Credential::Validation(1)), Some(revocation));
+        // a cleared revocation stays in the list
+        revocations.clear(Credential::Validation(1));
+        assert!(!revocations.is_revoked(Credential::Validation(1)));
+        assert_eq!(revocations.revoke(revocation), Ok(2));
+        assert_eq!(revocations.revocations_count(), 3);
+        assert_eq!(revocations.list(1, 5).len(), 2);
+        assert_eq!(revocations.list(0, 1), [revocation]);
+    }
+}
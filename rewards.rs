@@ -0,0 +1,112 @@
+//! # Rewards
+//!
+//! Rewards in a PSP22 token paid for the verification work: validations of the assets, test
+//! results of the laboratories and compliance certificates.
+//!
+//! The administrators set the reward token, which must allow the contract to mint, and the
+//! amount earned for each work from the accounts of each role. A reward that cannot be minted
+//! is skipped: the work is never refused because of the reward token.
+//!
+
+use crate::access_control::Role;
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+};
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+
+/// Verification work rewarded
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub enum Work {
+    /// Validation of an asset
+    Validation,
+    /// Test result of a laboratory
+    LabTest,
+    /// Compliance certificate of a shipment
+    Certificate,
+}
+
+/// Storage of the reward token and of the rewards
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Rewards {
+    /// PSP22 token of the rewards, None = no rewards
+    token: Option<AccountId>,
+    /// Amount earned for each work from the accounts of the role
+    role_rate: StorageHashMap<Role, Balance>,
+    /// Total rewards minted to the account
+    account_earned: StorageHashMap<AccountId, Balance>,
+}
+
+impl Rewards {
+    /// Returns the PSP22 token of the rewards
+    pub fn token(&self) -> Option<AccountId> {
+        self.token
+    }
+
+    /// Sets or removes the PSP22 token of the rewards
+    pub fn token_set(&mut self, token: Option<AccountId>) {
+        self.token = token;
+    }
+
+    /// Returns the amount earned for each work from the accounts of `role`
+    pub fn rate_of(&self, role: Role) -> Balance {
+        *self.role_rate.get(&role).unwrap_or(&0)
+    }
+
+    /// Sets the amount earned for each work from the accounts of `role`, 0 removes the reward
+    pub fn rate_set(&mut self, role: Role, rate: Balance) {
+        if rate == 0 {
+            self.role_rate.take(&role);
+        } else {
+            self.role_rate.insert(role, rate);
+        }
+    }
+
+    /// Returns the total rewards minted to `account`
+    pub fn earned_of(&self, account: &AccountId) -> Balance {
+        *self.account_earned.get(account).unwrap_or(&0)
+    }
+
+    /// Adds `amount` to the rewards minted to `account`
+    pub fn record(&mut self, account: AccountId, amount: Balance) {
+        let earned = self.earned_of(&account).saturating_add(amount);
+        self.account_earned.insert(account, earned);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn rates_work() {
+        let bob = AccountId::from([0x2; 32]);
+        let mut rewards = Rewards::default();
+        assert_eq!(rewards.rate_of(6), 0);
+        rewards.rate_set(6, 10);
+        assert_eq!(rewards.rate_of(6), 10);
+        rewards.rate_set(6, 0);
+        assert_eq!(rewards.rate_of(6), 0);
+        rewards.record(bob, 10);
+        rewards.record(bob, 5);
+        assert_eq!(rewards.earned_of(&bob), 15);
+    }
+}
@@ -0,0 +1,246 @@
+//! # Roles
+//!
+//! Roles of the accounts and registry of the roles.
+//!
+//! The built-in roles listed in [`crate::access_control`] are always registered, the
+//! administrators can register new roles and disable the existing ones. A disabled role
+//! cannot be assigned and gives no permission to the accounts that have it.
+//!
+
+use crate::{
+    access_control::{
+        Capabilities,
+        Role,
+        ADMINISTRATOR,
+        BUILTIN_CAPABILITIES,
+        BUILTIN_ROLES,
+        DEFAULT_CAPABILITIES,
+    },
+    asset_erc721::Error,
+};
+use ink_env::{
+    hash::Blake2x256,
+    AccountId,
+    Hash,
+};
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+/// Role of the registry
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct RoleInfo {
+    /// Hash of the name of the role
+    pub name: Hash,
+    /// The role can be assigned and gives its permissions
+    pub enabled: bool,
+    /// Capabilities of the accounts with the role
+    pub capabilities: Capabilities,
+}
+
+/// Storage of the roles of the accounts
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Roles {
+    /// Mapping the role of an account (0 = Producer, 1= Wholesaler, 2 = Retailer, 3 = Final Buyer, 4=Shipper, 5=Administrator, 6=Laboratory, 7=Inspector, 8=Recycler, 9=Auditor)
+    account_role: StorageHashMap<AccountId, Role>,
+    /// Roles registered from the administrators and built-in roles disabled
+    registry: StorageHashMap<Role, RoleInfo>,
+}
+
+impl Roles {
+    /// Returns the role of `account`
+    pub fn role_of(&self, account: &AccountId) -> Option<Role> {
+        self.account_role.get(account).cloned()
+    }
+
+    /// Returns the role of `account` if it is enabled
+    pub fn active_role_of(&self, account: &AccountId) -> Option<Role> {
+        self.role_of(account).filter(|role| self.is_enabled(*role))
+    }
+
+    /// Returns the hash of the name of a role
+    pub fn name_hash(name: &[u8]) -> Hash {
+        let mut output = [0x0; 32];
+        ink_env::hash_bytes::<Blake2x256>(name, &mut output);
+        Hash::from(output)
+    }
+
+    /// Returns the registration of `role`
+    pub fn role_info(&self, role: Role) -> Option<RoleInfo> {
+        self.registry.get(&role).cloned().or_else(|| {
+            BUILTIN_ROLES.get(role as usize).map(|name| {
+                RoleInfo {
+                    name: Self::name_hash(name),
+                    enabled: true,
+                    capabilities: BUILTIN_CAPABILITIES[role as usize],
+                }
+            })
+        })
+    }
+
+    /// Returns true if `role` is registered and enabled
+    pub fn is_enabled(&self, role: Role) -> bool {
+        self.role_info(role).is_some_and(|info| info.enabled)
+    }
+
+    /// Returns the capabilities of `account`: the ones of its role when enabled, the default ones otherwise
+    pub fn capabilities_of(&self, account: &AccountId) -> Capabilities {
+        self.active_role_of(account)
+            .and_then(|role| self.role_info(role))
+            .map_or(DEFAULT_CAPABILITIES, |info| info.capabilities)
+    }
+
+    /// Registers the new `role` enabled with the default capabilities, fails if it is already registered
+    pub fn register(&mut self, role: Role, name: Hash) -> Result<(), Error> {
+        if self.role_info(role).is_some() {
+            return Err(Error::DuplicatedData)
+        }
+        self.registry.insert(role, RoleInfo {
+            name,
+            enabled: true,
+            capabilities: DEFAULT_CAPABILITIES,
+        });
+        Ok(())
+    }
+
+    /// Sets the capabilities of the registered `role`, the capabilities of the Administrator role cannot be changed
+    pub fn capabilities_set(&mut self, role: Role, capabilities: Capabilities) -> Result<(), Error> {
+        let info = self.role_info(role).ok_or(Error::RoleNotFound)?;
+        if role == ADMINISTRATOR {
+            return Err(Error::NotAllowed)
+        }
+        self.registry.insert(role, RoleInfo { capabilities, ..info });
+        Ok(())
+    }
+
+    /// Enables or disables the registered `role`, the Administrator role cannot be disabled
+    pub fn enabled_set(&mut self, role: Role, enabled: bool) -> Result<(), Error> {
+        let info = self.role_info(role).ok_or(Error::RoleNotFound)?;
+        if role == ADMINISTRATOR {
+            return Err(Error::NotAllowed)
+        }
+        self.registry.insert(role, RoleInfo { enabled, ..info });
+        Ok(())
+    }
+
+    /// Returns true if `account` has the Administrator role
+    pub fn is_administrator(&self, account: &AccountId) -> bool {
+        self.role_of(account) == Some(ADMINISTRATOR)
+    }
+
+    /// Assigns `role` to `account`, fails if the role is not enabled or the account has already a role
+    pub fn insert(&mut self, account: AccountId, role: Role) -> Result<(), Error> {
+        if !self.is_enabled(role) {
+            return Err(Error::CannotInsert)
+        }
+        if self.account_role.contains_key(&account) {
+            return Err(Error::DuplicatedData)
+        }
+        self.account_role.insert(account, role);
+        Ok(())
+    }
+
+    /// Replaces the role of `account` with `role` and returns the previous one, fails if the role is not enabled,
+    /// the account has no role or has already `role`
+    pub fn update(&mut self, account: AccountId, role: Role) -> Result<Role, Error> {
+        if !self.is_enabled(role) {
+            return Err(Error::CannotInsert)
+        }
+        let current = self.account_role.get_mut(&account).ok_or(Error::RoleNotFound)?;
+        if *current == role {
+            return Err(Error::DuplicatedData)
+        }
+        Ok(core::mem::replace(current, role))
+    }
+
+    /// Removes the role of `account` and returns it
+    pub fn remove(&mut self, account: &AccountId) -> Result<Role, Error> {
+        self.account_role.take(account).ok_or(Error::CannotRemove)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::access_control::{
+        AUDITOR,
+        CAN_VALIDATE,
+        RECYCLER,
+        SHIPPER,
+    };
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn roles_work() {
+        let alice = AccountId::from([0x1; 32]);
+        let bob = AccountId::from([0x2; 32]);
+        let mut roles = Roles::default();
+        assert_eq!(roles.insert(alice, AUDITOR + 1), Err(Error::CannotInsert));
+        assert_eq!(roles.insert(alice, ADMINISTRATOR), Ok(()));
+        assert_eq!(roles.insert(alice, SHIPPER), Err(Error::DuplicatedData));
+        assert_eq!(roles.insert(bob, SHIPPER), Ok(()));
+        assert!(roles.is_administrator(&alice));
+        assert!(!roles.is_administrator(&bob));
+        assert_eq!(roles.remove(&alice), Ok(ADMINISTRATOR));
+        assert_eq!(roles.remove(&alice), Err(Error::CannotRemove));
+        assert_eq!(roles.role_of(&alice), None);
+        assert_eq!(roles.role_of(&bob), Some(SHIPPER));
+        // the role is replaced in place, the account always has one
+        assert_eq!(roles.update(alice, SHIPPER), Err(Error::RoleNotFound));
+        assert_eq!(roles.update(bob, AUDITOR + 1), Err(Error::CannotInsert));
+        assert_eq!(roles.update(bob, SHIPPER), Err(Error::DuplicatedData));
+        assert_eq!(roles.update(bob, RECYCLER), Ok(SHIPPER));
+        assert_eq!(roles.role_of(&bob), Some(RECYCLER));
+    }
+
+    #[ink::test]
+    fn registry_works() {
+        let alice = AccountId::from([0x1; 32]);
+        let customs = AUDITOR + 1;
+        let mut roles = Roles::default();
+        assert_eq!(roles.role_info(SHIPPER).map(|info| info.name), Some(Roles::name_hash(b"Shipper")));
+        assert_eq!(roles.role_info(AUDITOR).map(|info| info.capabilities), Some(0));
+        assert_eq!(roles.register(SHIPPER, Hash::from([0x01; 32])), Err(Error::DuplicatedData));
+        assert_eq!(roles.enabled_set(customs, true), Err(Error::RoleNotFound));
+        assert_eq!(roles.register(customs, Roles::name_hash(b"Customs")), Ok(()));
+        assert_eq!(roles.insert(alice, customs), Ok(()));
+        assert_eq!(roles.active_role_of(&alice), Some(customs));
+        // a disabled role gives no permission and cannot be assigned
+        assert_eq!(roles.enabled_set(customs, false), Ok(()));
+        assert_eq!(roles.role_of(&alice), Some(customs));
+        assert_eq!(roles.active_role_of(&alice), None);
+        assert_eq!(roles.remove(&alice), Ok(customs));
+        assert_eq!(roles.insert(alice, customs), Err(Error::CannotInsert));
+        assert_eq!(roles.enabled_set(ADMINISTRATOR, false), Err(Error::NotAllowed));
+    }
+
+    #[ink::test]
+    fn capabilities_work() {
+        let alice = AccountId::from([0x1; 32]);
+        let bob = AccountId::from([0x2; 32]);
+        let mut roles = Roles::default();
+        assert_eq!(roles.capabilities_of(&alice), DEFAULT_CAPABILITIES);
+        assert_eq!(roles.insert(alice, SHIPPER), Ok(()));
+        assert_eq!(roles.capabilities_of(&alice), BUILTIN_CAPABILITIES[SHIPPER as usize]);
+        assert_eq!(roles.capabilities_set(SHIPPER, CAN_VALIDATE), Ok(()));
+        assert_eq!(roles.capabilities_of(&alice), CAN_VALIDATE);
+        assert_eq!(roles.capabilities_of(&bob), DEFAULT_CAPABILITIES);
+        // a disabled role gives the default capabilities
+        assert_eq!(roles.enabled_set(SHIPPER, false), Ok(()));
+        assert_eq!(roles.capabilities_of(&alice), DEFAULT_CAPABILITIES);
+        assert_eq!(roles.capabilities_set(ADMINISTRATOR, 0), Err(Error::NotAllowed));
+        assert_eq!(roles.capabilities_set(AUDITOR + 1, 0), Err(Error::RoleNotFound));
+    }
+}
@@ -0,0 +1,463 @@
+//! # Sales
+//!
+//! Listings of the assets for sale and the records of the sales settled through the contract.
+//!
+//! The price of a listing is either in the settlement token, or in a fiat reference unit: the
+//! amount paid is then computed at the purchase from the price oracle registered for the
+//! currency from the administrators, and the rate used is kept in the record of the sale for
+//! the audits.
+//!
+//! The buyer can attach the distributor that referred the sale: the share of the proceeds
+//! configured from the administrators is then paid to the referrer instead of the seller. The
+//! seller and the buyer cannot refer their own sale.
+//!
+//! The seller can donate a share of the proceeds of a listing to a charity: the share is paid to
+//! the charity at the settlement and recorded in the sale, and the buyers read it on the listing
+//! before the purchase.
+//!
+//! The seller can register discount codes on a listing as the Blake2x256 hashes of the codes,
+//! with a reduction of the price and an expiry. The buyer presents the code at the purchase and
+//! pays the reduced price; a code of a seller is redeemed once, whatever the listing.
+//!
+
+use crate::{
+    asset_erc721::{
+        AssetId,
+        Error,
+    },
+    oracle::Currency,
+};
+use ink_env::{
+    hash::Blake2x256,
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Asset offered for sale from its owner
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Listing {
+    /// Owner of the asset when it was listed
+    pub seller: AccountId,
+    /// Price in the smallest unit of `currency`, or of the settlement token when `currency` is None
+    pub price: Balance,
+    /// Fiat reference unit of the price
+    pub currency: Option<Currency>,
+    /// PSP22 token of the payment, `None` for the native token
+    pub token: Option<AccountId>,
+    /// Charity receiving a share of the proceeds
+    pub charity: Option<Charity>,
+    /// Block of the listing
+    pub block: BlockNumber,
+}
+
+/// Charity receiving a share of the proceeds of a sale
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Charity {
+    /// Account of the charity
+    pub account: AccountId,
+    /// Share of the proceeds of the seller donated, in basis points
+    pub share: u16,
+}
+
+/// Sale of an asset settled through the contract
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Sale {
+    /// Previous owner of the asset
+    pub seller: AccountId,
+    /// New owner of the asset
+    pub buyer: AccountId,
+    /// Price of the listing, reduced from the discount
+    pub price: Balance,
+    /// Fiat reference unit of the price
+    pub currency: Option<Currency>,
+    /// Rate of the oracle used to convert the price, scaled by [`crate::oracle::RATE_SCALE`]
+    pub rate: Option<Balance>,
+    /// Amount paid from the buyer
+    pub amount: Balance,
+    /// PSP22 token of the payment, `None` for the native token
+    pub token: Option<AccountId>,
+    /// Account that referred the sale
+    pub referrer: Option<AccountId>,
+    /// Share of the amount paid to the referrer
+    pub referral: Balance,
+    /// Fee of the amount paid to the treasury
+    pub fee: Balance,
+    /// Charity that received a share of the proceeds
+    pub charity: Option<AccountId>,
+    /// Share of the proceeds donated to the charity
+    pub donation: Balance,
+    /// Hash of the discount code redeemed
+    pub discount: Option<Hash>,
+    /// Block of the sale
+    pub block: BlockNumber,
+}
+
+/// Reduction of the price granted from a discount code
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub enum Reduction {
+    /// Percentage of the price, from 1 to 100
+    Percent(u8),
+    /// Amount in the unit of the price
+    Amount(Balance),
+}
+
+/// Discount code registered on a listing
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Discount {
+    /// Reduction of the price
+    pub reduction: Reduction,
+    /// First block the code cannot be redeemed
+    pub expiry: BlockNumber,
+}
+
+impl Discount {
+    /// Returns true if the reduction is a percentage from 1 to 100 or a positive amount
+    pub fn is_valid(&self) -> bool {
+        match self.reduction {
+            Reduction::Percent(percent) => percent > 0 && percent <= 100,
+            Reduction::Amount(amount) => amount > 0,
+        }
+    }
+
+    /// Returns `price` reduced from the discount
+    pub fn apply(&self, price: Balance) -> Balance {
+        match self.reduction {
+            Reduction::Percent(percent) => price - price.saturating_mul(percent as Balance) / 100,
+            Reduction::Amount(amount) => price.saturating_sub(amount),
+        }
+    }
+}
+
+/// Basis points of the whole proceeds of a sale
+pub const SHARE_MAX: u16 = 10_000;
+
+/// Returns the share `share`, in basis points, of `amount`
+pub fn share_of(amount: Balance, share: u16) -> Balance {
+    amount.saturating_mul(share as Balance) / SHARE_MAX as Balance
+}
+
+/// Storage of the listings, of the sales and of the price oracles
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Sales {
+    /// Price oracle of the fiat reference unit
+    currency_oracle: StorageHashMap<Currency, AccountId>,
+    /// Listing of the asset
+    asset_listing: StorageHashMap<AssetId, Listing>,
+    /// Counter of the sales of each asset
+    asset_sales_count: StorageHashMap<AssetId, u32>,
+    /// List of the sales of each asset, indexed from 0 to the asset sales counter
+    asset_sales: StorageHashMap<(AssetId, u32), Sale>,
+    /// Discount of the hash of a code on the listing of the asset
+    listing_discount: StorageHashMap<(AssetId, Hash), Discount>,
+    /// Hashes of the discount codes on the listing of the asset
+    listing_codes: StorageHashMap<AssetId, Vec<Hash>>,
+    /// Block of the redemption of the hash of a code of the seller
+    code_redeemed: StorageHashMap<(AccountId, Hash), BlockNumber>,
+}
+
+impl Sales {
+    /// Returns the price oracle of `currency`
+    pub fn oracle_of(&self, currency: &Currency) -> Option<AccountId> {
+        self.currency_oracle.get(currency).cloned()
+    }
+
+    /// Registers or removes the price oracle of `currency`
+    pub fn oracle_set(&mut self, currency: Currency, oracle: Option<AccountId>) {
+        match oracle {
+            Some(oracle) => self.currency_oracle.insert(currency, oracle),
+            None => self.currency_oracle.take(&currency),
+        };
+    }
+
+    /// Returns the listing of asset `id`
+    pub fn listing_of(&self, id: AssetId) -> Option<Listing> {
+        self.asset_listing.get(&id).cloned()
+    }
+
+    /// Lists asset `id` for sale, the price in a fiat reference unit needs the oracle of the currency
+    pub fn list(&mut self, id: AssetId, listing: Listing) -> Result<(), Error> {
+        if self.asset_listing.contains_key(&id) {
+            return Err(Error::DuplicatedData)
+        }
+        if let Some(currency) = listing.currency {
+            if !self.currency_oracle.contains_key(&currency) {
+                return Err(Error::OracleNotFound)
+            }
+        }
+        self.asset_listing.insert(id, listing);
+        Ok(())
+    }
+
+    /// Sets or removes the charity of the listing of asset `id`. The share must be positive and the charity cannot
+    /// be the seller.
+    pub fn charity_set(&mut self, id: AssetId, charity: Option<Charity>) -> Result<(), Error> {
+        let listing = self.asset_listing.get_mut(&id).ok_or(Error::ListingNotFound)?;
+        if let Some(charity) = charity {
+            if charity.share == 0 || charity.share > SHARE_MAX || charity.account == listing.seller {
+                return Err(Error::NotAllowed)
+            }
+        }
+        listing.charity = charity;
+        Ok(())
+    }
+
+    /// Removes the listing of asset `id` and returns it
+    pub fn unlist(&mut self, id: AssetId) -> Option<Listing> {
+        self.discounts_clear(id);
+        self.asset_listing.take(&id)
+    }
+
+    /// Returns the hash of a discount `code`
+    pub fn code_hash_of(code: &[u8]) -> Hash {
+        let mut output = [0x0; 32];
+        ink_env::hash_bytes::<Blake2x256>(code, &mut output);
+        Hash::from(output)
+    }
+
+    /// Returns the discount of the hash of a code on the listing of asset `id`
+    pub fn discount_of(&self, id: AssetId, code_hash: Hash) -> Option<Discount> {
+        self.listing_discount.get(&(id, code_hash)).cloned()
+    }
+
+    /// Registers the discount of the hash of a code on the listing of asset `id`, a redeemed code cannot be registered again
+    pub fn discount_add(&mut self, id: AssetId, code_hash: Hash, discount: Discount) -> Result<(), Error> {
+        let listing = self.listing_of(id).ok_or(Error::ListingNotFound)?;
+        if self.code_redeemed.contains_key(&(listing.seller, code_hash)) {
+            return Err(Error::DiscountRedeemed)
+        }
+        if self.listing_discount.insert((id, code_hash), discount).is_none() {
+            let mut codes = self.listing_codes.get(&id).cloned().unwrap_or_default();
+            codes.push(code_hash);
+            self.listing_codes.insert(id, codes);
+        }
+        Ok(())
+    }
+
+    /// Removes the discount of the hash of a code from the listing of asset `id`
+    pub fn discount_remove(&mut self, id: AssetId, code_hash: Hash) -> Result<(), Error> {
+        self.listing_discount.take(&(id, code_hash)).ok_or(Error::DiscountNotFound)?;
+        let mut codes = self.listing_codes.get(&id).cloned().unwrap_or_default();
+        codes.retain(|code| *code != code_hash);
+        self.listing_codes.insert(id, codes);
+        Ok(())
+    }
+
+    /// Returns the hash of `code` and its discount on the listing of asset `id`, if it can be redeemed at block `now`
+    pub fn discount_check(&self, id: AssetId, code: &[u8], now: BlockNumber) -> Result<(Hash, Discount), Error> {
+        let code_hash = Self::code_hash_of(code);
+        let discount = self.discount_of(id, code_hash).ok_or(Error::DiscountNotFound)?;
+        let seller = self.listing_of(id).ok_or(Error::ListingNotFound)?.seller;
+        if self.code_redeemed.contains_key(&(seller, code_hash)) {
+            return Err(Error::DiscountRedeemed)
+        }
+        if now >= discount.expiry {
+            return Err(Error::DiscountExpired)
+        }
+        Ok((code_hash, discount))
+    }
+
+    /// Marks the hash of a code of `seller` as redeemed at block `now`
+    pub fn redeem(&mut self, seller: AccountId, code_hash: Hash, now: BlockNumber) {
+        self.code_redeemed.insert((seller, code_hash), now);
+    }
+
+    /// Returns the block of the redemption of the hash of a code of `seller`
+    pub fn redeemed_of(&self, seller: AccountId, code_hash: Hash) -> Option<BlockNumber> {
+        self.code_redeemed.get(&(seller, code_hash)).cloned()
+    }
+
+    /// Removes the discounts of the listing of asset `id`
+    fn discounts_clear(&mut self, id: AssetId) {
+        for code_hash in self.listing_codes.take(&id).unwrap_or_default() {
+            self.listing_discount.take(&(id, code_hash));
+        }
+    }
+
+    /// Returns the sales of asset `id`, oldest first
+    pub fn sales_of(&self, id: AssetId) -> Vec<Sale> {
+        let count = *self.asset_sales_count.get(&id).unwrap_or(&0);
+        (0..count)
+            .filter_map(|index| self.asset_sales.get(&(id, index)).cloned())
+            .collect()
+    }
+
+    /// Returns the number of sales of asset `id`
+    pub fn sales_count(&self, id: AssetId) -> u32 {
+        *self.asset_sales_count.get(&id).unwrap_or(&0)
+    }
+
+    /// Returns the sale of asset `id` at position `index` of its sales
+    pub fn sale_of(&self, id: AssetId, index: u32) -> Option<Sale> {
+        self.asset_sales.get(&(id, index)).cloned()
+    }
+
+    /// Records the sale of asset `id`, the listing is removed
+    pub fn record(&mut self, id: AssetId, sale: Sale) {
+        self.unlist(id);
+        let index = *self.asset_sales_count.get(&id).unwrap_or(&0);
+        self.asset_sales.insert((id, index), sale);
+        self.asset_sales_count.insert(id, index + 1);
+    }
+
+    /// Replaces `account` with `pseudonym` in the sales of asset `id`
+    pub fn pseudonymize(&mut self, id: AssetId, account: &AccountId, pseudonym: AccountId) {
+        let count = *self.asset_sales_count.get(&id).unwrap_or(&0);
+        for index in 0..count {
+            if let Some(sale) = self.asset_sales.get_mut(&(id, index)) {
+                if sale.seller == *account {
+                    sale.seller = pseudonym;
+                }
+                if sale.buyer == *account {
+                    sale.buyer = pseudonym;
+                }
+                if sale.referrer == Some(*account) {
+                    sale.referrer = Some(pseudonym);
+                }
+                if sale.charity == Some(*account) {
+                    sale.charity = Some(pseudonym);
+                }
+            }
+        }
+    }
+
+    /// Removes the listing and the sales of the deleted asset `id`
+    pub fn remove(&mut self, id: AssetId) {
+        self.unlist(id);
+        let count = self.asset_sales_count.take(&id).unwrap_or(0);
+        for index in 0..count {
+            self.asset_sales.take(&(id, index));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    fn listing(currency: Option<Currency>) -> Listing {
+        Listing {
+            seller: AccountId::from([0x1; 32]),
+            price: 100,
+            currency,
+            token: None,
+            charity: None,
+            block: 1,
+        }
+    }
+
+    #[ink::test]
+    fn listings_work() {
+        let mut sales = Sales::default();
+        assert_eq!(sales.list(1, listing(Some(*b"EUR"))), Err(Error::OracleNotFound));
+        sales.oracle_set(*b"EUR", Some(AccountId::from([0x9; 32])));
+        assert_eq!(sales.oracle_of(b"EUR"), Some(AccountId::from([0x9; 32])));
+        assert_eq!(sales.list(1, listing(Some(*b"EUR"))), Ok(()));
+        assert_eq!(sales.list(1, listing(None)), Err(Error::DuplicatedData));
+        assert_eq!(sales.listing_of(1), Some(listing(Some(*b"EUR"))));
+        // the charity is not the seller and receives a positive share
+        let charity = Charity {
+            account: AccountId::from([0x3; 32]),
+            share: 1_000,
+        };
+        assert_eq!(sales.charity_set(2, Some(charity)), Err(Error::ListingNotFound));
+        assert_eq!(sales.charity_set(1, Some(Charity { share: 0, ..charity })), Err(Error::NotAllowed));
+        assert_eq!(sales.charity_set(1, Some(Charity { account: AccountId::from([0x1; 32]), ..charity })), Err(Error::NotAllowed));
+        assert_eq!(sales.charity_set(1, Some(charity)), Ok(()));
+        assert_eq!(sales.listing_of(1).and_then(|listing| listing.charity), Some(charity));
+        let sale = Sale {
+            seller: AccountId::from([0x1; 32]),
+            buyer: AccountId::from([0x2; 32]),
+            price: 100,
+            currency: Some(*b"EUR"),
+            rate: Some(2_000_000_000),
+            amount: 200,
+            token: None,
+            referrer: None,
+            referral: 0,
+            fee: 0,
+            charity: None,
+            donation: 0,
+            discount: None,
+            block: 2,
+        };
+        sales.record(1, sale);
+        assert_eq!(sales.listing_of(1), None);
+        assert_eq!(sales.sales_of(1), [sale]);
+        sales.remove(1);
+        assert!(sales.sales_of(1).is_empty());
+        sales.oracle_set(*b"EUR", None);
+        assert_eq!(sales.oracle_of(b"EUR"), None);
+    }
+
+    #[ink::test]
+    fn discounts_work() {
+        let seller = AccountId::from([0x1; 32]);
+        let code_hash = Sales::code_hash_of(b"SPRING");
+        let discount = Discount {
+            reduction: Reduction::Percent(10),
+            expiry: 5,
+        };
+        let mut sales = Sales::default();
+        assert_eq!(sales.discount_add(1, code_hash, discount), Err(Error::ListingNotFound));
+        assert_eq!(sales.list(1, listing(None)), Ok(()));
+        assert_eq!(sales.discount_add(1, code_hash, discount), Ok(()));
+        assert_eq!(sales.discount_check(1, b"SUMMER", 1), Err(Error::DiscountNotFound));
+        assert_eq!(sales.discount_check(1, b"SPRING", 5), Err(Error::DiscountExpired));
+        assert_eq!(sales.discount_check(1, b"SPRING", 4), Ok((code_hash, discount)));
+        // a redeemed code cannot be redeemed or registered again
+        sales.redeem(seller, code_hash, 4);
+        assert_eq!(sales.redeemed_of(seller, code_hash), Some(4));
+        assert_eq!(sales.discount_check(1, b"SPRING", 4), Err(Error::DiscountRedeemed));
+        assert_eq!(sales.discount_add(1, code_hash, discount), Err(Error::DiscountRedeemed));
+        // the discounts are removed with the listing
+        sales.unlist(1);
+        assert_eq!(sales.discount_of(1, code_hash), None);
+        assert_eq!(sales.discount_remove(1, code_hash), Err(Error::DiscountNotFound));
+    }
+
+    #[test]
+    fn reduction_works() {
+        let discount = Discount {
+            reduction: Reduction::Percent(10),
+            expiry: 5,
+        };
+        assert_eq!(discount.apply(200), 180);
+        assert_eq!(Discount { reduction: Reduction::Amount(50), ..discount }.apply(200), 150);
+        assert_eq!(Discount { reduction: Reduction::Amount(250), ..discount }.apply(200), 0);
+        assert!(!Discount { reduction: Reduction::Percent(101), ..discount }.is_valid());
+        assert!(!Discount { reduction: Reduction::Amount(0), ..discount }.is_valid());
+    }
+
+    #[test]
+    fn share_works() {
+        assert_eq!(share_of(200, 250), 5);
+        assert_eq!(share_of(200, SHARE_MAX), 200);
+        assert_eq!(share_of(199, 50), 0);
+    }
+}
@@ -0,0 +1,170 @@
+//! # Sealed
+//!
+//! Commit-reveal updates of the commercially sensitive fields of the assets, like a negotiated
+//! price.
+//!
+//! The owner commits the Blake2x256 hash of the value followed by a random salt: the block of
+//! the commitment proves when the value was agreed while the value stays private. When both
+//! parties are ready, any of them reveals the value and the salt, and the value is disclosed
+//! with the block of its commitment.
+//!
+
+use crate::asset_erc721::{
+    AssetId,
+    Error,
+};
+use ink_env::{
+    hash::Blake2x256,
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Commitment of a sealed value
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Commitment {
+    /// Blake2x256 hash of the value followed by the salt
+    pub hash: Hash,
+    /// Account that committed the value
+    pub committer: AccountId,
+    /// Block of the commitment
+    pub block: BlockNumber,
+}
+
+/// Sealed value disclosed
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Disclosure {
+    /// Value revealed
+    pub value: Vec<u8>,
+    /// Account that committed the value
+    pub committer: AccountId,
+    /// Block of the commitment
+    pub committed: BlockNumber,
+    /// Block of the reveal
+    pub revealed: BlockNumber,
+}
+
+/// Storage of the sealed values of the assets
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Sealed {
+    /// Commitment of the asset waiting for its reveal
+    asset_pending: StorageHashMap<AssetId, Commitment>,
+    /// Counter of the disclosures of each asset
+    asset_disclosures_count: StorageHashMap<AssetId, u32>,
+    /// List of the disclosures of each asset, indexed from 0 to the asset disclosures counter
+    asset_disclosures: StorageHashMap<(AssetId, u32), Disclosure>,
+}
+
+impl Sealed {
+    /// Returns the hash of `value` followed by `salt` to commit for an asset
+    pub fn commitment_of(value: &[u8], salt: &Hash) -> Hash {
+        let mut input = value.to_vec();
+        input.extend_from_slice(salt.as_ref());
+        let mut output = [0x0; 32];
+        ink_env::hash_bytes::<Blake2x256>(&input, &mut output);
+        Hash::from(output)
+    }
+
+    /// Returns the commitment of asset `id` waiting for its reveal
+    pub fn pending_of(&self, id: AssetId) -> Option<Commitment> {
+        self.asset_pending.get(&id).cloned()
+    }
+
+    /// Stores the commitment of asset `id`, the previous commitment must have been revealed
+    pub fn commit(&mut self, id: AssetId, commitment: Commitment) -> Result<(), Error> {
+        if self.asset_pending.contains_key(&id) {
+            return Err(Error::DuplicatedData)
+        }
+        self.asset_pending.insert(id, commitment);
+        Ok(())
+    }
+
+    /// Checks `value` and `salt` match the pending commitment of asset `id` and discloses the value
+    pub fn reveal(&mut self, id: AssetId, value: Vec<u8>, salt: &Hash, block: BlockNumber) -> Result<(), Error> {
+        let commitment = self.pending_of(id).ok_or(Error::CommitmentNotFound)?;
+        if commitment.hash != Self::commitment_of(&value, salt) {
+            return Err(Error::InvalidSecret)
+        }
+        self.asset_pending.take(&id);
+        let index = *self.asset_disclosures_count.get(&id).unwrap_or(&0);
+        self.asset_disclosures.insert((id, index), Disclosure {
+            value,
+            committer: commitment.committer,
+            committed: commitment.block,
+            revealed: block,
+        });
+        self.asset_disclosures_count.insert(id, index + 1);
+        Ok(())
+    }
+
+    /// Returns the disclosures of asset `id`, oldest first
+    pub fn disclosures_of(&self, id: AssetId) -> Vec<Disclosure> {
+        let count = *self.asset_disclosures_count.get(&id).unwrap_or(&0);
+        (0..count)
+            .filter_map(|index| self.asset_disclosures.get(&(id, index)).cloned())
+            .collect()
+    }
+
+    /// Removes the commitment and the disclosures of the deleted asset `id`
+    pub fn remove(&mut self, id: AssetId) {
+        self.asset_pending.take(&id);
+        let count = self.asset_disclosures_count.take(&id).unwrap_or(0);
+        for index in 0..count {
+            self.asset_disclosures.take(&(id, index));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn reveal_works() {
+        let salt = Hash::from([0x05; 32]);
+        let commitment = Commitment {
+            hash: Sealed::commitment_of(b"1500", &salt),
+            committer: AccountId::from([0x1; 32]),
+            block: 1,
+        };
+        let mut sealed = Sealed::default();
+        assert_eq!(sealed.reveal(1, b"1500".to_vec(), &salt, 2), Err(Error::CommitmentNotFound));
+        assert_eq!(sealed.commit(1, commitment), Ok(()));
+        assert_eq!(sealed.commit(1, commitment), Err(Error::DuplicatedData));
+        assert_eq!(sealed.reveal(1, b"1400".to_vec(), &salt, 2), Err(Error::InvalidSecret));
+        assert_eq!(sealed.reveal(1, b"1500".to_vec(), &Hash::from([0x06; 32]), 2), Err(Error::InvalidSecret));
+        assert_eq!(sealed.reveal(1, b"1500".to_vec(), &salt, 3), Ok(()));
+        assert_eq!(sealed.pending_of(1), None);
+        assert_eq!(sealed.disclosures_of(1), [Disclosure {
+            value: b"1500".to_vec(),
+            committer: AccountId::from([0x1; 32]),
+            committed: 1,
+            revealed: 3,
+        }]);
+        // a new value can be committed once the previous one is revealed
+        assert_eq!(sealed.commit(1, commitment), Ok(()));
+        sealed.remove(1);
+        assert!(sealed.disclosures_of(1).is_empty());
+    }
+}
@@ -0,0 +1,167 @@
+//! # Services
+//!
+//! Maintenance and service records of the durable assets, like machinery and vehicles.
+//!
+//! The owner authorizes the service providers on an asset, like a workshop or the dealer, then
+//! they append the entries of their services: the type of service, the hash of the report and
+//! the reading of the odometer or of the usage counter, which cannot go backwards. The log stays
+//! with the asset when it is transferred, the authorizations are dropped with the ownership.
+//!
+
+use crate::asset_erc721::{
+    AssetId,
+    Error,
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Type of service, the codes are agreed between the parties of the platform
+pub type ServiceType = u32;
+
+/// Entry of the service log of an asset
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct ServiceEntry {
+    /// Service provider that did the service
+    pub provider: AccountId,
+    /// Type of the service
+    pub service_type: ServiceType,
+    /// Hash of the report of the service
+    pub report: Hash,
+    /// Reading of the odometer or of the usage counter
+    pub usage: u64,
+    /// Block of the record
+    pub block: BlockNumber,
+}
+
+/// Storage of the service logs of the assets
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Services {
+    /// Service providers authorized on the asset
+    asset_providers: StorageHashMap<AssetId, Vec<AccountId>>,
+    /// Counter of the entries of each asset
+    asset_entries_count: StorageHashMap<AssetId, u32>,
+    /// List of the entries of each asset, indexed from 0 to the asset entries counter
+    asset_entries: StorageHashMap<(AssetId, u32), ServiceEntry>,
+}
+
+impl Services {
+    /// Returns the service providers authorized on asset `id`
+    pub fn providers_of(&self, id: AssetId) -> Vec<AccountId> {
+        self.asset_providers.get(&id).cloned().unwrap_or_default()
+    }
+
+    /// Authorizes or revokes `provider` on asset `id`
+    pub fn authorize(&mut self, id: AssetId, provider: AccountId, authorized: bool) {
+        let mut providers = self.providers_of(id);
+        providers.retain(|other| *other != provider);
+        if authorized {
+            providers.push(provider);
+        }
+        if providers.is_empty() {
+            self.asset_providers.take(&id);
+        } else {
+            self.asset_providers.insert(id, providers);
+        }
+    }
+
+    /// Revokes all the service providers of asset `id`
+    pub fn revoke_all(&mut self, id: AssetId) {
+        self.asset_providers.take(&id);
+    }
+
+    /// Returns the number of entries of asset `id`
+    pub fn entries_count(&self, id: AssetId) -> u32 {
+        *self.asset_entries_count.get(&id).unwrap_or(&0)
+    }
+
+    /// Returns at most `limit` entries of asset `id` starting from position `offset`, oldest first
+    pub fn entries_of(&self, id: AssetId, offset: u32, limit: u32) -> Vec<ServiceEntry> {
+        (offset..self.entries_count(id))
+            .take(limit as usize)
+            .filter_map(|index| self.asset_entries.get(&(id, index)).cloned())
+            .collect()
+    }
+
+    /// Appends `entry` to the log of asset `id`. The provider must be authorized on the asset and the
+    /// usage cannot be lower than the one of the last entry.
+    pub fn record(&mut self, id: AssetId, entry: ServiceEntry) -> Result<(), Error> {
+        if !self.asset_providers.get(&id).is_some_and(|providers| providers.contains(&entry.provider)) {
+            return Err(Error::NotAllowed)
+        }
+        let index = self.entries_count(id);
+        if index > 0 && self.asset_entries.get(&(id, index - 1)).is_some_and(|last| entry.usage < last.usage) {
+            return Err(Error::InvalidUsage)
+        }
+        self.asset_entries.insert((id, index), entry);
+        self.asset_entries_count.insert(id, index + 1);
+        Ok(())
+    }
+
+    /// Removes the authorizations and the log of the deleted asset `id`
+    pub fn remove(&mut self, id: AssetId) {
+        self.asset_providers.take(&id);
+        let count = self.asset_entries_count.take(&id).unwrap_or(0);
+        for index in 0..count {
+            self.asset_entries.take(&(id, index));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    fn entry(usage: u64) -> ServiceEntry {
+        ServiceEntry {
+            provider: AccountId::from([0x1; 32]),
+            service_type: 3,
+            report: Hash::from([0x01; 32]),
+            usage,
+            block: 1,
+        }
+    }
+
+    #[ink::test]
+    fn record_works() {
+        let provider = AccountId::from([0x1; 32]);
+        let mut services = Services::default();
+        assert_eq!(services.record(1, entry(1_000)), Err(Error::NotAllowed));
+        services.authorize(1, provider, true);
+        services.authorize(1, provider, true);
+        assert_eq!(services.providers_of(1), [provider]);
+        assert_eq!(services.record(1, entry(1_000)), Ok(()));
+        assert_eq!(services.record(1, entry(900)), Err(Error::InvalidUsage));
+        assert_eq!(services.record(1, entry(1_500)), Ok(()));
+        assert_eq!(services.record(1, entry(2_000)), Ok(()));
+        assert_eq!(services.entries_count(1), 3);
+        assert_eq!(services.entries_of(1, 1, 1), [entry(1_500)]);
+        assert_eq!(services.entries_of(1, 1, 10), [entry(1_500), entry(2_000)]);
+        services.revoke_all(1);
+        assert_eq!(services.record(1, entry(2_500)), Err(Error::NotAllowed));
+        services.remove(1);
+        assert!(services.entries_of(1, 0, 10).is_empty());
+    }
+}
@@ -0,0 +1,511 @@
+//! # Shipments
+//!
+//! Manifests of the shipments handed over between the parties of the supply chain.
+//!
+//! A manifest lists the assets of a shipment with their quantities and the seals applied to the
+//! packages. The Blake2x256 hash of the encoded manifest is stored with it, so the parties can
+//! check a copy of the manifest received off-chain against the one stored in the contract.
+//! The manifests are numbered from 0 in the order they are created and cannot be changed.
+//!
+//! The consignor can agree the freight cost of a shipment with a shipper, depositing it in the
+//! contract. The deposit is paid to the shipper when the receiver confirms the delivery.
+//!
+//! The freight can come with a service level agreement: a delivery confirmed after the deadline
+//! costs the shipper a penalty for each block of delay, up to a cap. The penalty is deducted
+//! from the deposit and refunded to the consignor, the part exceeding the deposit is slashed
+//! from the stake of the shipper. A penalty that cannot be paid at the delivery is owed to the
+//! consignor, who claims it later, so the delivery and the payment of the shipper stand.
+//!
+//! The customs duty of a cross-border shipment can be deposited in the contract when the manifest
+//! is created. It is paid to the customs collector on clearance, or refunded to the sender if the
+//! shipment is cancelled before the clearance.
+//!
+
+use crate::{
+    asset_erc721::{
+        AssetId,
+        Error,
+    },
+    jurisdictions::Jurisdiction,
+    quantity::Quantity,
+};
+use ink_env::{
+    hash::Blake2x256,
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+    Lazy,
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Manifest ID
+pub type ManifestId = u32;
+
+/// Maximum number of assets listed in a manifest
+pub const MAX_ITEMS: usize = 100;
+
+/// Asset listed in a manifest
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct ManifestItem {
+    /// Asset shipped
+    pub id: AssetId,
+    /// Quantity of the asset when the manifest has been created
+    pub quantity: Option<Quantity>,
+    /// Hash of the seal applied to the package of the asset
+    pub seal: Hash,
+}
+
+/// Manifest of a shipment
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Manifest {
+    /// Account handing over the shipment
+    pub sender: AccountId,
+    /// Account receiving the shipment
+    pub receiver: AccountId,
+    /// Block of the creation of the manifest
+    pub block: BlockNumber,
+    /// Assets of the shipment
+    pub items: Vec<ManifestItem>,
+}
+
+impl Manifest {
+    /// Returns the Blake2x256 hash of the encoded manifest
+    pub fn hash(&self) -> Hash {
+        let mut output = [0x0; 32];
+        ink_env::hash_encoded::<Blake2x256, _>(self, &mut output);
+        Hash::from(output)
+    }
+}
+
+/// Freight cost of a shipment agreed with the shipper
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Freight {
+    /// Shipper paid on delivery
+    pub shipper: AccountId,
+    /// Cost deposited from the consignor
+    pub cost: Balance,
+    /// PSP22 token of the payment, `None` for the native token
+    pub token: Option<AccountId>,
+    /// The cost has been paid to the shipper
+    pub settled: bool,
+    /// Service level agreed for the delivery
+    pub sla: Option<Sla>,
+    /// Penalty due for the late delivery, computed on delivery
+    pub penalty: Balance,
+}
+
+/// Service level agreed for the delivery of a shipment
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Sla {
+    /// Last block of a delivery on time
+    pub deadline: BlockNumber,
+    /// Penalty for each block of delay
+    pub penalty_per_block: Balance,
+    /// Maximum penalty
+    pub cap: Balance,
+}
+
+impl Sla {
+    /// Returns the penalty of a delivery at block `delivered`
+    pub fn penalty(&self, delivered: BlockNumber) -> Balance {
+        let delay = delivered.saturating_sub(self.deadline) as Balance;
+        delay.saturating_mul(self.penalty_per_block).min(self.cap)
+    }
+}
+
+/// State of the customs duty of a shipment
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub enum DutyStatus {
+    /// Deposited in the contract
+    Escrowed,
+    /// Paid to the customs collector
+    Released,
+    /// Refunded to the sender
+    Refunded,
+}
+
+/// Customs duty of a cross-border shipment
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Duty {
+    /// Customs collector paid on clearance
+    pub collector: AccountId,
+    /// Jurisdiction of the customs, `None` if the collector is not registered
+    pub jurisdiction: Option<Jurisdiction>,
+    /// Amount deposited from the sender
+    pub amount: Balance,
+    /// PSP22 token of the payment, `None` for the native token
+    pub token: Option<AccountId>,
+    /// State of the duty
+    pub status: DutyStatus,
+}
+
+/// Storage of the manifests of the shipments
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Shipments {
+    /// Manifests indexed from 0 to the manifests counter
+    manifests: StorageHashMap<ManifestId, Manifest>,
+    /// Hash of the manifest
+    manifest_hash: StorageHashMap<ManifestId, Hash>,
+    /// Counter of the manifests created
+    manifests_count: u32,
+    /// Freight agreed for the shipment
+    manifest_freight: StorageHashMap<ManifestId, Freight>,
+    /// Block of the delivery of the shipment
+    manifest_delivery: StorageHashMap<ManifestId, BlockNumber>,
+    /// Customs duty of the shipment
+    manifest_duty: StorageHashMap<ManifestId, Duty>,
+    /// Block of the cancellation of the shipment
+    manifest_cancellation: StorageHashMap<ManifestId, BlockNumber>,
+    /// Penalties owed to the consignor in the token, loaded when used
+    penalties_owed: Lazy<StorageHashMap<(AccountId, Option<AccountId>), Balance>>,
+}
+
+impl Shipments {
+    /// Returns the manifest `manifest_id`
+    pub fn get(&self, manifest_id: ManifestId) -> Option<Manifest> {
+        self.manifests.get(&manifest_id).cloned()
+    }
+
+    /// Returns the hash of the manifest `manifest_id`
+    pub fn hash_of(&self, manifest_id: ManifestId) -> Option<Hash> {
+        self.manifest_hash.get(&manifest_id).cloned()
+    }
+
+    /// Checks the manifest lists at least one asset and at most [`MAX_ITEMS`], each asset once
+    pub fn check(manifest: &Manifest) -> Result<(), Error> {
+        if manifest.items.is_empty() || manifest.items.len() > MAX_ITEMS {
+            return Err(Error::InvalidManifest)
+        }
+        for (position, item) in manifest.items.iter().enumerate() {
+            if manifest.items[..position].iter().any(|other| other.id == item.id) {
+                return Err(Error::DuplicatedData)
+            }
+        }
+        Ok(())
+    }
+
+    /// Stores the manifest and returns its id and its hash, the manifest must pass [`Shipments::check`]
+    pub fn create(&mut self, manifest: Manifest) -> Result<(ManifestId, Hash), Error> {
+        Self::check(&manifest)?;
+        let manifest_id = self.manifests_count;
+        let hash = manifest.hash();
+        self.manifests.insert(manifest_id, manifest);
+        self.manifest_hash.insert(manifest_id, hash);
+        self.manifests_count += 1;
+        Ok((manifest_id, hash))
+    }
+
+    /// Returns the freight agreed for the shipment `manifest_id`
+    pub fn freight_of(&self, manifest_id: ManifestId) -> Option<Freight> {
+        self.manifest_freight.get(&manifest_id).cloned()
+    }
+
+    /// Stores the freight of the shipment `manifest_id`, it can be agreed once before the delivery
+    pub fn freight_set(&mut self, manifest_id: ManifestId, freight: Freight) -> Result<(), Error> {
+        if !self.manifests.contains_key(&manifest_id) {
+            return Err(Error::ManifestNotFound)
+        }
+        if self.manifest_cancellation.contains_key(&manifest_id) {
+            return Err(Error::ShipmentCancelled)
+        }
+        if self.manifest_freight.contains_key(&manifest_id) || self.manifest_delivery.contains_key(&manifest_id) {
+            return Err(Error::DuplicatedData)
+        }
+        self.manifest_freight.insert(manifest_id, freight);
+        Ok(())
+    }
+
+    /// Removes the freight of the shipment `manifest_id`
+    pub fn freight_remove(&mut self, manifest_id: ManifestId) -> Option<Freight> {
+        self.manifest_freight.take(&manifest_id)
+    }
+
+    /// Returns the block of the delivery of the shipment `manifest_id`
+    pub fn delivery_of(&self, manifest_id: ManifestId) -> Option<BlockNumber> {
+        self.manifest_delivery.get(&manifest_id).cloned()
+    }
+
+    /// Records the delivery of the shipment `manifest_id` and settles its freight with the penalty
+    /// of a late delivery, returning the freight to pay. The delivery can be recorded once.
+    pub fn deliver(&mut self, manifest_id: ManifestId, block: BlockNumber) -> Result<Option<Freight>, Error> {
+        if !self.manifests.contains_key(&manifest_id) {
+            return Err(Error::ManifestNotFound)
+        }
+        if self.manifest_cancellation.contains_key(&manifest_id) {
+            return Err(Error::ShipmentCancelled)
+        }
+        if self.manifest_delivery.contains_key(&manifest_id) {
+            return Err(Error::DuplicatedData)
+        }
+        self.manifest_delivery.insert(manifest_id, block);
+        Ok(self.manifest_freight.get_mut(&manifest_id).map(|freight| {
+            freight.settled = true;
+            freight.penalty = freight.sla.map_or(0, |sla| sla.penalty(block));
+            *freight
+        }))
+    }
+
+    /// Reverts the delivery of the shipment `manifest_id` when the freight cannot be paid
+    pub fn undeliver(&mut self, manifest_id: ManifestId) {
+        self.manifest_delivery.take(&manifest_id);
+        if let Some(freight) = self.manifest_freight.get_mut(&manifest_id) {
+            freight.settled = false;
+            freight.penalty = 0;
+        }
+    }
+
+    /// Returns the customs duty of the shipment `manifest_id`
+    pub fn duty_of(&self, manifest_id: ManifestId) -> Option<Duty> {
+        self.manifest_duty.get(&manifest_id).cloned()
+    }
+
+    /// Stores the customs duty deposited for the new shipment `manifest_id`
+    pub fn duty_set(&mut self, manifest_id: ManifestId, duty: Duty) {
+        self.manifest_duty.insert(manifest_id, duty);
+    }
+
+    /// Releases the escrowed duty of the shipment `manifest_id` to the collector and returns it
+    pub fn clear(&mut self, manifest_id: ManifestId) -> Result<Duty, Error> {
+        if self.manifest_cancellation.contains_key(&manifest_id) {
+            return Err(Error::ShipmentCancelled)
+        }
+        let duty = self.manifest_duty.get_mut(&manifest_id).ok_or(Error::DutyNotFound)?;
+        if duty.status != DutyStatus::Escrowed {
+            return Err(Error::DuplicatedData)
+        }
+        duty.status = DutyStatus::Released;
+        Ok(*duty)
+    }
+
+    /// Sets the duty of the shipment `manifest_id` as escrowed again when it cannot be paid
+    pub fn unclear(&mut self, manifest_id: ManifestId) {
+        if let Some(duty) = self.manifest_duty.get_mut(&manifest_id) {
+            duty.status = DutyStatus::Escrowed;
+        }
+    }
+
+    /// Returns the block of the cancellation of the shipment `manifest_id`
+    pub fn cancellation_of(&self, manifest_id: ManifestId) -> Option<BlockNumber> {
+        self.manifest_cancellation.get(&manifest_id).cloned()
+    }
+
+    /// Cancels the shipment `manifest_id` before its delivery. Returns the escrowed duty and the
+    /// unpaid freight to refund, the freight is removed.
+    pub fn cancel(&mut self, manifest_id: ManifestId, block: BlockNumber) -> Result<(Option<Duty>, Option<Freight>), Error> {
+        if !self.manifests.contains_key(&manifest_id) {
+            return Err(Error::ManifestNotFound)
+        }
+        if self.manifest_cancellation.contains_key(&manifest_id) {
+            return Err(Error::ShipmentCancelled)
+        }
+        if self.manifest_delivery.contains_key(&manifest_id) {
+            return Err(Error::NotAllowed)
+        }
+        self.manifest_cancellation.insert(manifest_id, block);
+        let duty = self
+            .manifest_duty
+            .get_mut(&manifest_id)
+            .filter(|duty| duty.status == DutyStatus::Escrowed)
+            .map(|duty| {
+                duty.status = DutyStatus::Refunded;
+                *duty
+            });
+        Ok((duty, self.manifest_freight.take(&manifest_id)))
+    }
+
+    /// Returns the penalties owed to `account` in `token`
+    pub fn owed_of(&self, account: &AccountId, token: Option<AccountId>) -> Balance {
+        *self.penalties_owed.get(&(*account, token)).unwrap_or(&0)
+    }
+
+    /// Adds `amount` in `token` to the penalties owed to `account`, when they cannot be paid
+    pub fn owe(&mut self, account: AccountId, token: Option<AccountId>, amount: Balance) {
+        let owed = self.owed_of(&account, token);
+        self.penalties_owed.insert((account, token), owed.saturating_add(amount));
+    }
+
+    /// Takes the penalties owed to `account` in `token` to pay them, and returns them
+    pub fn claim(&mut self, account: AccountId, token: Option<AccountId>) -> Result<Balance, Error> {
+        self.penalties_owed.take(&(account, token)).ok_or(Error::InsufficientBalance)
+    }
+
+    /// Reverts the cancellation of the shipment `manifest_id` when a refund cannot be paid, escrowing again the duty
+    /// and the freight not refunded. A duty refunded already stays refunded, so it cannot be paid twice.
+    pub fn uncancel(&mut self, manifest_id: ManifestId, duty: Option<Duty>, freight: Option<Freight>) {
+        self.manifest_cancellation.take(&manifest_id);
+        if let Some(duty) = duty {
+            self.manifest_duty.insert(manifest_id, Duty {
+                status: DutyStatus::Escrowed,
+                ..duty
+            });
+        }
+        if let Some(freight) = freight {
+            self.manifest_freight.insert(manifest_id, freight);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    fn manifest(ids: &[AssetId]) -> Manifest {
+        Manifest {
+            sender: AccountId::from([0x1; 32]),
+            receiver: AccountId::from([0x2; 32]),
+            block: 1,
+            items: ids
+                .iter()
+                .map(|id| {
+                    ManifestItem {
+                        id: *id,
+                        quantity: None,
+                        seal: Hash::from([*id as u8; 32]),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    #[ink::test]
+    fn create_works() {
+        let mut shipments = Shipments::default();
+        assert_eq!(shipments.create(manifest(&[])), Err(Error::InvalidManifest));
+        assert_eq!(shipments.create(manifest(&[1, 2, 1])), Err(Error::DuplicatedData));
+        let hash = manifest(&[1, 2]).hash();
+        assert_eq!(shipments.create(manifest(&[1, 2])), Ok((0, hash)));
+        assert_eq!(shipments.get(0), Some(manifest(&[1, 2])));
+        assert_eq!(shipments.hash_of(0), Some(hash));
+        // a different manifest has a different hash
+        assert_eq!(shipments.create(manifest(&[1, 3])).map(|(id, _)| id), Ok(1));
+        assert_ne!(shipments.hash_of(1), Some(hash));
+    }
+
+    #[ink::test]
+    fn deliver_settles_freight() {
+        let freight = Freight {
+            shipper: AccountId::from([0x3; 32]),
+            cost: 100,
+            token: None,
+            settled: false,
+            sla: None,
+            penalty: 0,
+        };
+        let mut shipments = Shipments::default();
+        assert_eq!(shipments.freight_set(0, freight), Err(Error::ManifestNotFound));
+        assert_eq!(shipments.create(manifest(&[1])).map(|(id, _)| id), Ok(0));
+        assert_eq!(shipments.create(manifest(&[2])).map(|(id, _)| id), Ok(1));
+        assert_eq!(shipments.freight_set(0, freight), Ok(()));
+        assert_eq!(shipments.freight_set(0, freight), Err(Error::DuplicatedData));
+        let settled = Freight { settled: true, ..freight };
+        assert_eq!(shipments.deliver(0, 5), Ok(Some(settled)));
+        assert_eq!(shipments.deliver(0, 6), Err(Error::DuplicatedData));
+        assert_eq!(shipments.delivery_of(0), Some(5));
+        assert_eq!(shipments.freight_of(0), Some(settled));
+        // a shipment without freight is delivered without payment
+        assert_eq!(shipments.deliver(1, 5), Ok(None));
+        assert_eq!(shipments.freight_set(1, freight), Err(Error::DuplicatedData));
+        shipments.undeliver(0);
+        assert_eq!(shipments.delivery_of(0), None);
+        assert_eq!(shipments.freight_of(0), Some(freight));
+    }
+
+    #[ink::test]
+    fn late_delivery_penalty_works() {
+        let sla = Sla {
+            deadline: 10,
+            penalty_per_block: 5,
+            cap: 40,
+        };
+        assert_eq!(sla.penalty(9), 0);
+        assert_eq!(sla.penalty(10), 0);
+        assert_eq!(sla.penalty(13), 15);
+        assert_eq!(sla.penalty(100), 40);
+        let freight = Freight {
+            shipper: AccountId::from([0x3; 32]),
+            cost: 100,
+            token: None,
+            settled: false,
+            sla: Some(sla),
+            penalty: 0,
+        };
+        let mut shipments = Shipments::default();
+        assert_eq!(shipments.create(manifest(&[1])).map(|(id, _)| id), Ok(0));
+        assert_eq!(shipments.freight_set(0, freight), Ok(()));
+        assert_eq!(shipments.deliver(0, 12).map(|freight| freight.map(|freight| freight.penalty)), Ok(Some(10)));
+        shipments.undeliver(0);
+        assert_eq!(shipments.freight_of(0), Some(freight));
+    }
+
+    #[ink::test]
+    fn cancel_refunds_duty() {
+        let duty = Duty {
+            collector: AccountId::from([0x4; 32]),
+            jurisdiction: None,
+            amount: 50,
+            token: None,
+            status: DutyStatus::Escrowed,
+        };
+        let mut shipments = Shipments::default();
+        assert_eq!(shipments.create(manifest(&[1])).map(|(id, _)| id), Ok(0));
+        assert_eq!(shipments.create(manifest(&[2])).map(|(id, _)| id), Ok(1));
+        assert_eq!(shipments.clear(0), Err(Error::DutyNotFound));
+        shipments.duty_set(0, duty);
+        shipments.duty_set(1, duty);
+        // the duty of a cleared shipment is not refunded
+        let released = Duty { status: DutyStatus::Released, ..duty };
+        assert_eq!(shipments.clear(1), Ok(released));
+        assert_eq!(shipments.clear(1), Err(Error::DuplicatedData));
+        assert_eq!(shipments.cancel(1, 3), Ok((None, None)));
+        let refunded = Duty { status: DutyStatus::Refunded, ..duty };
+        assert_eq!(shipments.cancel(0, 3), Ok((Some(refunded), None)));
+        assert_eq!(shipments.cancel(0, 3), Err(Error::ShipmentCancelled));
+        assert_eq!(shipments.clear(0), Err(Error::ShipmentCancelled));
+        assert_eq!(shipments.deliver(0, 4), Err(Error::ShipmentCancelled));
+        assert_eq!(shipments.cancellation_of(0), Some(3));
+        shipments.uncancel(0, Some(refunded), None);
+        assert_eq!(shipments.duty_of(0), Some(duty));
+        assert_eq!(shipments.cancellation_of(0), None);
+    }
+
+    #[ink::test]
+    fn penalties_owed_work() {
+        let alice = AccountId::from([0x1; 32]);
+        let token = Some(AccountId::from([0x5; 32]));
+        let mut shipments = Shipments::default();
+        assert_eq!(shipments.claim(alice, None), Err(Error::InsufficientBalance));
+        shipments.owe(alice, None, 30);
+        shipments.owe(alice, None, 20);
+        shipments.owe(alice, token, 10);
+        assert_eq!(shipments.owed_of(&alice, None), 50);
+        // each token is claimed on its own
+        assert_eq!(shipments.claim(alice, None), Ok(50));
+        assert_eq!(shipments.owed_of(&alice, None), 0);
+        assert_eq!(shipments.owed_of(&alice, token), 10);
+    }
+}
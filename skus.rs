@@ -0,0 +1,146 @@
+//! # SKUs
+//!
+//! Registry of the stock keeping units of the products.
+//!
+//! A SKU code maps to one product definition of [`crate::products`], its category and the
+//! default attributes of the units sold under the code. The codes are unique across the
+//! producers: they are compared trimmed and in upper case, so the same SKU string cannot map
+//! to two conflicting definitions on chain. A code is free again once removed.
+//!
+
+use crate::{
+    asset_erc721::Error,
+    products::{
+        ProductId,
+        Variant,
+    },
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Maximum length of a SKU code
+pub const MAX_SKU_LEN: usize = 32;
+
+/// Definition a SKU code maps to
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct SkuEntry {
+    /// Product of the SKU
+    pub product: ProductId,
+    /// Category of the product
+    pub category: u32,
+    /// Default attributes of the units of the SKU
+    pub attributes: Variant,
+    /// Account that registered the SKU
+    pub registrar: AccountId,
+    /// Block of the last change of the SKU
+    pub block: BlockNumber,
+}
+
+/// Storage of the SKUs
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Skus {
+    /// Definition of the normalized SKU code
+    skus: StorageHashMap<Vec<u8>, SkuEntry>,
+}
+
+impl Skus {
+    /// Returns `code` trimmed and in upper case, fails if it is empty, too long or contains other than
+    /// printable ASCII characters
+    pub fn normalize(code: &[u8]) -> Result<Vec<u8>, Error> {
+        let start = code.iter().position(|byte| !byte.is_ascii_whitespace()).unwrap_or(code.len());
+        let end = code.iter().rposition(|byte| !byte.is_ascii_whitespace()).map_or(start, |end| end + 1);
+        let code = &code[start..end];
+        if code.is_empty() || code.len() > MAX_SKU_LEN || !code.iter().all(|byte| byte.is_ascii_graphic()) {
+            return Err(Error::InvalidSku)
+        }
+        Ok(code.to_ascii_uppercase())
+    }
+
+    /// Returns the definition of SKU `code`
+    pub fn get(&self, code: &[u8]) -> Option<SkuEntry> {
+        let code = Self::normalize(code).ok()?;
+        self.skus.get(&code).cloned()
+    }
+
+    /// Registers SKU `code` with `entry`, fails if the code is taken
+    pub fn register(&mut self, code: &[u8], entry: SkuEntry) -> Result<(), Error> {
+        let code = Self::normalize(code)?;
+        if self.skus.contains_key(&code) {
+            return Err(Error::SkuTaken)
+        }
+        self.skus.insert(code, entry);
+        Ok(())
+    }
+
+    /// Replaces the default attributes of SKU `code`
+    pub fn attributes_set(&mut self, code: &[u8], attributes: Variant, block: BlockNumber) -> Result<(), Error> {
+        let code = Self::normalize(code)?;
+        let entry = self.skus.get_mut(&code).ok_or(Error::SkuNotFound)?;
+        entry.attributes = attributes;
+        entry.block = block;
+        Ok(())
+    }
+
+    /// Removes SKU `code` and returns its definition
+    pub fn remove(&mut self, code: &[u8]) -> Result<SkuEntry, Error> {
+        let code = Self::normalize(code)?;
+        self.skus.take(&code).ok_or(Error::SkuNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn sku_is_unique() {
+        let attributes = Variant {
+            size: Some(38),
+            color: None,
+            formulation: None,
+        };
+        let entry = SkuEntry {
+            product: 0,
+            category: 10,
+            attributes,
+            registrar: AccountId::from([0x1; 32]),
+            block: 1,
+        };
+        let mut skus = Skus::default();
+        assert_eq!(skus.register(b"  ", entry), Err(Error::InvalidSku));
+        assert_eq!(skus.register(b"AB 1", entry), Err(Error::InvalidSku));
+        assert_eq!(skus.register(&[b'A'; MAX_SKU_LEN + 1], entry), Err(Error::InvalidSku));
+        assert_eq!(skus.register(b"ab-1", entry), Ok(()));
+        // the same code in another case or padded maps to the same SKU
+        assert_eq!(skus.register(b" AB-1 ", SkuEntry { product: 1, ..entry }), Err(Error::SkuTaken));
+        assert_eq!(skus.get(b"AB-1"), Some(entry));
+        let attributes = Variant { size: Some(40), ..attributes };
+        assert_eq!(skus.attributes_set(b"AB-2", attributes, 2), Err(Error::SkuNotFound));
+        assert_eq!(skus.attributes_set(b"Ab-1", attributes, 2), Ok(()));
+        assert_eq!(skus.get(b"ab-1").map(|entry| entry.attributes.size), Some(Some(40)));
+        assert_eq!(skus.remove(b"AB-1").map(|entry| entry.block), Ok(2));
+        assert_eq!(skus.register(b"AB-1", SkuEntry { product: 1, ..entry }), Ok(()));
+    }
+}
@@ -0,0 +1,170 @@
+//! # Stakes
+//!
+//! Native-token bonds of the accounts answerable for their work, like the validators.
+//!
+//! An account bonds a stake in the contract and can ask to unbond part of it: the amount
+//! unbonding is released after the configured delay, at least [`UNBONDING_PERIOD_MIN`], and
+//! stays slashable until then. An account with a dispute open against it can neither unbond
+//! nor withdraw, so a validator cannot escape a dispute by withdrawing its stake. A slash takes
+//! from the bond first and then from the amount unbonding.
+//!
+
+use crate::asset_erc721::Error;
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+};
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Minimum number of blocks before the stake unbonding can be withdrawn
+pub const UNBONDING_PERIOD_MIN: BlockNumber = 1;
+
+/// Stake of an account
+#[derive(Encode, Decode, Debug, Default, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Bond {
+    /// Amount bonded
+    pub bonded: Balance,
+    /// Amount unbonding
+    pub unbonding: Balance,
+    /// Block from which the amount unbonding can be withdrawn
+    pub release: BlockNumber,
+}
+
+/// Storage of the stakes of the accounts
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Stakes {
+    /// Stake of the account
+    account_bond: StorageHashMap<AccountId, Bond>,
+}
+
+impl Stakes {
+    /// Returns the stake of `account`
+    pub fn bond_of(&self, account: &AccountId) -> Option<Bond> {
+        self.account_bond.get(account).cloned()
+    }
+
+    /// Returns the amount bonded from `account`
+    pub fn bonded_of(&self, account: &AccountId) -> Balance {
+        self.account_bond.get(account).map_or(0, |bond| bond.bonded)
+    }
+
+    /// Adds `amount` to the bond of `account`, and returns the bond
+    pub fn bond(&mut self, account: AccountId, amount: Balance) -> Bond {
+        let mut bond = self.bond_of(&account).unwrap_or_default();
+        bond.bonded = bond.bonded.saturating_add(amount);
+        self.account_bond.insert(account, bond);
+        bond
+    }
+
+    /// Starts unbonding `amount` of the bond of `account`, the whole amount unbonding is released at block `release`
+    pub fn unbond(&mut self, account: AccountId, amount: Balance, release: BlockNumber) -> Result<Bond, Error> {
+        let mut bond = self.bond_of(&account).unwrap_or_default();
+        bond.bonded = bond.bonded.checked_sub(amount).ok_or(Error::InsufficientBalance)?;
+        bond.unbonding = bond.unbonding.saturating_add(amount);
+        bond.release = release;
+        self.account_bond.insert(account, bond);
+        Ok(bond)
+    }
+
+    /// Releases the amount unbonding of `account` at block `now`, and returns it
+    pub fn withdraw(&mut self, account: AccountId, now: BlockNumber) -> Result<Balance, Error> {
+        let mut bond = self.bond_of(&account).unwrap_or_default();
+        if bond.unbonding == 0 {
+            return Err(Error::InsufficientBalance)
+        }
+        if now < bond.release {
+            return Err(Error::StakeLocked)
+        }
+        let amount = bond.unbonding;
+        bond.unbonding = 0;
+        self.store(account, bond);
+        Ok(amount)
+    }
+
+    /// Restores the amount unbonding of `account` withdrawn when it cannot be paid
+    pub fn unwithdraw(&mut self, account: AccountId, amount: Balance) {
+        let mut bond = self.bond_of(&account).unwrap_or_default();
+        bond.unbonding = bond.unbonding.saturating_add(amount);
+        self.account_bond.insert(account, bond);
+    }
+
+    /// Takes at most `amount` from the stake of `account`, and returns the amount taken
+    pub fn slash(&mut self, account: AccountId, amount: Balance) -> Balance {
+        let mut bond = self.bond_of(&account).unwrap_or_default();
+        let from_bonded = amount.min(bond.bonded);
+        let from_unbonding = (amount - from_bonded).min(bond.unbonding);
+        bond.bonded -= from_bonded;
+        bond.unbonding -= from_unbonding;
+        self.store(account, bond);
+        from_bonded + from_unbonding
+    }
+
+    /// Restores the stake `bond` of `account` slashed when the slash cannot be paid
+    pub fn unslash(&mut self, account: AccountId, bond: Bond) {
+        self.store(account, bond);
+    }
+
+    /// Stores the stake of `account`, removing it when empty
+    fn store(&mut self, account: AccountId, bond: Bond) {
+        if bond.bonded == 0 && bond.unbonding == 0 {
+            self.account_bond.take(&account);
+        } else {
+            self.account_bond.insert(account, bond);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn bond_works() {
+        let bob = AccountId::from([0x2; 32]);
+        let mut stakes = Stakes::default();
+        assert_eq!(stakes.bond(bob, 100).bonded, 100);
+        assert_eq!(stakes.unbond(bob, 150, 10), Err(Error::InsufficientBalance));
+        assert_eq!(stakes.unbond(bob, 40, 10).map(|bond| (bond.bonded, bond.unbonding)), Ok((60, 40)));
+        assert_eq!(stakes.withdraw(bob, 9), Err(Error::StakeLocked));
+        assert_eq!(stakes.withdraw(bob, 10), Ok(40));
+        assert_eq!(stakes.withdraw(bob, 10), Err(Error::InsufficientBalance));
+        stakes.unwithdraw(bob, 40);
+        assert_eq!(stakes.withdraw(bob, 10), Ok(40));
+        assert_eq!(stakes.bonded_of(&bob), 60);
+    }
+
+    #[ink::test]
+    fn slash_works() {
+        let bob = AccountId::from([0x2; 32]);
+        let mut stakes = Stakes::default();
+        stakes.bond(bob, 100);
+        assert!(stakes.unbond(bob, 70, 10).is_ok());
+        // the amount unbonding is slashed after the bond
+        assert_eq!(stakes.slash(bob, 50), 50);
+        assert_eq!(stakes.bond_of(&bob), Some(Bond { bonded: 0, unbonding: 50, release: 10 }));
+        let bond = Bond { bonded: 0, unbonding: 50, release: 10 };
+        assert_eq!(stakes.slash(bob, 80), 50);
+        assert_eq!(stakes.bond_of(&bob), None);
+        stakes.unslash(bob, bond);
+        assert_eq!(stakes.bond_of(&bob), Some(bond));
+    }
+}
@@ -0,0 +1,128 @@
+//! # Subscriptions
+//!
+//! Participation fees of the producers.
+//!
+//! When the administrators configure a fee, the accounts with the Producer role pay it for
+//! each era they want to mint in: the payment extends the subscription from its end, or from
+//! the current block when it has lapsed. A lapsed subscription only suspends the minting, the
+//! assets already created are transferred and updated as usual.
+//!
+//! The fees stay in the balance of the contract until an administrator withdraws them.
+//!
+
+use crate::asset_erc721::Error;
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+};
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+    Lazy,
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Subscription of a producer
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Subscription {
+    /// First block not covered from the fees paid
+    pub paid_until: BlockNumber,
+    /// Block of the last renewal
+    pub renewed: BlockNumber,
+    /// Total fees paid
+    pub paid: Balance,
+}
+
+/// Storage of the subscriptions of the producers
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Subscriptions {
+    /// Subscription of the account
+    account_subscription: StorageHashMap<AccountId, Subscription>,
+    /// Fees collected and not withdrawn yet, loaded when used
+    fees: Lazy<Balance>,
+}
+
+impl Subscriptions {
+    /// Returns the subscription of `account`
+    pub fn subscription_of(&self, account: &AccountId) -> Option<Subscription> {
+        self.account_subscription.get(account).cloned()
+    }
+
+    /// Returns true if the subscription of `account` covers block `now`
+    pub fn is_active(&self, account: &AccountId, now: BlockNumber) -> bool {
+        self.account_subscription
+            .get(account)
+            .is_some_and(|subscription| now < subscription.paid_until)
+    }
+
+    /// Extends the subscription of `account` by `eras` eras of `era` blocks paying `amount`, and returns it
+    pub fn renew(&mut self, account: AccountId, eras: BlockNumber, era: BlockNumber, amount: Balance, now: BlockNumber) -> Subscription {
+        let mut subscription = self.subscription_of(&account).unwrap_or(Subscription {
+            paid_until: now,
+            renewed: now,
+            paid: 0,
+        });
+        subscription.paid_until = subscription.paid_until.max(now).saturating_add(eras.saturating_mul(era));
+        subscription.renewed = now;
+        subscription.paid = subscription.paid.saturating_add(amount);
+        self.account_subscription.insert(account, subscription);
+        *self.fees = self.fees.saturating_add(amount);
+        subscription
+    }
+
+    /// Returns the fees collected and not withdrawn yet
+    pub fn fees(&self) -> Balance {
+        *self.fees
+    }
+
+    /// Deducts `amount` from the fees collected
+    pub fn withdraw(&mut self, amount: Balance) -> Result<(), Error> {
+        *self.fees = self.fees.checked_sub(amount).ok_or(Error::InsufficientBalance)?;
+        Ok(())
+    }
+
+    /// Restores `amount` to the fees collected when its withdrawal cannot be paid
+    pub fn unwithdraw(&mut self, amount: Balance) {
+        *self.fees = self.fees.saturating_add(amount);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn renew_works() {
+        let bob = AccountId::from([0x2; 32]);
+        let mut subscriptions = Subscriptions::default();
+        assert!(!subscriptions.is_active(&bob, 1));
+        assert_eq!(subscriptions.renew(bob, 2, 10, 200, 5).paid_until, 25);
+        assert!(subscriptions.is_active(&bob, 24));
+        assert!(!subscriptions.is_active(&bob, 25));
+        // a renewal before the end extends the subscription from its end
+        assert_eq!(subscriptions.renew(bob, 1, 10, 100, 20).paid_until, 35);
+        // a renewal after the end starts from the current block
+        assert_eq!(subscriptions.renew(bob, 1, 10, 100, 50).paid_until, 60);
+        assert_eq!(subscriptions.subscription_of(&bob).map(|subscription| subscription.paid), Some(400));
+        assert_eq!(subscriptions.withdraw(500), Err(Error::InsufficientBalance));
+        assert_eq!(subscriptions.withdraw(300), Ok(()));
+        assert_eq!(subscriptions.fees(), 100);
+        subscriptions.unwithdraw(300);
+        assert_eq!(subscriptions.fees(), 400);
+    }
+}
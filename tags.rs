@@ -0,0 +1,104 @@
+//! # Tags
+//!
+//! Binding of the NFC/RFID tags applied to the products to their assets.
+//!
+//! The binding is one-to-one: a tag resolves to exactly one asset and an asset has at most
+//! one tag, so scanning a tag always identifies a single asset.
+//!
+
+use crate::asset_erc721::{
+    AssetId,
+    Error,
+};
+use ink_env::Hash;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::SpreadLayout,
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+
+/// Storage of the bindings of the tags
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Tags {
+    /// Asset bound to the tag UID
+    tag_asset: StorageHashMap<Hash, AssetId>,
+    /// Tag UID bound to the asset
+    asset_tag: StorageHashMap<AssetId, Hash>,
+}
+
+impl Tags {
+    /// Returns the asset bound to tag `uid`
+    pub fn asset_of(&self, uid: &Hash) -> Option<AssetId> {
+        self.tag_asset.get(uid).cloned()
+    }
+
+    /// Returns the tag bound to asset `id`
+    pub fn tag_of(&self, id: AssetId) -> Option<Hash> {
+        self.asset_tag.get(&id).cloned()
+    }
+
+    /// Binds tag `uid` to asset `id`, fails if the tag or the asset are already bound
+    pub fn bind(&mut self, uid: Hash, id: AssetId) -> Result<(), Error> {
+        if self.tag_asset.contains_key(&uid) || self.asset_tag.contains_key(&id) {
+            return Err(Error::TagAlreadyBound)
+        }
+        self.tag_asset.insert(uid, id);
+        self.asset_tag.insert(id, uid);
+        Ok(())
+    }
+
+    /// Removes the binding of asset `id` and returns its tag
+    pub fn unbind(&mut self, id: AssetId) -> Option<Hash> {
+        let uid = self.asset_tag.take(&id)?;
+        self.tag_asset.take(&uid);
+        Some(uid)
+    }
+
+    /// Binds tag `uid` to asset `id` removing the previous bindings of both
+    pub fn rebind(&mut self, uid: Hash, id: AssetId) {
+        if let Some(previous) = self.asset_of(&uid) {
+            self.unbind(previous);
+        }
+        self.unbind(id);
+        self.tag_asset.insert(uid, id);
+        self.asset_tag.insert(id, uid);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn bind_is_one_to_one() {
+        let first = Hash::from([0x01; 32]);
+        let second = Hash::from([0x02; 32]);
+        let mut tags = Tags::default();
+        assert_eq!(tags.bind(first, 1), Ok(()));
+        assert_eq!(tags.bind(first, 2), Err(Error::TagAlreadyBound));
+        assert_eq!(tags.bind(second, 1), Err(Error::TagAlreadyBound));
+        assert_eq!(tags.asset_of(&first), Some(1));
+        assert_eq!(tags.tag_of(1), Some(first));
+        assert_eq!(tags.unbind(1), Some(first));
+        assert_eq!(tags.asset_of(&first), None);
+        assert_eq!(tags.bind(first, 2), Ok(()));
+    }
+
+    #[ink::test]
+    fn rebind_works() {
+        let first = Hash::from([0x01; 32]);
+        let second = Hash::from([0x02; 32]);
+        let mut tags = Tags::default();
+        assert_eq!(tags.bind(first, 1), Ok(()));
+        assert_eq!(tags.bind(second, 2), Ok(()));
+        // the tag of asset 1 moves to asset 2, that loses its tag
+        tags.rebind(first, 2);
+        assert_eq!(tags.asset_of(&first), Some(2));
+        assert_eq!(tags.asset_of(&second), None);
+        assert_eq!(tags.tag_of(1), None);
+        assert_eq!(tags.tag_of(2), Some(first));
+    }
+}
@@ -0,0 +1,220 @@
+//! # Taxes
+//!
+//! Tax rates of the jurisdictions and the tax trail of the sales settled through the contract.
+//!
+//! The administrators configure the rate of each registered jurisdiction, as a default and for
+//! single categories, and register the jurisdiction of the sellers. The prices of the listings
+//! include the tax: when a sale is settled the contract computes the tax component from the rate
+//! of the jurisdiction of the seller for the category of the asset, and appends it to the tax
+//! trail.
+//! When the rate names a collection account, the tax is paid to it instead of the seller.
+//!
+
+use crate::{
+    asset_erc721::{
+        AssetId,
+        Error,
+    },
+    jurisdictions::Jurisdiction,
+    sales::SHARE_MAX,
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Tax record ID
+pub type TaxRecordId = u32;
+
+/// Tax rate of a jurisdiction
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct TaxRate {
+    /// Rate in basis points of the price before the tax
+    pub rate: u16,
+    /// Account collecting the tax, `None` if the seller remits it
+    pub collector: Option<AccountId>,
+}
+
+impl TaxRate {
+    /// Returns the tax included in `amount`, rounded down
+    pub fn tax_of(&self, amount: Balance) -> Balance {
+        amount.saturating_mul(self.rate as Balance) / (SHARE_MAX as Balance + self.rate as Balance)
+    }
+}
+
+/// Tax component of a sale
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct TaxRecord {
+    /// Asset sold
+    pub id: AssetId,
+    /// Position of the sale in the sales of the asset
+    pub sale: u32,
+    /// Jurisdiction of the seller
+    pub jurisdiction: Jurisdiction,
+    /// Category of the asset
+    pub category: Option<u32>,
+    /// Rate applied, in basis points
+    pub rate: u16,
+    /// Tax included in the amount paid
+    pub tax: Balance,
+    /// Account the tax was paid to, `None` if it was paid to the seller
+    pub collector: Option<AccountId>,
+    /// Block of the sale
+    pub block: BlockNumber,
+}
+
+/// Storage of the tax rates and of the tax trail
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Taxes {
+    /// Rate of the jurisdiction for the category, or for any category when it is `None`
+    rates: StorageHashMap<(Jurisdiction, Option<u32>), TaxRate>,
+    /// Jurisdiction of the seller
+    account_jurisdiction: StorageHashMap<AccountId, Jurisdiction>,
+    /// Tax records indexed from 0 to the records counter
+    records: StorageHashMap<TaxRecordId, TaxRecord>,
+    /// Counter of the tax records
+    records_count: u32,
+    /// Tax record of the sale of the asset
+    sale_record: StorageHashMap<(AssetId, u32), TaxRecordId>,
+}
+
+impl Taxes {
+    /// Returns the rate of `jurisdiction` for `category`, falling back to the default rate of the jurisdiction
+    pub fn rate_of(&self, jurisdiction: Jurisdiction, category: Option<u32>) -> Option<TaxRate> {
+        category
+            .and_then(|category| self.rates.get(&(jurisdiction, Some(category))))
+            .or_else(|| self.rates.get(&(jurisdiction, None)))
+            .cloned()
+    }
+
+    /// Sets or removes the rate of `jurisdiction` for `category`, or its default rate when `category` is `None`.
+    /// Fails if the rate is above 100%.
+    pub fn rate_set(&mut self, jurisdiction: Jurisdiction, category: Option<u32>, rate: Option<TaxRate>) -> Result<(), Error> {
+        match rate {
+            Some(rate) if rate.rate > SHARE_MAX => return Err(Error::NotAllowed),
+            Some(rate) => {
+                self.rates.insert((jurisdiction, category), rate);
+            }
+            None => {
+                self.rates.take(&(jurisdiction, category));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the jurisdiction of `account`
+    pub fn jurisdiction_of(&self, account: &AccountId) -> Option<Jurisdiction> {
+        self.account_jurisdiction.get(account).cloned()
+    }
+
+    /// Sets or removes the jurisdiction of `account`
+    pub fn jurisdiction_set(&mut self, account: AccountId, jurisdiction: Option<Jurisdiction>) {
+        match jurisdiction {
+            Some(jurisdiction) => self.account_jurisdiction.insert(account, jurisdiction),
+            None => self.account_jurisdiction.take(&account),
+        };
+    }
+
+    /// Returns tax record `record_id`
+    pub fn get(&self, record_id: TaxRecordId) -> Option<TaxRecord> {
+        self.records.get(&record_id).cloned()
+    }
+
+    /// Returns the tax record of the sale at position `sale` of asset `id`
+    pub fn record_of(&self, id: AssetId, sale: u32) -> Option<TaxRecord> {
+        self.sale_record.get(&(id, sale)).and_then(|record_id| self.get(*record_id))
+    }
+
+    /// Returns the number of tax records
+    pub fn records_count(&self) -> u32 {
+        self.records_count
+    }
+
+    /// Returns at most `limit` tax records from position `cursor` of the trail, oldest first
+    pub fn list(&self, cursor: u32, limit: u32) -> Vec<TaxRecord> {
+        (cursor..self.records_count.min(cursor.saturating_add(limit)))
+            .filter_map(|record_id| self.get(record_id))
+            .collect()
+    }
+
+    /// Appends `record` to the tax trail, and returns its id
+    pub fn record(&mut self, record: TaxRecord) -> TaxRecordId {
+        let record_id = self.records_count;
+        self.records.insert(record_id, record);
+        self.sale_record.insert((record.id, record.sale), record_id);
+        self.records_count += 1;
+        record_id
+    }
+
+    /// Unlinks the sales of the deleted asset `id` from their records, the records stay in the trail
+    pub fn forget(&mut self, id: AssetId, sales: u32) {
+        for sale in 0..sales {
+            self.sale_record.take(&(id, sale));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn taxes_work() {
+        let collector = AccountId::from([0x1; 32]);
+        let rate = TaxRate {
+            rate: 2_200,
+            collector: None,
+        };
+        let mut taxes = Taxes::default();
+        assert_eq!(taxes.rate_set(*b"IT", None, Some(TaxRate { rate: 10_001, ..rate })), Err(Error::NotAllowed));
+        assert_eq!(taxes.rate_set(*b"IT", None, Some(rate)), Ok(()));
+        assert_eq!(taxes.rate_set(*b"IT", Some(1), Some(TaxRate { rate: 400, collector: Some(collector) })), Ok(()));
+        // the rate of the category prevails over the default rate
+        assert_eq!(taxes.rate_of(*b"IT", Some(1)).map(|rate| rate.rate), Some(400));
+        assert_eq!(taxes.rate_of(*b"IT", Some(2)), Some(rate));
+        assert_eq!(taxes.rate_of(*b"IT", None), Some(rate));
+        assert_eq!(taxes.rate_of(*b"FR", Some(1)), None);
+        // the tax is included in the amount
+        assert_eq!(rate.tax_of(12_200), 2_200);
+        let record = TaxRecord {
+            id: 1,
+            sale: 0,
+            jurisdiction: *b"IT",
+            category: None,
+            rate: 2_200,
+            tax: 2_200,
+            collector: None,
+            block: 1,
+        };
+        assert_eq!(taxes.record(record), 0);
+        assert_eq!(taxes.record(TaxRecord { sale: 1, ..record }), 1);
+        assert_eq!(taxes.record_of(1, 1), Some(TaxRecord { sale: 1, ..record }));
+        assert_eq!(taxes.list(1, 10).len(), 1);
+        // the records of a deleted asset stay in the trail
+        taxes.forget(1, 2);
+        assert_eq!(taxes.record_of(1, 0), None);
+        assert_eq!(taxes.records_count(), 2);
+    }
+}
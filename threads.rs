@@ -0,0 +1,139 @@
+//! # Threads
+//!
+//! Threads of messages between the two parties of a sale or of a dispute.
+//!
+//! The seller and the buyer of a sale, or the claimant and the respondent of a dispute, append
+//! the hashes of the messages they exchange, while the contents stay off-chain. Each message
+//! keeps its author and block and cannot be changed, so the thread is a tamper-evident record of
+//! the negotiation the arbitrators read when they resolve a dispute.
+//!
+
+use crate::{
+    asset_erc721::AssetId,
+    disputes::DisputeId,
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Subject of a thread
+#[derive(Encode, Decode, Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub enum Thread {
+    /// Sale of the asset, with its position in the sales of the asset
+    Sale(AssetId, u32),
+    /// Dispute
+    Dispute(DisputeId),
+}
+
+/// Message of a thread
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Post {
+    /// Party that wrote the message
+    pub author: AccountId,
+    /// Hash of the content of the message
+    pub content: Hash,
+    /// Block of the message
+    pub block: BlockNumber,
+}
+
+/// Storage of the threads
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Threads {
+    /// Counter of the messages of each thread
+    thread_posts_count: StorageHashMap<Thread, u32>,
+    /// Messages of each thread, indexed from 0 to the thread messages counter
+    thread_posts: StorageHashMap<(Thread, u32), Post>,
+}
+
+impl Threads {
+    /// Returns the number of messages of `thread`
+    pub fn posts_count(&self, thread: Thread) -> u32 {
+        *self.thread_posts_count.get(&thread).unwrap_or(&0)
+    }
+
+    /// Returns at most `limit` messages of `thread` starting from position `offset`, oldest first
+    pub fn posts_of(&self, thread: Thread, offset: u32, limit: u32) -> Vec<Post> {
+        (offset..self.posts_count(thread))
+            .take(limit as usize)
+            .filter_map(|index| self.thread_posts.get(&(thread, index)).cloned())
+            .collect()
+    }
+
+    /// Appends `post` to `thread`
+    pub fn append(&mut self, thread: Thread, post: Post) {
+        let index = self.posts_count(thread);
+        self.thread_posts.insert((thread, index), post);
+        self.thread_posts_count.insert(thread, index + 1);
+    }
+
+    /// Replaces `account` with `pseudonym` as the author of the messages of `thread`
+    pub fn pseudonymize(&mut self, thread: Thread, account: &AccountId, pseudonym: AccountId) {
+        for index in 0..self.posts_count(thread) {
+            if let Some(post) = self.thread_posts.get_mut(&(thread, index)) {
+                if post.author == *account {
+                    post.author = pseudonym;
+                }
+            }
+        }
+    }
+
+    /// Removes `thread` once its subject is deleted
+    pub fn remove(&mut self, thread: Thread) {
+        let count = self.thread_posts_count.take(&thread).unwrap_or(0);
+        for index in 0..count {
+            self.thread_posts.take(&(thread, index));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    fn post(content: u8) -> Post {
+        Post {
+            author: AccountId::from([0x1; 32]),
+            content: Hash::from([content; 32]),
+            block: 1,
+        }
+    }
+
+    #[ink::test]
+    fn threads_work() {
+        let mut threads = Threads::default();
+        threads.append(Thread::Sale(1, 0), post(1));
+        threads.append(Thread::Sale(1, 0), post(2));
+        // the threads of each subject are apart
+        threads.append(Thread::Sale(1, 1), post(3));
+        threads.append(Thread::Dispute(1), post(4));
+        assert_eq!(threads.posts_count(Thread::Sale(1, 0)), 2);
+        assert_eq!(threads.posts_of(Thread::Sale(1, 0), 1, 10), [post(2)]);
+        assert_eq!(threads.posts_of(Thread::Dispute(1), 0, 10), [post(4)]);
+        threads.remove(Thread::Sale(1, 0));
+        assert_eq!(threads.posts_count(Thread::Sale(1, 0)), 0);
+        assert_eq!(threads.posts_of(Thread::Sale(1, 1), 0, 10), [post(3)]);
+    }
+}
@@ -0,0 +1,205 @@
+//! # Treasury
+//!
+//! Fees collected from the contract to fund the operations of the registry.
+//!
+//! The sales settled through the contract pay a fee, in basis points of the amount, to the
+//! treasury. The fees are accounted per payment token, the native token or a PSP22 token, and
+//! stay on the balance of the contract until the administrators withdraw them.
+//!
+//! A withdrawal is never done from a single administrator: one proposes it, the others approve
+//! it, and it is executed once approved from as many administrators as the configured threshold.
+//! The proposals are kept with their approvals and their outcome as the trail of the withdrawals.
+//!
+
+use crate::asset_erc721::Error;
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Withdrawal ID
+pub type WithdrawalId = u32;
+
+/// Maximum fee on the sales, in basis points
+pub const FEE_MAX: u16 = 1_000;
+
+/// Minimum number of administrators approving a withdrawal
+pub const THRESHOLD_MIN: u32 = 2;
+
+/// Proposal of a withdrawal from the treasury
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct Withdrawal {
+    /// PSP22 token withdrawn, `None` for the native token
+    pub token: Option<AccountId>,
+    /// Recipient of the withdrawal
+    pub to: AccountId,
+    /// Amount withdrawn
+    pub amount: Balance,
+    /// Administrators that approved the withdrawal, the proposer first
+    pub approvals: Vec<AccountId>,
+    /// Block of the proposal
+    pub block: BlockNumber,
+    /// Block of the execution, `None` until executed
+    pub executed: Option<BlockNumber>,
+}
+
+/// Storage of the treasury
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Treasury {
+    /// Fees collected and not withdrawn, per PSP22 token or `None` for the native token
+    balances: StorageHashMap<Option<AccountId>, Balance>,
+    /// Withdrawals proposed, indexed from 0 to the withdrawals counter
+    withdrawals: StorageHashMap<WithdrawalId, Withdrawal>,
+    /// Counter of the withdrawals proposed
+    withdrawals_count: u32,
+}
+
+impl Treasury {
+    /// Returns the fees collected in `token` and not withdrawn
+    pub fn balance_of(&self, token: Option<AccountId>) -> Balance {
+        *self.balances.get(&token).unwrap_or(&0)
+    }
+
+    /// Adds the fee `amount` collected in `token`
+    pub fn deposit(&mut self, token: Option<AccountId>, amount: Balance) {
+        if amount > 0 {
+            let balance = self.balance_of(token).saturating_add(amount);
+            self.balances.insert(token, balance);
+        }
+    }
+
+    /// Returns withdrawal `withdrawal_id`
+    pub fn withdrawal_of(&self, withdrawal_id: WithdrawalId) -> Option<Withdrawal> {
+        self.withdrawals.get(&withdrawal_id).cloned()
+    }
+
+    /// Returns the number of withdrawals proposed
+    pub fn withdrawals_count(&self) -> u32 {
+        self.withdrawals_count
+    }
+
+    /// Proposes the withdrawal of `amount` in `token` to `to` approved from `proposer`, and returns its id.
+    /// Fails if the fees collected are not enough.
+    pub fn propose(&mut self, proposer: AccountId, token: Option<AccountId>, to: AccountId, amount: Balance, block: BlockNumber) -> Result<WithdrawalId, Error> {
+        if amount > self.balance_of(token) {
+            return Err(Error::InsufficientBalance)
+        }
+        let withdrawal_id = self.withdrawals_count;
+        self.withdrawals.insert(withdrawal_id, Withdrawal {
+            token,
+            to,
+            amount,
+            approvals: ink_prelude::vec![proposer],
+            block,
+            executed: None,
+        });
+        self.withdrawals_count += 1;
+        Ok(withdrawal_id)
+    }
+
+    /// Adds the approval of `approver` to the pending withdrawal `withdrawal_id`, and returns the withdrawal
+    pub fn approve(&mut self, withdrawal_id: WithdrawalId, approver: AccountId) -> Result<Withdrawal, Error> {
+        let withdrawal = self.pending_mut(withdrawal_id)?;
+        if withdrawal.approvals.contains(&approver) {
+            return Err(Error::DuplicatedData)
+        }
+        withdrawal.approvals.push(approver);
+        Ok(withdrawal.clone())
+    }
+
+    /// Marks the pending withdrawal `withdrawal_id` executed at `block` and removes its amount from the fees
+    /// collected, and returns it
+    pub fn execute(&mut self, withdrawal_id: WithdrawalId, block: BlockNumber) -> Result<Withdrawal, Error> {
+        let withdrawal = self.pending_mut(withdrawal_id)?.clone();
+        self.withdraw(withdrawal.token, withdrawal.amount)?;
+        if let Some(withdrawal) = self.withdrawals.get_mut(&withdrawal_id) {
+            withdrawal.executed = Some(block);
+        }
+        Ok(withdrawal)
+    }
+
+    /// Restores the pending state of the withdrawal `withdrawal_id` when it cannot be paid
+    pub fn unexecute(&mut self, withdrawal_id: WithdrawalId) {
+        if let Some(withdrawal) = self.withdrawals.get_mut(&withdrawal_id) {
+            withdrawal.executed = None;
+            let (token, amount) = (withdrawal.token, withdrawal.amount);
+            self.deposit(token, amount);
+        }
+    }
+
+    /// Removes `amount` of the fees collected in `token`, fails if they are not enough
+    fn withdraw(&mut self, token: Option<AccountId>, amount: Balance) -> Result<(), Error> {
+        let balance = self.balance_of(token);
+        if amount > balance {
+            return Err(Error::InsufficientBalance)
+        }
+        self.balances.insert(token, balance - amount);
+        Ok(())
+    }
+
+    /// Returns the pending withdrawal `withdrawal_id`
+    fn pending_mut(&mut self, withdrawal_id: WithdrawalId) -> Result<&mut Withdrawal, Error> {
+        self.withdrawals
+            .get_mut(&withdrawal_id)
+            .filter(|withdrawal| withdrawal.executed.is_none())
+            .ok_or(Error::WithdrawalNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn withdrawals_work() {
+        let alice = AccountId::from([0x1; 32]);
+        let bob = AccountId::from([0x2; 32]);
+        let token = Some(AccountId::from([0x7; 32]));
+        let mut treasury = Treasury::default();
+        treasury.deposit(None, 30);
+        treasury.deposit(token, 5);
+        treasury.deposit(None, 20);
+        assert_eq!(treasury.balance_of(None), 50);
+        // the fees of each token are accounted apart
+        assert_eq!(treasury.propose(alice, token, bob, 6, 1), Err(Error::InsufficientBalance));
+        assert_eq!(treasury.propose(alice, None, bob, 40, 1), Ok(0));
+        assert_eq!(treasury.approve(0, alice), Err(Error::DuplicatedData));
+        assert_eq!(treasury.approve(0, bob).map(|withdrawal| withdrawal.approvals), Ok(ink_prelude::vec![alice, bob]));
+        assert_eq!(treasury.execute(0, 2).map(|withdrawal| withdrawal.amount), Ok(40));
+        assert_eq!(treasury.balance_of(None), 10);
+        assert_eq!(treasury.balance_of(token), 5);
+        // an executed withdrawal is kept in the trail
+        assert_eq!(treasury.execute(0, 2), Err(Error::WithdrawalNotFound));
+        assert_eq!(treasury.withdrawal_of(0).and_then(|withdrawal| withdrawal.executed), Some(2));
+        // the fees are checked again at the execution
+        assert_eq!(treasury.propose(alice, None, bob, 10, 3), Ok(1));
+        assert_eq!(treasury.propose(alice, None, bob, 10, 3), Ok(2));
+        assert_eq!(treasury.execute(1, 4).map(|withdrawal| withdrawal.amount), Ok(10));
+        assert_eq!(treasury.execute(2, 4), Err(Error::InsufficientBalance));
+        treasury.unexecute(1);
+        assert_eq!(treasury.balance_of(None), 10);
+        assert_eq!(treasury.withdrawal_of(1).and_then(|withdrawal| withdrawal.executed), None);
+        assert_eq!(treasury.withdrawals_count(), 3);
+    }
+}
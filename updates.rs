@@ -0,0 +1,171 @@
+//! # Updates
+//!
+//! Log of the recent updates of each asset, bounded to keep the storage of the busy assets small.
+//!
+//! The log of an asset keeps the last updates up to the size configured from the administrators.
+//! The older updates are evicted from the log and folded in a rolling digest: the Blake2x256
+//! hash of the SCALE encoding of the previous digest and of the evicted update. An archive of
+//! the `AssetUpdate` events is verified off-chain by folding its updates in the same order and
+//! comparing the result with the digest and the number of updates evicted.
+//!
+
+use crate::asset_erc721::{
+    AssetId,
+    UpdateKind,
+};
+use ink_env::{
+    hash::Blake2x256,
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+    Hash,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Update of an asset
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct UpdateEntry {
+    /// Account that updated the asset
+    pub from: AccountId,
+    /// Kind of data changed
+    pub kind: UpdateKind,
+    /// Block of the update
+    pub block: BlockNumber,
+}
+
+/// Digest of the updates evicted from the log of an asset
+#[derive(Encode, Decode, Debug, Default, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct UpdateDigest {
+    /// Rolling hash of the updates evicted, the zero hash when none
+    pub hash: Hash,
+    /// Number of updates evicted
+    pub count: u32,
+}
+
+impl UpdateDigest {
+    /// Returns the digest with `entry` folded in
+    pub fn fold(&self, entry: &UpdateEntry) -> Self {
+        let mut output = [0x0; 32];
+        ink_env::hash_encoded::<Blake2x256, _>(&(self.hash, entry), &mut output);
+        Self {
+            hash: Hash::from(output),
+            count: self.count + 1,
+        }
+    }
+}
+
+/// Storage of the logs of the recent updates of the assets
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct Updates {
+    /// Updates of the asset, indexed from the number of updates evicted to the updates counter
+    asset_updates: StorageHashMap<(AssetId, u32), UpdateEntry>,
+    /// Counter of all the updates of the asset
+    asset_updates_count: StorageHashMap<AssetId, u32>,
+    /// Digest of the updates evicted from the log of the asset
+    asset_digest: StorageHashMap<AssetId, UpdateDigest>,
+}
+
+impl Updates {
+    /// Returns the digest of the updates evicted from the log of asset `id`
+    pub fn digest_of(&self, id: AssetId) -> UpdateDigest {
+        self.asset_digest.get(&id).cloned().unwrap_or_default()
+    }
+
+    /// Returns the number of updates kept in the log of asset `id`
+    pub fn len(&self, id: AssetId) -> u32 {
+        self.count_of(id) - self.digest_of(id).count
+    }
+
+    /// Returns at most `limit` updates of the log of asset `id` from the newest, skipping the newest `offset`
+    pub fn latest(&self, id: AssetId, offset: u32, limit: u32) -> Vec<UpdateEntry> {
+        let count = self.count_of(id);
+        (offset..self.len(id))
+            .take(limit as usize)
+            .filter_map(|position| self.asset_updates.get(&(id, count - position - 1)).cloned())
+            .collect()
+    }
+
+    /// Appends `entry` to the log of asset `id`, evicting the oldest updates beyond `size` to the digest
+    pub fn record(&mut self, id: AssetId, entry: UpdateEntry, size: u32) {
+        let count = self.count_of(id);
+        self.asset_updates.insert((id, count), entry);
+        self.asset_updates_count.insert(id, count + 1);
+        let mut digest = self.digest_of(id);
+        let evicted = digest.count;
+        while count + 1 - digest.count > size {
+            match self.asset_updates.take(&(id, digest.count)) {
+                Some(oldest) => digest = digest.fold(&oldest),
+                None => break,
+            }
+        }
+        if digest.count != evicted {
+            self.asset_digest.insert(id, digest);
+        }
+    }
+
+    /// Removes the log and the digest of the deleted asset `id`
+    pub fn remove(&mut self, id: AssetId) {
+        let count = self.asset_updates_count.take(&id).unwrap_or(0);
+        let digest = self.asset_digest.take(&id).unwrap_or_default();
+        for index in digest.count..count {
+            self.asset_updates.take(&(id, index));
+        }
+    }
+
+    /// Returns the number of updates recorded for asset `id`
+    fn count_of(&self, id: AssetId) -> u32 {
+        *self.asset_updates_count.get(&id).unwrap_or(&0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    fn entry(block: BlockNumber) -> UpdateEntry {
+        UpdateEntry {
+            from: AccountId::from([0x1; 32]),
+            kind: UpdateKind::Location,
+            block,
+        }
+    }
+
+    #[ink::test]
+    fn log_is_bounded() {
+        let mut updates = Updates::default();
+        for block in 0..3 {
+            updates.record(1, entry(block), 2);
+        }
+        assert_eq!(updates.len(1), 2);
+        assert_eq!(updates.latest(1, 0, 10), [entry(2), entry(1)]);
+        assert_eq!(updates.digest_of(1), UpdateDigest::default().fold(&entry(0)));
+        // a smaller size evicts the excess at the next update
+        updates.record(1, entry(3), 1);
+        assert_eq!(updates.latest(1, 0, 10), [entry(3)]);
+        let digest = UpdateDigest::default().fold(&entry(0)).fold(&entry(1)).fold(&entry(2));
+        assert_eq!(updates.digest_of(1), digest);
+        assert_eq!(digest.count, 3);
+        updates.remove(1);
+        assert_eq!(updates.len(1), 0);
+        assert_eq!(updates.digest_of(1), UpdateDigest::default());
+    }
+}
@@ -0,0 +1,160 @@
+//! # Validation queue
+//!
+//! Work list of the assets awaiting validation.
+//!
+//! An asset enters the queue when its owner requests the validation or when it is assigned to
+//! a category the administrators marked as requiring validation. It leaves the queue once it is
+//! validated or deleted. The administrators and the inspectors read the queue in pages, the
+//! category of each request lets the inspectors pick the assets of their accreditation. The
+//! queue is kept as a list indexed from 0 to its length: a removed asset is replaced with the
+//! last one, so the order of the requests is not kept.
+//!
+
+use crate::asset_erc721::{
+    AssetId,
+    Error,
+};
+use ink_env::{
+    AccountId,
+    DefaultEnvironment,
+    Environment,
+};
+use ink_prelude::vec::Vec;
+use ink_storage::{
+    collections::HashMap as StorageHashMap,
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+use scale::{
+    Decode,
+    Encode,
+};
+
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Pending request of validation of an asset
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Copy, Clone, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct ValidationRequest {
+    /// Account that requested the validation or assigned the category requiring it
+    pub requester: AccountId,
+    /// Category of the asset when the validation was requested
+    pub category: Option<u32>,
+    /// Block of the request
+    pub block: BlockNumber,
+}
+
+/// Storage of the validation queue
+#[derive(Debug, Default, SpreadLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout))]
+pub struct ValidationQueue {
+    /// Pending request of the asset
+    requests: StorageHashMap<AssetId, ValidationRequest>,
+    /// Assets awaiting validation, indexed from 0 to the queue length
+    queue: StorageHashMap<u32, AssetId>,
+    /// Position of the asset in the queue
+    queue_index: StorageHashMap<AssetId, u32>,
+    /// Number of assets awaiting validation
+    len: u32,
+    /// Categories whose assets require validation
+    required: StorageHashMap<u32, bool>,
+}
+
+impl ValidationQueue {
+    /// Returns the number of assets awaiting validation
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    /// Returns true if no asset awaits validation
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns at most `limit` assets awaiting validation starting from position `offset`
+    pub fn list(&self, offset: u32, limit: u32) -> Vec<AssetId> {
+        (offset..self.len)
+            .take(limit as usize)
+            .filter_map(|index| self.queue.get(&index).cloned())
+            .collect()
+    }
+
+    /// Returns the pending request of validation of asset `id`
+    pub fn request_of(&self, id: AssetId) -> Option<ValidationRequest> {
+        self.requests.get(&id).cloned()
+    }
+
+    /// Returns true if the assets of `category` require validation
+    pub fn is_required(&self, category: u32) -> bool {
+        self.required.contains_key(&category)
+    }
+
+    /// Sets whether the assets of `category` require validation
+    pub fn required_set(&mut self, category: u32, required: bool) {
+        if required {
+            self.required.insert(category, true);
+        } else {
+            self.required.take(&category);
+        }
+    }
+
+    /// Appends asset `id` to the queue, fails if it is already queued
+    pub fn push(&mut self, id: AssetId, request: ValidationRequest) -> Result<(), Error> {
+        if self.requests.contains_key(&id) {
+            return Err(Error::DuplicatedData)
+        }
+        self.requests.insert(id, request);
+        self.queue.insert(self.len, id);
+        self.queue_index.insert(id, self.len);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Removes asset `id` from the queue moving the last asset in the freed position, returns its request
+    pub fn remove(&mut self, id: AssetId) -> Option<ValidationRequest> {
+        let request = self.requests.take(&id)?;
+        let last = self.len.saturating_sub(1);
+        self.len = last;
+        let index = self.queue_index.take(&id).unwrap_or(last);
+        if let Some(last_id) = self.queue.take(&last) {
+            if index != last {
+                self.queue.insert(index, last_id);
+                self.queue_index.insert(last_id, index);
+            }
+        }
+        Some(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+
+    #[ink::test]
+    fn queue_works() {
+        let request = ValidationRequest {
+            requester: AccountId::from([0x1; 32]),
+            category: None,
+            block: 1,
+        };
+        let mut queue = ValidationQueue::default();
+        assert_eq!(queue.push(1, request), Ok(()));
+        assert_eq!(queue.push(2, request), Ok(()));
+        assert_eq!(queue.push(3, ValidationRequest { category: Some(10), ..request }), Ok(()));
+        assert_eq!(queue.push(1, request), Err(Error::DuplicatedData));
+        assert_eq!(queue.list(1, 10), [2, 3]);
+        // the last asset takes the place of the removed one
+        assert_eq!(queue.remove(1), Some(request));
+        assert_eq!(queue.remove(1), None);
+        assert_eq!(queue.list(0, 10), [3, 2]);
+        assert_eq!(queue.request_of(3).and_then(|request| request.category), Some(10));
+        assert_eq!(queue.remove(2), Some(request));
+        assert_eq!(queue.remove(3).map(|request| request.block), Some(1));
+        assert!(queue.is_empty());
+    }
+}